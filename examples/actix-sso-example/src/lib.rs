@@ -0,0 +1,176 @@
+// Author: 金书记
+//
+//! Actix-web SSO 客户端示例 | Actix-web SSO Client Example
+//!
+//! 两个独立的应用（SSO 模式三）：SSO 服务端签发票据，actix-web 客户端通过签名的 HTTP 请求向服务端校验票据后建立本地会话。
+//! Two separate apps (SSO mode 3): the SSO server issues tickets, the actix-web client checks them
+//! with the server over signed HTTP requests and then opens a local session.
+//!
+//! ```text
+//! 客户端 | Client  GET  /login          → 302 服务端 | server /sso/auth?service=...
+//! 服务端 | Server  GET  /sso/auth       → 登录页 | login page
+//! 服务端 | Server  POST /sso/doLogin    → 302 {service}?ticket=ST-...
+//! 客户端 | Client  GET  /sso/callback   → GET 服务端 | server /sso/checkTicket?ticket=...&sign=... → 本地 token | local token
+//! 客户端 | Client  GET  /api/me         → SaCheckLoginMiddleware
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use actix_web::{HttpResponse, http::header, web};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_plugin_actix_web::*;
+
+/// 服务端与客户端共享的签名密钥 | Sign secret shared by the server and the client
+const SSO_SECRET: &str = "sso-demo-secret";
+
+// ==================== SSO 服务端 | SSO Server ====================
+
+/// 创建 SSO 服务端，使用独立的存储与管理器 | Create the SSO server with its own storage and manager
+pub fn sso_server() -> Arc<SsoServer> {
+    let storage = Arc::new(MemoryStorage::new());
+    let manager = SaTokenManager::new(storage.clone(), SaTokenConfig::default());
+    Arc::new(
+        SsoServer::new(Arc::new(manager))
+            .with_ticket_timeout(60)
+            .with_allow_urls(vec!["http://127.0.0.1:*".to_string()])
+            .with_sign(SaSignManager::new(SSO_SECRET, storage)),
+    )
+}
+
+/// 注册服务端路由 | Register the server routes
+pub fn configure_server(server: Arc<SsoServer>) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.app_data(web::Data::from(server))
+            .route("/sso/auth", web::get().to(auth_page))
+            .route("/sso/doLogin", web::post().to(do_login))
+            .route("/sso/checkTicket", web::get().to(check_ticket));
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthQuery {
+    service: Option<String>,
+}
+
+async fn auth_page(server: web::Data<SsoServer>, query: web::Query<AuthQuery>) -> HttpResponse {
+    match server.render_login_page("/sso/doLogin", query.service.as_deref(), None, None) {
+        Ok(html) => HttpResponse::Ok().content_type("text/html; charset=utf-8").body(html),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e.to_string() })),
+    }
+}
+
+#[derive(Deserialize)]
+struct DoLoginRequest {
+    username: String,
+    password: String,
+    service: String,
+}
+
+async fn do_login(server: web::Data<SsoServer>, req: web::Form<DoLoginRequest>) -> HttpResponse {
+    // 示例账号：alice / alice123 | Demo account: alice / alice123
+    if req.username != "alice" || req.password != "alice123" {
+        return HttpResponse::Unauthorized().json(json!({ "error": "invalid_credentials" }));
+    }
+    if let Err(e) = server.check_redirect_url(&req.service) {
+        return HttpResponse::BadRequest().json(json!({ "error": e.to_string() }));
+    }
+    if let Err(e) = server.login(req.username.clone(), req.service.clone()).await {
+        return HttpResponse::InternalServerError().json(json!({ "error": e.to_string() }));
+    }
+    match server.build_redirect_url(&req.username, &req.service).await {
+        Ok(redirect) => HttpResponse::Found()
+            .insert_header((header::LOCATION, redirect))
+            .finish(),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e.to_string() })),
+    }
+}
+
+async fn check_ticket(server: web::Data<SsoServer>, query: web::Query<BTreeMap<String, String>>) -> HttpResponse {
+    match server.check_ticket_request(&query).await {
+        Ok(login_id) => HttpResponse::Ok().json(json!({ "login_id": login_id })),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e.to_string() })),
+    }
+}
+
+// ==================== SSO 客户端 | SSO Client ====================
+
+/// 客户端状态 | Client state
+#[derive(Clone)]
+pub struct ClientState {
+    pub sa_token: web::Data<SaTokenState>,
+    pub sso: Arc<SsoClient>,
+    http: reqwest::Client,
+}
+
+impl ClientState {
+    /// 创建客户端状态 | Create the client state
+    ///
+    /// # 参数 | Parameters
+    /// * `server_url` - SSO 服务端地址 | SSO server base URL
+    /// * `client_url` - 本应用地址 | This application's base URL
+    pub fn new(server_url: &str, client_url: &str) -> Self {
+        let sa_token = SaTokenState::builder()
+            .storage(Arc::new(MemoryStorage::new()))
+            .token_name("satoken")
+            .build();
+        let sso = SsoClient::new(
+            sa_token.manager.clone(),
+            format!("{}/sso/auth", server_url),
+            format!("{}/sso/callback", client_url),
+        )
+        .with_check_ticket_url(format!("{}/sso/checkTicket", server_url))
+        .with_sign(SaSignManager::new(SSO_SECRET, Arc::new(MemoryStorage::new())));
+        Self {
+            sa_token,
+            sso: Arc::new(sso),
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+/// 注册客户端路由 | Register the client routes
+pub fn configure_client(state: ClientState) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.app_data(web::Data::new(state.clone()))
+            .route("/login", web::get().to(redirect_to_server))
+            .route("/sso/callback", web::get().to(callback))
+            .service(
+                web::scope("/api")
+                    .wrap(SaCheckLoginMiddleware::new(state.sa_token.get_ref().clone()))
+                    .route("/me", web::get().to(me)),
+            );
+    }
+}
+
+async fn redirect_to_server(state: web::Data<ClientState>) -> HttpResponse {
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, state.sso.get_login_url()))
+        .finish()
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    ticket: String,
+}
+
+async fn callback(state: web::Data<ClientState>, query: web::Query<CallbackQuery>) -> HttpResponse {
+    let Ok(url) = state.sso.check_ticket_url(&query.ticket) else {
+        return HttpResponse::InternalServerError().json(json!({ "error": "sso_not_configured" }));
+    };
+    let checked = match state.http.get(url).send().await {
+        Ok(res) if res.status().is_success() => res.json::<serde_json::Value>().await.ok(),
+        _ => None,
+    };
+    let Some(login_id) = checked.as_ref().and_then(|v| v["login_id"].as_str()) else {
+        return HttpResponse::Unauthorized().json(json!({ "error": "invalid_ticket" }));
+    };
+    match state.sso.login_by_ticket(login_id.to_string()).await {
+        Ok(token) => HttpResponse::Ok().json(json!({ "token": token })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e.to_string() })),
+    }
+}
+
+async fn me(LoginIdExtractor(login_id): LoginIdExtractor) -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "login_id": login_id }))
+}
@@ -0,0 +1,30 @@
+// Author: 金书记
+//
+//! 启动 SSO 服务端（:9000）与客户端（:9001）| Start the SSO server (:9000) and client (:9001)
+//!
+//! ```bash
+//! cargo run -p actix-sso-example
+//! # 浏览器打开 | open in a browser: http://127.0.0.1:9001/login
+//! ```
+
+use actix_web::{App, HttpServer};
+use actix_sso_example::{ClientState, configure_client, configure_server, sso_server};
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    tracing_subscriber::fmt().with_target(false).compact().init();
+
+    let server = sso_server();
+    let sso = HttpServer::new(move || App::new().configure(configure_server(server.clone())))
+        .bind(("127.0.0.1", 9000))?
+        .run();
+
+    let state = ClientState::new("http://127.0.0.1:9000", "http://127.0.0.1:9001");
+    let client = HttpServer::new(move || App::new().configure(configure_client(state.clone())))
+        .bind(("127.0.0.1", 9001))?
+        .run();
+
+    tracing::info!("SSO server on http://127.0.0.1:9000, client on http://127.0.0.1:9001");
+    tokio::try_join!(sso, client)?;
+    Ok(())
+}
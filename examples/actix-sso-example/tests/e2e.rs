@@ -0,0 +1,69 @@
+// Author: 金书记
+//
+//! SSO 票据流程端到端测试 | End-to-end test of the SSO ticket flow
+
+use std::net::TcpListener;
+use actix_web::{App, HttpServer};
+use actix_sso_example::{ClientState, configure_client, configure_server, sso_server};
+use reqwest::{StatusCode, header::LOCATION, redirect::Policy};
+use serde_json::Value;
+
+fn bind() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+#[actix_web::test]
+async fn test_sso_ticket_flow() {
+    let (server_listener, server_url) = bind();
+    let (client_listener, client_url) = bind();
+
+    let server = sso_server();
+    actix_web::rt::spawn(
+        HttpServer::new(move || App::new().configure(configure_server(server.clone())))
+            .listen(server_listener).unwrap()
+            .run(),
+    );
+    let state = ClientState::new(&server_url, &client_url);
+    actix_web::rt::spawn(
+        HttpServer::new(move || App::new().configure(configure_client(state.clone())))
+            .listen(client_listener).unwrap()
+            .run(),
+    );
+    let http = reqwest::Client::builder().redirect(Policy::none()).build().unwrap();
+
+    // 未登录访问受保护接口 | Protected endpoint without a session
+    let res = http.get(format!("{client_url}/api/me")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    // 客户端跳转到服务端登录页 | The client redirects to the server login page
+    let res = http.get(format!("{client_url}/login")).send().await.unwrap();
+    let login_url = res.headers()[LOCATION].to_str().unwrap().to_string();
+    assert!(login_url.starts_with(&format!("{server_url}/sso/auth?service=")));
+    let page = http.get(&login_url).send().await.unwrap().text().await.unwrap();
+    assert!(page.contains("name=\"username\""));
+
+    let service = format!("{client_url}/sso/callback");
+    let res = http.post(format!("{server_url}/sso/doLogin"))
+        .form(&[("username", "alice"), ("password", "alice123"), ("service", service.as_str())])
+        .send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::FOUND);
+    let callback = res.headers()[LOCATION].to_str().unwrap().to_string();
+    assert!(callback.starts_with(&service));
+
+    let res = http.get(&callback).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = res.json().await.unwrap();
+    let token = body["token"].as_str().unwrap();
+
+    let me: Value = http.get(format!("{client_url}/api/me"))
+        .header("satoken", token)
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(me["login_id"], "alice");
+
+    // 票据只能使用一次 | Tickets are single-use
+    let res = http.get(&callback).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -55,6 +55,7 @@ async fn main() -> anyhow::Result<()> {
     // Create Sa-Token state
     let sa_token_state = SaTokenState {
         manager: sa_token_manager.clone(),
+        logics: Default::default(),
     };
 
     let sa_token_data = web::Data::new(sa_token_state.clone());
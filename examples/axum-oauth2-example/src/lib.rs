@@ -0,0 +1,172 @@
+// Author: 金书记
+//
+//! Axum + Redis + OAuth2 授权服务器示例 | Axum + Redis + OAuth2 Authorization Server Example
+//!
+//! ```text
+//! POST /login                       账号密码登录，返回 sa-token | log in, returns a sa-token
+//! GET  /oauth2/authorize            已登录用户同意授权，302 回调并附带 code | logged-in user grants, 302 to the callback with a code
+//! POST /oauth2/token                code / refresh_token 换取 access_token | exchange a code or refresh_token for an access_token
+//! GET  /oauth2/userinfo             Bearer access_token 查询用户 | look up the user behind a Bearer access_token
+//! POST /oauth2/introspect           资源服务器内省 access_token（RFC 7662）| resource servers introspect an access_token (RFC 7662)
+//! POST /oauth2/revoke               客户端撤销令牌（RFC 7009）| clients revoke their tokens (RFC 7009)
+//! ```
+//!
+//! 授权码、access_token 与 sa-token 会话共用同一个存储（生产环境为 Redis）。
+//! Codes, access tokens and sa-token sessions share one storage (Redis in production).
+
+use std::sync::Arc;
+use axum::{
+    Form, Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+};
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_plugin_axum::*;
+
+/// 示例客户端 | Demo client
+pub const CLIENT_ID: &str = "demo-app";
+/// 示例客户端密钥 | Demo client secret
+pub const CLIENT_SECRET: &str = "demo-secret";
+/// 示例客户端回调地址 | Demo client callback
+pub const REDIRECT_URI: &str = "http://localhost:8080/callback";
+
+/// 应用状态 | Application state
+#[derive(Clone)]
+pub struct AppState {
+    pub sa_token: SaTokenState,
+    pub oauth2: Arc<OAuth2Manager>,
+}
+
+impl AppState {
+    /// 创建状态并注册示例客户端 | Create the state and register the demo client
+    pub async fn new(storage: Arc<dyn SaStorage>) -> SaTokenResult<Self> {
+        let sa_token = SaTokenState::builder()
+            .storage(storage.clone())
+            .token_name("satoken")
+            .timeout(86400)
+            .build();
+        let oauth2 = Arc::new(OAuth2Manager::new(storage));
+        oauth2.register_client(&OAuth2Client {
+            client_id: CLIENT_ID.to_string(),
+            client_secret: CLIENT_SECRET.to_string(),
+            redirect_uris: vec![REDIRECT_URI.to_string()],
+            grant_types: vec!["authorization_code".to_string(), "refresh_token".to_string()],
+            scope: vec!["profile".to_string(), "email".to_string()],
+        }).await?;
+        Ok(Self { sa_token, oauth2 })
+    }
+}
+
+/// 根据 `REDIS_URL` 选择存储，未设置时使用内存存储
+/// Pick the storage from `REDIS_URL`, falling back to memory storage when unset
+pub async fn storage_from_env() -> Arc<dyn SaStorage> {
+    match std::env::var("REDIS_URL") {
+        Ok(url) => Arc::new(
+            RedisStorage::new(&url, "sa-token:oauth2-example:").await
+                .expect("failed to connect to REDIS_URL"),
+        ),
+        Err(_) => Arc::new(MemoryStorage::new()),
+    }
+}
+
+/// 创建路由 | Build the router
+pub fn app(state: AppState) -> Router {
+    Router::new()
+        .route("/login", post(login))
+        .route("/oauth2/authorize", get(authorize))
+        .route("/oauth2/token", post(token))
+        .route("/oauth2/userinfo", get(userinfo))
+        .route("/oauth2/introspect", introspection_handler(state.oauth2.clone()))
+        .route("/oauth2/revoke", revocation_handler(state.oauth2.clone()))
+        .layer(SaTokenLayer::new(state.sa_token.clone()))
+        .with_state(state)
+}
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+async fn login(State(state): State<AppState>, Json(req): Json<LoginRequest>) -> Response {
+    // 示例账号：alice / alice123 | Demo account: alice / alice123
+    if req.username != "alice" || req.password != "alice123" {
+        return oauth2_error(StatusCode::UNAUTHORIZED, "invalid_credentials");
+    }
+    match state.sa_token.manager.login(&req.username).await {
+        Ok(token) => Json(json!({ "token": token.as_str() })).into_response(),
+        Err(e) => oauth2_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthorizeQuery {
+    client_id: String,
+    redirect_uri: String,
+    #[serde(default)]
+    scope: String,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+async fn authorize(
+    State(state): State<AppState>,
+    LoginIdExtractor(login_id): LoginIdExtractor,
+    Query(query): Query<AuthorizeQuery>,
+) -> Response {
+    let Ok(client) = state.oauth2.get_client(&query.client_id).await else {
+        return oauth2_error(StatusCode::BAD_REQUEST, "invalid_client");
+    };
+    if !state.oauth2.validate_redirect_uri(&client, &query.redirect_uri) {
+        return oauth2_error(StatusCode::BAD_REQUEST, "invalid_redirect_uri");
+    }
+    let scope: Vec<String> = query.scope.split_whitespace().map(str::to_string).collect();
+    if !state.oauth2.validate_scope(&client, &scope) {
+        return oauth2_error(StatusCode::BAD_REQUEST, "invalid_scope");
+    }
+
+    let code = state.oauth2.generate_authorization_code(client.client_id, login_id, query.redirect_uri.clone(), scope);
+    if let Err(e) = state.oauth2.store_authorization_code(&code).await {
+        return oauth2_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
+    }
+    let mut location = format!("{}?code={}", query.redirect_uri, code.code);
+    if let Some(s) = query.state {
+        location.push_str(&format!("&state={}", urlencoding::encode(&s)));
+    }
+    Redirect::to(&location).into_response()
+}
+
+async fn token(State(state): State<AppState>, Form(request): Form<OAuth2TokenRequest>) -> Response {
+    match state.oauth2.token_request(&request).await {
+        Ok(token) => Json(token).into_response(),
+        Err(e) => {
+            let error = OAuth2ErrorResponse::from(&e);
+            let status = StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+            (status, Json(error)).into_response()
+        }
+    }
+}
+
+async fn userinfo(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let bearer = headers.get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let Some(access_token) = bearer else {
+        return oauth2_error(StatusCode::UNAUTHORIZED, "invalid_token");
+    };
+    match state.oauth2.verify_access_token(access_token).await {
+        Ok(info) => Json(json!({
+            "sub": info.user_id,
+            "client_id": info.client_id,
+            "scope": info.scope,
+        })).into_response(),
+        Err(_) => oauth2_error(StatusCode::UNAUTHORIZED, "invalid_token"),
+    }
+}
+
+fn oauth2_error(status: StatusCode, error: &str) -> Response {
+    (status, Json(json!({ "error": error }))).into_response()
+}
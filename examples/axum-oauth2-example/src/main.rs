@@ -0,0 +1,20 @@
+// Author: 金书记
+//
+//! 启动 OAuth2 授权服务器 | Start the OAuth2 authorization server
+//!
+//! ```bash
+//! REDIS_URL=redis://127.0.0.1:6379 cargo run -p axum-oauth2-example
+//! ```
+
+use axum_oauth2_example::{AppState, app, storage_from_env};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt().with_target(false).compact().init();
+
+    let state = AppState::new(storage_from_env().await).await?;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+    tracing::info!("OAuth2 server listening on http://127.0.0.1:3000");
+    axum::serve(listener, app(state)).await?;
+    Ok(())
+}
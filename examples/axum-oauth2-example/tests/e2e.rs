@@ -0,0 +1,92 @@
+// Author: 金书记
+//
+//! 授权码流程端到端测试；设置 `REDIS_URL` 时使用真实 Redis
+//! End-to-end test of the authorization code flow; runs against real Redis when `REDIS_URL` is set
+
+use axum_oauth2_example::{AppState, CLIENT_ID, CLIENT_SECRET, REDIRECT_URI, app, storage_from_env};
+use reqwest::{StatusCode, header::LOCATION, redirect::Policy};
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn test_authorization_code_flow() {
+    let state = AppState::new(storage_from_env().await).await.unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app(state)).await.unwrap() });
+    let client = reqwest::Client::builder().redirect(Policy::none()).build().unwrap();
+
+    let login: Value = client.post(format!("{base}/login"))
+        .json(&json!({ "username": "alice", "password": "alice123" }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    let token = login["token"].as_str().unwrap();
+
+    let authorize_url = format!("{base}/oauth2/authorize?client_id={CLIENT_ID}&redirect_uri={REDIRECT_URI}&scope=profile&state=xyz");
+    // 未登录不能授权 | Granting requires a login
+    let res = client.get(&authorize_url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = client.get(&authorize_url).header("satoken", token).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::SEE_OTHER);
+    let location = res.headers()[LOCATION].to_str().unwrap().to_string();
+    assert!(location.starts_with(REDIRECT_URI) && location.ends_with("&state=xyz"));
+    let code = location.split("code=").nth(1).unwrap().split('&').next().unwrap();
+
+    let exchange = [
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("client_id", CLIENT_ID),
+        ("client_secret", CLIENT_SECRET),
+        ("redirect_uri", REDIRECT_URI),
+    ];
+    let res = client.post(format!("{base}/oauth2/token")).form(&exchange).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let issued: Value = res.json().await.unwrap();
+
+    // 授权码只能使用一次 | Codes are single-use
+    let res = client.post(format!("{base}/oauth2/token")).form(&exchange).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let userinfo: Value = client.get(format!("{base}/oauth2/userinfo"))
+        .bearer_auth(issued["access_token"].as_str().unwrap())
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(userinfo["sub"], "alice");
+    assert_eq!(userinfo["scope"], json!(["profile"]));
+
+    // 资源服务器内省 access_token | A resource server introspects the access_token
+    let introspect = [("token", issued["access_token"].as_str().unwrap())];
+    let res = client.post(format!("{base}/oauth2/introspect")).form(&introspect).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let info: Value = client.post(format!("{base}/oauth2/introspect"))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&introspect)
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(info["active"], true);
+    assert_eq!(info["sub"], "alice");
+    assert_eq!(info["scope"], "profile");
+
+    let res = client.post(format!("{base}/oauth2/token"))
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", issued["refresh_token"].as_str().unwrap()),
+            ("client_id", CLIENT_ID),
+            ("client_secret", "wrong"),
+        ])
+        .send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    // 撤销刷新令牌会连带撤销 access_token | Revoking the refresh token also revokes the access_token
+    let res = client.post(format!("{base}/oauth2/revoke"))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&[("token", issued["refresh_token"].as_str().unwrap()), ("token_type_hint", "refresh_token")])
+        .send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let info: Value = client.post(format!("{base}/oauth2/introspect"))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&introspect)
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(info, json!({ "active": false }));
+}
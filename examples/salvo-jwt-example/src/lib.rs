@@ -0,0 +1,145 @@
+// Author: 金书记
+//
+//! Salvo + JWT 无状态校验示例 | Salvo + Stateless JWT Example
+//!
+//! 登录签发 JWT 风格的 token。同一个 token 有两种校验方式：
+//! Login issues a JWT-style token, which can be checked in two ways:
+//!
+//! ```text
+//! GET /api/me      SaCheckLoginMiddleware   查存储，登出立即失效 | looks up storage, revoked at logout
+//! GET /api/claims  JwtAuth                  只验签名与过期时间，不访问存储 | checks signature and expiry only, no storage
+//! ```
+//!
+//! 无状态校验适合无法访问会话存储的下游服务；代价是登出后 token 在过期前仍能通过。
+//! Stateless checks suit downstream services without access to the session storage;
+//! the trade-off is that a logged-out token still passes until it expires.
+
+use std::sync::Arc;
+use salvo::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use sa_token_plugin_salvo::*;
+
+/// 示例 JWT 密钥 | Demo JWT secret
+pub const JWT_SECRET: &str = "salvo-jwt-example-secret";
+
+/// 创建 sa-token 状态 | Create the sa-token state
+pub fn sa_token_state() -> SaTokenState {
+    SaTokenState::builder()
+        .storage(Arc::new(MemoryStorage::new()))
+        .token_name("Authorization")
+        .token_style(TokenStyle::Jwt)
+        .jwt_secret_key(JWT_SECRET)
+        .timeout(3600)
+        .build()
+}
+
+/// 创建路由 | Build the router
+pub fn router(state: SaTokenState) -> Router {
+    Router::new()
+        .hoop(InjectState(state.clone()))
+        .push(Router::with_path("login").post(login))
+        .push(Router::with_path("logout").post(logout))
+        .push(
+            Router::with_path("api/me")
+                .hoop(SaCheckLoginMiddleware::new(state))
+                .get(me),
+        )
+        .push(
+            Router::with_path("api/claims")
+                .hoop(JwtAuth::new(JWT_SECRET))
+                .get(show_claims),
+        )
+}
+
+/// 把 sa-token 状态放入 depot | Put the sa-token state into the depot
+struct InjectState(SaTokenState);
+
+#[async_trait]
+impl Handler for InjectState {
+    async fn handle(&self, _req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
+        depot.inject(self.0.clone());
+    }
+}
+
+/// 无状态 JWT 校验 | Stateless JWT check
+///
+/// 只验证签名与过期时间，通过后把声明放入 depot
+/// Verifies the signature and expiry only, then puts the claims in the depot
+pub struct JwtAuth {
+    jwt: JwtManager,
+}
+
+impl JwtAuth {
+    /// 创建校验器 | Create the check
+    pub fn new(secret: &str) -> Self {
+        Self { jwt: JwtManager::new(secret) }
+    }
+}
+
+#[async_trait]
+impl Handler for JwtAuth {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let token = extract_token_from_request(req, "Authorization");
+        match token.map(|t| self.jwt.validate(&t)) {
+            Some(Ok(claims)) => {
+                depot.inject(claims);
+            }
+            _ => {
+                res.status_code(StatusCode::UNAUTHORIZED);
+                res.render(Json(json!({ "error": "invalid_token" })));
+                ctrl.skip_rest();
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+#[handler]
+async fn login(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = depot.obtain::<SaTokenState>().expect("state is injected");
+    let body = req.parse_json::<LoginRequest>().await;
+    // 示例账号：alice / alice123 | Demo account: alice / alice123
+    let Ok(LoginRequest { username, password }) = body else {
+        res.status_code(StatusCode::BAD_REQUEST);
+        return;
+    };
+    if username != "alice" || password != "alice123" {
+        res.status_code(StatusCode::UNAUTHORIZED);
+        res.render(Json(json!({ "error": "invalid_credentials" })));
+        return;
+    }
+    match state.manager.login(&username).await {
+        Ok(token) => res.render(Json(json!({ "token": token.as_str() }))),
+        Err(e) => {
+            res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+            res.render(Json(json!({ "error": e.to_string() })));
+        }
+    }
+}
+
+#[handler]
+async fn logout(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = depot.obtain::<SaTokenState>().expect("state is injected");
+    if let Some(token) = extract_token_from_request(req, &state.manager.config.token_name) {
+        let _ = state.manager.logout(&TokenValue::new(token)).await;
+    }
+    res.status_code(StatusCode::NO_CONTENT);
+}
+
+#[handler]
+async fn me(depot: &mut Depot, res: &mut Response) {
+    let login_id = depot.sa_login_id().unwrap_or_default();
+    res.render(Json(json!({ "login_id": login_id })));
+}
+
+#[handler]
+async fn show_claims(depot: &mut Depot, res: &mut Response) {
+    let claims = depot.obtain::<JwtClaims>().expect("claims are injected by JwtAuth");
+    res.render(Json(json!({ "login_id": claims.login_id, "exp": claims.exp })));
+}
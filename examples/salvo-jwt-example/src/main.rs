@@ -0,0 +1,19 @@
+// Author: 金书记
+//
+//! 启动 Salvo JWT 示例 | Start the Salvo JWT example
+//!
+//! ```bash
+//! cargo run -p salvo-jwt-example
+//! ```
+
+use salvo::prelude::*;
+use salvo_jwt_example::{router, sa_token_state};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt().with_target(false).compact().init();
+
+    let acceptor = TcpListener::new("127.0.0.1:5800").bind().await;
+    tracing::info!("Salvo JWT example listening on http://127.0.0.1:5800");
+    Server::new(acceptor).serve(router(sa_token_state())).await;
+}
@@ -0,0 +1,51 @@
+// Author: 金书记
+//
+//! 有状态与无状态校验的端到端测试 | End-to-end test of stateful and stateless checks
+
+use salvo::conn::tcp::TcpAcceptor;
+use salvo::prelude::*;
+use salvo_jwt_example::{router, sa_token_state};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn test_jwt_stateful_and_stateless() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let acceptor = TcpAcceptor::try_from(listener).unwrap();
+    let base = format!("http://{}", acceptor.local_addr().unwrap());
+    tokio::spawn(Server::new(acceptor).serve(router(sa_token_state())));
+    let http = reqwest::Client::new();
+
+    let login: Value = http.post(format!("{base}/login"))
+        .json(&json!({ "username": "alice", "password": "alice123" }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    let token = login["token"].as_str().unwrap();
+    // JWT 由三段组成 | A JWT has three segments
+    assert_eq!(token.split('.').count(), 3);
+
+    let me: Value = http.get(format!("{base}/api/me"))
+        .bearer_auth(token)
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(me["login_id"], "alice");
+
+    let claims: Value = http.get(format!("{base}/api/claims"))
+        .bearer_auth(token)
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(claims["login_id"], "alice");
+
+    // 篡改签名后无状态校验失败 | A tampered signature fails the stateless check
+    let tampered = format!("{}x", token);
+    let res = http.get(format!("{base}/api/claims")).bearer_auth(tampered).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    // 登出后有状态校验立即失效，无状态校验在过期前仍通过
+    // After logout the stateful check fails at once, the stateless one passes until expiry
+    http.post(format!("{base}/logout")).bearer_auth(token).send().await.unwrap();
+    let res = http.get(format!("{base}/api/me")).bearer_auth(token).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = http.get(format!("{base}/api/claims")).bearer_auth(token).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
@@ -0,0 +1,138 @@
+// Author: 金书记
+//
+//! Warp 路径鉴权示例 | Warp Path Auth Example
+//!
+//! 所有路由共用一个 `path_auth` 过滤器，由 `PathAuthConfig` 决定哪些路径需要登录、角色或限流。
+//! Every route shares one `path_auth` filter; `PathAuthConfig` decides which paths need a login,
+//! a role or a rate limit.
+//!
+//! ```text
+//! /public/**        公开 | public
+//! /api/login        公开，每个 IP 每分钟 5 次 | public, 5 per IP per minute
+//! /api/**           需要登录 | login required
+//! /api/admin/**     需要 admin 角色 | admin role required
+//! ```
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use serde::Deserialize;
+use serde_json::json;
+use warp::{Filter, Rejection, Reply, http::{HeaderMap, Method, StatusCode}, path::FullPath};
+use sa_token_plugin_warp::*;
+use sa_token_plugin_warp::sa_token_core::{Guard, RateLimitRule, router::{AuthResult, PathAuthConfig, process_auth_request}};
+
+/// 创建 sa-token 状态与示例账号 | Create the sa-token state and the demo accounts
+pub async fn sa_token_state() -> SaTokenState {
+    let state = SaTokenState::builder()
+        .storage(Arc::new(MemoryStorage::new()))
+        .token_name("satoken")
+        .build();
+    state.manager.set_roles("admin", vec!["admin".to_string()]).await;
+    state
+}
+
+/// 路径鉴权规则 | Path auth rules
+pub fn path_auth_config() -> PathAuthConfig {
+    PathAuthConfig::new()
+        .include(vec!["/api/**".to_string()])
+        .exclude(vec!["/api/login".to_string()])
+        .guard("/api/admin/**", Guard::role("admin"))
+        .rate_limit(RateLimitRule::per_ip("/api/login", 5, 60))
+}
+
+/// 被路径鉴权拒绝 | Rejected by path auth
+#[derive(Debug)]
+struct PathAuthRejection {
+    status: u16,
+    body: String,
+    retry_after: Option<u64>,
+}
+
+impl warp::reject::Reject for PathAuthRejection {}
+
+/// 路径鉴权过滤器，通过时提取 `AuthResult` | Path auth filter, extracts the `AuthResult` when the request passes
+pub fn path_auth(
+    state: SaTokenState,
+    config: Arc<PathAuthConfig>,
+) -> impl Filter<Extract = (AuthResult,), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(warp::method())
+        .and(warp::header::headers_cloned())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::addr::remote())
+        .and_then(move |path: FullPath, method: Method, headers: HeaderMap, query: String, remote: Option<SocketAddr>| {
+            let (state, config) = (state.clone(), config.clone());
+            async move {
+                let token = extract_token_from_request(&headers, &query, &state);
+                let client_ip = remote.map(|addr| addr.ip().to_string());
+                let result = process_auth_request(path.as_str(), Some(method.as_str()), None, client_ip.as_deref(), None, token, &config, &state.manager).await;
+                if result.should_reject() {
+                    let (status, body) = config.render_rejection(&result, state.error_renderer.as_ref());
+                    return Err(warp::reject::custom(PathAuthRejection { status, body, retry_after: result.retry_after() }));
+                }
+                Ok(result)
+            }
+        })
+}
+
+/// 创建路由 | Build the routes
+pub fn routes(state: SaTokenState) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone {
+    let auth = path_auth(state.clone(), Arc::new(path_auth_config()));
+    let with_state = warp::any().map(move || state.clone());
+
+    let ping = warp::path!("public" / "ping").and(warp::get()).map(|| "pong");
+    let login = warp::path!("api" / "login")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(warp::body::json())
+        .and(with_state)
+        .and_then(login);
+    let me = warp::path!("api" / "me")
+        .and(warp::get())
+        .and(auth.clone())
+        .map(|result: AuthResult| warp::reply::json(&json!({ "login_id": result.login_id() })));
+    let stats = warp::path!("api" / "admin" / "stats")
+        .and(warp::get())
+        .and(auth)
+        .map(|_: AuthResult| warp::reply::json(&json!({ "users": 2 })));
+
+    ping.or(login).or(me).or(stats).recover(handle_rejection)
+}
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+async fn login(_: AuthResult, req: LoginRequest, state: SaTokenState) -> Result<warp::reply::Response, Rejection> {
+    // 示例账号：admin / admin123，alice / alice123 | Demo accounts: admin / admin123, alice / alice123
+    let valid = matches!((req.username.as_str(), req.password.as_str()), ("admin", "admin123") | ("alice", "alice123"));
+    if !valid {
+        let body = warp::reply::json(&json!({ "error": "invalid_credentials" }));
+        return Ok(warp::reply::with_status(body, StatusCode::UNAUTHORIZED).into_response());
+    }
+    match state.manager.login(&req.username).await {
+        Ok(token) => Ok(warp::reply::json(&json!({ "token": token.as_str() })).into_response()),
+        Err(e) => {
+            let body = warp::reply::json(&json!({ "error": e.to_string() }));
+            Ok(warp::reply::with_status(body, StatusCode::INTERNAL_SERVER_ERROR).into_response())
+        }
+    }
+}
+
+async fn handle_rejection(err: Rejection) -> Result<warp::reply::Response, Infallible> {
+    let Some(rejection) = err.find::<PathAuthRejection>() else {
+        let status = if err.is_not_found() { StatusCode::NOT_FOUND } else { StatusCode::BAD_REQUEST };
+        return Ok(warp::reply::with_status(warp::reply(), status).into_response());
+    };
+    let status = StatusCode::from_u16(rejection.status).unwrap_or(StatusCode::UNAUTHORIZED);
+    let mut response = warp::http::Response::builder()
+        .status(status)
+        .header("content-type", "application/json");
+    if let Some(seconds) = rejection.retry_after {
+        response = response.header("retry-after", seconds);
+    }
+    Ok(response.body(rejection.body.clone().into()).unwrap_or_default())
+}
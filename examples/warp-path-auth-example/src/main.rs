@@ -0,0 +1,17 @@
+// Author: 金书记
+//
+//! 启动 Warp 路径鉴权示例 | Start the Warp path auth example
+//!
+//! ```bash
+//! cargo run -p warp-path-auth-example
+//! ```
+
+use warp_path_auth_example::{routes, sa_token_state};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt().with_target(false).compact().init();
+
+    tracing::info!("Warp path auth example listening on http://127.0.0.1:3030");
+    warp::serve(routes(sa_token_state().await)).run(([127, 0, 0, 1], 3030)).await;
+}
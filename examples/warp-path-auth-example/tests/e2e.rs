@@ -0,0 +1,51 @@
+// Author: 金书记
+//
+//! 路径鉴权、角色守卫与限流的端到端测试 | End-to-end test of path auth, role guards and rate limits
+
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use warp_path_auth_example::{routes, sa_token_state};
+
+#[tokio::test]
+async fn test_path_auth_rules() {
+    let (addr, server) = warp::serve(routes(sa_token_state().await)).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    let base = format!("http://{addr}");
+    let http = reqwest::Client::new();
+    let login = |username: &'static str| {
+        let http = http.clone();
+        let url = format!("{base}/api/login");
+        async move {
+            http.post(url)
+                .json(&json!({ "username": username, "password": format!("{username}123") }))
+                .send().await.unwrap()
+        }
+    };
+
+    let res = http.get(format!("{base}/public/ping")).send().await.unwrap();
+    assert_eq!(res.text().await.unwrap(), "pong");
+
+    let res = http.get(format!("{base}/api/me")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let alice: Value = login("alice").await.json().await.unwrap();
+    let admin: Value = login("admin").await.json().await.unwrap();
+    let (alice, admin) = (alice["token"].as_str().unwrap(), admin["token"].as_str().unwrap());
+
+    let me: Value = http.get(format!("{base}/api/me")).bearer_auth(alice).send().await.unwrap().json().await.unwrap();
+    assert_eq!(me["login_id"], "alice");
+
+    // 角色守卫 | Role guard
+    let res = http.get(format!("{base}/api/admin/stats")).bearer_auth(alice).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = http.get(format!("{base}/api/admin/stats")).bearer_auth(admin).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // 登录接口每个 IP 每分钟 5 次，前面已用 2 次 | Login allows 5 per IP per minute, 2 are used above
+    for _ in 0..3 {
+        assert_eq!(login("alice").await.status(), StatusCode::OK);
+    }
+    let res = login("alice").await;
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(res.headers().contains_key("retry-after"));
+}
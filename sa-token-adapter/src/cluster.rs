@@ -0,0 +1,27 @@
+// Author: 金书记
+//
+//! 集群消息通道trait定义
+//!
+//! 多实例部署时，一个实例登出或踢人后通过该通道通知其他实例丢弃本地缓存。
+//! Redis pub/sub、NATS 等消息系统实现这个trait即可接入。
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use crate::storage::StorageResult;
+
+/// 收到消息时的回调 | Callback invoked for every received message
+pub type ClusterMessageHandler = Arc<dyn Fn(String) + Send + Sync>;
+
+/// 集群消息通道trait
+///
+/// 消息是不透明的字符串，由 sa-token-core 负责编解码
+#[async_trait]
+pub trait ClusterChannel: Send + Sync {
+    /// 向所有实例（包括自己）广播一条消息
+    async fn publish(&self, payload: &str) -> StorageResult<()>;
+
+    /// 订阅通道，把收到的每条消息交给 `on_message`
+    ///
+    /// 订阅期间一直运行，连接断开时返回，调用方负责重新订阅
+    async fn subscribe(&self, on_message: ClusterMessageHandler) -> StorageResult<()>;
+}
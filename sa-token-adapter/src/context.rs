@@ -108,7 +108,7 @@ pub struct CookieOptions {
 }
 
 /// SameSite 属性
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SameSite {
     Strict,
     Lax,
@@ -8,13 +8,16 @@
 //! - 存储适配器
 //! - 请求/响应上下文适配器
 //! - 框架集成适配器
+//! - 集群消息通道
 
 pub mod storage;
 pub mod context;
 pub mod framework;
 pub mod utils;
+pub mod cluster;
 
 pub use storage::SaStorage;
 pub use context::{SaRequest, SaResponse, CookieOptions, SameSite};
 pub use framework::FrameworkAdapter;
+pub use cluster::{ClusterChannel, ClusterMessageHandler};
 pub use utils::{parse_cookies, parse_query_string, build_cookie_string, extract_bearer_token};
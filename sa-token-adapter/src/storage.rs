@@ -42,6 +42,22 @@ pub trait SaStorage: Send + Sync {
     /// * `ttl` - 过期时间（None表示永不过期）
     async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()>;
     
+    /// 仅当键不存在时设置值，返回是否设置成功
+    /// 
+    /// 默认实现先检查再写入（非原子操作），多实例共享的后端（如 Redis）应覆盖此方法
+    /// 
+    /// # 参数
+    /// * `key` - 键
+    /// * `value` - 值
+    /// * `ttl` - 过期时间（None表示永不过期）
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        if self.exists(key).await? {
+            return Ok(false);
+        }
+        self.set(key, value, ttl).await?;
+        Ok(true)
+    }
+    
     /// 删除值
     async fn delete(&self, key: &str) -> StorageResult<()>;
     
@@ -99,9 +115,60 @@ pub trait SaStorage: Send + Sync {
         Ok(new_value)
     }
     
+    /// 向集合添加成员
+    /// 
+    /// 默认实现把集合序列化为 JSON 数组存放在普通键中（非原子操作），
+    /// 支持原生集合的后端（如 Redis）应覆盖此方法
+    /// 
+    /// # 参数
+    /// * `key` - 集合键
+    /// * `member` - 成员
+    async fn sadd(&self, key: &str, member: &str) -> StorageResult<()> {
+        let mut members = self.smembers(key).await?;
+        if !members.iter().any(|m| m == member) {
+            members.push(member.to_string());
+            let value = serde_json::to_string(&members)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            self.set(key, &value, None).await?;
+        }
+        Ok(())
+    }
+    
+    /// 从集合移除成员，集合为空时删除该键
+    async fn srem(&self, key: &str, member: &str) -> StorageResult<()> {
+        let mut members = self.smembers(key).await?;
+        let len = members.len();
+        members.retain(|m| m != member);
+        if members.len() == len {
+            return Ok(());
+        }
+        if members.is_empty() {
+            self.delete(key).await
+        } else {
+            let value = serde_json::to_string(&members)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            self.set(key, &value, None).await
+        }
+    }
+    
+    /// 获取集合的所有成员
+    async fn smembers(&self, key: &str) -> StorageResult<Vec<String>> {
+        match self.get(key).await? {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+            None => Ok(Vec::new()),
+        }
+    }
+    
     /// 清空所有数据（谨慎使用）
     async fn clear(&self) -> StorageResult<()>;
     
+    /// 预热：建立连接并确认后端可用，在启动时由 `SaTokenManager::warm_up` 调用
+    async fn warm_up(&self) -> StorageResult<()> {
+        // 默认实现：无需预热
+        Ok(())
+    }
+    
     /// 获取匹配模式的所有键
     /// 
     /// # 参数
@@ -0,0 +1,80 @@
+// Author: 金书记
+//
+// Path matching benchmark: precompiled patterns versus parsing the pattern on every call
+// 路径匹配基准测试：预编译模式与每次调用都解析模式的对比
+//
+// Run with | 运行方式: cargo bench -p sa-token-core --bench router
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use sa_token_core::router::{match_path, CompiledPattern, PathAuthConfig};
+
+const ITERATIONS: u32 = 200_000;
+
+const PATTERNS: &[&str] = &[
+    "/api/public/**",
+    "/api/*/health",
+    "*.html",
+    "/api/users/*",
+    "/login",
+    r"regex:/api/orders/\d+(/items)?",
+    "/api/**",
+];
+
+const PATHS: &[&str] = &[
+    "/api/public/docs/index",
+    "/api/users/42",
+    "/api/orders/1001/items",
+    "/static/app.html",
+    "/api/reports/2024/summary",
+];
+
+/// Time `f` over `ITERATIONS` rounds and print the cost per round
+/// 执行 `f` 共 `ITERATIONS` 轮并输出每轮耗时
+fn bench(name: &str, mut f: impl FnMut() -> usize) -> Duration {
+    // 预热 | Warm up
+    for _ in 0..ITERATIONS / 10 {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let per_round = start.elapsed() / ITERATIONS;
+    println!("{:<40} {:>10.1?} / round", name, per_round);
+    per_round
+}
+
+fn main() {
+    let compiled: Vec<CompiledPattern> = PATTERNS.iter().map(|p| CompiledPattern::new(*p)).collect();
+    let config = PathAuthConfig::new()
+        .include(vec!["/api/**".to_string()])
+        .exclude(PATTERNS[..6].iter().map(|p| p.to_string()).collect());
+
+    println!("{} patterns x {} paths", PATTERNS.len(), PATHS.len());
+
+    let per_call = bench("parse on every call (try_new + matches)", || {
+        PATHS.iter()
+            .map(|path| PATTERNS.iter().filter(|p| CompiledPattern::try_new(**p).is_ok_and(|c| c.matches(path))).count())
+            .sum()
+    });
+    let cached = bench("match_path (regex cached)", || {
+        PATHS.iter()
+            .map(|path| PATTERNS.iter().filter(|p| match_path(path, p)).count())
+            .sum()
+    });
+    let precompiled = bench("precompiled CompiledPattern::matches", || {
+        PATHS.iter()
+            .map(|path| compiled.iter().filter(|p| p.matches(path)).count())
+            .sum()
+    });
+    bench("PathAuthConfig::check", || {
+        PATHS.iter().filter(|path| config.check(path)).count()
+    });
+
+    println!(
+        "precompiled is {:.1}x faster than parsing per call, {:.1}x faster than match_path",
+        per_call.as_secs_f64() / precompiled.as_secs_f64().max(f64::EPSILON),
+        cached.as_secs_f64() / precompiled.as_secs_f64().max(f64::EPSILON),
+    );
+}
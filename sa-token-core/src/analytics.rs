@@ -0,0 +1,258 @@
+// Author: 金书记
+//
+//! Token 使用统计 | Token Usage Analytics
+//!
+//! ## 流程 | Flow
+//!
+//! ```text
+//! 请求 | Request
+//!   └─→ SaTokenManager::record_access(token_info, route)
+//!         └─→ UsageAnalytics::record
+//!               ├─→ 采样：每 N 次访问记录 1 次，计数按 N 放大 | Sampling: 1 of every N accesses, counts scaled by N
+//!               └─→ 写入内存缓冲区 | Buffered in memory
+//!                     ↓ 缓冲达到 batch_size 或手动 flush | buffer reaches batch_size or manual flush
+//!               存储 sa:usage:{login_id} ← 合并最后访问时间与路由计数 | merge last access & route counts
+//!
+//! UsageAnalytics::for_user(login_id)
+//!   └─→ 存储数据 + 未刷新的缓冲 → UserUsage | stored data + pending buffer → UserUsage
+//!         ├─→ last_access / idle_for()：如「该 API Key 已 90 天未使用」| e.g. "key unused for 90 days"
+//!         └─→ top_routes(n)：路由热度 | route hot map
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 用户的使用统计 | Usage statistics of a user
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserUsage {
+    /// 登录 ID | Login ID
+    pub login_id: String,
+
+    /// 最后访问时间 | Last access time
+    pub last_access: Option<DateTime<Utc>>,
+
+    /// 每个 token 的最后访问时间 | Last access time of every token
+    #[serde(default)]
+    pub tokens: HashMap<String, DateTime<Utc>>,
+
+    /// 路由 → 访问次数（采样估算）| Route → access count (estimated from samples)
+    #[serde(default)]
+    pub routes: HashMap<String, u64>,
+}
+
+impl UserUsage {
+    fn new(login_id: &str) -> Self {
+        Self {
+            login_id: login_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 距最后访问的时长，从未访问时为 `None` | Time since the last access, `None` if never accessed
+    pub fn idle_for(&self) -> Option<Duration> {
+        self.last_access.map(|t| Utc::now() - t)
+    }
+
+    /// 是否已至少 `period` 未使用（从未使用也算）| Whether unused for at least `period` (never used counts)
+    pub fn is_idle(&self, period: Duration) -> bool {
+        self.idle_for().is_none_or(|idle| idle >= period)
+    }
+
+    /// 访问次数最多的路由 | Most accessed routes
+    pub fn top_routes(&self, n: usize) -> Vec<(String, u64)> {
+        let mut routes: Vec<(String, u64)> = self.routes.iter().map(|(r, c)| (r.clone(), *c)).collect();
+        routes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        routes.truncate(n);
+        routes
+    }
+
+    /// 合并另一份统计 | Merge another usage record
+    fn merge(&mut self, other: &UserUsage, max_routes: usize) {
+        self.last_access = self.last_access.max(other.last_access);
+        for (token, time) in &other.tokens {
+            let entry = self.tokens.entry(token.clone()).or_insert(*time);
+            *entry = (*entry).max(*time);
+        }
+        for (route, count) in &other.routes {
+            if let Some(existing) = self.routes.get_mut(route) {
+                *existing += count;
+            } else if self.routes.len() < max_routes {
+                self.routes.insert(route.clone(), *count);
+            }
+        }
+    }
+}
+
+/// Token 使用统计 | Token usage analytics
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let analytics = Arc::new(UsageAnalytics::new(storage.clone()).sample_every(10).batch_size(500));
+/// let manager = SaTokenManager::new(storage, config).with_usage_analytics(analytics.clone());
+///
+/// // 定时任务 | Scheduled job
+/// let usage = analytics.for_user("api-key-42").await?;
+/// if usage.is_idle(chrono::Duration::days(90)) {
+///     // 提示轮换 | Prompt for rotation
+/// }
+/// ```
+pub struct UsageAnalytics {
+    storage: Arc<dyn SaStorage>,
+    sample_every: u64,
+    batch_size: usize,
+    max_routes: usize,
+    counter: AtomicU64,
+    pending: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    users: HashMap<String, UserUsage>,
+    events: usize,
+}
+
+impl UsageAnalytics {
+    /// 创建统计器（记录每次访问，每 100 次写入存储）| Create analytics (record every access, flush every 100)
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            storage,
+            sample_every: 1,
+            batch_size: 100,
+            max_routes: 200,
+            counter: AtomicU64::new(0),
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    /// 每 `n` 次访问采样 1 次 | Sample 1 of every `n` accesses
+    pub fn sample_every(mut self, n: u64) -> Self {
+        self.sample_every = n.max(1);
+        self
+    }
+
+    /// 缓冲多少条采样后写入存储 | Number of buffered samples before flushing to storage
+    pub fn batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// 每个用户最多统计的路由数，超出的新路由被忽略 | Maximum routes tracked per user, new routes beyond it are ignored
+    pub fn max_routes(mut self, max: usize) -> Self {
+        self.max_routes = max;
+        self
+    }
+
+    /// 记录一次访问 | Record an access
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 登录 ID | Login ID
+    /// * `token` - 访问使用的 token | Token used for the access
+    /// * `route` - 路由（建议使用路由模式而非原始路径）| Route (prefer the route pattern over the raw path)
+    pub async fn record(&self, login_id: &str, token: &str, route: &str) -> SaTokenResult<()> {
+        if !self.counter.fetch_add(1, Ordering::Relaxed).is_multiple_of(self.sample_every) {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let should_flush = {
+            let mut pending = self.pending.lock().await;
+            let usage = pending.users.entry(login_id.to_string()).or_insert_with(|| UserUsage::new(login_id));
+            usage.last_access = Some(now);
+            usage.tokens.insert(token.to_string(), now);
+            if let Some(count) = usage.routes.get_mut(route) {
+                *count += self.sample_every;
+            } else if usage.routes.len() < self.max_routes {
+                usage.routes.insert(route.to_string(), self.sample_every);
+            }
+            pending.events += 1;
+            pending.events >= self.batch_size
+        };
+
+        if should_flush {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// 将缓冲写入存储 | Flush the buffer to storage
+    ///
+    /// 关闭服务前应调用一次 | Should be called once before shutting down
+    pub async fn flush(&self) -> SaTokenResult<()> {
+        let users = std::mem::take(&mut *self.pending.lock().await).users;
+        for (login_id, pending) in users {
+            let mut usage = self.load(&login_id).await?;
+            usage.merge(&pending, self.max_routes);
+            let value = serde_json::to_string(&usage)?;
+            self.storage.set(&Self::usage_key(&login_id), &value, None).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// 查询用户的使用统计（包含未刷新的缓冲）| Query the usage of a user (including the pending buffer)
+    pub async fn for_user(&self, login_id: &str) -> SaTokenResult<UserUsage> {
+        let mut usage = self.load(login_id).await?;
+        if let Some(pending) = self.pending.lock().await.users.get(login_id) {
+            usage.merge(pending, self.max_routes);
+        }
+        Ok(usage)
+    }
+
+    /// 删除用户的使用统计 | Delete the usage of a user
+    pub async fn clear_user(&self, login_id: &str) -> SaTokenResult<()> {
+        self.pending.lock().await.users.remove(login_id);
+        self.storage.delete(&Self::usage_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load(&self, login_id: &str) -> SaTokenResult<UserUsage> {
+        let value = self.storage.get(&Self::usage_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        match value {
+            Some(value) => Ok(serde_json::from_str(&value)?),
+            None => Ok(UserUsage::new(login_id)),
+        }
+    }
+
+    fn usage_key(login_id: &str) -> String {
+        format!("sa:usage:{}", login_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_sampled_batched_usage() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let analytics = UsageAnalytics::new(storage.clone()).sample_every(2).batch_size(3);
+
+        for _ in 0..4 {
+            analytics.record("1001", "token-a", "/api/orders").await.unwrap();
+        }
+        analytics.record("1001", "token-b", "/api/users").await.unwrap();
+        analytics.record("1001", "token-b", "/api/users").await.unwrap();
+
+        // 6 次访问采样 3 次，已触发一次写入 | 3 of 6 accesses sampled, one flush triggered
+        assert!(storage.exists("sa:usage:1001").await.unwrap());
+
+        let usage = analytics.for_user("1001").await.unwrap();
+        assert_eq!(usage.routes.get("/api/orders"), Some(&4));
+        assert_eq!(usage.routes.get("/api/users"), Some(&2));
+        assert_eq!(usage.tokens.len(), 2);
+        assert_eq!(usage.top_routes(1), vec![("/api/orders".to_string(), 4)]);
+        assert!(!usage.is_idle(Duration::days(90)));
+
+        let unknown = analytics.for_user("1002").await.unwrap();
+        assert!(unknown.is_idle(Duration::days(90)));
+    }
+}
@@ -0,0 +1,331 @@
+// Author: 金书记
+//
+//! API Key 管理 | API Key Management
+//!
+//! 为脚本、CI 和第三方集成签发长期有效的 API Key。每个 Key 绑定一个 login_id，
+//! 可限定 scope 和过期时间；请求在 `X-API-Key` 请求头中携带，作为会话 token 之外的另一种凭证。
+//! Issues long-lived API keys for scripts, CI and third-party integrations. Each key is bound to
+//! a login_id and may carry scopes and an expiry; requests send it in the `X-API-Key` header as an
+//! alternative credential to the session token.
+//!
+//! 存储中只保存 Key 的哈希，明文只在创建时返回一次。
+//! Only a hash of the key is stored; the plaintext is returned once, on creation.
+//!
+//! ## 流程 | Flow
+//!
+//! ```text
+//! ApiKeyManager::create(login_id, name, scopes, ttl)
+//!   └─→ sk_{key_id}_{secret}   明文只返回一次 | plaintext returned once
+//!
+//! ApiKeyManager::verify(header)
+//!   ├─→ 按 key_id 读取记录，校验 secret 哈希 | load the record by key_id, verify the secret hash
+//!   ├─→ 已撤销、已过期或不匹配 → InvalidApiKey | revoked, expired or mismatched → InvalidApiKey
+//!   └─→ 更新 last_used_at 并返回 ApiKeyInfo | update last_used_at and return the ApiKeyInfo
+//!
+//! sa:api-key:{key_id}            Key 信息与 secret 哈希 | Key information and secret hash
+//! sa:api-key:user:{login_id}     用户的 key_id 集合 | Set of the user's key_ids
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::secure::{PasswordHasher, SecretHasher};
+
+/// 默认的 API Key 请求头 | Default API key header
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// API Key 信息 | API key information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyInfo {
+    /// Key ID，明文 Key 的公开部分 | Key ID, the public part of the plaintext key
+    pub key_id: String,
+
+    /// 绑定的登录 ID | Bound login ID
+    pub login_id: String,
+
+    /// 便于识别的名称 | Human-readable name
+    pub name: String,
+
+    /// 授予的 scope | Granted scopes
+    pub scopes: Vec<String>,
+
+    /// 创建时间 | Creation time
+    pub created_at: DateTime<Utc>,
+
+    /// 过期时间，`None` 表示永不过期 | Expiry time, `None` means it never expires
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// 最近一次使用时间 | Last time the key was used
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyInfo {
+    /// 是否已过期 | Whether the key has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|at| at <= Utc::now())
+    }
+
+    /// 是否拥有指定 scope | Whether the key has the given scope
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredApiKey {
+    #[serde(flatten)]
+    info: ApiKeyInfo,
+    secret_hash: String,
+}
+
+/// API Key 管理器 | API key manager
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let api_keys = ApiKeyManager::new(storage.clone());
+///
+/// // 签发，明文只返回这一次 | Issue; the plaintext is only returned here
+/// let (key, info) = api_keys.create("10001", "CI deploy", vec!["deploy".into()], Some(90 * 86400)).await?;
+///
+/// // 校验请求头 | Verify the header
+/// let info = api_keys.verify(&header_value).await?;
+/// assert!(info.has_scope("deploy"));
+///
+/// // 撤销 | Revoke
+/// api_keys.revoke(&info.key_id).await?;
+/// ```
+#[derive(Clone)]
+pub struct ApiKeyManager {
+    storage: Arc<dyn SaStorage>,
+    prefix: String,
+    last_used_interval: i64,
+    header: String,
+}
+
+impl ApiKeyManager {
+    /// 创建管理器（前缀 `sk`，最近使用时间每 60 秒最多更新一次）
+    /// Create a manager (prefix `sk`, last-used time updated at most once per 60 seconds)
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            storage,
+            prefix: "sk".to_string(),
+            last_used_interval: 60,
+            header: API_KEY_HEADER.to_string(),
+        }
+    }
+
+    /// 设置 Key 前缀，便于在日志和密钥扫描中识别 | Set the key prefix, so keys are recognizable in logs and secret scanners
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// 最近使用时间的最小更新间隔（秒），`0` 表示每次使用都更新
+    /// Minimum interval between last-used updates in seconds, `0` updates on every use
+    pub fn last_used_interval(mut self, seconds: i64) -> Self {
+        self.last_used_interval = seconds;
+        self
+    }
+
+    /// 设置请求头名称 | Set the header name
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// 请求头名称 | Header name
+    pub fn header_name(&self) -> &str {
+        &self.header
+    }
+
+    /// 创建 API Key | Create an API key
+    ///
+    /// 返回明文 Key 和 Key 信息；明文不会被保存，之后无法再次获取。
+    /// Returns the plaintext key and its information; the plaintext is not stored and cannot be retrieved later.
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 绑定的登录 ID | Login ID to bind
+    /// * `name` - 便于识别的名称 | Human-readable name
+    /// * `scopes` - 授予的 scope | Granted scopes
+    /// * `ttl` - 有效期（秒），`None` 表示永不过期 | Lifetime in seconds, `None` never expires
+    pub async fn create(
+        &self,
+        login_id: &str,
+        name: &str,
+        scopes: Vec<String>,
+        ttl: Option<i64>,
+    ) -> SaTokenResult<(String, ApiKeyInfo)> {
+        let key_id = uuid::Uuid::new_v4().simple().to_string();
+        let secret = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let now = Utc::now();
+        let stored = StoredApiKey {
+            info: ApiKeyInfo {
+                key_id: key_id.clone(),
+                login_id: login_id.to_string(),
+                name: name.to_string(),
+                scopes,
+                created_at: now,
+                expires_at: ttl.map(|ttl| now + chrono::Duration::seconds(ttl)),
+                last_used_at: None,
+            },
+            secret_hash: SecretHasher.hash(&secret)?,
+        };
+
+        self.save(&stored).await?;
+        self.storage.sadd(&Self::user_key(login_id), &key_id).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok((format!("{}_{}_{}", self.prefix, key_id, secret), stored.info))
+    }
+
+    /// 校验 API Key 并记录使用时间 | Verify an API key and record its use
+    ///
+    /// # 参数 | Parameters
+    /// * `key` - 请求头中的明文 Key | Plaintext key from the header
+    pub async fn verify(&self, key: &str) -> SaTokenResult<ApiKeyInfo> {
+        let (key_id, secret) = key.strip_prefix(&format!("{}_", self.prefix))
+            .and_then(|rest| rest.split_once('_'))
+            .ok_or(SaTokenError::InvalidApiKey)?;
+        let mut stored = self.load(key_id).await?.ok_or(SaTokenError::InvalidApiKey)?;
+        if stored.info.is_expired() || !SecretHasher.verify(secret, &stored.secret_hash)? {
+            return Err(SaTokenError::InvalidApiKey);
+        }
+
+        let now = Utc::now();
+        let due = stored.info.last_used_at
+            .is_none_or(|at| (now - at).num_seconds() >= self.last_used_interval);
+        if due {
+            stored.info.last_used_at = Some(now);
+            self.save(&stored).await?;
+        }
+        Ok(stored.info)
+    }
+
+    /// 获取 Key 信息 | Get key information
+    ///
+    /// # 参数 | Parameters
+    /// * `key_id` - Key ID
+    pub async fn get(&self, key_id: &str) -> SaTokenResult<Option<ApiKeyInfo>> {
+        Ok(self.load(key_id).await?.map(|stored| stored.info).filter(|info| !info.is_expired()))
+    }
+
+    /// 列出用户的所有有效 Key，按创建时间排序 | List all live keys of a user, ordered by creation time
+    pub async fn list(&self, login_id: &str) -> SaTokenResult<Vec<ApiKeyInfo>> {
+        let key_ids = self.storage.smembers(&Self::user_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let mut keys = Vec::new();
+        for key_id in key_ids {
+            match self.get(&key_id).await? {
+                Some(info) => keys.push(info),
+                // 已过期的 Key 从索引中移除 | Expired keys are dropped from the index
+                None => self.storage.srem(&Self::user_key(login_id), &key_id).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?,
+            }
+        }
+        keys.sort_by_key(|k| k.created_at);
+        Ok(keys)
+    }
+
+    /// 撤销 Key，不存在时忽略 | Revoke a key; unknown keys are ignored
+    pub async fn revoke(&self, key_id: &str) -> SaTokenResult<()> {
+        let Some(stored) = self.load(key_id).await? else {
+            return Ok(());
+        };
+        self.storage.delete(&Self::key_key(key_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.srem(&Self::user_key(&stored.info.login_id), key_id).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 撤销用户的所有 Key | Revoke all keys of a user
+    pub async fn revoke_all(&self, login_id: &str) -> SaTokenResult<()> {
+        let key_ids = self.storage.smembers(&Self::user_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        for key_id in key_ids {
+            self.storage.delete(&Self::key_key(&key_id)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        self.storage.delete(&Self::user_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn load(&self, key_id: &str) -> SaTokenResult<Option<StoredApiKey>> {
+        let value = self.storage.get(&Self::key_key(key_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        value.map(|v| serde_json::from_str(&v).map_err(SaTokenError::from)).transpose()
+    }
+
+    async fn save(&self, stored: &StoredApiKey) -> SaTokenResult<()> {
+        let ttl = stored.info.expires_at
+            .map(|at| Duration::from_secs((at - Utc::now()).num_seconds().max(1) as u64));
+        let value = serde_json::to_string(stored)?;
+        self.storage.set(&Self::key_key(&stored.info.key_id), &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    fn key_key(key_id: &str) -> String {
+        format!("sa:api-key:{}", key_id)
+    }
+
+    fn user_key(login_id: &str) -> String {
+        format!("sa:api-key:user:{}", login_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_api_key_lifecycle() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let manager = ApiKeyManager::new(storage.clone()).last_used_interval(0);
+
+        let (key, info) = manager.create("alice", "ci", vec!["deploy".to_string()], None).await.unwrap();
+        assert!(key.starts_with(&format!("sk_{}_", info.key_id)));
+        assert!(info.last_used_at.is_none());
+
+        // 存储中没有明文 | The plaintext is not stored
+        let raw = storage.get(&format!("sa:api-key:{}", info.key_id)).await.unwrap().unwrap();
+        assert!(!raw.contains(key.rsplit('_').next().unwrap()));
+
+        let verified = manager.verify(&key).await.unwrap();
+        assert_eq!(verified.login_id, "alice");
+        assert!(verified.has_scope("deploy"));
+        assert!(!verified.has_scope("admin"));
+        assert!(manager.get(&info.key_id).await.unwrap().unwrap().last_used_at.is_some());
+
+        let forged = format!("{}0", key);
+        assert!(matches!(manager.verify(&forged).await, Err(SaTokenError::InvalidApiKey)));
+        assert!(matches!(manager.verify("garbage").await, Err(SaTokenError::InvalidApiKey)));
+
+        let (other, _) = manager.create("alice", "script", vec![], Some(3600)).await.unwrap();
+        let names: Vec<_> = manager.list("alice").await.unwrap().into_iter().map(|k| k.name).collect();
+        assert_eq!(names, ["ci", "script"]);
+
+        manager.revoke(&info.key_id).await.unwrap();
+        assert!(matches!(manager.verify(&key).await, Err(SaTokenError::InvalidApiKey)));
+        assert!(manager.verify(&other).await.is_ok());
+
+        manager.revoke_all("alice").await.unwrap();
+        assert!(manager.verify(&other).await.is_err());
+        assert!(manager.list("alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_api_key_expires() {
+        let manager = ApiKeyManager::new(Arc::new(MemoryStorage::new()));
+        let (key, info) = manager.create("alice", "old", vec![], Some(3600)).await.unwrap();
+        assert!(info.expires_at.is_some());
+
+        let mut stored = manager.load(&info.key_id).await.unwrap().unwrap();
+        stored.info.expires_at = Some(Utc::now() - chrono::Duration::seconds(1));
+        manager.storage.set(&ApiKeyManager::key_key(&info.key_id), &serde_json::to_string(&stored).unwrap(), None).await.unwrap();
+
+        assert!(matches!(manager.verify(&key).await, Err(SaTokenError::InvalidApiKey)));
+        assert!(manager.list("alice").await.unwrap().is_empty());
+    }
+}
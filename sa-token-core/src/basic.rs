@@ -0,0 +1,129 @@
+// Author: 金书记
+//
+//! HTTP Basic 认证 | HTTP Basic Authentication
+//!
+//! 用 `Authorization: Basic <base64(username:password)>` 保护运维接口（如 `/metrics`、`/admin`），
+//! 无需登录会话。
+//! Protects ops endpoints such as `/metrics` and `/admin` with
+//! `Authorization: Basic <base64(username:password)>`, no login session required.
+//!
+//! ```text
+//! HttpBasicAuth.check(Authorization 请求头 | header)
+//!   ├─→ 缺失或格式错误 | missing or malformed → BasicAuthFailed
+//!   ├─→ 凭证不匹配 | credentials rejected   → BasicAuthFailed
+//!   └─→ 通过，返回用户名 | passed, returns the username
+//!
+//! 拒绝时响应 401，并带上 WWW-Authenticate: Basic realm="..."
+//! Rejections answer 401 with WWW-Authenticate: Basic realm="..."
+//! ```
+
+use std::sync::Arc;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::secure::SaSecureUtil;
+
+/// 默认认证域 | Default realm
+pub const DEFAULT_BASIC_REALM: &str = "Sa-Token";
+
+/// 凭证校验函数类型 | Credentials verifier function type
+pub type BasicVerifier = Arc<dyn Fn(&str, &str) -> bool + Send + Sync>;
+
+/// HTTP Basic 认证 | HTTP Basic authentication
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::basic::HttpBasicAuth;
+///
+/// let basic = HttpBasicAuth::new("ops", "s3cret").realm("metrics");
+/// assert_eq!(basic.check(Some("Basic b3BzOnMzY3JldA==")).unwrap(), "ops");
+/// assert!(basic.check(None).is_err());
+/// assert_eq!(basic.challenge(), "Basic realm=\"metrics\"");
+/// ```
+#[derive(Clone)]
+pub struct HttpBasicAuth {
+    realm: String,
+    verifier: BasicVerifier,
+}
+
+impl HttpBasicAuth {
+    /// 使用固定的用户名和密码创建 | Create with a fixed username and password
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        let (username, password) = (username.into(), password.into());
+        Self::with_verifier(move |u, p| {
+            // 两项都比较，避免通过耗时推断用户名 | Compare both so timing does not reveal the username
+            SaSecureUtil::constant_time_eq(u.as_bytes(), username.as_bytes()) & SaSecureUtil::constant_time_eq(p.as_bytes(), password.as_bytes())
+        })
+    }
+
+    /// 使用自定义校验函数创建（如查询数据库）| Create with a custom verifier (e.g. a database lookup)
+    pub fn with_verifier<F>(f: F) -> Self
+    where
+        F: Fn(&str, &str) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            realm: DEFAULT_BASIC_REALM.to_string(),
+            verifier: Arc::new(f),
+        }
+    }
+
+    /// 设置认证域 | Set the realm
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = realm.into();
+        self
+    }
+
+    /// `WWW-Authenticate` 响应头的值 | Value of the `WWW-Authenticate` header
+    pub fn challenge(&self) -> String {
+        format!("Basic realm=\"{}\"", self.realm.replace('"', "'"))
+    }
+
+    /// 校验 `Authorization` 请求头，通过时返回用户名
+    /// Check the `Authorization` header, returning the username on success
+    ///
+    /// # 参数 | Parameters
+    /// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+    pub fn check(&self, authorization: Option<&str>) -> SaTokenResult<String> {
+        let (username, password) = authorization
+            .and_then(parse_basic)
+            .ok_or(SaTokenError::BasicAuthFailed)?;
+        if (self.verifier)(&username, &password) {
+            Ok(username)
+        } else {
+            Err(SaTokenError::BasicAuthFailed)
+        }
+    }
+}
+
+/// 解析 `Basic` 凭证 | Parse `Basic` credentials
+pub(crate) fn parse_basic(header: &str) -> Option<(String, String)> {
+    let (scheme, encoded) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(credentials: &str) -> String {
+        format!("Basic {}", STANDARD.encode(credentials))
+    }
+
+    #[test]
+    fn test_basic_auth() {
+        let basic = HttpBasicAuth::new("ops", "pa:ss");
+        assert_eq!(basic.check(Some(&header("ops:pa:ss"))).unwrap(), "ops");
+        assert!(basic.check(Some(&header("ops:wrong"))).is_err());
+        assert!(basic.check(Some("Bearer abc")).is_err());
+        assert!(basic.check(Some("Basic !!!")).is_err());
+
+        let basic = HttpBasicAuth::with_verifier(|u, p| u.starts_with("svc-") && p == "token");
+        assert!(matches!(basic.check(Some(&header("svc-a:token"))), Ok(ref u) if u == "svc-a"));
+        assert!(matches!(basic.check(Some(&header("user:token"))), Err(SaTokenError::BasicAuthFailed)));
+    }
+}
@@ -0,0 +1,235 @@
+// Author: 金书记
+//
+//! CAS 3.0 协议兼容 | CAS 3.0 Protocol Compatibility
+//!
+//! 在 `SsoServer` 之上提供 CAS 协议的 `/login`、`/serviceValidate` 与 `/p3/serviceValidate`，
+//! 使现有的 CAS 客户端库无需修改即可接入。票据、回跳地址白名单与登出仍由 `SsoServer` 负责。
+//! Provides the CAS `/login`, `/serviceValidate` and `/p3/serviceValidate` endpoints on top of
+//! `SsoServer`, so existing CAS client libraries work unchanged. Tickets, the redirect allow list
+//! and logout stay with `SsoServer`.
+//!
+//! ```text
+//! GET /cas/login?service=https://app/cas
+//!   ├─> 已登录 | logged in  → 302 https://app/cas?ticket=ST-...
+//!   ├─> gateway=true        → 302 https://app/cas（不带票据 | without a ticket）
+//!   └─> 未登录 | not logged in → 登录页 | login page
+//!
+//! GET /cas/serviceValidate?service=https://app/cas&ticket=ST-...      CAS 2.0 XML
+//! GET /cas/p3/serviceValidate?service=https://app/cas&ticket=ST-...   CAS 3.0 XML，含属性 | with attributes
+//! ```
+//!
+//! 不支持 `renew` 与代理票据（`pgtUrl`、`/proxyValidate`）。
+//! `renew` and proxy tickets (`pgtUrl`, `/proxyValidate`) are not supported.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::sso::{service_of, SsoServer};
+
+/// CAS 响应的 XML 命名空间 | XML namespace of CAS responses
+pub const CAS_NAMESPACE: &str = "http://www.yale.edu/tp/cas";
+
+/// CAS 3.0 用户属性提供者 | CAS 3.0 user attribute provider
+#[async_trait]
+pub trait CasAttributeProvider: Send + Sync {
+    /// 返回用户属性，每个属性可有多个值 | Return the user's attributes; each may have several values
+    async fn attributes(&self, login_id: &str) -> SaTokenResult<BTreeMap<String, Vec<String>>>;
+}
+
+/// CAS `/login` 请求的处理结果 | Outcome of a CAS `/login` request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CasLoginAction {
+    /// 302 重定向到该地址 | 302 redirect to this URL
+    Redirect(String),
+    /// 用户需要先登录 | The user must log in first
+    Login,
+}
+
+/// CAS 协议服务端 | CAS protocol server
+#[derive(Clone)]
+pub struct CasServer {
+    sso: Arc<SsoServer>,
+    attributes: Option<Arc<dyn CasAttributeProvider>>,
+}
+
+impl CasServer {
+    /// 创建 CAS 服务端 | Create a CAS server
+    ///
+    /// # 参数 | Parameters
+    /// * `sso` - 签发与校验票据的 SSO 服务端 | SSO server issuing and checking tickets
+    pub fn new(sso: Arc<SsoServer>) -> Self {
+        Self { sso, attributes: None }
+    }
+
+    /// 设置 `/p3/serviceValidate` 返回的用户属性提供者 | Set the provider of user attributes returned by `/p3/serviceValidate`
+    pub fn with_attribute_provider(mut self, provider: Arc<dyn CasAttributeProvider>) -> Self {
+        self.attributes = Some(provider);
+        self
+    }
+
+    /// 底层的 SSO 服务端 | The underlying SSO server
+    pub fn sso(&self) -> &SsoServer {
+        &self.sso
+    }
+
+    /// 处理 `/login` 请求 | Handle a `/login` request
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 当前登录用户，未登录时为 `None` | Current user, `None` when not logged in
+    /// * `params` - 查询参数 | Query parameters
+    ///
+    /// # 错误 | Errors
+    /// * `SsoRedirectNotAllowed` - 已登录但 `service` 缺失或不在白名单中 | Logged in, but `service` is missing or not in the allow list
+    pub async fn login_request(&self, login_id: Option<&str>, params: &BTreeMap<String, String>) -> SaTokenResult<CasLoginAction> {
+        let service = params.get("service").filter(|s| !s.is_empty());
+        match (login_id, service) {
+            (Some(login_id), service) => {
+                let service = service.map_or("", String::as_str);
+                Ok(CasLoginAction::Redirect(self.sso.build_redirect_url(login_id, service).await?))
+            }
+            (None, Some(service)) if params.get("gateway").is_some_and(|g| g == "true") => {
+                self.sso.check_redirect_url(service)?;
+                Ok(CasLoginAction::Redirect(service.clone()))
+            }
+            (None, _) => Ok(CasLoginAction::Login),
+        }
+    }
+
+    /// 处理 `/serviceValidate`（CAS 2.0）请求，返回 XML | Handle a `/serviceValidate` (CAS 2.0) request and return the XML
+    pub async fn service_validate(&self, params: &BTreeMap<String, String>) -> String {
+        self.validate(params, false).await
+    }
+
+    /// 处理 `/p3/serviceValidate`（CAS 3.0）请求，返回带属性的 XML
+    /// Handle a `/p3/serviceValidate` (CAS 3.0) request and return the XML with attributes
+    pub async fn p3_service_validate(&self, params: &BTreeMap<String, String>) -> String {
+        self.validate(params, true).await
+    }
+
+    async fn validate(&self, params: &BTreeMap<String, String>, with_attributes: bool) -> String {
+        let (Some(ticket), Some(service)) = (params.get("ticket"), params.get("service")) else {
+            return failure("INVALID_REQUEST", "'ticket' and 'service' parameters are both required");
+        };
+        let login_id = match self.sso.validate_ticket(ticket, service_of(service)).await {
+            Ok(login_id) => login_id,
+            Err(SaTokenError::ServiceMismatch) => {
+                return failure("INVALID_SERVICE", &format!("Ticket '{}' does not match supplied service", ticket));
+            }
+            Err(SaTokenError::InvalidTicket | SaTokenError::TicketExpired) => {
+                return failure("INVALID_TICKET", &format!("Ticket '{}' not recognized", ticket));
+            }
+            Err(e) => return failure("INTERNAL_ERROR", &e.to_string()),
+        };
+
+        let attributes = match (&self.attributes, with_attributes) {
+            (Some(provider), true) => match provider.attributes(&login_id).await {
+                Ok(attributes) => Some(attributes),
+                Err(e) => return failure("INTERNAL_ERROR", &e.to_string()),
+            },
+            (None, true) => Some(BTreeMap::new()),
+            (_, false) => None,
+        };
+        success(&login_id, attributes.as_ref())
+    }
+}
+
+fn success(login_id: &str, attributes: Option<&BTreeMap<String, Vec<String>>>) -> String {
+    let mut xml = format!(
+        "<cas:serviceResponse xmlns:cas=\"{}\">\n    <cas:authenticationSuccess>\n        <cas:user>{}</cas:user>\n",
+        CAS_NAMESPACE,
+        escape(login_id),
+    );
+    if let Some(attributes) = attributes {
+        xml.push_str("        <cas:attributes>\n");
+        // 属性名会成为元素名，不合法的名称被忽略 | Attribute names become element names; invalid ones are skipped
+        for (name, values) in attributes.iter().filter(|(name, _)| is_xml_name(name)) {
+            for value in values {
+                xml.push_str(&format!("            <cas:{0}>{1}</cas:{0}>\n", name, escape(value)));
+            }
+        }
+        xml.push_str("        </cas:attributes>\n");
+    }
+    xml.push_str("    </cas:authenticationSuccess>\n</cas:serviceResponse>\n");
+    xml
+}
+
+fn failure(code: &str, message: &str) -> String {
+    format!(
+        "<cas:serviceResponse xmlns:cas=\"{}\">\n    <cas:authenticationFailure code=\"{}\">{}</cas:authenticationFailure>\n</cas:serviceResponse>\n",
+        CAS_NAMESPACE,
+        code,
+        escape(message),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SaTokenConfig, SaTokenManager};
+    use sa_token_storage_memory::MemoryStorage;
+
+    struct StaticAttributes;
+
+    #[async_trait]
+    impl CasAttributeProvider for StaticAttributes {
+        async fn attributes(&self, _login_id: &str) -> SaTokenResult<BTreeMap<String, Vec<String>>> {
+            Ok(BTreeMap::from([
+                ("email".to_string(), vec!["alice@example.com".to_string()]),
+                ("memberOf".to_string(), vec!["admin".to_string(), "r&d".to_string()]),
+                ("bad name".to_string(), vec!["x".to_string()]),
+            ]))
+        }
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_cas_protocol() {
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let sso = Arc::new(SsoServer::new(manager).with_allow_urls(vec!["https://app.com/*".to_string()]));
+        let cas = CasServer::new(sso).with_attribute_provider(Arc::new(StaticAttributes));
+        let service = "https://app.com/cas?page=1";
+
+        assert_eq!(cas.login_request(None, &params(&[("service", service)])).await.unwrap(), CasLoginAction::Login);
+        assert_eq!(
+            cas.login_request(None, &params(&[("service", service), ("gateway", "true")])).await.unwrap(),
+            CasLoginAction::Redirect(service.to_string()),
+        );
+        assert!(cas.login_request(Some("alice"), &params(&[("service", "https://evil.com/")])).await.is_err());
+
+        let CasLoginAction::Redirect(location) = cas.login_request(Some("alice"), &params(&[("service", service)])).await.unwrap() else {
+            panic!("expected a redirect");
+        };
+        let ticket = location.split_once("&ticket=").unwrap().1;
+        assert!(ticket.starts_with("ST-"));
+
+        let xml = cas.service_validate(&params(&[("service", "https://app.com/other"), ("ticket", ticket)])).await;
+        assert!(xml.contains("code=\"INVALID_SERVICE\""));
+
+        let xml = cas.p3_service_validate(&params(&[("service", service), ("ticket", ticket)])).await;
+        assert!(xml.contains("<cas:user>alice</cas:user>"));
+        assert!(xml.contains("<cas:memberOf>r&amp;d</cas:memberOf>"));
+        assert!(!xml.contains("bad name"));
+
+        let xml = cas.service_validate(&params(&[("service", service), ("ticket", ticket)])).await;
+        assert!(xml.contains("code=\"INVALID_TICKET\""));
+        assert!(cas.service_validate(&params(&[("ticket", ticket)])).await.contains("code=\"INVALID_REQUEST\""));
+    }
+}
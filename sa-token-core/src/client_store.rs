@@ -0,0 +1,204 @@
+// Author: 金书记
+//
+//! OAuth2 客户端注册表 | OAuth2 Client Registry
+//!
+//! `ClientStore` 持久化已注册的 OAuth2 客户端，使客户端在重启后仍然存在，并可在运行时
+//! 增删查。`OAuth2Manager` 默认使用基于 `SaStorage` 的 `StorageClientStore`；
+//! 本 crate 不提供基于数据库的实现（`sa-token-storage-database` 仍是占位符），需要关系型数据库时
+//! 自行实现 `ClientStore` 后通过 `OAuth2Manager::with_client_store` 注册。
+//! `ClientStore` persists registered OAuth2 clients so they survive restarts and can be added,
+//! listed and removed at runtime. `OAuth2Manager` uses the `SaStorage`-backed `StorageClientStore`
+//! by default. No database-backed store is shipped (`sa-token-storage-database` is still a
+//! placeholder); for a relational database, implement `ClientStore` yourself and register it with
+//! `OAuth2Manager::with_client_store`.
+//!
+//! 客户端密钥由 `OAuth2Manager` 在保存前哈希，存储中只保存哈希值。
+//! Client secrets are hashed by `OAuth2Manager` before they are saved; stores only ever see hashes.
+//!
+//! ```text
+//! oauth2:client:{client_id}   客户端信息 | Client information
+//! oauth2:clients              已注册的 client_id 集合 | Set of registered client_ids
+//! ```
+//!
+//! ## 自定义数据库实现示例 | Example Custom Database Implementation
+//!
+//! 以下为应用侧自行实现的示意，不属于本 crate。| A sketch of an application-side store, not part of this crate.
+//!
+//! ```sql
+//! CREATE TABLE oauth2_clients (
+//!     client_id     VARCHAR(255) PRIMARY KEY,
+//!     client_secret VARCHAR(255) NOT NULL,  -- 哈希值，公共客户端为空 | hash, empty for public clients
+//!     redirect_uris TEXT NOT NULL,          -- JSON 数组 | JSON array
+//!     grant_types   TEXT NOT NULL,          -- JSON 数组 | JSON array
+//!     scope         TEXT NOT NULL           -- JSON 数组 | JSON array
+//! );
+//! ```
+//!
+//! ```rust,ignore
+//! use sqlx::PgPool;
+//!
+//! pub struct PostgresClientStore {
+//!     pool: PgPool,
+//! }
+//!
+//! #[async_trait]
+//! impl ClientStore for PostgresClientStore {
+//!     async fn get_client(&self, client_id: &str) -> SaTokenResult<Option<OAuth2Client>> {
+//!         let row = sqlx::query!(
+//!             "SELECT client_id, client_secret, redirect_uris, grant_types, scope
+//!              FROM oauth2_clients WHERE client_id = $1",
+//!             client_id,
+//!         )
+//!         .fetch_optional(&self.pool)
+//!         .await
+//!         .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+//!
+//!         row.map(|r| Ok(OAuth2Client {
+//!             client_id: r.client_id,
+//!             client_secret: r.client_secret,
+//!             redirect_uris: serde_json::from_str(&r.redirect_uris)?,
+//!             grant_types: serde_json::from_str(&r.grant_types)?,
+//!             scope: serde_json::from_str(&r.scope)?,
+//!         })).transpose()
+//!     }
+//!
+//!     async fn save_client(&self, client: &OAuth2Client) -> SaTokenResult<()> {
+//!         sqlx::query!(
+//!             "INSERT INTO oauth2_clients (client_id, client_secret, redirect_uris, grant_types, scope)
+//!              VALUES ($1, $2, $3, $4, $5)
+//!              ON CONFLICT (client_id) DO UPDATE
+//!              SET client_secret = $2, redirect_uris = $3, grant_types = $4, scope = $5",
+//!             client.client_id,
+//!             client.client_secret,
+//!             serde_json::to_string(&client.redirect_uris)?,
+//!             serde_json::to_string(&client.grant_types)?,
+//!             serde_json::to_string(&client.scope)?,
+//!         )
+//!         .execute(&self.pool)
+//!         .await
+//!         .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+//!         Ok(())
+//!     }
+//!
+//!     // ... list / delete
+//! }
+//! ```
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::oauth2::OAuth2Client;
+
+/// OAuth2 客户端存储 | OAuth2 client store
+#[async_trait]
+pub trait ClientStore: Send + Sync {
+    /// 获取客户端，不存在时返回 `None` | Get a client, `None` when it does not exist
+    async fn get_client(&self, client_id: &str) -> SaTokenResult<Option<OAuth2Client>>;
+
+    /// 保存客户端，已存在时覆盖 | Save a client, replacing an existing one
+    async fn save_client(&self, client: &OAuth2Client) -> SaTokenResult<()>;
+
+    /// 列出所有客户端 | List all clients
+    async fn list(&self) -> SaTokenResult<Vec<OAuth2Client>>;
+
+    /// 删除客户端，不存在时忽略 | Delete a client; unknown clients are ignored
+    async fn delete(&self, client_id: &str) -> SaTokenResult<()>;
+}
+
+/// 基于 `SaStorage` 的客户端存储 | `SaStorage`-backed client store
+///
+/// 使用 Redis 等持久化存储时客户端在重启后仍然存在。
+/// Clients survive restarts when the storage is persistent, such as Redis.
+#[derive(Clone)]
+pub struct StorageClientStore {
+    storage: Arc<dyn SaStorage>,
+}
+
+impl StorageClientStore {
+    const INDEX_KEY: &'static str = "oauth2:clients";
+
+    /// 创建客户端存储 | Create a client store
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage }
+    }
+
+    fn client_key(client_id: &str) -> String {
+        format!("oauth2:client:{}", client_id)
+    }
+}
+
+#[async_trait]
+impl ClientStore for StorageClientStore {
+    async fn get_client(&self, client_id: &str) -> SaTokenResult<Option<OAuth2Client>> {
+        let value = self.storage.get(&Self::client_key(client_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        value.map(|v| serde_json::from_str(&v).map_err(SaTokenError::SerializationError))
+            .transpose()
+    }
+
+    async fn save_client(&self, client: &OAuth2Client) -> SaTokenResult<()> {
+        let value = serde_json::to_string(client).map_err(SaTokenError::SerializationError)?;
+        self.storage.set(&Self::client_key(&client.client_id), &value, None).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.sadd(Self::INDEX_KEY, &client.client_id).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn list(&self) -> SaTokenResult<Vec<OAuth2Client>> {
+        let client_ids = self.storage.smembers(Self::INDEX_KEY).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let mut clients = Vec::new();
+        for client_id in client_ids {
+            if let Some(client) = self.get_client(&client_id).await? {
+                clients.push(client);
+            }
+        }
+        clients.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        Ok(clients)
+    }
+
+    async fn delete(&self, client_id: &str) -> SaTokenResult<()> {
+        self.storage.delete(&Self::client_key(client_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.srem(Self::INDEX_KEY, client_id).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn client(client_id: &str) -> OAuth2Client {
+        OAuth2Client {
+            client_id: client_id.to_string(),
+            client_secret: String::new(),
+            redirect_uris: vec!["https://app/cb".to_string()],
+            grant_types: vec!["authorization_code".to_string()],
+            scope: vec!["read".to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_storage_client_store() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let store = StorageClientStore::new(storage.clone());
+
+        store.save_client(&client("web")).await.unwrap();
+        store.save_client(&client("cli")).await.unwrap();
+        assert_eq!(store.get_client("web").await.unwrap().unwrap().scope, ["read"]);
+        assert!(store.get_client("missing").await.unwrap().is_none());
+
+        // A second store over the same storage sees the clients, as after a restart
+        let reopened = StorageClientStore::new(storage);
+        let ids: Vec<_> = reopened.list().await.unwrap().into_iter().map(|c| c.client_id).collect();
+        assert_eq!(ids, ["cli", "web"]);
+
+        reopened.delete("cli").await.unwrap();
+        reopened.delete("missing").await.unwrap();
+        assert!(store.get_client("cli").await.unwrap().is_none());
+        assert_eq!(store.list().await.unwrap().len(), 1);
+    }
+}
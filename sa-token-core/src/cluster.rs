@@ -0,0 +1,165 @@
+// Author: 金书记
+//
+//! 集群失效通知 | Cluster invalidation
+//!
+//! 多实例部署时，实例 A 登出或踢人后，实例 B 内存中的权限缓存与在线状态也要同步失效。
+//! `SaTokenManager::with_cluster_channel` 接入一个 [`ClusterChannel`]（Redis pub/sub、NATS 等），
+//! 登出、踢人与权限缓存失效时广播 [`InvalidationMessage`]，`start_cluster_sync` 在其他实例上应用这些消息。
+//! With several instances, when instance A logs out or kicks a user, instance B must drop its in-memory
+//! permission cache and online state too. `SaTokenManager::with_cluster_channel` plugs in a
+//! [`ClusterChannel`] (Redis pub/sub, NATS, ...); logout, kick-out and permission cache invalidation
+//! broadcast an [`InvalidationMessage`], and `start_cluster_sync` applies them on the other instances.
+//!
+//! ```text
+//! A: kick_out("1001") ──publish──▶ channel ──▶ B: drop permission cache, notify local connections
+//! ```
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use sa_token_adapter::cluster::{ClusterChannel, ClusterMessageHandler};
+use sa_token_adapter::storage::{StorageError, StorageResult};
+
+/// 默认的集群频道名 | Default cluster channel name
+pub const DEFAULT_CLUSTER_CHANNEL: &str = "sa-token:invalidation";
+
+/// 需要其他实例失效的内容 | What the other instances must invalidate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Invalidation {
+    /// 单个 token 登出 | A single token was logged out
+    Logout { login_id: String, login_type: String, token: String },
+    /// 账号被踢下线 | An account was kicked out
+    KickOut { login_id: String, login_type: String },
+    /// 用户的权限与角色缓存失效 | The cached permissions and roles of a user are stale
+    Permissions { login_id: String },
+    /// 全部权限与角色缓存失效 | The whole permission and role cache is stale
+    ClearPermissions,
+}
+
+/// 集群失效消息 | Cluster invalidation message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvalidationMessage {
+    /// 发布消息的实例，实例忽略自己发出的消息 | Publishing instance; instances ignore their own messages
+    pub origin: String,
+    /// 消息所属的租户 | Tenant the message belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// 失效内容 | What to invalidate
+    #[serde(flatten)]
+    pub invalidation: Invalidation,
+}
+
+/// 进程内的集群通道，用于测试或同一进程中的多个管理器
+/// In-process cluster channel, for tests or several managers in one process
+#[derive(Clone)]
+pub struct InMemoryClusterChannel {
+    sender: broadcast::Sender<String>,
+}
+
+impl InMemoryClusterChannel {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(1024);
+        Self { sender }
+    }
+}
+
+impl Default for InMemoryClusterChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ClusterChannel for InMemoryClusterChannel {
+    async fn publish(&self, payload: &str) -> StorageResult<()> {
+        // 没有订阅者时消息直接丢弃 | Messages are dropped when nobody subscribes
+        let _ = self.sender.send(payload.to_string());
+        Ok(())
+    }
+
+    async fn subscribe(&self, on_message: ClusterMessageHandler) -> StorageResult<()> {
+        let mut receiver = self.sender.subscribe();
+        loop {
+            match receiver.recv().await {
+                Ok(payload) => on_message(payload),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err(StorageError::ConnectionError("cluster channel closed".to_string()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use crate::{SaTokenConfig, SaTokenManager, SaTokenResult, PermissionProvider};
+    use crate::online::{OnlineManager, InMemoryPusher, MessageType};
+    use sa_token_storage_memory::MemoryStorage;
+
+    struct CountingProvider(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl PermissionProvider for CountingProvider {
+        async fn get_permission_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(vec!["user:read".to_string()])
+        }
+
+        async fn get_role_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cluster_invalidation() {
+        let storage = Arc::new(MemoryStorage::new());
+        let channel = Arc::new(InMemoryClusterChannel::new());
+        let config = SaTokenConfig { permission_cache_timeout: 60, ..Default::default() };
+        let instance_a = SaTokenManager::new(storage.clone(), config.clone())
+            .with_cluster_channel(channel.clone());
+        let online = Arc::new(OnlineManager::new());
+        let pusher = Arc::new(InMemoryPusher::new());
+        online.register_pusher(pusher.clone()).await;
+        let instance_b = SaTokenManager::new(storage, config)
+            .with_cluster_channel(channel)
+            .with_online_manager(online.clone());
+        let calls = Arc::new(AtomicUsize::new(0));
+        instance_b.set_permission_provider(Arc::new(CountingProvider(calls.clone())));
+        let sync = instance_b.start_cluster_sync().unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let token = instance_a.login("1001").await.unwrap();
+        let info = instance_b.get_token_info(&token).await.unwrap();
+        online.mark_online(crate::online::OnlineUser {
+            login_id: "1001".to_string(),
+            token: token.as_str().to_string(),
+            device: "web".to_string(),
+            connect_time: chrono::Utc::now(),
+            last_activity: chrono::Utc::now(),
+            metadata: Default::default(),
+        }).await;
+        assert!(instance_b.has_permission(&info.login_id, "user:read").await);
+        assert!(instance_b.has_permission(&info.login_id, "user:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A 踢人后 B 丢弃缓存并通知本地连接 | After A kicks, B drops its cache and notifies local connections
+        instance_a.kick_out("1001").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!online.is_online("1001").await);
+        assert_eq!(pusher.get_messages("1001").await[0].message_type, MessageType::KickOut);
+        assert!(instance_b.has_permission("1001", "user:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 本实例的失效立即生效 | Local invalidation takes effect at once
+        instance_b.invalidate_permission_cache("1001").await;
+        assert!(instance_b.has_permission("1001", "user:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        sync.abort();
+    }
+}
@@ -4,9 +4,89 @@
 
 use std::time::Duration;
 use std::sync::Arc;
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use sa_token_adapter::storage::SaStorage;
+use sa_token_adapter::context::{SaRequest, CookieOptions, SameSite};
 use crate::event::SaTokenListener;
+use crate::token::TokenGenerator;
+use crate::id::{IdGenerator, MIN_ID_ENTROPY_BITS};
+use crate::permission::{PermissionProvider, RoleHierarchy};
+use crate::schema::SchemaMigrator;
+use crate::response::{ErrorRenderer, ResponseEnvelope};
+use crate::tenant::TenantResolver;
+
+/// Cookie 属性配置 | Cookie attribute configuration
+/// 
+/// 用于登录写回的 token cookie、登出时的过期 cookie 与 remember-me cookie。
+/// 跨站使用 `SameSite=None` 时浏览器要求同时开启 `secure`。
+/// Used by the token cookie written on login, the expiring cookie on logout and the remember-me cookie.
+/// Browsers require `secure` together with `SameSite=None` for cross-site use.
+/// 
+/// ```toml
+/// [cookie]
+/// domain = "example.com"
+/// secure = true
+/// same_site = "Strict"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaCookieConfig {
+    /// 作用域名，`None` 表示仅当前主机
+    pub domain: Option<String>,
+    
+    /// 作用路径
+    pub path: String,
+    
+    /// 是否仅通过 HTTPS 发送
+    pub secure: bool,
+    
+    /// 是否禁止脚本读取
+    pub http_only: bool,
+    
+    /// SameSite 属性，`None` 表示不写该属性
+    pub same_site: Option<SameSite>,
+    
+    /// token cookie 的有效期（秒）
+    /// 
+    /// `None` 与 token 有效期一致；负数写会话 cookie（关闭浏览器即失效）
+    pub max_age: Option<i64>,
+}
+
+impl Default for SaCookieConfig {
+    fn default() -> Self {
+        Self {
+            domain: None,
+            path: "/".to_string(),
+            secure: false,
+            http_only: true,
+            same_site: Some(SameSite::Lax),
+            max_age: None,
+        }
+    }
+}
+
+impl SaCookieConfig {
+    /// 转换为 cookie 选项，`max_age` 为 `None` 时写会话 cookie
+    pub fn options(&self, max_age: Option<i64>) -> CookieOptions {
+        CookieOptions {
+            domain: self.domain.clone(),
+            path: Some(self.path.clone()),
+            max_age,
+            http_only: self.http_only,
+            secure: self.secure,
+            same_site: self.same_site,
+        }
+    }
+    
+    /// token cookie 的 Max-Age | Max-Age of the token cookie
+    pub(crate) fn token_max_age(&self, timeout: i64) -> Option<i64> {
+        match self.max_age {
+            Some(max_age) => (max_age >= 0).then_some(max_age),
+            None => (timeout > 0).then_some(timeout),
+        }
+    }
+}
 
 /// sa-token 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +132,14 @@ pub struct SaTokenConfig {
     /// 是否从 header 中读取 token
     pub is_read_header: bool,
     
+    /// 登录后除 cookie 外是否把 token 写入响应头（名称为 `token_name`），供非浏览器客户端读取
+    #[serde(default)]
+    pub is_write_header: bool,
+    
+    /// token cookie 与 remember-me cookie 的属性
+    #[serde(default)]
+    pub cookie: SaCookieConfig,
+    
     /// 是否从请求体中读取 token
     pub is_read_body: bool,
     
@@ -64,6 +152,38 @@ pub struct SaTokenConfig {
     /// JWT 算法（默认 HS256）
     pub jwt_algorithm: Option<String>,
     
+    /// JWT 签名私钥 PEM（RS*/ES* 算法）
+    /// 
+    /// 资源服务器只验证 token 时可以不配置
+    #[serde(default)]
+    pub jwt_private_key: Option<String>,
+    
+    /// JWT 验证公钥 PEM（RS*/ES* 算法）
+    #[serde(default)]
+    pub jwt_public_key: Option<String>,
+    
+    /// JWT 签名密钥环（支持密钥轮换与 JWKS），设置后优先于上面的密钥配置
+    #[serde(skip)]
+    pub jwt_key_ring: Option<crate::token::JwksKeyRing>,
+    
+    /// JWE 加密密钥，设置后签发的 JWT 被加密为 JWE：对称算法为 Base64 编码的 32 字节，非对称算法为 PEM 密钥（公钥只能加密）
+    #[serde(default)]
+    pub jwt_encryption_key: Option<String>,
+    
+    /// JWE 密钥管理算法（"dir"、"A256KW"、"RSA-OAEP-256" 或 "ECDH-ES+A256KW"，默认 "dir"），内容加密固定为 A256GCM
+    #[serde(default)]
+    pub jwt_encryption_algorithm: Option<String>,
+    
+    /// `TokenInfo` 与 JWT 声明的映射（Mixin / Stateless 模式与 `JwtManager::from_config` 使用），None 表示默认映射
+    #[serde(skip)]
+    pub jwt_claims_mapper: Option<Arc<dyn crate::token::ClaimsMapper>>,
+    
+    /// JWT 集成模式（Simple / Mixin / Stateless），None 表示由 `token_style` 决定
+    /// 
+    /// 见 `JwtMode` 中各模式的取舍
+    #[serde(default)]
+    pub jwt_mode: Option<JwtMode>,
+    
     /// JWT 签发者
     pub jwt_issuer: Option<String>,
     
@@ -81,6 +201,152 @@ pub struct SaTokenConfig {
     
     /// Refresh Token 有效期（秒），默认 7 天
     pub refresh_token_timeout: i64,
+    
+    /// 建立二级索引的会话属性（TokenInfo.extra 中的键，例如 `tenant_id`）
+    /// 
+    /// 已索引的属性可以通过 `list_sessions_by_attribute` / `logout_by_attribute` 高效查询
+    #[serde(default)]
+    pub indexed_attributes: Vec<String>,
+    
+    /// `PermissionProvider` 结果的进程内缓存时长（秒），0 表示不缓存
+    /// 
+    /// 登出、踢人下线时自动失效，也可以调用 `StpUtil::invalidate_permission_cache` 手动失效
+    #[serde(default)]
+    pub permission_cache_timeout: i64,
+    
+    /// 缓存过期后仍可使用旧数据的时长（秒），0 表示不使用
+    /// 
+    /// 期间先返回旧数据，同时在后台刷新；`PermissionProvider` 不可用时旧数据继续兜底，直到超过该时长
+    #[serde(default)]
+    pub permission_cache_max_stale: i64,
+    
+    /// 单次调用 `PermissionProvider` 的超时（毫秒），0 表示不限制
+    #[serde(default)]
+    pub permission_provider_timeout_ms: u64,
+    
+    /// `PermissionProvider` 连续失败多少次后熔断，0 表示不熔断
+    #[serde(default)]
+    pub permission_breaker_threshold: u32,
+    
+    /// 熔断持续时间（秒），期间不调用 `PermissionProvider`
+    #[serde(default = "default_permission_breaker_cooldown")]
+    pub permission_breaker_cooldown: i64,
+    
+    /// 启动预热时加载权限与角色缓存的登录 ID（默认账号类型）
+    /// 
+    /// 需要设置 `PermissionProvider` 且 `permission_cache_timeout` 大于 0
+    #[serde(default)]
+    pub warmup_login_ids: Vec<String>,
+    
+    /// 是否关闭 token 提取日志（`sa_token::extract`），拒绝日志不受影响
+    #[serde(default)]
+    pub mute_extract_log: bool,
+    
+    /// SSO 票据、OAuth2 授权码、nonce 等唯一 ID 的生成器，None 表示安全随机的默认实现
+    #[serde(skip)]
+    pub id_generator: Option<Arc<dyn IdGenerator>>,
+    
+    /// `id_generator` 要求的最小熵（位），0 表示默认的 128 位
+    #[serde(default)]
+    pub min_id_entropy_bits: u32,
+    
+    /// 是否将 token 绑定到登录时的客户端 IP，其他 IP 的请求视为未登录
+    #[serde(default)]
+    pub bind_ip: bool,
+    
+    /// 读取客户端 IP 的请求头（如 `X-Forwarded-For`，取第一个地址），None 表示使用连接的对端地址
+    /// 
+    /// 仅在可信代理会覆盖该请求头时设置
+    #[serde(default)]
+    pub client_ip_header: Option<String>,
+    
+    /// 是否将 token 绑定到登录时的设备指纹（User-Agent 与 `fingerprint_header` 的哈希）
+    #[serde(default)]
+    pub bind_fingerprint: bool,
+    
+    /// 参与设备指纹计算的自定义请求头（如客户端生成的 `X-Device-Id`）
+    #[serde(default)]
+    pub fingerprint_header: Option<String>,
+    
+    /// 失败计数窗口内允许的登录失败次数，达到后锁定账号，0 表示不限制
+    /// 
+    /// 失败由业务调用 `StpUtil::record_failed_login` 记录，`StpUtil::login` 会自动拒绝已锁定的账号
+    #[serde(default)]
+    pub login_max_failures: u32,
+    
+    /// 登录失败计数窗口（秒）
+    #[serde(default = "default_login_failure_window")]
+    pub login_failure_window: i64,
+    
+    /// 首次锁定时长（秒），之后每次锁定翻倍
+    #[serde(default = "default_login_lockout")]
+    pub login_lockout: i64,
+    
+    /// 锁定时长上限（秒），也是退避等级归零前的静默时长
+    #[serde(default = "default_login_lockout_max")]
+    pub login_lockout_max: i64,
+    
+    /// TOTP 双因素认证在验证器应用中显示的发行方
+    #[serde(default = "default_totp_issuer")]
+    pub totp_issuer: String,
+    
+    /// remember-me token 有效期（秒），0 表示关闭“记住我”
+    /// 
+    /// 会话 token 失效后，插件用 remember-me cookie 静默重建会话，每次使用都会轮换
+    #[serde(default)]
+    pub remember_me_timeout: i64,
+    
+    /// 保存 remember-me token 的 cookie 名称
+    #[serde(default = "default_remember_me_cookie")]
+    pub remember_me_cookie: String,
+    
+    /// 权限提升（二级认证、双因素认证通过）后是否自动重新生成 token，防止会话固定攻击
+    #[serde(default)]
+    pub regenerate_token_on_elevation: bool,
+    
+    /// 中间件写回 token 最后活跃时间与 IP 的最小间隔（秒），默认 60；0 表示每次请求都写，负数表示不记录
+    /// 
+    /// IP 变化时不受间隔限制，立即写回
+    #[serde(default = "default_activity_record_interval")]
+    pub activity_record_interval: i64,
+    
+    /// 多租户：从请求中解析租户的方式，None 表示不区分租户
+    /// 
+    /// 设置后存储键按租户加前缀，见 [`crate::tenant`]
+    #[serde(default)]
+    pub tenant_resolver: Option<TenantResolver>,
+    
+    /// 各租户的 token 有效期（秒），未列出的租户使用 `timeout`
+    #[serde(default)]
+    pub tenant_timeouts: HashMap<String, i64>,
+}
+
+fn default_permission_breaker_cooldown() -> i64 {
+    30
+}
+
+fn default_login_failure_window() -> i64 {
+    900
+}
+
+fn default_login_lockout() -> i64 {
+    300
+}
+
+fn default_login_lockout_max() -> i64 {
+    86400
+}
+
+fn default_totp_issuer() -> String {
+    "sa-token".to_string()
+}
+
+fn default_activity_record_interval() -> i64 {
+    60
+}
+
+fn default_remember_me_cookie() -> String {
+    "satoken-remember".to_string()
 }
 
 impl Default for SaTokenConfig {
@@ -96,16 +362,50 @@ impl Default for SaTokenConfig {
             is_log: false,
             is_read_cookie: true,
             is_read_header: true,
+            is_write_header: false,
+            cookie: SaCookieConfig::default(),
             is_read_body: false,
             token_prefix: None,
             jwt_secret_key: None,
             jwt_algorithm: Some("HS256".to_string()),
+            jwt_private_key: None,
+            jwt_public_key: None,
+            jwt_key_ring: None,
+            jwt_encryption_key: None,
+            jwt_encryption_algorithm: None,
+            jwt_claims_mapper: None,
+            jwt_mode: None,
             jwt_issuer: None,
             jwt_audience: None,
             enable_nonce: false,
             nonce_timeout: -1,
             enable_refresh_token: false,
             refresh_token_timeout: 604800, // 7 天
+            indexed_attributes: Vec::new(),
+            permission_cache_timeout: 0,
+            permission_cache_max_stale: 0,
+            permission_provider_timeout_ms: 0,
+            permission_breaker_threshold: 0,
+            permission_breaker_cooldown: default_permission_breaker_cooldown(),
+            warmup_login_ids: Vec::new(),
+            mute_extract_log: false,
+            id_generator: None,
+            min_id_entropy_bits: 0,
+            bind_ip: false,
+            client_ip_header: None,
+            bind_fingerprint: false,
+            fingerprint_header: None,
+            login_max_failures: 0,
+            login_failure_window: default_login_failure_window(),
+            login_lockout: default_login_lockout(),
+            login_lockout_max: default_login_lockout_max(),
+            totp_issuer: default_totp_issuer(),
+            remember_me_timeout: 0,
+            remember_me_cookie: default_remember_me_cookie(),
+            regenerate_token_on_elevation: false,
+            activity_record_interval: default_activity_record_interval(),
+            tenant_resolver: None,
+            tenant_timeouts: HashMap::new(),
         }
     }
 }
@@ -115,17 +415,94 @@ impl SaTokenConfig {
         SaTokenConfigBuilder::default()
     }
     
+    /// 校验唯一 ID 生成器的熵与输出格式（`build()` 时自动调用）
+    pub fn validate_id_generator(&self) -> crate::error::SaTokenResult<()> {
+        let min_bits = if self.min_id_entropy_bits > 0 { self.min_id_entropy_bits } else { MIN_ID_ENTROPY_BITS };
+        match &self.id_generator {
+            Some(generator) => crate::id::validate_id_generator(generator.as_ref(), min_bits),
+            None => Ok(()),
+        }
+    }
+    
+    /// 解析请求的客户端 IP | Resolve the client IP of a request
+    /// 
+    /// 配置了 `client_ip_header` 时取该请求头的第一个地址，未配置或缺失时使用适配器提供的对端地址
+    /// Takes the first address of `client_ip_header`, falling back to the peer address from the adapter
+    pub fn client_ip(&self, request: &impl SaRequest) -> Option<String> {
+        self.resolve_client_ip(|name| request.get_header(name), || request.get_client_ip())
+    }
+    
+    /// 从请求头与对端地址解析客户端 IP（无法构造请求适配器时使用）
+    /// Resolve the client IP from a header lookup and the peer address (for when no request adapter is at hand)
+    /// 
+    /// # 参数 | Parameters
+    /// * `header` - 按名称读取请求头 | Reads a header by name
+    /// * `peer` - 连接的对端地址 | Peer address of the connection
+    pub fn resolve_client_ip(
+        &self,
+        header: impl FnOnce(&str) -> Option<String>,
+        peer: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        self.client_ip_header.as_deref()
+            .and_then(header)
+            .and_then(|v| v.split(',').next().map(|ip| ip.trim().to_string()))
+            .filter(|ip| !ip.is_empty())
+            .or_else(peer)
+    }
+    
+    /// 计算请求的设备指纹，未开启 `bind_fingerprint` 时返回 None
+    /// Compute the device fingerprint of a request, None when `bind_fingerprint` is off
+    pub fn fingerprint(&self, request: &impl SaRequest) -> Option<String> {
+        self.resolve_fingerprint(|name| request.get_header(name))
+    }
+    
+    /// 按请求头计算设备指纹（无法构造请求适配器时使用）
+    /// Compute the device fingerprint from a header lookup (for when no request adapter is at hand)
+    /// 
+    /// 指纹为 `SHA-256(User-Agent + "\n" + fingerprint_header)` 的十六进制
+    /// The fingerprint is the hex of `SHA-256(User-Agent + "\n" + fingerprint_header)`
+    pub fn resolve_fingerprint(&self, header: impl Fn(&str) -> Option<String>) -> Option<String> {
+        use sha2::{Digest, Sha256};
+        if !self.bind_fingerprint {
+            return None;
+        }
+        let user_agent = header("user-agent").unwrap_or_default();
+        let custom = self.fingerprint_header.as_deref().and_then(&header).unwrap_or_default();
+        Some(hex::encode(Sha256::digest(format!("{}\n{}", user_agent, custom))))
+    }
+    
+    /// 解析请求所属的租户，未设置 `tenant_resolver` 时返回 None
+    /// Resolve the tenant of a request, None when `tenant_resolver` is not set
+    pub fn tenant(&self, request: &impl SaRequest) -> Option<String> {
+        self.resolve_tenant(|name| request.get_header(name))
+    }
+    
+    /// 按请求头解析租户（无法构造请求适配器时使用）
+    /// Resolve the tenant from a header lookup (for when no request adapter is at hand)
+    pub fn resolve_tenant(&self, header: impl Fn(&str) -> Option<String>) -> Option<String> {
+        self.tenant_resolver.as_ref().and_then(|resolver| resolver.resolve(header))
+    }
+    
+    /// 当前租户的 token 有效期（秒），不在租户中或租户未单独配置时为 `timeout`
+    /// Token timeout of the current tenant in seconds, `timeout` outside of a tenant or when it has no override
+    pub fn effective_timeout(&self) -> i64 {
+        crate::tenant::current()
+            .and_then(|tenant| self.tenant_timeouts.get(&tenant).copied())
+            .unwrap_or(self.timeout)
+    }
+    
     pub fn timeout_duration(&self) -> Option<Duration> {
-        if self.timeout < 0 {
+        let timeout = self.effective_timeout();
+        if timeout < 0 {
             None
         } else {
-            Some(Duration::from_secs(self.timeout as u64))
+            Some(Duration::from_secs(timeout as u64))
         }
     }
 }
 
 /// Token 风格 | Token Style
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TokenStyle {
     /// UUID 风格 | UUID style
     Uuid,
@@ -145,24 +522,48 @@ pub enum TokenStyle {
     Timestamp,
     /// Tik 风格（短小精悍的8位字符）| Tik style (short 8-character token)
     Tik,
+    /// 自定义生成器（不参与序列化）| Custom generator (not serialized)
+    #[serde(skip)]
+    Custom(Arc<dyn TokenGenerator>),
+}
+
+/// JWT 集成模式 | JWT Integration Mode
+/// 
+/// 与 Java sa-token-jwt 插件的三种模式对应，均需配置 JWT 密钥（`jwt_secret_key`、PEM 密钥或 `jwt_key_ring`）。
+/// Mirrors the three styles of the Java sa-token-jwt plugin; each needs JWT keys
+/// (`jwt_secret_key`, PEM keys or `jwt_key_ring`).
+/// 
+/// | 模式 Mode | 存储 Storage | 吊销 Revocation | 每个请求 Per request |
+/// |-----------|-------------|-----------------|---------------------|
+/// | `Simple`    | token 信息 token info | 全部功能 full | 读取 token 信息 read token info |
+/// | `Mixin`     | 仅吊销标记 revocation markers only | 登出、踢人 logout, kick-out | 验证签名 + 查吊销标记 signature + revocation lookup |
+/// | `Stateless` | 无 none | 不支持 none | 仅验证签名 signature only |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JwtMode {
+    /// JWT 仅作为 token 风格，存储仍是唯一依据，所有功能可用
+    /// JWT is just the token style; storage stays authoritative and every feature works
+    Simple,
+    /// 登录数据放在 JWT 声明中，存储只保存登出与踢人产生的吊销标记
+    /// Login data lives in the JWT claims; storage only keeps revocation markers from logout and kick-out
+    Mixin,
+    /// 完全无状态，校验不访问存储，token 在过期前无法吊销
+    /// Fully stateless; validation never touches storage and tokens cannot be revoked before they expire
+    Stateless,
 }
 
 /// 配置构建器
+#[derive(Default)]
 pub struct SaTokenConfigBuilder {
     config: SaTokenConfig,
     storage: Option<Arc<dyn SaStorage>>,
     listeners: Vec<Arc<dyn SaTokenListener>>,
+    permission_provider: Option<Arc<dyn PermissionProvider>>,
+    role_hierarchy: Option<RoleHierarchy>,
+    schema_migrators: Vec<Arc<dyn SchemaMigrator>>,
+    response_envelope: Option<ResponseEnvelope>,
+    error_renderer: Option<Arc<dyn ErrorRenderer>>,
 }
 
-impl Default for SaTokenConfigBuilder {
-    fn default() -> Self {
-        Self {
-            config: SaTokenConfig::default(),
-            storage: None,
-            listeners: Vec::new(),
-        }
-    }
-}
 
 impl SaTokenConfigBuilder {
     pub fn token_name(mut self, name: impl Into<String>) -> Self {
@@ -217,6 +618,48 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置 JWT 签名私钥 PEM（RS*/ES* 算法）
+    pub fn jwt_private_key(mut self, pem: impl Into<String>) -> Self {
+        self.config.jwt_private_key = Some(pem.into());
+        self
+    }
+    
+    /// 设置 JWT 验证公钥 PEM（RS*/ES* 算法）
+    pub fn jwt_public_key(mut self, pem: impl Into<String>) -> Self {
+        self.config.jwt_public_key = Some(pem.into());
+        self
+    }
+    
+    /// 设置 JWT 签名密钥环（支持密钥轮换与 JWKS）
+    pub fn jwt_key_ring(mut self, key_ring: crate::token::JwksKeyRing) -> Self {
+        self.config.jwt_key_ring = Some(key_ring);
+        self
+    }
+    
+    /// 设置 JWE 加密密钥（对称算法为 Base64 编码的 32 字节，非对称算法为 PEM 密钥）
+    pub fn jwt_encryption_key(mut self, key: impl Into<String>) -> Self {
+        self.config.jwt_encryption_key = Some(key.into());
+        self
+    }
+    
+    /// 设置 JWE 密钥管理算法（"dir"、"A256KW"、"RSA-OAEP-256" 或 "ECDH-ES+A256KW"）
+    pub fn jwt_encryption_algorithm(mut self, algorithm: impl Into<String>) -> Self {
+        self.config.jwt_encryption_algorithm = Some(algorithm.into());
+        self
+    }
+    
+    /// 设置 `TokenInfo` 与 JWT 声明的映射
+    pub fn jwt_claims_mapper(mut self, mapper: Arc<dyn crate::token::ClaimsMapper>) -> Self {
+        self.config.jwt_claims_mapper = Some(mapper);
+        self
+    }
+    
+    /// 设置 JWT 集成模式
+    pub fn jwt_mode(mut self, mode: JwtMode) -> Self {
+        self.config.jwt_mode = Some(mode);
+        self
+    }
+    
     /// 设置 JWT 签发者
     pub fn jwt_issuer(mut self, issuer: impl Into<String>) -> Self {
         self.config.jwt_issuer = Some(issuer.into());
@@ -253,6 +696,160 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置需要建立二级索引的会话属性
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let config = SaTokenConfig::builder()
+    ///     .indexed_attributes(vec!["tenant_id".to_string(), "department".to_string()])
+    ///     .build_config();
+    /// ```
+    pub fn indexed_attributes(mut self, attributes: Vec<String>) -> Self {
+        self.config.indexed_attributes = attributes;
+        self
+    }
+    
+    /// 设置权限与角色列表的缓存时长（秒），0 表示不缓存
+    pub fn permission_cache_timeout(mut self, timeout: i64) -> Self {
+        self.config.permission_cache_timeout = timeout;
+        self
+    }
+    
+    /// 设置缓存过期后仍可使用旧数据的时长（秒）
+    pub fn permission_cache_max_stale(mut self, seconds: i64) -> Self {
+        self.config.permission_cache_max_stale = seconds;
+        self
+    }
+    
+    /// 设置单次调用 `PermissionProvider` 的超时（毫秒）
+    pub fn permission_provider_timeout_ms(mut self, millis: u64) -> Self {
+        self.config.permission_provider_timeout_ms = millis;
+        self
+    }
+    
+    /// 设置 `PermissionProvider` 的熔断阈值与熔断持续时间（秒）
+    pub fn permission_breaker(mut self, threshold: u32, cooldown: i64) -> Self {
+        self.config.permission_breaker_threshold = threshold;
+        self.config.permission_breaker_cooldown = cooldown;
+        self
+    }
+    
+    /// 设置启动预热时加载权限缓存的热点用户
+    pub fn warmup_login_ids(mut self, login_ids: Vec<String>) -> Self {
+        self.config.warmup_login_ids = login_ids;
+        self
+    }
+    
+    /// 设置是否关闭 token 提取日志
+    pub fn mute_extract_log(mut self, mute: bool) -> Self {
+        self.config.mute_extract_log = mute;
+        self
+    }
+    
+    /// 设置唯一 ID 生成器（SSO 票据、OAuth2 授权码、nonce 等）
+    pub fn id_generator(mut self, generator: Arc<dyn IdGenerator>) -> Self {
+        self.config.id_generator = Some(generator);
+        self
+    }
+    
+    /// 设置唯一 ID 生成器要求的最小熵（位）
+    pub fn min_id_entropy_bits(mut self, bits: u32) -> Self {
+        self.config.min_id_entropy_bits = bits;
+        self
+    }
+    
+    /// 设置是否将 token 绑定到登录时的客户端 IP
+    pub fn bind_ip(mut self, bind_ip: bool) -> Self {
+        self.config.bind_ip = bind_ip;
+        self
+    }
+    
+    /// 设置读取客户端 IP 的请求头（仅在可信代理之后使用）
+    pub fn client_ip_header(mut self, name: impl Into<String>) -> Self {
+        self.config.client_ip_header = Some(name.into());
+        self
+    }
+    
+    /// 设置是否将 token 绑定到登录时的设备指纹
+    pub fn bind_fingerprint(mut self, bind_fingerprint: bool) -> Self {
+        self.config.bind_fingerprint = bind_fingerprint;
+        self
+    }
+    
+    /// 设置参与设备指纹计算的自定义请求头
+    pub fn fingerprint_header(mut self, name: impl Into<String>) -> Self {
+        self.config.fingerprint_header = Some(name.into());
+        self
+    }
+    
+    /// 设置登录失败锁定：窗口（秒）内失败 `max_failures` 次后锁定 `lockout` 秒
+    pub fn login_lockout(mut self, max_failures: u32, window: i64, lockout: i64) -> Self {
+        self.config.login_max_failures = max_failures;
+        self.config.login_failure_window = window;
+        self.config.login_lockout = lockout;
+        self
+    }
+    
+    /// 设置指数退避后的锁定时长上限（秒）
+    pub fn login_lockout_max(mut self, seconds: i64) -> Self {
+        self.config.login_lockout_max = seconds;
+        self
+    }
+    
+    /// 设置 TOTP 双因素认证的发行方（显示在验证器应用中）
+    pub fn totp_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.config.totp_issuer = issuer.into();
+        self
+    }
+    
+    /// 开启“记住我”，设置 remember-me token 有效期（秒）
+    pub fn remember_me(mut self, timeout: i64) -> Self {
+        self.config.remember_me_timeout = timeout;
+        self
+    }
+    
+    /// 设置保存 remember-me token 的 cookie 名称
+    pub fn remember_me_cookie(mut self, name: impl Into<String>) -> Self {
+        self.config.remember_me_cookie = name.into();
+        self
+    }
+    
+    /// 设置登录后是否把 token 写入响应头
+    pub fn is_write_header(mut self, enable: bool) -> Self {
+        self.config.is_write_header = enable;
+        self
+    }
+    
+    /// 设置 cookie 属性
+    pub fn cookie(mut self, cookie: SaCookieConfig) -> Self {
+        self.config.cookie = cookie;
+        self
+    }
+    
+    /// 设置权限提升后是否自动重新生成 token
+    pub fn regenerate_token_on_elevation(mut self, enable: bool) -> Self {
+        self.config.regenerate_token_on_elevation = enable;
+        self
+    }
+    
+    /// 设置写回 token 最后活跃时间与 IP 的最小间隔（秒），负数表示不记录
+    pub fn activity_record_interval(mut self, seconds: i64) -> Self {
+        self.config.activity_record_interval = seconds;
+        self
+    }
+    
+    /// 设置租户解析方式，开启多租户隔离
+    pub fn tenant_resolver(mut self, resolver: TenantResolver) -> Self {
+        self.config.tenant_resolver = Some(resolver);
+        self
+    }
+    
+    /// 设置某个租户的 token 有效期（秒）
+    pub fn tenant_timeout(mut self, tenant: impl Into<String>, timeout: i64) -> Self {
+        self.config.tenant_timeouts.insert(tenant.into(), timeout);
+        self
+    }
+    
     /// 设置存储方式
     pub fn storage(mut self, storage: Arc<dyn SaStorage>) -> Self {
         self.storage = Some(storage);
@@ -281,6 +878,61 @@ impl SaTokenConfigBuilder {
         self
     }
     
+    /// 设置权限数据提供者
+    /// 
+    /// 构建后 `StpUtil::has_permission` / `has_role` 会自动从该提供者加载权限与角色
+    pub fn permission_provider(mut self, provider: Arc<dyn PermissionProvider>) -> Self {
+        self.permission_provider = Some(provider);
+        self
+    }
+    
+    /// 设置角色层级，上级角色在 `has_role` 中同样满足
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// SaTokenConfig::builder()
+    ///     .storage(Arc::new(MemoryStorage::new()))
+    ///     .role_hierarchy(RoleHierarchy::parse("admin > manager > user").unwrap())
+    ///     .build();
+    /// ```
+    pub fn role_hierarchy(mut self, hierarchy: RoleHierarchy) -> Self {
+        self.role_hierarchy = Some(hierarchy);
+        self
+    }
+    
+    /// 注册数据迁移器，由 `SaTokenManager::ensure_schema` 在启动时执行
+    pub fn schema_migrator(mut self, migrator: Arc<dyn SchemaMigrator>) -> Self {
+        self.schema_migrators.push(migrator);
+        self
+    }
+    
+    /// 设置内置响应的 JSON 信封（字段名等），构建时设为全局
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // {"status": 401, "msg": "..."}
+    /// SaTokenConfig::builder()
+    ///     .response_envelope(ResponseEnvelope::new().code_field("status").message_field("msg"))
+    /// ```
+    pub fn response_envelope(mut self, envelope: ResponseEnvelope) -> Self {
+        self.response_envelope = Some(envelope);
+        self
+    }
+    
+    /// 设置拒绝响应渲染器，保存在构建出的 `SaTokenManager` 中，由使用它的插件状态传给中间件与提取器
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// SaTokenConfig::builder()
+    ///     .error_renderer(Arc::new(|e: &SaTokenError| {
+    ///         (e.http_status(), vec![], json!({ "success": false, "reason": e.to_string() }).to_string())
+    ///     }))
+    /// ```
+    pub fn error_renderer(mut self, renderer: Arc<dyn ErrorRenderer>) -> Self {
+        self.error_renderer = Some(renderer);
+        self
+    }
+    
     /// 构建 SaTokenManager（需要先设置 storage）
     /// 
     /// 自动完成以下操作：
@@ -312,6 +964,9 @@ impl SaTokenConfigBuilder {
     /// ```
     pub fn build(self) -> crate::SaTokenManager {
         let storage = self.storage.expect("Storage must be set before building SaTokenManager. Use .storage() method.");
+        if let Err(e) = self.config.validate_id_generator() {
+            panic!("Invalid IdGenerator: {}", e);
+        }
         let manager = crate::SaTokenManager::new(storage, self.config);
         
         // 同步注册所有监听器
@@ -323,6 +978,26 @@ impl SaTokenConfigBuilder {
             }
         }
         
+        if let Some(provider) = self.permission_provider {
+            manager.set_permission_provider(provider);
+        }
+        
+        for migrator in self.schema_migrators {
+            manager.register_schema_migrator(migrator);
+        }
+        
+        if let Some(hierarchy) = self.role_hierarchy {
+            manager.set_role_hierarchy(Some(hierarchy));
+        }
+        
+        if let Some(envelope) = self.response_envelope {
+            envelope.install();
+        }
+        
+        if let Some(renderer) = self.error_renderer {
+            manager.set_error_renderer(renderer);
+        }
+        
         // 自动初始化 StpUtil
         // Auto-initialize StpUtil
         crate::StpUtil::init_manager(manager.clone());
@@ -0,0 +1,290 @@
+// Author: 金书记
+//
+//! OAuth2 授权同意持久化 | OAuth2 Consent Persistence
+//!
+//! 记录用户已经授予每个 client_id 的 scope。再次授权时若请求的 scope 都已授予，
+//! 可以跳过授权确认页；用户可以查看并撤销自己的授权。
+//! Records which scopes a user has granted to each client_id. When every requested scope
+//! has been granted before, the consent screen can be skipped; users can list and revoke
+//! their grants.
+//!
+//! ```text
+//! GET /authorize?client_id=app&scope=read write
+//!   └─→ ConsentStore.requires_consent(user, app, [read, write])
+//!         ├─→ false → 直接签发授权码 | issue the code directly
+//!         └─→ true  → 展示确认页 | show the consent page
+//!                       └─→ 用户同意 | user accepts → ConsentStore.grant(user, app, [read, write])
+//!
+//! oauth2:consent:{user_id}:{client_id}   授权记录 | Grant record
+//! oauth2:consent_index:{user_id}         用户已授权的 client_id 集合 | Set of client_ids the user granted
+//! ```
+//!
+//! 键中的 `user_id` 与 `client_id` 会转义 `%` 与 `:`，不同的 ID 组合不会落到同一个键上。
+//! `%` and `:` in `user_id` and `client_id` are escaped, so no two ID pairs share a key.
+//!
+//! 撤销授权只影响之后的授权请求，已签发的令牌需通过 `OAuth2Manager::revoke` 撤销。
+//! Revoking a grant only affects later authorization requests; revoke tokens already
+//! issued with `OAuth2Manager::revoke`.
+//!
+//! `OffSessionManager` 的离线授权也保存在这里，用户在一个地方即可查看和撤销全部授权。
+//! `OffSessionManager` keeps its off-session consents here too, so users see and revoke
+//! every grant in one place.
+
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::token::escape_key_part;
+
+/// 用户对客户端的授权 | A user's grant to a client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuth2Grant {
+    /// 授权用户 ID | Granting user ID
+    pub user_id: String,
+
+    /// 被授权的客户端 ID | Client ID the grant is for
+    pub client_id: String,
+
+    /// 已授予的 scope | Granted scopes
+    pub scopes: Vec<String>,
+
+    /// 首次授权时间 | First grant time
+    pub granted_at: DateTime<Utc>,
+
+    /// 最近一次更新时间 | Last update time
+    pub updated_at: DateTime<Utc>,
+
+    /// 授权过期时间（None 表示长期有效）| Grant expiration (None means no expiration)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl OAuth2Grant {
+    /// 是否已过期 | Whether the grant has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|t| Utc::now() > t)
+    }
+
+    /// 是否覆盖全部请求的 scope | Whether all requested scopes are covered
+    pub fn covers(&self, scopes: &[String]) -> bool {
+        scopes.iter().all(|s| self.scopes.contains(s))
+    }
+}
+
+/// 授权同意存储 | Consent store
+///
+/// 基于 `SaStorage` 持久化用户对客户端的授权，授权默认不过期，`replace` 可设置有效期。
+/// Persists users' grants to clients in `SaStorage`; grants do not expire unless `replace` sets a lifetime.
+#[derive(Clone)]
+pub struct ConsentStore {
+    storage: Arc<dyn SaStorage>,
+}
+
+impl ConsentStore {
+    /// 创建授权同意存储 | Create a consent store
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// 获取未过期的授权记录 | Get a grant that has not expired
+    ///
+    /// # 参数 | Parameters
+    /// * `user_id` - 用户 ID | User ID
+    /// * `client_id` - 客户端 ID | Client ID
+    pub async fn get(&self, user_id: &str, client_id: &str) -> SaTokenResult<Option<OAuth2Grant>> {
+        let value = self.storage.get(&Self::grant_key(user_id, client_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let grant: Option<OAuth2Grant> = value
+            .map(|v| serde_json::from_str(&v).map_err(SaTokenError::SerializationError))
+            .transpose()?;
+        Ok(grant.filter(|grant| !grant.is_expired()))
+    }
+
+    /// 是否需要展示授权确认页 | Whether the consent screen must be shown
+    ///
+    /// 请求的 scope 中有任何一个尚未授予时返回 `true`
+    /// Returns `true` when any requested scope has not been granted yet
+    ///
+    /// # 参数 | Parameters
+    /// * `user_id` - 用户 ID | User ID
+    /// * `client_id` - 客户端 ID | Client ID
+    /// * `scopes` - 本次请求的 scope | Scopes of this request
+    pub async fn requires_consent(&self, user_id: &str, client_id: &str, scopes: &[String]) -> SaTokenResult<bool> {
+        Ok(!self.get(user_id, client_id).await?.is_some_and(|grant| grant.covers(scopes)))
+    }
+
+    /// 记录用户同意的 scope，与已有授权合并 | Record consented scopes, merged into the existing grant
+    ///
+    /// # 参数 | Parameters
+    /// * `user_id` - 用户 ID | User ID
+    /// * `client_id` - 客户端 ID | Client ID
+    /// * `scopes` - 用户同意的 scope | Scopes the user consented to
+    pub async fn grant(&self, user_id: &str, client_id: &str, scopes: &[String]) -> SaTokenResult<OAuth2Grant> {
+        let now = Utc::now();
+        let grant = match self.get(user_id, client_id).await? {
+            Some(mut grant) => {
+                for scope in scopes {
+                    if !grant.scopes.contains(scope) {
+                        grant.scopes.push(scope.clone());
+                    }
+                }
+                grant.updated_at = now;
+                grant
+            }
+            None => OAuth2Grant {
+                user_id: user_id.to_string(),
+                client_id: client_id.to_string(),
+                scopes: scopes.to_vec(),
+                granted_at: now,
+                updated_at: now,
+                expires_at: None,
+            },
+        };
+        self.save(grant).await
+    }
+
+    /// 保存授权，覆盖已授予的 scope 并设置有效期 | Save a grant, replacing the granted scopes and setting its lifetime
+    ///
+    /// # 参数 | Parameters
+    /// * `user_id` - 用户 ID | User ID
+    /// * `client_id` - 客户端 ID | Client ID
+    /// * `scopes` - 授予的全部 scope | Every granted scope
+    /// * `ttl` - 有效期（秒），None 表示长期有效 | Lifetime in seconds, None for no expiration
+    pub async fn replace(&self, user_id: &str, client_id: &str, scopes: Vec<String>, ttl: Option<i64>) -> SaTokenResult<OAuth2Grant> {
+        let now = Utc::now();
+        let granted_at = self.get(user_id, client_id).await?.map_or(now, |grant| grant.granted_at);
+        self.save(OAuth2Grant {
+            user_id: user_id.to_string(),
+            client_id: client_id.to_string(),
+            scopes,
+            granted_at,
+            updated_at: now,
+            expires_at: ttl.map(|secs| now + Duration::seconds(secs)),
+        }).await
+    }
+
+    async fn save(&self, grant: OAuth2Grant) -> SaTokenResult<OAuth2Grant> {
+        let value = serde_json::to_string(&grant).map_err(SaTokenError::SerializationError)?;
+        let ttl = grant.expires_at
+            .map(|t| std::time::Duration::from_secs((t - Utc::now()).num_seconds().max(1) as u64));
+        self.storage.set(&Self::grant_key(&grant.user_id, &grant.client_id), &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.sadd(&Self::user_key(&grant.user_id), &grant.client_id).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(grant)
+    }
+
+    /// 列出用户的所有授权 | List all grants of a user
+    pub async fn list(&self, user_id: &str) -> SaTokenResult<Vec<OAuth2Grant>> {
+        let client_ids = self.storage.smembers(&Self::user_key(user_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let mut grants = Vec::new();
+        for client_id in client_ids {
+            if let Some(grant) = self.get(user_id, &client_id).await? {
+                grants.push(grant);
+            }
+        }
+        grants.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        Ok(grants)
+    }
+
+    /// 撤销用户对某个客户端的授权 | Revoke a user's grant to a client
+    pub async fn revoke(&self, user_id: &str, client_id: &str) -> SaTokenResult<()> {
+        self.storage.delete(&Self::grant_key(user_id, client_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.srem(&Self::user_key(user_id), client_id).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 撤销用户的所有授权 | Revoke all grants of a user
+    pub async fn revoke_all(&self, user_id: &str) -> SaTokenResult<()> {
+        let client_ids = self.storage.smembers(&Self::user_key(user_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        for client_id in client_ids {
+            self.revoke(user_id, &client_id).await?;
+        }
+        Ok(())
+    }
+
+    fn grant_key(user_id: &str, client_id: &str) -> String {
+        format!("oauth2:consent:{}:{}", escape_key_part(user_id), escape_key_part(client_id))
+    }
+
+    fn user_key(user_id: &str) -> String {
+        format!("oauth2:consent_index:{}", escape_key_part(user_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn scopes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_consent_store() {
+        let store = ConsentStore::new(Arc::new(MemoryStorage::new()));
+
+        assert!(store.requires_consent("alice", "app", &scopes(&["read"])).await.unwrap());
+        store.grant("alice", "app", &scopes(&["read"])).await.unwrap();
+        assert!(!store.requires_consent("alice", "app", &scopes(&["read"])).await.unwrap());
+
+        // A new scope needs consent again; granting it merges with the existing grant
+        assert!(store.requires_consent("alice", "app", &scopes(&["read", "write"])).await.unwrap());
+        let grant = store.grant("alice", "app", &scopes(&["write"])).await.unwrap();
+        assert_eq!(grant.scopes, scopes(&["read", "write"]));
+        assert!(grant.updated_at >= grant.granted_at);
+
+        store.grant("alice", "other", &scopes(&["read"])).await.unwrap();
+        let grants = store.list("alice").await.unwrap();
+        assert_eq!(grants.iter().map(|g| g.client_id.as_str()).collect::<Vec<_>>(), ["app", "other"]);
+        assert!(store.list("bob").await.unwrap().is_empty());
+
+        store.revoke("alice", "app").await.unwrap();
+        assert!(store.requires_consent("alice", "app", &scopes(&["read"])).await.unwrap());
+        assert_eq!(store.list("alice").await.unwrap().len(), 1);
+
+        store.revoke_all("alice").await.unwrap();
+        assert!(store.list("alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_consent_keys_do_not_collide() {
+        let store = ConsentStore::new(Arc::new(MemoryStorage::new()));
+
+        // 用户 "user" 对客户端 "bob" 的授权不会覆盖用户 "bob" 的索引
+        // User "user"'s grant to client "bob" does not overwrite user "bob"'s index
+        store.grant("bob", "app", &scopes(&["read"])).await.unwrap();
+        store.grant("user", "bob", &scopes(&["read"])).await.unwrap();
+        assert_eq!(store.list("bob").await.unwrap().len(), 1);
+
+        // 含 `:` 的 ID 不会与其他组合混淆 | IDs containing `:` are not confused with other pairs
+        store.grant("a:b", "c", &scopes(&["read"])).await.unwrap();
+        assert!(store.get("a", "b:c").await.unwrap().is_none());
+        assert_eq!(store.list("a:b").await.unwrap()[0].client_id, "c");
+    }
+
+    #[tokio::test]
+    async fn test_replace_with_ttl() {
+        let store = ConsentStore::new(Arc::new(MemoryStorage::new()));
+        let first = store.grant("alice", "sync", &scopes(&["read", "write"])).await.unwrap();
+
+        let grant = store.replace("alice", "sync", scopes(&["read"]), Some(3600)).await.unwrap();
+        assert_eq!(grant.scopes, scopes(&["read"]));
+        assert_eq!(grant.granted_at, first.granted_at);
+        assert!(grant.expires_at.is_some());
+
+        // 合并新 scope 时保留有效期 | Merging a new scope keeps the lifetime
+        let merged = store.grant("alice", "sync", &scopes(&["write"])).await.unwrap();
+        assert_eq!(merged.expires_at, grant.expires_at);
+
+        // 过期的授权视为不存在 | Expired grants count as absent
+        store.replace("alice", "sync", scopes(&["read"]), Some(-1)).await.unwrap();
+        assert!(store.get("alice", "sync").await.unwrap().is_none());
+        assert!(store.list("alice").await.unwrap().is_empty());
+    }
+}
@@ -7,10 +7,18 @@
 
 use std::sync::Arc;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
 use crate::token::{TokenInfo, TokenValue};
 
 thread_local! {
-    static CONTEXT: RefCell<Option<SaTokenContext>> = RefCell::new(None);
+    static CONTEXT: RefCell<Option<SaTokenContext>> = const { RefCell::new(None) };
+}
+
+tokio::task_local! {
+    /// 身份切换后的上下文，只对切换所在的任务可见
+    /// Context after an identity switch, visible only to the task that switched
+    static SWITCHED: SaTokenContext;
 }
 
 /// sa-token 上下文 | sa-token Context
@@ -22,6 +30,11 @@ thread_local! {
 /// - `token`: 当前请求的 token | Current request's token
 /// - `token_info`: Token 详细信息 | Token detailed information
 /// - `login_id`: 登录用户 ID | Logged-in user ID
+/// - `switched_from`: 身份切换前的登录 ID | Login ID before identity switching
+/// - `typed_tokens`: 各账号体系的 token | Tokens of each account system
+/// - `client_ip`: 客户端 IP | Client IP
+/// - `fingerprint`: 设备指纹 | Device fingerprint
+/// - `tenant`: 所属租户 | Tenant of the request
 #[derive(Debug, Clone)]
 pub struct SaTokenContext {
     /// 当前请求的 token | Current request's token
@@ -32,6 +45,26 @@ pub struct SaTokenContext {
     
     /// 登录 ID | Login ID
     pub login_id: Option<String>,
+    
+    /// 身份切换前的原始登录 ID，未切换时为 None
+    /// Original login ID before identity switching, None when not switched
+    pub switched_from: Option<String>,
+    
+    /// 账号类型 → 该账号体系的 token（见 `StpLogic`）
+    /// Account type → token of that account system (see `StpLogic`)
+    pub typed_tokens: HashMap<String, TokenValue>,
+    
+    /// 客户端 IP，由插件的层设置，登录时用于 `bind_ip`
+    /// Client IP, set by the plugin layer and used by `bind_ip` at login
+    pub client_ip: Option<String>,
+    
+    /// 设备指纹，由插件的层设置，登录时用于 `bind_fingerprint`
+    /// Device fingerprint, set by the plugin layer and used by `bind_fingerprint` at login
+    pub fingerprint: Option<String>,
+    
+    /// 请求所属的租户，创建上下文时取自 [`crate::tenant::current`]
+    /// Tenant of the request, taken from [`crate::tenant::current`] when the context is created
+    pub tenant: Option<String>,
 }
 
 impl SaTokenContext {
@@ -40,6 +73,11 @@ impl SaTokenContext {
             token: None,
             token_info: None,
             login_id: None,
+            switched_from: None,
+            typed_tokens: HashMap::new(),
+            client_ip: None,
+            fingerprint: None,
+            tenant: crate::tenant::current(),
         }
     }
     
@@ -56,11 +94,14 @@ impl SaTokenContext {
     /// 获取当前上下文 | Get Current Context
     /// 
     /// # 返回 | Returns
-    /// 当前线程的上下文，如果不存在则返回 None
-    /// Current thread's context, or None if not exists
+    /// 身份切换中返回切换后的上下文，否则返回当前线程的上下文，不存在时返回 None
+    /// The switched context while inside [`Self::switch_scope`], otherwise the current thread's
+    /// context, or None if not exists
     pub fn get_current() -> Option<SaTokenContext> {
-        CONTEXT.with(|c| {
-            c.borrow().clone()
+        SWITCHED.try_with(Clone::clone).ok().or_else(|| {
+            CONTEXT.with(|c| {
+                c.borrow().clone()
+            })
         })
     }
     
@@ -73,6 +114,44 @@ impl SaTokenContext {
             *c.borrow_mut() = None;
         });
     }
+    
+    /// 当前上下文是否处于身份切换中 | Whether the current context is switched
+    pub fn is_switched(&self) -> bool {
+        self.switched_from.is_some()
+    }
+    
+    /// 获取指定账号类型的 token | Get the token of an account type
+    /// 
+    /// 未单独读取时，主 token 的账号类型相同也可使用
+    /// Falls back to the main token when its account type matches
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_type`: 账号类型 | Account type
+    pub fn token_for_type(&self, login_type: &str) -> Option<TokenValue> {
+        self.typed_tokens.get(login_type).cloned().or_else(|| {
+            self.token_info.as_ref()
+                .filter(|info| info.account_type() == login_type)
+                .and(self.token.clone())
+        })
+    }
+    
+    /// 以切换后的登录 ID 执行 future，结束后自动恢复 | Run a future under a switched login ID, restored afterwards
+    /// 
+    /// 切换后的上下文保存在 `task_local` 中，同一工作线程上的其他任务与 future 在其他线程恢复执行时都不受影响，
+    /// 支持嵌套切换
+    /// The switched context lives in a `task_local`, so other tasks polled on the same worker thread
+    /// are unaffected and the switch follows the future across threads; nesting is supported
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 要切换到的登录 ID | Login ID to switch to
+    /// - `future`: 切换期间执行的 future | Future run while switched
+    pub async fn switch_scope<F: Future>(login_id: impl Into<String>, future: F) -> F::Output {
+        let mut ctx = Self::get_current().unwrap_or_default();
+        // 嵌套切换时保留最初的登录 ID
+        ctx.switched_from = ctx.switched_from.take().or_else(|| ctx.login_id.clone());
+        ctx.login_id = Some(login_id.into());
+        SWITCHED.scope(ctx, future).await
+    }
 }
 
 impl Default for SaTokenContext {
@@ -80,3 +159,36 @@ impl Default for SaTokenContext {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_switch_scope_restores_context() {
+        let mut ctx = SaTokenContext::new();
+        ctx.login_id = Some("admin".to_string());
+        SaTokenContext::set_current(ctx);
+
+        SaTokenContext::switch_scope("10044", async {
+            SaTokenContext::switch_scope("10045", async {
+                let current = SaTokenContext::get_current().unwrap();
+                assert_eq!(current.login_id.as_deref(), Some("10045"));
+                assert_eq!(current.switched_from.as_deref(), Some("admin"));
+            }).await;
+            let current = SaTokenContext::get_current().unwrap();
+            assert_eq!(current.login_id.as_deref(), Some("10044"));
+
+            // 同一线程上的其他任务看不到切换后的身份 | Other tasks on the same thread do not see the switch
+            let other = tokio::spawn(async { SaTokenContext::get_current() }).await.unwrap();
+            assert_eq!(other.and_then(|ctx| ctx.login_id).as_deref(), Some("admin"));
+            tokio::task::yield_now().await;
+            assert_eq!(SaTokenContext::get_current().unwrap().login_id.as_deref(), Some("10044"));
+        }).await;
+
+        let current = SaTokenContext::get_current().unwrap();
+        assert_eq!(current.login_id.as_deref(), Some("admin"));
+        assert!(!current.is_switched());
+        SaTokenContext::clear();
+    }
+}
@@ -0,0 +1,187 @@
+// Author: 金书记
+//
+//! 防 CSRF 令牌 | Anti-CSRF Tokens
+//!
+//! token 通过 Cookie 下发时，浏览器会在跨站请求中自动携带它。
+//! 每个会话另外持有一个 CSRF 令牌，页面通过表单字段或 `<meta>` 拿到它，
+//! 在修改状态的请求中放进 `X-CSRF-Token` 请求头；其他站点读不到这个值，伪造的请求因此被拒绝。
+//! When the token travels in a cookie, browsers attach it to cross-site requests as well.
+//! Every session also holds a CSRF token; pages get it through a form field or `<meta>` and send it
+//! in the `X-CSRF-Token` header of state-changing requests. Other sites cannot read it, so forged requests fail.
+//!
+//! ```text
+//! 渲染页面 | Render a page: CsrfManager::get_token(&session) → meta_tag / hidden_input
+//!
+//! 请求 | Request (POST / PUT / PATCH / DELETE，且携带会话 Cookie | with the session cookie)
+//!   └─→ CsrfManager::verify(&session, X-CSRF-Token)
+//!         ├─→ 一致 → 放行 | matches → pass
+//!         └─→ 缺失或不一致 → InvalidCsrfToken (403) | missing or different → InvalidCsrfToken (403)
+//! ```
+
+use std::sync::Arc;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::page::escape_html;
+use crate::token::TokenValue;
+
+/// 默认的 CSRF 请求头 | Default CSRF header
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// 表单中 CSRF 字段的名称 | Name of the CSRF form field
+pub const CSRF_FIELD: &str = "_csrf";
+
+/// 防 CSRF 令牌管理器 | Anti-CSRF token manager
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let csrf = CsrfManager::new(storage.clone()).cookie_name("sa-token");
+///
+/// // 渲染页面 | Render a page
+/// let meta = csrf.meta_tag(&csrf.get_token(&session).await?);
+///
+/// // 处理请求 | Handle a request
+/// csrf.verify(&session, header_value.as_deref()).await?;
+/// ```
+#[derive(Clone)]
+pub struct CsrfManager {
+    storage: Arc<dyn SaStorage>,
+    timeout: i64,
+    header: String,
+    cookie_name: String,
+}
+
+impl CsrfManager {
+    /// 创建管理器（令牌有效期 1 天）| Create a manager (tokens last one day)
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            storage,
+            timeout: 86400,
+            header: CSRF_HEADER.to_string(),
+            cookie_name: "sa-token".to_string(),
+        }
+    }
+
+    /// CSRF 令牌有效期（秒），应不短于会话有效期 | CSRF token lifetime in seconds, should not be shorter than the session's
+    pub fn timeout(mut self, timeout: i64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置请求头名称 | Set the header name
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// 设置会话 Cookie 名称（即 `token_name`）| Set the session cookie name (the `token_name`)
+    pub fn cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.cookie_name = cookie_name.into();
+        self
+    }
+
+    /// 请求头名称 | Header name
+    pub fn header_name(&self) -> &str {
+        &self.header
+    }
+
+    /// 会话 Cookie 名称 | Session cookie name
+    pub fn session_cookie(&self) -> &str {
+        &self.cookie_name
+    }
+
+    /// 获取会话的 CSRF 令牌，不存在时生成 | Get the CSRF token of a session, generating it when missing
+    pub async fn get_token(&self, session: &TokenValue) -> SaTokenResult<String> {
+        let stored = self.storage.get(&Self::key(session)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        match stored {
+            Some(token) => Ok(token),
+            None => self.rotate(session).await,
+        }
+    }
+
+    /// 为会话生成新的 CSRF 令牌（如登录或提权后）| Issue a new CSRF token for a session (e.g. after login or elevation)
+    pub async fn rotate(&self, session: &TokenValue) -> SaTokenResult<String> {
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let ttl = (self.timeout > 0).then(|| std::time::Duration::from_secs(self.timeout as u64));
+        self.storage.set(&Self::key(session), &token, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(token)
+    }
+
+    /// 删除会话的 CSRF 令牌（登出时调用）| Delete the CSRF token of a session (call on logout)
+    pub async fn remove(&self, session: &TokenValue) -> SaTokenResult<()> {
+        self.storage.delete(&Self::key(session)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 校验请求携带的 CSRF 令牌 | Verify the CSRF token sent with a request
+    ///
+    /// # 参数 | Parameters
+    /// * `session` - 会话 token | Session token
+    /// * `csrf` - 请求头中的值 | Value of the header
+    pub async fn verify(&self, session: &TokenValue, csrf: Option<&str>) -> SaTokenResult<()> {
+        let csrf = csrf.map(str::trim).filter(|c| !c.is_empty()).ok_or(SaTokenError::InvalidCsrfToken)?;
+        let stored = self.storage.get(&Self::key(session)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::InvalidCsrfToken)?;
+        let matches = stored.len() == csrf.len()
+            && stored.bytes().zip(csrf.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+        if matches {
+            Ok(())
+        } else {
+            Err(SaTokenError::InvalidCsrfToken)
+        }
+    }
+
+    /// 该请求方法是否需要校验（会修改状态）| Whether a method needs the check (changes state)
+    pub fn requires_check(method: &str) -> bool {
+        !matches!(method.to_ascii_uppercase().as_str(), "GET" | "HEAD" | "OPTIONS" | "TRACE")
+    }
+
+    /// 表单隐藏字段 | Hidden form field
+    pub fn hidden_input(&self, csrf: &str) -> String {
+        format!("<input type=\"hidden\" name=\"{}\" value=\"{}\">", CSRF_FIELD, escape_html(csrf))
+    }
+
+    /// `<meta>` 标签，供前端脚本读取后放入请求头 | `<meta>` tag, read by scripts and put into the header
+    pub fn meta_tag(&self, csrf: &str) -> String {
+        format!("<meta name=\"csrf-token\" content=\"{}\">", escape_html(csrf))
+    }
+
+    fn key(session: &TokenValue) -> String {
+        format!("sa:csrf:{}", session.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_csrf_tokens() {
+        let csrf = CsrfManager::new(Arc::new(MemoryStorage::new()));
+        let alice = TokenValue::new("alice-session");
+        let bob = TokenValue::new("bob-session");
+
+        let token = csrf.get_token(&alice).await.unwrap();
+        assert_eq!(csrf.get_token(&alice).await.unwrap(), token);
+        assert!(csrf.verify(&alice, Some(&token)).await.is_ok());
+
+        // 其他会话的令牌无效 | Another session's token is rejected
+        let bob_token = csrf.get_token(&bob).await.unwrap();
+        assert!(matches!(csrf.verify(&alice, Some(&bob_token)).await, Err(SaTokenError::InvalidCsrfToken)));
+        assert!(csrf.verify(&alice, None).await.is_err());
+
+        let rotated = csrf.rotate(&alice).await.unwrap();
+        assert!(csrf.verify(&alice, Some(&token)).await.is_err());
+        assert!(csrf.verify(&alice, Some(&rotated)).await.is_ok());
+
+        csrf.remove(&alice).await.unwrap();
+        assert!(csrf.verify(&alice, Some(&rotated)).await.is_err());
+
+        assert!(CsrfManager::requires_check("post"));
+        assert!(!CsrfManager::requires_check("GET"));
+        assert_eq!(csrf.meta_tag("a\"b"), "<meta name=\"csrf-token\" content=\"a&quot;b\">");
+    }
+}
@@ -0,0 +1,261 @@
+// Author: 金书记
+//
+//! 存储加密 | Storage Encryption
+//!
+//! `EncryptedStorage<S>` 包装任意 `SaStorage`，写入前用 AES-256-GCM 加密值，读取时解密，
+//! Redis 或数据库的转储中因此看不到 token 信息与会话内容。
+//! `EncryptedStorage<S>` wraps any `SaStorage` and AES-256-GCM encrypts values before writing them
+//! and decrypts them on read, so Redis or database dumps do not leak token info or session contents.
+//!
+//! ```text
+//! set("sa:token:abc", "{...}")
+//!   └─→ inner.set("sa:token:abc", "$aes256gcm$<key_id>$<base64(nonce | ciphertext)>")
+//! ```
+//!
+//! - 存储键作为附加认证数据，密文不能被挪到其他键下使用
+//!   The storage key is the additional authenticated data, so a ciphertext cannot be moved to another key
+//! - 每个值带有密钥 ID，轮换密钥后旧密钥仍可解密，`reencrypt` 把旧数据改用当前密钥
+//!   Every value carries its key ID; after a rotation old keys still decrypt, and `reencrypt` moves old data to the current key
+//! - 没有加密前缀的值原样返回，已有的明文数据可以平滑迁移
+//!   Values without the prefix are returned as they are, so existing plaintext data migrates smoothly
+//! - 计数器（`incr`/`decr`）与键名不加密；集合整体加密保存为普通值
+//!   Counters (`incr`/`decr`) and key names are not encrypted; sets are stored encrypted as plain values
+
+use std::collections::HashMap;
+use std::time::Duration;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use sa_token_adapter::storage::{SaStorage, StorageError, StorageResult};
+
+/// 加密值的前缀 | Prefix of encrypted values
+const PREFIX: &str = "$aes256gcm$";
+
+/// AES-GCM nonce 长度 | AES-GCM nonce length
+const NONCE_LEN: usize = 12;
+
+/// 加密存储包装器 | Encrypting storage wrapper
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let storage = EncryptedStorage::new(RedisStorage::new(url, "sa-token:").await?, "2024-06", &key)
+///     .with_previous_key("2023-12", &old_key);
+/// let manager = SaTokenConfig::builder().storage(Arc::new(storage)).build();
+/// ```
+pub struct EncryptedStorage<S> {
+    inner: S,
+    current_key_id: String,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl<S: SaStorage> EncryptedStorage<S> {
+    /// 创建加密存储 | Create an encrypting storage
+    ///
+    /// # 参数 | Parameters
+    /// * `inner` - 实际的存储后端 | The real storage backend
+    /// * `key_id` - 当前密钥 ID，不能包含 `$` | Current key ID, must not contain `$`
+    /// * `key` - 当前 256 位密钥，用于加密与解密 | Current 256-bit key, used to encrypt and decrypt
+    pub fn new(inner: S, key_id: impl Into<String>, key: &[u8; 32]) -> Self {
+        let key_id = key_id.into();
+        assert!(!key_id.contains('$'), "key id must not contain '$'");
+        let mut keys = HashMap::new();
+        keys.insert(key_id.clone(), Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)));
+        Self { inner, current_key_id: key_id, keys }
+    }
+
+    /// 添加轮换前的旧密钥，仅用于解密 | Add a key from before a rotation, used for decryption only
+    pub fn with_previous_key(mut self, key_id: impl Into<String>, key: &[u8; 32]) -> Self {
+        let key_id = key_id.into();
+        assert!(!key_id.contains('$'), "key id must not contain '$'");
+        self.keys.entry(key_id).or_insert_with(|| Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)));
+        self
+    }
+
+    /// 生成随机的 256 位密钥 | Generate a random 256-bit key
+    pub fn generate_key() -> [u8; 32] {
+        let mut key = [0u8; 32];
+        getrandom::fill(&mut key).expect("system random number generator is unavailable");
+        key
+    }
+
+    /// 当前密钥 ID | Current key ID
+    pub fn current_key_id(&self) -> &str {
+        &self.current_key_id
+    }
+
+    /// 内部存储后端 | Inner storage backend
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// 用当前密钥重新加密匹配的键（密钥轮换后或迁移明文数据时使用），保留剩余有效期
+    /// Re-encrypt the matching keys with the current key (after a key rotation or to migrate
+    /// plaintext data), keeping their remaining lifetime
+    ///
+    /// # 参数 | Parameters
+    /// * `pattern` - 键匹配模式，支持 * 通配符 | Key pattern, supports the * wildcard
+    ///
+    /// # 返回 | Returns
+    /// 重新加密的键数量 | Number of re-encrypted keys
+    pub async fn reencrypt(&self, pattern: &str) -> StorageResult<usize> {
+        let current = format!("{}{}$", PREFIX, self.current_key_id);
+        let mut count = 0;
+        for key in self.inner.keys(pattern).await? {
+            let Some(raw) = self.inner.get(&key).await? else {
+                continue;
+            };
+            // 计数器保持明文 | Counters stay in plaintext
+            if raw.starts_with(&current) || raw.parse::<i64>().is_ok() {
+                continue;
+            }
+            let value = self.decrypt(&key, raw)?;
+            let ttl = self.inner.ttl(&key).await?;
+            self.inner.set(&key, &self.encrypt(&key, &value)?, ttl).await?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn encrypt(&self, key: &str, value: &str) -> StorageResult<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::fill(&mut nonce).map_err(|e| StorageError::InternalError(e.to_string()))?;
+        let cipher = &self.keys[&self.current_key_id];
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: value.as_bytes(), aad: key.as_bytes() })
+            .map_err(|_| StorageError::InternalError(format!("failed to encrypt value of {}", key)))?;
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}${}", PREFIX, self.current_key_id, STANDARD_NO_PAD.encode(data)))
+    }
+
+    fn decrypt(&self, key: &str, raw: String) -> StorageResult<String> {
+        let Some(rest) = raw.strip_prefix(PREFIX) else {
+            return Ok(raw);
+        };
+        let invalid = || StorageError::InternalError(format!("failed to decrypt value of {}", key));
+        let (key_id, data) = rest.split_once('$').ok_or_else(invalid)?;
+        let cipher = self.keys.get(key_id)
+            .ok_or_else(|| StorageError::InternalError(format!("unknown encryption key id {}", key_id)))?;
+        let data = STANDARD_NO_PAD.decode(data).map_err(|_| invalid())?;
+        if data.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: key.as_bytes() })
+            .map_err(|_| invalid())?;
+        String::from_utf8(plaintext).map_err(|_| invalid())
+    }
+}
+
+#[async_trait]
+impl<S: SaStorage> SaStorage for EncryptedStorage<S> {
+    async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+        self.inner.get(key).await?
+            .map(|raw| self.decrypt(key, raw))
+            .transpose()
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
+        self.inner.set(key, &self.encrypt(key, value)?, ttl).await
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        self.inner.set_nx(key, &self.encrypt(key, value)?, ttl).await
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<()> {
+        self.inner.delete(key).await
+    }
+
+    async fn exists(&self, key: &str) -> StorageResult<bool> {
+        self.inner.exists(key).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn ttl(&self, key: &str) -> StorageResult<Option<Duration>> {
+        self.inner.ttl(key).await
+    }
+
+    async fn mget(&self, keys: &[&str]) -> StorageResult<Vec<Option<String>>> {
+        let values = self.inner.mget(keys).await?;
+        keys.iter()
+            .zip(values)
+            .map(|(key, raw)| raw.map(|raw| self.decrypt(key, raw)).transpose())
+            .collect()
+    }
+
+    async fn mset(&self, items: &[(&str, &str)], ttl: Option<Duration>) -> StorageResult<()> {
+        let encrypted = items.iter()
+            .map(|(key, value)| Ok((*key, self.encrypt(key, value)?)))
+            .collect::<StorageResult<Vec<_>>>()?;
+        let items: Vec<(&str, &str)> = encrypted.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        self.inner.mset(&items, ttl).await
+    }
+
+    async fn mdel(&self, keys: &[&str]) -> StorageResult<()> {
+        self.inner.mdel(keys).await
+    }
+
+    async fn incr(&self, key: &str) -> StorageResult<i64> {
+        self.inner.incr(key).await
+    }
+
+    async fn decr(&self, key: &str) -> StorageResult<i64> {
+        self.inner.decr(key).await
+    }
+
+    async fn clear(&self) -> StorageResult<()> {
+        self.inner.clear().await
+    }
+
+    async fn warm_up(&self) -> StorageResult<()> {
+        self.inner.warm_up().await
+    }
+
+    async fn keys(&self, pattern: &str) -> StorageResult<Vec<String>> {
+        self.inner.keys(pattern).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_encrypted_storage() {
+        let old_key = EncryptedStorage::<MemoryStorage>::generate_key();
+        let storage = EncryptedStorage::new(MemoryStorage::new(), "k1", &old_key);
+        storage.set("sa:token:abc", r#"{"login_id":"1001"}"#, None).await.unwrap();
+        storage.sadd("sa:index:dept:1", "abc").await.unwrap();
+        storage.inner().set("sa:plain", "legacy", None).await.unwrap();
+
+        let raw = storage.inner().get("sa:token:abc").await.unwrap().unwrap();
+        assert!(raw.starts_with("$aes256gcm$k1$"));
+        assert!(!raw.contains("1001"));
+        assert_eq!(storage.get("sa:token:abc").await.unwrap().as_deref(), Some(r#"{"login_id":"1001"}"#));
+        assert_eq!(storage.smembers("sa:index:dept:1").await.unwrap(), vec!["abc"]);
+        assert_eq!(storage.get("sa:plain").await.unwrap().as_deref(), Some("legacy"));
+        assert_eq!(storage.incr("sa:counter").await.unwrap(), 1);
+
+        // 密文绑定到存储键 | Ciphertexts are bound to their storage key
+        storage.inner().set("sa:token:moved", &raw, None).await.unwrap();
+        assert!(storage.get("sa:token:moved").await.is_err());
+        storage.delete("sa:token:moved").await.unwrap();
+
+        // 轮换密钥：旧数据可读，重新加密后改用新密钥 | Rotation: old data stays readable, reencrypt moves it to the new key
+        let storage = EncryptedStorage::new(storage.inner, "k2", &EncryptedStorage::<MemoryStorage>::generate_key())
+            .with_previous_key("k1", &old_key);
+        assert_eq!(storage.get("sa:token:abc").await.unwrap().as_deref(), Some(r#"{"login_id":"1001"}"#));
+        assert_eq!(storage.reencrypt("sa:*").await.unwrap(), 3);
+        assert!(storage.inner().get("sa:plain").await.unwrap().unwrap().starts_with("$aes256gcm$k2$"));
+        assert_eq!(storage.get("sa:counter").await.unwrap().as_deref(), Some("1"));
+        assert_eq!(storage.get("sa:token:abc").await.unwrap().as_deref(), Some(r#"{"login_id":"1001"}"#));
+    }
+}
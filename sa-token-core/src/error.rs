@@ -18,6 +18,28 @@ pub enum SaTokenError {
     #[error("Token has expired")]
     TokenExpired,
     
+    #[error("Token is frozen")]
+    TokenFrozen,
+    
+    #[error("Token is bound to another client IP")]
+    TokenIpMismatch,
+    
+    #[error("Token is bound to another device")]
+    FingerprintMismatch,
+    
+    // ============ JWT Errors | JWT 错误 ============
+    #[error("JWT issuer is not accepted")]
+    JwtIssuerMismatch,
+    
+    #[error("JWT audience is not accepted")]
+    JwtAudienceMismatch,
+    
+    #[error("JWT is not valid yet")]
+    JwtNotYetValid,
+    
+    #[error("JWT is missing the required '{0}' claim")]
+    JwtMissingClaim(String),
+    
     // ============ Authentication Errors | 认证错误 ============
     #[error("User not logged in")]
     NotLogin,
@@ -25,6 +47,15 @@ pub enum SaTokenError {
     #[error("Token is inactive")]
     TokenInactive,
     
+    #[error("Second-level authentication required")]
+    NotSafe,
+    
+    #[error("Re-authentication required")]
+    ReauthRequired,
+    
+    #[error("Remember-me token is invalid or expired")]
+    RememberMeInvalid,
+    
     // ============ Authorization Errors | 授权错误 ============
     #[error("Permission denied")]
     PermissionDenied,
@@ -42,6 +73,9 @@ pub enum SaTokenError {
     #[error("Account is kicked out")]
     AccountKickedOut,
     
+    #[error("Account is locked, retry after {0} seconds")]
+    AccountLocked(u64),
+    
     // ============ Session Errors | Session 错误 ============
     #[error("Session not found")]
     SessionNotFound,
@@ -69,6 +103,9 @@ pub enum SaTokenError {
     #[error("Invalid expire time format in refresh token")]
     RefreshTokenInvalidExpireTime,
     
+    #[error("Refresh token was already used, its token family has been revoked")]
+    RefreshTokenReused,
+    
     // ============ Token Validation Errors | Token 验证错误 ============
     #[error("Token is empty")]
     TokenEmpty,
@@ -79,6 +116,9 @@ pub enum SaTokenError {
     #[error("Login ID is not a valid number")]
     LoginIdNotNumber,
     
+    #[error("Login ID cannot be converted to {0}")]
+    LoginIdTypeMismatch(String),
+    
     // ============ OAuth2 Errors | OAuth2 错误 ============
     #[error("OAuth2 client not found")]
     OAuth2ClientNotFound,
@@ -107,6 +147,103 @@ pub enum SaTokenError {
     #[error("Invalid scope data")]
     OAuth2InvalidScope,
     
+    #[error("Invalid PKCE code_challenge or code_challenge_method")]
+    OAuth2InvalidCodeChallenge,
+    
+    #[error("PKCE code_verifier does not match code_challenge")]
+    OAuth2InvalidCodeVerifier,
+    
+    #[error("PKCE code_verifier is required")]
+    OAuth2PkceRequired,
+    
+    #[error("Requested scope exceeds the originally granted scope")]
+    OAuth2ScopeExceedsGrant,
+    
+    #[error("Unsupported grant type: '{0}'")]
+    OAuth2UnsupportedGrantType(String),
+    
+    #[error("Client is not authorized to use this grant type")]
+    OAuth2UnauthorizedClient,
+    
+    #[error("Missing required parameter: '{0}'")]
+    OAuth2MissingParameter(String),
+    
+    #[error("Authorization pending")]
+    OAuth2AuthorizationPending,
+    
+    #[error("Polling too frequently, slow down")]
+    OAuth2SlowDown,
+    
+    #[error("The user denied the authorization request")]
+    OAuth2AccessDenied,
+    
+    #[error("Device code expired")]
+    OAuth2DeviceCodeExpired,
+    
+    #[error("User code not found or expired")]
+    OAuth2UserCodeNotFound,
+    
+    #[error("Access token lacks the required scope: '{0}'")]
+    OAuth2InsufficientScope(String),
+    
+    #[error("ID token nonce mismatch")]
+    OidcNonceMismatch,
+    
+    #[error("Unsupported response type: '{0}'")]
+    OAuth2UnsupportedResponseType(String),
+    
+    #[error("Consent ticket not found or expired")]
+    OAuth2ConsentTicketInvalid,
+    
+    #[error("Invalid '{0}' for token exchange")]
+    OAuth2InvalidExchangeToken(String),
+    
+    #[error("Requested audience is not allowed: '{0}'")]
+    OAuth2InvalidTarget(String),
+    
+    // ============ Off-session Errors | 离线令牌错误 ============
+    #[error("User consent required")]
+    ConsentRequired,
+    
+    #[error("Scope not consented: '{0}'")]
+    ScopeNotConsented(String),
+    
+    #[error("Off-session token cannot be used for an interactive session")]
+    OffSessionNotAllowed,
+    
+    // ============ Sign Errors | 签名错误 ============
+    #[error("Signature is missing")]
+    SignatureMissing,
+    
+    #[error("Signature is invalid")]
+    InvalidSignature,
+    
+    #[error("Request body exceeds the {0} bytes limit")]
+    BodyTooLarge(usize),
+    
+    #[error("Signature timestamp is outside the allowed window")]
+    SignTimestampExpired,
+    
+    // ============ Same-Token Errors | Same-Token 错误 ============
+    #[error("Same-Token is missing or invalid")]
+    InvalidSameToken,
+    
+    // ============ API Key Errors | API Key 错误 ============
+    #[error("API key is missing or invalid")]
+    InvalidApiKey,
+    
+    // ============ CSRF Errors | CSRF 错误 ============
+    #[error("CSRF token is missing or invalid")]
+    InvalidCsrfToken,
+    
+    // ============ Rate Limit Errors | 限流错误 ============
+    #[error("Too many requests, retry after {0} seconds")]
+    RateLimited(u64),
+    
+    // ============ HTTP Basic Errors | HTTP Basic 认证错误 ============
+    #[error("HTTP Basic authentication failed")]
+    BasicAuthFailed,
+    
     // ============ SSO Errors | SSO 单点登录错误 ============
     #[error("SSO ticket not found or invalid")]
     InvalidTicket,
@@ -120,6 +257,59 @@ pub enum SaTokenError {
     #[error("SSO session not found")]
     SsoSessionNotFound,
     
+    #[error("Redirect URL is not allowed: '{0}'")]
+    SsoRedirectNotAllowed(String),
+
+    // ============ SAML Errors | SAML 错误 ============
+    #[error("SAML response is invalid: {0}")]
+    SamlResponseInvalid(String),
+
+    #[error("SAML signature is missing or invalid")]
+    SamlSignatureInvalid,
+
+    #[error("SAML assertion is expired or not yet valid")]
+    SamlAssertionExpired,
+
+    #[error("SAML assertion has already been used")]
+    SamlAssertionReplayed,
+
+    // ============ Social Login Errors | 第三方登录错误 ============
+    #[error("Unknown social login provider: '{0}'")]
+    SocialProviderNotFound(String),
+
+    #[error("Social login state is missing, expired or for another provider")]
+    SocialStateInvalid,
+
+    #[error("Social login failed: {0}")]
+    SocialLoginFailed(String),
+
+    #[error("Third-party identity is already bound to another account")]
+    SocialIdentityBound,
+
+    // ============ QR Login Errors | 扫码登录错误 ============
+    #[error("QR login ticket is {0}")]
+    QrTicketStateMismatch(String),
+    
+    // ============ Login Link Errors | 登录链接错误 ============
+    #[error("Login link is invalid or expired")]
+    LoginLinkInvalid,
+    
+    #[error("Login link has already been used")]
+    LoginLinkReused,
+    
+    // ============ Two-Factor Errors | 双因素认证错误 ============
+    #[error("Two-factor authentication required")]
+    TwoFactorRequired,
+    
+    #[error("Two-factor authentication is not enabled")]
+    TwoFactorNotEnabled,
+    
+    #[error("TOTP code is invalid or already used")]
+    InvalidTotpCode,
+    
+    #[error("TOTP secret is invalid: {0}")]
+    InvalidTotpSecret(String),
+    
     // ============ System Errors | 系统错误 ============
     #[error("Storage error: {0}")]
     StorageError(String),
@@ -130,6 +320,9 @@ pub enum SaTokenError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
     
+    #[error("Storage schema version {found} is incompatible with {expected}, register a SchemaMigrator or upgrade")]
+    SchemaVersionMismatch { found: u32, expected: u32 },
+    
     #[error("Internal error: {0}")]
     InternalError(String),
 }
@@ -160,7 +353,17 @@ impl SaTokenError {
             | Self::TokenNotFound 
             | Self::TokenExpired 
             | Self::TokenInactive 
+            | Self::TokenFrozen
+            | Self::TokenIpMismatch
+            | Self::FingerprintMismatch
+            | Self::ReauthRequired
+            | Self::RememberMeInvalid
             | Self::InvalidToken(_)
+            | Self::JwtIssuerMismatch
+            | Self::JwtAudienceMismatch
+            | Self::JwtNotYetValid
+            | Self::JwtMissingClaim(_)
+            | Self::BasicAuthFailed
         )
     }
     
@@ -175,6 +378,32 @@ impl SaTokenError {
             | Self::RoleDenied(_)
         )
     }
+    
+    /// HTTP status a rejected request answers with
+    /// 
+    /// 400 for login IDs of the wrong type, 403 for permission, role, CSRF and account status errors,
+    /// 413 for oversized bodies, 429 for rate limits and locked accounts, 500 for server-side failures,
+    /// otherwise 401
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::LoginIdNotNumber | Self::LoginIdTypeMismatch(_) => 400,
+            Self::PermissionDenied
+            | Self::PermissionDeniedDetail(_)
+            | Self::RoleDenied(_)
+            | Self::InvalidCsrfToken
+            | Self::AccountBanned(_)
+            | Self::OAuth2InsufficientScope(_)
+            | Self::ScopeNotConsented(_) => 403,
+            Self::BodyTooLarge(_) => 413,
+            Self::RateLimited(_) | Self::AccountLocked(_) => 429,
+            Self::StorageError(_)
+            | Self::ConfigError(_)
+            | Self::SerializationError(_)
+            | Self::SchemaVersionMismatch { .. }
+            | Self::InternalError(_) => 500,
+            _ => 401,
+        }
+    }
 }
 
 /// Application-level error messages
@@ -205,4 +434,7 @@ pub mod messages {
     
     /// Role required
     pub const ROLE_REQUIRED: &str = "Role required";
+    
+    /// Second-level authentication required
+    pub const SAFE_AUTH_REQUIRED: &str = "Second-level authentication required";
 }
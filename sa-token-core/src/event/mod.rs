@@ -125,7 +125,8 @@
 //!               ├─ KickOut ────▶ on_kick_out(...)
 //!               ├─ RenewTimeout ▶ on_renew_timeout(...)
 //!               ├─ Replaced ───▶ on_replaced(...)
-//!               └─ Banned ─────▶ on_banned(...)
+//!               ├─ Banned ─────▶ on_banned(...)
+//!               └─ Expired ────▶ on_expired(...)
 //! 
 //! Notes | 注意：
 //! - Listeners execute in registration order
@@ -259,6 +260,20 @@ pub enum SaTokenEventType {
     Replaced,
     /// 被封禁事件
     Banned,
+    /// 身份切换事件（用于审计）
+    SwitchTo,
+    /// 权限数据降级事件（`PermissionProvider` 失败、超时或熔断）
+    PermissionDegraded,
+    /// 账号因登录失败次数过多被锁定事件
+    AccountLocked,
+    /// 登录链接被使用事件
+    LoginLinkConsumed,
+    /// 登录链接被拒绝事件（过期或重复使用）
+    LoginLinkRejected,
+    /// 实时连接心跳超时事件
+    ConnectionTimeout,
+    /// Token 因 TTL 到期被存储删除事件
+    Expired,
 }
 
 /// 事件数据
@@ -351,6 +366,102 @@ impl SaTokenEvent {
         }
     }
 
+    /// 创建身份切换事件
+    /// 
+    /// `login_id` 为发起切换的登录 ID，切换目标记录在 `extra.switch_to` 中
+    pub fn switch_to(login_id: impl Into<String>, token: impl Into<String>, switch_to: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::SwitchTo,
+            login_id: login_id.into(),
+            token: token.into(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: Some(serde_json::json!({ "switch_to": switch_to.into() })),
+        }
+    }
+
+    /// 创建权限数据降级事件
+    /// 
+    /// 原因记录在 `extra.reason` 中，`extra.stale` 表示是否用旧缓存兜底
+    pub fn permission_degraded(login_id: impl Into<String>, reason: impl Into<String>, stale: bool) -> Self {
+        Self {
+            event_type: SaTokenEventType::PermissionDegraded,
+            login_id: login_id.into(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: Some(serde_json::json!({ "reason": reason.into(), "stale": stale })),
+        }
+    }
+
+    /// 创建账号锁定事件
+    /// 
+    /// 失败次数记录在 `extra.failures` 中，锁定秒数记录在 `extra.lock_seconds` 中
+    pub fn account_locked(login_id: impl Into<String>, failures: u32, lock_seconds: u64) -> Self {
+        Self {
+            event_type: SaTokenEventType::AccountLocked,
+            login_id: login_id.into(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: Some(serde_json::json!({ "failures": failures, "lock_seconds": lock_seconds })),
+        }
+    }
+
+    /// 创建登录链接被使用事件，`token` 为新会话的 token
+    pub fn login_link_consumed(login_id: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::LoginLinkConsumed,
+            login_id: login_id.into(),
+            token: token.into(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: None,
+        }
+    }
+
+    /// 创建登录链接被拒绝事件
+    /// 
+    /// 原因（`expired` 或 `reused`）记录在 `extra.reason` 中
+    pub fn login_link_rejected(login_id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::LoginLinkRejected,
+            login_id: login_id.into(),
+            token: String::new(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: Some(serde_json::json!({ "reason": reason.into() })),
+        }
+    }
+
+    /// 创建实时连接心跳超时事件
+    /// 
+    /// 连接 ID 记录在 `extra.connection_id` 中
+    pub fn connection_timeout(login_id: impl Into<String>, token: impl Into<String>, connection_id: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::ConnectionTimeout,
+            login_id: login_id.into(),
+            token: token.into(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: Some(serde_json::json!({ "connection_id": connection_id.into() })),
+        }
+    }
+
+    /// 创建 token 过期事件
+    /// 
+    /// 由存储层在 token 因 TTL 到期被删除后发布（例如 Redis 键空间通知）
+    pub fn expired(login_id: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            event_type: SaTokenEventType::Expired,
+            login_id: login_id.into(),
+            token: token.into(),
+            login_type: "default".to_string(),
+            timestamp: Utc::now(),
+            extra: None,
+        }
+    }
+
     /// 设置登录类型
     pub fn with_login_type(mut self, login_type: impl Into<String>) -> Self {
         self.login_type = login_type.into();
@@ -459,6 +570,100 @@ pub trait SaTokenListener: Send + Sync {
         let _ = (login_id, login_type);
     }
 
+    /// 身份切换事件 | Switch To Event
+    /// 
+    /// 当已登录用户临时切换为其他登录 ID 时触发，可用于审计
+    /// Triggered when a logged-in user temporarily acts as another login ID, useful for auditing
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 发起切换的登录 ID | Login ID that initiated the switch
+    /// - `switch_to`: 切换到的登录 ID | Login ID switched to
+    /// - `login_type`: 登录类型 | Login type
+    async fn on_switch_to(&self, login_id: &str, switch_to: &str, login_type: &str) {
+        let _ = (login_id, switch_to, login_type);
+    }
+
+    /// 权限数据降级事件 | Permission Degraded Event
+    /// 
+    /// 当 `PermissionProvider` 失败、超时或熔断时触发，可用于告警
+    /// Triggered when the `PermissionProvider` fails, times out or its circuit is open, useful for alerting
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 登录类型 | Login type
+    /// - `reason`: 原因 | Reason
+    /// - `stale`: 是否用旧缓存兜底 | Whether stale cached data was served
+    async fn on_permission_degraded(&self, login_id: &str, login_type: &str, reason: &str, stale: bool) {
+        let _ = (login_id, login_type, reason, stale);
+    }
+
+    /// 账号锁定事件 | Account Locked Event
+    /// 
+    /// 当登录失败次数达到上限、账号被锁定时触发，可用于告警
+    /// Triggered when too many failed logins lock an account, useful for alerting
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 登录类型 | Login type
+    /// - `failures`: 触发锁定的失败次数 | Failures that triggered the lock
+    /// - `lock_seconds`: 锁定时长（秒）| Lock duration in seconds
+    async fn on_account_locked(&self, login_id: &str, login_type: &str, failures: u32, lock_seconds: u64) {
+        let _ = (login_id, login_type, failures, lock_seconds);
+    }
+
+    /// 登录链接被使用事件 | Login Link Consumed Event
+    /// 
+    /// 当一次性登录链接换取会话成功时触发
+    /// Triggered when a single-use login link is exchanged for a session
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `token`: 新会话的 token | Token of the new session
+    /// - `login_type`: 登录类型 | Login type
+    async fn on_login_link_consumed(&self, login_id: &str, token: &str, login_type: &str) {
+        let _ = (login_id, token, login_type);
+    }
+
+    /// 登录链接被拒绝事件 | Login Link Rejected Event
+    /// 
+    /// 当过期或已使用的登录链接再次被使用时触发；`reused` 可能意味着链接被截获，可用于告警
+    /// Triggered when an expired or already used link is presented; `reused` may mean the link was intercepted, useful for alerting
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 登录类型 | Login type
+    /// - `reason`: `expired` 或 `reused` | `expired` or `reused`
+    async fn on_login_link_rejected(&self, login_id: &str, login_type: &str, reason: &str) {
+        let _ = (login_id, login_type, reason);
+    }
+
+    /// 实时连接心跳超时事件 | Connection Timeout Event
+    /// 
+    /// 当已登记的实时连接在静默期内没有心跳、被移出在线列表时触发
+    /// Triggered when a registered realtime connection sends no heartbeat within the silence period and is removed from the online list
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `token`: 连接使用的 token | Token of the connection
+    /// - `login_type`: 登录类型 | Login type
+    /// - `connection_id`: 连接 ID | Connection ID
+    async fn on_connection_timeout(&self, login_id: &str, token: &str, login_type: &str, connection_id: &str) {
+        let _ = (login_id, token, login_type, connection_id);
+    }
+
+    /// Token 过期事件 | Expired Event
+    /// 
+    /// 当 token 没有登出、因 TTL 到期被存储删除时触发，需要存储层支持过期通知
+    /// Triggered when a token is removed by its storage TTL instead of a logout; requires expiry notifications from the storage
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `token`: 过期的 token | Expired token
+    /// - `login_type`: 登录类型 | Login type
+    async fn on_expired(&self, login_id: &str, token: &str, login_type: &str) {
+        let _ = (login_id, token, login_type);
+    }
+
     /// 通用事件处理（所有事件都会触发此方法）
     /// Generic Event Handler (triggered by all events)
     /// 
@@ -544,6 +749,57 @@ impl SaTokenEventBus {
                 SaTokenEventType::Banned => {
                     listener.on_banned(&event.login_id, &event.login_type).await;
                 }
+                SaTokenEventType::SwitchTo => {
+                    let switch_to = event.extra.as_ref()
+                        .and_then(|extra| extra.get("switch_to"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    listener.on_switch_to(&event.login_id, switch_to, &event.login_type).await;
+                }
+                SaTokenEventType::PermissionDegraded => {
+                    let extra = event.extra.as_ref();
+                    let reason = extra
+                        .and_then(|extra| extra.get("reason"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    let stale = extra
+                        .and_then(|extra| extra.get("stale"))
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                    listener.on_permission_degraded(&event.login_id, &event.login_type, reason, stale).await;
+                }
+                SaTokenEventType::AccountLocked => {
+                    let extra = event.extra.as_ref();
+                    let failures = extra
+                        .and_then(|extra| extra.get("failures"))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as u32;
+                    let lock_seconds = extra
+                        .and_then(|extra| extra.get("lock_seconds"))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    listener.on_account_locked(&event.login_id, &event.login_type, failures, lock_seconds).await;
+                }
+                SaTokenEventType::LoginLinkConsumed => {
+                    listener.on_login_link_consumed(&event.login_id, &event.token, &event.login_type).await;
+                }
+                SaTokenEventType::LoginLinkRejected => {
+                    let reason = event.extra.as_ref()
+                        .and_then(|extra| extra.get("reason"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    listener.on_login_link_rejected(&event.login_id, &event.login_type, reason).await;
+                }
+                SaTokenEventType::ConnectionTimeout => {
+                    let connection_id = event.extra.as_ref()
+                        .and_then(|extra| extra.get("connection_id"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    listener.on_connection_timeout(&event.login_id, &event.token, &event.login_type, connection_id).await;
+                }
+                SaTokenEventType::Expired => {
+                    listener.on_expired(&event.login_id, &event.token, &event.login_type).await;
+                }
             }
         }
     }
@@ -612,6 +868,35 @@ impl SaTokenListener for LoggingListener {
             "用户被封禁"
         );
     }
+
+    async fn on_permission_degraded(&self, login_id: &str, login_type: &str, reason: &str, stale: bool) {
+        tracing::warn!(
+            login_id = %login_id,
+            login_type = %login_type,
+            reason = %reason,
+            stale = stale,
+            "权限数据降级"
+        );
+    }
+
+    async fn on_account_locked(&self, login_id: &str, login_type: &str, failures: u32, lock_seconds: u64) {
+        tracing::warn!(
+            login_id = %login_id,
+            login_type = %login_type,
+            failures = failures,
+            lock_seconds = lock_seconds,
+            "账号已锁定"
+        );
+    }
+
+    async fn on_login_link_rejected(&self, login_id: &str, login_type: &str, reason: &str) {
+        tracing::warn!(
+            login_id = %login_id,
+            login_type = %login_type,
+            reason = %reason,
+            "登录链接被拒绝"
+        );
+    }
 }
 
 #[cfg(test)]
@@ -633,7 +918,7 @@ mod tests {
     #[async_trait]
     impl SaTokenListener for TestListener {
         async fn on_login(&self, _login_id: &str, _token: &str, _login_type: &str) {
-            let mut count = self.login_count.write().await;
+            let mut count = self.login_count.write().unwrap();
             *count += 1;
         }
     }
@@ -644,14 +929,14 @@ mod tests {
         let listener = Arc::new(TestListener::new());
         let login_count = Arc::clone(&listener.login_count);
         
-        bus.register(listener).await;
+        bus.register(listener);
         
         // 发布登录事件
         let event = SaTokenEvent::login("user_123", "token_abc");
         bus.publish(event).await;
         
         // 验证监听器被调用
-        let count = login_count.read().await;
+        let count = login_count.read().unwrap();
         assert_eq!(*count, 1);
     }
 
@@ -0,0 +1,214 @@
+// Author: 金书记
+//
+//! 可组合的鉴权守卫 | Composable Guards
+//!
+//! 用 `and` / `or` 把登录、权限、角色、二级认证、双因素认证检查组合成一个守卫，
+//! 挂到 `PathAuthConfig::guard` 后由各插件共用的 `process_auth` 流程统一执行。
+//! Combine login, permission, role, safe-auth and two-factor checks with `and` / `or` into a single guard;
+//! attached through `PathAuthConfig::guard`, it runs in the `process_auth` pipeline shared by every plugin.
+//!
+//! ```text
+//! Guard::login().and(Guard::permission("a")).or(Guard::role("admin"))
+//!   = ((login && permission(a)) || role(admin))
+//!
+//! and：左侧失败即短路 | short-circuits when the left side fails
+//! or： 左侧通过即短路；都失败时优先返回认证错误（401），否则返回右侧错误（403）
+//!      short-circuits when the left side passes; when both fail, an authentication error (401)
+//!      wins, otherwise the right side's error (403) is returned
+//! ```
+
+use std::fmt;
+use futures_util::future::BoxFuture;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::token::TokenInfo;
+use crate::permission::{PermissionChecker, RoleChecker};
+use crate::SaTokenManager;
+
+/// 鉴权守卫 | Guard
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::Guard;
+///
+/// let guard = Guard::login().and(Guard::permission("order:read")).or(Guard::role("admin"));
+/// assert_eq!(guard.to_string(), "((login && permission(order:read)) || role(admin))");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Guard {
+    /// 已登录 | Logged in
+    Login,
+    /// 拥有权限（支持 `admin:*` 通配符）| Has a permission (`admin:*` wildcards supported)
+    Permission(String),
+    /// 拥有角色（遵循角色层级）| Has a role (respects the role hierarchy)
+    Role(String),
+    /// 处于二级认证有效期内 | Within the safe-auth window
+    Safe,
+    /// 本会话已完成 TOTP 双因素认证 | The session passed TOTP two-factor authentication
+    TwoFactor,
+    /// 两者都通过 | Both pass
+    And(Box<Guard>, Box<Guard>),
+    /// 任一通过 | Either passes
+    Or(Box<Guard>, Box<Guard>),
+}
+
+impl Guard {
+    /// 要求登录 | Require login
+    pub fn login() -> Self {
+        Guard::Login
+    }
+
+    /// 要求权限 | Require a permission
+    pub fn permission(permission: impl Into<String>) -> Self {
+        Guard::Permission(permission.into())
+    }
+
+    /// 要求角色 | Require a role
+    pub fn role(role: impl Into<String>) -> Self {
+        Guard::Role(role.into())
+    }
+
+    /// 要求二级认证 | Require second-level authentication
+    pub fn safe() -> Self {
+        Guard::Safe
+    }
+
+    /// 要求本会话已完成双因素认证 | Require two-factor authentication in this session
+    pub fn two_factor() -> Self {
+        Guard::TwoFactor
+    }
+
+    /// 与另一个守卫同时满足 | Require this guard and another
+    pub fn and(self, other: Guard) -> Self {
+        Guard::And(Box::new(self), Box::new(other))
+    }
+
+    /// 满足任一守卫 | Require this guard or another
+    pub fn or(self, other: Guard) -> Self {
+        Guard::Or(Box::new(self), Box::new(other))
+    }
+
+    /// 执行检查 | Run the check
+    ///
+    /// # 参数 | Parameters
+    /// * `manager` - 管理器 | Manager
+    /// * `token_info` - 已校验的 token 信息，`None` 表示未登录 | Validated token info, `None` when not logged in
+    pub fn check<'a>(
+        &'a self,
+        manager: &'a SaTokenManager,
+        token_info: Option<&'a TokenInfo>,
+    ) -> BoxFuture<'a, SaTokenResult<()>> {
+        self.check_with(manager, token_info, None, None)
+    }
+
+    /// 执行检查，权限与角色交给指定的检查器判断 | Run the check, asking the given checkers about permissions and roles
+    ///
+    /// 检查器为 `None` 时回退到 `manager` 的权限与角色数据
+    /// Falls back to the permission and role data of `manager` when a checker is `None`
+    ///
+    /// # 参数 | Parameters
+    /// * `manager` - 管理器 | Manager
+    /// * `token_info` - 已校验的 token 信息，`None` 表示未登录 | Validated token info, `None` when not logged in
+    /// * `permissions` - 权限检查器 | Permission checker
+    /// * `roles` - 角色检查器 | Role checker
+    pub fn check_with<'a>(
+        &'a self,
+        manager: &'a SaTokenManager,
+        token_info: Option<&'a TokenInfo>,
+        permissions: Option<&'a dyn PermissionChecker>,
+        roles: Option<&'a dyn RoleChecker>,
+    ) -> BoxFuture<'a, SaTokenResult<()>> {
+        Box::pin(async move {
+            match self {
+                Guard::And(left, right) => {
+                    left.check_with(manager, token_info, permissions, roles).await?;
+                    right.check_with(manager, token_info, permissions, roles).await
+                }
+                Guard::Or(left, right) => {
+                    let Err(left_error) = left.check_with(manager, token_info, permissions, roles).await else {
+                        return Ok(());
+                    };
+                    match right.check_with(manager, token_info, permissions, roles).await {
+                        Ok(()) => Ok(()),
+                        Err(_) if left_error.is_auth_error() => Err(left_error),
+                        Err(right_error) => Err(right_error),
+                    }
+                }
+                Guard::Login => token_info.map(|_| ()).ok_or(SaTokenError::NotLogin),
+                Guard::Permission(permission) => {
+                    let info = token_info.ok_or(SaTokenError::NotLogin)?;
+                    let granted = match permissions {
+                        Some(checker) => checker.has_permission(&info.login_id, permission).await?,
+                        None => manager.has_permission_by_type(&info.login_id, info.account_type(), permission).await,
+                    };
+                    if granted {
+                        Ok(())
+                    } else {
+                        Err(SaTokenError::PermissionDeniedDetail(permission.clone()))
+                    }
+                }
+                Guard::Role(role) => {
+                    let info = token_info.ok_or(SaTokenError::NotLogin)?;
+                    let granted = match roles {
+                        Some(checker) => checker.has_role(&info.login_id, role).await?,
+                        None => manager.has_role_by_type(&info.login_id, info.account_type(), role).await,
+                    };
+                    if granted {
+                        Ok(())
+                    } else {
+                        Err(SaTokenError::RoleDenied(role.clone()))
+                    }
+                }
+                Guard::Safe => {
+                    let info = token_info.ok_or(SaTokenError::NotLogin)?;
+                    manager.check_safe(&info.token).await
+                }
+                Guard::TwoFactor => {
+                    let info = token_info.ok_or(SaTokenError::NotLogin)?;
+                    manager.check_two_factor(&info.token).await
+                }
+            }
+        })
+    }
+}
+
+impl fmt::Display for Guard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Guard::Login => write!(f, "login"),
+            Guard::Permission(permission) => write!(f, "permission({})", permission),
+            Guard::Role(role) => write!(f, "role({})", role),
+            Guard::Safe => write!(f, "safe"),
+            Guard::TwoFactor => write!(f, "two_factor"),
+            Guard::And(left, right) => write!(f, "({} && {})", left, right),
+            Guard::Or(left, right) => write!(f, "({} || {})", left, right),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_guard_combinators() {
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        manager.set_permissions("reader", vec!["order:read".to_string()]).await;
+        manager.set_roles("boss", vec!["admin".to_string()]).await;
+
+        let reader = manager.get_token_info(&manager.login("reader").await.unwrap()).await.unwrap();
+        let boss = manager.get_token_info(&manager.login("boss").await.unwrap()).await.unwrap();
+        let guest = manager.get_token_info(&manager.login("guest").await.unwrap()).await.unwrap();
+
+        let guard = Guard::login().and(Guard::permission("order:read")).or(Guard::role("admin"));
+        assert!(guard.check(&manager, Some(&reader)).await.is_ok());
+        assert!(guard.check(&manager, Some(&boss)).await.is_ok());
+        assert!(matches!(guard.check(&manager, Some(&guest)).await, Err(SaTokenError::RoleDenied(_))));
+        assert!(matches!(guard.check(&manager, None).await, Err(SaTokenError::NotLogin)));
+
+        let guard = Guard::permission("order:read").and(Guard::safe());
+        assert!(matches!(guard.check(&manager, Some(&reader)).await, Err(SaTokenError::NotSafe)));
+    }
+}
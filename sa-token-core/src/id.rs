@@ -0,0 +1,200 @@
+// Author: 金书记
+//
+//! 唯一 ID 生成器 | Unique ID Generator
+//!
+//! SSO 票据、OAuth2 授权码、设备码和 nonce 都由同一个 `IdGenerator` 生成。
+//! 默认实现使用操作系统的安全随机源；应用可以替换为 HSM、带前缀或可排序的实现。
+//! SSO tickets, OAuth2 codes, device codes and nonces all come from one `IdGenerator`.
+//! The default draws from the OS secure random source; applications can plug in
+//! HSM-backed, prefixed or sortable implementations.
+//!
+//! ```text
+//! SaTokenConfig::builder().id_generator(Arc::new(MyGenerator))
+//!   └─→ build() 时校验 | validated in build():
+//!         ├─→ entropy_bits() >= min_id_entropy_bits（默认 128）| (default 128)
+//!         └─→ 每种 IdKind 的样例非空、URL 安全且两次生成不同
+//!             samples of every IdKind are non-empty, URL-safe and differ between calls
+//! ```
+
+use std::fmt;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 默认要求的最小熵（位）| Default minimum entropy in bits
+pub const MIN_ID_ENTROPY_BITS: u32 = 128;
+
+/// ID 的用途 | What an ID is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdKind {
+    /// SSO 票据 | SSO ticket
+    SsoTicket,
+    /// OAuth2 授权码 | OAuth2 authorization code
+    OAuth2Code,
+    /// 设备授权码 | Device authorization code
+    DeviceCode,
+    /// 防重放 nonce | Anti-replay nonce
+    Nonce,
+    /// 扫码登录票据 | QR login ticket
+    QrTicket,
+    /// 一次性登录链接 | Single-use login link
+    LoginLink,
+    /// 记住我 token | Remember-me token
+    RememberMe,
+    /// SAML AuthnRequest ID | SAML AuthnRequest ID
+    SamlRequest,
+    /// 第三方登录的 state | Social login state
+    SocialState,
+}
+
+impl IdKind {
+    /// 所有用途 | All kinds
+    pub const ALL: [IdKind; 9] = [
+        IdKind::SsoTicket, IdKind::OAuth2Code, IdKind::DeviceCode, IdKind::Nonce, IdKind::QrTicket, IdKind::LoginLink,
+        IdKind::RememberMe, IdKind::SamlRequest, IdKind::SocialState,
+    ];
+
+    /// 名称 | Name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdKind::SsoTicket => "sso-ticket",
+            IdKind::OAuth2Code => "oauth2-code",
+            IdKind::DeviceCode => "device-code",
+            IdKind::Nonce => "nonce",
+            IdKind::QrTicket => "qr-ticket",
+            IdKind::LoginLink => "login-link",
+            IdKind::RememberMe => "remember-me",
+            IdKind::SamlRequest => "saml-request",
+            IdKind::SocialState => "social-state",
+        }
+    }
+}
+
+/// 唯一 ID 生成器 | Unique ID generator
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::id::{IdGenerator, IdKind, RandomIdGenerator, validate_id_generator};
+///
+/// /// 带环境前缀的 ID | IDs with an environment prefix
+/// struct Prefixed(RandomIdGenerator);
+///
+/// impl IdGenerator for Prefixed {
+///     fn generate(&self, kind: IdKind) -> String {
+///         format!("prod-{}", self.0.generate(kind))
+///     }
+///
+///     fn entropy_bits(&self) -> u32 {
+///         self.0.entropy_bits()
+///     }
+/// }
+///
+/// let generator = Prefixed(RandomIdGenerator::default());
+/// assert!(generator.generate(IdKind::SsoTicket).starts_with("prod-"));
+/// assert!(validate_id_generator(&generator, 128).is_ok());
+/// ```
+pub trait IdGenerator: Send + Sync {
+    /// 生成 ID | Generate an ID
+    ///
+    /// # 参数 | Parameters
+    /// * `kind` - ID 的用途 | What the ID is used for
+    fn generate(&self, kind: IdKind) -> String;
+
+    /// 每个 ID 中不可预测部分的熵（位）| Entropy of the unpredictable part of each ID, in bits
+    fn entropy_bits(&self) -> u32;
+}
+
+impl fmt::Debug for dyn IdGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IdGenerator")
+    }
+}
+
+/// 安全随机 ID 生成器（默认，十六进制）| Secure random ID generator (default, hex)
+#[derive(Debug, Clone)]
+pub struct RandomIdGenerator {
+    bytes: usize,
+}
+
+impl RandomIdGenerator {
+    /// 创建生成器 | Create a generator
+    ///
+    /// # 参数 | Parameters
+    /// * `bytes` - 随机字节数，ID 长度为其两倍 | Number of random bytes, the ID is twice as long
+    pub fn new(bytes: usize) -> Self {
+        Self { bytes }
+    }
+}
+
+impl Default for RandomIdGenerator {
+    /// 16 字节（128 位）| 16 bytes (128 bits)
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl IdGenerator for RandomIdGenerator {
+    fn generate(&self, _kind: IdKind) -> String {
+        let mut buf = vec![0u8; self.bytes];
+        getrandom::fill(&mut buf).expect("OS random source is unavailable");
+        hex::encode(buf)
+    }
+
+    fn entropy_bits(&self) -> u32 {
+        u32::try_from(self.bytes * 8).unwrap_or(u32::MAX)
+    }
+}
+
+/// 校验生成器的熵与输出格式，启动时调用 | Check a generator's entropy and output, called at startup
+///
+/// # 参数 | Parameters
+/// * `generator` - 待校验的生成器 | Generator to check
+/// * `min_bits` - 要求的最小熵（位）| Required minimum entropy in bits
+pub fn validate_id_generator(generator: &dyn IdGenerator, min_bits: u32) -> SaTokenResult<()> {
+    if generator.entropy_bits() < min_bits {
+        return Err(SaTokenError::ConfigError(format!(
+            "IdGenerator provides {} bits of entropy, at least {} required",
+            generator.entropy_bits(), min_bits
+        )));
+    }
+    for kind in IdKind::ALL {
+        let (first, second) = (generator.generate(kind), generator.generate(kind));
+        if first.is_empty() || !first.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.~".contains(&b)) {
+            return Err(SaTokenError::ConfigError(format!(
+                "IdGenerator produced an empty or non URL-safe {} id", kind.as_str()
+            )));
+        }
+        if first == second {
+            return Err(SaTokenError::ConfigError(format!(
+                "IdGenerator produced the same {} id twice", kind.as_str()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed;
+
+    impl IdGenerator for Fixed {
+        fn generate(&self, _kind: IdKind) -> String {
+            "fixed".to_string()
+        }
+
+        fn entropy_bits(&self) -> u32 {
+            256
+        }
+    }
+
+    #[test]
+    fn test_id_generator_validation() {
+        let generator = RandomIdGenerator::default();
+        assert_eq!(generator.generate(IdKind::Nonce).len(), 32);
+        assert!(validate_id_generator(&generator, MIN_ID_ENTROPY_BITS).is_ok());
+
+        // 熵不足或输出重复都在启动时被拒绝 | Low entropy and repeated output are both rejected at startup
+        assert!(matches!(validate_id_generator(&RandomIdGenerator::new(8), MIN_ID_ENTROPY_BITS), Err(SaTokenError::ConfigError(_))));
+        assert!(validate_id_generator(&Fixed, MIN_ID_ENTROPY_BITS).is_err());
+    }
+}
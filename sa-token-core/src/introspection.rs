@@ -0,0 +1,312 @@
+// Author: 金书记
+//
+//! 远程 Token 内省 | Remote Token Introspection
+//!
+//! 资源服务器通过调用独立认证服务器的内省端点（RFC 7662）校验不透明 token，
+//! 并缓存结果，避免每个请求都访问认证服务器。
+//! A resource server validates opaque tokens by calling the introspection endpoint (RFC 7662)
+//! of a separate auth server, caching results so not every request reaches the auth server.
+//!
+//! ```text
+//! RemoteIntrospectionValidator.validate(token)
+//!   ├─→ 缓存命中且未过期 | cache hit, not expired → 直接返回 | return it
+//!   └─→ IntrospectionClient.introspect(token)
+//!         ├─→ active = true  → 缓存 min(cache_ttl, exp) | cached for min(cache_ttl, exp)
+//!         ├─→ active = false → 缓存 negative_ttl，返回 InvalidToken | cached for negative_ttl, InvalidToken
+//!         └─→ 请求失败 | request failed → 不缓存，返回错误 | not cached, error returned
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 内省响应（RFC 7662）| Introspection response (RFC 7662)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntrospectionResponse {
+    /// token 是否有效 | Whether the token is active
+    pub active: bool,
+    /// 空格分隔的权限范围 | Space-separated scopes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// 客户端 ID | Client ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// 用户名 | Username
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// token 类型 | Token type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    /// 过期时间（Unix 秒）| Expiration time (Unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    /// 签发时间（Unix 秒）| Issued-at time (Unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    /// 主体（登录 ID）| Subject (login ID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    /// 签发者 | Issuer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// 其他字段 | Other members
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl IntrospectionResponse {
+    /// 登录 ID（`sub`，缺失时为 `username`）| Login ID (`sub`, falling back to `username`)
+    pub fn login_id(&self) -> Option<&str> {
+        self.sub.as_deref().or(self.username.as_deref())
+    }
+
+    /// 权限范围列表 | Scopes as a list
+    pub fn scopes(&self) -> Vec<&str> {
+        self.scope.as_deref().map(|s| s.split_whitespace().collect()).unwrap_or_default()
+    }
+
+    /// 是否拥有某个权限范围 | Whether a scope was granted
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes().contains(&scope)
+    }
+}
+
+/// 内省客户端：向认证服务器查询 token | Introspection client: asks the auth server about a token
+///
+/// 启用 `remote-introspection` feature 后可使用基于 HTTP 的 `HttpIntrospectionClient`；
+/// 也可自行实现以使用其他传输方式。
+/// With the `remote-introspection` feature, `HttpIntrospectionClient` talks HTTP;
+/// implement the trait yourself for other transports.
+#[async_trait]
+pub trait IntrospectionClient: Send + Sync {
+    /// 内省 token | Introspect a token
+    async fn introspect(&self, token: &str) -> SaTokenResult<IntrospectionResponse>;
+}
+
+/// 基于 HTTP 的内省客户端 | HTTP introspection client
+///
+/// 以 `application/x-www-form-urlencoded` POST `token` 到内省端点，
+/// 配置了客户端凭证时使用 HTTP Basic 认证。
+/// POSTs `token` as `application/x-www-form-urlencoded` to the endpoint,
+/// authenticating with HTTP Basic when client credentials are set.
+#[cfg(feature = "remote-introspection")]
+#[derive(Clone)]
+pub struct HttpIntrospectionClient {
+    endpoint: String,
+    credentials: Option<(String, String)>,
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "remote-introspection")]
+impl HttpIntrospectionClient {
+    /// 创建客户端 | Create a client
+    ///
+    /// # 参数 | Parameters
+    /// * `endpoint` - 内省端点 URL | Introspection endpoint URL
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            credentials: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// 设置客户端凭证 | Set the client credentials
+    pub fn credentials(mut self, client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        self.credentials = Some((client_id.into(), client_secret.into()));
+        self
+    }
+
+    /// 使用自定义的 reqwest 客户端（超时、代理等）| Use a custom reqwest client (timeouts, proxies, ...)
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+}
+
+#[cfg(feature = "remote-introspection")]
+#[async_trait]
+impl IntrospectionClient for HttpIntrospectionClient {
+    async fn introspect(&self, token: &str) -> SaTokenResult<IntrospectionResponse> {
+        let mut request = self.http.post(&self.endpoint)
+            .form(&[("token", token), ("token_type_hint", "access_token")]);
+        if let Some((client_id, client_secret)) = &self.credentials {
+            request = request.basic_auth(client_id, Some(client_secret));
+        }
+
+        let response = request.send().await
+            .map_err(|e| SaTokenError::InternalError(format!("introspection request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(SaTokenError::InternalError(
+                format!("introspection endpoint returned {}", response.status()),
+            ));
+        }
+        let body = response.text().await
+            .map_err(|e| SaTokenError::InternalError(format!("introspection request failed: {}", e)))?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+/// 远程内省校验器 | Remote introspection validator
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let validator = RemoteIntrospectionValidator::new(
+///     HttpIntrospectionClient::new("https://auth.example.com/oauth2/introspect")
+///         .credentials("resource-server", "secret"),
+/// )
+/// .cache_ttl(60)
+/// .negative_ttl(10);
+///
+/// let info = validator.validate(&token).await?;
+/// let login_id = info.login_id();
+/// ```
+#[derive(Clone)]
+pub struct RemoteIntrospectionValidator {
+    client: Arc<dyn IntrospectionClient>,
+    cache_ttl: i64,
+    negative_ttl: i64,
+    cache: Arc<RwLock<HashMap<String, (IntrospectionResponse, Instant)>>>,
+}
+
+impl RemoteIntrospectionValidator {
+    /// 创建校验器（有效结果缓存 60 秒，无效结果缓存 10 秒）
+    /// Create a validator (active results cached for 60s, inactive results for 10s)
+    pub fn new(client: impl IntrospectionClient + 'static) -> Self {
+        Self::with_client(Arc::new(client))
+    }
+
+    /// 使用共享的客户端创建校验器 | Create a validator with a shared client
+    pub fn with_client(client: Arc<dyn IntrospectionClient>) -> Self {
+        Self {
+            client,
+            cache_ttl: 60,
+            negative_ttl: 10,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// 有效结果的缓存时长（秒），`<= 0` 表示不缓存 | Cache time of active results in seconds, `<= 0` disables caching
+    pub fn cache_ttl(mut self, seconds: i64) -> Self {
+        self.cache_ttl = seconds;
+        self
+    }
+
+    /// 无效结果的缓存时长（秒），`<= 0` 表示不缓存 | Cache time of inactive results in seconds, `<= 0` disables caching
+    pub fn negative_ttl(mut self, seconds: i64) -> Self {
+        self.negative_ttl = seconds;
+        self
+    }
+
+    /// 内省 token，优先使用缓存 | Introspect a token, using the cache first
+    pub async fn introspect(&self, token: &str) -> SaTokenResult<IntrospectionResponse> {
+        if let Some((response, expires_at)) = self.cache.read().await.get(token)
+            && *expires_at > Instant::now() {
+            return Ok(response.clone());
+        }
+
+        let response = self.client.introspect(token).await?;
+        let ttl = if response.active {
+            // 不超过 token 自身的剩余有效期 | Never beyond the token's own remaining lifetime
+            match response.exp {
+                Some(exp) => self.cache_ttl.min(exp - Utc::now().timestamp()),
+                None => self.cache_ttl,
+            }
+        } else {
+            self.negative_ttl
+        };
+        if ttl > 0 {
+            let now = Instant::now();
+            let mut cache = self.cache.write().await;
+            cache.retain(|_, (_, expires_at)| *expires_at > now);
+            cache.insert(token.to_string(), (response.clone(), now + Duration::from_secs(ttl as u64)));
+        }
+        Ok(response)
+    }
+
+    /// 校验 token，无效或已过期时返回错误 | Validate a token, erroring when inactive or expired
+    pub async fn validate(&self, token: &str) -> SaTokenResult<IntrospectionResponse> {
+        let response = self.introspect(token).await?;
+        if !response.active {
+            return Err(SaTokenError::InvalidToken("token is not active".to_string()));
+        }
+        if response.exp.is_some_and(|exp| exp <= Utc::now().timestamp()) {
+            return Err(SaTokenError::TokenExpired);
+        }
+        Ok(response)
+    }
+
+    /// token 是否有效（请求失败视为无效）| Whether the token is active (a failed request counts as inactive)
+    pub async fn is_active(&self, token: &str) -> bool {
+        self.validate(token).await.is_ok()
+    }
+
+    /// 移除某个 token 的缓存（如收到吊销通知时）| Drop the cached result of a token (e.g. on a revocation notice)
+    pub async fn invalidate(&self, token: &str) {
+        self.cache.write().await.remove(token);
+    }
+
+    /// 清空缓存 | Clear the cache
+    pub async fn clear_cache(&self) {
+        self.cache.write().await.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingClient(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl IntrospectionClient for CountingClient {
+        async fn introspect(&self, token: &str) -> SaTokenResult<IntrospectionResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            match token {
+                "good" => Ok(serde_json::from_str(
+                    r#"{"active":true,"sub":"1001","scope":"read write","exp":4102444800,"tenant":"acme"}"#,
+                )?),
+                "down" => Err(SaTokenError::InternalError("unreachable".to_string())),
+                _ => Ok(IntrospectionResponse::default()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_introspection_cache() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let validator = RemoteIntrospectionValidator::new(CountingClient(calls.clone()));
+
+        let info = validator.validate("good").await.unwrap();
+        assert_eq!(info.login_id(), Some("1001"));
+        assert!(info.has_scope("write"));
+        assert_eq!(info.extra["tenant"], "acme");
+        validator.validate("good").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 无效结果同样被缓存 | Inactive results are cached as well
+        assert!(matches!(validator.validate("revoked").await, Err(SaTokenError::InvalidToken(_))));
+        assert!(!validator.is_active("revoked").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 请求失败不缓存 | Failed requests are not cached
+        assert!(validator.validate("down").await.is_err());
+        assert!(validator.validate("down").await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        validator.invalidate("good").await;
+        validator.validate("good").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+        let uncached = RemoteIntrospectionValidator::new(CountingClient(calls.clone())).negative_ttl(0);
+        uncached.is_active("revoked").await;
+        uncached.is_active("revoked").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 7);
+    }
+}
@@ -30,10 +30,47 @@ pub mod event;
 pub mod nonce;
 pub mod refresh;
 pub mod oauth2;
+pub mod consent;
+pub mod client_store;
+pub mod oidc;
 pub mod ws;
 pub mod online;
 pub mod distributed;
 pub mod sso;
+pub mod ticket_store;
+pub mod cas;
+#[cfg(feature = "saml")]
+pub mod saml;
+pub mod analytics;
+pub mod schema;
+pub mod off_session;
+pub mod sign;
+pub mod same_token;
+pub mod api_key;
+pub mod introspection;
+#[cfg(feature = "propagation")]
+pub mod propagation;
+pub mod basic;
+pub mod warmup;
+pub mod csrf;
+pub mod logging;
+pub mod id;
+pub mod rate_limit;
+pub mod login_guard;
+pub mod totp;
+pub mod secure;
+pub mod qr_login;
+pub mod social;
+pub mod login_link;
+pub mod remember_me;
+pub mod encrypted_storage;
+pub mod tenant;
+pub mod cluster;
+pub mod stateless;
+pub mod page;
+pub mod response;
+pub mod guard;
+pub mod stp_logic;
 pub mod router;
 pub mod prelude;
 
@@ -42,28 +79,81 @@ mod manager;
 
 pub use error::{SaTokenError, SaTokenResult};
 pub use manager::SaTokenManager;
-pub use config::SaTokenConfig;
-pub use util::{StpUtil, LoginId};
+pub use config::{SaTokenConfig, SaCookieConfig};
+pub use util::{StpUtil, LoginId, parse_login_id};
 pub use context::SaTokenContext;
 
 // 重新导出核心类型
-pub use token::{TokenInfo, TokenValue, JwtManager, JwtClaims, JwtAlgorithm};
+pub use token::{TokenInfo, TokenValue, JwtManager, JwtClaims, JwtAlgorithm, JwksKeyRing, JwksClient, RemoteJwksValidator, JweAlgorithm, JweEncryption, ClaimsMapper, DefaultClaimsMapper};
+#[cfg(feature = "remote-jwks")]
+pub use token::HttpJwksClient;
 pub use session::SaSession;
-pub use permission::{PermissionChecker, RoleChecker};
+pub use permission::{PermissionChecker, RoleChecker, PermissionProvider, RoleHierarchy, MenuItem, PermissionSnapshot};
 pub use event::{
     SaTokenEvent, SaTokenEventType, SaTokenListener, 
     SaTokenEventBus, LoggingListener
 };
 pub use nonce::NonceManager;
-pub use refresh::RefreshTokenManager;
-pub use oauth2::{OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo};
-pub use ws::{WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor};
-pub use online::{OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher};
+pub use id::{IdGenerator, IdKind, RandomIdGenerator};
+pub use rate_limit::{RateLimitRule, RateLimitKey};
+pub use login_guard::{LoginGuard, LoginFailure};
+pub use totp::{TotpManager, TotpEnrollment};
+pub use secure::{SaSecureUtil, PasswordHasher, Argon2Hasher, Pbkdf2Hasher, SecretHasher};
+#[cfg(feature = "bcrypt")]
+pub use secure::BcryptHasher;
+pub use qr_login::{QrLoginManager, QrLoginState, QrTicket};
+pub use social::{
+    SocialLoginManager, SocialProvider, SocialProfile, SocialBinding, SocialApp, SocialHttpClient,
+    GitHubProvider, GoogleProvider, WeChatProvider, GiteeProvider,
+};
+#[cfg(feature = "social-http")]
+pub use social::HttpSocialClient;
+pub use login_link::LoginLink;
+pub use remember_me::{RememberMeRecord, RememberedSession};
+pub use refresh::{RefreshTokenManager, RotatedTokens};
+pub use encrypted_storage::EncryptedStorage;
+pub use tenant::{TenantResolver, TenantStorage};
+pub use cluster::{Invalidation, InvalidationMessage, InMemoryClusterChannel, DEFAULT_CLUSTER_CHANNEL};
+pub use oauth2::{
+    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo, PkceMethod, OAuth2TokenRequest,
+    OAuth2ErrorResponse, OAuth2IntrospectionRequest, OAuth2RevocationRequest, OAuth2AuthorizeRequest, OAuth2ConsentDecision,
+    OAuth2AuthorizeResponse, OAuth2Actor, DeviceAuthorization, DeviceCode, DeviceCodeStatus,
+    DEVICE_CODE_GRANT_TYPE, TOKEN_EXCHANGE_GRANT_TYPE, ACCESS_TOKEN_TYPE,
+};
+pub use ws::{WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor, until_revoked, DEFAULT_REVOCATION_CHECK_INTERVAL, DEFAULT_HEARTBEAT_TIMEOUT};
+pub use online::{OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher, TimedOutConnection, DEFAULT_DEVICE, DEVICE_KEY, OnlineFilter, OnlinePage, IP_KEY, OnlineStats, DEFAULT_LOGIN_WINDOW};
+pub use analytics::{UsageAnalytics, UserUsage};
+pub use schema::{SchemaMigrator, SCHEMA_VERSION};
+pub use warmup::{WarmupTask, WarmupReport, WarmupStep};
 pub use distributed::{
     DistributedSessionManager, DistributedSession, DistributedSessionStorage,
     ServiceCredential, InMemoryDistributedStorage
 };
 pub use sso::{
-    SsoServer, SsoClient, SsoManager, SsoTicket, SsoSession, SsoConfig
+    SsoServer, SsoClient, SsoManager, SsoTicket, SsoSession, SsoConfig, SsoLogoutNotifier
 };
-pub use router::{match_path, match_any, need_auth, PathAuthConfig};
+#[cfg(feature = "sso-http")]
+pub use sso::HttpSsoLogoutNotifier;
+pub use ticket_store::{TicketStore, StorageTicketStore, InMemoryTicketStore};
+pub use cas::{CasServer, CasAttributeProvider, CasLoginAction, CAS_NAMESPACE};
+#[cfg(feature = "saml")]
+pub use saml::{SamlServiceProvider, IdpMetadata, SamlAuthnRequest, SamlAssertion};
+pub use off_session::{OffSessionManager, ConsentRecord};
+pub use consent::{ConsentStore, OAuth2Grant};
+pub use client_store::{ClientStore, StorageClientStore};
+pub use oidc::{OidcProvider, OidcDiscovery, OidcEndpoints, IdTokenClaims, UserInfoProvider, OPENID_SCOPE};
+pub use sign::{BodySignVerifier, BodySignState, SaSignManager};
+pub use same_token::{SameTokenManager, SAME_TOKEN_HEADER};
+pub use api_key::{ApiKeyManager, ApiKeyInfo, API_KEY_HEADER};
+pub use basic::HttpBasicAuth;
+pub use csrf::{CsrfManager, CSRF_HEADER};
+pub use introspection::{RemoteIntrospectionValidator, IntrospectionClient, IntrospectionResponse};
+#[cfg(feature = "remote-introspection")]
+pub use introspection::HttpIntrospectionClient;
+#[cfg(feature = "propagation")]
+pub use propagation::TokenPropagatingClient;
+pub use response::{ResponseEnvelope, ErrorRenderer, DefaultErrorRenderer};
+pub use page::{PageRenderer, DefaultPageRenderer, PageBranding, SaPage, LoginPage, ConsentPage, ErrorPage, negotiate_locale};
+pub use guard::Guard;
+pub use stp_logic::StpLogic;
+pub use router::{match_path, match_any, need_auth, required_permission, required_role, PathAuthConfig, SaRouter, CompiledPattern, RouteRule, RouteRuleKind, RejectionRenderer};
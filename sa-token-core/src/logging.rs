@@ -0,0 +1,77 @@
+// Author: 金书记
+//
+//! 日志事件 | Log Events
+//!
+//! 所有日志都是带字段的结构化事件，按 target 分类，便于在生产环境按类别调整级别。
+//! Every log line is a structured event with fields, grouped by target so production setups can tune each category.
+//!
+//! | target | 级别 Level | 内容 Content |
+//! |--------|-----------|--------------|
+//! | `sa_token::extract`  | DEBUG | 从请求中提取 token | token extracted from a request |
+//! | `sa_token::validate` | DEBUG | token 校验结果 | token validation result |
+//! | `sa_token::reject`   | INFO  | 请求被拒绝 | request rejected |
+//! | `sa_token::session`  | DEBUG | 登出等会话变更 | session changes such as logout |
+//!
+//! ```text
+//! # 只保留拒绝日志 | Keep rejection logs only
+//! RUST_LOG=info,sa_token::reject=info,sa_token::extract=off
+//! ```
+//!
+//! 提取日志也可以通过配置 `mute_extract_log` 关闭。
+//! Extraction logs can also be turned off with the `mute_extract_log` option.
+
+use crate::config::SaTokenConfig;
+
+/// token 提取 | Token extraction
+pub const TARGET_EXTRACT: &str = "sa_token::extract";
+
+/// token 校验 | Token validation
+pub const TARGET_VALIDATE: &str = "sa_token::validate";
+
+/// 请求拒绝 | Request rejection
+pub const TARGET_REJECT: &str = "sa_token::reject";
+
+/// 会话变更 | Session changes
+pub const TARGET_SESSION: &str = "sa_token::session";
+
+/// 记录从请求中提取到 token | Log a token extracted from a request
+///
+/// # 参数 | Parameters
+/// * `config` - 配置，`mute_extract_log` 为 true 时不输出 | Config, nothing is logged when `mute_extract_log` is true
+/// * `middleware` - 中间件名称，如 `layer`、`login-check` | Middleware name, e.g. `layer`, `login-check`
+/// * `token` - 提取到的 token | Extracted token
+pub fn token_extracted(config: &SaTokenConfig, middleware: &str, token: &str) {
+    if !config.mute_extract_log {
+        tracing::debug!(target: TARGET_EXTRACT, middleware, token, "token extracted");
+    }
+}
+
+/// 记录 token 的来源 | Log where a token was found
+///
+/// # 参数 | Parameters
+/// * `config` - 配置 | Config
+/// * `source` - `header`、`cookie` 或 `query` | `header`, `cookie` or `query`
+/// * `name` - 请求头、Cookie 或参数名称 | Name of the header, cookie or parameter
+pub fn token_source(config: &SaTokenConfig, source: &str, name: &str) {
+    if !config.mute_extract_log {
+        tracing::debug!(target: TARGET_EXTRACT, source, name, "token found");
+    }
+}
+
+/// 记录请求中没有 token | Log that a request carries no token
+pub fn token_missing(config: &SaTokenConfig) {
+    if !config.mute_extract_log {
+        tracing::debug!(target: TARGET_EXTRACT, name = %config.token_name, "no token in request");
+    }
+}
+
+/// 记录被拒绝的请求 | Log a rejected request
+///
+/// # 参数 | Parameters
+/// * `middleware` - 中间件名称 | Middleware name
+/// * `path` - 请求路径 | Request path
+/// * `status` - 响应状态码 | Response status
+/// * `reason` - 拒绝原因 | Rejection reason
+pub fn rejected(middleware: &str, path: &str, status: u16, reason: &dyn std::fmt::Display) {
+    tracing::info!(target: TARGET_REJECT, middleware, path, status, reason = %reason, "request rejected");
+}
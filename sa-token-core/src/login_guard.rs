@@ -0,0 +1,205 @@
+// Author: 金书记
+//
+//! 登录保护 | Login Guard
+//!
+//! 统计每个账号的登录失败次数，窗口内失败次数达到上限后锁定账号，锁定时长按指数退避递增。
+//! Counts failed logins per account. Once the failures within the window reach the limit the
+//! account is locked, and each further lockout doubles the lock duration.
+//!
+//! ```text
+//! record_failure(login_id)
+//!   └─→ sa:login-guard:fail:{id} += 1（TTL = window）
+//!         └─→ >= max_failures
+//!               ├─→ sa:login-guard:level:{id} += 1（TTL = max_lockout）
+//!               ├─→ 锁定 | lock = min(lockout × 2^(level-1), max_lockout)
+//!               └─→ sa:login-guard:lock:{id}（TTL = lock）
+//!
+//! login(login_id) → is_locked? → AccountLocked(remaining)
+//!                 → 登录成功清除失败计数 | success clears the failure count
+//! ```
+//!
+//! 计数保存在 `SaStorage` 中，多实例共享；退避等级在 `max_lockout` 秒内没有新的锁定后自动归零。
+//! Counters live in `SaStorage`, so every instance sees the same numbers. The backoff level
+//! resets once no lockout has happened for `max_lockout` seconds.
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 一次失败登录的记录结果 | Outcome of recording a failed login
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginFailure {
+    /// 当前窗口内的失败次数 | Failures within the current window
+    pub failures: u32,
+    /// 本次触发或已存在的锁定剩余秒数 | Remaining lock seconds, if the account is locked
+    pub lockout: Option<u64>,
+}
+
+/// 登录保护 | Login guard
+#[derive(Clone)]
+pub struct LoginGuard {
+    storage: Arc<dyn SaStorage>,
+    max_failures: u32,
+    window: i64,
+    lockout: i64,
+    max_lockout: i64,
+    namespace: &'static str,
+}
+
+impl LoginGuard {
+    /// 创建登录保护 | Create a login guard
+    ///
+    /// # 参数 | Parameters
+    /// * `storage` - 计数存储 | Counter storage
+    /// * `max_failures` - 窗口内允许的失败次数，0 表示关闭 | Failures allowed per window, 0 disables the guard
+    /// * `window` - 失败计数窗口（秒）| Failure counting window in seconds
+    /// * `lockout` - 首次锁定时长（秒）| First lockout duration in seconds
+    /// * `max_lockout` - 锁定时长上限（秒）| Upper bound of the lockout duration in seconds
+    pub fn new(storage: Arc<dyn SaStorage>, max_failures: u32, window: i64, lockout: i64, max_lockout: i64) -> Self {
+        let lockout = lockout.max(1);
+        Self {
+            storage,
+            max_failures,
+            window: window.max(1),
+            lockout,
+            max_lockout: max_lockout.max(lockout),
+            namespace: "login-guard",
+        }
+    }
+
+    /// 设置计数键的命名空间（默认 `login-guard`），使其他校验（如 TOTP）的计数与登录失败互不影响
+    /// Set the namespace of the counter keys (`login-guard` by default), so other checks such as TOTP
+    /// keep counters separate from failed logins
+    pub fn namespace(mut self, namespace: &'static str) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// 是否启用 | Whether the guard is enabled
+    pub fn enabled(&self) -> bool {
+        self.max_failures > 0
+    }
+
+    /// 记录一次失败登录，达到上限时锁定账号 | Record a failed login and lock the account at the limit
+    ///
+    /// 账号已锁定时不再计数，直接返回剩余锁定时长
+    /// While the account is locked nothing is counted and the remaining lock is returned
+    pub async fn record_failure(&self, login_id: &str) -> SaTokenResult<LoginFailure> {
+        if !self.enabled() {
+            return Ok(LoginFailure { failures: 0, lockout: None });
+        }
+        if let Some(remaining) = self.is_locked(login_id).await? {
+            return Ok(LoginFailure { failures: self.max_failures, lockout: Some(remaining) });
+        }
+
+        let fail_key = self.fail_key(login_id);
+        let failures = self.storage.incr(&fail_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if failures == 1 {
+            self.storage.expire(&fail_key, Duration::from_secs(self.window as u64)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        let failures = failures.clamp(0, u32::MAX as i64) as u32;
+        if failures < self.max_failures {
+            return Ok(LoginFailure { failures, lockout: None });
+        }
+
+        let level_key = self.level_key(login_id);
+        let level = self.storage.incr(&level_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.expire(&level_key, Duration::from_secs(self.max_lockout as u64)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        let shift = (level - 1).clamp(0, 32) as u32;
+        let lock = self.lockout.saturating_mul(1i64 << shift).min(self.max_lockout) as u64;
+        let until = Utc::now().timestamp() + lock as i64;
+        self.storage.set(&self.lock_key(login_id), &until.to_string(), Some(Duration::from_secs(lock))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.delete(&fail_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        Ok(LoginFailure { failures, lockout: Some(lock) })
+    }
+
+    /// 查询账号是否锁定，返回剩余锁定秒数 | Check whether the account is locked, returning the remaining seconds
+    pub async fn is_locked(&self, login_id: &str) -> SaTokenResult<Option<u64>> {
+        if !self.enabled() {
+            return Ok(None);
+        }
+        let until = self.storage.get(&self.lock_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|v| v.parse::<i64>().ok());
+        let remaining = until.map(|until| until - Utc::now().timestamp()).filter(|r| *r > 0);
+        Ok(remaining.map(|r| r as u64))
+    }
+
+    /// 清除失败计数（登录成功后调用）| Clear the failure count (called after a successful login)
+    pub async fn clear_failures(&self, login_id: &str) -> SaTokenResult<()> {
+        if !self.enabled() {
+            return Ok(());
+        }
+        self.storage.delete(&self.fail_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 手动解锁并重置退避等级 | Unlock manually and reset the backoff level
+    pub async fn unlock(&self, login_id: &str) -> SaTokenResult<()> {
+        let keys = [self.fail_key(login_id), self.level_key(login_id), self.lock_key(login_id)];
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.storage.mdel(&keys).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    fn fail_key(&self, login_id: &str) -> String {
+        format!("sa:{}:fail:{}", self.namespace, login_id)
+    }
+
+    fn level_key(&self, login_id: &str) -> String {
+        format!("sa:{}:level:{}", self.namespace, login_id)
+    }
+
+    fn lock_key(&self, login_id: &str) -> String {
+        format!("sa:{}:lock:{}", self.namespace, login_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_lockout_with_backoff() {
+        let guard = LoginGuard::new(Arc::new(MemoryStorage::new()), 3, 60, 10, 25);
+
+        for expected in 1..3 {
+            let failure = guard.record_failure("alice").await.unwrap();
+            assert_eq!(failure, LoginFailure { failures: expected, lockout: None });
+        }
+        assert_eq!(guard.record_failure("alice").await.unwrap().lockout, Some(10));
+        assert!(guard.is_locked("alice").await.unwrap().is_some());
+        assert_eq!(guard.is_locked("bob").await.unwrap(), None);
+
+        // 锁定期间不再计数 | Nothing is counted while locked
+        let failure = guard.record_failure("alice").await.unwrap();
+        assert!(failure.lockout.is_some_and(|r| r <= 10));
+
+        // 第二次锁定时长翻倍，第三次受上限约束 | The second lock doubles, the third hits the cap
+        guard.storage.delete(&guard.lock_key("alice")).await.unwrap();
+        for _ in 0..3 {
+            guard.record_failure("alice").await.unwrap();
+        }
+        assert_eq!(guard.is_locked("alice").await.unwrap().map(|r| r > 10), Some(true));
+        guard.storage.delete(&guard.lock_key("alice")).await.unwrap();
+        for _ in 0..2 {
+            guard.record_failure("alice").await.unwrap();
+        }
+        assert_eq!(guard.record_failure("alice").await.unwrap().lockout, Some(25));
+
+        guard.unlock("alice").await.unwrap();
+        assert_eq!(guard.is_locked("alice").await.unwrap(), None);
+        assert_eq!(guard.record_failure("alice").await.unwrap().failures, 1);
+    }
+}
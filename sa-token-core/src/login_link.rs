@@ -0,0 +1,58 @@
+// Author: 金书记
+//
+//! 登录链接（免密邮件登录）| Login Links (Passwordless Email Login)
+//!
+//! 为账号签发一次性的登录链接 token，通过邮件等渠道发送；使用时直接登录并立即作废。
+//! Issues a single-use login token for an account, to be sent by email or similar; consuming it
+//! logs the account in and invalidates it at once.
+//!
+//! ```text
+//! create_login_link(login_id, ttl) → link token（sa:link:{token}）
+//!   └─→ https://example.com/magic?token=...
+//!
+//! consume_login_link(token)
+//!   ├─→ 有效 | valid    → 作废 + login(login_id) → LoginLinkConsumed 事件 | event
+//!   ├─→ 过期 | expired  → LoginLinkInvalid  + LoginLinkRejected("expired") 事件 | event
+//!   └─→ 重用 | reused   → LoginLinkReused   + LoginLinkRejected("reused") 事件 | event
+//! ```
+//!
+//! 记录在过期后仍保留 `REUSE_DETECTION_WINDOW` 秒，以便识别过期与重复使用（链接可能已被截获）。
+//! Records are kept for `REUSE_DETECTION_WINDOW` seconds past expiry so expired and reused links
+//! can be told apart (a reused link may have been intercepted).
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// 过期后保留记录用于识别重用的时长（秒）| Seconds a record is kept past expiry to detect reuse
+pub const REUSE_DETECTION_WINDOW: i64 = 86400;
+
+/// 登录链接记录 | Login link record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginLink {
+    /// 登录 ID | Login ID
+    pub login_id: String,
+    /// 登录类型 | Login type
+    pub login_type: String,
+    /// 创建时间 | Creation time
+    pub create_time: DateTime<Utc>,
+    /// 过期时间 | Expiration time
+    pub expire_time: DateTime<Utc>,
+    /// 使用时间，未使用为 None | When it was consumed, None while unused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used_time: Option<DateTime<Utc>>,
+}
+
+impl LoginLink {
+    /// 是否已过期 | Whether the link has expired
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.expire_time
+    }
+
+    pub(crate) fn key(token: &str) -> String {
+        format!("sa:link:{}", token)
+    }
+
+    pub(crate) fn claim_key(token: &str) -> String {
+        format!("sa:link:claimed:{}", token)
+    }
+}
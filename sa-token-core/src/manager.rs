@@ -3,17 +3,47 @@
 //! Token 管理器 - sa-token 的核心入口
 
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Duration, Utc};
 use tokio::sync::RwLock;
 use sa_token_adapter::storage::SaStorage;
-use crate::config::SaTokenConfig;
+use crate::config::{JwtMode, SaTokenConfig};
 use crate::error::{SaTokenError, SaTokenResult};
-use crate::token::{TokenInfo, TokenValue, TokenGenerator};
+use crate::token::{TokenInfo, TokenValue, DefaultTokenGenerator, DEFAULT_ACCOUNT_TYPE, namespaced_login_id};
 use crate::session::SaSession;
 use crate::event::{SaTokenEventBus, SaTokenEvent};
 use crate::online::OnlineManager;
+use crate::tenant::{self, TenantStorage};
+use crate::cluster::{Invalidation, InvalidationMessage};
+use sa_token_adapter::cluster::{ClusterChannel, ClusterMessageHandler};
 use crate::distributed::DistributedSessionManager;
+use crate::permission::{CircuitBreaker, CircuitState, MenuItem, PermissionProvider, RoleHierarchy};
+use crate::analytics::UsageAnalytics;
+use crate::schema::{self, SchemaMigrator, SCHEMA_VERSION};
+use crate::warmup::{WarmupReport, WarmupTask};
+use crate::nonce::NonceManager;
+use crate::login_guard::{LoginFailure, LoginGuard};
+use crate::totp::{TotpEnrollment, TotpManager};
+use crate::login_link::{LoginLink, REUSE_DETECTION_WINDOW};
+use crate::remember_me::{self, RememberMeRecord, RememberedSession};
+use crate::refresh::{RefreshTokenManager, RotatedTokens};
+use crate::stateless::{self, StatelessKeys};
+use crate::id::IdKind;
+use crate::id::{IdGenerator, RandomIdGenerator};
+use crate::response::ErrorRenderer;
+
+/// 权限缓存类别 | Permission cache kinds
+const PERMISSION_CACHE: &str = "permission";
+const ROLE_CACHE: &str = "role";
+
+/// (类别, 账号类型, login_id) | (kind, account type, login_id)
+type PermissionCacheKey = (&'static str, String, String);
+
+/// 缓存键 -> (列表, 过期时间) | cache key -> (list, expiry)
+type PermissionCache = HashMap<PermissionCacheKey, (Vec<String>, std::time::Instant)>;
+
+/// 集群订阅断开后重新订阅前的等待时间 | Delay before resubscribing after the cluster subscription ends
+const CLUSTER_RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
 
 /// sa-token 管理器
 #[derive(Clone)]
@@ -30,11 +60,73 @@ pub struct SaTokenManager {
     online_manager: Option<Arc<OnlineManager>>,
     /// 分布式 Session 管理器
     distributed_manager: Option<Arc<DistributedSessionManager>>,
+    /// Token 使用统计
+    usage_analytics: Option<Arc<UsageAnalytics>>,
+    /// 权限数据提供者（可在运行时注册）
+    permission_provider: Arc<std::sync::RwLock<Option<Arc<dyn PermissionProvider>>>>,
+    /// 角色层级（可在运行时配置）
+    role_hierarchy: Arc<std::sync::RwLock<Option<Arc<RoleHierarchy>>>>,
+    /// 拒绝响应渲染器 | Rejection renderer
+    error_renderer: Arc<std::sync::RwLock<Arc<dyn ErrorRenderer>>>,
+    /// `PermissionProvider` 结果缓存
+    permission_cache: Arc<RwLock<PermissionCache>>,
+    /// 正在后台刷新的缓存键
+    permission_refreshing: Arc<std::sync::Mutex<HashSet<PermissionCacheKey>>>,
+    /// `PermissionProvider` 熔断器
+    permission_breaker: Arc<CircuitBreaker>,
+    /// 存储数据迁移器
+    schema_migrators: Arc<std::sync::RwLock<Vec<Arc<dyn SchemaMigrator>>>>,
+    /// 启动预热任务
+    warmup_tasks: Arc<std::sync::RwLock<Vec<Arc<dyn WarmupTask>>>>,
+    /// 登录 nonce 管理器（`enable_nonce` 开启时使用）
+    nonce_manager: NonceManager,
+    /// 登录失败锁定（`login_max_failures` 大于 0 时启用）
+    login_guard: LoginGuard,
+    /// TOTP 双因素认证
+    totp_manager: TotpManager,
+    /// Refresh token 管理器
+    refresh_token_manager: RefreshTokenManager,
+    /// 唯一 ID 生成器
+    id_generator: Arc<dyn IdGenerator>,
+    /// 自包含 JWT 的密钥（Mixin / Stateless 模式使用）
+    jwt_keys: Option<StatelessKeys>,
+    /// 集群失效通知通道
+    cluster_channel: Option<Arc<dyn ClusterChannel>>,
+    /// 本实例 ID，用于忽略自己发出的集群消息
+    instance_id: String,
 }
 
 impl SaTokenManager {
     /// 创建新的管理器实例
     pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
+        // 开启多租户时所有键按租户隔离
+        let storage: Arc<dyn SaStorage> = if config.tenant_resolver.is_some() {
+            Arc::new(TenantStorage::new(storage))
+        } else {
+            storage
+        };
+        // nonce_timeout 为 -1 时使用 token timeout
+        let nonce_window = if config.nonce_timeout > 0 { config.nonce_timeout } else { config.timeout };
+        let id_generator = config.id_generator.clone()
+            .unwrap_or_else(|| Arc::new(RandomIdGenerator::default()));
+        let nonce_manager = NonceManager::new(storage.clone(), nonce_window)
+            .window(nonce_window)
+            .id_generator(id_generator.clone());
+        let login_guard = LoginGuard::new(
+            storage.clone(),
+            config.login_max_failures,
+            config.login_failure_window,
+            config.login_lockout,
+            config.login_lockout_max,
+        );
+        let totp_manager = TotpManager::new(storage.clone(), config.totp_issuer.clone());
+        let refresh_token_manager = RefreshTokenManager::new(storage.clone(), Arc::new(config.clone()));
+        let jwt_keys = matches!(config.jwt_mode, Some(JwtMode::Mixin | JwtMode::Stateless))
+            .then(|| StatelessKeys::from_config(&config));
+        let permission_breaker = CircuitBreaker::new(
+            config.permission_breaker_threshold,
+            std::time::Duration::from_secs(config.permission_breaker_cooldown.max(0) as u64),
+        );
         Self { 
             storage, 
             config,
@@ -43,6 +135,23 @@ impl SaTokenManager {
             event_bus: SaTokenEventBus::new(),
             online_manager: None,
             distributed_manager: None,
+            usage_analytics: None,
+            permission_provider: Arc::new(std::sync::RwLock::new(None)),
+            role_hierarchy: Arc::new(std::sync::RwLock::new(None)),
+            error_renderer: Arc::new(std::sync::RwLock::new(crate::response::default_error_renderer())),
+            permission_cache: Arc::new(RwLock::new(HashMap::new())),
+            permission_refreshing: Arc::new(std::sync::Mutex::new(HashSet::new())),
+            permission_breaker: Arc::new(permission_breaker),
+            schema_migrators: Arc::new(std::sync::RwLock::new(Vec::new())),
+            warmup_tasks: Arc::new(std::sync::RwLock::new(Vec::new())),
+            nonce_manager,
+            login_guard,
+            totp_manager,
+            refresh_token_manager,
+            id_generator,
+            jwt_keys,
+            cluster_channel: None,
+            instance_id: uuid::Uuid::new_v4().to_string(),
         }
     }
     
@@ -56,6 +165,26 @@ impl SaTokenManager {
         self
     }
     
+    pub fn with_usage_analytics(mut self, analytics: Arc<UsageAnalytics>) -> Self {
+        self.usage_analytics = Some(analytics);
+        self
+    }
+    
+    /// 接入集群失效通知通道，登出、踢人与权限缓存失效会广播给其他实例
+    /// Plug in a cluster invalidation channel; logout, kick-out and permission cache invalidation are broadcast to other instances
+    /// 
+    /// 调用 `start_cluster_sync` 才会应用其他实例的消息
+    /// Messages from other instances are only applied after `start_cluster_sync`
+    pub fn with_cluster_channel(mut self, channel: Arc<dyn ClusterChannel>) -> Self {
+        self.cluster_channel = Some(channel);
+        self
+    }
+    
+    /// 本实例 ID | ID of this instance
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+    
     pub fn online_manager(&self) -> Option<&Arc<OnlineManager>> {
         self.online_manager.as_ref()
     }
@@ -64,11 +193,160 @@ impl SaTokenManager {
         self.distributed_manager.as_ref()
     }
     
+    pub fn usage_analytics(&self) -> Option<&Arc<UsageAnalytics>> {
+        self.usage_analytics.as_ref()
+    }
+    
+    /// 记录一次 token 访问，未启用使用统计时忽略 | Record a token access, ignored when usage analytics is disabled
+    /// 
+    /// 统计失败只记录日志，不影响请求 | Failures are only logged and never fail the request
+    /// 
+    /// # 参数 | Parameters
+    /// * `token_info` - 已校验的 token 信息 | Validated token info
+    /// * `route` - 访问的路由 | Accessed route
+    pub async fn record_access(&self, token_info: &TokenInfo, route: &str) {
+        if let Some(analytics) = &self.usage_analytics
+            && let Err(e) = analytics.record(&token_info.login_id, token_info.token.as_str(), route).await {
+            tracing::warn!("Failed to record token usage of {}: {}", token_info.login_id, e);
+        }
+    }
+    
+    /// 记录一次已认证请求的活跃时间与客户端 IP，由中间件调用 | Record the activity and client IP of an authenticated request, called by the middleware
+    /// 
+    /// 距上次写回不足 `activity_record_interval` 秒且 IP 未变化时不写存储；写回前重新读取 token 信息，
+    /// 不会覆盖自动续签等并发更新。JWT 模式下 token 信息不在存储中，不记录。失败只记录日志，不影响请求
+    /// Skips the storage write when less than `activity_record_interval` seconds passed since the last one
+    /// and the IP is unchanged; re-reads the token info before writing so concurrent updates such as
+    /// auto-renewal are kept. Not recorded in JWT modes, where token info is not stored. Failures are only logged
+    /// 
+    /// # 参数 | Parameters
+    /// * `token_info` - 已校验的 token 信息 | Validated token info
+    /// * `client_ip` - 当前请求的客户端 IP | Client IP of the current request
+    pub async fn record_activity(&self, token_info: &TokenInfo, client_ip: Option<&str>) {
+        let interval = self.config.activity_record_interval;
+        if interval < 0 || self.jwt_keys.is_some() {
+            return;
+        }
+        let now = Utc::now();
+        let ip_changed = client_ip.is_some() && client_ip != token_info.last_ip.as_deref();
+        if !ip_changed && (now - token_info.last_active_time).num_seconds() < interval {
+            return;
+        }
+        if let Err(e) = self.write_activity(&token_info.token, client_ip, now).await {
+            tracing::warn!("Failed to record activity of {}: {}", token_info.login_id, e);
+        }
+    }
+    
+    async fn write_activity(&self, token: &TokenValue, client_ip: Option<&str>, now: DateTime<Utc>) -> SaTokenResult<()> {
+        let key = format!("sa:token:{}", token.as_str());
+        let Some(value) = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))? else {
+            return Ok(());
+        };
+        let mut token_info: TokenInfo = serde_json::from_str(&value)
+            .map_err(SaTokenError::SerializationError)?;
+        token_info.last_active_time = now;
+        if let Some(ip) = client_ip {
+            token_info.last_ip = Some(ip.to_string());
+        }
+        let ttl = match token_info.expire_time {
+            Some(expire_time) if expire_time <= now => return Ok(()),
+            Some(expire_time) => (expire_time - now).to_std().ok(),
+            None => None,
+        };
+        let value = serde_json::to_string(&token_info)
+            .map_err(SaTokenError::SerializationError)?;
+        self.storage.set(&key, &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 获取账号最近一次登录的 token 的最后活跃时间，未登录时返回 None | Get the last active time of an account's latest login token, None when not logged in
+    /// 
+    /// 直接读取存储，不触发自动续签；JWT 模式下返回 None
+    /// Reads storage directly without auto-renewal; None in JWT modes
+    /// 
+    /// # 参数 | Parameters
+    /// * `login_id` - 登录 ID | Login ID
+    /// * `account_type` - 账号类型 | Account type
+    pub async fn get_last_active(&self, login_id: &str, account_type: &str) -> SaTokenResult<Option<DateTime<Utc>>> {
+        let Some(token) = self.storage.get(&Self::login_token_key(login_id, account_type)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))? else {
+            return Ok(None);
+        };
+        let value = self.storage.get(&format!("sa:token:{}", token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let token_info = value.and_then(|value| serde_json::from_str::<TokenInfo>(&value).ok());
+        Ok(token_info.filter(|info| !info.is_expired()).map(|info| info.last_active_time))
+    }
+    
     /// 获取事件总线的引用
     pub fn event_bus(&self) -> &SaTokenEventBus {
         &self.event_bus
     }
     
+    /// 获取唯一 ID 生成器
+    pub fn id_generator(&self) -> &Arc<dyn IdGenerator> {
+        &self.id_generator
+    }
+    
+    /// 获取登录 nonce 管理器（窗口为 `nonce_timeout`）
+    pub fn nonce_manager(&self) -> &NonceManager {
+        &self.nonce_manager
+    }
+    
+    /// 获取登录失败锁定
+    pub fn login_guard(&self) -> &LoginGuard {
+        &self.login_guard
+    }
+    
+    /// 获取 TOTP 双因素认证管理器
+    pub fn totp_manager(&self) -> &TotpManager {
+        &self.totp_manager
+    }
+    
+    /// 获取 Refresh token 管理器
+    pub fn refresh_token_manager(&self) -> &RefreshTokenManager {
+        &self.refresh_token_manager
+    }
+    
+    /// 记录一次登录失败（如密码错误），达到上限时锁定账号并触发 `AccountLocked` 事件
+    pub async fn record_failed_login(&self, login_id: &str) -> SaTokenResult<LoginFailure> {
+        self.record_failed_login_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 记录指定账号类型下的一次登录失败，不同账号类型的相同 login_id 分别计数
+    pub async fn record_failed_login_by_type(&self, login_id: &str, login_type: &str) -> SaTokenResult<LoginFailure> {
+        let guard_id = namespaced_login_id(login_id, login_type);
+        let already_locked = self.login_guard.is_locked(&guard_id).await?.is_some();
+        let failure = self.login_guard.record_failure(&guard_id).await?;
+        if !already_locked && let Some(lock_seconds) = failure.lockout {
+            let event = SaTokenEvent::account_locked(login_id, failure.failures, lock_seconds)
+                .with_login_type(login_type);
+            self.event_bus.publish(event).await;
+        }
+        Ok(failure)
+    }
+    
+    /// 查询账号是否因登录失败被锁定，返回剩余锁定秒数
+    pub async fn is_locked(&self, login_id: &str) -> SaTokenResult<Option<u64>> {
+        self.is_locked_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 查询指定账号类型下的账号是否被锁定，返回剩余锁定秒数
+    pub async fn is_locked_by_type(&self, login_id: &str, login_type: &str) -> SaTokenResult<Option<u64>> {
+        self.login_guard.is_locked(&namespaced_login_id(login_id, login_type)).await
+    }
+    
+    /// 解除登录失败锁定并重置退避等级
+    pub async fn unlock_login(&self, login_id: &str) -> SaTokenResult<()> {
+        self.unlock_login_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 解除指定账号类型下的登录失败锁定
+    pub async fn unlock_login_by_type(&self, login_id: &str, login_type: &str) -> SaTokenResult<()> {
+        self.login_guard.unlock(&namespaced_login_id(login_id, login_type)).await
+    }
+    
     /// 登录：为指定账号创建 token
     pub async fn login(&self, login_id: impl Into<String>) -> SaTokenResult<TokenValue> {
         self.login_with_options(login_id, None, None, None, None, None).await
@@ -106,14 +384,18 @@ impl SaTokenManager {
     ) -> SaTokenResult<TokenValue> {
         let login_id = login_id.into();
         
-        // 生成 token（支持 JWT）
-        let token = TokenGenerator::generate_with_login_id(&self.config, &login_id);
+        // 生成 token（支持 JWT），Mixin / Stateless 模式在 login_with_token_info 中签发
+        let token = if self.jwt_keys.is_some() {
+            TokenValue::new(String::new())
+        } else {
+            DefaultTokenGenerator::generate_with_device(&self.config, &login_id, device.as_deref())
+        };
         
         // 创建 token 信息
         let mut token_info = TokenInfo::new(token.clone(), login_id.clone());
         
         // 设置登录类型
-        token_info.login_type = login_type.unwrap_or_else(|| "default".to_string());
+        token_info.login_type = login_type.unwrap_or_else(|| DEFAULT_ACCOUNT_TYPE.to_string());
         
         // 设置设备标识
         if let Some(device_str) = device {
@@ -171,9 +453,19 @@ impl SaTokenManager {
     pub async fn login_with_token_info(&self, mut token_info: TokenInfo) -> SaTokenResult<TokenValue> {
         let login_id = token_info.login_id.clone();
         
-        // 如果 token_info 中没有 token，则生成一个
-        let token = if token_info.token.as_str().is_empty() {
-            TokenGenerator::generate_with_login_id(&self.config, &login_id)
+        // 因登录失败次数过多被锁定的账号不能登录
+        if let Some(remaining) = self.login_guard.is_locked(&token_info.namespaced_login_id()).await? {
+            return Err(SaTokenError::AccountLocked(remaining));
+        }
+        
+        // 开启防重放时，登录携带的 nonce 只能使用一次
+        if self.config.enable_nonce && let Some(nonce) = &token_info.nonce {
+            self.nonce_manager.validate_and_consume(nonce, &login_id).await?;
+        }
+        
+        // 如果 token_info 中没有 token，则生成一个（Mixin / Stateless 模式稍后签发 JWT）
+        let token = if self.jwt_keys.is_some() || token_info.token.as_str().is_empty() {
+            DefaultTokenGenerator::generate_with_device(&self.config, &login_id, token_info.device.as_deref())
         } else {
             token_info.token.clone()
         };
@@ -186,39 +478,71 @@ impl SaTokenManager {
         
         // 如果过期时间为 None，使用配置的过期时间
         let now = Utc::now();
-        if token_info.expire_time.is_none() {
-            if let Some(timeout) = self.config.timeout_duration() {
-                token_info.expire_time = Some(now + Duration::from_std(timeout).unwrap());
-            }
+        if token_info.expire_time.is_none()
+            && let Some(timeout) = self.config.timeout_duration() {
+            token_info.expire_time = Some(now + Duration::from_std(timeout).unwrap());
         }
         
         // 确保登录类型不为空
         if token_info.login_type.is_empty() {
-            token_info.login_type = "default".to_string();
+            token_info.login_type = DEFAULT_ACCOUNT_TYPE.to_string();
+        }
+        
+        // 开启 IP / 设备指纹绑定时，记录当前请求的客户端 IP 与设备指纹
+        if (self.config.bind_ip || self.config.bind_fingerprint)
+            && let Some(ctx) = crate::SaTokenContext::get_current() {
+            if self.config.bind_ip && token_info.login_ip.is_none() {
+                token_info.login_ip = ctx.client_ip;
+            }
+            if self.config.bind_fingerprint && token_info.fingerprint.is_none() {
+                token_info.fingerprint = ctx.fingerprint;
+            }
+        }
+        
+        // Mixin / Stateless 模式：全部信息写入 JWT 声明，不保存 token 信息
+        if let Some(keys) = &self.jwt_keys {
+            // Mixin 模式不允许并发登录时，吊销之前签发的 token
+            if self.is_mixin() && !self.config.is_concurrent {
+                self.revoke_jwt_before(&token_info.namespaced_login_id(), token_info.create_time).await?;
+            }
+            let permissions = self.granted_permissions(&login_id, &token_info.login_type).await;
+            let roles = self.granted_roles(&login_id, &token_info.login_type).await;
+            let token = stateless::encode(keys, &token_info, permissions, roles)?;
+            
+            let event = SaTokenEvent::login(login_id.clone(), token.as_str())
+                .with_login_type(&token_info.login_type);
+            self.event_bus.publish(event).await;
+            self.login_guard.clear_failures(&token_info.namespaced_login_id()).await?;
+            return Ok(token);
         }
         
         // 存储 token 信息
         let key = format!("sa:token:{}", token.as_str());
         let value = serde_json::to_string(&token_info)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         self.storage.set(&key, &value, self.config.timeout_duration()).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
         // 保存 login_id 到 token 的映射（用于根据 login_id 查找 token）
-        // 如果 login_type 不为空，使用包含 login_type 的 key 格式避免冲突
-        // If login_type is not empty, use key format with login_type to avoid conflicts
-        let login_token_key = if !token_info.login_type.is_empty() && token_info.login_type != "default" {
-            format!("sa:login:token:{}:{}", login_id, token_info.login_type)
-        } else {
-            format!("sa:login:token:{}", login_id)
-        };
+        // 按账号类型命名空间化，避免不同账号类型的相同 login_id 冲突
+        // Namespaced by account type to avoid collisions of the same login_id across account types
+        let login_token_key = Self::login_token_key(&login_id, &token_info.login_type);
         self.storage.set(&login_token_key, token.as_str(), self.config.timeout_duration()).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
-        // 如果不允许并发登录，踢掉之前的 token
+        // 维护会话属性索引
+        self.update_indexes(&token_info, true).await?;
+        
+        // 如果不允许并发登录，踢掉之前的 token（仅限同一账号类型）
         if !self.config.is_concurrent {
-            self.logout_by_login_id(&login_id).await?;
+            self.logout_by_login_id_and_type(&login_id, &token_info.login_type).await?;
+        }
+        
+        // 如果有在线用户管理，登记登录设备
+        if let Some(online_mgr) = &self.online_manager {
+            let device = token_info.device.as_deref().unwrap_or(crate::online::DEFAULT_DEVICE);
+            online_mgr.register_device(&token_info.namespaced_login_id(), token.as_str(), device).await;
         }
         
         // 触发登录事件
@@ -226,54 +550,93 @@ impl SaTokenManager {
             .with_login_type(&token_info.login_type);
         self.event_bus.publish(event).await;
         
+        // 登录成功后清除失败计数
+        self.login_guard.clear_failures(&token_info.namespaced_login_id()).await?;
+        
         Ok(token)
     }
     
     /// 登出：删除指定 token
     pub async fn logout(&self, token: &TokenValue) -> SaTokenResult<()> {
-        tracing::debug!("Manager: 开始 logout，token: {}", token);
+        // Mixin 模式写入吊销标记；Stateless token 无法吊销，只触发登出事件
+        // Mixin writes a revocation marker; Stateless tokens cannot be revoked, only the event is published
+        if let Some(keys) = &self.jwt_keys {
+            if let Ok(stateless::DecodedToken { info, jti }) = stateless::decode(keys, token) {
+                if self.is_mixin() && let (Some(jti), Some(expire_time)) = (jti, info.expire_time) {
+                    let remaining = (expire_time - Utc::now()).num_seconds().max(1) as u64;
+                    self.storage.set(&stateless::revoked_key(&jti), "1", Some(std::time::Duration::from_secs(remaining))).await
+                        .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+                }
+                let event = SaTokenEvent::logout(&info.login_id, token.as_str())
+                    .with_login_type(&info.login_type);
+                self.event_bus.publish(event).await;
+            }
+            return Ok(());
+        }
         
         // 先从存储获取 token 信息，用于触发事件（不调用 get_token_info 避免递归）
         let key = format!("sa:token:{}", token.as_str());
-        tracing::debug!("Manager: 查询 token 信息，key: {}", key);
-        
         let token_info_str = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
-        let token_info = if let Some(value) = token_info_str {
-            tracing::debug!("Manager: 找到 token 信息: {}", value);
-            serde_json::from_str::<TokenInfo>(&value).ok()
-        } else {
-            tracing::debug!("Manager: 未找到 token 信息");
-            None
-        };
+        let token_info = token_info_str.and_then(|value| serde_json::from_str::<TokenInfo>(&value).ok());
         
-        // 删除 token
-        tracing::debug!("Manager: 删除 token，key: {}", key);
+        // 删除 token 及其二级认证状态
         self.storage.delete(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
-        tracing::debug!("Manager: token 已从存储中删除");
+        let _ = self.storage.delete(&format!("sa:safe:{}", token.as_str())).await;
+        let _ = self.storage.delete(&Self::two_factor_key(token)).await;
+        let _ = self.storage.delete(&Self::freeze_key(token)).await;
+        let _ = self.storage.delete(&Self::soft_logout_key(token)).await;
+        
+        // 从会话属性索引中移除，并使权限缓存失效
+        if let Some(info) = &token_info {
+            let _ = self.update_indexes(info, false).await;
+            self.drop_permission_cache(&info.login_id).await;
+            self.publish_invalidation(Invalidation::Logout {
+                login_id: info.login_id.clone(),
+                login_type: info.account_type().to_string(),
+                token: token.as_str().to_string(),
+            }).await;
+        }
         
         // 触发登出事件
         if let Some(info) = token_info.clone() {
-            tracing::debug!("Manager: 触发登出事件，login_id: {}, login_type: {}", info.login_id, info.login_type);
             let event = SaTokenEvent::logout(&info.login_id, token.as_str())
                 .with_login_type(&info.login_type);
             self.event_bus.publish(event).await;
             
             // 如果有在线用户管理，通知用户下线
             if let Some(online_mgr) = &self.online_manager {
-                tracing::debug!("Manager: 标记用户下线，login_id: {}", info.login_id);
-                online_mgr.mark_offline(&info.login_id, token.as_str()).await;
+                online_mgr.mark_offline(&info.namespaced_login_id(), token.as_str()).await;
             }
         }
         
-        tracing::debug!("Manager: logout 完成，token: {}", token);
+        tracing::debug!(
+            target: crate::logging::TARGET_SESSION,
+            token = %token,
+            login_id = token_info.as_ref().map(|info| info.login_id.as_str()),
+            "logout"
+        );
         Ok(())
     }
     
-    /// 根据登录 ID 登出所有 token
+    /// 根据登录 ID 登出默认账号类型下的所有 token
     pub async fn logout_by_login_id(&self, login_id: &str) -> SaTokenResult<()> {
+        self.logout_by_login_id_and_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 根据登录 ID 和账号类型登出所有 token
+    /// 
+    /// # 参数 | Parameters
+    /// * `login_id` - 登录用户 ID | Login user ID
+    /// * `account_type` - 账号类型（即 `login_type`）| Account type (i.e. `login_type`)
+    pub async fn logout_by_login_id_and_type(&self, login_id: &str, account_type: &str) -> SaTokenResult<()> {
+        // Mixin 模式：吊销该账号此前签发的全部 token
+        if self.is_mixin() {
+            self.revoke_jwt_before(&namespaced_login_id(login_id, account_type), Utc::now()).await?;
+        }
+        
         // 获取所有 token 键的前缀
         let token_prefix = "sa:token:";
         
@@ -285,8 +648,8 @@ impl SaTokenManager {
                 if let Ok(Some(token_info_str)) = self.storage.get(&key).await {
                     // 反序列化 token 信息
                     if let Ok(token_info) = serde_json::from_str::<TokenInfo>(&token_info_str) {
-                        // 如果 login_id 匹配，则登出该 token
-                        if token_info.login_id == login_id {
+                        // 如果 login_id 与账号类型都匹配，则登出该 token
+                        if token_info.login_id == login_id && token_info.account_type() == account_type {
                             // 提取 token 字符串（从键中移除前缀）
                             let token_str = key[token_prefix.len()..].to_string();
                             let token = TokenValue::new(token_str);
@@ -299,18 +662,32 @@ impl SaTokenManager {
             }
         }
         
+        // 登出所有设备时一并撤销 remember-me token，避免被静默重建会话
+        self.revoke_remember_tokens(login_id, account_type).await?;
+        
         Ok(())
     }
     
     /// 获取 token 信息
     pub async fn get_token_info(&self, token: &TokenValue) -> SaTokenResult<TokenInfo> {
+        // Mixin / Stateless 模式：验证 JWT 签名与声明，Mixin 另外检查吊销标记
+        if let Some(keys) = &self.jwt_keys {
+            let stateless::DecodedToken { info, jti } = stateless::decode(keys, token)?;
+            if self.is_mixin() && self.is_jwt_revoked(&info, jti.as_deref()).await? {
+                return Err(SaTokenError::TokenNotFound);
+            }
+            return Ok(info);
+        }
+        
         let key = format!("sa:token:{}", token.as_str());
         let value = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
             .ok_or(SaTokenError::TokenNotFound)?;
         
-        let token_info: TokenInfo = serde_json::from_str(&value)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+        let token_info: TokenInfo = match serde_json::from_str(&value) {
+            Ok(info) => info,
+            Err(e) => return Err(self.deserialization_error(e).await),
+        };
         
         // 检查是否过期
         if token_info.is_expired() {
@@ -319,13 +696,27 @@ impl SaTokenManager {
             return Err(SaTokenError::TokenExpired);
         }
         
+        // 冻结中的 token 暂不可用，也不续签
+        if self.is_frozen(token).await {
+            return Err(SaTokenError::TokenFrozen);
+        }
+        
+        // 软登出：宽限期内需要重新认证，超过宽限期则彻底登出
+        if let Some(deadline) = self.soft_logout_deadline(token).await {
+            if Utc::now().timestamp() > deadline {
+                self.logout(token).await?;
+                return Err(SaTokenError::TokenExpired);
+            }
+            return Err(SaTokenError::ReauthRequired);
+        }
+        
         // 如果开启了自动续签，则自动续签
         // 注意：为了避免递归调用 get_token_info，这里直接更新过期时间
         if self.config.auto_renew {
             let renew_timeout = if self.config.active_timeout > 0 {
                 self.config.active_timeout
             } else {
-                self.config.timeout
+                self.config.effective_timeout()
             };
             
             // 直接续签（不递归调用 get_token_info）
@@ -335,23 +726,98 @@ impl SaTokenManager {
         Ok(token_info)
     }
     
+    /// 是否为 Mixin JWT 模式 | Whether the Mixin JWT mode is on
+    fn is_mixin(&self) -> bool {
+        self.config.jwt_mode == Some(JwtMode::Mixin)
+    }
+    
+    /// 吊销某账号在 `before` 之前签发的 Mixin token | Revoke the Mixin tokens an account was issued before `before`
+    async fn revoke_jwt_before(&self, namespaced_login_id: &str, before: DateTime<Utc>) -> SaTokenResult<()> {
+        self.storage.set(
+            &stateless::revoked_before_key(namespaced_login_id),
+            &before.timestamp_millis().to_string(),
+            self.config.timeout_duration(),
+        ).await.map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// Mixin token 是否已被登出或踢下线 | Whether a Mixin token was logged out or kicked out
+    async fn is_jwt_revoked(&self, info: &TokenInfo, jti: Option<&str>) -> SaTokenResult<bool> {
+        if let Some(jti) = jti && self.storage.exists(&stateless::revoked_key(jti)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))? {
+            return Ok(true);
+        }
+        let before = self.storage.get(&stateless::revoked_before_key(&info.namespaced_login_id())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|millis| millis.parse::<i64>().ok());
+        Ok(before.is_some_and(|before| info.create_time.timestamp_millis() < before))
+    }
+    
     /// 检查 token 是否有效
     pub async fn is_valid(&self, token: &TokenValue) -> bool {
         self.get_token_info(token).await.is_ok()
     }
     
-    /// 获取 session
+    /// 检查请求 IP 是否与 token 绑定的 IP 一致 | Check the request IP against the IP bound to a token
+    /// 
+    /// 仅在开启 `bind_ip` 且登录时记录了 IP 时生效；此时无法确定请求 IP 也视为不一致
+    /// Only applies when `bind_ip` is on and an IP was recorded at login; an unknown request IP then counts as a mismatch
+    /// 
+    /// # 参数 | Parameters
+    /// * `token_info` - token 信息 | Token info
+    /// * `client_ip` - 当前请求的客户端 IP | Client IP of the current request
+    pub fn check_client_ip(&self, token_info: &TokenInfo, client_ip: Option<&str>) -> SaTokenResult<()> {
+        match &token_info.login_ip {
+            Some(bound) if self.config.bind_ip && client_ip != Some(bound.as_str()) => {
+                Err(SaTokenError::TokenIpMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// 检查请求的设备指纹是否与登录时一致 | Check the request's device fingerprint against the one captured at login
+    /// 
+    /// 仅在开启 `bind_fingerprint` 且登录时记录了指纹时生效
+    /// Only applies when `bind_fingerprint` is on and a fingerprint was captured at login
+    /// 
+    /// # 参数 | Parameters
+    /// * `token_info` - token 信息 | Token info
+    /// * `fingerprint` - 中间件计算的当前请求指纹 | Fingerprint of the current request, computed by the middleware
+    pub fn check_fingerprint(&self, token_info: &TokenInfo, fingerprint: Option<&str>) -> SaTokenResult<()> {
+        match &token_info.fingerprint {
+            Some(bound) if self.config.bind_fingerprint && fingerprint != Some(bound.as_str()) => {
+                Err(SaTokenError::FingerprintMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// 依次检查 IP 与设备指纹绑定 | Check the IP binding, then the device fingerprint binding
+    pub fn check_binding(&self, token_info: &TokenInfo, client_ip: Option<&str>, fingerprint: Option<&str>) -> SaTokenResult<()> {
+        self.check_client_ip(token_info, client_ip)?;
+        self.check_fingerprint(token_info, fingerprint)
+    }
+    
+    /// 获取 session（默认账号类型）
     pub async fn get_session(&self, login_id: &str) -> SaTokenResult<SaSession> {
-        let key = format!("sa:session:{}", login_id);
+        self.get_session_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 获取指定账号类型的 session
+    /// 
+    /// Session ID 为按账号类型命名空间化的登录 ID，`save_session` 会据此写回
+    /// The session ID is the login ID namespaced by account type, which `save_session` uses to write back
+    pub async fn get_session_by_type(&self, login_id: &str, account_type: &str) -> SaTokenResult<SaSession> {
+        let session_id = namespaced_login_id(login_id, account_type);
+        let key = format!("sa:session:{}", session_id);
         let value = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         
         if let Some(value) = value {
             let session: SaSession = serde_json::from_str(&value)
-                .map_err(|e| SaTokenError::SerializationError(e))?;
+                .map_err(SaTokenError::SerializationError)?;
             Ok(session)
         } else {
-            Ok(SaSession::new(login_id))
+            Ok(SaSession::new(session_id))
         }
     }
     
@@ -359,7 +825,7 @@ impl SaTokenManager {
     pub async fn save_session(&self, session: &SaSession) -> SaTokenResult<()> {
         let key = format!("sa:session:{}", session.id);
         let value = serde_json::to_string(session)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         self.storage.set(&key, &value, None).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
@@ -367,9 +833,14 @@ impl SaTokenManager {
         Ok(())
     }
     
-    /// 删除 session
+    /// 删除 session（默认账号类型）
     pub async fn delete_session(&self, login_id: &str) -> SaTokenResult<()> {
-        let key = format!("sa:session:{}", login_id);
+        self.delete_session_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 删除指定账号类型的 session
+    pub async fn delete_session_by_type(&self, login_id: &str, account_type: &str) -> SaTokenResult<()> {
+        let key = format!("sa:session:{}", namespaced_login_id(login_id, account_type));
         self.storage.delete(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         Ok(())
@@ -402,7 +873,7 @@ impl SaTokenManager {
         // 保存更新后的 token 信息
         let key = format!("sa:token:{}", token.as_str());
         let value = serde_json::to_string(&new_token_info)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         let timeout = std::time::Duration::from_secs(timeout_seconds as u64);
         self.storage.set(&key, &value, Some(timeout)).await
@@ -411,22 +882,2127 @@ impl SaTokenManager {
         Ok(())
     }
     
-    /// 踢人下线
+    /// 踢人下线（默认账号类型）
     pub async fn kick_out(&self, login_id: &str) -> SaTokenResult<()> {
-        let token_result = self.storage.get(&format!("sa:login:token:{}", login_id)).await;
+        self.kick_out_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 踢人下线（指定账号类型）
+    /// 
+    /// 只影响该账号类型下的 token、session 和在线状态
+    /// Only affects tokens, session and online state of the given account type
+    pub async fn kick_out_by_type(&self, login_id: &str, account_type: &str) -> SaTokenResult<()> {
+        let token_result = self.storage.get(&Self::login_token_key(login_id, account_type)).await;
         
         if let Some(online_mgr) = &self.online_manager {
-            let _ = online_mgr.kick_out_notify(login_id, "Account kicked out".to_string()).await;
+            let online_key = namespaced_login_id(login_id, account_type);
+            let _ = online_mgr.kick_out_notify(&online_key, "Account kicked out".to_string()).await;
         }
         
-        self.logout_by_login_id(login_id).await?;
-        self.delete_session(login_id).await?;
+        self.logout_by_login_id_and_type(login_id, account_type).await?;
+        self.delete_session_by_type(login_id, account_type).await?;
+        self.drop_permission_cache(login_id).await;
+        self.publish_invalidation(Invalidation::KickOut {
+            login_id: login_id.to_string(),
+            login_type: account_type.to_string(),
+        }).await;
         
         if let Ok(Some(token_str)) = token_result {
-            let event = SaTokenEvent::kick_out(login_id, token_str);
+            let event = SaTokenEvent::kick_out(login_id, token_str)
+                .with_login_type(account_type);
+            self.event_bus.publish(event).await;
+        }
+        
+        Ok(())
+    }
+    
+    /// 踢下线某个设备上的全部会话（默认账号类型）
+    /// Kick out every session of a user on one device (default account type)
+    /// 
+    /// 向该设备推送踢出消息，登出其 token，并为每个 token 发布 `KickOut` 事件。
+    /// 未携带设备信息的登录属于 [`DEFAULT_DEVICE`](crate::online::DEFAULT_DEVICE)。
+    /// Pushes a kick-out message to the device, logs its tokens out and publishes a `KickOut`
+    /// event per token. Logins without device info belong to [`DEFAULT_DEVICE`](crate::online::DEFAULT_DEVICE).
+    /// 
+    /// # 返回 | Returns
+    /// 被踢下线的 token 数量 | Number of tokens kicked out
+    pub async fn kick_out_device(&self, login_id: &str, device: &str) -> SaTokenResult<usize> {
+        let token_prefix = "sa:token:";
+        let keys = self.storage.keys(&format!("{}*", token_prefix)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        let mut tokens = Vec::new();
+        for key in keys {
+            let Ok(Some(value)) = self.storage.get(&key).await else { continue };
+            let Ok(info) = serde_json::from_str::<TokenInfo>(&value) else { continue };
+            let token_device = info.device.as_deref().unwrap_or(crate::online::DEFAULT_DEVICE);
+            if info.login_id == login_id && info.account_type() == DEFAULT_ACCOUNT_TYPE && token_device == device {
+                tokens.push(TokenValue::new(&key[token_prefix.len()..]));
+            }
+        }
+        
+        if !tokens.is_empty() && let Some(online_mgr) = &self.online_manager {
+            let online_key = namespaced_login_id(login_id, DEFAULT_ACCOUNT_TYPE);
+            let _ = online_mgr.kick_out_device_notify(&online_key, device, "Device kicked out".to_string()).await;
+        }
+        
+        for token in &tokens {
+            self.logout(token).await?;
+            let event = SaTokenEvent::kick_out(login_id, token.as_str())
+                .with_login_type(DEFAULT_ACCOUNT_TYPE);
             self.event_bus.publish(event).await;
         }
         
+        Ok(tokens.len())
+    }
+    
+    /// 踢下线单个 token | Kick out a single token
+    /// 
+    /// 向 token 所在设备推送踢出消息，登出该 token 并发布 `KickOut` 事件；
+    /// 与 `logout` 不同，token 不存在时返回 `TokenNotFound`。
+    /// Pushes a kick-out message to the token's device, logs the token out and publishes a
+    /// `KickOut` event; unlike `logout`, returns `TokenNotFound` for an unknown token.
+    pub async fn kick_out_token(&self, token: &TokenValue) -> SaTokenResult<()> {
+        let value = self.storage.get(&format!("sa:token:{}", token.as_str())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let Some(info) = value.and_then(|value| serde_json::from_str::<TokenInfo>(&value).ok()) else {
+            return Err(SaTokenError::TokenNotFound);
+        };
+        
+        if let Some(online_mgr) = &self.online_manager {
+            let device = info.device.as_deref().unwrap_or(crate::online::DEFAULT_DEVICE);
+            let _ = online_mgr.kick_out_device_notify(&info.namespaced_login_id(), device, "Token kicked out".to_string()).await;
+        }
+        
+        self.logout(token).await?;
+        let event = SaTokenEvent::kick_out(&info.login_id, token.as_str())
+            .with_login_type(&info.login_type);
+        self.event_bus.publish(event).await;
+        
+        Ok(())
+    }
+    
+    // ==================== 二级认证 | Second-level Authentication ====================
+    
+    /// 开启二级认证 | Open second-level authentication
+    /// 
+    /// 在 `safe_time` 秒内，该 token 被视为已完成二级认证（如再次输入密码）
+    /// Within `safe_time` seconds the token is treated as recently re-authenticated (e.g. password re-entered)
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 已登录的 token | A logged-in token
+    /// * `safe_time` - 二级认证有效期（秒）| Safe-auth validity in seconds
+    /// 
+    /// # 返回 | Returns
+    /// 之后应使用的 token，开启 `regenerate_token_on_elevation` 时为新 token
+    /// The token to use from now on, a new one when `regenerate_token_on_elevation` is enabled
+    pub async fn open_safe(&self, token: &TokenValue, safe_time: i64) -> SaTokenResult<TokenValue> {
+        // 只有有效的 token 才能开启二级认证
+        self.get_token_info(token).await?;
+        
+        let key = format!("sa:safe:{}", token.as_str());
+        let ttl = std::time::Duration::from_secs(safe_time.max(0) as u64);
+        self.storage.set(&key, &Utc::now().timestamp().to_string(), Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        self.after_elevation(token).await
+    }
+    
+    /// 检查 token 是否处于二级认证有效期内 | Check whether the token is within safe-auth validity
+    pub async fn is_safe(&self, token: &TokenValue) -> bool {
+        let key = format!("sa:safe:{}", token.as_str());
+        self.storage.exists(&key).await.unwrap_or(false)
+    }
+    
+    /// 校验二级认证，未通过则返回 `SaTokenError::NotSafe`
+    /// Check second-level authentication, returns `SaTokenError::NotSafe` if not passed
+    pub async fn check_safe(&self, token: &TokenValue) -> SaTokenResult<()> {
+        if self.is_safe(token).await {
+            Ok(())
+        } else {
+            Err(SaTokenError::NotSafe)
+        }
+    }
+    
+    /// 关闭二级认证 | Close second-level authentication
+    pub async fn close_safe(&self, token: &TokenValue) -> SaTokenResult<()> {
+        let key = format!("sa:safe:{}", token.as_str());
+        self.storage.delete(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
         Ok(())
     }
+    
+    // ==================== 会话固定防护 | Session Fixation Protection ====================
+    
+    /// 重新生成 token：签发新的 token 值并迁移会话数据，旧 token 立即失效
+    /// Regenerate a token: issue a new token value, migrate the session data and invalidate the old token
+    /// 
+    /// 过期时间、额外数据、二级认证/双因素认证/冻结状态与属性索引都会迁移到新 token；
+    /// 当前请求上下文中的 token 也会一并更新
+    /// Expiry, extra data, safe-auth / two-factor / freeze markers and attribute indexes move to the new token;
+    /// the token of the current request context is updated as well
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 已登录的 token | A logged-in token
+    pub async fn regenerate_token(&self, token: &TokenValue) -> SaTokenResult<TokenValue> {
+        let info = self.get_token_info(token).await?;
+        let new_token = DefaultTokenGenerator::generate_with_device(&self.config, &info.login_id, info.device.as_deref());
+        let mut new_info = info.clone();
+        new_info.token = new_token.clone();
+        
+        // 新 token 沿用旧 token 的剩余有效期 | The new token keeps the remaining lifetime of the old one
+        let key = format!("sa:token:{}", token.as_str());
+        let ttl = self.storage.ttl(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let value = serde_json::to_string(&new_info)
+            .map_err(SaTokenError::SerializationError)?;
+        self.storage.set(&format!("sa:token:{}", new_token.as_str()), &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        let login_token_key = Self::login_token_key(&info.login_id, &info.login_type);
+        if self.storage.get(&login_token_key).await.ok().flatten().as_deref() == Some(token.as_str()) {
+            let ttl = self.storage.ttl(&login_token_key).await.ok().flatten();
+            self.storage.set(&login_token_key, new_token.as_str(), ttl).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        
+        for (from, to) in [
+            (format!("sa:safe:{}", token.as_str()), format!("sa:safe:{}", new_token.as_str())),
+            (Self::two_factor_key(token), Self::two_factor_key(&new_token)),
+            (Self::freeze_key(token), Self::freeze_key(&new_token)),
+            (Self::soft_logout_key(token), Self::soft_logout_key(&new_token)),
+        ] {
+            self.move_key(&from, &to).await?;
+        }
+        
+        self.update_indexes(&info, false).await?;
+        self.update_indexes(&new_info, true).await?;
+        self.storage.delete(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        if let Some(online_mgr) = &self.online_manager {
+            online_mgr.replace_token(&info.namespaced_login_id(), token.as_str(), new_token.as_str()).await;
+        }
+        if let Some(mut ctx) = crate::SaTokenContext::get_current()
+            && ctx.token.as_ref() == Some(token) {
+            ctx.token = Some(new_token.clone());
+            ctx.token_info = Some(Arc::new(new_info));
+            crate::SaTokenContext::set_current(ctx);
+        }
+        
+        tracing::debug!(
+            target: crate::logging::TARGET_SESSION,
+            token = %token,
+            new_token = %new_token,
+            login_id = %info.login_id,
+            "token regenerated"
+        );
+        Ok(new_token)
+    }
+    
+    /// 权限提升后按配置重新生成 token | Regenerate the token after an elevation when configured
+    async fn after_elevation(&self, token: &TokenValue) -> SaTokenResult<TokenValue> {
+        if self.config.regenerate_token_on_elevation {
+            self.regenerate_token(token).await
+        } else {
+            Ok(token.clone())
+        }
+    }
+    
+    /// 把键连同剩余有效期移动到新键 | Move a key to a new key together with its remaining lifetime
+    async fn move_key(&self, from: &str, to: &str) -> SaTokenResult<()> {
+        let Some(value) = self.storage.get(from).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))? else {
+            return Ok(());
+        };
+        let ttl = self.storage.ttl(from).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.set(to, &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.delete(from).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    // ==================== 记住我 | Remember Me ====================
+    
+    /// 为已登录的会话签发 remember-me token（需设置 `remember_me_timeout`）
+    /// Issue a remember-me token for a logged-in session (requires `remember_me_timeout`)
+    /// 
+    /// 返回的 token 应通过 `remember_me_cookie` 写入单独的 cookie
+    /// Put the returned token into its own cookie with `remember_me_cookie`
+    pub async fn remember_me(&self, token: &TokenValue) -> SaTokenResult<String> {
+        if self.config.remember_me_timeout <= 0 {
+            return Err(SaTokenError::ConfigError("remember_me_timeout is not set".to_string()));
+        }
+        let info = self.get_token_info(token).await?;
+        self.issue_remember_token(&RememberMeRecord {
+            login_id: info.login_id,
+            login_type: info.login_type,
+            device: info.device,
+            create_time: Utc::now(),
+        }).await
+    }
+    
+    /// 用 remember-me token 重建会话，旧 token 作废并轮换为新 token
+    /// Re-create a session from a remember-me token; the old token is invalidated and rotated
+    pub async fn restore_session(&self, remember_token: &str) -> SaTokenResult<RememberedSession> {
+        let key = RememberMeRecord::key(remember_token);
+        let record = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|v| serde_json::from_str::<RememberMeRecord>(&v).ok())
+            .ok_or(SaTokenError::RememberMeInvalid)?;
+        
+        // 并发请求携带同一 token 时只有一个成功 | Only one of several concurrent requests with the same token succeeds
+        let claim_key = format!("sa:remember:claimed:{}", remember_token);
+        let claimed = self.storage.set_nx(&claim_key, "1", Some(std::time::Duration::from_secs(60))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            return Err(SaTokenError::RememberMeInvalid);
+        }
+        self.forget_me(remember_token).await?;
+        
+        let token = self.login_with_options(
+            record.login_id.clone(),
+            Some(record.login_type.clone()),
+            record.device.clone(),
+            None,
+            None,
+            None,
+        ).await?;
+        let rotated = self.issue_remember_token(&RememberMeRecord { create_time: Utc::now(), ..record }).await?;
+        Ok(RememberedSession {
+            set_cookies: vec![
+                self.token_cookie(&token),
+                self.remember_me_cookie(&rotated),
+            ],
+            token,
+            remember_token: rotated,
+        })
+    }
+    
+    /// 插件使用：会话无效且带有 remember-me cookie 时静默重建会话，其他情况返回 None
+    /// For plugins: silently re-create the session when it is invalid and a remember-me cookie is present, None otherwise
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 请求携带的会话 token | Session token of the request
+    /// * `remember_token` - 请求携带的 remember-me cookie | Remember-me cookie of the request
+    pub async fn restore_remembered(&self, token: Option<&str>, remember_token: Option<&str>) -> Option<RememberedSession> {
+        if self.config.remember_me_timeout <= 0 {
+            return None;
+        }
+        let remember_token = remember_token.filter(|t| !t.is_empty())?;
+        if let Some(token) = token
+            && self.is_valid(&TokenValue::new(token)).await {
+            return None;
+        }
+        match self.restore_session(remember_token).await {
+            Ok(session) => Some(session),
+            Err(e) => {
+                tracing::debug!("Failed to restore remembered session: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// 作废一个 remember-me token（如当前设备登出）| Invalidate one remember-me token (e.g. logging out this device)
+    pub async fn forget_me(&self, remember_token: &str) -> SaTokenResult<()> {
+        let key = RememberMeRecord::key(remember_token);
+        if let Ok(Some(value)) = self.storage.get(&key).await
+            && let Ok(record) = serde_json::from_str::<RememberMeRecord>(&value) {
+            let index = RememberMeRecord::index_key(&namespaced_login_id(&record.login_id, &record.login_type));
+            let _ = self.storage.srem(&index, remember_token).await;
+        }
+        self.storage.delete(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 撤销账号的全部 remember-me token | Revoke every remember-me token of an account
+    pub async fn revoke_remember_tokens(&self, login_id: &str, account_type: &str) -> SaTokenResult<()> {
+        let index = RememberMeRecord::index_key(&namespaced_login_id(login_id, account_type));
+        let tokens = self.storage.smembers(&index).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        let mut keys: Vec<String> = tokens.iter().map(|t| RememberMeRecord::key(t)).collect();
+        keys.push(index);
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.storage.mdel(&keys).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    /// 会话 token cookie 的 `Set-Cookie` 值，有效期与 token 一致 | `Set-Cookie` value of the session token cookie, living as long as the token
+    pub fn token_cookie(&self, token: &TokenValue) -> String {
+        let max_age = self.config.cookie.token_max_age(self.config.effective_timeout());
+        remember_me::cookie(&self.config.cookie, &self.config.token_name, token.as_str(), max_age)
+    }
+    
+    /// 清除会话 token cookie 的 `Set-Cookie` 值 | `Set-Cookie` value that clears the session token cookie
+    pub fn clear_token_cookie(&self) -> String {
+        remember_me::cookie(&self.config.cookie, &self.config.token_name, "", Some(0))
+    }
+    
+    /// 插件使用：登录后写给客户端的响应头 | For plugins: response headers delivering a new token to the client
+    /// 
+    /// `is_read_cookie` 开启时写入 token cookie，`is_write_header` 开启时另外写入 `token_name` 响应头
+    /// Sets the token cookie when `is_read_cookie` is on, plus a `token_name` header when `is_write_header` is on
+    pub fn token_response_headers(&self, token: &TokenValue) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if self.config.is_read_cookie {
+            headers.push(("Set-Cookie".to_string(), self.token_cookie(token)));
+        }
+        if self.config.is_write_header {
+            headers.push((self.config.token_name.clone(), token.as_str().to_string()));
+        }
+        headers
+    }
+    
+    /// 插件使用：登出后清除 token cookie 的响应头 | For plugins: response headers clearing the token cookie after logout
+    pub fn logout_response_headers(&self) -> Vec<(String, String)> {
+        if !self.config.is_read_cookie {
+            return Vec::new();
+        }
+        vec![("Set-Cookie".to_string(), self.clear_token_cookie())]
+    }
+    
+    /// remember-me cookie 的 `Set-Cookie` 值 | `Set-Cookie` value of the remember-me cookie
+    pub fn remember_me_cookie(&self, remember_token: &str) -> String {
+        remember_me::cookie(&self.config.cookie, &self.config.remember_me_cookie, remember_token, Some(self.config.remember_me_timeout))
+    }
+    
+    /// 清除 remember-me cookie 的 `Set-Cookie` 值 | `Set-Cookie` value that clears the remember-me cookie
+    pub fn forget_me_cookie(&self) -> String {
+        remember_me::cookie(&self.config.cookie, &self.config.remember_me_cookie, "", Some(0))
+    }
+    
+    async fn issue_remember_token(&self, record: &RememberMeRecord) -> SaTokenResult<String> {
+        let token = self.id_generator.generate(IdKind::RememberMe);
+        let ttl = std::time::Duration::from_secs(self.config.remember_me_timeout.max(1) as u64);
+        let value = serde_json::to_string(record)?;
+        self.storage.set(&RememberMeRecord::key(&token), &value, Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        let index = RememberMeRecord::index_key(&namespaced_login_id(&record.login_id, &record.login_type));
+        self.storage.sadd(&index, &token).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.storage.expire(&index, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(token)
+    }
+    
+    // ==================== 刷新令牌 | Refresh Tokens ====================
+    
+    /// 为已登录的会话签发 refresh token，开启一个新的 token 家族
+    /// Issue a refresh token for a logged-in session, starting a new token family
+    pub async fn create_refresh_token(&self, token: &TokenValue) -> SaTokenResult<String> {
+        let info = self.get_token_info(token).await?;
+        let refresh_token = self.refresh_token_manager.generate(&info.login_id);
+        self.refresh_token_manager.store(&refresh_token, token.as_str(), &info.login_id).await?;
+        Ok(refresh_token)
+    }
+    
+    /// 轮换刷新：重新登录得到新的会话 token，并签发同一家族的新 refresh token
+    /// Refresh with rotation: log in again for a new session token and issue a new refresh token in the same family
+    /// 
+    /// 传入已使用过的 refresh token 会撤销整个家族并返回 `RefreshTokenReused`
+    /// Presenting a used refresh token revokes the whole family and returns `RefreshTokenReused`
+    pub async fn refresh_with_rotation(&self, refresh_token: &str) -> SaTokenResult<RotatedTokens> {
+        let (login_id, family) = self.refresh_token_manager.consume(refresh_token).await?;
+        let access_token = self.login(login_id.clone()).await?;
+        let new_refresh_token = self.refresh_token_manager.generate(&login_id);
+        self.refresh_token_manager
+            .store_in_family(&new_refresh_token, access_token.as_str(), &login_id, &family)
+            .await?;
+        Ok(RotatedTokens {
+            access_token,
+            refresh_token: new_refresh_token,
+            login_id,
+        })
+    }
+    
+    // ==================== 登录链接 | Login Links ====================
+    
+    /// 签发一次性登录链接 token（如免密邮件登录）| Issue a single-use login link token (e.g. passwordless email login)
+    /// 
+    /// # 参数 | Parameters
+    /// * `login_id` - 登录 ID | Login ID
+    /// * `login_type` - 登录类型 | Login type
+    /// * `ttl` - 有效期（秒）| Validity in seconds
+    pub async fn create_login_link(&self, login_id: &str, login_type: &str, ttl: i64) -> SaTokenResult<String> {
+        let token = self.id_generator.generate(IdKind::LoginLink);
+        let now = Utc::now();
+        let link = LoginLink {
+            login_id: login_id.to_string(),
+            login_type: login_type.to_string(),
+            create_time: now,
+            expire_time: now + Duration::seconds(ttl.max(1)),
+            used_time: None,
+        };
+        self.save_login_link(&token, &link).await?;
+        Ok(token)
+    }
+    
+    /// 使用登录链接：登录对应账号并立即作废链接 | Consume a login link: log the account in and invalidate the link
+    /// 
+    /// 过期时返回 `LoginLinkInvalid`，重复使用时返回 `LoginLinkReused`，两者都会发布 `LoginLinkRejected` 事件
+    /// Returns `LoginLinkInvalid` when expired and `LoginLinkReused` when used again; both publish a `LoginLinkRejected` event
+    pub async fn consume_login_link(&self, link_token: &str) -> SaTokenResult<TokenValue> {
+        let key = LoginLink::key(link_token);
+        let mut link = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|v| serde_json::from_str::<LoginLink>(&v).ok())
+            .ok_or(SaTokenError::LoginLinkInvalid)?;
+        
+        if link.used_time.is_some() {
+            return Err(self.reject_login_link(&link, "reused").await);
+        }
+        if link.is_expired() {
+            return Err(self.reject_login_link(&link, "expired").await);
+        }
+        
+        // 并发使用时只有一个成功 | Only one of several concurrent uses succeeds
+        let ttl = std::time::Duration::from_secs(REUSE_DETECTION_WINDOW as u64);
+        let claimed = self.storage.set_nx(&LoginLink::claim_key(link_token), "1", Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            return Err(self.reject_login_link(&link, "reused").await);
+        }
+        link.used_time = Some(Utc::now());
+        self.save_login_link(link_token, &link).await?;
+        
+        let token = self.login_with_options(link.login_id.clone(), Some(link.login_type.clone()), None, None, None, None).await?;
+        let event = SaTokenEvent::login_link_consumed(&link.login_id, token.as_str())
+            .with_login_type(&link.login_type);
+        self.event_bus.publish(event).await;
+        Ok(token)
+    }
+    
+    /// 作废尚未使用的登录链接 | Revoke a login link that has not been used
+    pub async fn revoke_login_link(&self, link_token: &str) -> SaTokenResult<()> {
+        self.storage.delete(&LoginLink::key(link_token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    async fn save_login_link(&self, link_token: &str, link: &LoginLink) -> SaTokenResult<()> {
+        let retain = (link.expire_time - Utc::now()).num_seconds().max(0) + REUSE_DETECTION_WINDOW;
+        let value = serde_json::to_string(link)?;
+        self.storage.set(&LoginLink::key(link_token), &value, Some(std::time::Duration::from_secs(retain as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    async fn reject_login_link(&self, link: &LoginLink, reason: &str) -> SaTokenError {
+        let event = SaTokenEvent::login_link_rejected(&link.login_id, reason)
+            .with_login_type(&link.login_type);
+        self.event_bus.publish(event).await;
+        if reason == "reused" { SaTokenError::LoginLinkReused } else { SaTokenError::LoginLinkInvalid }
+    }
+    
+    // ==================== 双因素认证 | Two-Factor Authentication ====================
+    
+    /// 为当前账号开始绑定 TOTP，返回密钥与 `otpauth://` 地址 | Start TOTP enrollment, returning the secret and `otpauth://` URI
+    /// 
+    /// 绑定在 `confirm_two_factor` 校验第一个验证码后生效
+    /// The secret takes effect once `confirm_two_factor` has checked the first code
+    pub async fn enroll_two_factor(&self, token: &TokenValue) -> SaTokenResult<TotpEnrollment> {
+        let info = self.get_token_info(token).await?;
+        self.totp_manager.enroll(&info.namespaced_login_id(), &info.login_id).await
+    }
+    
+    /// 确认 TOTP 绑定，并将当前会话标记为已完成双因素认证 | Confirm TOTP enrollment and mark the session as two-factor verified
+    /// 
+    /// 返回之后应使用的 token（见 `regenerate_token_on_elevation`）
+    /// Returns the token to use from now on (see `regenerate_token_on_elevation`)
+    pub async fn confirm_two_factor(&self, token: &TokenValue, code: &str) -> SaTokenResult<TokenValue> {
+        let info = self.get_token_info(token).await?;
+        self.totp_manager.confirm(&info.namespaced_login_id(), code).await?;
+        self.mark_two_factor(&info).await?;
+        self.after_elevation(token).await
+    }
+    
+    /// 校验 TOTP 验证码，通过后当前会话在 token 有效期内视为已完成双因素认证
+    /// Verify a TOTP code; on success the session counts as two-factor verified for the token's lifetime
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 已登录的 token | A logged-in token
+    /// * `code` - 验证器应用显示的验证码 | Code shown in the authenticator app
+    /// 
+    /// # 返回 | Returns
+    /// 之后应使用的 token，开启 `regenerate_token_on_elevation` 时为新 token
+    /// The token to use from now on, a new one when `regenerate_token_on_elevation` is enabled
+    pub async fn verify_two_factor(&self, token: &TokenValue, code: &str) -> SaTokenResult<TokenValue> {
+        let info = self.get_token_info(token).await?;
+        self.totp_manager.verify(&info.namespaced_login_id(), code).await?;
+        self.mark_two_factor(&info).await?;
+        self.after_elevation(token).await
+    }
+    
+    /// 账号是否已绑定 TOTP | Whether the account has TOTP enabled
+    pub async fn is_two_factor_enabled(&self, login_id: &str, account_type: &str) -> SaTokenResult<bool> {
+        self.totp_manager.is_enabled(&namespaced_login_id(login_id, account_type)).await
+    }
+    
+    /// 当前会话是否已完成双因素认证 | Whether the session is two-factor verified
+    pub async fn is_two_factor_verified(&self, token: &TokenValue) -> bool {
+        self.storage.exists(&Self::two_factor_key(token)).await.unwrap_or(false)
+    }
+    
+    /// 校验双因素认证，未通过则返回 `SaTokenError::TwoFactorRequired`
+    /// Check two-factor authentication, returns `SaTokenError::TwoFactorRequired` if not passed
+    pub async fn check_two_factor(&self, token: &TokenValue) -> SaTokenResult<()> {
+        if self.is_two_factor_verified(token).await {
+            Ok(())
+        } else {
+            Err(SaTokenError::TwoFactorRequired)
+        }
+    }
+    
+    /// 解除账号的 TOTP 绑定 | Remove the account's TOTP secret
+    pub async fn disable_two_factor(&self, token: &TokenValue) -> SaTokenResult<()> {
+        let info = self.get_token_info(token).await?;
+        self.totp_manager.disable(&info.namespaced_login_id()).await?;
+        self.storage.delete(&Self::two_factor_key(token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    async fn mark_two_factor(&self, info: &TokenInfo) -> SaTokenResult<()> {
+        let ttl = info.expire_time.and_then(|t| (t - Utc::now()).to_std().ok());
+        self.storage.set(&Self::two_factor_key(&info.token), &Utc::now().timestamp().to_string(), ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+    
+    fn two_factor_key(token: &TokenValue) -> String {
+        format!("sa:safe:2fa:{}", token.as_str())
+    }
+    
+    // ==================== 权限与角色 | Permissions & Roles ====================
+    
+    /// 设置用户权限（默认账号类型）| Set the permissions of a user (default account type)
+    pub async fn set_permissions(&self, login_id: &str, permissions: Vec<String>) {
+        self.set_permissions_by_type(login_id, DEFAULT_ACCOUNT_TYPE, permissions).await;
+    }
+    
+    /// 设置指定账号类型下的用户权限 | Set the permissions of a user for an account type
+    pub async fn set_permissions_by_type(&self, login_id: &str, login_type: &str, permissions: Vec<String>) {
+        self.user_permissions.write().await.insert(Self::grant_key(login_id, login_type), permissions);
+    }
+    
+    /// 手动设置的权限与角色的键，按租户与账号类型隔离
+    /// Key of manually set permissions and roles, isolated per tenant and account type
+    pub(crate) fn grant_key(login_id: &str, login_type: &str) -> String {
+        tenant::scoped(&namespaced_login_id(login_id, login_type))
+    }
+    
+    /// 获取用户权限（默认账号类型）| Get the permissions of a user (default account type)
+    /// 
+    /// 包含手动设置的权限和 `PermissionProvider` 提供的权限
+    /// Includes permissions set manually and those from the `PermissionProvider`
+    pub async fn get_permissions(&self, login_id: &str) -> Vec<String> {
+        self.get_permissions_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 获取指定账号类型下的用户权限 | Get the permissions of a user for an account type
+    pub async fn get_permissions_by_type(&self, login_id: &str, login_type: &str) -> Vec<String> {
+        if let Some(permissions) = self.stateless_claim_list(stateless::PERMISSIONS_CLAIM, login_id, login_type) {
+            return permissions;
+        }
+        self.granted_permissions(login_id, login_type).await
+    }
+    
+    /// 手动设置与 `PermissionProvider` 提供的权限 | Permissions set manually plus those from the `PermissionProvider`
+    async fn granted_permissions(&self, login_id: &str, login_type: &str) -> Vec<String> {
+        let mut permissions = self.user_permissions.read().await.get(&Self::grant_key(login_id, login_type)).cloned().unwrap_or_default();
+        if let Some(provided) = self.provided_list(PERMISSION_CACHE, login_id, login_type).await {
+            Self::merge_into(&mut permissions, provided);
+        }
+        permissions
+    }
+    
+    /// 检查用户是否拥有指定权限，支持 `admin:*` 通配符
+    /// Check whether a user has a permission, `admin:*` wildcards are supported
+    pub async fn has_permission(&self, login_id: &str, permission: &str) -> bool {
+        self.has_permission_by_type(login_id, DEFAULT_ACCOUNT_TYPE, permission).await
+    }
+    
+    /// 检查指定账号类型下的用户是否拥有指定权限
+    /// Check whether a user of an account type has a permission
+    pub async fn has_permission_by_type(&self, login_id: &str, login_type: &str, permission: &str) -> bool {
+        let permissions = self.get_permissions_by_type(login_id, login_type).await;
+        Self::permission_granted(&permissions, permission)
+    }
+    
+    /// 批量检查权限，只加载一次权限列表，结果与 `permissions` 一一对应
+    /// Check permissions in bulk, loading the permission list once; results line up with `permissions`
+    pub async fn has_permissions_bulk(&self, login_id: &str, permissions: &[&str]) -> Vec<bool> {
+        self.has_permissions_bulk_by_type(login_id, DEFAULT_ACCOUNT_TYPE, permissions).await
+    }
+    
+    /// 批量检查指定账号类型下的权限
+    /// Check permissions of a user of an account type in bulk
+    /// 
+    /// # 参数 | Parameters
+    /// * `login_id` - 登录 ID | Login ID
+    /// * `login_type` - 账号类型 | Account type
+    /// * `permissions` - 待检查的权限 | Permissions to check
+    pub async fn has_permissions_bulk_by_type(&self, login_id: &str, login_type: &str, permissions: &[&str]) -> Vec<bool> {
+        let granted = self.get_permissions_by_type(login_id, login_type).await;
+        permissions.iter().map(|permission| Self::permission_granted(&granted, permission)).collect()
+    }
+    
+    /// 按用户权限过滤菜单 / 路由清单（默认账号类型）
+    /// Filter a menu or route manifest by the permissions of a user (default account type)
+    pub async fn filter_menu(&self, login_id: &str, items: &[MenuItem]) -> Vec<MenuItem> {
+        self.filter_menu_by_type(login_id, DEFAULT_ACCOUNT_TYPE, items).await
+    }
+    
+    /// 按指定账号类型下的用户权限过滤菜单 / 路由清单，所有权限只做一次批量检查
+    /// Filter a manifest by the permissions of a user of an account type, checking all permissions in one bulk call
+    /// 
+    /// # 参数 | Parameters
+    /// * `login_id` - 登录 ID | Login ID
+    /// * `login_type` - 账号类型 | Account type
+    /// * `items` - 菜单清单 | Manifest
+    pub async fn filter_menu_by_type(&self, login_id: &str, login_type: &str, items: &[MenuItem]) -> Vec<MenuItem> {
+        let required = crate::permission::required_permissions(items);
+        let checked = self.has_permissions_bulk_by_type(login_id, login_type, &required).await;
+        let granted: HashSet<&str> = required.into_iter().zip(checked).filter(|(_, ok)| *ok).map(|(p, _)| p).collect();
+        crate::permission::filter_menu(items, &|permission| granted.contains(permission))
+    }
+    
+    /// Mixin / Stateless 模式下，从当前请求 token 的声明读取权限或角色
+    /// In the Mixin / Stateless modes, read permissions or roles from the claims of the current request's token
+    fn stateless_claim_list(&self, claim: &str, login_id: &str, login_type: &str) -> Option<Vec<String>> {
+        self.jwt_keys.as_ref()?;
+        let token_info = crate::SaTokenContext::get_current()?.token_info?;
+        if token_info.login_id != login_id || token_info.login_type != login_type {
+            return None;
+        }
+        stateless::claim_list(&token_info, claim)
+    }
+    
+    pub(crate) fn permission_granted(granted: &[String], permission: &str) -> bool {
+        // 精确匹配
+        if granted.iter().any(|p| p == permission) {
+            return true;
+        }
+        
+        // 通配符匹配（例如 admin:* 匹配 admin:read）
+        granted.iter().any(|perm| {
+            perm.strip_suffix(":*").is_some_and(|prefix| permission.starts_with(prefix))
+        })
+    }
+    
+    /// 设置用户角色（默认账号类型）| Set the roles of a user (default account type)
+    pub async fn set_roles(&self, login_id: &str, roles: Vec<String>) {
+        self.set_roles_by_type(login_id, DEFAULT_ACCOUNT_TYPE, roles).await;
+    }
+    
+    /// 设置指定账号类型下的用户角色 | Set the roles of a user for an account type
+    pub async fn set_roles_by_type(&self, login_id: &str, login_type: &str, roles: Vec<String>) {
+        self.user_roles.write().await.insert(Self::grant_key(login_id, login_type), roles);
+    }
+    
+    /// 获取用户角色（默认账号类型）| Get the roles of a user (default account type)
+    pub async fn get_roles(&self, login_id: &str) -> Vec<String> {
+        self.get_roles_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 获取指定账号类型下的用户角色 | Get the roles of a user for an account type
+    pub async fn get_roles_by_type(&self, login_id: &str, login_type: &str) -> Vec<String> {
+        if let Some(roles) = self.stateless_claim_list(stateless::ROLES_CLAIM, login_id, login_type) {
+            return roles;
+        }
+        self.granted_roles(login_id, login_type).await
+    }
+    
+    /// 手动设置与 `PermissionProvider` 提供的角色 | Roles set manually plus those from the `PermissionProvider`
+    async fn granted_roles(&self, login_id: &str, login_type: &str) -> Vec<String> {
+        let mut roles = self.user_roles.read().await.get(&Self::grant_key(login_id, login_type)).cloned().unwrap_or_default();
+        if let Some(provided) = self.provided_list(ROLE_CACHE, login_id, login_type).await {
+            Self::merge_into(&mut roles, provided);
+        }
+        roles
+    }
+    
+    /// 检查用户是否拥有指定角色 | Check whether a user has a role
+    pub async fn has_role(&self, login_id: &str, role: &str) -> bool {
+        self.has_role_by_type(login_id, DEFAULT_ACCOUNT_TYPE, role).await
+    }
+    
+    /// 检查指定账号类型下的用户是否拥有指定角色 | Check whether a user of an account type has a role
+    /// 
+    /// 配置了角色层级时，上级角色同样满足 | With a role hierarchy configured, senior roles also satisfy the check
+    pub async fn has_role_by_type(&self, login_id: &str, login_type: &str, role: &str) -> bool {
+        let roles = self.get_roles_by_type(login_id, login_type).await;
+        match self.role_hierarchy() {
+            Some(hierarchy) => roles.iter().any(|r| hierarchy.implies(r, role)),
+            None => roles.iter().any(|r| r == role),
+        }
+    }
+    
+    /// 获取用户角色及其继承的全部角色 | Get the roles of a user including every inherited role
+    pub async fn get_effective_roles(&self, login_id: &str) -> Vec<String> {
+        let roles = self.get_roles(login_id).await;
+        match self.role_hierarchy() {
+            Some(hierarchy) => hierarchy.expand(&roles),
+            None => roles,
+        }
+    }
+    
+    /// 设置角色层级，`None` 表示关闭继承 | Set the role hierarchy, `None` disables inheritance
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// manager.set_role_hierarchy(Some(RoleHierarchy::parse("admin > manager > user")?));
+    /// assert!(manager.has_role("1001", "user").await); // 1001 只拥有 admin | 1001 only holds admin
+    /// ```
+    pub fn set_role_hierarchy(&self, hierarchy: Option<RoleHierarchy>) {
+        *self.role_hierarchy.write().unwrap_or_else(|e| e.into_inner()) = hierarchy.map(Arc::new);
+    }
+    
+    /// 获取当前的角色层级 | Get the current role hierarchy
+    pub fn role_hierarchy(&self) -> Option<Arc<RoleHierarchy>> {
+        self.role_hierarchy.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 设置拒绝响应渲染器，插件状态在构建时读取 | Set the rejection renderer, read by plugin states when they are built
+    pub fn set_error_renderer(&self, renderer: Arc<dyn ErrorRenderer>) {
+        *self.error_renderer.write().unwrap_or_else(|e| e.into_inner()) = renderer;
+    }
+
+    /// 获取拒绝响应渲染器（默认 `DefaultErrorRenderer`）| Get the rejection renderer (`DefaultErrorRenderer` by default)
+    pub fn error_renderer(&self) -> Arc<dyn ErrorRenderer> {
+        self.error_renderer.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    
+    /// 注册权限数据提供者 | Register the permission provider
+    /// 
+    /// 注册后权限与角色检查会自动调用该提供者，可在运行时替换
+    /// Permission and role checks call the provider automatically once registered; it can be replaced at runtime
+    pub fn set_permission_provider(&self, provider: Arc<dyn PermissionProvider>) {
+        *self.permission_provider.write().unwrap_or_else(|e| e.into_inner()) = Some(provider);
+    }
+    
+    /// 获取已注册的权限数据提供者 | Get the registered permission provider
+    pub fn permission_provider(&self) -> Option<Arc<dyn PermissionProvider>> {
+        self.permission_provider.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    
+    /// 使用户的权限与角色缓存失效（所有账号类型）| Invalidate the cached permissions and roles of a user (all account types)
+    /// 
+    /// `PermissionProvider` 的数据源变化后调用；登出和踢人下线时自动调用
+    /// Call after the `PermissionProvider` data source changes; called automatically on logout and kick-out
+    pub async fn invalidate_permission_cache(&self, login_id: &str) {
+        self.drop_permission_cache(login_id).await;
+        self.publish_invalidation(Invalidation::Permissions { login_id: login_id.to_string() }).await;
+    }
+    
+    /// 清空全部权限与角色缓存 | Clear the whole permission and role cache
+    pub async fn clear_permission_cache(&self) {
+        self.permission_cache.write().await.clear();
+        self.publish_invalidation(Invalidation::ClearPermissions).await;
+    }
+    
+    /// 只在本实例丢弃用户的权限与角色缓存 | Drop the cached permissions and roles of a user on this instance only
+    async fn drop_permission_cache(&self, login_id: &str) {
+        let login_id = tenant::scoped(login_id);
+        self.permission_cache.write().await.retain(|(_, _, id), _| *id != login_id);
+    }
+    
+    // ==================== 集群同步 | Cluster Sync ====================
+    
+    /// 启动集群同步：订阅集群通道，在本实例应用其他实例发布的失效消息
+    /// Start cluster sync: subscribe to the cluster channel and apply invalidations published by other instances
+    /// 
+    /// 订阅断开后自动重新订阅；未接入集群通道时返回 None。中止返回的任务即停止同步
+    /// Resubscribes when the subscription ends; returns None without a cluster channel. Abort the returned task to stop
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let channel = RedisClusterChannel::new("redis://localhost:6379/0", DEFAULT_CLUSTER_CHANNEL).await?;
+    /// let manager = manager.with_cluster_channel(Arc::new(channel));
+    /// manager.start_cluster_sync();
+    /// ```
+    pub fn start_cluster_sync(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let channel = self.cluster_channel.clone()?;
+        let manager = self.clone();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let on_message: ClusterMessageHandler = Arc::new(move |payload| {
+            let _ = sender.send(payload);
+        });
+        
+        Some(tokio::spawn(async move {
+            loop {
+                let subscription = channel.subscribe(on_message.clone());
+                tokio::pin!(subscription);
+                loop {
+                    tokio::select! {
+                        result = &mut subscription => {
+                            if let Err(e) = result {
+                                tracing::warn!("Cluster channel subscription failed: {}", e);
+                            }
+                            break;
+                        }
+                        Some(payload) = receiver.recv() => manager.apply_cluster_message(&payload).await,
+                    }
+                }
+                tokio::time::sleep(CLUSTER_RESUBSCRIBE_DELAY).await;
+            }
+        }))
+    }
+    
+    /// 向其他实例广播失效消息，未接入集群通道时忽略 | Broadcast an invalidation, ignored without a cluster channel
+    async fn publish_invalidation(&self, invalidation: Invalidation) {
+        let Some(channel) = &self.cluster_channel else {
+            return;
+        };
+        let message = InvalidationMessage {
+            origin: self.instance_id.clone(),
+            tenant: tenant::current(),
+            invalidation,
+        };
+        let result = match serde_json::to_string(&message) {
+            Ok(payload) => channel.publish(&payload).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to publish cluster invalidation: {}", e);
+        }
+    }
+    
+    /// 应用其他实例发布的失效消息，忽略本实例发出的消息 | Apply an invalidation from another instance, ignoring our own
+    async fn apply_cluster_message(&self, payload: &str) {
+        let message = match serde_json::from_str::<InvalidationMessage>(payload) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Ignoring malformed cluster message: {}", e);
+                return;
+            }
+        };
+        if message.origin == self.instance_id {
+            return;
+        }
+        
+        tenant::scope(message.tenant, async {
+            match message.invalidation {
+                Invalidation::Logout { login_id, login_type, token } => {
+                    self.drop_permission_cache(&login_id).await;
+                    if let Some(online_mgr) = &self.online_manager {
+                        online_mgr.mark_offline(&namespaced_login_id(&login_id, &login_type), &token).await;
+                    }
+                }
+                Invalidation::KickOut { login_id, login_type } => {
+                    self.drop_permission_cache(&login_id).await;
+                    if let Some(online_mgr) = &self.online_manager {
+                        let online_key = namespaced_login_id(&login_id, &login_type);
+                        let _ = online_mgr.kick_out_notify(&online_key, "Account kicked out".to_string()).await;
+                    }
+                }
+                Invalidation::Permissions { login_id } => self.drop_permission_cache(&login_id).await,
+                Invalidation::ClearPermissions => self.permission_cache.write().await.clear(),
+            }
+        }).await;
+    }
+    
+    /// `PermissionProvider` 熔断器的当前状态 | Current state of the `PermissionProvider` circuit breaker
+    pub fn permission_provider_state(&self) -> CircuitState {
+        self.permission_breaker.state()
+    }
+    
+    /// 从缓存或 `PermissionProvider` 获取列表 | Get a list from the cache or the `PermissionProvider`
+    /// 
+    /// 缓存过期但仍在 `permission_cache_max_stale` 内时先返回旧数据，并在后台刷新
+    /// Within `permission_cache_max_stale` after expiry the stale list is returned and refreshed in the background
+    async fn provided_list(&self, kind: &'static str, login_id: &str, login_type: &str) -> Option<Vec<String>> {
+        self.permission_provider()?;
+        match self.cached_list(kind, login_id, login_type).await {
+            Some((list, true)) => Some(list),
+            Some((list, false)) => {
+                self.refresh_in_background(kind, login_id, login_type);
+                Some(list)
+            }
+            None => match self.load_list(kind, login_id, login_type).await {
+                Ok(list) => Some(list),
+                Err(e) => {
+                    self.degraded(login_id, login_type, kind, &e, false).await;
+                    None
+                }
+            },
+        }
+    }
+    
+    /// 调用 `PermissionProvider` 并写入缓存，受超时与熔断器约束
+    /// Call the `PermissionProvider` and cache the result, subject to the timeout and the circuit breaker
+    async fn load_list(&self, kind: &'static str, login_id: &str, login_type: &str) -> SaTokenResult<Vec<String>> {
+        let provider = self.permission_provider()
+            .ok_or_else(|| SaTokenError::InternalError("no PermissionProvider registered".to_string()))?;
+        if !self.permission_breaker.allow() {
+            return Err(SaTokenError::InternalError("PermissionProvider circuit is open".to_string()));
+        }
+        
+        let call = if kind == PERMISSION_CACHE {
+            provider.get_permission_list(login_id, login_type)
+        } else {
+            provider.get_role_list(login_id, login_type)
+        };
+        let result = match self.config.permission_provider_timeout_ms {
+            0 => call.await,
+            millis => tokio::time::timeout(std::time::Duration::from_millis(millis), call).await
+                .unwrap_or_else(|_| Err(SaTokenError::InternalError(format!("PermissionProvider timed out after {}ms", millis)))),
+        };
+        
+        match result {
+            Ok(list) => {
+                if self.permission_breaker.record_success() {
+                    tracing::info!("PermissionProvider recovered, circuit closed");
+                }
+                self.cache_list(kind, login_id, login_type, &list).await;
+                Ok(list)
+            }
+            Err(e) => {
+                if self.permission_breaker.record_failure() {
+                    tracing::warn!(
+                        "PermissionProvider circuit opened for {}s: {}",
+                        self.config.permission_breaker_cooldown, e
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+    
+    /// 在后台刷新过期的缓存，同一键同时只刷新一次 | Refresh a stale entry in the background, once per key at a time
+    fn refresh_in_background(&self, kind: &'static str, login_id: &str, login_type: &str) {
+        // 熔断期间不刷新，继续使用旧数据 | Keep serving stale data without refreshing while the circuit is open
+        if self.permission_breaker.state() == CircuitState::Open {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let key = (kind, login_type.to_string(), login_id.to_string());
+        if !self.permission_refreshing.lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone()) {
+            return;
+        }
+        
+        let manager = self.clone();
+        runtime.spawn(async move {
+            let (kind, login_type, login_id) = &key;
+            if let Err(e) = manager.load_list(kind, login_id, login_type).await {
+                manager.degraded(login_id, login_type, kind, &e, true).await;
+            }
+            manager.permission_refreshing.lock().unwrap_or_else(|e| e.into_inner()).remove(&key);
+        });
+    }
+    
+    async fn degraded(&self, login_id: &str, login_type: &str, kind: &str, error: &SaTokenError, stale: bool) {
+        tracing::warn!("PermissionProvider failed to load {} list of {}: {}", kind, login_id, error);
+        let event = SaTokenEvent::permission_degraded(login_id, error.to_string(), stale)
+            .with_login_type(login_type);
+        self.event_bus.publish(event).await;
+    }
+    
+    /// 返回缓存的列表及其是否新鲜 | Return a cached list and whether it is fresh
+    async fn cached_list(&self, kind: &'static str, login_id: &str, login_type: &str) -> Option<(Vec<String>, bool)> {
+        if self.config.permission_cache_timeout <= 0 {
+            return None;
+        }
+        let key = (kind, login_type.to_string(), tenant::scoped(login_id));
+        let now = std::time::Instant::now();
+        self.permission_cache.read().await.get(&key)
+            .filter(|(_, expires_at)| *expires_at + self.max_stale() > now)
+            .map(|(list, expires_at)| (list.clone(), *expires_at > now))
+    }
+    
+    async fn cache_list(&self, kind: &'static str, login_id: &str, login_type: &str, list: &[String]) {
+        if self.config.permission_cache_timeout <= 0 {
+            return;
+        }
+        let now = std::time::Instant::now();
+        let expires_at = now + std::time::Duration::from_secs(self.config.permission_cache_timeout as u64);
+        let max_stale = self.max_stale();
+        let mut cache = self.permission_cache.write().await;
+        cache.retain(|_, (_, expires)| *expires + max_stale > now);
+        cache.insert((kind, login_type.to_string(), tenant::scoped(login_id)), (list.to_vec(), expires_at));
+    }
+    
+    fn max_stale(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.config.permission_cache_max_stale.max(0) as u64)
+    }
+    
+    /// 合并列表并去重 | Merge a list without duplicates
+    fn merge_into(target: &mut Vec<String>, items: Vec<String>) {
+        for item in items {
+            if !target.contains(&item) {
+                target.push(item);
+            }
+        }
+    }
+    
+    // ==================== 数据版本 | Schema Version ====================
+    
+    /// 注册数据迁移器 | Register a schema migrator
+    pub fn register_schema_migrator(&self, migrator: Arc<dyn SchemaMigrator>) {
+        self.schema_migrators.write().unwrap_or_else(|e| e.into_inner()).push(migrator);
+    }
+    
+    /// 检查存储数据版本，按需执行迁移，应在启动时调用
+    /// Check the storage schema version and migrate if needed, should be called at startup
+    /// 
+    /// 版本不兼容且没有对应迁移器时返回 `SaTokenError::SchemaVersionMismatch`
+    /// Returns `SaTokenError::SchemaVersionMismatch` when the version is incompatible and no migrator applies
+    pub async fn ensure_schema(&self) -> SaTokenResult<u32> {
+        let migrators = self.schema_migrators.read().unwrap_or_else(|e| e.into_inner()).clone();
+        schema::ensure_schema(self.storage.as_ref(), &migrators).await
+    }
+    
+    // ==================== 启动预热 | Warm-up ====================
+    
+    /// 注册预热任务 | Register a warm-up task
+    pub fn register_warmup_task(&self, task: Arc<dyn WarmupTask>) {
+        self.warmup_tasks.write().unwrap_or_else(|e| e.into_inner()).push(task);
+    }
+    
+    /// 预热存储连接、热点用户的权限缓存和已注册的任务，应在启动时、接收流量前调用
+    /// Warm up storage connections, the permission cache of hot users and registered tasks;
+    /// should be called at startup, before taking traffic
+    /// 
+    /// 失败的步骤记录在报告中，不会中断其他步骤
+    /// Failed steps are recorded in the report and do not stop the other steps
+    pub async fn warm_up(&self) -> WarmupReport {
+        let mut report = WarmupReport::default();
+        
+        let started = std::time::Instant::now();
+        let result = self.storage.warm_up().await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()));
+        report.record("storage", started.elapsed(), result);
+        
+        if self.permission_provider().is_some()
+            && self.config.permission_cache_timeout > 0
+            && !self.config.warmup_login_ids.is_empty() {
+            let started = std::time::Instant::now();
+            for login_id in &self.config.warmup_login_ids {
+                self.get_permissions_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await;
+                self.get_roles_by_type(login_id, DEFAULT_ACCOUNT_TYPE).await;
+            }
+            report.record("permission-cache", started.elapsed(), Ok(()));
+        }
+        
+        let tasks = self.warmup_tasks.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for task in tasks {
+            let started = std::time::Instant::now();
+            let result = task.warm_up(self).await;
+            report.record(task.name(), started.elapsed(), result);
+        }
+        
+        report
+    }
+    
+    /// 反序列化失败时，优先报告数据版本不兼容 | On deserialization failure, report a schema mismatch first
+    async fn deserialization_error(&self, error: serde_json::Error) -> SaTokenError {
+        match schema::stored_version(self.storage.as_ref()).await {
+            Ok(Some(found)) if found != SCHEMA_VERSION => {
+                tracing::error!("Storage schema v{} is incompatible with v{}, run ensure_schema at startup", found, SCHEMA_VERSION);
+                SaTokenError::SchemaVersionMismatch { found, expected: SCHEMA_VERSION }
+            }
+            _ => SaTokenError::SerializationError(error),
+        }
+    }
+    
+    // ==================== 冻结 | Freeze ====================
+    
+    /// 冻结 token | Freeze a token
+    /// 
+    /// 冻结期间 token 不会被删除，但所有校验都返回 `SaTokenError::TokenFrozen`（如等待风控审核）
+    /// The token is kept while frozen, but every check returns `SaTokenError::TokenFrozen` (e.g. pending fraud review)
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 要冻结的 token | Token to freeze
+    /// * `freeze_time` - 冻结时长（秒），`-1` 表示直到手动解冻 | Freeze duration in seconds, `-1` means until unfrozen
+    /// 
+    /// # 错误 | Errors
+    /// * `ConfigError` - `freeze_time` 既不是正数也不是 `-1` | `freeze_time` is neither positive nor `-1`
+    pub async fn freeze_token(&self, token: &TokenValue, freeze_time: i64) -> SaTokenResult<()> {
+        // 0 与其他负数不能当作永久冻结 | 0 and other negatives must not turn into a permanent freeze
+        let ttl = match freeze_time {
+            -1 => None,
+            secs if secs > 0 => Some(std::time::Duration::from_secs(secs as u64)),
+            secs => return Err(SaTokenError::ConfigError(format!("freeze_time must be positive or -1, got {}", secs))),
+        };
+        
+        // 不经过 get_token_info，已冻结的 token 也可以重新设置冻结时长
+        let exists = self.storage.exists(&format!("sa:token:{}", token.as_str())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !exists {
+            return Err(SaTokenError::TokenNotFound);
+        }
+        
+        self.storage.set(&Self::freeze_key(token), &Utc::now().timestamp().to_string(), ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// 解冻 token | Unfreeze a token
+    pub async fn unfreeze_token(&self, token: &TokenValue) -> SaTokenResult<()> {
+        self.storage.delete(&Self::freeze_key(token)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+    
+    /// 检查 token 是否被冻结 | Check whether a token is frozen
+    pub async fn is_frozen(&self, token: &TokenValue) -> bool {
+        self.storage.exists(&Self::freeze_key(token)).await.unwrap_or(false)
+    }
+    
+    // ==================== 软登出 | Soft Logout ====================
+    
+    /// 软登出 | Soft logout
+    /// 
+    /// token 与会话数据保留 `grace_time` 秒，期间所有校验返回 `SaTokenError::ReauthRequired`；
+    /// 宽限期内调用 `reauthenticate` 可恢复原会话（购物车、草稿等），超时后 token 被彻底登出。
+    /// The token and its session data are kept for `grace_time` seconds, during which every check
+    /// returns `SaTokenError::ReauthRequired`; calling `reauthenticate` in time resumes the same
+    /// session (cart, drafts), otherwise the token is fully logged out.
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 已登录的 token | A logged-in token
+    /// * `grace_time` - 宽限期（秒）| Grace period in seconds
+    pub async fn soft_logout(&self, token: &TokenValue, grace_time: i64) -> SaTokenResult<()> {
+        self.get_token_info(token).await?;
+        
+        let deadline = Utc::now().timestamp() + grace_time.max(0);
+        self.storage.set(&Self::soft_logout_key(token), &deadline.to_string(), self.config.timeout_duration()).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// 重新认证后恢复软登出的 token | Resume a soft-logged-out token after re-authentication
+    /// 
+    /// 调用方需先校验用户凭证（如密码），宽限期已过时返回 `SaTokenError::TokenExpired`
+    /// The caller verifies the credentials (e.g. password) first; returns `SaTokenError::TokenExpired`
+    /// once the grace period has passed
+    pub async fn reauthenticate(&self, token: &TokenValue) -> SaTokenResult<()> {
+        if let Some(deadline) = self.soft_logout_deadline(token).await {
+            if Utc::now().timestamp() > deadline {
+                self.logout(token).await?;
+                return Err(SaTokenError::TokenExpired);
+            }
+            self.storage.delete(&Self::soft_logout_key(token)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        
+        self.get_token_info(token).await.map(|_| ())
+    }
+    
+    /// 检查 token 是否处于软登出状态 | Check whether a token is soft-logged-out
+    pub async fn is_soft_logged_out(&self, token: &TokenValue) -> bool {
+        self.soft_logout_deadline(token).await.is_some()
+    }
+    
+    /// 软登出宽限期截止时间（Unix 秒）| Grace deadline of a soft logout (Unix seconds)
+    async fn soft_logout_deadline(&self, token: &TokenValue) -> Option<i64> {
+        self.storage.get(&Self::soft_logout_key(token)).await.ok()
+            .flatten()
+            .and_then(|v| v.parse().ok())
+    }
+    
+    /// 软登出标记的存储键 | Storage key of the soft logout marker
+    fn soft_logout_key(token: &TokenValue) -> String {
+        format!("sa:soft-logout:{}", token.as_str())
+    }
+    
+    // ==================== 会话属性索引 | Session Attribute Indexes ====================
+    
+    /// 设置 token 的单个附加数据，并维护已索引属性的二级索引
+    /// Set a single extra value of a token and maintain the index of indexed attributes
+    /// 
+    /// # 参数 | Parameters
+    /// * `token` - 已登录的 token | A logged-in token
+    /// * `key` - 附加数据键 | Extra key
+    /// * `value` - 附加数据值 | Extra value
+    pub async fn set_token_extra(
+        &self,
+        token: &TokenValue,
+        key: impl Into<String>,
+        value: serde_json::Value,
+    ) -> SaTokenResult<()> {
+        let key = key.into();
+        let mut token_info = self.get_token_info(token).await?;
+        
+        if self.is_indexed(&key) {
+            if let Some(old) = token_info.get_extra(&key) {
+                self.storage.srem(&Self::index_key(&key, old), token.as_str()).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+            }
+            self.storage.sadd(&Self::index_key(&key, &value), token.as_str()).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        token_info.set_extra(key, value);
+        
+        let storage_key = format!("sa:token:{}", token.as_str());
+        let value = serde_json::to_string(&token_info)
+            .map_err(SaTokenError::SerializationError)?;
+        self.storage.set(&storage_key, &value, self.config.timeout_duration()).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// 按已索引的属性列出会话 | List sessions by an indexed attribute
+    /// 
+    /// 已失效的 token 会在查询时从索引中清理
+    /// Tokens that are no longer valid are pruned from the index while querying
+    /// 
+    /// # 参数 | Parameters
+    /// * `attribute` - 已在 `indexed_attributes` 中声明的属性 | Attribute declared in `indexed_attributes`
+    /// * `value` - 属性值 | Attribute value
+    pub async fn list_sessions_by_attribute(
+        &self,
+        attribute: &str,
+        value: &serde_json::Value,
+    ) -> SaTokenResult<Vec<TokenInfo>> {
+        if !self.is_indexed(attribute) {
+            return Err(SaTokenError::ConfigError(format!("session attribute '{}' is not indexed", attribute)));
+        }
+        
+        let index_key = Self::index_key(attribute, value);
+        let members = self.storage.smembers(&index_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        
+        let mut sessions = Vec::with_capacity(members.len());
+        for member in members {
+            let stored = self.storage.get(&format!("sa:token:{}", member)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+                .and_then(|v| serde_json::from_str::<TokenInfo>(&v).ok())
+                .filter(|info| !info.is_expired() && info.get_extra(attribute) == Some(value));
+            match stored {
+                Some(info) => sessions.push(info),
+                None => {
+                    let _ = self.storage.srem(&index_key, &member).await;
+                }
+            }
+        }
+        
+        Ok(sessions)
+    }
+    
+    /// 登出某个已索引属性值下的所有会话，返回登出的数量
+    /// Log out all sessions with an indexed attribute value, returns the number logged out
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// // 吊销租户 42 的所有会话 | Revoke all sessions of tenant 42
+    /// let revoked = manager.logout_by_attribute("tenant_id", &json!(42)).await?;
+    /// ```
+    pub async fn logout_by_attribute(&self, attribute: &str, value: &serde_json::Value) -> SaTokenResult<usize> {
+        let sessions = self.list_sessions_by_attribute(attribute, value).await?;
+        for info in &sessions {
+            self.logout(&info.token).await?;
+        }
+        Ok(sessions.len())
+    }
+    
+    /// 属性是否已声明索引 | Whether an attribute is declared as indexed
+    fn is_indexed(&self, attribute: &str) -> bool {
+        self.config.indexed_attributes.iter().any(|a| a == attribute)
+    }
+    
+    /// 属性索引的存储键 | Storage key of an attribute index
+    fn index_key(attribute: &str, value: &serde_json::Value) -> String {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        format!("sa:index:{}:{}", attribute, value)
+    }
+    
+    /// 向已索引属性的索引中添加或移除 token | Add or remove a token in the indexes of its indexed attributes
+    async fn update_indexes(&self, token_info: &TokenInfo, add: bool) -> SaTokenResult<()> {
+        for attribute in &self.config.indexed_attributes {
+            let Some(value) = token_info.get_extra(attribute) else {
+                continue;
+            };
+            let key = Self::index_key(attribute, value);
+            let result = if add {
+                self.storage.sadd(&key, token_info.token.as_str()).await
+            } else {
+                self.storage.srem(&key, token_info.token.as_str()).await
+            };
+            result.map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        Ok(())
+    }
+    
+    /// 冻结标记的存储键 | Storage key of the freeze marker
+    fn freeze_key(token: &TokenValue) -> String {
+        format!("sa:freeze:{}", token.as_str())
+    }
+    
+    /// login_id -> token 映射的存储键 | Storage key of the login_id -> token mapping
+    pub(crate) fn login_token_key(login_id: &str, account_type: &str) -> String {
+        format!("sa:login:token:{}", namespaced_login_id(login_id, account_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+    use crate::config::SaCookieConfig;
+    use sa_token_adapter::context::SameSite;
+    
+    fn create_manager() -> SaTokenManager {
+        SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+    }
+    
+    #[tokio::test]
+    async fn test_account_types_do_not_collide() {
+        let manager = create_manager();
+        
+        let user_token = manager.login("1001").await.unwrap();
+        let admin_token = manager
+            .login_with_options("1001", Some("admin".to_string()), None, None, None, None)
+            .await
+            .unwrap();
+        
+        let admin_info = manager.get_token_info(&admin_token).await.unwrap();
+        assert_eq!(admin_info.account_type(), "admin");
+        assert_eq!(admin_info.namespaced_login_id(), "1001:admin");
+        
+        // 登出 admin 不影响同 ID 的 user | Logging out admin keeps the user with the same ID
+        manager.logout_by_login_id_and_type("1001", "admin").await.unwrap();
+        assert!(!manager.is_valid(&admin_token).await);
+        assert!(manager.is_valid(&user_token).await);
+    }
+    
+    #[tokio::test]
+    async fn test_login_id_with_colon_does_not_collide() {
+        let manager = create_manager();
+        
+        // 默认类型的 `1001:admin` 与 admin 类型的 `1001` 是不同的账号
+        // Default-type `1001:admin` and admin-type `1001` are different accounts
+        let colon_token = manager.login("1001:admin").await.unwrap();
+        let admin_token = manager
+            .login_with_options("1001", Some("admin".to_string()), None, None, None, None)
+            .await
+            .unwrap();
+        assert_ne!(
+            SaTokenManager::login_token_key("1001:admin", DEFAULT_ACCOUNT_TYPE),
+            SaTokenManager::login_token_key("1001", "admin"),
+        );
+        
+        let mut session = manager.get_session("1001:admin").await.unwrap();
+        session.set("owner", "colon").unwrap();
+        manager.save_session(&session).await.unwrap();
+        let admin_session = manager.get_session_by_type("1001", "admin").await.unwrap();
+        assert!(admin_session.get::<String>("owner").is_none());
+        
+        manager.logout_by_login_id("1001:admin").await.unwrap();
+        assert!(!manager.is_valid(&colon_token).await);
+        assert!(manager.is_valid(&admin_token).await);
+    }
+    
+    #[tokio::test]
+    async fn test_session_namespaced_by_account_type() {
+        let manager = create_manager();
+        
+        let mut admin_session = manager.get_session_by_type("1001", "admin").await.unwrap();
+        admin_session.set("role", "super").unwrap();
+        manager.save_session(&admin_session).await.unwrap();
+        
+        let user_session = manager.get_session("1001").await.unwrap();
+        assert!(!user_session.has("role"));
+        
+        let admin_session = manager.get_session_by_type("1001", "admin").await.unwrap();
+        assert_eq!(admin_session.get::<String>("role"), Some("super".to_string()));
+    }
+    
+    #[tokio::test]
+    async fn test_safe_auth() {
+        let manager = create_manager();
+        let token = manager.login("user_1").await.unwrap();
+        
+        assert!(!manager.is_safe(&token).await);
+        assert!(matches!(manager.check_safe(&token).await, Err(SaTokenError::NotSafe)));
+        
+        manager.open_safe(&token, 120).await.unwrap();
+        assert!(manager.is_safe(&token).await);
+        assert!(manager.check_safe(&token).await.is_ok());
+        
+        manager.close_safe(&token).await.unwrap();
+        assert!(!manager.is_safe(&token).await);
+        
+        // 无效 token 不能开启二级认证 | An invalid token cannot open safe auth
+        let invalid = TokenValue::new("not-a-token");
+        assert!(manager.open_safe(&invalid, 120).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_kick_out_by_type() {
+        let manager = create_manager();
+        
+        let user_token = manager.login("1001").await.unwrap();
+        let admin_token = manager
+            .login_with_options("1001", Some("admin".to_string()), None, None, None, None)
+            .await
+            .unwrap();
+        
+        manager.kick_out("1001").await.unwrap();
+        assert!(!manager.is_valid(&user_token).await);
+        assert!(manager.is_valid(&admin_token).await);
+    }
+    
+    #[tokio::test]
+    async fn test_freeze_token() {
+        let manager = create_manager();
+        let token = manager.login("1001").await.unwrap();
+        
+        manager.freeze_token(&token, -1).await.unwrap();
+        assert!(manager.is_frozen(&token).await);
+        assert!(!manager.is_valid(&token).await);
+        assert!(matches!(manager.get_token_info(&token).await, Err(SaTokenError::TokenFrozen)));
+        
+        manager.unfreeze_token(&token).await.unwrap();
+        assert!(manager.is_valid(&token).await);
+        
+        // 只有 -1 表示永久冻结 | Only -1 means a permanent freeze
+        for freeze_time in [0, -2, i64::MIN] {
+            assert!(matches!(manager.freeze_token(&token, freeze_time).await, Err(SaTokenError::ConfigError(_))));
+            assert!(!manager.is_frozen(&token).await);
+        }
+        manager.freeze_token(&token, 60).await.unwrap();
+        assert!(matches!(manager.get_token_info(&token).await, Err(SaTokenError::TokenFrozen)));
+        manager.unfreeze_token(&token).await.unwrap();
+        
+        let missing = TokenValue::new("missing-token");
+        assert!(matches!(manager.freeze_token(&missing, 60).await, Err(SaTokenError::TokenNotFound)));
+    }
+    
+    #[tokio::test]
+    async fn test_token_extra_roundtrip() {
+        let manager = create_manager();
+        let mut token_info = TokenInfo::new(TokenValue::new("extra-token"), "1001");
+        token_info.set_extra("tenant_id", serde_json::json!(42));
+        let token = manager.login_with_token_info(token_info).await.unwrap();
+        
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.get_extra("tenant_id"), Some(&serde_json::json!(42)));
+        
+        // 未设置附加数据时不写入该字段，旧数据也能正常读取
+        let plain = serde_json::to_string(&TokenInfo::new(TokenValue::new("t"), "1002")).unwrap();
+        assert!(!plain.contains("\"extra\""));
+        let parsed: TokenInfo = serde_json::from_str(&plain).unwrap();
+        assert!(parsed.extra.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_custom_token_generator() {
+        struct PrefixGenerator;
+        
+        impl crate::token::TokenGenerator for PrefixGenerator {
+            fn generate(&self, login_id: &str, device: Option<&str>) -> String {
+                let body = format!("{}-{}", device.unwrap_or("web"), login_id);
+                let checksum = body.bytes().fold(0u8, |acc, b| acc.wrapping_add(b));
+                format!("acme_{}_{:02x}", body, checksum)
+            }
+        }
+        
+        let config = SaTokenConfig {
+            token_style: crate::config::TokenStyle::Custom(Arc::new(PrefixGenerator)),
+            ..Default::default()
+        };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let token = manager
+            .login_with_options("1001", None, Some("ios".to_string()), None, None, None)
+            .await
+            .unwrap();
+        assert!(token.as_str().starts_with("acme_ios-1001_"));
+        assert!(manager.is_valid(&token).await);
+    }
+    
+    #[tokio::test]
+    async fn test_sessions_by_attribute() {
+        let config = SaTokenConfig {
+            indexed_attributes: vec!["tenant_id".to_string()],
+            ..Default::default()
+        };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let mut tokens = Vec::new();
+        for (login_id, tenant) in [("1", 7), ("2", 7), ("3", 8)] {
+            let mut info = TokenInfo::new(TokenValue::new(format!("t{}", login_id)), login_id);
+            info.set_extra("tenant_id", serde_json::json!(tenant));
+            tokens.push(manager.login_with_token_info(info).await.unwrap());
+        }
+        
+        assert_eq!(manager.list_sessions_by_attribute("tenant_id", &serde_json::json!(7)).await.unwrap().len(), 2);
+        
+        // 修改属性会移动索引 | Changing the attribute moves the index entry
+        manager.set_token_extra(&tokens[1], "tenant_id", serde_json::json!(8)).await.unwrap();
+        assert_eq!(manager.logout_by_attribute("tenant_id", &serde_json::json!(8)).await.unwrap(), 2);
+        assert!(manager.is_valid(&tokens[0]).await);
+        assert!(!manager.is_valid(&tokens[2]).await);
+        assert!(manager.list_sessions_by_attribute("tenant_id", &serde_json::json!(8)).await.unwrap().is_empty());
+        
+        assert!(matches!(
+            manager.list_sessions_by_attribute("department", &serde_json::json!("sales")).await,
+            Err(SaTokenError::ConfigError(_))
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_soft_logout() {
+        let manager = create_manager();
+        let token = manager.login("1001").await.unwrap();
+        
+        manager.soft_logout(&token, 60).await.unwrap();
+        assert!(manager.is_soft_logged_out(&token).await);
+        assert!(matches!(manager.get_token_info(&token).await, Err(SaTokenError::ReauthRequired)));
+        
+        manager.reauthenticate(&token).await.unwrap();
+        assert!(manager.is_valid(&token).await);
+        
+        // 宽限期已过则彻底登出 | Fully logged out once the grace period has passed
+        manager.soft_logout(&token, 0).await.unwrap();
+        manager.storage.set(&SaTokenManager::soft_logout_key(&token), "0", None).await.unwrap();
+        assert!(matches!(manager.reauthenticate(&token).await, Err(SaTokenError::TokenExpired)));
+        assert!(!manager.is_soft_logged_out(&token).await);
+        assert!(matches!(manager.get_token_info(&token).await, Err(SaTokenError::TokenNotFound)));
+    }
+    
+    #[tokio::test]
+    async fn test_permission_provider() {
+        struct StaticProvider;
+        
+        #[async_trait::async_trait]
+        impl PermissionProvider for StaticProvider {
+            async fn get_permission_list(&self, login_id: &str, login_type: &str) -> SaTokenResult<Vec<String>> {
+                Ok(match (login_id, login_type) {
+                    ("1001", DEFAULT_ACCOUNT_TYPE) => vec!["order:*".to_string()],
+                    ("1001", "admin") => vec!["system:config".to_string()],
+                    _ => Vec::new(),
+                })
+            }
+            
+            async fn get_role_list(&self, login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                Err(SaTokenError::StorageError(format!("db down for {}", login_id)))
+            }
+        }
+        
+        let manager = create_manager();
+        manager.set_permissions("1001", vec!["user:read".to_string()]).await;
+        manager.set_roles("1001", vec!["vip".to_string()]).await;
+        manager.set_permission_provider(Arc::new(StaticProvider));
+        
+        assert!(manager.has_permission("1001", "user:read").await);
+        assert!(manager.has_permission("1001", "order:delete").await);
+        assert!(!manager.has_permission("1001", "system:config").await);
+        assert!(manager.has_permission_by_type("1001", "admin", "system:config").await);
+        
+        // 提供者出错时退回手动设置的数据 | Falls back to manually set data when the provider fails
+        assert!(manager.has_role("1001", "vip").await);
+        assert!(!manager.has_role("1001", "admin").await);
+        
+        // 手动设置的数据按账号类型隔离 | Manually set data is isolated per account type
+        assert!(!manager.has_permission_by_type("1001", "admin", "user:read").await);
+        assert!(!manager.has_role_by_type("1001", "admin", "vip").await);
+        manager.set_roles_by_type("1001", "admin", vec!["super".to_string()]).await;
+        manager.set_permissions_by_type("1001", "admin", vec!["audit:read".to_string()]).await;
+        assert!(manager.has_role_by_type("1001", "admin", "super").await);
+        assert!(!manager.has_role("1001", "super").await);
+        assert!(manager.has_permission_by_type("1001", "admin", "audit:read").await);
+        assert!(!manager.has_permission("1001", "audit:read").await);
+    }
+    
+    #[tokio::test]
+    async fn test_role_hierarchy() {
+        let manager = create_manager();
+        manager.set_roles("1001", vec!["admin".to_string()]).await;
+        manager.set_roles("1002", vec!["manager".to_string()]).await;
+        
+        assert!(!manager.has_role("1001", "user").await);
+        
+        manager.set_role_hierarchy(Some(RoleHierarchy::parse("admin > manager > user").unwrap()));
+        assert!(manager.has_role("1001", "user").await);
+        assert!(manager.has_role("1002", "user").await);
+        assert!(!manager.has_role("1002", "admin").await);
+        assert_eq!(manager.get_effective_roles("1002").await, vec!["manager".to_string(), "user".to_string()]);
+        
+        manager.set_role_hierarchy(None);
+        assert!(!manager.has_role("1001", "user").await);
+    }
+    
+    #[tokio::test]
+    async fn test_permission_cache() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        struct CountingProvider(Arc<AtomicUsize>);
+        
+        #[async_trait::async_trait]
+        impl PermissionProvider for CountingProvider {
+            async fn get_permission_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(vec!["user:read".to_string()])
+            }
+            
+            async fn get_role_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                Ok(Vec::new())
+            }
+        }
+        
+        let config = SaTokenConfig { permission_cache_timeout: 60, ..Default::default() };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let calls = Arc::new(AtomicUsize::new(0));
+        manager.set_permission_provider(Arc::new(CountingProvider(calls.clone())));
+        
+        assert!(manager.has_permission("1001", "user:read").await);
+        assert!(manager.has_permission("1001", "user:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        
+        manager.invalidate_permission_cache("1001").await;
+        assert!(manager.has_permission("1001", "user:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        
+        // 登出自动失效 | Logout invalidates automatically
+        let token = manager.login("1001").await.unwrap();
+        manager.logout(&token).await.unwrap();
+        assert!(manager.has_permission("1001", "user:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+    
+    #[tokio::test]
+    async fn test_has_permissions_bulk() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        struct CountingProvider(Arc<AtomicUsize>);
+        
+        #[async_trait::async_trait]
+        impl PermissionProvider for CountingProvider {
+            async fn get_permission_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(vec!["menu:order:*".to_string(), "menu:user".to_string()])
+            }
+            
+            async fn get_role_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                Ok(Vec::new())
+            }
+        }
+        
+        let manager = create_manager();
+        let calls = Arc::new(AtomicUsize::new(0));
+        manager.set_permission_provider(Arc::new(CountingProvider(calls.clone())));
+        
+        let menu = ["menu:user", "menu:order:list", "menu:system", "menu:order:export"];
+        assert_eq!(manager.has_permissions_bulk("1001", &menu).await, [true, true, false, true]);
+        // 无缓存时也只调用一次 provider | Only one provider call even without the cache
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_bind_ip() {
+        let config = SaTokenConfig { bind_ip: true, ..Default::default() };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        
+        let mut ctx = crate::SaTokenContext::new();
+        ctx.client_ip = Some("10.0.0.1".to_string());
+        crate::SaTokenContext::set_current(ctx);
+        let token = manager.login("1001").await.unwrap();
+        crate::SaTokenContext::clear();
+        
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.login_ip.as_deref(), Some("10.0.0.1"));
+        assert!(manager.check_client_ip(&info, Some("10.0.0.1")).is_ok());
+        assert!(matches!(manager.check_client_ip(&info, Some("10.0.0.2")), Err(SaTokenError::TokenIpMismatch)));
+        assert!(manager.check_client_ip(&info, None).is_err());
+        
+        // 登录时没有 IP 的 token 不绑定 | Tokens logged in without an IP are not bound
+        let unbound = manager.login("1002").await.unwrap();
+        let info = manager.get_token_info(&unbound).await.unwrap();
+        assert!(manager.check_client_ip(&info, Some("10.0.0.2")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_fingerprint() {
+        let config = SaTokenConfig { bind_fingerprint: true, fingerprint_header: Some("X-Device-Id".to_string()), ..Default::default() };
+        let fingerprint = |ua: &str, device: &str| config.resolve_fingerprint(|name| match name {
+            "user-agent" => Some(ua.to_string()),
+            "X-Device-Id" => Some(device.to_string()),
+            _ => None,
+        });
+        let phone = fingerprint("Mozilla/5.0 (iPhone)", "d-1");
+        assert_eq!(phone, fingerprint("Mozilla/5.0 (iPhone)", "d-1"));
+        assert_ne!(phone, fingerprint("Mozilla/5.0 (iPhone)", "d-2"));
+
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config.clone());
+        let mut ctx = crate::SaTokenContext::new();
+        ctx.fingerprint = phone.clone();
+        crate::SaTokenContext::set_current(ctx);
+        let token = manager.login("1001").await.unwrap();
+        crate::SaTokenContext::clear();
+
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert!(manager.check_binding(&info, None, phone.as_deref()).is_ok());
+        let other = fingerprint("curl/8.0", "d-1");
+        assert!(matches!(manager.check_binding(&info, None, other.as_deref()), Err(SaTokenError::FingerprintMismatch)));
+        assert!(manager.check_fingerprint(&info, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_permission_provider_stale_while_revalidate() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use crate::event::SaTokenListener;
+        
+        struct FlakyProvider(Arc<AtomicBool>);
+        
+        #[async_trait::async_trait]
+        impl PermissionProvider for FlakyProvider {
+            async fn get_permission_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                if self.0.load(Ordering::SeqCst) {
+                    return Err(SaTokenError::InternalError("database unreachable".to_string()));
+                }
+                Ok(vec!["order:read".to_string()])
+            }
+            
+            async fn get_role_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+                Ok(Vec::new())
+            }
+        }
+        
+        struct DegradedCounter(Arc<AtomicUsize>);
+        
+        #[async_trait::async_trait]
+        impl SaTokenListener for DegradedCounter {
+            async fn on_permission_degraded(&self, _login_id: &str, _login_type: &str, _reason: &str, stale: bool) {
+                assert!(stale);
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        
+        let config = SaTokenConfig {
+            permission_cache_timeout: 1,
+            permission_cache_max_stale: 60,
+            permission_breaker_threshold: 1,
+            ..Default::default()
+        };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let down = Arc::new(AtomicBool::new(false));
+        let degraded = Arc::new(AtomicUsize::new(0));
+        manager.set_permission_provider(Arc::new(FlakyProvider(down.clone())));
+        manager.event_bus().register(Arc::new(DegradedCounter(degraded.clone())));
+        
+        assert!(manager.has_permission("1001", "order:read").await);
+        
+        // 后端宕机且缓存过期：返回旧数据，后台刷新失败后熔断并发出降级事件
+        // Backend down and the entry expired: stale data is served, the failed background refresh opens the circuit and emits an event
+        down.store(true, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        assert!(manager.has_permission("1001", "order:read").await);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(degraded.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.permission_provider_state(), CircuitState::Open);
+        
+        // 熔断期间不再调用后端 | The backend is not called while the circuit is open
+        assert!(manager.has_permission("1001", "order:read").await);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(degraded.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_login_lockout() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use crate::event::SaTokenListener;
+        
+        struct LockedListener(Arc<AtomicU64>);
+        
+        #[async_trait::async_trait]
+        impl SaTokenListener for LockedListener {
+            async fn on_account_locked(&self, _login_id: &str, _login_type: &str, failures: u32, lock_seconds: u64) {
+                assert_eq!(failures, 2);
+                self.0.fetch_add(lock_seconds, Ordering::SeqCst);
+            }
+        }
+        
+        let config = SaTokenConfig::builder().login_lockout(2, 60, 30).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let locked = Arc::new(AtomicU64::new(0));
+        manager.event_bus().register(Arc::new(LockedListener(locked.clone())));
+        
+        // 登录成功清除失败计数 | A successful login clears the failure count
+        manager.record_failed_login("1001").await.unwrap();
+        manager.login("1001").await.unwrap();
+        assert_eq!(manager.record_failed_login("1001").await.unwrap().lockout, None);
+        
+        assert_eq!(manager.record_failed_login("1001").await.unwrap().lockout, Some(30));
+        manager.record_failed_login("1001").await.unwrap();
+        assert_eq!(locked.load(Ordering::SeqCst), 30);
+        assert!(matches!(manager.login("1001").await, Err(SaTokenError::AccountLocked(_))));
+        
+        // 其他账号类型的相同 login_id 不受影响 | The same login_id of another account type is unaffected
+        assert_eq!(manager.is_locked_by_type("1001", "admin").await.unwrap(), None);
+        assert!(manager.login_with_options("1001", Some("admin".to_string()), None, None, None, None).await.is_ok());
+        manager.record_failed_login_by_type("1001", "admin").await.unwrap();
+        manager.record_failed_login_by_type("1001", "admin").await.unwrap();
+        assert!(manager.is_locked_by_type("1001", "admin").await.unwrap().is_some());
+        
+        manager.unlock_login("1001").await.unwrap();
+        assert!(manager.login("1001").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_two_factor() {
+        use crate::Guard;
+        
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        let token = manager.login("1001").await.unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        let guard = Guard::login().and(Guard::two_factor());
+        assert!(matches!(guard.check(&manager, Some(&info)).await, Err(SaTokenError::TwoFactorRequired)));
+        assert!(matches!(manager.verify_two_factor(&token, "123456").await, Err(SaTokenError::TwoFactorNotEnabled)));
+        
+        let enrollment = manager.enroll_two_factor(&token).await.unwrap();
+        let code_at = |offset: i64| manager.totp_manager()
+            .code_at(&enrollment.secret, (Utc::now().timestamp() + offset) as u64)
+            .unwrap();
+        manager.confirm_two_factor(&token, &code_at(0)).await.unwrap();
+        assert!(manager.is_two_factor_enabled("1001", "default").await.unwrap());
+        assert!(guard.check(&manager, Some(&info)).await.is_ok());
+        
+        // 新会话需要重新校验 | A new session has to verify again
+        let other = manager.login("1001").await.unwrap();
+        assert!(!manager.is_two_factor_verified(&other).await);
+        assert!(matches!(manager.verify_two_factor(&other, "abcdef").await, Err(SaTokenError::InvalidTotpCode)));
+        manager.verify_two_factor(&other, &code_at(30)).await.unwrap();
+        assert!(manager.check_two_factor(&other).await.is_ok());
+        
+        manager.logout(&other).await.unwrap();
+        assert!(!manager.is_two_factor_verified(&other).await);
+    }
+
+    #[tokio::test]
+    async fn test_record_activity() {
+        let manager = create_manager();
+        let token = manager.login("1001").await.unwrap();
+        let info = manager.get_token_info(&token).await.unwrap();
+        
+        // 间隔内 IP 首次出现时立即写回 | A new IP is written back at once, even within the interval
+        manager.record_activity(&info, Some("10.0.0.1")).await;
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.last_ip.as_deref(), Some("10.0.0.1"));
+        let first = info.last_active_time;
+        
+        // 间隔内且 IP 未变化时不写存储 | No write within the interval when the IP is unchanged
+        manager.record_activity(&info, Some("10.0.0.1")).await;
+        assert_eq!(manager.get_token_info(&token).await.unwrap().last_active_time, first);
+        
+        // 超过间隔后写回 | Written back once the interval has passed
+        let mut stale = info.clone();
+        stale.last_active_time = first - Duration::seconds(61);
+        manager.record_activity(&stale, Some("10.0.0.1")).await;
+        let last_active = manager.get_last_active("1001", DEFAULT_ACCOUNT_TYPE).await.unwrap().unwrap();
+        assert!(last_active > first);
+        
+        assert!(manager.get_last_active("1002", DEFAULT_ACCOUNT_TYPE).await.unwrap().is_none());
+        manager.logout(&token).await.unwrap();
+        assert!(manager.get_last_active("1001", DEFAULT_ACCOUNT_TYPE).await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_login_link() {
+        use std::sync::Mutex;
+        use crate::event::SaTokenListener;
+        
+        struct LinkListener(Arc<Mutex<Vec<String>>>);
+        
+        #[async_trait::async_trait]
+        impl SaTokenListener for LinkListener {
+            async fn on_login_link_consumed(&self, login_id: &str, _token: &str, _login_type: &str) {
+                self.0.lock().unwrap().push(format!("consumed:{}", login_id));
+            }
+            
+            async fn on_login_link_rejected(&self, login_id: &str, _login_type: &str, reason: &str) {
+                self.0.lock().unwrap().push(format!("{}:{}", reason, login_id));
+            }
+        }
+        
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        manager.event_bus().register(Arc::new(LinkListener(seen.clone())));
+        
+        let link = manager.create_login_link("1001", "default", 600).await.unwrap();
+        let token = manager.consume_login_link(&link).await.unwrap();
+        assert_eq!(manager.get_token_info(&token).await.unwrap().login_id, "1001");
+        assert!(matches!(manager.consume_login_link(&link).await, Err(SaTokenError::LoginLinkReused)));
+        assert!(matches!(manager.consume_login_link("unknown").await, Err(SaTokenError::LoginLinkInvalid)));
+        
+        // 已过期的链接 | An expired link
+        let expired = manager.create_login_link("1002", "default", 600).await.unwrap();
+        let mut record: LoginLink = serde_json::from_str(&manager.storage.get(&LoginLink::key(&expired)).await.unwrap().unwrap()).unwrap();
+        record.expire_time = Utc::now() - Duration::seconds(1);
+        manager.save_login_link(&expired, &record).await.unwrap();
+        assert!(matches!(manager.consume_login_link(&expired).await, Err(SaTokenError::LoginLinkInvalid)));
+        
+        assert_eq!(*seen.lock().unwrap(), vec!["consumed:1001", "reused:1001", "expired:1002"]);
+    }
+
+    #[tokio::test]
+    async fn test_remember_me() {
+        let config = SaTokenConfig::builder().timeout(60).remember_me(86400).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert!(manager.restore_remembered(None, Some("unknown")).await.is_none());
+        
+        let token = manager.login("1001").await.unwrap();
+        let remember = manager.remember_me(&token).await.unwrap();
+        assert!(manager.remember_me_cookie(&remember).starts_with(&format!("satoken-remember={}; Path=/; Max-Age=86400", remember)));
+        
+        // 会话有效时不重建 | No restore while the session is valid
+        assert!(manager.restore_remembered(Some(token.as_str()), Some(&remember)).await.is_none());
+        
+        // 会话失效后重建并轮换 | Restore and rotate once the session is gone
+        manager.logout(&token).await.unwrap();
+        let restored = manager.restore_remembered(Some(token.as_str()), Some(&remember)).await.unwrap();
+        assert_eq!(manager.get_token_info(&restored.token).await.unwrap().login_id, "1001");
+        assert_ne!(restored.remember_token, remember);
+        assert!(restored.set_cookies[0].starts_with(&format!("sa-token={}; Path=/; Max-Age=60", restored.token.as_str())));
+        assert!(matches!(manager.restore_session(&remember).await, Err(SaTokenError::RememberMeInvalid)));
+        
+        // 登出所有设备会撤销 remember-me token | Logging out every device revokes remember-me tokens
+        manager.logout_by_login_id("1001").await.unwrap();
+        assert!(manager.restore_remembered(None, Some(&restored.remember_token)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_response_headers() {
+        let config = SaTokenConfig::builder().timeout(60).is_write_header(true).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let token = manager.login("1001").await.unwrap();
+        assert_eq!(manager.token_response_headers(&token), vec![
+            ("Set-Cookie".to_string(), format!("sa-token={}; Path=/; Max-Age=60; HttpOnly; SameSite=Lax", token.as_str())),
+            ("sa-token".to_string(), token.as_str().to_string()),
+        ]);
+        assert_eq!(manager.logout_response_headers()[0].1, "sa-token=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax");
+        
+        // 不从 cookie 读取 token 时也不写 cookie | No cookie when tokens are not read from cookies
+        let config = SaTokenConfig { is_read_cookie: false, ..Default::default() };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert!(manager.token_response_headers(&token).is_empty());
+        assert!(manager.logout_response_headers().is_empty());
+        
+        // 自定义 cookie 属性，登出时以相同的域名与路径过期 | Custom attributes; logout expires the same domain and path
+        let cookie = SaCookieConfig {
+            domain: Some("example.com".to_string()),
+            path: "/app".to_string(),
+            secure: true,
+            same_site: Some(SameSite::Strict),
+            max_age: Some(-1),
+            ..Default::default()
+        };
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::builder().cookie(cookie).build_config());
+        assert_eq!(manager.token_cookie(&token), format!("sa-token={}; Domain=example.com; Path=/app; HttpOnly; Secure; SameSite=Strict", token.as_str()));
+        assert_eq!(manager.clear_token_cookie(), "sa-token=; Domain=example.com; Path=/app; Max-Age=0; HttpOnly; Secure; SameSite=Strict");
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_token() {
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        let token = manager.login("1001").await.unwrap();
+        assert_eq!(manager.open_safe(&token, 120).await.unwrap(), token);
+        
+        let new_token = manager.regenerate_token(&token).await.unwrap();
+        assert_ne!(new_token, token);
+        assert!(!manager.is_valid(&token).await);
+        assert_eq!(manager.get_token_info(&new_token).await.unwrap().login_id, "1001");
+        let mapped = manager.storage.get(&SaTokenManager::login_token_key("1001", DEFAULT_ACCOUNT_TYPE)).await.unwrap();
+        assert_eq!(mapped.as_deref(), Some(new_token.as_str()));
+        assert!(manager.is_safe(&new_token).await);
+        assert!(!manager.is_safe(&token).await);
+        
+        // 开启 regenerate_token_on_elevation 时，二级认证后换发新 token | A new token after safe-auth when enabled
+        let config = SaTokenConfig::builder().regenerate_token_on_elevation(true).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let token = manager.login("1001").await.unwrap();
+        let elevated = manager.open_safe(&token, 120).await.unwrap();
+        assert_ne!(elevated, token);
+        assert!(manager.is_safe(&elevated).await);
+        assert!(!manager.is_valid(&token).await);
+    }
+
+    #[tokio::test]
+    async fn test_stateless_mode() {
+        let storage = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig::builder().jwt_mode(JwtMode::Stateless).jwt_secret_key("test-secret").build_config();
+        let manager = SaTokenManager::new(storage.clone(), config);
+        manager.set_permissions("1001", vec!["user:read".to_string()]).await;
+        
+        let token = manager.login_with_options("1001", None, Some("ios".to_string()), None, None, None).await.unwrap();
+        assert!(storage.keys("sa:*").await.unwrap().is_empty());
+        let info = manager.get_token_info(&token).await.unwrap();
+        assert_eq!(info.login_id, "1001");
+        assert_eq!(info.device.as_deref(), Some("ios"));
+        assert!(!info.is_expired());
+        
+        // 权限来自 token 声明而不是当前的权限数据 | Permissions come from the claims, not the current permission data
+        manager.set_permissions("1001", Vec::new()).await;
+        crate::SaTokenContext::set_current(crate::SaTokenContext {
+            token: Some(token.clone()),
+            token_info: Some(Arc::new(info)),
+            login_id: Some("1001".to_string()),
+            ..crate::SaTokenContext::new()
+        });
+        assert!(manager.has_permission("1001", "user:read").await);
+        assert!(!manager.has_permission("1002", "user:read").await);
+        crate::SaTokenContext::clear();
+        assert!(!manager.has_permission("1001", "user:read").await);
+        
+        // 篡改或由其他密钥签发的 token 无效 | Tampered tokens or tokens from another key are rejected
+        let forged = SaTokenConfig::builder().jwt_mode(JwtMode::Stateless).jwt_secret_key("other-secret").build_config();
+        let forged = SaTokenManager::new(Arc::new(MemoryStorage::new()), forged).login("1001").await.unwrap();
+        assert!(matches!(manager.get_token_info(&forged).await, Err(SaTokenError::TokenNotFound)));
+        
+        // 无状态 token 必须有过期时间 | Stateless tokens must expire
+        let config = SaTokenConfig::builder().jwt_mode(JwtMode::Stateless).jwt_secret_key("test-secret").timeout(-1).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        assert!(matches!(manager.login("1001").await, Err(SaTokenError::ConfigError(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_jwt_modes() {
+        // Simple：JWT 只是 token 风格，存储仍然是权威来源 | Simple: the JWT is only a token style, storage stays authoritative
+        let storage = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig::builder().jwt_mode(JwtMode::Simple).jwt_secret_key("test-secret").build_config();
+        let manager = SaTokenManager::new(storage.clone(), config);
+        let token = manager.login("1001").await.unwrap();
+        assert_eq!(token.as_str().split('.').count(), 3);
+        assert!(storage.exists(&format!("sa:token:{}", token.as_str())).await.unwrap());
+        manager.logout(&token).await.unwrap();
+        assert!(!manager.is_valid(&token).await);
+        
+        // Mixin：登录信息在声明中，存储只保存吊销标记 | Mixin: login data lives in the claims, storage only holds revocation markers
+        let storage = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig::builder().jwt_mode(JwtMode::Mixin).jwt_secret_key("test-secret").build_config();
+        let manager = SaTokenManager::new(storage.clone(), config);
+        let first = manager.login("1001").await.unwrap();
+        let second = manager.login("1001").await.unwrap();
+        assert!(storage.keys("sa:token:*").await.unwrap().is_empty());
+        assert!(manager.is_valid(&first).await);
+        
+        manager.logout(&first).await.unwrap();
+        assert!(!manager.is_valid(&first).await);
+        assert!(manager.is_valid(&second).await);
+        
+        manager.kick_out("1001").await.unwrap();
+        assert!(!manager.is_valid(&second).await);
+        let third = manager.login("1001").await.unwrap();
+        assert!(manager.is_valid(&third).await);
+        
+        // 不允许并发登录时，新登录吊销旧 token | Without concurrent login, a new login revokes the older tokens
+        let config = SaTokenConfig::builder().jwt_mode(JwtMode::Mixin).jwt_secret_key("test-secret").is_concurrent(false).build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let first = manager.login("1001").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let second = manager.login("1001").await.unwrap();
+        assert!(!manager.is_valid(&first).await);
+        assert!(manager.is_valid(&second).await);
+    }
 }
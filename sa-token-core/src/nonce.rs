@@ -131,10 +131,26 @@
 //!    - Prevents time-based attacks
 //!    
 //! 5. ✅ Atomic Operations | 原子操作
-//!    - validate_and_consume() is atomic
-//!    - Prevents race conditions
+//!    - validate_and_consume() uses SaStorage::set_nx
+//!    - Prevents race conditions, also across instances sharing Redis
+//!    - 基于 SaStorage::set_nx，共享 Redis 的多个实例之间同样不会重复消费
 //! ```
 //!
+//! ## Sliding Window | 滑动窗口
+//!
+//! ```text
+//! NonceManager::new(storage, 60).window(300)
+//!
+//! consume_in_window(nonce)
+//!   ├─→ |now - nonce 时间戳| > 300s → InvalidNonceTimestamp
+//!   │   |now - nonce timestamp| > 300s → InvalidNonceTimestamp
+//!   └─→ validate_and_consume()，记录保留 600s（窗口两侧）后由存储 TTL 自动清理
+//!       validate_and_consume(), the record is kept 600s (both sides of the window) and expired by the storage TTL
+//! ```
+//!
+//! 超出窗口的 nonce 直接被拒绝，因此存储中只需保留窗口内的记录。
+//! Nonces outside the window are rejected up front, so storage only keeps records inside the window.
+//!
 //! ## Usage Examples | 使用示例
 //!
 //! ### Example 1: Login with Nonce | 带 Nonce 的登录
@@ -210,7 +226,7 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use sa_token_adapter::storage::SaStorage;
 use crate::error::{SaTokenError, SaTokenResult};
-use uuid::Uuid;
+use crate::id::{IdGenerator, IdKind, RandomIdGenerator};
 
 /// Nonce Manager | Nonce 管理器
 ///
@@ -220,6 +236,8 @@ use uuid::Uuid;
 pub struct NonceManager {
     storage: Arc<dyn SaStorage>,
     timeout: i64,
+    window: i64,
+    id_generator: Arc<dyn IdGenerator>,
 }
 
 impl NonceManager {
@@ -230,25 +248,54 @@ impl NonceManager {
     /// * `storage` - Storage backend | 存储后端
     /// * `timeout` - Nonce validity period in seconds | Nonce 有效期（秒）
     pub fn new(storage: Arc<dyn SaStorage>, timeout: i64) -> Self {
-        Self { storage, timeout }
+        Self { storage, timeout, window: timeout, id_generator: Arc::new(RandomIdGenerator::default()) }
+    }
+
+    /// Set the generator for the random part of nonces | 设置 nonce 随机部分的生成器
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `generator` - Unique ID generator | 唯一 ID 生成器
+    pub fn id_generator(mut self, generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = generator;
+        self
+    }
+
+    /// Set the sliding window for nonce timestamps | 设置 nonce 时间戳的滑动窗口
+    ///
+    /// Used nonces are kept for twice the window, since timestamps on both sides of now are accepted.
+    /// 已使用的 nonce 保留两倍窗口时长，因为当前时间两侧的时间戳都会被接受。
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `seconds` - Maximum distance between the nonce timestamp and now | nonce 时间戳与当前时间的最大差值
+    pub fn window(mut self, seconds: i64) -> Self {
+        self.window = seconds;
+        self.timeout = seconds * 2;
+        self
+    }
+
+    /// Sliding window in seconds | 滑动窗口（秒）
+    pub fn window_seconds(&self) -> i64 {
+        self.window
     }
 
     /// Generate a new nonce | 生成新的 nonce
     ///
-    /// Generates a unique nonce using timestamp + UUID to ensure uniqueness.
-    /// 使用时间戳 + UUID 生成唯一的 nonce 以确保唯一性。
+    /// Generates a unique nonce from the timestamp and the `IdGenerator` (secure random by default).
+    /// 使用时间戳和 `IdGenerator`（默认安全随机）生成唯一的 nonce。
     ///
     /// # Returns | 返回
     ///
-    /// Unique nonce string in format: `nonce_{timestamp_ms}_{uuid}`
-    /// 格式为 `nonce_{时间戳_毫秒}_{uuid}` 的唯一 nonce 字符串
+    /// Unique nonce string in format: `nonce_{timestamp_ms}_{id}`
+    /// 格式为 `nonce_{时间戳_毫秒}_{id}` 的唯一 nonce 字符串
     ///
     /// # Format | 格式
     ///
     /// ```text
     /// nonce_1234567890123_abc123def456
     ///   │         │            │
-    ///   │         │            └─ UUID (32 hex chars)
+    ///   │         │            └─ IdGenerator output (32 hex chars by default)
     ///   │         └─ Timestamp in milliseconds
     ///   └─ Prefix
     /// ```
@@ -260,7 +307,7 @@ impl NonceManager {
     /// // Returns: "nonce_1701234567890_a1b2c3d4e5f6..."
     /// ```
     pub fn generate(&self) -> String {
-        format!("nonce_{}_{}", Utc::now().timestamp_millis(), Uuid::new_v4().simple())
+        format!("nonce_{}_{}", Utc::now().timestamp_millis(), self.id_generator.generate(IdKind::Nonce))
     }
 
     /// Store and mark nonce as used | 存储并标记 nonce 为已使用
@@ -289,15 +336,8 @@ impl NonceManager {
     /// // Storage now contains: sa:nonce:nonce_123_abc (expires after timeout)
     /// ```
     pub async fn store(&self, nonce: &str, login_id: &str) -> SaTokenResult<()> {
-        let key = format!("sa:nonce:{}", nonce);
-        let value = serde_json::json!({
-            "login_id": login_id,
-            "created_at": Utc::now().to_rfc3339(),
-        }).to_string();
-
         // Set TTL to automatically expire the nonce
-        let ttl = Some(std::time::Duration::from_secs(self.timeout as u64));
-        self.storage.set(&key, &value, ttl)
+        self.storage.set(&Self::key(nonce), &Self::record(login_id), self.ttl())
             .await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
 
@@ -339,11 +379,9 @@ impl NonceManager {
     /// }
     /// ```
     pub async fn validate(&self, nonce: &str) -> SaTokenResult<bool> {
-        let key = format!("sa:nonce:{}", nonce);
-        
         // Check if nonce exists in storage
         // 检查 nonce 是否存在于存储中
-        let exists = self.storage.get(&key)
+        let exists = self.storage.get(&Self::key(nonce))
             .await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
             .is_some();
@@ -376,12 +414,13 @@ impl NonceManager {
     /// This operation is **atomic** from the application perspective:
     /// 此操作从应用程序角度来看是**原子性**的：
     ///
-    /// 1. Check if nonce exists (validate)
-    /// 2. If valid, store it immediately (consume)
-    /// 3. Return success
+    /// 1. Store the nonce only if it is absent (`SaStorage::set_nx`)
+    /// 2. Return success if it was stored, `NonceAlreadyUsed` otherwise
     ///
-    /// If two requests use the same nonce simultaneously, only one will succeed.
-    /// 如果两个请求同时使用相同的 nonce，只有一个会成功。
+    /// If two requests use the same nonce simultaneously, only one will succeed,
+    /// even on different instances as long as the storage backend implements `set_nx` atomically (Redis, memory).
+    /// 如果两个请求同时使用相同的 nonce，只有一个会成功；
+    /// 只要存储后端原子地实现了 `set_nx`（Redis、内存），不同实例之间也是如此。
     ///
     /// # Integration with Login | 与登录集成
     ///
@@ -406,17 +445,35 @@ impl NonceManager {
     /// assert!(matches!(result, Err(SaTokenError::NonceAlreadyUsed)));
     /// ```
     pub async fn validate_and_consume(&self, nonce: &str, login_id: &str) -> SaTokenResult<()> {
-        // 1. Validate: check if nonce has NOT been used
-        // 验证：检查 nonce 是否未被使用
-        if !self.validate(nonce).await? {
-            return Err(SaTokenError::NonceAlreadyUsed);
+        // Validate and consume in one storage call: only the first writer succeeds
+        // 验证与消费在一次存储调用中完成：只有第一个写入者成功
+        let stored = self.storage.set_nx(&Self::key(nonce), &Self::record(login_id), self.ttl())
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        if stored {
+            Ok(())
+        } else {
+            Err(SaTokenError::NonceAlreadyUsed)
         }
+    }
 
-        // 2. Consume: store nonce to mark as used
-        // 消费：存储 nonce 以标记为已使用
-        self.store(nonce, login_id).await?;
-        
-        Ok(())
+    /// Check the timestamp against the sliding window, then consume the nonce
+    /// 按滑动窗口检查时间戳，然后消费 nonce
+    ///
+    /// Only accepts nonces produced by [`generate`](Self::generate).
+    /// 只接受 [`generate`](Self::generate) 生成的 nonce。
+    ///
+    /// # Returns | 返回
+    ///
+    /// * `Ok(())` - Nonce is fresh and now consumed | Nonce 在窗口内且已消费
+    /// * `Err(InvalidNonceTimestamp)` - Outside the window | 超出窗口
+    /// * `Err(NonceAlreadyUsed)` - Nonce has already been used | Nonce 已被使用
+    pub async fn consume_in_window(&self, nonce: &str, login_id: &str) -> SaTokenResult<()> {
+        if !self.check_timestamp(nonce, self.window)? {
+            return Err(SaTokenError::InvalidNonceTimestamp);
+        }
+        self.validate_and_consume(nonce, login_id).await
     }
 
     /// Extract timestamp from nonce and check if it's within valid time window
@@ -479,7 +536,7 @@ impl NonceManager {
             .map_err(|_| SaTokenError::InvalidNonceTimestamp)?;
 
         let nonce_time = DateTime::from_timestamp_millis(timestamp_ms)
-            .ok_or_else(|| SaTokenError::InvalidNonceTimestamp)?;
+            .ok_or(SaTokenError::InvalidNonceTimestamp)?;
 
         // Calculate time difference
         // 计算时间差
@@ -527,6 +584,22 @@ impl NonceManager {
         // 对于 Redis/Memory 存储，这是一个空操作
         Ok(())
     }
+
+    fn key(nonce: &str) -> String {
+        format!("sa:nonce:{}", nonce)
+    }
+
+    fn record(login_id: &str) -> String {
+        serde_json::json!({
+            "login_id": login_id,
+            "created_at": Utc::now().to_rfc3339(),
+        }).to_string()
+    }
+
+    /// Records never expire when the timeout is not positive | 超时时间不大于 0 时记录不过期
+    fn ttl(&self) -> Option<std::time::Duration> {
+        (self.timeout > 0).then(|| std::time::Duration::from_secs(self.timeout as u64))
+    }
 }
 
 #[cfg(test)]
@@ -591,5 +664,25 @@ mod tests {
         // Should also be within 1 second
         assert!(nonce_mgr.check_timestamp(&nonce, 1).unwrap());
     }
-}
 
+    #[tokio::test]
+    async fn test_nonce_sliding_window() {
+        let storage = Arc::new(MemoryStorage::new());
+        let nonce_mgr = NonceManager::new(storage.clone(), 60).window(300);
+        assert_eq!(nonce_mgr.window_seconds(), 300);
+
+        let nonce = nonce_mgr.generate();
+        nonce_mgr.consume_in_window(&nonce, "user_123").await.unwrap();
+
+        // Another instance sharing the storage sees the nonce as used
+        // 共享存储的另一个实例同样认为 nonce 已使用
+        let other = NonceManager::new(storage.clone(), 60).window(300);
+        assert!(matches!(other.consume_in_window(&nonce, "user_123").await, Err(SaTokenError::NonceAlreadyUsed)));
+        let ttl = storage.ttl(&format!("sa:nonce:{}", nonce)).await.unwrap().unwrap();
+        assert!(ttl.as_secs() > 300 && ttl.as_secs() <= 600);
+
+        let stale = format!("nonce_{}_abc", Utc::now().timestamp_millis() - 301_000);
+        assert!(matches!(nonce_mgr.consume_in_window(&stale, "user_123").await, Err(SaTokenError::InvalidNonceTimestamp)));
+        assert!(matches!(nonce_mgr.consume_in_window("client-nonce", "user_123").await, Err(SaTokenError::InvalidNonceFormat)));
+    }
+}
@@ -104,9 +104,14 @@
 //!
 //! ```text
 //! oauth2:client:{client_id}         - Client information | 客户端信息
+//! oauth2:clients                    - Registered client IDs | 已注册的客户端 ID 集合
 //! oauth2:code:{authorization_code}  - Authorization code | 授权码 (TTL: 10 min)
 //! oauth2:token:{access_token}       - Token info | 令牌信息 (TTL: 1 hour)
 //! oauth2:refresh:{refresh_token}    - Refresh token | 刷新令牌 (TTL: 30 days)
+//! oauth2:device:{device_code}       - Device authorization | 设备授权 (TTL: 10 min)
+//! oauth2:device:user:{user_code}    - User code → device code | 用户码索引 (TTL: 10 min)
+//! oauth2:consent:{user_id}:{client} - Granted scopes | 已授权的权限范围 (no TTL)
+//! oauth2:authorize:{ticket}         - Authorization awaiting consent | 等待确认的授权请求 (TTL: 10 min)
 //! ```
 //!
 //! ### Security Validations | 安全验证
@@ -131,6 +136,7 @@
 //! │    - Single use (consumed after exchange) | 单次使用    │
 //! │    - Client ID match | 客户端ID匹配                     │
 //! │    - Redirect URI match | 回调URI匹配                   │
+//! │    - PKCE code_verifier match | PKCE校验码匹配          │
 //! ├────────────────────────────────────────────────────────┤
 //! │ 5. Token Validation | 令牌验证                          │
 //! │    - Token exists | 令牌存在                            │
@@ -175,11 +181,21 @@
 //! ```
 
 use std::sync::Arc;
+use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 use sa_token_adapter::storage::SaStorage;
 use crate::error::{SaTokenError, SaTokenResult};
+use crate::page::{ConsentPage, DefaultPageRenderer, PageRenderer, SaPage};
+use crate::id::{IdGenerator, IdKind, RandomIdGenerator};
+use crate::secure::{PasswordHasher, SaSecureUtil, SecretHasher};
+use crate::basic::parse_basic;
+use crate::introspection::IntrospectionResponse;
+use crate::consent::ConsentStore;
+use crate::client_store::{ClientStore, StorageClientStore};
+use crate::oidc::{OidcProvider, OPENID_SCOPE};
 
 /// OAuth2 Client Information | OAuth2 客户端信息
 /// 
@@ -230,6 +246,397 @@ pub struct AuthorizationCode {
     
     /// Code expiration timestamp | 授权码过期时间戳
     pub expires_at: DateTime<Utc>,
+
+    /// PKCE code challenge sent with the authorization request | 授权请求携带的 PKCE code_challenge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_challenge: Option<String>,
+
+    /// PKCE code challenge method | PKCE code_challenge 计算方式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_challenge_method: Option<PkceMethod>,
+
+    /// OpenID Connect nonce echoed in the ID token | 回显到 ID Token 中的 OpenID Connect nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+impl AuthorizationCode {
+    /// Bind the OpenID Connect `nonce` of the authorization request | 绑定授权请求的 OpenID Connect `nonce`
+    pub fn with_nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+}
+
+/// PKCE Code Challenge Method | PKCE code_challenge 计算方式
+///
+/// Transformation applied to the `code_verifier` to produce the `code_challenge` (RFC 7636).
+/// 将 `code_verifier` 转换为 `code_challenge` 的方式（RFC 7636）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PkceMethod {
+    /// `code_challenge = code_verifier` | 明文
+    #[serde(rename = "plain")]
+    Plain,
+
+    /// `code_challenge = BASE64URL(SHA256(code_verifier))` | SHA-256 摘要
+    S256,
+}
+
+impl PkceMethod {
+    /// Parse a `code_challenge_method` value | 解析 `code_challenge_method` 参数
+    ///
+    /// A missing method defaults to `plain` as required by RFC 7636.
+    /// 未传入时按 RFC 7636 默认为 `plain`。
+    pub fn parse(method: Option<&str>) -> SaTokenResult<Self> {
+        match method {
+            None | Some("plain") => Ok(Self::Plain),
+            Some("S256") => Ok(Self::S256),
+            Some(_) => Err(SaTokenError::OAuth2InvalidCodeChallenge),
+        }
+    }
+
+    /// Wire name of the method | 方法名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::S256 => "S256",
+        }
+    }
+
+    /// Compute the code challenge for a verifier | 根据 code_verifier 计算 code_challenge
+    pub fn challenge(&self, code_verifier: &str) -> String {
+        match self {
+            Self::Plain => code_verifier.to_string(),
+            Self::S256 => {
+                use base64::Engine;
+                use sha2::{Digest, Sha256};
+                base64::engine::general_purpose::URL_SAFE_NO_PAD
+                    .encode(Sha256::digest(code_verifier.as_bytes()))
+            }
+        }
+    }
+
+    /// Check that a value is 43-128 unreserved characters | 校验值为 43-128 位非保留字符
+    fn is_valid_value(value: &str) -> bool {
+        (43..=128).contains(&value.len())
+            && value.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
+    }
+}
+
+/// Token Endpoint Request | 令牌端点请求
+///
+/// Form parameters of `POST /token` (RFC 6749 §4.1.3 and §6).
+/// `POST /token` 的表单参数（RFC 6749 §4.1.3 与 §6）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OAuth2TokenRequest {
+    /// `authorization_code`, `refresh_token`, `client_credentials`, `DEVICE_CODE_GRANT_TYPE` or `TOKEN_EXCHANGE_GRANT_TYPE`
+    /// `authorization_code`、`refresh_token`、`client_credentials`、`DEVICE_CODE_GRANT_TYPE` 或 `TOKEN_EXCHANGE_GRANT_TYPE`
+    pub grant_type: String,
+
+    /// Client identifier, may instead be sent with HTTP Basic | 客户端标识符，也可通过 HTTP Basic 发送
+    #[serde(default)]
+    pub client_id: String,
+
+    /// Client secret, absent for public clients | 客户端密钥，公共客户端不传
+    #[serde(default)]
+    pub client_secret: Option<String>,
+
+    /// Authorization code (authorization_code grant) | 授权码（authorization_code 授权）
+    #[serde(default)]
+    pub code: Option<String>,
+
+    /// Redirect URI used in authorization (authorization_code grant) | 授权时使用的回调 URI
+    #[serde(default)]
+    pub redirect_uri: Option<String>,
+
+    /// PKCE code verifier (authorization_code grant) | PKCE code_verifier
+    #[serde(default)]
+    pub code_verifier: Option<String>,
+
+    /// Device code (device_code grant) | 设备码（device_code 授权）
+    #[serde(default)]
+    pub device_code: Option<String>,
+
+    /// Refresh token (refresh_token grant) | 刷新令牌（refresh_token 授权）
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+
+    /// Space-delimited scope (refresh_token / client_credentials / token exchange grant) | 空格分隔的权限范围（refresh_token / client_credentials / 令牌交换授权）
+    #[serde(default)]
+    pub scope: Option<String>,
+
+    /// Token to exchange (token exchange grant) | 要交换的令牌（令牌交换授权）
+    #[serde(default)]
+    pub subject_token: Option<String>,
+
+    /// Must be `ACCESS_TOKEN_TYPE` (token exchange grant) | 必须为 `ACCESS_TOKEN_TYPE`（令牌交换授权）
+    #[serde(default)]
+    pub subject_token_type: Option<String>,
+
+    /// Token of the party acting on behalf of the subject (token exchange grant) | 代表主体行事一方的令牌（令牌交换授权）
+    #[serde(default)]
+    pub actor_token: Option<String>,
+
+    /// Must be `ACCESS_TOKEN_TYPE` when `actor_token` is sent (token exchange grant) | 发送 `actor_token` 时必须为 `ACCESS_TOKEN_TYPE`（令牌交换授权）
+    #[serde(default)]
+    pub actor_token_type: Option<String>,
+
+    /// Space-delimited audiences of the issued token (token exchange grant) | 空格分隔的签发令牌受众（令牌交换授权）
+    #[serde(default)]
+    pub audience: Option<String>,
+}
+
+/// Introspection Request | 内省请求
+///
+/// Form parameters of `POST /introspect` (RFC 7662 §2.1). The calling resource server
+/// authenticates with HTTP Basic or with `client_id` / `client_secret` in the form.
+/// `POST /introspect` 的表单参数（RFC 7662 §2.1）。调用方资源服务器通过 HTTP Basic
+/// 或表单中的 `client_id` / `client_secret` 认证。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OAuth2IntrospectionRequest {
+    /// Token to introspect | 要内省的令牌
+    pub token: String,
+
+    /// `access_token` or `refresh_token`, decides the lookup order | `access_token` 或 `refresh_token`，决定查找顺序
+    #[serde(default)]
+    pub token_type_hint: Option<String>,
+
+    /// Caller client ID when not using HTTP Basic | 未使用 HTTP Basic 时的调用方客户端 ID
+    #[serde(default)]
+    pub client_id: Option<String>,
+
+    /// Caller client secret when not using HTTP Basic | 未使用 HTTP Basic 时的调用方客户端密钥
+    #[serde(default)]
+    pub client_secret: Option<String>,
+}
+
+/// Authorization Request | 授权请求
+///
+/// Query parameters of `GET /authorize` (RFC 6749 §4.1.1), with the PKCE challenge
+/// (RFC 7636 §4.3) and the OpenID Connect `nonce`.
+/// `GET /authorize` 的查询参数（RFC 6749 §4.1.1），包含 PKCE 挑战（RFC 7636 §4.3）
+/// 与 OpenID Connect 的 `nonce`。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OAuth2AuthorizeRequest {
+    /// Must be `code` | 必须为 `code`
+    #[serde(default)]
+    pub response_type: String,
+
+    /// Client identifier | 客户端标识符
+    #[serde(default)]
+    pub client_id: String,
+
+    /// Registered redirect URI | 已注册的回调 URI
+    #[serde(default)]
+    pub redirect_uri: String,
+
+    /// Space-delimited scope | 空格分隔的权限范围
+    #[serde(default)]
+    pub scope: Option<String>,
+
+    /// Client state, returned unchanged | 客户端 state，原样返回
+    #[serde(default)]
+    pub state: Option<String>,
+
+    /// PKCE code challenge, required for public clients | PKCE code_challenge，公共客户端必填
+    #[serde(default)]
+    pub code_challenge: Option<String>,
+
+    /// `S256` or `plain` (default) | `S256` 或 `plain`（默认）
+    #[serde(default)]
+    pub code_challenge_method: Option<String>,
+
+    /// OpenID Connect nonce, echoed in the ID token | OpenID Connect nonce，原样写入 ID Token
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+/// Consent Decision | 授权确认结果
+///
+/// Form posted by the consent page to `POST /authorize`.
+/// 授权确认页提交到 `POST /authorize` 的表单。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OAuth2ConsentDecision {
+    /// One-time ticket rendered into the consent page | 渲染在确认页中的一次性票据
+    #[serde(default)]
+    pub ticket: String,
+
+    /// `approve` or `deny` | `approve` 或 `deny`
+    #[serde(default)]
+    pub decision: String,
+}
+
+/// Authorization Endpoint Outcome | 授权端点结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OAuth2AuthorizeResponse {
+    /// Redirect the user agent to the client with `code` or `error` | 携带 `code` 或 `error` 重定向回客户端
+    Redirect(String),
+
+    /// Show the consent page (HTML) | 展示授权确认页（HTML）
+    Consent(String),
+}
+
+/// Authorization request waiting for the user's consent | 等待用户确认的授权请求
+#[derive(Serialize, Deserialize)]
+struct PendingAuthorization {
+    user_id: String,
+    scope: Vec<String>,
+    request: OAuth2AuthorizeRequest,
+}
+
+/// Revocation Request | 撤销请求
+///
+/// Form parameters of `POST /revoke` (RFC 7009 §2.1); same shape as the introspection request.
+/// `POST /revoke` 的表单参数（RFC 7009 §2.1），与内省请求结构相同。
+pub type OAuth2RevocationRequest = OAuth2IntrospectionRequest;
+
+/// OAuth2 Error Response | OAuth2 错误响应
+///
+/// Error body of the token endpoint (RFC 6749 §5.2), e.g. `{"error":"invalid_grant"}`.
+/// 令牌端点的错误响应体（RFC 6749 §5.2），例如 `{"error":"invalid_grant"}`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2ErrorResponse {
+    /// Error code such as `invalid_grant` | 错误码，例如 `invalid_grant`
+    pub error: String,
+
+    /// Human-readable description | 可读的错误描述
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+impl OAuth2ErrorResponse {
+    /// HTTP status for this error: 401 for `invalid_client` and `invalid_token`,
+    /// 403 for `insufficient_scope`, 500 for `server_error`, else 400
+    /// 对应的 HTTP 状态码：`invalid_client` 与 `invalid_token` 为 401，`insufficient_scope` 为 403，
+    /// `server_error` 为 500，其余为 400
+    pub fn status_code(&self) -> u16 {
+        match self.error.as_str() {
+            "invalid_client" | "invalid_token" => 401,
+            "insufficient_scope" => 403,
+            "server_error" => 500,
+            _ => 400,
+        }
+    }
+}
+
+impl From<&SaTokenError> for OAuth2ErrorResponse {
+    fn from(err: &SaTokenError) -> Self {
+        let error = match err {
+            SaTokenError::OAuth2ClientNotFound | SaTokenError::OAuth2InvalidCredentials => "invalid_client",
+            SaTokenError::OAuth2CodeNotFound
+            | SaTokenError::OAuth2ClientIdMismatch
+            | SaTokenError::OAuth2RedirectUriMismatch
+            | SaTokenError::OAuth2RefreshTokenNotFound
+            | SaTokenError::OAuth2InvalidRefreshToken
+            | SaTokenError::OAuth2InvalidCodeVerifier
+            | SaTokenError::OAuth2PkceRequired
+            | SaTokenError::RefreshTokenReused
+            | SaTokenError::OAuth2UserCodeNotFound
+            | SaTokenError::TokenExpired => "invalid_grant",
+            SaTokenError::OAuth2InvalidScope | SaTokenError::OAuth2ScopeExceedsGrant => "invalid_scope",
+            SaTokenError::OAuth2InvalidCodeChallenge
+            | SaTokenError::OAuth2MissingParameter(_)
+            | SaTokenError::OAuth2ConsentTicketInvalid
+            | SaTokenError::OAuth2InvalidExchangeToken(_) => "invalid_request",
+            SaTokenError::OAuth2InvalidTarget(_) => "invalid_target",
+            SaTokenError::OAuth2UnsupportedGrantType(_) => "unsupported_grant_type",
+            SaTokenError::OAuth2UnsupportedResponseType(_) => "unsupported_response_type",
+            SaTokenError::OAuth2UnauthorizedClient => "unauthorized_client",
+            SaTokenError::OAuth2AuthorizationPending => "authorization_pending",
+            SaTokenError::OAuth2SlowDown => "slow_down",
+            SaTokenError::OAuth2AccessDenied => "access_denied",
+            SaTokenError::OAuth2DeviceCodeExpired => "expired_token",
+            SaTokenError::OAuth2AccessTokenNotFound => "invalid_token",
+            SaTokenError::OAuth2InsufficientScope(_) => "insufficient_scope",
+            _ => "server_error",
+        };
+        // Internal failures are not described to the client | 内部错误不向客户端暴露细节
+        let error_description = (error != "server_error").then(|| err.to_string());
+        Self { error: error.to_string(), error_description }
+    }
+}
+
+/// Grant type of the device authorization flow (RFC 8628) | 设备授权流程的授权类型（RFC 8628）
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Grant type of token exchange (RFC 8693) | 令牌交换的授权类型（RFC 8693）
+pub const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+
+/// Token type identifier of access tokens (RFC 8693 §3) | 访问令牌的令牌类型标识（RFC 8693 §3）
+pub const ACCESS_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:access_token";
+
+/// Device Authorization Response | 设备授权响应
+///
+/// Returned to the device by the device authorization endpoint (RFC 8628 §3.2).
+/// 设备授权端点返回给设备的响应（RFC 8628 §3.2）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceAuthorization {
+    /// Code the device polls the token endpoint with | 设备轮询令牌端点使用的设备码
+    pub device_code: String,
+
+    /// Short code the user enters on the verification page | 用户在验证页输入的短码
+    pub user_code: String,
+
+    /// Verification page URI | 验证页 URI
+    pub verification_uri: String,
+
+    /// Verification URI with the user code pre-filled | 预填用户码的验证页 URI
+    pub verification_uri_complete: String,
+
+    /// Lifetime of the codes in seconds | 设备码有效期（秒）
+    pub expires_in: i64,
+
+    /// Minimum polling interval in seconds | 最小轮询间隔（秒）
+    pub interval: i64,
+}
+
+/// Device Authorization Status | 设备授权状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DeviceCodeStatus {
+    /// Waiting for the user | 等待用户操作
+    Pending,
+
+    /// Approved by the user | 用户已同意
+    Approved {
+        /// User who approved | 同意授权的用户
+        user_id: String,
+    },
+
+    /// Denied by the user | 用户已拒绝
+    Denied,
+}
+
+/// Device Code | 设备码
+///
+/// Stored state of a device authorization request.
+/// 设备授权请求的存储状态。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCode {
+    /// Device code value | 设备码
+    pub device_code: String,
+
+    /// User code value (normalized, without dash) | 用户码（规范化，不含连字符）
+    pub user_code: String,
+
+    /// Client that requested authorization | 请求授权的客户端
+    pub client_id: String,
+
+    /// Requested scopes | 请求的权限范围
+    pub scope: Vec<String>,
+
+    /// Current status | 当前状态
+    #[serde(flatten)]
+    pub status: DeviceCodeStatus,
+
+    /// Current minimum polling interval in seconds | 当前最小轮询间隔（秒）
+    pub interval: i64,
+
+    /// Last time the device polled | 设备上次轮询时间
+    pub last_polled_at: Option<DateTime<Utc>>,
+
+    /// Expiration timestamp | 过期时间戳
+    pub expires_at: DateTime<Utc>,
 }
 
 /// Access Token Response | 访问令牌响应
@@ -252,6 +659,14 @@ pub struct AccessToken {
     
     /// Granted scopes | 授予的权限范围
     pub scope: Vec<String>,
+
+    /// OpenID Connect ID token, issued for the `openid` scope | OpenID Connect ID Token，授予 `openid` scope 时签发
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+
+    /// Type of the issued token, set by token exchange (RFC 8693 §2.2.1) | 签发的令牌类型，由令牌交换设置（RFC 8693 §2.2.1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_token_type: Option<String>,
 }
 
 /// OAuth2 Token Information (for storage) | OAuth2 令牌信息（用于存储）
@@ -280,6 +695,50 @@ pub struct OAuth2TokenInfo {
     
     /// Optional refresh token | 可选的刷新令牌
     pub refresh_token: Option<String>,
+
+    /// Issued to the client itself by the client_credentials grant, with no user
+    /// 由 client_credentials 授权签发给客户端自身，不关联用户
+    #[serde(default)]
+    pub client_only: bool,
+
+    /// Audiences the token is restricted to, empty when unrestricted (token exchange)
+    /// 令牌限定的受众，为空表示不限（令牌交换）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audience: Vec<String>,
+
+    /// Party acting on behalf of the user (token exchange `act` claim)
+    /// 代表用户行事的一方（令牌交换的 `act` 声明）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<OAuth2Actor>,
+}
+
+/// Token Exchange Actor | 令牌交换的行为方
+///
+/// `act` claim of RFC 8693 §4.1. Each exchange of a delegated token nests the previous
+/// actor, so `act.act` is the party that acted before.
+/// RFC 8693 §4.1 的 `act` 声明。委托令牌每交换一次就嵌套前一个行为方，`act.act` 即更早的行为方。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2Actor {
+    /// Acting user or client ID | 行为方的用户或客户端 ID
+    pub sub: String,
+
+    /// Previous actor in the delegation chain | 委托链中的前一个行为方
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Box<OAuth2Actor>>,
+}
+
+impl OAuth2TokenInfo {
+    /// Login ID of the user behind the token, `None` for client-only tokens
+    /// 令牌对应用户的登录 ID，客户端令牌返回 `None`
+    pub fn login_id(&self) -> Option<&str> {
+        (!self.client_only).then_some(self.user_id.as_str())
+    }
+
+    /// Whether the token may be used at `audience`; tokens without audiences are accepted everywhere
+    /// 令牌能否用于 `audience`；未限定受众的令牌处处可用
+    pub fn allows_audience(&self, audience: &str) -> bool {
+        self.audience.is_empty() || self.audience.iter().any(|a| a == audience)
+    }
 }
 
 /// OAuth2 Manager | OAuth2 管理器
@@ -308,6 +767,40 @@ pub struct OAuth2Manager {
     /// Refresh token TTL in seconds (default: 2592000 = 30 days)
     /// 刷新令牌有效期（秒）（默认：2592000 = 30 天）
     refresh_token_ttl: i64,
+
+    /// Renderer for the consent page | 授权确认页渲染器
+    page_renderer: Arc<dyn PageRenderer>,
+
+    /// Generator for authorization codes | 授权码生成器
+    id_generator: Arc<dyn IdGenerator>,
+
+    /// Rotate refresh tokens on the refresh_token grant (default: false)
+    /// refresh_token 授权时是否轮换刷新令牌（默认：false）
+    rotate_refresh_tokens: bool,
+
+    /// Verification page shown to device users (default: "/oauth2/device")
+    /// 设备用户访问的验证页（默认："/oauth2/device"）
+    device_verification_uri: String,
+
+    /// Device code TTL in seconds (default: 600 = 10 minutes)
+    /// 设备码有效期（秒）（默认：600 = 10 分钟）
+    device_code_ttl: i64,
+
+    /// Minimum device polling interval in seconds (default: 5)
+    /// 设备最小轮询间隔（秒）（默认：5）
+    device_poll_interval: i64,
+
+    /// Scopes each user has granted to each client | 每个用户授予各客户端的权限范围
+    consents: ConsentStore,
+
+    /// OpenID Connect provider, None when OIDC is disabled | OpenID Connect 提供方，未启用 OIDC 时为 None
+    oidc: Option<Arc<OidcProvider>>,
+
+    /// Registry of OAuth2 clients | OAuth2 客户端注册表
+    clients: Arc<dyn ClientStore>,
+
+    /// Hasher for client secrets (default: salted SHA-256) | 客户端密钥哈希器（默认：加盐 SHA-256）
+    secret_hasher: Arc<dyn PasswordHasher>,
 }
 
 impl OAuth2Manager {
@@ -323,10 +816,20 @@ impl OAuth2Manager {
     /// * `storage` - Storage backend for persistence | 用于持久化的存储后端
     pub fn new(storage: Arc<dyn SaStorage>) -> Self {
         Self {
+            consents: ConsentStore::new(storage.clone()),
+            clients: Arc::new(StorageClientStore::new(storage.clone())),
+            secret_hasher: Arc::new(SecretHasher),
             storage,
             code_ttl: 600,        // 10 minutes
             token_ttl: 3600,      // 1 hour
             refresh_token_ttl: 2592000, // 30 days
+            page_renderer: Arc::new(DefaultPageRenderer::new()),
+            id_generator: Arc::new(RandomIdGenerator::default()),
+            rotate_refresh_tokens: false,
+            device_verification_uri: "/oauth2/device".to_string(),
+            device_code_ttl: 600,
+            device_poll_interval: 5,
+            oidc: None,
         }
     }
 
@@ -350,10 +853,312 @@ impl OAuth2Manager {
         self
     }
 
+    /// Rotate refresh tokens on the refresh_token grant | refresh_token 授权时轮换刷新令牌
+    ///
+    /// When enabled, `refresh_token_grant` behaves like `refresh_with_rotation`.
+    /// 启用后 `refresh_token_grant` 的行为与 `refresh_with_rotation` 相同。
+    pub fn with_refresh_token_rotation(mut self, enabled: bool) -> Self {
+        self.rotate_refresh_tokens = enabled;
+        self
+    }
+
+    /// Configure the device authorization flow | 配置设备授权流程
+    ///
+    /// # Arguments | 参数
+    /// * `verification_uri` - Page where users enter the user code | 用户输入用户码的页面
+    /// * `device_code_ttl` - Device code TTL in seconds | 设备码有效期（秒）
+    /// * `poll_interval` - Minimum polling interval in seconds | 最小轮询间隔（秒）
+    pub fn with_device_flow(mut self, verification_uri: impl Into<String>, device_code_ttl: i64, poll_interval: i64) -> Self {
+        self.device_verification_uri = verification_uri.into();
+        self.device_code_ttl = device_code_ttl;
+        self.device_poll_interval = poll_interval;
+        self
+    }
+
+    /// Set a custom page renderer | 设置自定义页面渲染器
+    /// 
+    /// # Arguments | 参数
+    /// * `renderer` - Renderer for the consent and error pages | 授权确认页与错误页的渲染器
+    pub fn with_page_renderer(mut self, renderer: Arc<dyn PageRenderer>) -> Self {
+        self.page_renderer = renderer;
+        self
+    }
+
+    /// Get the page renderer | 获取页面渲染器
+    pub fn page_renderer(&self) -> &Arc<dyn PageRenderer> {
+        &self.page_renderer
+    }
+
+    /// Consent store sharing this manager's storage | 与本管理器共用存储的授权同意存储
+    ///
+    /// Check `requires_consent` before rendering the consent page, and record the
+    /// user's decision with `grant`.
+    /// 渲染授权确认页前调用 `requires_consent` 判断，用户同意后调用 `grant` 记录。
+    pub fn consents(&self) -> &ConsentStore {
+        &self.consents
+    }
+
+    /// Set the client registry | 设置客户端注册表
+    ///
+    /// # Arguments | 参数
+    /// * `store` - Client store, e.g. a database implementation | 客户端存储，例如数据库实现
+    pub fn with_client_store(mut self, store: Arc<dyn ClientStore>) -> Self {
+        self.clients = store;
+        self
+    }
+
+    /// Get the client registry | 获取客户端注册表
+    pub fn client_store(&self) -> &Arc<dyn ClientStore> {
+        &self.clients
+    }
+
+    /// Set the hasher for client secrets | 设置客户端密钥哈希器
+    ///
+    /// # Arguments | 参数
+    /// * `hasher` - Secret hasher, e.g. `Argon2Hasher` | 密钥哈希器，例如 `Argon2Hasher`
+    pub fn with_secret_hasher(mut self, hasher: Arc<dyn PasswordHasher>) -> Self {
+        self.secret_hasher = hasher;
+        self
+    }
+
+    /// Enable OpenID Connect | 启用 OpenID Connect
+    ///
+    /// Authorization codes granted the `openid` scope then also yield an `id_token`.
+    /// 之后授予 `openid` scope 的授权码在换取令牌时会额外签发 `id_token`。
+    ///
+    /// # Arguments | 参数
+    /// * `provider` - Issuer and signing key of the ID tokens | ID Token 的签发者与签名密钥
+    pub fn with_oidc(mut self, provider: OidcProvider) -> Self {
+        self.oidc = Some(Arc::new(provider));
+        self
+    }
+
+    /// Get the OpenID Connect provider | 获取 OpenID Connect 提供方
+    pub fn oidc(&self) -> Option<&OidcProvider> {
+        self.oidc.as_deref()
+    }
+
+    /// Set the generator for authorization codes | 设置授权码生成器
+    /// 
+    /// # Arguments | 参数
+    /// * `generator` - Unique ID generator, e.g. `manager.id_generator().clone()` | 唯一 ID 生成器，例如 `manager.id_generator().clone()`
+    pub fn with_id_generator(mut self, generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = generator;
+        self
+    }
+
+    /// Render the consent page for an authorization request | 为授权请求渲染授权确认页
+    /// 
+    /// The redirect URI and scopes are validated against the client before rendering.
+    /// 渲染前会根据客户端校验回调 URI 与权限范围。
+    /// 
+    /// # Arguments | 参数
+    /// * `client` - Requesting client | 发起请求的客户端
+    /// * `action` - URL the consent form posts to | 确认表单提交地址
+    /// * `redirect_uri` - Requested redirect URI | 请求的回调 URI
+    /// * `scope` - Requested scopes | 请求的权限范围
+    /// * `state` - Client state | 客户端 state
+    /// * `accept_language` - `Accept-Language` header for locale negotiation | 用于语言协商的 `Accept-Language` 请求头
+    pub fn render_consent_page(
+        &self,
+        client: &OAuth2Client,
+        action: &str,
+        redirect_uri: &str,
+        scope: &[String],
+        state: Option<&str>,
+        accept_language: Option<&str>,
+    ) -> SaTokenResult<String> {
+        if !self.validate_redirect_uri(client, redirect_uri) {
+            return Err(SaTokenError::OAuth2RedirectUriMismatch);
+        }
+        if !self.validate_scope(client, scope) {
+            return Err(SaTokenError::OAuth2InvalidScope);
+        }
+
+        let page = SaPage::Consent(ConsentPage {
+            action: action.to_string(),
+            client_id: client.client_id.clone(),
+            scopes: scope.to_vec(),
+            redirect_uri: redirect_uri.to_string(),
+            state: state.map(str::to_string),
+            ticket: None,
+        });
+        self.page_renderer.render_negotiated(&page, accept_language)
+    }
+
+    /// Handle an authorization endpoint request | 处理授权端点请求
+    ///
+    /// Validates the request for the logged-in user. Scopes the user granted before skip the
+    /// consent page and redirect with a `code` right away; otherwise the request is kept under
+    /// a one-time ticket (TTL: code TTL) and the consent page is returned, whose form posts the
+    /// ticket to `authorize_decision`. Once the redirect URI is known to be valid, request
+    /// errors are reported to the client as `error` redirects (RFC 6749 §4.1.2.1).
+    /// 为已登录用户校验授权请求。用户之前已授予的权限范围跳过确认页，直接携带 `code` 重定向；
+    /// 否则将请求保存在一次性票据下（有效期同授权码），返回确认页，确认表单把票据提交给
+    /// `authorize_decision`。回调 URI 校验通过后，请求错误以 `error` 重定向告知客户端
+    /// （RFC 6749 §4.1.2.1）。
+    ///
+    /// # Arguments | 参数
+    /// * `user_id` - Logged-in user | 已登录用户
+    /// * `request` - Authorization query parameters | 授权查询参数
+    /// * `action` - URL the consent form posts to | 确认表单提交地址
+    /// * `accept_language` - `Accept-Language` header for locale negotiation | 用于语言协商的 `Accept-Language` 请求头
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2ClientNotFound)` if the client is unknown | 客户端不存在时
+    /// * `Err(OAuth2RedirectUriMismatch)` if the redirect URI is not registered | 回调 URI 未注册时
+    pub async fn authorize_request(
+        &self,
+        user_id: &str,
+        request: &OAuth2AuthorizeRequest,
+        action: &str,
+        accept_language: Option<&str>,
+    ) -> SaTokenResult<OAuth2AuthorizeResponse> {
+        let client = self.get_client(&request.client_id).await?;
+        if !self.validate_redirect_uri(&client, &request.redirect_uri) {
+            return Err(SaTokenError::OAuth2RedirectUriMismatch);
+        }
+
+        let scope: Vec<String> = request.scope.as_deref().unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        if let Err(e) = self.check_authorize_request(&client, request, &scope) {
+            return Ok(OAuth2AuthorizeResponse::Redirect(authorize_error_redirect(request, &e)));
+        }
+
+        if !self.consents.requires_consent(user_id, &client.client_id, &scope).await? {
+            return self.issue_authorization_redirect(user_id, request, scope).await;
+        }
+
+        let ticket = Uuid::new_v4().simple().to_string();
+        let pending = PendingAuthorization {
+            user_id: user_id.to_string(),
+            scope: scope.clone(),
+            request: request.clone(),
+        };
+        let value = serde_json::to_string(&pending).map_err(SaTokenError::SerializationError)?;
+        let ttl = Some(std::time::Duration::from_secs(self.code_ttl as u64));
+        self.storage.set(&format!("oauth2:authorize:{}", ticket), &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        let page = SaPage::Consent(ConsentPage {
+            action: action.to_string(),
+            client_id: client.client_id,
+            scopes: scope,
+            redirect_uri: request.redirect_uri.clone(),
+            state: request.state.clone(),
+            ticket: Some(ticket),
+        });
+        Ok(OAuth2AuthorizeResponse::Consent(self.page_renderer.render_negotiated(&page, accept_language)?))
+    }
+
+    /// Handle the consent page's decision | 处理授权确认页的结果
+    ///
+    /// Consumes the ticket issued by `authorize_request`. On `approve` the scopes are recorded
+    /// in the consent store and the user is redirected with a `code`; any other decision
+    /// redirects with `error=access_denied`.
+    /// 消费 `authorize_request` 签发的票据。`approve` 时把权限范围记录到授权同意存储，
+    /// 并携带 `code` 重定向；其他结果以 `error=access_denied` 重定向。
+    ///
+    /// # Arguments | 参数
+    /// * `user_id` - Logged-in user | 已登录用户
+    /// * `decision` - Consent form parameters | 确认表单参数
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2ConsentTicketInvalid)` if the ticket is unknown, expired or belongs to another user | 票据不存在、已过期或属于其他用户时
+    pub async fn authorize_decision(
+        &self,
+        user_id: &str,
+        decision: &OAuth2ConsentDecision,
+    ) -> SaTokenResult<OAuth2AuthorizeResponse> {
+        let key = format!("oauth2:authorize:{}", decision.ticket);
+        let value = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::OAuth2ConsentTicketInvalid)?;
+        let pending: PendingAuthorization = serde_json::from_str(&value)
+            .map_err(SaTokenError::SerializationError)?;
+        if pending.user_id != user_id {
+            return Err(SaTokenError::OAuth2ConsentTicketInvalid);
+        }
+        self.storage.delete(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        if decision.decision != "approve" {
+            let redirect = authorize_error_redirect(&pending.request, &SaTokenError::OAuth2AccessDenied);
+            return Ok(OAuth2AuthorizeResponse::Redirect(redirect));
+        }
+        self.consents.grant(user_id, &pending.request.client_id, &pending.scope).await?;
+        self.issue_authorization_redirect(user_id, &pending.request, pending.scope).await
+    }
+
+    /// Check an authorization request whose redirect URI is valid | 校验回调 URI 有效的授权请求
+    fn check_authorize_request(
+        &self,
+        client: &OAuth2Client,
+        request: &OAuth2AuthorizeRequest,
+        scope: &[String],
+    ) -> SaTokenResult<()> {
+        if request.response_type != "code" {
+            return Err(SaTokenError::OAuth2UnsupportedResponseType(request.response_type.clone()));
+        }
+        if !client.grant_types.iter().any(|g| g == "authorization_code") {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        if !self.validate_scope(client, scope) {
+            return Err(SaTokenError::OAuth2InvalidScope);
+        }
+        match &request.code_challenge {
+            Some(challenge) => {
+                PkceMethod::parse(request.code_challenge_method.as_deref())?;
+                if !PkceMethod::is_valid_value(challenge) {
+                    return Err(SaTokenError::OAuth2InvalidCodeChallenge);
+                }
+            }
+            // Public clients cannot authenticate the exchange | 公共客户端无法在换取令牌时认证
+            None if client.client_secret.is_empty() => return Err(SaTokenError::OAuth2PkceRequired),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Issue and store a code, returning the redirect to the client | 签发并存储授权码，返回回调地址
+    async fn issue_authorization_redirect(
+        &self,
+        user_id: &str,
+        request: &OAuth2AuthorizeRequest,
+        scope: Vec<String>,
+    ) -> SaTokenResult<OAuth2AuthorizeResponse> {
+        let client_id = request.client_id.clone();
+        let redirect_uri = request.redirect_uri.clone();
+        let mut code = match &request.code_challenge {
+            Some(challenge) => self.generate_authorization_code_with_pkce(
+                client_id, user_id.to_string(), redirect_uri, scope,
+                challenge.clone(), request.code_challenge_method.as_deref(),
+            )?,
+            None => self.generate_authorization_code(client_id, user_id.to_string(), redirect_uri, scope),
+        };
+        if let Some(nonce) = &request.nonce {
+            code = code.with_nonce(nonce.clone());
+        }
+        self.store_authorization_code(&code).await?;
+
+        let redirect = redirect_with(&request.redirect_uri, &[
+            ("code", Some(code.code.as_str())),
+            ("state", request.state.as_deref()),
+        ]);
+        Ok(OAuth2AuthorizeResponse::Redirect(redirect))
+    }
+
     /// Register a new OAuth2 client | 注册新的 OAuth2 客户端
     /// 
-    /// Stores client information in the backend for future authentication.
-    /// 将客户端信息存储在后端，用于未来的认证。
+    /// Stores client information in the client store for future authentication. The secret
+    /// is hashed before it is saved; a secret that already starts with `$` is taken as a hash,
+    /// so clients read back with `get_client` can be registered again unchanged.
+    /// Registering an existing `client_id` replaces it.
+    /// 将客户端信息存储在客户端注册表中，用于未来的认证。密钥在保存前哈希；以 `$` 开头的密钥
+    /// 视为已哈希，因此通过 `get_client` 读回的客户端可以原样重新注册。重复注册同一
+    /// `client_id` 会覆盖原有客户端。
     /// 
     /// # Arguments | 参数
     /// * `client` - Client information to register | 要注册的客户端信息
@@ -374,14 +1179,11 @@ impl OAuth2Manager {
     /// oauth2.register_client(&client).await?;
     /// ```
     pub async fn register_client(&self, client: &OAuth2Client) -> SaTokenResult<()> {
-        let key = format!("oauth2:client:{}", client.client_id);
-        let value = serde_json::to_string(client)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
-        
-        self.storage.set(&key, &value, None).await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
-        
-        Ok(())
+        let mut client = client.clone();
+        if !client.client_secret.is_empty() && !client.client_secret.starts_with('$') {
+            client.client_secret = self.secret_hasher.hash(&client.client_secret)?;
+        }
+        self.clients.save_client(&client).await
     }
 
     /// Retrieve client information by client ID | 通过客户端 ID 检索客户端信息
@@ -393,13 +1195,27 @@ impl OAuth2Manager {
     /// * `Ok(OAuth2Client)` if found | 找到时返回客户端信息
     /// * `Err(OAuth2ClientNotFound)` if client doesn't exist | 客户端不存在时返回错误
     pub async fn get_client(&self, client_id: &str) -> SaTokenResult<OAuth2Client> {
-        let key = format!("oauth2:client:{}", client_id);
-        let value = self.storage.get(&key).await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::OAuth2ClientNotFound)?;
-        
-        serde_json::from_str(&value)
-            .map_err(|e| SaTokenError::SerializationError(e))
+        self.clients.get_client(client_id).await?
+            .ok_or(SaTokenError::OAuth2ClientNotFound)
+    }
+
+    /// List all registered clients | 列出所有已注册的客户端
+    ///
+    /// Secrets are returned hashed. | 返回的密钥为哈希值。
+    pub async fn list_clients(&self) -> SaTokenResult<Vec<OAuth2Client>> {
+        self.clients.list().await
+    }
+
+    /// Delete a client | 删除客户端
+    ///
+    /// The client can no longer authenticate; tokens already issued to it stay valid until
+    /// they expire or are revoked.
+    /// 客户端之后无法再认证；已签发给它的令牌在过期或被撤销前仍然有效。
+    ///
+    /// # Arguments | 参数
+    /// * `client_id` - Client identifier | 客户端标识符
+    pub async fn delete_client(&self, client_id: &str) -> SaTokenResult<()> {
+        self.clients.delete(client_id).await
     }
 
     /// Verify client credentials | 验证客户端凭据
@@ -417,7 +1233,32 @@ impl OAuth2Manager {
     /// * `Err(OAuth2ClientNotFound)` if client doesn't exist | 客户端不存在时返回错误
     pub async fn verify_client(&self, client_id: &str, client_secret: &str) -> SaTokenResult<bool> {
         let client = self.get_client(client_id).await?;
-        Ok(client.client_secret == client_secret)
+        Ok(self.verify_secret(&client, client_secret))
+    }
+
+    /// Check a presented secret against the stored hash; secrets stored in plain text
+    /// before hashing was introduced are compared directly
+    /// 用存储的哈希校验密钥；引入哈希之前以明文保存的密钥直接比较
+    fn verify_secret(&self, client: &OAuth2Client, client_secret: &str) -> bool {
+        let stored = client.client_secret.as_str();
+        if stored.starts_with('$') {
+            self.secret_hasher.verify(client_secret, stored).unwrap_or(false)
+        } else {
+            SaSecureUtil::constant_time_eq(stored.as_bytes(), client_secret.as_bytes())
+        }
+    }
+
+    /// Authenticate a client; public clients (empty secret) need no secret
+    /// 认证客户端；公共客户端（密钥为空）无需密钥
+    async fn authenticate_client(&self, client_id: &str, client_secret: Option<&str>) -> SaTokenResult<OAuth2Client> {
+        let client = self.get_client(client_id).await?;
+        if !client.client_secret.is_empty() {
+            let secret = client_secret.ok_or(SaTokenError::OAuth2InvalidCredentials)?;
+            if !self.verify_secret(&client, secret) {
+                return Err(SaTokenError::OAuth2InvalidCredentials);
+            }
+        }
+        Ok(client)
     }
 
     /// Generate a new authorization code | 生成新的授权码
@@ -445,7 +1286,7 @@ impl OAuth2Manager {
         scope: Vec<String>,
     ) -> AuthorizationCode {
         let now = Utc::now();
-        let code = format!("code_{}", Uuid::new_v4().simple());
+        let code = format!("code_{}", self.id_generator.generate(IdKind::OAuth2Code));
         
         AuthorizationCode {
             code,
@@ -455,7 +1296,46 @@ impl OAuth2Manager {
             scope,
             created_at: now,
             expires_at: now + Duration::seconds(self.code_ttl),
+            code_challenge: None,
+            code_challenge_method: None,
+            nonce: None,
+        }
+    }
+
+    /// Generate an authorization code bound to a PKCE challenge | 生成绑定 PKCE 挑战的授权码
+    ///
+    /// Records the `code_challenge` sent with the authorization request so the
+    /// matching `code_verifier` must be presented at token exchange.
+    /// 记录授权请求中的 `code_challenge`，换取令牌时必须提供匹配的 `code_verifier`。
+    ///
+    /// # Arguments | 参数
+    /// * `client_id` - Client requesting authorization | 请求授权的客户端
+    /// * `user_id` - User granting authorization | 授予授权的用户
+    /// * `redirect_uri` - Callback URI for this authorization | 此授权的回调 URI
+    /// * `scope` - Granted permissions | 授予的权限
+    /// * `code_challenge` - PKCE code challenge | PKCE code_challenge
+    /// * `code_challenge_method` - `"S256"` or `"plain"` (default) | `"S256"` 或 `"plain"`（默认）
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2InvalidCodeChallenge)` if the method or challenge is malformed | 方法或挑战格式非法时
+    pub fn generate_authorization_code_with_pkce(
+        &self,
+        client_id: String,
+        user_id: String,
+        redirect_uri: String,
+        scope: Vec<String>,
+        code_challenge: String,
+        code_challenge_method: Option<&str>,
+    ) -> SaTokenResult<AuthorizationCode> {
+        let method = PkceMethod::parse(code_challenge_method)?;
+        if !PkceMethod::is_valid_value(&code_challenge) {
+            return Err(SaTokenError::OAuth2InvalidCodeChallenge);
         }
+
+        let mut auth_code = self.generate_authorization_code(client_id, user_id, redirect_uri, scope);
+        auth_code.code_challenge = Some(code_challenge);
+        auth_code.code_challenge_method = Some(method);
+        Ok(auth_code)
     }
 
     /// Store authorization code in backend | 在后端存储授权码
@@ -471,7 +1351,7 @@ impl OAuth2Manager {
     pub async fn store_authorization_code(&self, auth_code: &AuthorizationCode) -> SaTokenResult<()> {
         let key = format!("oauth2:code:{}", auth_code.code);
         let value = serde_json::to_string(auth_code)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         let ttl = Some(std::time::Duration::from_secs(self.code_ttl as u64));
         self.storage.set(&key, &value, ttl).await
@@ -500,10 +1380,10 @@ impl OAuth2Manager {
         let key = format!("oauth2:code:{}", code);
         let value = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::OAuth2CodeNotFound)?;
+            .ok_or(SaTokenError::OAuth2CodeNotFound)?;
         
         let auth_code: AuthorizationCode = serde_json::from_str(&value)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         // Check expiration and auto-cleanup if expired
         if Utc::now() > auth_code.expires_at {
@@ -576,10 +1456,50 @@ impl OAuth2Manager {
         client_secret: &str,
         redirect_uri: &str,
     ) -> SaTokenResult<AccessToken> {
-        // 1. Verify client credentials
-        if !self.verify_client(client_id, client_secret).await? {
-            return Err(SaTokenError::OAuth2InvalidCredentials);
-        }
+        let client = self.authenticate_client(client_id, Some(client_secret)).await?;
+        self.exchange_code(code, &client, redirect_uri, None).await
+    }
+
+    /// Exchange a PKCE-bound authorization code for access token | 使用 PKCE 授权码换取访问令牌
+    ///
+    /// Verifies `code_verifier` against the challenge stored with the code. Public clients
+    /// (registered with an empty `client_secret`) pass `None` as the secret and must use PKCE.
+    /// 使用授权码中保存的挑战校验 `code_verifier`。公共客户端（注册时 `client_secret` 为空）
+    /// 传入 `None` 作为密钥，且必须使用 PKCE。
+    ///
+    /// # Arguments | 参数
+    /// * `code` - Authorization code | 授权码
+    /// * `client_id` - Client identifier | 客户端标识符
+    /// * `client_secret` - Client secret, `None` for public clients | 客户端密钥，公共客户端为 `None`
+    /// * `redirect_uri` - Redirect URI used in authorization | 授权时使用的回调 URI
+    /// * `code_verifier` - PKCE code verifier | PKCE code_verifier
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2InvalidCredentials)` if a confidential client omits or mismatches its secret | 机密客户端密钥缺失或错误时
+    /// * `Err(OAuth2PkceRequired)` if the code was issued without a challenge to a public client | 公共客户端的授权码未绑定挑战时
+    /// * `Err(OAuth2InvalidCodeVerifier)` if the verifier does not match | code_verifier 不匹配时
+    pub async fn exchange_code_with_pkce(
+        &self,
+        code: &str,
+        client_id: &str,
+        client_secret: Option<&str>,
+        redirect_uri: &str,
+        code_verifier: &str,
+    ) -> SaTokenResult<AccessToken> {
+        let client = self.authenticate_client(client_id, client_secret).await?;
+        self.exchange_code(code, &client, redirect_uri, Some(code_verifier)).await
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        client: &OAuth2Client,
+        redirect_uri: &str,
+        code_verifier: Option<&str>,
+    ) -> SaTokenResult<AccessToken> {
+        // 1. The client has been authenticated by the caller
+        let client_id = client.client_id.as_str();
+        let is_public = client.client_secret.is_empty();
 
         // 2. Consume the authorization code (one-time use)
         let auth_code = self.consume_authorization_code(code).await?;
@@ -594,8 +1514,538 @@ impl OAuth2Manager {
             return Err(SaTokenError::OAuth2RedirectUriMismatch);
         }
 
-        // 5. Generate and return access token
-        self.generate_access_token(&auth_code.client_id, &auth_code.user_id, auth_code.scope).await
+        // 5. Verify PKCE; a verifier without a stored challenge is rejected as a downgrade
+        match (&auth_code.code_challenge, code_verifier) {
+            (Some(challenge), Some(verifier)) => {
+                let method = auth_code.code_challenge_method.unwrap_or(PkceMethod::Plain);
+                if !PkceMethod::is_valid_value(verifier)
+                    || !SaSecureUtil::constant_time_eq(method.challenge(verifier).as_bytes(), challenge.as_bytes())
+                {
+                    return Err(SaTokenError::OAuth2InvalidCodeVerifier);
+                }
+            }
+            (Some(_), None) => return Err(SaTokenError::OAuth2PkceRequired),
+            (None, Some(_)) => return Err(SaTokenError::OAuth2InvalidCodeVerifier),
+            (None, None) if is_public => return Err(SaTokenError::OAuth2PkceRequired),
+            (None, None) => {}
+        }
+
+        // 6. Generate access token
+        let openid = auth_code.scope.iter().any(|s| s == OPENID_SCOPE);
+        let mut token = self.generate_access_token(&auth_code.client_id, &auth_code.user_id, auth_code.scope).await?;
+
+        // 7. Add the ID token for OpenID Connect requests
+        if let Some(oidc) = &self.oidc
+            && openid
+        {
+            token.id_token = Some(oidc.issue_id_token(
+                &auth_code.client_id,
+                &auth_code.user_id,
+                auth_code.nonce.as_deref(),
+                Some(auth_code.created_at),
+                Some(&token.access_token),
+            )?);
+        }
+        Ok(token)
+    }
+
+    /// Handle a token endpoint request | 处理令牌端点请求
+    ///
+    /// Dispatches on `grant_type` (`authorization_code`, `refresh_token`, `client_credentials`,
+    /// `DEVICE_CODE_GRANT_TYPE` or `TOKEN_EXCHANGE_GRANT_TYPE`) after checking that the client is
+    /// allowed to use the grant. Convert errors with `OAuth2ErrorResponse::from`.
+    /// 根据 `grant_type`（`authorization_code`、`refresh_token`、`client_credentials`、
+    /// `DEVICE_CODE_GRANT_TYPE` 或 `TOKEN_EXCHANGE_GRANT_TYPE`）分派，并检查客户端是否允许使用
+    /// 该授权类型。错误可通过 `OAuth2ErrorResponse::from` 转换为响应。
+    ///
+    /// # Arguments | 参数
+    /// * `request` - Token endpoint form parameters | 令牌端点表单参数
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2UnsupportedGrantType)` for other grant types | 其他授权类型
+    /// * `Err(OAuth2UnauthorizedClient)` if the client is not registered for the grant | 客户端未注册该授权类型时
+    /// * `Err(OAuth2MissingParameter)` if a required parameter is missing | 缺少必需参数时
+    pub async fn token_request(&self, request: &OAuth2TokenRequest) -> SaTokenResult<AccessToken> {
+        let grant_type = request.grant_type.as_str();
+        if !matches!(
+            grant_type,
+            "authorization_code" | "refresh_token" | "client_credentials" | DEVICE_CODE_GRANT_TYPE | TOKEN_EXCHANGE_GRANT_TYPE
+        ) {
+            return Err(SaTokenError::OAuth2UnsupportedGrantType(grant_type.to_string()));
+        }
+
+        let client = self.authenticate_client(&request.client_id, request.client_secret.as_deref()).await?;
+        if !client.grant_types.iter().any(|g| g == grant_type) {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+
+        let scope = request.scope.as_deref()
+            .map(|s| s.split_whitespace().map(str::to_string).collect::<Vec<_>>());
+        if grant_type == "client_credentials" {
+            self.issue_client_token(&client, scope.as_deref()).await
+        } else if grant_type == TOKEN_EXCHANGE_GRANT_TYPE {
+            let subject_token = request.subject_token.as_deref()
+                .ok_or_else(|| SaTokenError::OAuth2MissingParameter("subject_token".to_string()))?;
+            if request.subject_token_type.as_deref() != Some(ACCESS_TOKEN_TYPE) {
+                return Err(SaTokenError::OAuth2InvalidExchangeToken("subject_token_type".to_string()));
+            }
+            if request.actor_token.is_some() && request.actor_token_type.as_deref() != Some(ACCESS_TOKEN_TYPE) {
+                return Err(SaTokenError::OAuth2InvalidExchangeToken("actor_token_type".to_string()));
+            }
+            let audience: Vec<String> = request.audience.as_deref().unwrap_or_default()
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            self.exchange(&client, subject_token, request.actor_token.as_deref(), scope.as_deref(), &audience).await
+        } else if grant_type == DEVICE_CODE_GRANT_TYPE {
+            let device_code = request.device_code.as_deref()
+                .ok_or_else(|| SaTokenError::OAuth2MissingParameter("device_code".to_string()))?;
+            self.poll_device(device_code, &client).await
+        } else if grant_type == "authorization_code" {
+            let code = request.code.as_deref().ok_or_else(|| SaTokenError::OAuth2MissingParameter("code".to_string()))?;
+            let redirect_uri = request.redirect_uri.as_deref()
+                .ok_or_else(|| SaTokenError::OAuth2MissingParameter("redirect_uri".to_string()))?;
+            self.exchange_code(code, &client, redirect_uri, request.code_verifier.as_deref()).await
+        } else {
+            let refresh_token = request.refresh_token.as_deref()
+                .ok_or_else(|| SaTokenError::OAuth2MissingParameter("refresh_token".to_string()))?;
+            self.refresh(refresh_token, &client.client_id, scope.as_deref(), self.rotate_refresh_tokens).await
+        }
+    }
+
+    /// Handle a token endpoint request with HTTP Basic client authentication
+    /// 处理使用 HTTP Basic 客户端认证的令牌端点请求
+    ///
+    /// Credentials in the `Authorization: Basic` header (`client_secret_basic`) take precedence
+    /// over `client_id` / `client_secret` in the form, then the request is handled by `token_request`.
+    /// `Authorization: Basic` 请求头中的凭据（`client_secret_basic`）优先于表单中的
+    /// `client_id` / `client_secret`，之后交由 `token_request` 处理。
+    ///
+    /// # Arguments | 参数
+    /// * `authorization` - Value of the `Authorization` header | `Authorization` 请求头的值
+    /// * `request` - Token endpoint form parameters | 令牌端点表单参数
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2InvalidCredentials)` if the form names a different client than the header | 表单与请求头中的客户端不一致时
+    pub async fn token_endpoint_request(
+        &self,
+        authorization: Option<&str>,
+        request: &OAuth2TokenRequest,
+    ) -> SaTokenResult<AccessToken> {
+        let Some((client_id, client_secret)) = authorization.and_then(parse_basic) else {
+            return self.token_request(request).await;
+        };
+        if !request.client_id.is_empty() && request.client_id != client_id {
+            return Err(SaTokenError::OAuth2InvalidCredentials);
+        }
+        let mut request = request.clone();
+        request.client_id = client_id;
+        request.client_secret = Some(client_secret);
+        self.token_request(&request).await
+    }
+
+    /// Client credentials grant | 客户端凭据授权
+    ///
+    /// Machine-to-machine access: the confidential client authenticates with its own secret
+    /// and receives an access token with no user (`OAuth2TokenInfo::client_only`) and no
+    /// refresh token (RFC 6749 §4.4).
+    /// 机器对机器访问：机密客户端使用自身密钥认证，获得不关联用户（`OAuth2TokenInfo::client_only`）
+    /// 且不带刷新令牌的访问令牌（RFC 6749 §4.4）。
+    ///
+    /// # Arguments | 参数
+    /// * `client_id` - Client identifier | 客户端标识符
+    /// * `client_secret` - Client secret | 客户端密钥
+    /// * `scope` - Requested scope, `None` for all registered scopes | 请求的范围，`None` 表示全部注册范围
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2InvalidCredentials)` if credentials invalid | 凭据无效时
+    /// * `Err(OAuth2UnauthorizedClient)` for public clients or clients not registered for the grant | 公共客户端或未注册该授权类型时
+    /// * `Err(OAuth2ScopeExceedsGrant)` if `scope` exceeds the registered scopes | `scope` 超出注册范围时
+    pub async fn client_credentials_grant(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        scope: Option<&[String]>,
+    ) -> SaTokenResult<AccessToken> {
+        let client = self.authenticate_client(client_id, Some(client_secret)).await?;
+        if !client.grant_types.iter().any(|g| g == "client_credentials") {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        self.issue_client_token(&client, scope).await
+    }
+
+    async fn issue_client_token(&self, client: &OAuth2Client, scope: Option<&[String]>) -> SaTokenResult<AccessToken> {
+        // Public clients cannot authenticate, so they cannot act on their own behalf
+        if client.client_secret.is_empty() {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        let scope = match scope {
+            Some(requested) if !requested.is_empty() => {
+                if !self.validate_scope(client, requested) {
+                    return Err(SaTokenError::OAuth2ScopeExceedsGrant);
+                }
+                requested.to_vec()
+            }
+            _ => client.scope.clone(),
+        };
+
+        let now = Utc::now();
+        let access_token = format!("at_{}", Uuid::new_v4().simple());
+        let token_info = OAuth2TokenInfo {
+            access_token: access_token.clone(),
+            client_id: client.client_id.clone(),
+            user_id: String::new(),
+            scope: scope.clone(),
+            created_at: now,
+            expires_at: now + Duration::seconds(self.token_ttl),
+            refresh_token: None,
+            client_only: true,
+            audience: Vec::new(),
+            act: None,
+        };
+
+        let key = format!("oauth2:token:{}", access_token);
+        let value = serde_json::to_string(&token_info)
+            .map_err(SaTokenError::SerializationError)?;
+        let ttl = Some(std::time::Duration::from_secs(self.token_ttl as u64));
+        self.storage.set(&key, &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        Ok(AccessToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.token_ttl,
+            refresh_token: None,
+            scope,
+            id_token: None,
+            issued_token_type: None,
+        })
+    }
+
+    /// Token exchange (RFC 8693) | 令牌交换（RFC 8693）
+    ///
+    /// Swaps an active access token (`subject_token`) for a narrower token issued to the calling
+    /// client, e.g. a gateway forwarding a user's request to a downstream service. The new token
+    /// keeps the subject's user, may only narrow its scopes and audiences, never outlives it and
+    /// has no refresh token. Delegation is recorded in `act`: the actor token's subject when one
+    /// is sent, otherwise the calling client, with any earlier actor nested inside.
+    /// 把有效的访问令牌（`subject_token`）换成签发给调用方客户端的更窄令牌，例如网关把用户请求
+    /// 转发给下游服务。新令牌保留原令牌的用户，只能缩小权限范围与受众，有效期不超过原令牌，
+    /// 且不带刷新令牌。委托关系记录在 `act` 中：发送了 actor_token 时为其主体，否则为调用方
+    /// 客户端，更早的行为方嵌套在内。
+    ///
+    /// # Arguments | 参数
+    /// * `client_id` - Calling confidential client | 调用方机密客户端
+    /// * `client_secret` - Client secret | 客户端密钥
+    /// * `subject_token` - Access token to exchange | 要交换的访问令牌
+    /// * `actor_token` - Access token of the acting party | 行为方的访问令牌
+    /// * `scope` - Requested scopes, `None` for the subject's scopes the client is registered for | 请求的权限范围，`None` 表示原令牌中客户端已注册的权限范围
+    /// * `audience` - Audiences of the new token, empty to keep the subject's | 新令牌的受众，为空时沿用原令牌
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2UnauthorizedClient)` for public clients or clients not registered for the grant | 公共客户端或未注册该授权类型时
+    /// * `Err(OAuth2InvalidExchangeToken)` if the subject or actor token is not active | 原令牌或行为方令牌无效时
+    /// * `Err(OAuth2ScopeExceedsGrant)` if `scope` exceeds the subject's or the client's scopes | `scope` 超出原令牌或客户端的范围时
+    /// * `Err(OAuth2InvalidTarget)` if `audience` exceeds the subject's audiences | `audience` 超出原令牌的受众时
+    pub async fn exchange_token(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        subject_token: &str,
+        actor_token: Option<&str>,
+        scope: Option<&[String]>,
+        audience: &[String],
+    ) -> SaTokenResult<AccessToken> {
+        let client = self.authenticate_client(client_id, Some(client_secret)).await?;
+        if !client.grant_types.iter().any(|g| g == TOKEN_EXCHANGE_GRANT_TYPE) {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        self.exchange(&client, subject_token, actor_token, scope, audience).await
+    }
+
+    async fn exchange(
+        &self,
+        client: &OAuth2Client,
+        subject_token: &str,
+        actor_token: Option<&str>,
+        scope: Option<&[String]>,
+        audience: &[String],
+    ) -> SaTokenResult<AccessToken> {
+        // Only confidential clients may act for a user | 只有机密客户端可以代表用户行事
+        if client.client_secret.is_empty() {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        let invalid = |name: &'static str| move |e| match e {
+            SaTokenError::OAuth2AccessTokenNotFound | SaTokenError::TokenExpired => {
+                SaTokenError::OAuth2InvalidExchangeToken(name.to_string())
+            }
+            e => e,
+        };
+        let subject = self.verify_access_token(subject_token).await.map_err(invalid("subject_token"))?;
+        let actor = match actor_token {
+            Some(token) => {
+                let info = self.verify_access_token(token).await.map_err(invalid("actor_token"))?;
+                info.login_id().unwrap_or(&info.client_id).to_string()
+            }
+            None => client.client_id.clone(),
+        };
+
+        let scope = match scope {
+            Some(requested) if !requested.is_empty() => {
+                if !requested.iter().all(|s| subject.scope.contains(s)) || !self.validate_scope(client, requested) {
+                    return Err(SaTokenError::OAuth2ScopeExceedsGrant);
+                }
+                requested.to_vec()
+            }
+            _ => subject.scope.iter().filter(|s| client.scope.contains(s)).cloned().collect(),
+        };
+        let audience = if audience.is_empty() {
+            subject.audience.clone()
+        } else {
+            if let Some(denied) = audience.iter().find(|a| !subject.allows_audience(a)) {
+                return Err(SaTokenError::OAuth2InvalidTarget(denied.clone()));
+            }
+            audience.to_vec()
+        };
+
+        let now = Utc::now();
+        let expires_at = subject.expires_at.min(now + Duration::seconds(self.token_ttl));
+        let expires_in = (expires_at - now).num_seconds().max(1);
+        let access_token = format!("at_{}", Uuid::new_v4().simple());
+        let token_info = OAuth2TokenInfo {
+            access_token: access_token.clone(),
+            client_id: client.client_id.clone(),
+            user_id: subject.user_id.clone(),
+            scope: scope.clone(),
+            created_at: now,
+            expires_at,
+            refresh_token: None,
+            client_only: subject.client_only,
+            audience,
+            act: Some(OAuth2Actor { sub: actor, act: subject.act.map(Box::new) }),
+        };
+
+        let key = format!("oauth2:token:{}", access_token);
+        let value = serde_json::to_string(&token_info)
+            .map_err(SaTokenError::SerializationError)?;
+        let ttl = Some(std::time::Duration::from_secs(expires_in as u64));
+        self.storage.set(&key, &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        Ok(AccessToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in,
+            refresh_token: None,
+            scope,
+            id_token: None,
+            issued_token_type: Some(ACCESS_TOKEN_TYPE.to_string()),
+        })
+    }
+
+    /// Start a device authorization request | 发起设备授权请求
+    ///
+    /// Issues a `device_code` for the device to poll with and a short `user_code` the user
+    /// enters at `verification_uri` (RFC 8628 §3.1).
+    /// 签发供设备轮询的 `device_code`，以及用户在 `verification_uri` 输入的短 `user_code`
+    /// （RFC 8628 §3.1）。
+    ///
+    /// # Arguments | 参数
+    /// * `client_id` - Client identifier | 客户端标识符
+    /// * `client_secret` - Client secret, `None` for public clients | 客户端密钥，公共客户端为 `None`
+    /// * `scope` - Requested scopes | 请求的权限范围
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2UnauthorizedClient)` if the client is not registered for `DEVICE_CODE_GRANT_TYPE` | 客户端未注册设备授权时
+    /// * `Err(OAuth2ScopeExceedsGrant)` if `scope` exceeds the registered scopes | `scope` 超出注册范围时
+    pub async fn request_device_authorization(
+        &self,
+        client_id: &str,
+        client_secret: Option<&str>,
+        scope: Vec<String>,
+    ) -> SaTokenResult<DeviceAuthorization> {
+        let client = self.authenticate_client(client_id, client_secret).await?;
+        if !client.grant_types.iter().any(|g| g == DEVICE_CODE_GRANT_TYPE) {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        if !self.validate_scope(&client, &scope) {
+            return Err(SaTokenError::OAuth2ScopeExceedsGrant);
+        }
+
+        let device = DeviceCode {
+            device_code: self.id_generator.generate(IdKind::DeviceCode),
+            user_code: generate_user_code(),
+            client_id: client.client_id,
+            scope,
+            status: DeviceCodeStatus::Pending,
+            interval: self.device_poll_interval,
+            last_polled_at: None,
+            expires_at: Utc::now() + Duration::seconds(self.device_code_ttl),
+        };
+        self.save_device(&device).await?;
+        let ttl = Some(std::time::Duration::from_secs(self.device_code_ttl as u64));
+        self.storage.set(&format!("oauth2:device:user:{}", device.user_code), &device.device_code, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        let user_code = format!("{}-{}", &device.user_code[..4], &device.user_code[4..]);
+        let separator = if self.device_verification_uri.contains('?') { '&' } else { '?' };
+        Ok(DeviceAuthorization {
+            verification_uri_complete: format!("{}{}user_code={}", self.device_verification_uri, separator, user_code),
+            verification_uri: self.device_verification_uri.clone(),
+            device_code: device.device_code,
+            user_code,
+            expires_in: self.device_code_ttl,
+            interval: device.interval,
+        })
+    }
+
+    /// Look up a pending device authorization by user code | 根据用户码查询待处理的设备授权
+    ///
+    /// Used by the verification page to show which client and scopes the user is approving.
+    /// Case, spaces and dashes in the user code are ignored.
+    /// 供验证页展示用户正在授权的客户端与权限范围。用户码忽略大小写、空格和连字符。
+    ///
+    /// # Arguments | 参数
+    /// * `user_code` - Code entered by the user | 用户输入的用户码
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2UserCodeNotFound)` if the code is unknown, expired or already used | 用户码不存在、已过期或已使用时
+    pub async fn get_device_authorization(&self, user_code: &str) -> SaTokenResult<DeviceCode> {
+        let key = format!("oauth2:device:user:{}", normalize_user_code(user_code));
+        let device_code = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::OAuth2UserCodeNotFound)?;
+        match self.load_device(&device_code).await? {
+            Some(device) if device.status == DeviceCodeStatus::Pending => Ok(device),
+            _ => Err(SaTokenError::OAuth2UserCodeNotFound),
+        }
+    }
+
+    /// Approve a device authorization | 同意设备授权
+    ///
+    /// Called by the verification page once the user has logged in and consented.
+    /// The device receives tokens for `user_id` on its next poll.
+    /// 用户登录并同意后由验证页调用，设备下次轮询时获得 `user_id` 的令牌。
+    ///
+    /// # Arguments | 参数
+    /// * `user_code` - Code entered by the user | 用户输入的用户码
+    /// * `user_id` - User granting authorization | 授予授权的用户
+    pub async fn approve_device_authorization(&self, user_code: &str, user_id: &str) -> SaTokenResult<()> {
+        self.resolve_device(user_code, DeviceCodeStatus::Approved { user_id: user_id.to_string() }).await
+    }
+
+    /// Deny a device authorization | 拒绝设备授权
+    ///
+    /// # Arguments | 参数
+    /// * `user_code` - Code entered by the user | 用户输入的用户码
+    pub async fn deny_device_authorization(&self, user_code: &str) -> SaTokenResult<()> {
+        self.resolve_device(user_code, DeviceCodeStatus::Denied).await
+    }
+
+    /// Poll the token endpoint with a device code | 使用设备码轮询令牌端点
+    ///
+    /// # Arguments | 参数
+    /// * `device_code` - Device code | 设备码
+    /// * `client_id` - Client identifier | 客户端标识符
+    /// * `client_secret` - Client secret, `None` for public clients | 客户端密钥，公共客户端为 `None`
+    ///
+    /// # Returns | 返回
+    /// * `Ok(AccessToken)` once the user approved | 用户同意后返回令牌
+    /// * `Err(OAuth2AuthorizationPending)` while the user has not decided | 用户尚未操作时
+    /// * `Err(OAuth2SlowDown)` if polled faster than the interval; the interval grows by 5 seconds | 轮询过快时，间隔增加 5 秒
+    /// * `Err(OAuth2AccessDenied)` if the user denied | 用户拒绝时
+    /// * `Err(OAuth2DeviceCodeExpired)` if the code expired or is unknown | 设备码过期或不存在时
+    pub async fn poll_device_token(
+        &self,
+        device_code: &str,
+        client_id: &str,
+        client_secret: Option<&str>,
+    ) -> SaTokenResult<AccessToken> {
+        let client = self.authenticate_client(client_id, client_secret).await?;
+        self.poll_device(device_code, &client).await
+    }
+
+    async fn poll_device(&self, device_code: &str, client: &OAuth2Client) -> SaTokenResult<AccessToken> {
+        let mut device = self.load_device(device_code).await?
+            .ok_or(SaTokenError::OAuth2DeviceCodeExpired)?;
+        if device.client_id != client.client_id {
+            return Err(SaTokenError::OAuth2ClientIdMismatch);
+        }
+
+        match device.status.clone() {
+            DeviceCodeStatus::Pending => {
+                let now = Utc::now();
+                let too_fast = device.last_polled_at
+                    .is_some_and(|last| now < last + Duration::seconds(device.interval));
+                device.last_polled_at = Some(now);
+                if too_fast {
+                    device.interval += 5;
+                }
+                self.save_device(&device).await?;
+                Err(if too_fast { SaTokenError::OAuth2SlowDown } else { SaTokenError::OAuth2AuthorizationPending })
+            }
+            DeviceCodeStatus::Denied => {
+                self.delete_device(&device).await?;
+                Err(SaTokenError::OAuth2AccessDenied)
+            }
+            DeviceCodeStatus::Approved { user_id } => {
+                // Only one concurrent poll may redeem the code
+                let claim_key = format!("oauth2:device:claimed:{}", device.device_code);
+                let ttl = Some(std::time::Duration::from_secs(self.device_code_ttl as u64));
+                let claimed = self.storage.set_nx(&claim_key, "1", ttl).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+                if !claimed {
+                    return Err(SaTokenError::OAuth2DeviceCodeExpired);
+                }
+                self.delete_device(&device).await?;
+                self.generate_access_token(&device.client_id, &user_id, device.scope).await
+            }
+        }
+    }
+
+    async fn resolve_device(&self, user_code: &str, status: DeviceCodeStatus) -> SaTokenResult<()> {
+        let mut device = self.get_device_authorization(user_code).await?;
+        device.status = status;
+        self.save_device(&device).await?;
+        // The user code is single-use | 用户码只能使用一次
+        self.storage.delete(&format!("oauth2:device:user:{}", device.user_code)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn load_device(&self, device_code: &str) -> SaTokenResult<Option<DeviceCode>> {
+        let key = format!("oauth2:device:{}", device_code);
+        let Some(value) = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))? else {
+            return Ok(None);
+        };
+        let device: DeviceCode = serde_json::from_str(&value)
+            .map_err(SaTokenError::SerializationError)?;
+        if Utc::now() > device.expires_at {
+            self.delete_device(&device).await?;
+            return Ok(None);
+        }
+        Ok(Some(device))
+    }
+
+    async fn save_device(&self, device: &DeviceCode) -> SaTokenResult<()> {
+        let key = format!("oauth2:device:{}", device.device_code);
+        let value = serde_json::to_string(device)
+            .map_err(SaTokenError::SerializationError)?;
+        let remaining = (device.expires_at - Utc::now()).num_seconds().max(1);
+        let ttl = Some(std::time::Duration::from_secs(remaining as u64));
+        self.storage.set(&key, &value, ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn delete_device(&self, device: &DeviceCode) -> SaTokenResult<()> {
+        for key in [
+            format!("oauth2:device:{}", device.device_code),
+            format!("oauth2:device:user:{}", device.user_code),
+        ] {
+            self.storage.delete(&key).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        Ok(())
     }
 
     /// Generate access token and refresh token | 生成访问令牌和刷新令牌
@@ -623,6 +2073,24 @@ impl OAuth2Manager {
         client_id: &str,
         user_id: &str,
         scope: Vec<String>,
+    ) -> SaTokenResult<AccessToken> {
+        let family = Uuid::new_v4().simple().to_string();
+        self.issue_tokens(client_id, user_id, scope.clone(), scope, &family).await
+    }
+
+    /// Issue a token pair as members of a token family | 签发属于某个 token 家族的令牌对
+    ///
+    /// Both tokens are added to `oauth2:family:{family}` so `revoke_family` can find them.
+    /// The refresh token keeps the full `granted_scope` even when `scope` was narrowed.
+    /// 两个令牌都会加入 `oauth2:family:{family}`，以便 `revoke_family` 查找。
+    /// 即使 `scope` 被缩小，刷新令牌仍保留完整的 `granted_scope`。
+    async fn issue_tokens(
+        &self,
+        client_id: &str,
+        user_id: &str,
+        scope: Vec<String>,
+        granted_scope: Vec<String>,
+        family: &str,
     ) -> SaTokenResult<AccessToken> {
         let now = Utc::now();
         let access_token = format!("at_{}", Uuid::new_v4().simple());
@@ -637,12 +2105,15 @@ impl OAuth2Manager {
             created_at: now,
             expires_at: now + Duration::seconds(self.token_ttl),
             refresh_token: Some(refresh_token.clone()),
+            client_only: false,
+            audience: Vec::new(),
+            act: None,
         };
 
         // Store access token with TTL
         let key = format!("oauth2:token:{}", access_token);
         let value = serde_json::to_string(&token_info)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         let ttl = Some(std::time::Duration::from_secs(self.token_ttl as u64));
         self.storage.set(&key, &value, ttl).await
@@ -653,11 +2124,21 @@ impl OAuth2Manager {
         let refresh_value = serde_json::json!({
             "user_id": user_id,
             "client_id": client_id,
-            "scope": scope,
+            "scope": granted_scope,
+            "family": family,
         }).to_string();
         
-        let refresh_ttl = Some(std::time::Duration::from_secs(self.refresh_token_ttl as u64));
-        self.storage.set(&refresh_key, &refresh_value, refresh_ttl).await
+        let refresh_ttl = std::time::Duration::from_secs(self.refresh_token_ttl as u64);
+        self.storage.set(&refresh_key, &refresh_value, Some(refresh_ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        // Track the family members for reuse revocation | 记录家族成员，用于重复使用时撤销
+        let family_key = format!("oauth2:family:{}", family);
+        for token in [&access_token, &refresh_token] {
+            self.storage.sadd(&family_key, token).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        self.storage.expire(&family_key, refresh_ttl).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
 
         // Return the access token response
@@ -667,6 +2148,8 @@ impl OAuth2Manager {
             expires_in: self.token_ttl,
             refresh_token: Some(refresh_token),
             scope,
+            id_token: None,
+            issued_token_type: None,
         })
     }
 
@@ -690,10 +2173,10 @@ impl OAuth2Manager {
         let key = format!("oauth2:token:{}", access_token);
         let value = self.storage.get(&key).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::OAuth2AccessTokenNotFound)?;
+            .ok_or(SaTokenError::OAuth2AccessTokenNotFound)?;
         
         let token_info: OAuth2TokenInfo = serde_json::from_str(&value)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         // Check expiration and auto-cleanup if expired
         if Utc::now() > token_info.expires_at {
@@ -704,6 +2187,29 @@ impl OAuth2Manager {
         Ok(token_info)
     }
 
+    /// OpenID Connect UserInfo claims for an access token | 访问令牌对应的 OpenID Connect 用户信息
+    ///
+    /// # Arguments | 参数
+    /// * `access_token` - Bearer access token | Bearer 访问令牌
+    ///
+    /// # Returns | 返回
+    /// * `Ok(claims)` with `sub` and the `UserInfoProvider` claims | 含 `sub` 与 `UserInfoProvider` 提供的声明
+    /// * `Err(OAuth2AccessTokenNotFound)` if the token is unknown or expired | 令牌不存在或已过期时
+    /// * `Err(OAuth2InsufficientScope)` if the token was not granted `openid` | 令牌未授予 `openid` 时
+    /// * `Err(ConfigError)` if OpenID Connect is not enabled | 未启用 OpenID Connect 时
+    pub async fn userinfo(&self, access_token: &str) -> SaTokenResult<Map<String, Value>> {
+        let oidc = self.oidc.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("OpenID Connect is not enabled".to_string()))?;
+        let info = self.verify_access_token(access_token).await.map_err(|e| match e {
+            SaTokenError::TokenExpired => SaTokenError::OAuth2AccessTokenNotFound,
+            e => e,
+        })?;
+        if info.client_only || !info.scope.iter().any(|s| s == OPENID_SCOPE) {
+            return Err(SaTokenError::OAuth2InsufficientScope(OPENID_SCOPE.to_string()));
+        }
+        oidc.userinfo(&info.user_id, &info.scope).await
+    }
+
     /// Refresh access token using refresh token | 使用刷新令牌刷新访问令牌
     /// 
     /// Issues a new access token (and optionally a new refresh token) when the old one expires.
@@ -730,40 +2236,152 @@ impl OAuth2Manager {
         client_id: &str,
         client_secret: &str,
     ) -> SaTokenResult<AccessToken> {
-        // 1. Verify client credentials
-        if !self.verify_client(client_id, client_secret).await? {
-            return Err(SaTokenError::OAuth2InvalidCredentials);
-        }
+        self.authenticate_client(client_id, Some(client_secret)).await?;
+        self.refresh(refresh_token, client_id, None, false).await
+    }
 
-        // 2. Get refresh token data from storage
-        let key = format!("oauth2:refresh:{}", refresh_token);
-        let value = self.storage.get(&key).await
-            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::OAuth2RefreshTokenNotFound)?;
-        
-        let data: serde_json::Value = serde_json::from_str(&value)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
-        
-        // 3. Validate client ID matches
-        let stored_client_id = data["client_id"].as_str()
-            .ok_or_else(|| SaTokenError::OAuth2InvalidRefreshToken)?;
-        
+    /// Refresh with rotation | 轮换刷新
+    /// 
+    /// Like `refresh_access_token`, but the presented refresh token is used up. Presenting it
+    /// again means it was stolen, so every token of its family is revoked.
+    /// 与 `refresh_access_token` 相同，但传入的刷新令牌随即作废。再次出现说明它已被盗用，
+    /// 此时该家族的所有令牌都会被撤销。
+    /// 
+    /// # Arguments | 参数
+    /// * `refresh_token` - Refresh token | 刷新令牌
+    /// * `client_id` - Client identifier | 客户端标识符
+    /// * `client_secret` - Client secret | 客户端密钥
+    /// 
+    /// # Returns | 返回
+    /// * `Ok(AccessToken)` with new access_token and refresh_token | 新的访问令牌和刷新令牌
+    /// * `Err(OAuth2InvalidCredentials)` if credentials invalid | 凭据无效时
+    /// * `Err(OAuth2RefreshTokenNotFound)` if refresh token not found | 刷新令牌未找到时
+    /// * `Err(OAuth2ClientIdMismatch)` if client ID doesn't match | 客户端 ID 不匹配时
+    /// * `Err(RefreshTokenReused)` if the refresh token was already used | 刷新令牌已被使用时
+    pub async fn refresh_with_rotation(
+        &self,
+        refresh_token: &str,
+        client_id: &str,
+        client_secret: &str,
+    ) -> SaTokenResult<AccessToken> {
+        self.authenticate_client(client_id, Some(client_secret)).await?;
+        self.refresh(refresh_token, client_id, None, true).await
+    }
+
+    /// Refresh token grant with scope narrowing | 支持缩小权限范围的刷新令牌授权
+    ///
+    /// The new access token carries `scope` when given, which must be a subset of the
+    /// originally granted scope; the new refresh token keeps the original scope (RFC 6749 §6).
+    /// Rotation follows `with_refresh_token_rotation`.
+    /// 传入 `scope` 时新访问令牌使用该范围，且必须是原授权范围的子集；新刷新令牌保留原范围
+    /// （RFC 6749 §6）。是否轮换取决于 `with_refresh_token_rotation`。
+    ///
+    /// # Arguments | 参数
+    /// * `refresh_token` - Refresh token | 刷新令牌
+    /// * `client_id` - Client identifier | 客户端标识符
+    /// * `client_secret` - Client secret, `None` for public clients | 客户端密钥，公共客户端为 `None`
+    /// * `scope` - Narrowed scope, `None` keeps the granted scope | 缩小后的范围，`None` 保持原范围
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2UnauthorizedClient)` if the client is not registered for `refresh_token` | 客户端未注册 `refresh_token` 授权时
+    /// * `Err(OAuth2ScopeExceedsGrant)` if `scope` widens the grant | `scope` 超出原授权范围时
+    pub async fn refresh_token_grant(
+        &self,
+        refresh_token: &str,
+        client_id: &str,
+        client_secret: Option<&str>,
+        scope: Option<&[String]>,
+    ) -> SaTokenResult<AccessToken> {
+        let client = self.authenticate_client(client_id, client_secret).await?;
+        if !client.grant_types.iter().any(|g| g == "refresh_token") {
+            return Err(SaTokenError::OAuth2UnauthorizedClient);
+        }
+        self.refresh(refresh_token, client_id, scope, self.rotate_refresh_tokens).await
+    }
+
+    async fn refresh(
+        &self,
+        refresh_token: &str,
+        client_id: &str,
+        scope: Option<&[String]>,
+        rotate: bool,
+    ) -> SaTokenResult<AccessToken> {
+        // 1. Get refresh token data from storage
+        let key = format!("oauth2:refresh:{}", refresh_token);
+        let value = self.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::OAuth2RefreshTokenNotFound)?;
+        
+        let mut data: serde_json::Value = serde_json::from_str(&value)
+            .map_err(SaTokenError::SerializationError)?;
+        
+        // 2. Validate client ID matches
+        let stored_client_id = data["client_id"].as_str()
+            .ok_or(SaTokenError::OAuth2InvalidRefreshToken)?;
         if stored_client_id != client_id {
             return Err(SaTokenError::OAuth2ClientIdMismatch);
         }
 
-        // 4. Extract user ID and scope
+        // 3. Extract user ID and scope, narrowing the scope if requested
         let user_id = data["user_id"].as_str()
-            .ok_or_else(|| SaTokenError::OAuth2InvalidRefreshToken)?;
-        
-        let scope: Vec<String> = data["scope"].as_array()
-            .ok_or_else(|| SaTokenError::OAuth2InvalidScope)?
+            .ok_or(SaTokenError::OAuth2InvalidRefreshToken)?
+            .to_string();
+        let granted: Vec<String> = data["scope"].as_array()
+            .ok_or(SaTokenError::OAuth2InvalidScope)?
             .iter()
             .filter_map(|v| v.as_str().map(|s| s.to_string()))
             .collect();
+        let scope = match scope {
+            Some(requested) if !requested.is_empty() => {
+                if !requested.iter().all(|s| granted.contains(s)) {
+                    return Err(SaTokenError::OAuth2ScopeExceedsGrant);
+                }
+                requested.to_vec()
+            }
+            _ => granted.clone(),
+        };
+
+        if !rotate {
+            // 4. Generate new access token in a new family
+            let family = Uuid::new_v4().simple().to_string();
+            return self.issue_tokens(client_id, &user_id, scope, granted, &family).await;
+        }
+
+        // 4. Claim the token; a used or concurrently claimed token revokes the family
+        let family = data["family"].as_str().unwrap_or(refresh_token).to_string();
+        let refresh_ttl = std::time::Duration::from_secs(self.refresh_token_ttl as u64);
+        let claim_key = format!("oauth2:refresh:claimed:{}", refresh_token);
+        let claimed = data["used_at"].is_null() && self.storage.set_nx(&claim_key, "1", Some(refresh_ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            tracing::warn!("OAuth2 refresh token reused by client {}, revoking token family {}", client_id, family);
+            self.revoke_family(&family).await?;
+            return Err(SaTokenError::RefreshTokenReused);
+        }
+
+        // 5. Keep the used record so a later replay is detected
+        data["used_at"] = serde_json::json!(Utc::now().to_rfc3339());
+        self.storage.set(&key, &data.to_string(), Some(refresh_ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
 
-        // 5. Generate new access token with same scope
-        self.generate_access_token(client_id, user_id, scope).await
+        // 6. Issue the new pair in the same family
+        self.issue_tokens(client_id, &user_id, scope, granted, &family).await
+    }
+
+    /// Revoke every access and refresh token of a token family | 撤销 token 家族的全部访问令牌与刷新令牌
+    /// 
+    /// # Arguments | 参数
+    /// * `family` - Token family ID | Token 家族 ID
+    pub async fn revoke_family(&self, family: &str) -> SaTokenResult<()> {
+        let family_key = format!("oauth2:family:{}", family);
+        let tokens = self.storage.smembers(&family_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        for token in tokens.iter().chain(std::iter::once(&family.to_string())) {
+            self.revoke_token(token).await?;
+        }
+        self.storage.delete(&family_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(())
     }
 
     /// Revoke an access token or refresh token | 撤销访问令牌或刷新令牌
@@ -788,6 +2406,163 @@ impl OAuth2Manager {
         Ok(())
     }
 
+    /// Introspect an access token (RFC 7662) | 内省访问令牌（RFC 7662）
+    ///
+    /// Unknown, expired and revoked tokens are reported as `{"active": false}` rather than
+    /// as errors; only storage failures return `Err`.
+    /// 不存在、已过期或已撤销的令牌返回 `{"active": false}` 而不是错误；只有存储故障返回 `Err`。
+    ///
+    /// # Arguments | 参数
+    /// * `token` - Access token to introspect | 要内省的访问令牌
+    ///
+    /// # Returns | 返回
+    /// * `active`, `scope`, `client_id`, `sub`, `token_type`, `exp`, `iat` for active tokens;
+    ///   `sub` is absent for client-only tokens | 有效令牌返回上述字段，客户端令牌不含 `sub`
+    pub async fn introspect(&self, token: &str) -> SaTokenResult<IntrospectionResponse> {
+        let info = match self.verify_access_token(token).await {
+            Ok(info) => info,
+            Err(SaTokenError::OAuth2AccessTokenNotFound | SaTokenError::TokenExpired) => {
+                return Ok(IntrospectionResponse::default());
+            }
+            Err(e) => return Err(e),
+        };
+        let mut extra = HashMap::new();
+        if !info.audience.is_empty() {
+            extra.insert("aud".to_string(), Value::from(info.audience.clone()));
+        }
+        if let Some(act) = &info.act {
+            extra.insert("act".to_string(), serde_json::to_value(act).map_err(SaTokenError::SerializationError)?);
+        }
+        Ok(IntrospectionResponse {
+            active: true,
+            scope: Some(info.scope.join(" ")),
+            sub: info.login_id().map(str::to_string),
+            client_id: Some(info.client_id),
+            token_type: Some("Bearer".to_string()),
+            exp: Some(info.expires_at.timestamp()),
+            iat: Some(info.created_at.timestamp()),
+            extra,
+            ..Default::default()
+        })
+    }
+
+    /// Handle an introspection endpoint request | 处理内省端点请求
+    ///
+    /// Authenticates the calling resource server (a confidential client) from the
+    /// `Authorization: Basic` header or the form, then introspects `request.token`.
+    /// 从 `Authorization: Basic` 请求头或表单认证调用方资源服务器（机密客户端），
+    /// 然后内省 `request.token`。
+    ///
+    /// # Arguments | 参数
+    /// * `authorization` - Value of the `Authorization` header | `Authorization` 请求头的值
+    /// * `request` - Introspection form parameters | 内省表单参数
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2InvalidCredentials)` if the caller is not an authenticated confidential client | 调用方不是已认证的机密客户端时
+    pub async fn introspection_request(
+        &self,
+        authorization: Option<&str>,
+        request: &OAuth2IntrospectionRequest,
+    ) -> SaTokenResult<IntrospectionResponse> {
+        let client = self.request_client(authorization, request).await?;
+        // Public clients cannot authenticate | 公共客户端无法认证
+        if client.client_secret.is_empty() {
+            return Err(SaTokenError::OAuth2InvalidCredentials);
+        }
+        self.introspect(&request.token).await
+    }
+
+    /// Revoke a token (RFC 7009) | 撤销令牌（RFC 7009）
+    ///
+    /// Revoking an access token revokes only that token. Revoking a refresh token cascades
+    /// to its whole token family, including access tokens issued from it. `token_type_hint`
+    /// (`access_token` / `refresh_token`) only decides which lookup runs first. Unknown
+    /// tokens are ignored.
+    /// 撤销访问令牌只撤销该令牌；撤销刷新令牌会级联撤销整个 token 家族，包括由其签发的
+    /// 访问令牌。`token_type_hint`（`access_token` / `refresh_token`）只决定查找顺序。
+    /// 未知令牌直接忽略。
+    ///
+    /// # Arguments | 参数
+    /// * `token` - Access or refresh token | 访问令牌或刷新令牌
+    /// * `token_type_hint` - Optional token type hint | 可选的令牌类型提示
+    pub async fn revoke(&self, token: &str, token_type_hint: Option<&str>) -> SaTokenResult<()> {
+        match self.find_token(token, token_type_hint).await? {
+            Some((_, None)) => self.revoke_token(token).await,
+            Some((_, Some(family))) => self.revoke_family(&family).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Handle a revocation endpoint request | 处理撤销端点请求
+    ///
+    /// Authenticates the client (public clients send only `client_id`) and revokes the
+    /// token if it was issued to that client. Unknown tokens succeed, as RFC 7009 requires.
+    /// 认证客户端（公共客户端只需发送 `client_id`），令牌属于该客户端时撤销。
+    /// 按 RFC 7009 的要求，未知令牌同样返回成功。
+    ///
+    /// # Arguments | 参数
+    /// * `authorization` - Value of the `Authorization` header | `Authorization` 请求头的值
+    /// * `request` - Revocation form parameters | 撤销表单参数
+    ///
+    /// # Returns | 返回
+    /// * `Err(OAuth2InvalidCredentials)` if the client fails to authenticate | 客户端认证失败时
+    /// * `Err(OAuth2ClientIdMismatch)` if the token belongs to another client | 令牌属于其他客户端时
+    pub async fn revocation_request(
+        &self,
+        authorization: Option<&str>,
+        request: &OAuth2RevocationRequest,
+    ) -> SaTokenResult<()> {
+        let client = self.request_client(authorization, request).await?;
+        match self.find_token(&request.token, request.token_type_hint.as_deref()).await? {
+            Some((owner, _)) if owner != client.client_id => Err(SaTokenError::OAuth2ClientIdMismatch),
+            Some((_, None)) => self.revoke_token(&request.token).await,
+            Some((_, Some(family))) => self.revoke_family(&family).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Authenticate the client of an introspection or revocation request
+    /// 认证内省或撤销请求的客户端
+    async fn request_client(
+        &self,
+        authorization: Option<&str>,
+        request: &OAuth2IntrospectionRequest,
+    ) -> SaTokenResult<OAuth2Client> {
+        let (client_id, client_secret) = match authorization.and_then(parse_basic) {
+            Some((id, secret)) => (id, Some(secret)),
+            None => (
+                request.client_id.clone().ok_or(SaTokenError::OAuth2InvalidCredentials)?,
+                request.client_secret.clone(),
+            ),
+        };
+        self.authenticate_client(&client_id, client_secret.as_deref()).await
+            .map_err(|e| match e {
+                SaTokenError::OAuth2ClientNotFound => SaTokenError::OAuth2InvalidCredentials,
+                e => e,
+            })
+    }
+
+    /// Find a token's owning client and, for refresh tokens, its family
+    /// 查找令牌所属的客户端；刷新令牌同时返回其家族
+    async fn find_token(&self, token: &str, token_type_hint: Option<&str>) -> SaTokenResult<Option<(String, Option<String>)>> {
+        let refresh_first = token_type_hint == Some("refresh_token");
+        for refresh in [refresh_first, !refresh_first] {
+            let prefix = if refresh { "oauth2:refresh" } else { "oauth2:token" };
+            let Some(value) = self.storage.get(&format!("{}:{}", prefix, token)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))? else {
+                continue;
+            };
+            let data: serde_json::Value = serde_json::from_str(&value)
+                .map_err(SaTokenError::SerializationError)?;
+            let client_id = data["client_id"].as_str()
+                .ok_or(SaTokenError::OAuth2InvalidRefreshToken)?
+                .to_string();
+            let family = refresh.then(|| data["family"].as_str().unwrap_or(token).to_string());
+            return Ok(Some((client_id, family)));
+        }
+        Ok(None)
+    }
+
     /// Validate redirect URI against client's whitelist | 根据客户端白名单验证回调 URI
     /// 
     /// Security check to prevent redirect URI hijacking.
@@ -821,6 +2596,64 @@ impl OAuth2Manager {
     }
 }
 
+/// Consonants only, so codes cannot spell words (RFC 8628 §6.1) | 仅用辅音字母，避免组成单词
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+
+/// Generate an 8-character user code | 生成 8 位用户码
+/// Append query parameters to a redirect URI, skipping absent ones
+/// 向回调 URI 追加查询参数，跳过缺失的参数
+fn redirect_with(redirect_uri: &str, params: &[(&str, Option<&str>)]) -> String {
+    let mut url = redirect_uri.to_string();
+    let mut separator = if redirect_uri.contains('?') { '&' } else { '?' };
+    for (name, value) in params {
+        if let Some(value) = value {
+            url.push(separator);
+            url.push_str(&format!("{}={}", name, urlencoding::encode(value)));
+            separator = '&';
+        }
+    }
+    url
+}
+
+/// Error redirect of the authorization endpoint (RFC 6749 §4.1.2.1)
+/// 授权端点的错误重定向（RFC 6749 §4.1.2.1）
+fn authorize_error_redirect(request: &OAuth2AuthorizeRequest, err: &SaTokenError) -> String {
+    let mut error = OAuth2ErrorResponse::from(err);
+    // invalid_grant is a token endpoint error | invalid_grant 属于令牌端点错误
+    if error.error == "invalid_grant" {
+        error.error = "invalid_request".to_string();
+    }
+    redirect_with(&request.redirect_uri, &[
+        ("error", Some(error.error.as_str())),
+        ("error_description", error.error_description.as_deref()),
+        ("state", request.state.as_deref()),
+    ])
+}
+
+fn generate_user_code() -> String {
+    let mut code = String::with_capacity(8);
+    let mut buf = [0u8; 16];
+    while code.len() < 8 {
+        getrandom::fill(&mut buf).expect("OS random source is unavailable");
+        // Reject bytes above the largest multiple of 20 to avoid modulo bias
+        for b in buf.iter().filter(|b| **b < 240) {
+            if code.len() == 8 {
+                break;
+            }
+            code.push(USER_CODE_CHARSET[(*b as usize) % USER_CODE_CHARSET.len()] as char);
+        }
+    }
+    code
+}
+
+/// Strip separators and upper-case a user code | 去除分隔符并转为大写
+fn normalize_user_code(user_code: &str) -> String {
+    user_code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -865,6 +2698,85 @@ mod tests {
         assert_eq!(token_info.client_id, "test_client");
     }
 
+    #[tokio::test]
+    async fn test_pkce_public_client() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage);
+
+        let client = OAuth2Client {
+            client_id: "mobile_app".to_string(),
+            client_secret: String::new(),
+            redirect_uris: vec!["app://callback".to_string()],
+            grant_types: vec!["authorization_code".to_string()],
+            scope: vec!["read".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let verifier = "dBjftJeZ4CVP-mJ92K9qyVSmOMGdtrS0G9Lz9q3L7bU";
+        let challenge = "w3EVWGXi7hmRGyfVRsFs6wvFmeeUP4rIPWJZSeyhlqo";
+        assert_eq!(PkceMethod::S256.challenge(verifier), challenge);
+
+        let issue = || async {
+            let auth_code = oauth2.generate_authorization_code_with_pkce(
+                "mobile_app".to_string(),
+                "user_123".to_string(),
+                "app://callback".to_string(),
+                vec!["read".to_string()],
+                challenge.to_string(),
+                Some("S256"),
+            ).unwrap();
+            oauth2.store_authorization_code(&auth_code).await.unwrap();
+            auth_code.code
+        };
+
+        let code = issue().await;
+        let token = oauth2.exchange_code_with_pkce(&code, "mobile_app", None, "app://callback", verifier)
+            .await.unwrap();
+        assert_eq!(oauth2.verify_access_token(&token.access_token).await.unwrap().user_id, "user_123");
+
+        let code = issue().await;
+        let wrong = "x".repeat(43);
+        assert!(matches!(
+            oauth2.exchange_code_with_pkce(&code, "mobile_app", None, "app://callback", &wrong).await,
+            Err(SaTokenError::OAuth2InvalidCodeVerifier)
+        ));
+        // A failed verification still consumes the code
+        assert!(matches!(
+            oauth2.exchange_code_with_pkce(&code, "mobile_app", None, "app://callback", verifier).await,
+            Err(SaTokenError::OAuth2CodeNotFound)
+        ));
+
+        let code = issue().await;
+        assert!(matches!(
+            oauth2.exchange_code_for_token(&code, "mobile_app", "", "app://callback").await,
+            Err(SaTokenError::OAuth2PkceRequired)
+        ));
+
+        let plain = oauth2.generate_authorization_code(
+            "mobile_app".to_string(),
+            "user_123".to_string(),
+            "app://callback".to_string(),
+            vec!["read".to_string()],
+        );
+        oauth2.store_authorization_code(&plain).await.unwrap();
+        assert!(matches!(
+            oauth2.exchange_code_for_token(&plain.code, "mobile_app", "", "app://callback").await,
+            Err(SaTokenError::OAuth2PkceRequired)
+        ));
+
+        assert!(matches!(
+            oauth2.generate_authorization_code_with_pkce(
+                "mobile_app".to_string(),
+                "user_123".to_string(),
+                "app://callback".to_string(),
+                vec![],
+                challenge.to_string(),
+                Some("S512"),
+            ),
+            Err(SaTokenError::OAuth2InvalidCodeChallenge)
+        ));
+    }
+
     #[tokio::test]
     async fn test_refresh_token() {
         let storage = Arc::new(MemoryStorage::new());
@@ -895,5 +2807,470 @@ mod tests {
 
         assert_ne!(new_token.access_token, token.access_token);
     }
-}
 
+    #[tokio::test]
+    async fn test_refresh_token_grant() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage).with_refresh_token_rotation(true);
+
+        let client = OAuth2Client {
+            client_id: "test_client".to_string(),
+            client_secret: "test_secret".to_string(),
+            redirect_uris: vec!["http://localhost:3000/callback".to_string()],
+            grant_types: vec!["authorization_code".to_string(), "refresh_token".to_string()],
+            scope: vec!["read".to_string(), "write".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let token = oauth2.generate_access_token(
+            "test_client",
+            "user_123",
+            vec!["read".to_string(), "write".to_string()],
+        ).await.unwrap();
+
+        // Narrow the scope; the rotated refresh token keeps the full grant
+        let request = OAuth2TokenRequest {
+            grant_type: "refresh_token".to_string(),
+            client_id: "test_client".to_string(),
+            client_secret: Some("test_secret".to_string()),
+            refresh_token: token.refresh_token.clone(),
+            scope: Some("read".to_string()),
+            ..Default::default()
+        };
+        let narrowed = oauth2.token_request(&request).await.unwrap();
+        assert_eq!(narrowed.scope, vec!["read".to_string()]);
+        assert_ne!(narrowed.refresh_token, token.refresh_token);
+
+        let full = oauth2.refresh_token_grant(
+            narrowed.refresh_token.as_deref().unwrap(), "test_client", Some("test_secret"), None,
+        ).await.unwrap();
+        assert_eq!(full.scope, vec!["read".to_string(), "write".to_string()]);
+
+        let err = oauth2.refresh_token_grant(
+            full.refresh_token.as_deref().unwrap(), "test_client", Some("test_secret"), Some(&["admin".to_string()]),
+        ).await.unwrap_err();
+        assert!(matches!(err, SaTokenError::OAuth2ScopeExceedsGrant));
+        assert_eq!(OAuth2ErrorResponse::from(&err).error, "invalid_scope");
+
+        // Rotation: the first refresh token is used up
+        let err = oauth2.token_request(&request).await.unwrap_err();
+        let response = OAuth2ErrorResponse::from(&err);
+        assert_eq!(response.error, "invalid_grant");
+        assert_eq!(response.status_code(), 400);
+
+        let err = oauth2.token_request(&OAuth2TokenRequest {
+            client_secret: Some("wrong".to_string()),
+            ..request.clone()
+        }).await.unwrap_err();
+        let response = OAuth2ErrorResponse::from(&err);
+        assert_eq!(response.error, "invalid_client");
+        assert_eq!(response.status_code(), 401);
+
+        let err = oauth2.token_request(&OAuth2TokenRequest {
+            grant_type: "password".to_string(),
+            ..request
+        }).await.unwrap_err();
+        assert_eq!(
+            serde_json::to_value(OAuth2ErrorResponse::from(&err)).unwrap(),
+            serde_json::json!({
+                "error": "unsupported_grant_type",
+                "error_description": "Unsupported grant type: 'password'",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials_grant() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage);
+
+        let client = OAuth2Client {
+            client_id: "billing_service".to_string(),
+            client_secret: "service_secret".to_string(),
+            redirect_uris: vec![],
+            grant_types: vec!["client_credentials".to_string()],
+            scope: vec!["invoices:read".to_string(), "invoices:write".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let token = oauth2.token_request(&OAuth2TokenRequest {
+            grant_type: "client_credentials".to_string(),
+            client_id: "billing_service".to_string(),
+            client_secret: Some("service_secret".to_string()),
+            scope: Some("invoices:read".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert!(token.refresh_token.is_none());
+        assert_eq!(token.scope, vec!["invoices:read".to_string()]);
+
+        let info = oauth2.verify_access_token(&token.access_token).await.unwrap();
+        assert!(info.client_only);
+        assert_eq!(info.client_id, "billing_service");
+        assert_eq!(info.login_id(), None);
+
+        assert!(matches!(
+            oauth2.client_credentials_grant("billing_service", "wrong", None).await,
+            Err(SaTokenError::OAuth2InvalidCredentials)
+        ));
+        assert!(matches!(
+            oauth2.client_credentials_grant("billing_service", "service_secret", Some(&["admin".to_string()])).await,
+            Err(SaTokenError::OAuth2ScopeExceedsGrant)
+        ));
+        let all = oauth2.client_credentials_grant("billing_service", "service_secret", None).await.unwrap();
+        assert_eq!(all.scope.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_client_secret_hashing() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage.clone());
+
+        let client = OAuth2Client {
+            client_id: "web".to_string(),
+            client_secret: "web_secret".to_string(),
+            redirect_uris: vec![],
+            grant_types: vec!["client_credentials".to_string()],
+            scope: vec!["read".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let stored = oauth2.get_client("web").await.unwrap();
+        assert!(stored.client_secret.starts_with("$sha256$"));
+        assert!(oauth2.verify_client("web", "web_secret").await.unwrap());
+        assert!(!oauth2.verify_client("web", &stored.client_secret).await.unwrap());
+
+        // Re-registering the stored client keeps the hash as is
+        oauth2.register_client(&stored).await.unwrap();
+        assert!(oauth2.client_credentials_grant("web", "web_secret", None).await.is_ok());
+
+        // Secrets saved in plain text by earlier versions still verify
+        let legacy = OAuth2Client { client_id: "legacy".to_string(), client_secret: "plain".to_string(), ..client };
+        storage.set("oauth2:client:legacy", &serde_json::to_string(&legacy).unwrap(), None).await.unwrap();
+        assert!(oauth2.verify_client("legacy", "plain").await.unwrap());
+
+        assert_eq!(oauth2.list_clients().await.unwrap().len(), 1);
+        oauth2.delete_client("web").await.unwrap();
+        assert!(matches!(oauth2.get_client("web").await, Err(SaTokenError::OAuth2ClientNotFound)));
+        assert!(oauth2.list_clients().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_device_authorization_flow() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage)
+            .with_device_flow("https://example.com/device", 600, 5);
+
+        let client = OAuth2Client {
+            client_id: "tv_app".to_string(),
+            client_secret: String::new(),
+            redirect_uris: vec![],
+            grant_types: vec![DEVICE_CODE_GRANT_TYPE.to_string()],
+            scope: vec!["profile".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let auth = oauth2.request_device_authorization("tv_app", None, vec!["profile".to_string()]).await.unwrap();
+        assert_eq!(auth.user_code.len(), 9);
+        assert_eq!(auth.verification_uri_complete, format!("https://example.com/device?user_code={}", auth.user_code));
+
+        let poll = OAuth2TokenRequest {
+            grant_type: DEVICE_CODE_GRANT_TYPE.to_string(),
+            client_id: "tv_app".to_string(),
+            device_code: Some(auth.device_code.clone()),
+            ..Default::default()
+        };
+        let err = oauth2.token_request(&poll).await.unwrap_err();
+        assert_eq!(OAuth2ErrorResponse::from(&err).error, "authorization_pending");
+        let err = oauth2.token_request(&poll).await.unwrap_err();
+        assert_eq!(OAuth2ErrorResponse::from(&err).error, "slow_down");
+
+        // The verification page accepts the code in any case, with or without the dash
+        let entered = auth.user_code.replace('-', "").to_lowercase();
+        let pending = oauth2.get_device_authorization(&entered).await.unwrap();
+        assert_eq!(pending.client_id, "tv_app");
+        assert_eq!(pending.interval, 10);
+        oauth2.approve_device_authorization(&entered, "user_123").await.unwrap();
+        assert!(matches!(
+            oauth2.approve_device_authorization(&entered, "user_456").await,
+            Err(SaTokenError::OAuth2UserCodeNotFound)
+        ));
+
+        let token = oauth2.poll_device_token(&auth.device_code, "tv_app", None).await.unwrap();
+        assert_eq!(oauth2.verify_access_token(&token.access_token).await.unwrap().user_id, "user_123");
+        assert!(matches!(
+            oauth2.poll_device_token(&auth.device_code, "tv_app", None).await,
+            Err(SaTokenError::OAuth2DeviceCodeExpired)
+        ));
+
+        let denied = oauth2.request_device_authorization("tv_app", None, vec![]).await.unwrap();
+        oauth2.deny_device_authorization(&denied.user_code).await.unwrap();
+        assert!(matches!(
+            oauth2.poll_device_token(&denied.device_code, "tv_app", None).await,
+            Err(SaTokenError::OAuth2AccessDenied)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_introspection() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage);
+
+        let client = OAuth2Client {
+            client_id: "resource_server".to_string(),
+            client_secret: "rs_secret".to_string(),
+            redirect_uris: vec![],
+            grant_types: vec!["client_credentials".to_string()],
+            scope: vec!["read".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let token = oauth2.generate_access_token(
+            "resource_server",
+            "user_123",
+            vec!["read".to_string(), "write".to_string()],
+        ).await.unwrap();
+        let info = oauth2.introspect(&token.access_token).await.unwrap();
+        assert!(info.active);
+        assert_eq!(info.scope.as_deref(), Some("read write"));
+        assert_eq!(info.sub.as_deref(), Some("user_123"));
+        assert_eq!(info.client_id.as_deref(), Some("resource_server"));
+        assert!(info.exp.unwrap() > Utc::now().timestamp());
+
+        let service = oauth2.client_credentials_grant("resource_server", "rs_secret", None).await.unwrap();
+        assert_eq!(oauth2.introspect(&service.access_token).await.unwrap().sub, None);
+
+        oauth2.revoke_token(&token.access_token).await.unwrap();
+        let request = OAuth2IntrospectionRequest { token: token.access_token.clone(), ..Default::default() };
+        // "resource_server:rs_secret"
+        let basic = "Basic cmVzb3VyY2Vfc2VydmVyOnJzX3NlY3JldA==";
+        let info = oauth2.introspection_request(Some(basic), &request).await.unwrap();
+        assert_eq!(serde_json::to_value(&info).unwrap(), serde_json::json!({ "active": false }));
+
+        assert!(matches!(
+            oauth2.introspection_request(None, &request).await,
+            Err(SaTokenError::OAuth2InvalidCredentials)
+        ));
+        let with_form = OAuth2IntrospectionRequest {
+            client_id: Some("resource_server".to_string()),
+            client_secret: Some("rs_secret".to_string()),
+            ..request
+        };
+        assert!(oauth2.introspection_request(None, &with_form).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_revocation() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage);
+
+        for (id, secret) in [("app_a", "secret_a"), ("app_b", "")] {
+            oauth2.register_client(&OAuth2Client {
+                client_id: id.to_string(),
+                client_secret: secret.to_string(),
+                redirect_uris: vec![],
+                grant_types: vec!["refresh_token".to_string()],
+                scope: vec!["read".to_string()],
+            }).await.unwrap();
+        }
+
+        // Revoking an access token leaves its refresh token usable
+        let first = oauth2.generate_access_token("app_a", "user_123", vec!["read".to_string()]).await.unwrap();
+        oauth2.revoke(&first.access_token, Some("access_token")).await.unwrap();
+        assert!(oauth2.verify_access_token(&first.access_token).await.is_err());
+        let refreshed = oauth2.refresh_with_rotation(first.refresh_token.as_deref().unwrap(), "app_a", "secret_a")
+            .await.unwrap();
+
+        // Revoking a refresh token cascades to the family, even with a wrong hint
+        let request = OAuth2RevocationRequest {
+            token: first.refresh_token.clone().unwrap(),
+            token_type_hint: Some("access_token".to_string()),
+            client_id: Some("app_b".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            oauth2.revocation_request(None, &request).await,
+            Err(SaTokenError::OAuth2ClientIdMismatch)
+        ));
+        // "app_a:secret_a"
+        oauth2.revocation_request(Some("Basic YXBwX2E6c2VjcmV0X2E="), &request).await.unwrap();
+        assert!(oauth2.verify_access_token(&refreshed.access_token).await.is_err());
+        assert!(oauth2.refresh_access_token(refreshed.refresh_token.as_deref().unwrap(), "app_a", "secret_a")
+            .await.is_err());
+
+        // Unknown tokens are accepted
+        let unknown = OAuth2RevocationRequest { token: "rt_unknown".to_string(), ..request };
+        assert!(oauth2.revocation_request(Some("Basic YXBwX2E6c2VjcmV0X2E="), &unknown).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_with_rotation() {
+        let storage = Arc::new(MemoryStorage::new());
+        let oauth2 = OAuth2Manager::new(storage);
+
+        let client = OAuth2Client {
+            client_id: "test_client".to_string(),
+            client_secret: "test_secret".to_string(),
+            redirect_uris: vec!["http://localhost:3000/callback".to_string()],
+            grant_types: vec!["refresh_token".to_string()],
+            scope: vec!["read".to_string()],
+        };
+        oauth2.register_client(&client).await.unwrap();
+
+        let token = oauth2.generate_access_token("test_client", "user_123", vec!["read".to_string()]).await.unwrap();
+        let rt1 = token.refresh_token.unwrap();
+        let rotated = oauth2.refresh_with_rotation(&rt1, "test_client", "test_secret").await.unwrap();
+        let rt2 = rotated.refresh_token.unwrap();
+        assert_ne!(rt1, rt2);
+
+        // Replaying the used token revokes the whole family | 重放已使用的令牌会撤销整个家族
+        let result = oauth2.refresh_with_rotation(&rt1, "test_client", "test_secret").await;
+        assert!(matches!(result, Err(SaTokenError::RefreshTokenReused)));
+        assert!(oauth2.verify_access_token(&rotated.access_token).await.is_err());
+        assert!(oauth2.verify_access_token(&token.access_token).await.is_err());
+        assert!(oauth2.refresh_with_rotation(&rt2, "test_client", "test_secret").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorization_endpoint() {
+        let oauth2 = OAuth2Manager::new(Arc::new(MemoryStorage::new()));
+        oauth2.register_client(&OAuth2Client {
+            client_id: "web".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uris: vec!["https://app/cb".to_string()],
+            grant_types: vec!["authorization_code".to_string()],
+            scope: vec!["read".to_string()],
+        }).await.unwrap();
+        let request = OAuth2AuthorizeRequest {
+            response_type: "code".to_string(),
+            client_id: "web".to_string(),
+            redirect_uri: "https://app/cb".to_string(),
+            scope: Some("read".to_string()),
+            state: Some("s 1".to_string()),
+            ..Default::default()
+        };
+        let ticket = |html: &str| html.split(r#"name="ticket" value=""#).nth(1).unwrap().split('"').next().unwrap().to_string();
+
+        // Unknown redirect URIs are never redirected to
+        let bad = OAuth2AuthorizeRequest { redirect_uri: "https://evil/cb".to_string(), ..request.clone() };
+        assert!(matches!(oauth2.authorize_request("alice", &bad, "/authorize", None).await,
+            Err(SaTokenError::OAuth2RedirectUriMismatch)));
+        let implicit = OAuth2AuthorizeRequest { response_type: "token".to_string(), ..request.clone() };
+        assert_eq!(
+            oauth2.authorize_request("alice", &implicit, "/authorize", None).await.unwrap(),
+            OAuth2AuthorizeResponse::Redirect(
+                "https://app/cb?error=unsupported_response_type&error_description=Unsupported%20response%20type%3A%20%27token%27&state=s%201".to_string()
+            )
+        );
+
+        // The first request shows the consent page carrying a one-time ticket
+        let OAuth2AuthorizeResponse::Consent(html) = oauth2.authorize_request("alice", &request, "/authorize", None).await.unwrap() else {
+            panic!("consent page expected");
+        };
+        let decision = OAuth2ConsentDecision { ticket: ticket(&html), decision: "approve".to_string() };
+        assert!(matches!(oauth2.authorize_decision("mallory", &decision).await,
+            Err(SaTokenError::OAuth2ConsentTicketInvalid)));
+        let OAuth2AuthorizeResponse::Redirect(location) = oauth2.authorize_decision("alice", &decision).await.unwrap() else {
+            panic!("redirect expected");
+        };
+        assert!(location.starts_with("https://app/cb?code=") && location.ends_with("&state=s%201"));
+        assert!(matches!(oauth2.authorize_decision("alice", &decision).await,
+            Err(SaTokenError::OAuth2ConsentTicketInvalid)));
+
+        // The client authenticates at the token endpoint with HTTP Basic
+        let code = location.split("code=").nth(1).unwrap().split('&').next().unwrap();
+        let token = oauth2.token_endpoint_request(Some("Basic d2ViOnNlY3JldA=="), &OAuth2TokenRequest {
+            grant_type: "authorization_code".to_string(),
+            code: Some(code.to_string()),
+            redirect_uri: Some("https://app/cb".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(oauth2.verify_access_token(&token.access_token).await.unwrap().user_id, "alice");
+
+        // Consent is remembered, so the next request redirects right away
+        assert!(matches!(oauth2.authorize_request("alice", &request, "/authorize", None).await.unwrap(),
+            OAuth2AuthorizeResponse::Redirect(location) if location.contains("code=")));
+
+        // Denying redirects with access_denied and records nothing
+        let OAuth2AuthorizeResponse::Consent(html) = oauth2.authorize_request("bob", &request, "/authorize", None).await.unwrap() else {
+            panic!("consent page expected");
+        };
+        let denied = OAuth2ConsentDecision { ticket: ticket(&html), decision: "deny".to_string() };
+        assert!(matches!(oauth2.authorize_decision("bob", &denied).await.unwrap(),
+            OAuth2AuthorizeResponse::Redirect(location) if location.starts_with("https://app/cb?error=access_denied")));
+        assert!(oauth2.consents().requires_consent("bob", "web", &["read".to_string()]).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_token_exchange() {
+        let oauth2 = OAuth2Manager::new(Arc::new(MemoryStorage::new()));
+        let client = |id: &str, grant_types: &[&str]| OAuth2Client {
+            client_id: id.to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uris: vec!["https://app/cb".to_string()],
+            grant_types: grant_types.iter().map(|g| g.to_string()).collect(),
+            scope: vec!["read".to_string(), "write".to_string()],
+        };
+        oauth2.register_client(&client("web", &["authorization_code"])).await.unwrap();
+        oauth2.register_client(&client("gateway", &[TOKEN_EXCHANGE_GRANT_TYPE])).await.unwrap();
+        oauth2.register_client(&client("svc", &["client_credentials"])).await.unwrap();
+
+        let code = oauth2.generate_authorization_code(
+            "web".to_string(), "alice".to_string(), "https://app/cb".to_string(),
+            vec!["read".to_string(), "write".to_string()],
+        );
+        oauth2.store_authorization_code(&code).await.unwrap();
+        let user_token = oauth2.exchange_code_for_token(&code.code, "web", "secret", "https://app/cb").await.unwrap();
+
+        // The gateway narrows the user's token for the orders service
+        let read = ["read".to_string()];
+        let orders = ["orders".to_string()];
+        let token = oauth2.exchange_token("gateway", "secret", &user_token.access_token, None, Some(&read), &orders)
+            .await.unwrap();
+        assert_eq!(token.issued_token_type.as_deref(), Some(ACCESS_TOKEN_TYPE));
+        assert!(token.refresh_token.is_none());
+        let info = oauth2.verify_access_token(&token.access_token).await.unwrap();
+        assert_eq!((info.user_id.as_str(), info.client_id.as_str()), ("alice", "gateway"));
+        assert_eq!(info.scope, read);
+        assert!(info.allows_audience("orders") && !info.allows_audience("billing"));
+        assert_eq!(info.act, Some(OAuth2Actor { sub: "gateway".to_string(), act: None }));
+        let introspection = oauth2.introspect(&token.access_token).await.unwrap();
+        assert_eq!(introspection.extra["aud"], serde_json::json!(["orders"]));
+        assert_eq!(introspection.extra["act"], serde_json::json!({ "sub": "gateway" }));
+
+        // Exchanged tokens can only narrow further, and nest the previous actor
+        assert!(matches!(
+            oauth2.exchange_token("gateway", "secret", &token.access_token, None, Some(&["write".to_string()]), &[]).await,
+            Err(SaTokenError::OAuth2ScopeExceedsGrant)
+        ));
+        assert!(matches!(
+            oauth2.exchange_token("gateway", "secret", &token.access_token, None, None, &["billing".to_string()]).await,
+            Err(SaTokenError::OAuth2InvalidTarget(aud)) if aud == "billing"
+        ));
+        let svc_token = oauth2.client_credentials_grant("svc", "secret", None).await.unwrap();
+        let chained = oauth2.token_request(&OAuth2TokenRequest {
+            grant_type: TOKEN_EXCHANGE_GRANT_TYPE.to_string(),
+            client_id: "gateway".to_string(),
+            client_secret: Some("secret".to_string()),
+            subject_token: Some(token.access_token.clone()),
+            subject_token_type: Some(ACCESS_TOKEN_TYPE.to_string()),
+            actor_token: Some(svc_token.access_token.clone()),
+            actor_token_type: Some(ACCESS_TOKEN_TYPE.to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        let info = oauth2.verify_access_token(&chained.access_token).await.unwrap();
+        assert_eq!(info.audience, orders);
+        assert_eq!(info.act.unwrap(), OAuth2Actor {
+            sub: "svc".to_string(),
+            act: Some(Box::new(OAuth2Actor { sub: "gateway".to_string(), act: None })),
+        });
+
+        assert!(matches!(
+            oauth2.exchange_token("gateway", "secret", "at_unknown", None, None, &[]).await,
+            Err(SaTokenError::OAuth2InvalidExchangeToken(name)) if name == "subject_token"
+        ));
+        assert!(matches!(
+            oauth2.exchange_token("web", "secret", &user_token.access_token, None, None, &[]).await,
+            Err(SaTokenError::OAuth2UnauthorizedClient)
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+// Author: 金书记
+//
+//! Off-session Tokens | 离线令牌
+//!
+//! Mint tokens on behalf of a user for background work (webhooks, nightly sync)
+//! based on an explicit consent record.
+//! 基于用户的显式授权记录，为后台任务（Webhook、夜间同步等）代用户签发令牌。
+//!
+//! ## Workflow | 工作流程
+//!
+//! ```text
+//! 1. User grants consent to a job / integration
+//!    用户授权某个后台任务 / 集成
+//!    OffSessionManager.grant_consent(login_id, client_id, scopes, ttl)
+//!    └─→ ConsentStore (oauth2:consent:{login_id}:{client_id})
+//!    ↓
+//! 2. Admin side mints an off-session token
+//!    管理端签发离线令牌
+//!    OffSessionManager.mint_token(login_id, client_id, scopes, timeout)
+//!    ├─→ Requires a valid consent covering the scopes | 需要覆盖所需 scope 的有效授权
+//!    └─→ login_type = "off_session" (separate namespace) | 独立的账号类型命名空间
+//!    ↓
+//! 3. Background job calls APIs with the token
+//!    后台任务携带令牌调用接口
+//!    OffSessionManager.check_scope(token, scope)
+//!    ↓
+//! 4. User revokes consent
+//!    用户撤销授权
+//!    OffSessionManager.revoke_consent(login_id, client_id)
+//!    └─→ All tokens minted for that client are logged out | 该客户端的所有令牌被注销
+//! ```
+//!
+//! Off-session tokens are not tied to an interactive session: logging the user out
+//! (default account type) does not revoke them, and they cannot open WebSocket connections,
+//! so they never show up in online user lists.
+//! 离线令牌不依附于交互式会话：用户登出（默认账号类型）不会注销它们，
+//! 并且它们不能建立 WebSocket 连接，因此不会出现在在线用户统计中。
+//!
+//! Consents live in the same `ConsentStore` as OAuth2 grants, so a user lists and revokes
+//! them in one place, and a job registered as an OAuth2 client shares a single grant.
+//! 授权记录与 OAuth2 授权共用 `ConsentStore`，用户可以在一处查看和撤销，
+//! 注册为 OAuth2 客户端的后台任务也只有一份授权。
+
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use crate::consent::{ConsentStore, OAuth2Grant};
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::manager::SaTokenManager;
+use crate::token::{TokenInfo, TokenValue};
+
+/// 离线令牌使用的账号类型 | Account type used by off-session tokens
+pub const OFF_SESSION_LOGIN_TYPE: &str = "off_session";
+
+/// 用户授权记录，即 `ConsentStore` 中的授权 | User consent record, a grant in the `ConsentStore`
+pub type ConsentRecord = OAuth2Grant;
+
+/// 离线令牌管理器 | Off-session token manager
+///
+/// 管理用户授权记录，并据此签发、校验和注销离线令牌
+/// Manages consent records and mints, checks and revokes off-session tokens based on them
+pub struct OffSessionManager {
+    manager: Arc<SaTokenManager>,
+    consents: ConsentStore,
+}
+
+impl OffSessionManager {
+    /// 创建离线令牌管理器 | Create an off-session token manager
+    pub fn new(manager: Arc<SaTokenManager>) -> Self {
+        let consents = ConsentStore::new(manager.storage.clone());
+        Self { manager, consents }
+    }
+
+    /// 获取授权同意存储 | Get the consent store
+    pub fn consents(&self) -> &ConsentStore {
+        &self.consents
+    }
+
+    /// 记录用户授权 | Record user consent
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 授权用户 ID | Consenting user ID
+    /// * `client_id` - 后台任务 / 集成 ID | Job / integration ID
+    /// * `scopes` - 授权的 scope | Granted scopes
+    /// * `ttl` - 授权有效期（秒），None 表示长期有效 | Consent validity in seconds, None for no expiration
+    pub async fn grant_consent(
+        &self,
+        login_id: &str,
+        client_id: &str,
+        scopes: Vec<String>,
+        ttl: Option<i64>,
+    ) -> SaTokenResult<ConsentRecord> {
+        self.consents.replace(login_id, client_id, scopes, ttl).await
+    }
+
+    /// 获取有效的授权记录 | Get a valid consent record
+    pub async fn get_consent(&self, login_id: &str, client_id: &str) -> SaTokenResult<Option<ConsentRecord>> {
+        self.consents.get(login_id, client_id).await
+    }
+
+    /// 列出用户的所有有效授权 | List all valid consents of a user
+    pub async fn list_consents(&self, login_id: &str) -> SaTokenResult<Vec<ConsentRecord>> {
+        self.consents.list(login_id).await
+    }
+
+    /// 撤销授权，并注销为该客户端签发的所有离线令牌
+    /// Revoke consent and log out every off-session token minted for the client
+    pub async fn revoke_consent(&self, login_id: &str, client_id: &str) -> SaTokenResult<()> {
+        self.consents.revoke(login_id, client_id).await?;
+
+        let token_prefix = "sa:token:";
+        if let Ok(keys) = self.manager.storage.keys(&format!("{}*", token_prefix)).await {
+            for key in keys {
+                if let Ok(Some(value)) = self.manager.storage.get(&key).await
+                    && let Ok(token_info) = serde_json::from_str::<TokenInfo>(&value)
+                    && token_info.login_id == login_id
+                    && token_info.is_off_session()
+                    && token_info.device.as_deref() == Some(client_id) {
+                    let token = TokenValue::new(&key[token_prefix.len()..]);
+                    let _ = self.manager.logout(&token).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 代用户签发离线令牌 | Mint an off-session token on behalf of the user
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 用户 ID | User ID
+    /// * `client_id` - 后台任务 / 集成 ID | Job / integration ID
+    /// * `scopes` - 令牌可使用的 scope，必须被授权覆盖 | Scopes of the token, must be covered by the consent
+    /// * `timeout` - 令牌有效期（秒）| Token validity in seconds
+    ///
+    /// # 错误 | Errors
+    /// * `ConsentRequired` - 没有有效的授权记录 | No valid consent record
+    /// * `ScopeNotConsented` - 请求的 scope 未被授权 | A requested scope is not consented
+    pub async fn mint_token(
+        &self,
+        login_id: &str,
+        client_id: &str,
+        scopes: Vec<String>,
+        timeout: i64,
+    ) -> SaTokenResult<TokenValue> {
+        let consent = self.get_consent(login_id, client_id).await?
+            .ok_or(SaTokenError::ConsentRequired)?;
+
+        if let Some(scope) = scopes.iter().find(|s| !consent.scopes.contains(s)) {
+            return Err(SaTokenError::ScopeNotConsented(scope.clone()));
+        }
+
+        // 令牌不能比授权活得更久 | The token must not outlive the consent
+        let mut expire_time = Utc::now() + Duration::seconds(timeout);
+        if let Some(consent_expire) = consent.expires_at {
+            expire_time = expire_time.min(consent_expire);
+        }
+
+        let mut token_info = TokenInfo::new(TokenValue::new(""), login_id);
+        token_info.login_type = OFF_SESSION_LOGIN_TYPE.to_string();
+        token_info.device = Some(client_id.to_string());
+        token_info.expire_time = Some(expire_time);
+        token_info.extra_data = Some(json!({
+            "off_session": true,
+            "client_id": client_id,
+            "scopes": scopes,
+        }));
+
+        self.manager.login_with_token_info(token_info).await
+    }
+
+    /// 校验离线令牌是否拥有指定 scope | Check that an off-session token carries the scope
+    pub async fn check_scope(&self, token: &TokenValue, scope: &str) -> SaTokenResult<()> {
+        let token_info = self.manager.get_token_info(token).await?;
+        if !token_info.is_off_session() {
+            return Err(SaTokenError::InvalidToken("not an off-session token".to_string()));
+        }
+
+        let granted = token_info.extra_data.as_ref()
+            .and_then(|extra| extra["scopes"].as_array())
+            .is_some_and(|scopes| scopes.iter().any(|s| s.as_str() == Some(scope)));
+
+        if granted {
+            Ok(())
+        } else {
+            Err(SaTokenError::ScopeNotConsented(scope.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn create_manager() -> (Arc<SaTokenManager>, OffSessionManager) {
+        let manager = Arc::new(SaTokenManager::new(
+            Arc::new(MemoryStorage::new()),
+            SaTokenConfig::default(),
+        ));
+        (manager.clone(), OffSessionManager::new(manager))
+    }
+
+    fn scopes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_mint_requires_consent() {
+        let (_, off_session) = create_manager();
+
+        let result = off_session.mint_token("user_1", "nightly-sync", scopes(&["orders:read"]), 3600).await;
+        assert!(matches!(result, Err(SaTokenError::ConsentRequired)));
+    }
+
+    #[tokio::test]
+    async fn test_mint_and_check_scope() {
+        let (manager, off_session) = create_manager();
+        off_session.grant_consent("user_1", "nightly-sync", scopes(&["orders:read", "orders:write"]), None).await.unwrap();
+
+        let result = off_session.mint_token("user_1", "nightly-sync", scopes(&["admin:all"]), 3600).await;
+        assert!(matches!(result, Err(SaTokenError::ScopeNotConsented(_))));
+
+        let token = off_session.mint_token("user_1", "nightly-sync", scopes(&["orders:read"]), 3600).await.unwrap();
+        let token_info = manager.get_token_info(&token).await.unwrap();
+        assert!(token_info.is_off_session());
+
+        assert!(off_session.check_scope(&token, "orders:read").await.is_ok());
+        assert!(off_session.check_scope(&token, "orders:write").await.is_err());
+
+        // 用户登出交互式会话不影响离线令牌 | Interactive logout keeps off-session tokens
+        manager.logout_by_login_id("user_1").await.unwrap();
+        assert!(manager.is_valid(&token).await);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_consent_logs_out_tokens() {
+        let (manager, off_session) = create_manager();
+        off_session.grant_consent("user_1", "webhook", scopes(&["events:read"]), None).await.unwrap();
+        let token = off_session.mint_token("user_1", "webhook", scopes(&["events:read"]), 3600).await.unwrap();
+
+        assert_eq!(off_session.list_consents("user_1").await.unwrap().len(), 1);
+        assert_eq!(off_session.consents().list("user_1").await.unwrap()[0].client_id, "webhook");
+
+        off_session.revoke_consent("user_1", "webhook").await.unwrap();
+        assert!(!manager.is_valid(&token).await);
+        assert!(off_session.get_consent("user_1", "webhook").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,426 @@
+// Author: 金书记
+//
+//! OpenID Connect 提供方 | OpenID Connect Provider
+//!
+//! 在 `OAuth2Manager` 之上实现基础的 OpenID Connect：授权码请求的 scope 含 `openid` 时，
+//! 令牌响应额外携带签名的 `id_token`；同时提供 userinfo 声明与
+//! `/.well-known/openid-configuration` 发现文档。
+//! A basic OpenID Connect layer on top of `OAuth2Manager`: when an authorization code request
+//! includes the `openid` scope, the token response also carries a signed `id_token`. UserInfo
+//! claims and the `/.well-known/openid-configuration` discovery document are provided as well.
+//!
+//! ```text
+//! GET  /oauth2/authorize?scope=openid profile&nonce=n-0S6
+//!   └─→ generate_authorization_code(..).with_nonce("n-0S6")
+//! POST /oauth2/token
+//!   └─→ { access_token, refresh_token, id_token }
+//!         id_token = JWT { iss, sub, aud, exp, iat, auth_time, nonce, at_hash }
+//! GET  /oauth2/userinfo   (Bearer access_token)
+//!   └─→ { sub, ...UserInfoProvider::claims }
+//! ```
+//!
+//! ```rust,ignore
+//! let jwt = JwtManager::from_pem(JwtAlgorithm::RS256, Some(private_pem), Some(public_pem))?.set_kid("key-1");
+//! let oauth2 = OAuth2Manager::new(storage)
+//!     .with_oidc(OidcProvider::new("https://auth.example.com", jwt));
+//! ```
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::oauth2::{DEVICE_CODE_GRANT_TYPE, TOKEN_EXCHANGE_GRANT_TYPE};
+use crate::token::jwt::{JwtAlgorithm, JwtManager, JwtValidationOptions};
+
+/// 触发 OpenID Connect 的 scope | Scope that turns a request into an OpenID Connect request
+pub const OPENID_SCOPE: &str = "openid";
+
+/// ID Token 声明 | ID token claims
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    /// 签发者 | Issuer
+    pub iss: String,
+
+    /// 用户 ID | Subject (user ID)
+    pub sub: String,
+
+    /// 客户端 ID | Audience (client ID)
+    pub aud: String,
+
+    /// 过期时间（Unix 时间戳）| Expiration time (Unix timestamp)
+    pub exp: i64,
+
+    /// 签发时间（Unix 时间戳）| Issued at (Unix timestamp)
+    pub iat: i64,
+
+    /// 用户授权时间（Unix 时间戳）| Time the user authorized the request (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+
+    /// 授权请求携带的 nonce | Nonce sent with the authorization request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+
+    /// access_token 哈希 | Access token hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_hash: Option<String>,
+}
+
+/// 用户信息提供者 | UserInfo claims provider
+///
+/// 为 userinfo 端点提供 `name`、`email` 等用户声明，可按 scope 过滤。
+/// Supplies user claims such as `name` or `email` to the userinfo endpoint, optionally filtered by scope.
+#[async_trait]
+pub trait UserInfoProvider: Send + Sync {
+    /// 返回用户声明，`sub` 由提供方填写 | Return the user's claims; `sub` is filled in by the provider
+    ///
+    /// # 参数 | Parameters
+    /// * `user_id` - 用户 ID | User ID
+    /// * `scope` - access_token 的 scope | Scopes of the access token
+    async fn claims(&self, user_id: &str, scope: &[String]) -> SaTokenResult<Map<String, Value>>;
+}
+
+/// 端点地址，相对路径会拼接在 issuer 之后 | Endpoint locations; relative paths are appended to the issuer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OidcEndpoints {
+    /// 授权端点 | Authorization endpoint
+    pub authorization: String,
+
+    /// 令牌端点 | Token endpoint
+    pub token: String,
+
+    /// 用户信息端点 | UserInfo endpoint
+    pub userinfo: String,
+
+    /// 公钥集地址 | JWKS document
+    pub jwks: String,
+
+    /// 内省端点 | Introspection endpoint
+    pub introspection: Option<String>,
+
+    /// 撤销端点 | Revocation endpoint
+    pub revocation: Option<String>,
+
+    /// 设备授权端点 | Device authorization endpoint
+    pub device_authorization: Option<String>,
+}
+
+impl Default for OidcEndpoints {
+    fn default() -> Self {
+        Self {
+            authorization: "/oauth2/authorize".to_string(),
+            token: "/oauth2/token".to_string(),
+            userinfo: "/oauth2/userinfo".to_string(),
+            jwks: "/.well-known/jwks.json".to_string(),
+            introspection: Some("/oauth2/introspect".to_string()),
+            revocation: Some("/oauth2/revoke".to_string()),
+            device_authorization: None,
+        }
+    }
+}
+
+/// OpenID Provider 发现文档 | OpenID Provider discovery document
+///
+/// 由 `/.well-known/openid-configuration` 返回 | Served at `/.well-known/openid-configuration`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OidcDiscovery {
+    /// 签发者 | Issuer
+    pub issuer: String,
+
+    /// 授权端点 | Authorization endpoint
+    pub authorization_endpoint: String,
+
+    /// 令牌端点 | Token endpoint
+    pub token_endpoint: String,
+
+    /// 用户信息端点 | UserInfo endpoint
+    pub userinfo_endpoint: String,
+
+    /// 公钥集地址 | JWKS URI
+    pub jwks_uri: String,
+
+    /// 内省端点 | Introspection endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introspection_endpoint: Option<String>,
+
+    /// 撤销端点 | Revocation endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revocation_endpoint: Option<String>,
+
+    /// 设备授权端点 | Device authorization endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_authorization_endpoint: Option<String>,
+
+    /// 支持的 scope | Supported scopes
+    pub scopes_supported: Vec<String>,
+
+    /// 支持的 response_type | Supported response types
+    pub response_types_supported: Vec<String>,
+
+    /// 支持的授权类型 | Supported grant types
+    pub grant_types_supported: Vec<String>,
+
+    /// 支持的 subject 类型 | Supported subject types
+    pub subject_types_supported: Vec<String>,
+
+    /// ID Token 签名算法 | ID token signing algorithms
+    pub id_token_signing_alg_values_supported: Vec<String>,
+
+    /// 令牌端点的客户端认证方式 | Client authentication methods of the token endpoint
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+
+    /// 支持的 PKCE 方法 | Supported PKCE methods
+    pub code_challenge_methods_supported: Vec<String>,
+
+    /// 支持的声明 | Supported claims
+    pub claims_supported: Vec<String>,
+}
+
+/// OpenID Connect 提供方 | OpenID Connect provider
+///
+/// 通过 `OAuth2Manager::with_oidc` 启用 | Enabled with `OAuth2Manager::with_oidc`
+#[derive(Clone)]
+pub struct OidcProvider {
+    issuer: String,
+    jwt: JwtManager,
+    id_token_ttl: i64,
+    endpoints: OidcEndpoints,
+    scopes_supported: Vec<String>,
+    userinfo_provider: Option<Arc<dyn UserInfoProvider>>,
+}
+
+impl OidcProvider {
+    /// 创建提供方，ID Token 默认有效期 3600 秒 | Create a provider; ID tokens last 3600 seconds by default
+    ///
+    /// # 参数 | Parameters
+    /// * `issuer` - 签发者 URL，如 `https://auth.example.com` | Issuer URL, e.g. `https://auth.example.com`
+    /// * `jwt` - 签名 ID Token 的 JWT 管理器 | JWT manager signing the ID tokens
+    pub fn new(issuer: impl Into<String>, jwt: JwtManager) -> Self {
+        Self {
+            issuer: issuer.into().trim_end_matches('/').to_string(),
+            jwt,
+            id_token_ttl: 3600,
+            endpoints: OidcEndpoints::default(),
+            scopes_supported: vec![OPENID_SCOPE.to_string(), "profile".to_string(), "email".to_string()],
+            userinfo_provider: None,
+        }
+    }
+
+    /// 设置 ID Token 有效期（秒）| Set the ID token lifetime in seconds
+    pub fn with_id_token_ttl(mut self, seconds: i64) -> Self {
+        self.id_token_ttl = seconds;
+        self
+    }
+
+    /// 设置发现文档中的端点地址 | Set the endpoint locations in the discovery document
+    pub fn with_endpoints(mut self, endpoints: OidcEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// 设置发现文档中的 `scopes_supported` | Set `scopes_supported` of the discovery document
+    pub fn with_scopes_supported(mut self, scopes: Vec<String>) -> Self {
+        self.scopes_supported = scopes;
+        self
+    }
+
+    /// 设置用户信息提供者 | Set the UserInfo claims provider
+    pub fn with_userinfo_provider(mut self, provider: Arc<dyn UserInfoProvider>) -> Self {
+        self.userinfo_provider = Some(provider);
+        self
+    }
+
+    /// 签发者 | Issuer
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// 签发 ID Token | Issue an ID token
+    ///
+    /// # 参数 | Parameters
+    /// * `client_id` - 客户端 ID（`aud`）| Client ID (`aud`)
+    /// * `user_id` - 用户 ID（`sub`）| User ID (`sub`)
+    /// * `nonce` - 授权请求携带的 nonce | Nonce sent with the authorization request
+    /// * `auth_time` - 用户授权时间 | Time the user authorized the request
+    /// * `access_token` - 同时签发的 access_token，用于计算 `at_hash` | Access token issued alongside, hashed into `at_hash`
+    pub fn issue_id_token(
+        &self,
+        client_id: &str,
+        user_id: &str,
+        nonce: Option<&str>,
+        auth_time: Option<DateTime<Utc>>,
+        access_token: Option<&str>,
+    ) -> SaTokenResult<String> {
+        let now = Utc::now();
+        let claims = IdTokenClaims {
+            iss: self.issuer.clone(),
+            sub: user_id.to_string(),
+            aud: client_id.to_string(),
+            exp: (now + Duration::seconds(self.id_token_ttl)).timestamp(),
+            iat: now.timestamp(),
+            auth_time: auth_time.map(|t| t.timestamp()),
+            nonce: nonce.map(str::to_string),
+            at_hash: access_token.map(|token| at_hash(self.jwt.algorithm(), token)),
+        };
+        self.jwt.sign(&claims)
+    }
+
+    /// 验证 ID Token（依赖方使用）| Verify an ID token (relying party side)
+    ///
+    /// 校验签名、`iss`、`aud` 与过期时间；传入 `nonce` 时还会校验 nonce。
+    /// Checks the signature, `iss`, `aud` and expiry; also checks the nonce when one is given.
+    ///
+    /// # 参数 | Parameters
+    /// * `id_token` - ID Token | ID token
+    /// * `client_id` - 期望的 `aud` | Expected `aud`
+    /// * `nonce` - 授权请求中发送的 nonce | Nonce sent with the authorization request
+    ///
+    /// # 返回 | Returns
+    /// * `Err(OidcNonceMismatch)` - nonce 不一致 | The nonce does not match
+    pub fn verify_id_token(&self, id_token: &str, client_id: &str, nonce: Option<&str>) -> SaTokenResult<IdTokenClaims> {
+        let options = JwtValidationOptions::new().issuer(&self.issuer).audience([client_id]);
+        let claims: IdTokenClaims = self.jwt.clone().set_validation_options(options).validate_as(id_token)?;
+        if let Some(expected) = nonce
+            && claims.nonce.as_deref() != Some(expected)
+        {
+            return Err(SaTokenError::OidcNonceMismatch);
+        }
+        Ok(claims)
+    }
+
+    /// 用户信息声明 | UserInfo claims
+    ///
+    /// # 参数 | Parameters
+    /// * `user_id` - 用户 ID | User ID
+    /// * `scope` - access_token 的 scope | Scopes of the access token
+    pub async fn userinfo(&self, user_id: &str, scope: &[String]) -> SaTokenResult<Map<String, Value>> {
+        let mut claims = match &self.userinfo_provider {
+            Some(provider) => provider.claims(user_id, scope).await?,
+            None => Map::new(),
+        };
+        claims.insert("sub".to_string(), Value::String(user_id.to_string()));
+        Ok(claims)
+    }
+
+    /// 生成发现文档 | Build the discovery document
+    pub fn discovery(&self) -> OidcDiscovery {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let mut grant_types = strings(&["authorization_code", "refresh_token", "client_credentials", TOKEN_EXCHANGE_GRANT_TYPE]);
+        if self.endpoints.device_authorization.is_some() {
+            grant_types.push(DEVICE_CODE_GRANT_TYPE.to_string());
+        }
+
+        OidcDiscovery {
+            issuer: self.issuer.clone(),
+            authorization_endpoint: self.url(&self.endpoints.authorization),
+            token_endpoint: self.url(&self.endpoints.token),
+            userinfo_endpoint: self.url(&self.endpoints.userinfo),
+            jwks_uri: self.url(&self.endpoints.jwks),
+            introspection_endpoint: self.endpoints.introspection.as_deref().map(|p| self.url(p)),
+            revocation_endpoint: self.endpoints.revocation.as_deref().map(|p| self.url(p)),
+            device_authorization_endpoint: self.endpoints.device_authorization.as_deref().map(|p| self.url(p)),
+            scopes_supported: self.scopes_supported.clone(),
+            response_types_supported: strings(&["code"]),
+            grant_types_supported: grant_types,
+            subject_types_supported: strings(&["public"]),
+            id_token_signing_alg_values_supported: vec![self.jwt.algorithm().as_str().to_string()],
+            token_endpoint_auth_methods_supported: strings(&["client_secret_basic", "client_secret_post", "none"]),
+            code_challenge_methods_supported: strings(&["plain", "S256"]),
+            claims_supported: strings(&["iss", "sub", "aud", "exp", "iat", "auth_time", "nonce", "at_hash"]),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        if path.starts_with("https://") || path.starts_with("http://") {
+            path.to_string()
+        } else {
+            format!("{}{}", self.issuer, path)
+        }
+    }
+}
+
+/// `at_hash`：access_token 摘要左半部分的 base64url，摘要算法与签名算法的位数一致
+/// `at_hash`: base64url of the left half of the access token digest, sized to the signing algorithm
+fn at_hash(algorithm: JwtAlgorithm, access_token: &str) -> String {
+    let digest = match algorithm {
+        JwtAlgorithm::HS384 | JwtAlgorithm::RS384 | JwtAlgorithm::ES384 => Sha384::digest(access_token).to_vec(),
+        JwtAlgorithm::HS512 | JwtAlgorithm::RS512 | JwtAlgorithm::EdDSA => Sha512::digest(access_token).to_vec(),
+        _ => Sha256::digest(access_token).to_vec(),
+    };
+    URL_SAFE_NO_PAD.encode(&digest[..digest.len() / 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oauth2::{OAuth2Client, OAuth2Manager};
+    use sa_token_storage_memory::MemoryStorage;
+
+    struct Profiles;
+
+    #[async_trait]
+    impl UserInfoProvider for Profiles {
+        async fn claims(&self, user_id: &str, scope: &[String]) -> SaTokenResult<Map<String, Value>> {
+            let mut claims = Map::new();
+            if scope.iter().any(|s| s == "profile") {
+                claims.insert("name".to_string(), Value::String(format!("User {}", user_id)));
+            }
+            // `sub` always comes from the access token
+            claims.insert("sub".to_string(), Value::String("spoofed".to_string()));
+            Ok(claims)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openid_connect_flow() {
+        let provider = OidcProvider::new("https://auth.example.com/", JwtManager::new("oidc-secret"))
+            .with_userinfo_provider(Arc::new(Profiles));
+        let oauth2 = OAuth2Manager::new(Arc::new(MemoryStorage::new())).with_oidc(provider);
+        oauth2.register_client(&OAuth2Client {
+            client_id: "app".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uris: vec!["https://app/cb".to_string()],
+            grant_types: vec!["authorization_code".to_string()],
+            scope: vec!["openid".to_string(), "profile".to_string()],
+        }).await.unwrap();
+
+        let scope = vec!["openid".to_string(), "profile".to_string()];
+        let code = oauth2.generate_authorization_code("app".to_string(), "alice".to_string(), "https://app/cb".to_string(), scope)
+            .with_nonce("n-0S6_WzA2Mj");
+        oauth2.store_authorization_code(&code).await.unwrap();
+        let token = oauth2.exchange_code_for_token(&code.code, "app", "secret", "https://app/cb").await.unwrap();
+
+        let oidc = oauth2.oidc().unwrap();
+        let id_token = token.id_token.as_deref().unwrap();
+        let claims = oidc.verify_id_token(id_token, "app", Some("n-0S6_WzA2Mj")).unwrap();
+        assert_eq!(claims.iss, "https://auth.example.com");
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.auth_time, Some(code.created_at.timestamp()));
+        assert_eq!(claims.at_hash, Some(at_hash(JwtAlgorithm::HS256, &token.access_token)));
+        assert!(matches!(oidc.verify_id_token(id_token, "app", Some("other")), Err(SaTokenError::OidcNonceMismatch)));
+        assert!(oidc.verify_id_token(id_token, "other-app", None).is_err());
+
+        let userinfo = oauth2.userinfo(&token.access_token).await.unwrap();
+        assert_eq!(userinfo["sub"], "alice");
+        assert_eq!(userinfo["name"], "User alice");
+
+        // Plain OAuth2 requests get neither an ID token nor userinfo
+        let code = oauth2.generate_authorization_code("app".to_string(), "alice".to_string(), "https://app/cb".to_string(), vec!["profile".to_string()]);
+        oauth2.store_authorization_code(&code).await.unwrap();
+        let token = oauth2.exchange_code_for_token(&code.code, "app", "secret", "https://app/cb").await.unwrap();
+        assert!(token.id_token.is_none());
+        assert!(matches!(oauth2.userinfo(&token.access_token).await, Err(SaTokenError::OAuth2InsufficientScope(_))));
+        assert!(matches!(oauth2.userinfo("at_missing").await, Err(SaTokenError::OAuth2AccessTokenNotFound)));
+
+        let discovery = oidc.discovery();
+        assert_eq!(discovery.issuer, "https://auth.example.com");
+        assert_eq!(discovery.token_endpoint, "https://auth.example.com/oauth2/token");
+        assert_eq!(discovery.id_token_signing_alg_values_supported, ["HS256"]);
+        assert!(discovery.device_authorization_endpoint.is_none());
+    }
+}
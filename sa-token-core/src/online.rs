@@ -140,9 +140,13 @@
 //! - Custom: 用户自定义类型
 
 use crate::error::SaTokenError;
+use crate::token::namespaced_login_id;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 
@@ -232,6 +236,157 @@ pub trait MessagePusher: Send + Sync {
     /// * `login_id` - User login ID | 用户登录 ID
     /// * `message` - Message to push | 要推送的消息
     async fn push(&self, login_id: &str, message: PushMessage) -> Result<(), SaTokenError>;
+
+    /// Push a message to one device of a user
+    /// 向用户的某个设备推送消息
+    ///
+    /// The default implementation stores the device in the message metadata under
+    /// [`DEVICE_KEY`] and calls `push`; override it to deliver to the device's connections only.
+    /// 默认实现把设备以 [`DEVICE_KEY`] 为键写入消息元数据后调用 `push`；
+    /// 重写此方法可只投递到该设备的连接。
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID | 用户登录 ID
+    /// * `device` - Target device | 目标设备
+    /// * `message` - Message to push | 要推送的消息
+    async fn push_to_device(&self, login_id: &str, device: &str, mut message: PushMessage) -> Result<(), SaTokenError> {
+        message.metadata.insert(DEVICE_KEY.to_string(), device.to_string());
+        self.push(login_id, message).await
+    }
+}
+
+/// Device registered for logins without device info
+/// 未携带设备信息的登录所登记的设备
+pub const DEFAULT_DEVICE: &str = "default";
+
+/// Message metadata key holding the target device of a device push
+/// 设备推送消息中目标设备所在的元数据键
+pub const DEVICE_KEY: &str = "device";
+
+/// Metadata key holding the connection ID of a registered connection
+/// 已登记连接的连接 ID 所在的元数据键
+pub const CONNECTION_ID_KEY: &str = "connection_id";
+
+/// A connection removed because no heartbeat arrived within the silence period
+/// 因静默期内未收到心跳而被移除的连接
+#[derive(Debug, Clone)]
+pub struct TimedOutConnection {
+    /// Connection ID | 连接 ID
+    pub connection_id: String,
+    
+    /// Account type of the connection | 连接所属的账号类型
+    pub account_type: String,
+    
+    /// The removed online entry | 被移除的在线记录
+    pub user: OnlineUser,
+}
+
+/// Default window of `OnlineStats::recent_logins`
+/// `OnlineStats::recent_logins` 的默认统计窗口
+pub const DEFAULT_LOGIN_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Online statistics for an admin dashboard
+/// 管理后台监控面板使用的在线统计数据
+#[derive(Debug, Clone, Serialize)]
+pub struct OnlineStats {
+    /// Online users | 在线用户数
+    pub online_users: usize,
+    
+    /// Online sessions | 在线会话数
+    pub online_sessions: usize,
+    
+    /// Online sessions per device | 各设备的在线会话数
+    pub sessions_by_device: HashMap<String, usize>,
+    
+    /// Logins within the login window | 统计窗口内的登录次数
+    pub recent_logins: usize,
+    
+    /// Login window in seconds | 登录统计窗口（秒）
+    pub login_window_seconds: u64,
+    
+    /// Highest number of concurrent online users since start | 启动以来的最高同时在线用户数
+    pub peak_online_users: usize,
+}
+
+/// Metadata key holding the client IP of an online session, matched by [`OnlineFilter::with_ip`]
+/// 在线会话客户端 IP 所在的元数据键，由 [`OnlineFilter::with_ip`] 匹配
+pub const IP_KEY: &str = "ip";
+
+/// Filter of the online session list
+/// 在线会话列表的过滤条件
+///
+/// Unset conditions match every session.
+/// 未设置的条件匹配所有会话。
+#[derive(Debug, Clone, Default)]
+pub struct OnlineFilter {
+    /// Device identifier | 设备标识
+    pub device: Option<String>,
+    
+    /// Sessions connected at or after this time | 在此时间及之后连接的会话
+    pub connected_after: Option<DateTime<Utc>>,
+    
+    /// Sessions connected before this time | 在此时间之前连接的会话
+    pub connected_before: Option<DateTime<Utc>>,
+    
+    /// Client IP, read from the [`IP_KEY`] metadata | 客户端 IP，读取自 [`IP_KEY`] 元数据
+    pub ip: Option<String>,
+}
+
+impl OnlineFilter {
+    /// Create a filter matching every session
+    /// 创建匹配所有会话的过滤条件
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match sessions of a device
+    /// 匹配指定设备的会话
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// Match sessions connected in `[from, to)`
+    /// 匹配在 `[from, to)` 内连接的会话
+    pub fn with_connect_time_range(mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.connected_after = Some(from);
+        self.connected_before = Some(to);
+        self
+    }
+
+    /// Match sessions from a client IP
+    /// 匹配指定客户端 IP 的会话
+    pub fn with_ip(mut self, ip: impl Into<String>) -> Self {
+        self.ip = Some(ip.into());
+        self
+    }
+
+    fn matches(&self, user: &OnlineUser) -> bool {
+        self.device.as_ref().is_none_or(|device| &user.device == device)
+            && self.connected_after.is_none_or(|after| user.connect_time >= after)
+            && self.connected_before.is_none_or(|before| user.connect_time < before)
+            && self.ip.as_ref().is_none_or(|ip| user.metadata.get(IP_KEY) == Some(ip))
+    }
+}
+
+/// One page of online sessions
+/// 一页在线会话
+#[derive(Debug, Clone)]
+pub struct OnlinePage {
+    /// Sessions on this page, newest connection first | 本页会话，按连接时间倒序
+    pub items: Vec<OnlineUser>,
+    
+    /// Matching sessions across all pages | 所有页中匹配的会话数
+    pub total: usize,
+    
+    /// Distinct users among the matching sessions | 匹配会话中的不同用户数
+    pub total_users: usize,
+    
+    /// Page number, starting at 1 | 页码，从 1 开始
+    pub page: usize,
+    
+    /// Page size | 每页数量
+    pub page_size: usize,
 }
 
 /// Online user manager
@@ -239,6 +394,11 @@ pub trait MessagePusher: Send + Sync {
 ///
 /// Manages online users and handles real-time message pushing
 /// 管理在线用户并处理实时消息推送
+///
+/// Online entries are keyed by the login ID namespaced by account type
+/// (see [`namespaced_login_id`]); for the default account type this is the plain login ID.
+/// 在线索引的键为按账号类型命名空间化的登录 ID（见 [`namespaced_login_id`]），
+/// 默认账号类型下即为原始登录 ID。
 pub struct OnlineManager {
     /// Online users map: login_id -> Vec<OnlineUser>
     /// 在线用户映射: login_id -> Vec<OnlineUser>
@@ -248,6 +408,23 @@ pub struct OnlineManager {
     
     /// Registered message pushers | 已注册的消息推送器
     pushers: Arc<RwLock<Vec<Arc<dyn MessagePusher>>>>,
+    
+    /// Registered connections: connection_id -> (online key, account type)
+    /// 已登记的连接: connection_id -> (在线索引键, 账号类型)
+    connections: Arc<RwLock<HashMap<String, (String, String)>>>,
+    
+    /// Device registry from login device info: login_id -> (token -> device)
+    /// 来自登录设备信息的设备登记表: login_id -> (token -> 设备)
+    devices: Arc<RwLock<HashMap<String, HashMap<String, String>>>>,
+    
+    /// Login times within the login window, oldest first | 登录统计窗口内的登录时间，最早的在前
+    logins: Arc<RwLock<VecDeque<DateTime<Utc>>>>,
+    
+    /// Window of `OnlineStats::recent_logins` | `OnlineStats::recent_logins` 的统计窗口
+    login_window: Duration,
+    
+    /// Highest number of concurrent online users | 最高同时在线用户数
+    peak_users: Arc<AtomicUsize>,
 }
 
 impl OnlineManager {
@@ -262,9 +439,26 @@ impl OnlineManager {
         Self {
             online_users: Arc::new(RwLock::new(HashMap::new())),
             pushers: Arc::new(RwLock::new(Vec::new())),
+            connections: Arc::new(RwLock::new(HashMap::new())),
+            devices: Arc::new(RwLock::new(HashMap::new())),
+            logins: Arc::new(RwLock::new(VecDeque::new())),
+            login_window: DEFAULT_LOGIN_WINDOW,
+            peak_users: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Set the window counted by `OnlineStats::recent_logins`, 15 minutes by default
+    /// 设置 `OnlineStats::recent_logins` 的统计窗口，默认 15 分钟
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let manager = OnlineManager::new().with_login_window(Duration::from_secs(3600));
+    /// ```
+    pub fn with_login_window(mut self, window: Duration) -> Self {
+        self.login_window = window;
+        self
+    }
+
     /// Register a message pusher
     /// 注册消息推送器
     ///
@@ -300,10 +494,31 @@ impl OnlineManager {
     /// manager.mark_online(user).await;
     /// ```
     pub async fn mark_online(&self, user: OnlineUser) {
+        self.insert_online(user.login_id.clone(), user).await;
+    }
+
+    /// Mark a user of a specific account type as online
+    /// 标记指定账号类型的用户上线
+    ///
+    /// # Arguments | 参数
+    /// * `user` - Online user information | 在线用户信息
+    /// * `account_type` - Account type (e.g., "admin") | 账号类型（如 "admin"）
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// manager.mark_online_with_type(user, "admin").await;
+    /// assert!(manager.is_online(&namespaced_login_id("1001", "admin")).await);
+    /// ```
+    pub async fn mark_online_with_type(&self, user: OnlineUser, account_type: &str) {
+        let key = namespaced_login_id(&user.login_id, account_type);
+        self.insert_online(key, user).await;
+    }
+
+    /// Add an online entry and update the peak | 添加在线记录并更新峰值
+    async fn insert_online(&self, key: String, user: OnlineUser) {
         let mut users = self.online_users.write().await;
-        users.entry(user.login_id.clone())
-            .or_insert_with(Vec::new)
-            .push(user);
+        users.entry(key).or_default().push(user);
+        self.peak_users.fetch_max(users.len(), Ordering::Relaxed);
     }
 
     /// Mark a specific user session as offline
@@ -318,15 +533,47 @@ impl OnlineManager {
     /// manager.mark_offline("user123", "token123").await;
     /// ```
     pub async fn mark_offline(&self, login_id: &str, token: &str) {
-        let mut users = self.online_users.write().await;
-        if let Some(user_sessions) = users.get_mut(login_id) {
-            user_sessions.retain(|u| u.token != token);
-            if user_sessions.is_empty() {
-                users.remove(login_id);
+        {
+            let mut users = self.online_users.write().await;
+            if let Some(user_sessions) = users.get_mut(login_id) {
+                user_sessions.retain(|u| u.token != token);
+                if user_sessions.is_empty() {
+                    users.remove(login_id);
+                }
+            }
+        }
+        let mut devices = self.devices.write().await;
+        if let Some(tokens) = devices.get_mut(login_id) {
+            tokens.remove(token);
+            if tokens.is_empty() {
+                devices.remove(login_id);
             }
         }
     }
 
+    /// Replace the token of an online session, e.g. after the token was regenerated
+    /// 替换在线会话的 Token（如 Token 被重新生成后）
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID | 用户登录 ID
+    /// * `old_token` - Previous session token | 原会话 Token
+    /// * `new_token` - New session token | 新会话 Token
+    pub async fn replace_token(&self, login_id: &str, old_token: &str, new_token: &str) {
+        {
+            let mut users = self.online_users.write().await;
+            if let Some(user_sessions) = users.get_mut(login_id) {
+                user_sessions.iter_mut()
+                    .filter(|u| u.token == old_token)
+                    .for_each(|u| u.token = new_token.to_string());
+            }
+        }
+        let mut devices = self.devices.write().await;
+        if let Some(tokens) = devices.get_mut(login_id)
+            && let Some(device) = tokens.remove(old_token) {
+            tokens.insert(new_token.to_string(), device);
+        }
+    }
+
     /// Mark all sessions of a user as offline
     /// 标记用户的所有会话离线
     ///
@@ -338,8 +585,8 @@ impl OnlineManager {
     /// manager.mark_offline_all("user123").await;
     /// ```
     pub async fn mark_offline_all(&self, login_id: &str) {
-        let mut users = self.online_users.write().await;
-        users.remove(login_id);
+        self.online_users.write().await.remove(login_id);
+        self.devices.write().await.remove(login_id);
     }
 
     /// Check if a user is online
@@ -397,6 +644,52 @@ impl OnlineManager {
         users.keys().cloned().collect()
     }
 
+    /// List online sessions page by page, e.g. for an admin "currently online" table
+    /// 分页查询在线会话（如管理后台的"当前在线"列表）
+    ///
+    /// Only the sessions of the requested page are cloned.
+    /// 只克隆所请求页的会话。
+    ///
+    /// # Arguments | 参数
+    /// * `page` - Page number, starting at 1 (0 is treated as 1) | 页码，从 1 开始（0 按 1 处理）
+    /// * `page_size` - Sessions per page | 每页会话数
+    /// * `filter` - Session filter | 会话过滤条件
+    ///
+    /// # Returns | 返回值
+    /// The page, with totals over all matching sessions | 该页会话及所有匹配会话的总数
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let page = manager.list_online(1, 20, &OnlineFilter::new().with_device("ios")).await;
+    /// println!("{} sessions, {} users", page.total, page.total_users);
+    /// ```
+    pub async fn list_online(&self, page: usize, page_size: usize, filter: &OnlineFilter) -> OnlinePage {
+        let page = page.max(1);
+        let users = self.online_users.read().await;
+        let mut matched: Vec<&OnlineUser> = users.values()
+            .flatten()
+            .filter(|u| filter.matches(u))
+            .collect();
+        let total_users = users.values()
+            .filter(|sessions| sessions.iter().any(|u| filter.matches(u)))
+            .count();
+        matched.sort_by(|a, b| b.connect_time.cmp(&a.connect_time)
+            .then_with(|| a.login_id.cmp(&b.login_id))
+            .then_with(|| a.token.cmp(&b.token)));
+        let items = matched.iter()
+            .skip((page - 1).saturating_mul(page_size))
+            .take(page_size)
+            .map(|u| (*u).clone())
+            .collect();
+        OnlinePage {
+            items,
+            total: matched.len(),
+            total_users,
+            page,
+            page_size,
+        }
+    }
+
     /// Get all sessions for a specific user
     /// 获取特定用户的所有会话
     ///
@@ -439,6 +732,218 @@ impl OnlineManager {
         }
     }
 
+    /// Register a realtime connection whose liveness is tracked by heartbeats
+    /// 登记一个通过心跳跟踪存活状态的实时连接
+    ///
+    /// The user is marked online and stays online while `touch` is called within the silence
+    /// period passed to `sweep_silent_connections`. The connection ID is stored in the entry
+    /// metadata under [`CONNECTION_ID_KEY`].
+    /// 用户被标记上线，只要在 `sweep_silent_connections` 的静默期内调用 `touch` 就保持在线。
+    /// 连接 ID 以 [`CONNECTION_ID_KEY`] 为键存入在线记录的元数据。
+    ///
+    /// # Arguments | 参数
+    /// * `connection_id` - Unique connection ID | 唯一连接 ID
+    /// * `user` - Online user information | 在线用户信息
+    /// * `account_type` - Account type (e.g., "default", "admin") | 账号类型（如 "default"、"admin"）
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// manager.register_connection(&auth_info.session_id, user, "default").await;
+    /// ```
+    pub async fn register_connection(&self, connection_id: &str, mut user: OnlineUser, account_type: &str) {
+        let key = namespaced_login_id(&user.login_id, account_type);
+        user.last_activity = Utc::now();
+        user.metadata.insert(CONNECTION_ID_KEY.to_string(), connection_id.to_string());
+        self.connections.write().await
+            .insert(connection_id.to_string(), (key.clone(), account_type.to_string()));
+        self.insert_online(key, user).await;
+    }
+
+    /// Record a heartbeat of a registered connection
+    /// 记录已登记连接的一次心跳
+    ///
+    /// # Arguments | 参数
+    /// * `connection_id` - Connection ID | 连接 ID
+    ///
+    /// # Returns | 返回值
+    /// * `true` - Heartbeat recorded | 已记录心跳
+    /// * `false` - Unknown connection, e.g. already swept or marked offline; the connection should be closed
+    ///   | 未知连接（如已被清理或标记离线），应关闭该连接
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// if !manager.touch(&auth_info.session_id).await {
+    ///     socket.close().await?;
+    /// }
+    /// ```
+    pub async fn touch(&self, connection_id: &str) -> bool {
+        let Some((key, _)) = self.connections.read().await.get(connection_id).cloned() else {
+            return false;
+        };
+        let mut users = self.online_users.write().await;
+        let entry = users.get_mut(&key)
+            .and_then(|sessions| sessions.iter_mut().find(|u| is_connection(u, connection_id)));
+        match entry {
+            Some(user) => {
+                user.last_activity = Utc::now();
+                true
+            }
+            None => {
+                drop(users);
+                self.connections.write().await.remove(connection_id);
+                false
+            }
+        }
+    }
+
+    /// Unregister a connection, e.g. when the socket closes
+    /// 注销连接（如套接字关闭时）
+    ///
+    /// # Arguments | 参数
+    /// * `connection_id` - Connection ID | 连接 ID
+    pub async fn unregister_connection(&self, connection_id: &str) {
+        let Some((key, _)) = self.connections.write().await.remove(connection_id) else {
+            return;
+        };
+        let mut users = self.online_users.write().await;
+        if let Some(user_sessions) = users.get_mut(&key) {
+            user_sessions.retain(|u| !is_connection(u, connection_id));
+            if user_sessions.is_empty() {
+                users.remove(&key);
+            }
+        }
+    }
+
+    /// Remove registered connections without a heartbeat for longer than `silence`
+    /// 移除超过 `silence` 未收到心跳的已登记连接
+    ///
+    /// Users left without any entry are no longer online. Entries added with `mark_online`
+    /// are not affected.
+    /// 没有剩余记录的用户不再在线。通过 `mark_online` 添加的记录不受影响。
+    ///
+    /// # Arguments | 参数
+    /// * `silence` - Silence period | 静默期
+    ///
+    /// # Returns | 返回值
+    /// The removed connections | 被移除的连接
+    pub async fn sweep_silent_connections(&self, silence: Duration) -> Vec<TimedOutConnection> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(silence).unwrap_or(chrono::Duration::MAX);
+        let connections = self.connections.read().await.clone();
+        let mut timed_out = Vec::new();
+        {
+            let mut users = self.online_users.write().await;
+            users.retain(|_, user_sessions| {
+                user_sessions.retain(|u| {
+                    if u.last_activity >= cutoff {
+                        return true;
+                    }
+                    let Some((connection_id, (_, account_type))) = u.metadata.get(CONNECTION_ID_KEY)
+                        .and_then(|id| connections.get_key_value(id)) else {
+                        return true;
+                    };
+                    timed_out.push(TimedOutConnection {
+                        connection_id: connection_id.clone(),
+                        account_type: account_type.clone(),
+                        user: u.clone(),
+                    });
+                    false
+                });
+                !user_sessions.is_empty()
+            });
+        }
+        if !timed_out.is_empty() {
+            let mut connections = self.connections.write().await;
+            for connection in &timed_out {
+                connections.remove(&connection.connection_id);
+            }
+        }
+        timed_out
+    }
+
+    /// Register the device a token logged in with
+    /// 登记 Token 登录时使用的设备
+    ///
+    /// `SaTokenManager` calls this on login when an `OnlineManager` is configured;
+    /// the entry is removed by `mark_offline` / `mark_offline_all` on logout.
+    /// 配置了 `OnlineManager` 时，`SaTokenManager` 在登录时调用此方法；
+    /// 登出时由 `mark_offline` / `mark_offline_all` 移除。
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID (namespaced by account type) | 用户登录 ID（按账号类型命名空间化）
+    /// * `token` - Session token | 会话 Token
+    /// * `device` - Device identifier | 设备标识
+    pub async fn register_device(&self, login_id: &str, token: &str, device: &str) {
+        self.devices.write().await
+            .entry(login_id.to_string())
+            .or_default()
+            .insert(token.to_string(), device.to_string());
+        let now = Utc::now();
+        let mut logins = self.logins.write().await;
+        logins.push_back(now);
+        self.trim_logins(&mut logins, now);
+    }
+
+    /// Aggregate online statistics for a dashboard
+    /// 汇总在线统计数据（用于监控面板）
+    ///
+    /// Computed from the in-memory online list and counters; storage is not read.
+    /// 由内存中的在线列表与计数器计算，不读取存储。
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let stats = manager.stats().await;
+    /// println!("{} online, peak {}", stats.online_users, stats.peak_online_users);
+    /// ```
+    pub async fn stats(&self) -> OnlineStats {
+        let recent_logins = {
+            let mut logins = self.logins.write().await;
+            self.trim_logins(&mut logins, Utc::now());
+            logins.len()
+        };
+        let users = self.online_users.read().await;
+        let mut sessions_by_device = HashMap::new();
+        for user in users.values().flatten() {
+            *sessions_by_device.entry(user.device.clone()).or_insert(0) += 1;
+        }
+        OnlineStats {
+            online_users: users.len(),
+            online_sessions: sessions_by_device.values().sum(),
+            sessions_by_device,
+            recent_logins,
+            login_window_seconds: self.login_window.as_secs(),
+            peak_online_users: self.peak_users.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drop login times older than the login window | 移除早于统计窗口的登录时间
+    fn trim_logins(&self, logins: &mut VecDeque<DateTime<Utc>>, now: DateTime<Utc>) {
+        let cutoff = now - chrono::Duration::from_std(self.login_window).unwrap_or(chrono::Duration::MAX);
+        while logins.front().is_some_and(|time| *time < cutoff) {
+            logins.pop_front();
+        }
+    }
+
+    /// Get the devices of a user, from logins and online sessions
+    /// 获取用户的设备列表（来自登录与在线会话）
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID | 用户登录 ID
+    ///
+    /// # Returns | 返回值
+    /// Sorted, deduplicated device identifiers | 排序去重后的设备标识
+    pub async fn get_user_devices(&self, login_id: &str) -> Vec<String> {
+        let mut devices: Vec<String> = self.devices.read().await
+            .get(login_id)
+            .map(|tokens| tokens.values().cloned().collect())
+            .unwrap_or_default();
+        if let Some(user_sessions) = self.online_users.read().await.get(login_id) {
+            devices.extend(user_sessions.iter().map(|u| u.device.clone()));
+        }
+        devices.sort();
+        devices.dedup();
+        devices
+    }
+
     /// Push a text message to a specific user
     /// 向特定用户推送文本消息
     ///
@@ -527,6 +1032,47 @@ impl OnlineManager {
         Ok(())
     }
 
+    /// Push a message to one device of a user
+    /// 向用户的某个设备推送消息
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID | 用户登录 ID
+    /// * `device` - Target device | 目标设备
+    /// * `message` - Message to push | 要推送的消息
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// manager.push_to_device("user123", "ios", message).await?;
+    /// ```
+    pub async fn push_to_device(&self, login_id: &str, device: &str, message: PushMessage) -> Result<(), SaTokenError> {
+        let pushers = self.pushers.read().await;
+        for pusher in pushers.iter() {
+            pusher.push_to_device(login_id, device, message.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// Push a message to every device of a user except one, e.g. "signed in on another device"
+    /// 向用户除某个设备外的所有设备推送消息（如"已在其他设备登录"）
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID | 用户登录 ID
+    /// * `device` - Device to skip | 要跳过的设备
+    /// * `message` - Message to push | 要推送的消息
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// manager.push_to_all_except("user123", "web", message).await?;
+    /// ```
+    pub async fn push_to_all_except(&self, login_id: &str, device: &str, message: PushMessage) -> Result<(), SaTokenError> {
+        for target in self.get_user_devices(login_id).await {
+            if target != device {
+                self.push_to_device(login_id, &target, message.clone()).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Kick out a user and send notification
     /// 踢出用户并发送通知
     ///
@@ -539,22 +1085,45 @@ impl OnlineManager {
     /// manager.kick_out_notify("user123", "Duplicate login detected".to_string()).await?;
     /// ```
     pub async fn kick_out_notify(&self, login_id: &str, reason: String) -> Result<(), SaTokenError> {
-        // Create kick-out message | 创建踢出消息
-        let message = PushMessage {
-            message_id: uuid::Uuid::new_v4().to_string(),
-            content: reason,
-            message_type: MessageType::KickOut,
-            timestamp: Utc::now(),
-            metadata: HashMap::new(),
-        };
-
         // Push notification | 推送通知
-        self.push_message_to_user(login_id, message).await?;
+        self.push_message_to_user(login_id, kick_out_message(reason)).await?;
         
         // Mark user offline | 标记用户离线
         self.mark_offline_all(login_id).await;
         Ok(())
     }
+
+    /// Notify one device of a user that it has been kicked out
+    /// 通知用户的某个设备已被踢下线
+    ///
+    /// Only pushes the message; the sessions are marked offline when their tokens are logged out.
+    /// 只推送消息；会话在其 token 登出时标记离线。
+    ///
+    /// # Arguments | 参数
+    /// * `login_id` - User login ID | 用户登录 ID
+    /// * `device` - Target device | 目标设备
+    /// * `reason` - Kick-out reason | 踢出原因
+    pub async fn kick_out_device_notify(&self, login_id: &str, device: &str, reason: String) -> Result<(), SaTokenError> {
+        self.push_to_device(login_id, device, kick_out_message(reason)).await
+    }
+}
+
+/// Build a kick-out notification
+/// 构造踢出通知消息
+fn kick_out_message(reason: String) -> PushMessage {
+    PushMessage {
+        message_id: uuid::Uuid::new_v4().to_string(),
+        content: reason,
+        message_type: MessageType::KickOut,
+        timestamp: Utc::now(),
+        metadata: HashMap::new(),
+    }
+}
+
+/// Whether an online entry belongs to a registered connection
+/// 在线记录是否属于指定的已登记连接
+fn is_connection(user: &OnlineUser, connection_id: &str) -> bool {
+    user.metadata.get(CONNECTION_ID_KEY).is_some_and(|id| id == connection_id)
 }
 
 impl Default for OnlineManager {
@@ -718,6 +1287,29 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_account_type_namespace() {
+        let manager = OnlineManager::new();
+        
+        for account_type in ["default", "admin"] {
+            let user = OnlineUser {
+                login_id: "1001".to_string(),
+                token: format!("token-{}", account_type),
+                device: "web".to_string(),
+                connect_time: Utc::now(),
+                last_activity: Utc::now(),
+                metadata: HashMap::new(),
+            };
+            manager.mark_online_with_type(user, account_type).await;
+        }
+        
+        assert_eq!(manager.get_online_count().await, 2);
+        
+        manager.mark_offline_all("1001:admin").await;
+        assert!(!manager.is_online("1001:admin").await);
+        assert!(manager.is_online("1001").await);
+    }
+
     #[tokio::test]
     async fn test_kick_out_notify() {
         let manager = OnlineManager::new();
@@ -745,4 +1337,172 @@ mod tests {
         assert_eq!(messages.len(), 1);
         assert_eq!(messages[0].message_type, MessageType::KickOut);
     }
+
+    #[tokio::test]
+    async fn test_connection_heartbeat() {
+        let manager = OnlineManager::new();
+        let user = |login_id: &str, token: &str| OnlineUser {
+            login_id: login_id.to_string(),
+            token: token.to_string(),
+            device: "web".to_string(),
+            connect_time: Utc::now(),
+            last_activity: Utc::now(),
+            metadata: HashMap::new(),
+        };
+        
+        manager.register_connection("conn-1", user("user5", "token5"), "default").await;
+        manager.register_connection("conn-2", user("user5", "token5"), "default").await;
+        manager.register_connection("conn-3", user("1001", "token6"), "admin").await;
+        manager.mark_online(user("user6", "token7")).await;
+        
+        tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+        assert!(manager.touch("conn-1").await);
+        let timed_out = manager.sweep_silent_connections(std::time::Duration::from_millis(20)).await;
+        let mut ids: Vec<_> = timed_out.iter().map(|c| c.connection_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["conn-2", "conn-3"]);
+        assert_eq!(timed_out.iter().find(|c| c.connection_id == "conn-3").unwrap().account_type, "admin");
+        assert!(manager.is_online("user5").await);
+        assert!(!manager.is_online("1001:admin").await);
+        assert!(!manager.touch("conn-2").await);
+        
+        manager.unregister_connection("conn-1").await;
+        assert!(!manager.is_online("user5").await);
+        assert!(!manager.touch("conn-1").await);
+        
+        // 未登记为连接的记录不会被清理 | Entries not registered as connections are never swept
+        assert!(manager.sweep_silent_connections(std::time::Duration::ZERO).await.is_empty());
+        assert!(manager.is_online("user6").await);
+    }
+
+    #[tokio::test]
+    async fn test_push_to_device() {
+        use crate::{SaTokenConfig, SaTokenManager};
+        use sa_token_storage_memory::MemoryStorage;
+        
+        let online = Arc::new(OnlineManager::new());
+        let pusher = Arc::new(InMemoryPusher::new());
+        online.register_pusher(pusher.clone()).await;
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+            .with_online_manager(online.clone());
+        
+        let ios = manager.login_with_options("user7", None, Some("ios".to_string()), None, None, None).await.unwrap();
+        manager.login_with_options("user7", None, Some("web".to_string()), None, None, None).await.unwrap();
+        manager.login("user7").await.unwrap();
+        assert_eq!(online.get_user_devices("user7").await, ["default", "ios", "web"]);
+        
+        let message = |content: &str| PushMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            content: content.to_string(),
+            message_type: MessageType::Notification,
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+        };
+        online.push_to_device("user7", "ios", message("to ios")).await.unwrap();
+        online.push_to_all_except("user7", "web", message("signed in on web")).await.unwrap();
+        
+        let delivered: Vec<_> = pusher.get_messages("user7").await.iter()
+            .map(|m| format!("{}@{}", m.content, m.metadata[DEVICE_KEY]))
+            .collect();
+        assert_eq!(delivered, ["to ios@ios", "signed in on web@default", "signed in on web@ios"]);
+        
+        // 登出后设备被移除 | The device is removed on logout
+        manager.logout(&ios).await.unwrap();
+        assert_eq!(online.get_user_devices("user7").await, ["default", "web"]);
+    }
+
+    #[tokio::test]
+    async fn test_kick_out_device() {
+        use crate::{SaTokenConfig, SaTokenManager};
+        use sa_token_storage_memory::MemoryStorage;
+        
+        let online = Arc::new(OnlineManager::new());
+        let pusher = Arc::new(InMemoryPusher::new());
+        online.register_pusher(pusher.clone()).await;
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+            .with_online_manager(online.clone());
+        
+        let ios = manager.login_with_options("user8", None, Some("ios".to_string()), None, None, None).await.unwrap();
+        let web = manager.login_with_options("user8", None, Some("web".to_string()), None, None, None).await.unwrap();
+        let default = manager.login("user8").await.unwrap();
+        
+        assert_eq!(manager.kick_out_device("user8", "ios").await.unwrap(), 1);
+        assert!(!manager.is_valid(&ios).await);
+        assert!(manager.is_valid(&web).await);
+        assert_eq!(online.get_user_devices("user8").await, ["default", "web"]);
+        assert_eq!(manager.kick_out_device("user8", "ios").await.unwrap(), 0);
+        
+        manager.kick_out_token(&default).await.unwrap();
+        assert!(!manager.is_valid(&default).await);
+        assert!(matches!(manager.kick_out_token(&default).await, Err(SaTokenError::TokenNotFound)));
+        
+        let delivered: Vec<_> = pusher.get_messages("user8").await.iter()
+            .map(|m| (m.message_type.clone(), m.metadata[DEVICE_KEY].clone()))
+            .collect();
+        assert_eq!(delivered, [(MessageType::KickOut, "ios".to_string()), (MessageType::KickOut, "default".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_list_online() {
+        let manager = OnlineManager::new();
+        let start = Utc::now();
+        for i in 0..5 {
+            let user = OnlineUser {
+                login_id: format!("user{}", i % 3),
+                token: format!("token{}", i),
+                device: if i % 2 == 0 { "web" } else { "ios" }.to_string(),
+                connect_time: start + chrono::Duration::minutes(i),
+                last_activity: Utc::now(),
+                metadata: HashMap::from([(IP_KEY.to_string(), format!("10.0.0.{}", i % 2))]),
+            };
+            manager.mark_online(user).await;
+        }
+        
+        let page = manager.list_online(1, 2, &OnlineFilter::new()).await;
+        assert_eq!((page.total, page.total_users), (5, 3));
+        let tokens: Vec<_> = page.items.iter().map(|u| u.token.as_str()).collect();
+        assert_eq!(tokens, ["token4", "token3"]);
+        let last = manager.list_online(3, 2, &OnlineFilter::new()).await;
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.items[0].token, "token0");
+        
+        let web = manager.list_online(1, 10, &OnlineFilter::new().with_device("web")).await;
+        assert_eq!((web.total, web.total_users), (3, 3));
+        
+        let filter = OnlineFilter::new()
+            .with_ip("10.0.0.1")
+            .with_connect_time_range(start + chrono::Duration::minutes(1), start + chrono::Duration::minutes(3));
+        let page = manager.list_online(1, 10, &filter).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].token, "token1");
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let manager = OnlineManager::new();
+        for (login_id, token, device) in [("user8", "t1", "web"), ("user8", "t2", "ios"), ("user9", "t3", "web")] {
+            manager.register_device(login_id, token, device).await;
+            let user = OnlineUser {
+                login_id: login_id.to_string(),
+                token: token.to_string(),
+                device: device.to_string(),
+                connect_time: Utc::now(),
+                last_activity: Utc::now(),
+                metadata: HashMap::new(),
+            };
+            manager.mark_online(user).await;
+        }
+        manager.mark_offline_all("user9").await;
+        
+        let stats = manager.stats().await;
+        assert_eq!((stats.online_users, stats.online_sessions), (1, 2));
+        assert_eq!(stats.sessions_by_device, HashMap::from([("web".to_string(), 1), ("ios".to_string(), 1)]));
+        assert_eq!(stats.recent_logins, 3);
+        assert_eq!(stats.peak_online_users, 2);
+        
+        let manager = OnlineManager::new().with_login_window(Duration::from_millis(10));
+        manager.register_device("user8", "t1", "web").await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(manager.stats().await.recent_logins, 0);
+    }
 }
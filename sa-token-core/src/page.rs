@@ -0,0 +1,520 @@
+// Author: 金书记
+//
+//! 用户页面渲染 | User-facing Page Rendering
+//!
+//! SSO 服务端与 OAuth2 授权流程需要的最小 HTML 页面（登录、授权确认、错误）。
+//! Minimal HTML pages (login, consent, error) needed by the SSO server and OAuth2 flows.
+//!
+//! ## 渲染流程 | Rendering Flow
+//!
+//! ```text
+//! Accept-Language: zh-CN,zh;q=0.9,en;q=0.8
+//!   ↓ negotiate_locale(header, renderer.supported_locales(), default)
+//! "zh-CN"
+//!   ↓ renderer.render(&SaPage::Login(..), "zh-CN")
+//! DefaultPageRenderer
+//!   ├─→ 文案: 按语言查找，缺失时回退默认语言 | texts: looked up by locale, falling back to the default
+//!   ├─→ PageBranding: 产品名、Logo、主题色、页脚、CSS | product name, logo, colour, footer, CSS
+//!   └─→ HTML（所有动态内容均已转义 | all dynamic values escaped）
+//! ```
+//!
+//! 实现 `PageRenderer` 可以完全替换页面，或仅通过 `PageBranding` / `with_messages`
+//! 调整默认页面。
+//! Implement `PageRenderer` to replace the pages entirely, or only adjust the default pages
+//! through `PageBranding` / `with_messages`.
+
+use std::collections::HashMap;
+use crate::error::SaTokenResult;
+
+/// 默认语言 | Default locale
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// 登录页 | Login page
+#[derive(Debug, Clone, Default)]
+pub struct LoginPage {
+    /// 表单提交地址 | Form action URL
+    pub action: String,
+    /// 登录成功后回跳的服务地址 | Service URL to return to after login
+    pub service: Option<String>,
+    /// 上次提交的错误信息 | Error message of the previous attempt
+    pub error: Option<String>,
+}
+
+/// 授权确认页 | Consent page
+#[derive(Debug, Clone, Default)]
+pub struct ConsentPage {
+    /// 表单提交地址 | Form action URL
+    pub action: String,
+    /// 客户端 ID | Client ID
+    pub client_id: String,
+    /// 请求的权限范围 | Requested scopes
+    pub scopes: Vec<String>,
+    /// 回调地址 | Redirect URI
+    pub redirect_uri: String,
+    /// 客户端传入的 state | State passed by the client
+    pub state: Option<String>,
+    /// 一次性确认票据，由 `OAuth2Manager::authorize_request` 签发 | One-time consent ticket issued by `OAuth2Manager::authorize_request`
+    pub ticket: Option<String>,
+}
+
+/// 错误页 | Error page
+#[derive(Debug, Clone, Default)]
+pub struct ErrorPage {
+    /// HTTP 状态码 | HTTP status code
+    pub status: u16,
+    /// 错误信息 | Error message
+    pub message: String,
+}
+
+/// 可渲染的页面 | Renderable page
+#[derive(Debug, Clone)]
+pub enum SaPage {
+    /// 登录页 | Login page
+    Login(LoginPage),
+    /// 授权确认页 | Consent page
+    Consent(ConsentPage),
+    /// 错误页 | Error page
+    Error(ErrorPage),
+}
+
+/// 页面渲染器 | Page renderer
+pub trait PageRenderer: Send + Sync {
+    /// 渲染页面 | Render a page
+    ///
+    /// # 参数 | Parameters
+    /// * `page` - 页面数据 | Page data
+    /// * `locale` - 协商后的语言 | Negotiated locale
+    fn render(&self, page: &SaPage, locale: &str) -> SaTokenResult<String>;
+
+    /// 支持的语言，第一个为默认语言 | Supported locales, the first one is the default
+    fn supported_locales(&self) -> Vec<String> {
+        vec![DEFAULT_LOCALE.to_string()]
+    }
+
+    /// 根据 `Accept-Language` 协商语言后渲染 | Render with the locale negotiated from `Accept-Language`
+    fn render_negotiated(&self, page: &SaPage, accept_language: Option<&str>) -> SaTokenResult<String> {
+        let supported = self.supported_locales();
+        let default = supported.first().map(String::as_str).unwrap_or(DEFAULT_LOCALE);
+        let locale = negotiate_locale(accept_language, &supported, default);
+        self.render(page, &locale)
+    }
+}
+
+/// 根据 `Accept-Language` 协商语言 | Negotiate a locale from `Accept-Language`
+///
+/// 按 q 值从高到低匹配，先精确匹配（忽略大小写），再按主语言匹配（`zh` ↔ `zh-CN`）。
+/// Matches by descending q value, exact match first (case-insensitive), then by primary
+/// language (`zh` ↔ `zh-CN`).
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::page::negotiate_locale;
+/// let supported = vec!["en".to_string(), "zh-CN".to_string()];
+/// assert_eq!(negotiate_locale(Some("zh;q=0.9,fr;q=1.0"), &supported, "en"), "zh-CN");
+/// assert_eq!(negotiate_locale(None, &supported, "en"), "en");
+/// ```
+pub fn negotiate_locale(accept_language: Option<&str>, supported: &[String], default: &str) -> String {
+    let Some(header) = accept_language else {
+        return default.to_string();
+    };
+
+    let mut ranges: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = parts.next()?.trim();
+            if tag.is_empty() {
+                return None;
+            }
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((tag, q))
+        })
+        .filter(|(_, q)| *q > 0.0)
+        .collect();
+    // 稳定排序，q 值相同时保持请求中的顺序 | Stable sort keeps request order for equal q values
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let primary = |tag: &str| tag.split(['-', '_']).next().unwrap_or(tag).to_ascii_lowercase();
+
+    for (tag, _) in ranges {
+        if tag == "*" {
+            return default.to_string();
+        }
+        if let Some(found) = supported.iter().find(|s| s.eq_ignore_ascii_case(tag)) {
+            return found.clone();
+        }
+        if let Some(found) = supported.iter().find(|s| primary(s) == primary(tag)) {
+            return found.clone();
+        }
+    }
+
+    default.to_string()
+}
+
+/// 转义 HTML 特殊字符 | Escape HTML special characters
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 页面品牌设置 | Page branding
+#[derive(Debug, Clone)]
+pub struct PageBranding {
+    /// 产品名称 | Product name
+    pub product_name: String,
+    /// Logo 地址 | Logo URL
+    pub logo_url: Option<String>,
+    /// 主题色 | Primary colour
+    pub primary_color: String,
+    /// 页脚文字 | Footer text
+    pub footer: Option<String>,
+    /// 附加 CSS | Extra CSS
+    pub custom_css: Option<String>,
+}
+
+impl Default for PageBranding {
+    fn default() -> Self {
+        Self {
+            product_name: "sa-token".to_string(),
+            logo_url: None,
+            primary_color: "#1677ff".to_string(),
+            footer: None,
+            custom_css: None,
+        }
+    }
+}
+
+impl PageBranding {
+    /// 设置产品名称 | Set the product name
+    pub fn product_name(mut self, name: impl Into<String>) -> Self {
+        self.product_name = name.into();
+        self
+    }
+
+    /// 设置 Logo 地址 | Set the logo URL
+    pub fn logo_url(mut self, url: impl Into<String>) -> Self {
+        self.logo_url = Some(url.into());
+        self
+    }
+
+    /// 设置主题色 | Set the primary colour
+    pub fn primary_color(mut self, color: impl Into<String>) -> Self {
+        self.primary_color = color.into();
+        self
+    }
+
+    /// 设置页脚文字 | Set the footer text
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// 设置附加 CSS | Set extra CSS
+    pub fn custom_css(mut self, css: impl Into<String>) -> Self {
+        self.custom_css = Some(css.into());
+        self
+    }
+}
+
+fn builtin_messages(locale: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match locale {
+        "en" => Some(&[
+            ("login.title", "Sign in"),
+            ("login.username", "Username"),
+            ("login.password", "Password"),
+            ("login.submit", "Sign in"),
+            ("consent.title", "Authorize application"),
+            ("consent.request", "is requesting access to:"),
+            ("consent.approve", "Allow"),
+            ("consent.deny", "Deny"),
+            ("error.title", "Something went wrong"),
+        ]),
+        "zh-CN" => Some(&[
+            ("login.title", "登录"),
+            ("login.username", "用户名"),
+            ("login.password", "密码"),
+            ("login.submit", "登录"),
+            ("consent.title", "授权应用"),
+            ("consent.request", "请求获取以下权限："),
+            ("consent.approve", "同意"),
+            ("consent.deny", "拒绝"),
+            ("error.title", "出错了"),
+        ]),
+        _ => None,
+    }
+}
+
+/// 默认页面渲染器 | Default page renderer
+///
+/// 内置 `en` 与 `zh-CN` 文案，可通过 `with_messages` 增加语言或覆盖文案。
+/// Ships `en` and `zh-CN` texts; `with_messages` adds locales or overrides texts.
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::page::{DefaultPageRenderer, PageBranding, PageRenderer, SaPage, ErrorPage};
+///
+/// let renderer = DefaultPageRenderer::new()
+///     .branding(PageBranding::default().product_name("Acme ID"));
+/// let html = renderer
+///     .render_negotiated(&SaPage::Error(ErrorPage { status: 400, message: "bad request".into() }), Some("zh-CN"))
+///     .unwrap();
+/// assert!(html.contains("Acme ID"));
+/// assert!(html.contains("出错了"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DefaultPageRenderer {
+    branding: PageBranding,
+    messages: HashMap<String, HashMap<String, String>>,
+    default_locale: String,
+}
+
+impl Default for DefaultPageRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultPageRenderer {
+    /// 创建默认渲染器 | Create the default renderer
+    pub fn new() -> Self {
+        let mut messages = HashMap::new();
+        for locale in ["en", "zh-CN"] {
+            let texts = builtin_messages(locale).unwrap_or_default();
+            messages.insert(
+                locale.to_string(),
+                texts.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            );
+        }
+        Self {
+            branding: PageBranding::default(),
+            messages,
+            default_locale: DEFAULT_LOCALE.to_string(),
+        }
+    }
+
+    /// 设置品牌 | Set the branding
+    pub fn branding(mut self, branding: PageBranding) -> Self {
+        self.branding = branding;
+        self
+    }
+
+    /// 设置默认语言 | Set the default locale
+    pub fn default_locale(mut self, locale: impl Into<String>) -> Self {
+        self.default_locale = locale.into();
+        self
+    }
+
+    /// 增加语言或覆盖文案 | Add a locale or override texts
+    ///
+    /// # 参数 | Parameters
+    /// * `locale` - 语言标签 | Locale tag
+    /// * `texts` - 文案键值，缺失的键回退到默认语言 | Text entries, missing keys fall back to the default locale
+    pub fn with_messages<K, V>(mut self, locale: impl Into<String>, texts: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let entry = self.messages.entry(locale.into()).or_default();
+        for (k, v) in texts {
+            entry.insert(k.into(), v.into());
+        }
+        self
+    }
+
+    fn text<'a>(&'a self, locale: &str, key: &'a str) -> &'a str {
+        self.messages.get(locale).and_then(|m| m.get(key))
+            .or_else(|| self.messages.get(&self.default_locale).and_then(|m| m.get(key)))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    fn layout(&self, locale: &str, title: &str, body: &str) -> String {
+        let b = &self.branding;
+        let logo = b.logo_url.as_deref()
+            .map(|url| format!(r#"<img class="logo" src="{}" alt="">"#, escape_html(url)))
+            .unwrap_or_default();
+        let footer = b.footer.as_deref()
+            .map(|f| format!("<footer>{}</footer>", escape_html(f)))
+            .unwrap_or_default();
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} - {product}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; background: #f5f5f5; display: flex; justify-content: center; padding-top: 10vh; }}
+main {{ background: #fff; padding: 2rem; border-radius: 8px; width: 22rem; box-shadow: 0 2px 8px rgba(0,0,0,.1); }}
+input {{ width: 100%; padding: .5rem; margin: .25rem 0 1rem; box-sizing: border-box; }}
+button {{ background: {color}; color: #fff; border: 0; padding: .6rem 1.2rem; border-radius: 4px; cursor: pointer; }}
+button.secondary {{ background: #999; }}
+.error {{ color: #d4380d; }}
+.logo {{ max-height: 48px; }}
+footer {{ margin-top: 2rem; color: #999; font-size: .8rem; }}
+{css}
+</style>
+</head>
+<body>
+<main>
+{logo}
+<h2>{product}</h2>
+<h3>{title}</h3>
+{body}
+{footer}
+</main>
+</body>
+</html>"#,
+            lang = escape_html(locale),
+            title = escape_html(title),
+            product = escape_html(&b.product_name),
+            color = escape_html(&b.primary_color),
+            css = b.custom_css.as_deref().unwrap_or_default(),
+            logo = logo,
+            body = body,
+            footer = footer,
+        )
+    }
+
+    fn hidden(name: &str, value: &str) -> String {
+        format!(r#"<input type="hidden" name="{}" value="{}">"#, name, escape_html(value))
+    }
+}
+
+impl PageRenderer for DefaultPageRenderer {
+    fn render(&self, page: &SaPage, locale: &str) -> SaTokenResult<String> {
+        let t = |key| self.text(locale, key);
+        let html = match page {
+            SaPage::Login(p) => {
+                let mut body = String::new();
+                if let Some(error) = &p.error {
+                    body.push_str(&format!(r#"<p class="error">{}</p>"#, escape_html(error)));
+                }
+                body.push_str(&format!(r#"<form method="post" action="{}">"#, escape_html(&p.action)));
+                if let Some(service) = &p.service {
+                    body.push_str(&Self::hidden("service", service));
+                }
+                body.push_str(&format!(
+                    r#"<label>{}<input name="username" autocomplete="username" required></label><label>{}<input name="password" type="password" autocomplete="current-password" required></label><button type="submit">{}</button></form>"#,
+                    escape_html(t("login.username")),
+                    escape_html(t("login.password")),
+                    escape_html(t("login.submit")),
+                ));
+                self.layout(locale, t("login.title"), &body)
+            }
+            SaPage::Consent(p) => {
+                let scopes: String = p.scopes.iter()
+                    .map(|s| format!("<li>{}</li>", escape_html(s)))
+                    .collect();
+                let mut body = format!(
+                    r#"<p><strong>{}</strong> {}</p><ul>{}</ul><form method="post" action="{}">"#,
+                    escape_html(&p.client_id),
+                    escape_html(t("consent.request")),
+                    scopes,
+                    escape_html(&p.action),
+                );
+                body.push_str(&Self::hidden("client_id", &p.client_id));
+                body.push_str(&Self::hidden("redirect_uri", &p.redirect_uri));
+                body.push_str(&Self::hidden("scope", &p.scopes.join(" ")));
+                if let Some(state) = &p.state {
+                    body.push_str(&Self::hidden("state", state));
+                }
+                if let Some(ticket) = &p.ticket {
+                    body.push_str(&Self::hidden("ticket", ticket));
+                }
+                body.push_str(&format!(
+                    r#"<button type="submit" name="decision" value="approve">{}</button> <button class="secondary" type="submit" name="decision" value="deny">{}</button></form>"#,
+                    escape_html(t("consent.approve")),
+                    escape_html(t("consent.deny")),
+                ));
+                self.layout(locale, t("consent.title"), &body)
+            }
+            SaPage::Error(p) => {
+                let body = format!(
+                    r#"<p class="error">{} {}</p>"#,
+                    p.status,
+                    escape_html(&p.message),
+                );
+                self.layout(locale, t("error.title"), &body)
+            }
+        };
+        Ok(html)
+    }
+
+    fn supported_locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.messages.keys()
+            .filter(|l| **l != self.default_locale)
+            .cloned()
+            .collect();
+        locales.sort();
+        locales.insert(0, self.default_locale.clone());
+        locales
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE_KEYS: &[&str] = &[
+        "login.title", "login.username", "login.password", "login.submit",
+        "consent.title", "consent.request", "consent.approve", "consent.deny",
+        "error.title",
+    ];
+
+    #[test]
+    fn test_builtin_messages_complete() {
+        for locale in ["en", "zh-CN"] {
+            let texts = builtin_messages(locale).unwrap();
+            for key in MESSAGE_KEYS {
+                assert!(texts.iter().any(|(k, _)| k == key), "{} missing {}", locale, key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_negotiate_locale() {
+        let supported = vec!["en".to_string(), "zh-CN".to_string()];
+        assert_eq!(negotiate_locale(Some("zh-cn"), &supported, "en"), "zh-CN");
+        assert_eq!(negotiate_locale(Some("fr, zh-TW;q=0.5"), &supported, "en"), "zh-CN");
+        assert_eq!(negotiate_locale(Some("fr"), &supported, "en"), "en");
+        assert_eq!(negotiate_locale(Some("zh;q=0, en-GB"), &supported, "en"), "en");
+    }
+
+    #[test]
+    fn test_render_escapes_and_overrides() {
+        let renderer = DefaultPageRenderer::new()
+            .with_messages("fr", [("consent.approve", "Autoriser")]);
+
+        let page = SaPage::Consent(ConsentPage {
+            action: "/oauth2/authorize".into(),
+            client_id: "<script>".into(),
+            scopes: vec!["read".into()],
+            redirect_uri: "https://app/cb".into(),
+            state: Some("x\"y".into()),
+            ticket: Some("t1".into()),
+        });
+        let html = renderer.render_negotiated(&page, Some("fr-FR")).unwrap();
+
+        assert!(html.contains("Autoriser"));
+        // 缺失的键回退到默认语言 | Missing keys fall back to the default locale
+        assert!(html.contains("Deny"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("x&quot;y"));
+        assert!(html.contains(r#"name="ticket" value="t1""#));
+    }
+}
@@ -0,0 +1,144 @@
+// Author: 金书记
+//
+//! 熔断器 | Circuit Breaker
+//!
+//! 保护 `PermissionProvider` 的后端：连续失败达到阈值后熔断一段时间，期间不再调用后端，
+//! 由缓存中的旧数据兜底；冷却结束后放行一次试探调用，成功即恢复。
+//! Protects the `PermissionProvider` backend: after a run of consecutive failures the circuit opens for a while
+//! and the backend is not called, stale cached data is served instead; after the cooldown one trial call is let
+//! through and a success closes the circuit again.
+//!
+//! ```text
+//! Closed ──连续失败 >= threshold | threshold consecutive failures──→ Open
+//!   ↑                                                                 │ cooldown 结束 | elapsed
+//!   └──────────── 试探成功 | trial succeeds ─── HalfOpen ←───────────┘
+//!                                                  │ 试探失败 | trial fails → Open
+//! ```
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 熔断器状态 | Circuit state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// 正常调用 | Calls go through
+    Closed,
+    /// 熔断中，不调用后端 | Open, the backend is not called
+    Open,
+    /// 冷却结束，放行一次试探调用 | Cooldown over, one trial call is let through
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    failures: u32,
+    open_until: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+/// 熔断器 | Circuit breaker
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    /// 创建熔断器 | Create a breaker
+    ///
+    /// # 参数 | Parameters
+    /// * `threshold` - 触发熔断的连续失败次数，0 表示从不熔断 | Consecutive failures that open the circuit, 0 never opens it
+    /// * `cooldown` - 熔断持续时间 | How long the circuit stays open
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            inner: Mutex::new(Inner { failures: 0, open_until: None, trial_in_flight: false }),
+        }
+    }
+
+    /// 当前状态 | Current state
+    pub fn state(&self) -> CircuitState {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match inner.open_until {
+            None => CircuitState::Closed,
+            Some(until) if until > Instant::now() => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// 是否允许调用后端；半开状态下只放行一次试探 | Whether a call may go through; only one trial in the half-open state
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match inner.open_until {
+            None => true,
+            Some(until) if until > Instant::now() => false,
+            Some(_) if inner.trial_in_flight => false,
+            Some(_) => {
+                inner.trial_in_flight = true;
+                true
+            }
+        }
+    }
+
+    /// 记录一次成功调用 | Record a successful call
+    ///
+    /// 返回熔断器是否因此恢复 | Returns whether this closed the circuit
+    pub fn record_success(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let recovered = inner.open_until.is_some();
+        *inner = Inner { failures: 0, open_until: None, trial_in_flight: false };
+        recovered
+    }
+
+    /// 记录一次失败调用 | Record a failed call
+    ///
+    /// 返回熔断器是否因此打开 | Returns whether this opened the circuit
+    pub fn record_failure(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.failures = inner.failures.saturating_add(1);
+        let trial_failed = inner.trial_in_flight;
+        inner.trial_in_flight = false;
+        if self.threshold > 0 && (trial_failed || inner.failures >= self.threshold) {
+            inner.open_until = Some(Instant::now() + self.cooldown);
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+        assert!(breaker.allow());
+        assert!(!breaker.record_failure());
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.allow());
+
+        // 冷却结束后只放行一次试探 | Only one trial after the cooldown
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.allow());
+        assert!(breaker.record_success());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        // 阈值为 0 时从不熔断 | Never opens with a threshold of 0
+        let disabled = CircuitBreaker::new(0, Duration::from_secs(30));
+        for _ in 0..10 {
+            disabled.record_failure();
+        }
+        assert!(disabled.allow());
+    }
+}
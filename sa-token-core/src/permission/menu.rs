@@ -0,0 +1,181 @@
+// Author: 金书记
+//
+//! 菜单 / 路由可见性清单 | Menu / Route Visibility Manifest
+//!
+//! 管理后台的前端通常按当前用户的权限渲染导航。服务端声明一份带所需权限的菜单清单，
+//! 再按用户过滤后返回，前端只渲染拿到的部分。
+//! Admin frontends usually render navigation from the current user's permissions. The server declares
+//! one manifest with the permissions each entry needs and returns the copy filtered for the user.
+//!
+//! ```text
+//! 清单 | Manifest (JSON / YAML / 代码 | code)
+//!   └─→ required_permissions()：收集所有权限，一次批量检查 | collect every permission, check them in one bulk call
+//!         └─→ filter_menu()
+//!               ├─→ 缺少任一所需权限的项连同子项一起移除 | entries missing any required permission are dropped with their children
+//!               └─→ 子项全被移除且自身没有路径的分组也被移除 | groups without a path whose children were all dropped are removed too
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+/// 菜单 / 路由项 | Menu or route entry
+///
+/// 除下列字段外的键（如 `icon`、`order`）原样保留在 `extra` 中，随过滤结果返回给前端
+/// Keys other than the fields below (e.g. `icon`, `order`) are kept in `extra` and returned with the filtered manifest
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::MenuItem;
+///
+/// let menu: Vec<MenuItem> = serde_json::from_str(r#"[
+///     {"key": "order", "title": "Orders", "permissions": ["order:read"], "children": [
+///         {"key": "order-list", "path": "/orders", "icon": "list"},
+///         {"key": "order-refund", "path": "/orders/refund", "permissions": ["order:refund"]}
+///     ]}
+/// ]"#).unwrap();
+/// assert_eq!(menu[0].children[0].extra["icon"], "list");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MenuItem {
+    /// 唯一标识 | Unique key
+    pub key: String,
+
+    /// 标题 | Title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// 路由路径，分组项可为空 | Route path, may be empty for groups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// 所需权限（全部满足才可见），为空表示所有人可见 | Required permissions (all of them), empty means visible to everyone
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+
+    /// 子项 | Children
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<MenuItem>,
+
+    /// 前端使用的其他字段 | Other fields used by the frontend
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl MenuItem {
+    /// 创建菜单项 | Create an entry
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            title: None,
+            path: None,
+            permissions: Vec::new(),
+            children: Vec::new(),
+            extra: HashMap::new(),
+        }
+    }
+
+    /// 设置标题 | Set the title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// 设置路由路径 | Set the route path
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// 添加所需权限 | Add a required permission
+    pub fn permission(mut self, permission: impl Into<String>) -> Self {
+        self.permissions.push(permission.into());
+        self
+    }
+
+    /// 添加子项 | Add a child
+    pub fn child(mut self, child: MenuItem) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// 添加前端字段 | Add a frontend field
+    pub fn extra(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.extra.insert(key.into(), value);
+        self
+    }
+}
+
+/// 收集清单中出现的所有权限（去重，保持首次出现的顺序）
+/// Collect every permission in a manifest (deduplicated, in order of first appearance)
+pub fn required_permissions(items: &[MenuItem]) -> Vec<&str> {
+    fn collect<'a>(items: &'a [MenuItem], seen: &mut HashSet<&'a str>, out: &mut Vec<&'a str>) {
+        for item in items {
+            for permission in &item.permissions {
+                if seen.insert(permission) {
+                    out.push(permission);
+                }
+            }
+            collect(&item.children, seen, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    collect(items, &mut HashSet::new(), &mut out);
+    out
+}
+
+/// 按权限过滤清单 | Filter a manifest by permission
+///
+/// # 参数 | Parameters
+/// * `items` - 菜单清单 | Manifest
+/// * `granted` - 判断是否拥有某个权限 | Whether a permission is granted
+pub fn filter_menu(items: &[MenuItem], granted: &dyn Fn(&str) -> bool) -> Vec<MenuItem> {
+    items
+        .iter()
+        .filter(|item| item.permissions.iter().all(|p| granted(p)))
+        .filter_map(|item| {
+            let children = filter_menu(&item.children, granted);
+            if !item.children.is_empty() && children.is_empty() && item.path.is_none() {
+                return None;
+            }
+            Some(MenuItem { children, ..item.clone() })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::{SaTokenConfig, SaTokenManager};
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn manifest() -> Vec<MenuItem> {
+        vec![
+            MenuItem::new("home").path("/"),
+            MenuItem::new("order").title("Orders")
+                .child(MenuItem::new("order-list").path("/orders").permission("order:read"))
+                .child(MenuItem::new("order-refund").path("/orders/refund").permission("order:refund")),
+            MenuItem::new("system").title("System")
+                .child(MenuItem::new("system-user").path("/system/users").permission("system:user")),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_filter_menu() {
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        manager.set_permissions("1001", vec!["order:*".to_string()]).await;
+
+        let menu = manifest();
+        assert_eq!(required_permissions(&menu), ["order:read", "order:refund", "system:user"]);
+
+        let filtered = manager.filter_menu("1001", &menu).await;
+        let keys: Vec<_> = filtered.iter().map(|item| item.key.as_str()).collect();
+        // 子项全被移除的分组不返回 | Groups whose children were all removed are not returned
+        assert_eq!(keys, ["home", "order"]);
+        assert_eq!(filtered[1].children.len(), 2);
+
+        let anonymous = filter_menu(&menu, &|_| false);
+        assert_eq!(anonymous, vec![MenuItem::new("home").path("/")]);
+    }
+}
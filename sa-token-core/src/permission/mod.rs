@@ -2,8 +2,17 @@
 //
 //! 权限验证模块
 
+use std::collections::{HashMap, HashSet};
 use async_trait::async_trait;
-use crate::error::SaTokenResult;
+use crate::error::{SaTokenError, SaTokenResult};
+
+pub mod menu;
+pub mod breaker;
+pub mod snapshot;
+
+pub use menu::{MenuItem, filter_menu, required_permissions};
+pub use breaker::{CircuitBreaker, CircuitState};
+pub use snapshot::PermissionSnapshot;
 
 /// 权限检查器 | Permission Checker
 /// 
@@ -148,3 +157,180 @@ pub trait RoleChecker: Send + Sync {
     /// 用户的角色列表 | User's role list
     async fn get_roles(&self, login_id: &str) -> SaTokenResult<Vec<String>>;
 }
+
+/// 权限数据提供者 | Permission Provider
+/// 
+/// 对应 Java 版 Sa-Token 的 `StpInterface`：由业务方从数据库等数据源加载权限和角色，
+/// 注册到 `SaTokenManager` 后，`StpUtil::has_permission` / `has_role` 会自动调用。
+/// Counterpart of `StpInterface` in the Java Sa-Token: the application loads permissions and roles
+/// from its own data source (e.g. a database); once registered on `SaTokenManager`,
+/// `StpUtil::has_permission` / `has_role` call it automatically.
+/// 
+/// # 使用示例 | Usage Example
+/// 
+/// ```rust,ignore
+/// use async_trait::async_trait;
+/// use sa_token_core::{PermissionProvider, SaTokenResult, StpUtil};
+/// 
+/// struct DbPermissionProvider { pool: PgPool }
+/// 
+/// #[async_trait]
+/// impl PermissionProvider for DbPermissionProvider {
+///     async fn get_permission_list(&self, login_id: &str, login_type: &str) -> SaTokenResult<Vec<String>> {
+///         // SELECT permission FROM user_permissions WHERE user_id = $1
+///         Ok(vec!["user:read".to_string()])
+///     }
+///     
+///     async fn get_role_list(&self, login_id: &str, login_type: &str) -> SaTokenResult<Vec<String>> {
+///         Ok(vec!["admin".to_string()])
+///     }
+/// }
+/// 
+/// StpUtil::set_permission_provider(Arc::new(DbPermissionProvider { pool }));
+/// ```
+#[async_trait]
+pub trait PermissionProvider: Send + Sync {
+    /// 获取用户的权限列表 | Get the permission list of a user
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 账号类型 | Account type
+    async fn get_permission_list(&self, login_id: &str, login_type: &str) -> SaTokenResult<Vec<String>>;
+    
+    /// 获取用户的角色列表 | Get the role list of a user
+    /// 
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `login_type`: 账号类型 | Account type
+    async fn get_role_list(&self, login_id: &str, login_type: &str) -> SaTokenResult<Vec<String>>;
+}
+
+/// 角色层级 | Role Hierarchy
+/// 
+/// 上级角色继承下级角色：配置 `admin > manager > user` 后，拥有 `admin` 的用户
+/// 调用 `has_role("user")` 也会通过。继承关系可传递，环路会被安全忽略。
+/// A senior role inherits its junior roles: with `admin > manager > user`, a user holding `admin`
+/// also passes `has_role("user")`. Inheritance is transitive and cycles are ignored safely.
+/// 
+/// # 使用示例 | Usage Example
+/// 
+/// ```rust
+/// use sa_token_core::RoleHierarchy;
+/// 
+/// let hierarchy = RoleHierarchy::parse("admin > manager > user").unwrap()
+///     .inherit("admin", "auditor");
+/// 
+/// assert!(hierarchy.implies("admin", "user"));
+/// assert!(hierarchy.implies("admin", "auditor"));
+/// assert!(!hierarchy.implies("user", "manager"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RoleHierarchy {
+    /// 角色 → 直接继承的下级角色 | Role → directly inherited junior roles
+    children: HashMap<String, Vec<String>>,
+}
+
+impl RoleHierarchy {
+    /// 创建空的角色层级 | Create an empty hierarchy
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 声明 `parent` 继承 `child` | Declare that `parent` inherits `child`
+    /// 
+    /// # 参数 | Parameters
+    /// - `parent`: 上级角色 | Senior role
+    /// - `child`: 下级角色 | Junior role
+    pub fn inherit(mut self, parent: impl Into<String>, child: impl Into<String>) -> Self {
+        let children = self.children.entry(parent.into()).or_default();
+        let child = child.into();
+        if !children.contains(&child) {
+            children.push(child);
+        }
+        self
+    }
+    
+    /// 解析层级表达式 | Parse a hierarchy expression
+    /// 
+    /// 每行（或以 `;` 分隔的每段）是一条 `a > b > c` 链
+    /// Every line (or `;`-separated segment) is a chain such as `a > b > c`
+    /// 
+    /// # 参数 | Parameters
+    /// - `expr`: 如 `"admin > manager > user; admin > auditor"` | e.g. `"admin > manager > user; admin > auditor"`
+    pub fn parse(expr: &str) -> SaTokenResult<Self> {
+        let mut hierarchy = Self::new();
+        for chain in expr.split(['\n', ';']).map(str::trim).filter(|c| !c.is_empty()) {
+            let roles: Vec<&str> = chain.split('>').map(str::trim).collect();
+            if roles.len() < 2 || roles.iter().any(|r| r.is_empty()) {
+                return Err(SaTokenError::ConfigError(format!("invalid role hierarchy '{}'", chain)));
+            }
+            for pair in roles.windows(2) {
+                hierarchy = hierarchy.inherit(pair[0], pair[1]);
+            }
+        }
+        Ok(hierarchy)
+    }
+    
+    /// 拥有 `held` 角色是否满足 `required` 角色 | Whether holding `held` satisfies `required`
+    pub fn implies(&self, held: &str, required: &str) -> bool {
+        held == required || self.reachable(held).contains(required)
+    }
+    
+    /// 展开为包含所有继承角色的列表 | Expand roles with every inherited role
+    /// 
+    /// 保持原有顺序，继承的角色追加在后 | Keeps the original order, inherited roles are appended
+    pub fn expand(&self, roles: &[String]) -> Vec<String> {
+        let mut expanded = roles.to_vec();
+        for role in roles {
+            for inherited in self.reachable(role) {
+                if !expanded.iter().any(|r| r == inherited) {
+                    expanded.push(inherited.to_string());
+                }
+            }
+        }
+        expanded
+    }
+    
+    /// 是否未配置任何继承关系 | Whether no inheritance is configured
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+    
+    /// 所有可传递继承的下级角色 | All transitively inherited junior roles
+    fn reachable<'a>(&'a self, role: &str) -> HashSet<&'a str> {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<&str> = self.children.get(role)
+            .map(|c| c.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        while let Some(current) = stack.pop() {
+            if visited.insert(current)
+                && let Some(children) = self.children.get(current) {
+                stack.extend(children.iter().map(String::as_str));
+            }
+        }
+        visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_role_hierarchy() {
+        let hierarchy = RoleHierarchy::parse("admin > manager > user\nuser > admin").unwrap();
+        
+        assert!(hierarchy.implies("admin", "user"));
+        assert!(hierarchy.implies("manager", "user"));
+        assert!(!hierarchy.implies("guest", "user"));
+        // 环路不会死循环 | Cycles do not loop forever
+        assert!(hierarchy.implies("user", "manager"));
+        
+        let hierarchy = RoleHierarchy::parse("admin > manager > user").unwrap();
+        assert_eq!(
+            hierarchy.expand(&["manager".to_string()]),
+            vec!["manager".to_string(), "user".to_string()]
+        );
+        assert!(RoleHierarchy::parse("admin >").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+// Author: 金书记
+//
+//! 权限快照 | Permission snapshot
+//!
+//! 一次加载账号的权限与角色列表，之后的检查都在内存中完成，供插件的 `CurrentPermissions` 提取器使用
+//! Loads the permission and role lists of an account once so later checks run in memory;
+//! backs the `CurrentPermissions` extractor of the plugins
+
+use crate::manager::SaTokenManager;
+
+/// 账号的权限与角色快照 | Snapshot of the permissions and roles of an account
+///
+/// 权限支持 `admin:*` 通配符；角色在加载时已按角色层级展开
+/// Permissions honour `admin:*` wildcards; roles are expanded along the role hierarchy when loaded
+///
+/// # 示例 | Example
+/// ```rust
+/// use sa_token_core::permission::PermissionSnapshot;
+///
+/// let snapshot = PermissionSnapshot::new(
+///     "10001",
+///     vec!["order:*".to_string()],
+///     vec!["manager".to_string()],
+/// );
+/// assert!(snapshot.has("order:refund"));
+/// assert!(snapshot.has_any(&["user:delete", "order:read"]));
+/// assert!(!snapshot.has_all(&["user:delete", "order:read"]));
+/// assert!(snapshot.has_role("manager"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PermissionSnapshot {
+    login_id: String,
+    permissions: Vec<String>,
+    roles: Vec<String>,
+}
+
+impl PermissionSnapshot {
+    /// 由已加载的列表创建快照 | Create a snapshot from already loaded lists
+    ///
+    /// # 参数 | Parameters
+    /// - `login_id`: 登录 ID | Login ID
+    /// - `permissions`: 权限列表 | Permission list
+    /// - `roles`: 角色列表（应已展开继承角色）| Role list, inherited roles already expanded
+    pub fn new(login_id: impl Into<String>, permissions: Vec<String>, roles: Vec<String>) -> Self {
+        Self { login_id: login_id.into(), permissions, roles }
+    }
+
+    /// 加载指定账号类型下用户的快照 | Load the snapshot of a user for an account type
+    pub async fn load(manager: &SaTokenManager, login_id: &str, login_type: &str) -> Self {
+        let permissions = manager.get_permissions_by_type(login_id, login_type).await;
+        let roles = manager.get_roles_by_type(login_id, login_type).await;
+        let roles = match manager.role_hierarchy() {
+            Some(hierarchy) => hierarchy.expand(&roles),
+            None => roles,
+        };
+        Self::new(login_id, permissions, roles)
+    }
+
+    /// 登录 ID | Login ID
+    pub fn login_id(&self) -> &str {
+        &self.login_id
+    }
+
+    /// 全部权限 | All permissions
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    /// 全部角色（含继承角色）| All roles, inherited ones included
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// 是否拥有指定权限 | Whether the permission is granted
+    pub fn has(&self, permission: &str) -> bool {
+        SaTokenManager::permission_granted(&self.permissions, permission)
+    }
+
+    /// 是否拥有任一权限（OR）| Whether any of the permissions is granted (OR)
+    pub fn has_any(&self, permissions: &[&str]) -> bool {
+        permissions.iter().any(|permission| self.has(permission))
+    }
+
+    /// 是否拥有全部权限（AND）| Whether all of the permissions are granted (AND)
+    pub fn has_all(&self, permissions: &[&str]) -> bool {
+        permissions.iter().all(|permission| self.has(permission))
+    }
+
+    /// 是否拥有指定角色 | Whether the role is held
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    /// 是否拥有任一角色（OR）| Whether any of the roles is held (OR)
+    pub fn has_any_role(&self, roles: &[&str]) -> bool {
+        roles.iter().any(|role| self.has_role(role))
+    }
+
+    /// 是否拥有全部角色（AND）| Whether all of the roles are held (AND)
+    pub fn has_all_roles(&self, roles: &[&str]) -> bool {
+        roles.iter().all(|role| self.has_role(role))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::{RoleHierarchy, SaTokenConfig};
+    use crate::token::DEFAULT_ACCOUNT_TYPE;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_load_snapshot() {
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        manager.set_permissions("10001", vec!["order:*".to_string(), "user:read".to_string()]).await;
+        manager.set_roles("10001", vec!["manager".to_string()]).await;
+        manager.set_role_hierarchy(Some(RoleHierarchy::parse("manager > user").unwrap()));
+
+        let snapshot = PermissionSnapshot::load(&manager, "10001", DEFAULT_ACCOUNT_TYPE).await;
+        assert_eq!(snapshot.login_id(), "10001");
+        assert!(snapshot.has("order:refund"));
+        assert!(!snapshot.has("user:delete"));
+        assert!(snapshot.has_all(&["order:read", "user:read"]));
+        assert!(snapshot.has_any(&["user:delete", "user:read"]));
+        assert!(snapshot.has_all_roles(&["manager", "user"]));
+        assert!(!snapshot.has_any_role(&["admin"]));
+    }
+}
@@ -1,16 +1,24 @@
 pub use crate::{
     SaTokenManager, StpUtil, SaTokenConfig, SaTokenError, SaTokenResult, SaTokenContext,
-    TokenValue, TokenInfo, SaSession, PermissionChecker, LoginId,
+    TokenValue, TokenInfo, SaSession, PermissionChecker, PermissionProvider, MenuItem, LoginId,
     SaTokenEvent, SaTokenEventType, SaTokenListener, SaTokenEventBus, LoggingListener,
-    JwtManager, JwtClaims, JwtAlgorithm,
-    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo,
-    NonceManager, RefreshTokenManager,
+    JwtManager, JwtClaims, JwtAlgorithm, JwksKeyRing, RemoteJwksValidator, JweAlgorithm, JweEncryption,
+    OAuth2Manager, OAuth2Client, AuthorizationCode, AccessToken, OAuth2TokenInfo, PkceMethod, OAuth2TokenRequest, OAuth2ErrorResponse,
+    OAuth2IntrospectionRequest, OAuth2RevocationRequest, OAuth2AuthorizeRequest, OAuth2ConsentDecision, OAuth2AuthorizeResponse, OAuth2Actor,
+    DeviceAuthorization, DeviceCode, DeviceCodeStatus, DEVICE_CODE_GRANT_TYPE, TOKEN_EXCHANGE_GRANT_TYPE, ACCESS_TOKEN_TYPE, ConsentStore, OAuth2Grant, ClientStore, StorageClientStore,
+    OidcProvider, OidcDiscovery, OidcEndpoints, IdTokenClaims, UserInfoProvider, OPENID_SCOPE,
+    NonceManager, RefreshTokenManager, LoginGuard, TotpManager, SaSecureUtil,
     WsAuthManager, WsAuthInfo, WsTokenExtractor, DefaultWsTokenExtractor,
-    OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher,
+    OnlineManager, OnlineUser, PushMessage, MessageType, MessagePusher, InMemoryPusher, TimedOutConnection, OnlineFilter, OnlinePage, OnlineStats,
     DistributedSessionManager, DistributedSession, DistributedSessionStorage, ServiceCredential, InMemoryDistributedStorage,
-    SsoServer, SsoClient, SsoManager, SsoTicket, SsoSession, SsoConfig,
-    router::{match_path, match_any, need_auth, PathAuthConfig, AuthResult, process_auth, create_context},
-    config::TokenStyle,
+    SsoServer, SsoClient, SsoManager, SsoTicket, SsoSession, SsoConfig, SsoLogoutNotifier,
+    TicketStore, StorageTicketStore, InMemoryTicketStore, CasServer, CasAttributeProvider, CasLoginAction,
+    QrLoginManager, QrLoginState, QrTicket, SocialLoginManager, SocialProvider, SocialProfile, SocialBinding, SocialApp,
+    OffSessionManager, ConsentRecord,
+    BodySignVerifier, SaSignManager, SameTokenManager, SAME_TOKEN_HEADER, ApiKeyManager, ApiKeyInfo, API_KEY_HEADER, HttpBasicAuth, CsrfManager, CSRF_HEADER,
+    StpLogic,
+    router::{match_path, match_any, need_auth, PathAuthConfig, SaRouter, AuthResult, process_auth, create_context},
+    config::{TokenStyle, JwtMode},
     token, error,
 };
 
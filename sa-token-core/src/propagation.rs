@@ -0,0 +1,151 @@
+// Author: 金书记
+//
+//! 出站 token 透传 | Outbound token propagation
+//!
+//! 服务间调用时，把当前请求的 token（以及 Same-Token、租户）自动带到下游请求上，
+//! 不必在每个处理函数里手动复制请求头。
+//! For service-to-service calls, attaches the token of the current request (plus the
+//! Same-Token and tenant) to outgoing requests, instead of copying headers in every handler.
+//!
+//! ```text
+//! 上游请求 | Inbound ──▶ 插件中间件设置 SaTokenContext | plugin middleware sets SaTokenContext
+//!                              │
+//!                              ▼
+//! TokenPropagatingClient::get(url) ──▶ <token_name>: <token>
+//!                                      SA-SAME-TOKEN: <same-token>   (same_token 已配置 | when configured)
+//!                                      X-Tenant-Id: <tenant>          (按请求头解析租户时 | header tenant resolver)
+//! ```
+
+use std::sync::Arc;
+use reqwest::{IntoUrl, Method, RequestBuilder};
+use crate::config::SaTokenConfig;
+use crate::context::SaTokenContext;
+use crate::error::SaTokenResult;
+use crate::same_token::SameTokenManager;
+use crate::tenant::{self, TenantResolver};
+
+/// 自动透传 token 的 HTTP 客户端 | HTTP client propagating the token automatically
+///
+/// 当前 token 从 `SaTokenContext` 读取，请在处理函数中（中间件设置上下文之后）创建请求。
+/// 没有登录上下文的后台任务只会带上 Same-Token。
+/// The current token is read from `SaTokenContext`, so build requests inside the handler,
+/// after the middleware has set the context. Background jobs without a login only send the Same-Token.
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let client = TokenPropagatingClient::new(&manager.config)
+///     .same_token(Arc::new(SameTokenManager::new(storage.clone())));
+///
+/// async fn handler(client: TokenPropagatingClient) -> SaTokenResult<String> {
+///     let orders = client.get("http://order-service/api/orders").await?
+///         .send().await
+///         .map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+///     ...
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TokenPropagatingClient {
+    http: reqwest::Client,
+    token_name: String,
+    tenant_header: Option<String>,
+    same_token: Option<Arc<SameTokenManager>>,
+}
+
+impl TokenPropagatingClient {
+    /// 按配置的 token 名称与租户请求头创建客户端 | Create a client using the configured token name and tenant header
+    pub fn new(config: &SaTokenConfig) -> Self {
+        let tenant_header = match &config.tenant_resolver {
+            Some(TenantResolver::Header(name)) => Some(name.clone()),
+            _ => None,
+        };
+        Self {
+            http: reqwest::Client::new(),
+            token_name: config.token_name.clone(),
+            tenant_header,
+            same_token: None,
+        }
+    }
+
+    /// 使用自定义的 reqwest 客户端（超时、代理等）| Use a custom reqwest client (timeouts, proxies, ...)
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// 同时携带 Same-Token，供只允许内部调用的接口校验 | Also send the Same-Token for internal-only routes
+    pub fn same_token(mut self, manager: Arc<SameTokenManager>) -> Self {
+        self.same_token = Some(manager);
+        self
+    }
+
+    /// 创建带透传请求头的请求 | Build a request carrying the propagated headers
+    pub async fn request(&self, method: Method, url: impl IntoUrl) -> SaTokenResult<RequestBuilder> {
+        // 在第一个 await 之前读取上下文 | Read the context before the first await
+        let token = SaTokenContext::get_current().and_then(|ctx| ctx.token);
+        let tenant = tenant::current();
+
+        let mut request = self.http.request(method, url);
+        if let Some(token) = token {
+            request = request.header(&self.token_name, token.as_str());
+        }
+        if let (Some(header), Some(tenant)) = (&self.tenant_header, tenant) {
+            request = request.header(header, tenant);
+        }
+        if let Some(same_token) = &self.same_token {
+            request = request.header(same_token.header_name(), same_token.get_token().await?);
+        }
+        Ok(request)
+    }
+
+    /// 创建 GET 请求 | Build a GET request
+    pub async fn get(&self, url: impl IntoUrl) -> SaTokenResult<RequestBuilder> {
+        self.request(Method::GET, url).await
+    }
+
+    /// 创建 POST 请求 | Build a POST request
+    pub async fn post(&self, url: impl IntoUrl) -> SaTokenResult<RequestBuilder> {
+        self.request(Method::POST, url).await
+    }
+
+    /// 创建 PUT 请求 | Build a PUT request
+    pub async fn put(&self, url: impl IntoUrl) -> SaTokenResult<RequestBuilder> {
+        self.request(Method::PUT, url).await
+    }
+
+    /// 创建 DELETE 请求 | Build a DELETE request
+    pub async fn delete(&self, url: impl IntoUrl) -> SaTokenResult<RequestBuilder> {
+        self.request(Method::DELETE, url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::TokenValue;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_token_propagation() {
+        let config = SaTokenConfig {
+            token_name: "Authorization".to_string(),
+            tenant_resolver: Some(TenantResolver::Header("X-Tenant-Id".to_string())),
+            ..Default::default()
+        };
+        let same_token = Arc::new(SameTokenManager::new(Arc::new(MemoryStorage::new())));
+        let client = TokenPropagatingClient::new(&config).same_token(same_token.clone());
+
+        // 没有登录上下文时只带 Same-Token | Only the Same-Token without a login context
+        let request = client.get("http://order-service/api/orders").await.unwrap().build().unwrap();
+        assert!(request.headers().get("Authorization").is_none());
+        assert!(same_token.is_valid(request.headers()["SA-SAME-TOKEN"].to_str().unwrap()).await);
+
+        let mut ctx = SaTokenContext::new();
+        ctx.token = Some(TokenValue::new("token-1001"));
+        SaTokenContext::set_current(ctx);
+        let request = tenant::scope(Some("acme".to_string()), client.post("http://order-service/api/orders"))
+            .await.unwrap().build().unwrap();
+        SaTokenContext::clear();
+        assert_eq!(request.headers()["Authorization"], "token-1001");
+        assert_eq!(request.headers()["X-Tenant-Id"], "acme");
+    }
+}
@@ -0,0 +1,296 @@
+// Author: 金书记
+//
+//! 扫码登录 | QR-Code Login
+//!
+//! 网页端展示二维码，已登录的移动端扫码并确认后，网页端用票据换取自己的会话。
+//! The web client shows a QR code; a logged-in mobile client scans and confirms it, then the web
+//! client exchanges the ticket for a session of its own.
+//!
+//! ```text
+//! 网页端 | Web                 移动端 | Mobile                  QrLoginManager
+//!   create()  ──────────────────────────────────────────────▶  Waiting
+//!   展示二维码 | show QR(ticket_id)
+//!                               scan(ticket, mobile_token)  ─▶  Scanned
+//!   wait(ticket, Waiting) ◀─────────────────────────────────── （状态变化 | state changed）
+//!                               confirm(ticket, mobile_token) ▶ Confirmed
+//!   wait(ticket, Scanned) ◀───────────────────────────────────
+//!   exchange(ticket) ────────────────────────────────────────▶ login(login_id) → token
+//! ```
+//!
+//! 票据保存在 `SaStorage` 中（`sa:qr:{ticket}`），多实例共享；过期后状态为 `Expired`，换取会话后立即失效。
+//! Tickets live in `SaStorage` (`sa:qr:{ticket}`), so every instance shares them. They read as
+//! `Expired` after the timeout and become unusable once exchanged.
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc, Duration as ChronoDuration};
+use serde::{Serialize, Deserialize};
+use crate::{SaTokenError, SaTokenResult, SaTokenManager};
+use crate::id::IdKind;
+use crate::token::TokenValue;
+
+/// 扫码登录票据的状态 | State of a QR login ticket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QrLoginState {
+    /// 等待扫码 | Waiting for a scan
+    Waiting,
+    /// 已扫码，等待确认 | Scanned, waiting for confirmation
+    Scanned,
+    /// 已确认，可换取会话 | Confirmed, ready to exchange
+    Confirmed,
+    /// 移动端取消 | Canceled on the mobile client
+    Canceled,
+    /// 已过期或已使用 | Expired or already used
+    Expired,
+}
+
+impl QrLoginState {
+    /// 名称 | Name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QrLoginState::Waiting => "waiting",
+            QrLoginState::Scanned => "scanned",
+            QrLoginState::Confirmed => "confirmed",
+            QrLoginState::Canceled => "canceled",
+            QrLoginState::Expired => "expired",
+        }
+    }
+}
+
+/// 扫码登录票据 | QR login ticket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QrTicket {
+    /// 票据 ID，编码在二维码中 | Ticket ID, encoded in the QR code
+    pub ticket_id: String,
+    /// 当前状态 | Current state
+    pub state: QrLoginState,
+    /// 扫码账号的登录 ID | Login ID of the scanning account
+    pub login_id: Option<String>,
+    /// 扫码账号的登录类型 | Login type of the scanning account
+    pub login_type: Option<String>,
+    /// 创建时间 | Creation time
+    pub create_time: DateTime<Utc>,
+    /// 过期时间 | Expiration time
+    pub expire_time: DateTime<Utc>,
+}
+
+impl QrTicket {
+    fn expired(ticket_id: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            ticket_id: ticket_id.to_string(),
+            state: QrLoginState::Expired,
+            login_id: None,
+            login_type: None,
+            create_time: now,
+            expire_time: now,
+        }
+    }
+}
+
+/// 扫码登录管理器 | QR login manager
+#[derive(Clone)]
+pub struct QrLoginManager {
+    manager: Arc<SaTokenManager>,
+    timeout: i64,
+    poll_interval: Duration,
+    device: String,
+}
+
+impl QrLoginManager {
+    /// 创建管理器（票据有效期 120 秒）| Create a manager (tickets live 120 seconds)
+    pub fn new(manager: Arc<SaTokenManager>) -> Self {
+        Self {
+            manager,
+            timeout: 120,
+            poll_interval: Duration::from_millis(500),
+            device: "web".to_string(),
+        }
+    }
+
+    /// 设置票据有效期（秒）| Set the ticket lifetime in seconds
+    pub fn with_timeout(mut self, seconds: i64) -> Self {
+        self.timeout = seconds.max(1);
+        self
+    }
+
+    /// 设置长轮询检查存储的间隔 | Set how often long-polling checks the storage
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 设置网页端会话的设备标识（默认 `web`）| Set the device of the web session (`web` by default)
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = device.into();
+        self
+    }
+
+    /// 创建票据 | Create a ticket
+    pub async fn create(&self) -> SaTokenResult<QrTicket> {
+        let now = Utc::now();
+        let ticket = QrTicket {
+            ticket_id: self.manager.id_generator().generate(IdKind::QrTicket),
+            state: QrLoginState::Waiting,
+            login_id: None,
+            login_type: None,
+            create_time: now,
+            expire_time: now + ChronoDuration::seconds(self.timeout),
+        };
+        self.save(&ticket).await?;
+        Ok(ticket)
+    }
+
+    /// 查询票据状态，不存在或已过期时返回 `Expired` | Get a ticket, `Expired` when missing or timed out
+    pub async fn status(&self, ticket_id: &str) -> SaTokenResult<QrTicket> {
+        let value = self.manager.storage.get(&Self::ticket_key(ticket_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        let ticket = value.and_then(|v| serde_json::from_str::<QrTicket>(&v).ok())
+            .filter(|t| Utc::now() <= t.expire_time);
+        Ok(ticket.unwrap_or_else(|| QrTicket::expired(ticket_id)))
+    }
+
+    /// 移动端扫码 | Scan on the mobile client
+    ///
+    /// # 参数 | Parameters
+    /// * `ticket_id` - 二维码中的票据 ID | Ticket ID from the QR code
+    /// * `mobile_token` - 移动端已登录的 token | The mobile client's logged-in token
+    pub async fn scan(&self, ticket_id: &str, mobile_token: &TokenValue) -> SaTokenResult<QrTicket> {
+        let info = self.manager.get_token_info(mobile_token).await?;
+        let mut ticket = self.expect_state(ticket_id, QrLoginState::Waiting).await?;
+        ticket.state = QrLoginState::Scanned;
+        ticket.login_id = Some(info.login_id);
+        ticket.login_type = Some(info.login_type);
+        self.save(&ticket).await?;
+        Ok(ticket)
+    }
+
+    /// 移动端确认登录，须与扫码账号相同 | Confirm on the mobile client; must be the account that scanned
+    pub async fn confirm(&self, ticket_id: &str, mobile_token: &TokenValue) -> SaTokenResult<QrTicket> {
+        self.finish_scan(ticket_id, mobile_token, QrLoginState::Confirmed).await
+    }
+
+    /// 移动端取消登录 | Cancel on the mobile client
+    pub async fn cancel(&self, ticket_id: &str, mobile_token: &TokenValue) -> SaTokenResult<QrTicket> {
+        self.finish_scan(ticket_id, mobile_token, QrLoginState::Canceled).await
+    }
+
+    /// 长轮询：等待状态不再是 `last_state`，或超过 `wait` | Long-poll until the state leaves `last_state` or `wait` elapses
+    ///
+    /// # 参数 | Parameters
+    /// * `ticket_id` - 票据 ID | Ticket ID
+    /// * `last_state` - 网页端已知的状态 | The state the web client already knows
+    /// * `wait` - 最长等待时间 | Maximum time to wait
+    pub async fn wait(&self, ticket_id: &str, last_state: QrLoginState, wait: Duration) -> SaTokenResult<QrTicket> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let ticket = self.status(ticket_id).await?;
+            if ticket.state != last_state || tokio::time::Instant::now() >= deadline {
+                return Ok(ticket);
+            }
+            tokio::time::sleep(self.poll_interval.min(deadline.saturating_duration_since(tokio::time::Instant::now()))).await;
+        }
+    }
+
+    /// 网页端用已确认的票据换取会话，票据随即失效 | Exchange a confirmed ticket for a web session; the ticket is then used up
+    pub async fn exchange(&self, ticket_id: &str) -> SaTokenResult<TokenValue> {
+        let ticket = self.expect_state(ticket_id, QrLoginState::Confirmed).await?;
+        let (Some(login_id), Some(login_type)) = (ticket.login_id, ticket.login_type) else {
+            return Err(SaTokenError::InternalError("confirmed QR ticket has no login_id".to_string()));
+        };
+
+        // 多个实例同时换取时只有一个成功 | Only one of several concurrent exchanges succeeds
+        let ttl = Some(Duration::from_secs(self.timeout as u64));
+        let claimed = self.manager.storage.set_nx(&Self::claim_key(ticket_id), "1", ttl).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            return Err(SaTokenError::QrTicketStateMismatch(QrLoginState::Expired.as_str().to_string()));
+        }
+        self.manager.storage.delete(&Self::ticket_key(ticket_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        self.manager.login_with_options(login_id, Some(login_type), Some(self.device.clone()), None, None, None).await
+    }
+
+    async fn finish_scan(&self, ticket_id: &str, mobile_token: &TokenValue, state: QrLoginState) -> SaTokenResult<QrTicket> {
+        let info = self.manager.get_token_info(mobile_token).await?;
+        let mut ticket = self.expect_state(ticket_id, QrLoginState::Scanned).await?;
+        if ticket.login_id.as_deref() != Some(info.login_id.as_str())
+            || ticket.login_type.as_deref() != Some(info.login_type.as_str()) {
+            return Err(SaTokenError::PermissionDenied);
+        }
+        ticket.state = state;
+        self.save(&ticket).await?;
+        Ok(ticket)
+    }
+
+    async fn expect_state(&self, ticket_id: &str, expected: QrLoginState) -> SaTokenResult<QrTicket> {
+        let ticket = self.status(ticket_id).await?;
+        if ticket.state != expected {
+            return Err(SaTokenError::QrTicketStateMismatch(ticket.state.as_str().to_string()));
+        }
+        Ok(ticket)
+    }
+
+    async fn save(&self, ticket: &QrTicket) -> SaTokenResult<()> {
+        let remaining = (ticket.expire_time - Utc::now()).num_seconds().max(1) as u64;
+        let value = serde_json::to_string(ticket)?;
+        self.manager.storage.set(&Self::ticket_key(&ticket.ticket_id), &value, Some(Duration::from_secs(remaining))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    fn ticket_key(ticket_id: &str) -> String {
+        format!("sa:qr:{}", ticket_id)
+    }
+
+    fn claim_key(ticket_id: &str) -> String {
+        format!("sa:qr:claimed:{}", ticket_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_qr_login_flow() {
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let qr = QrLoginManager::new(manager.clone()).with_poll_interval(Duration::from_millis(10));
+        let phone = manager.login_with_options("1001", None, Some("app".to_string()), None, None, None).await.unwrap();
+        let stranger = manager.login("2002").await.unwrap();
+
+        let ticket = qr.create().await.unwrap();
+        assert_eq!(ticket.state, QrLoginState::Waiting);
+        assert!(matches!(qr.exchange(&ticket.ticket_id).await, Err(SaTokenError::QrTicketStateMismatch(_))));
+
+        // 网页端长轮询，移动端扫码后返回 | The web client long-polls and wakes up after the scan
+        let waiter = {
+            let qr = qr.clone();
+            let id = ticket.ticket_id.clone();
+            tokio::spawn(async move { qr.wait(&id, QrLoginState::Waiting, Duration::from_secs(5)).await })
+        };
+        qr.scan(&ticket.ticket_id, &phone).await.unwrap();
+        let seen = waiter.await.unwrap().unwrap();
+        assert_eq!(seen.state, QrLoginState::Scanned);
+        assert_eq!(seen.login_id.as_deref(), Some("1001"));
+
+        // 只有扫码的账号可以确认 | Only the scanning account can confirm
+        assert!(matches!(qr.confirm(&ticket.ticket_id, &stranger).await, Err(SaTokenError::PermissionDenied)));
+        qr.confirm(&ticket.ticket_id, &phone).await.unwrap();
+
+        let web = qr.exchange(&ticket.ticket_id).await.unwrap();
+        let info = manager.get_token_info(&web).await.unwrap();
+        assert_eq!(info.login_id, "1001");
+        assert_eq!(info.device.as_deref(), Some("web"));
+        assert_eq!(qr.status(&ticket.ticket_id).await.unwrap().state, QrLoginState::Expired);
+        assert!(qr.exchange(&ticket.ticket_id).await.is_err());
+
+        // 未变化时长轮询到时返回原状态 | Long-polling returns the unchanged state at the deadline
+        let idle = qr.create().await.unwrap();
+        let seen = qr.wait(&idle.ticket_id, QrLoginState::Waiting, Duration::from_millis(30)).await.unwrap();
+        assert_eq!(seen.state, QrLoginState::Waiting);
+    }
+}
@@ -0,0 +1,156 @@
+// Author: 金书记
+//
+//! 限流 | Rate Limiting
+//!
+//! 按路径模式配置规则，按客户端 IP 或 login_id 计数，计数保存在 `SaStorage` 中，多实例共享。
+//! Rules are configured per path pattern and count per client IP or login_id.
+//! Counters live in `SaStorage`, so every instance sees the same numbers.
+//!
+//! ```text
+//! PathAuthConfig::new()
+//!     .rate_limit(RateLimitRule::per_ip("/api/login", 10, 60))
+//!     .rate_limit(RateLimitRule::per_login_id("/api/**", 100, 60))
+//!
+//! 请求 | Request → 匹配的每条规则 | every matching rule
+//!   └─→ 滑动窗口计数 | sliding window count
+//!         = 上一窗口计数 × 未过去的比例 + 当前窗口计数
+//!         = previous window × share not yet elapsed + current window
+//!         ├─→ <= limit → 放行 | pass
+//!         └─→ >  limit → RateLimited(retry_after) → 429
+//! ```
+//!
+//! 每个窗口一个计数键（`sa:rate:...`），TTL 为两个窗口，由存储自动清理。
+//! Each window has one counter key (`sa:rate:...`) with a TTL of two windows, expired by the storage.
+
+use std::time::Duration;
+use chrono::Utc;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::router::CompiledPattern;
+
+/// 限流计数的维度 | What a rate limit counts by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKey {
+    /// 客户端 IP | Client IP
+    Ip,
+    /// 登录 ID，未登录的请求不计数 | Login ID, anonymous requests are not counted
+    LoginId,
+}
+
+impl RateLimitKey {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitKey::Ip => "ip",
+            RateLimitKey::LoginId => "login",
+        }
+    }
+}
+
+/// 限流规则 | Rate limit rule
+#[derive(Debug, Clone)]
+pub struct RateLimitRule {
+    pattern: CompiledPattern,
+    key: RateLimitKey,
+    limit: u64,
+    window: i64,
+}
+
+impl RateLimitRule {
+    /// 创建规则 | Create a rule
+    ///
+    /// # 参数 | Parameters
+    /// * `pattern` - 路径模式，如 `/api/**` | Path pattern, e.g. `/api/**`
+    /// * `key` - 计数维度 | What to count by
+    /// * `limit` - 窗口内允许的请求数 | Requests allowed per window
+    /// * `window` - 窗口长度（秒）| Window length in seconds
+    pub fn new(pattern: &str, key: RateLimitKey, limit: u64, window: i64) -> Self {
+        Self {
+            pattern: CompiledPattern::new(pattern),
+            key,
+            limit,
+            window: window.max(1),
+        }
+    }
+
+    /// 按客户端 IP 限流 | Limit per client IP
+    pub fn per_ip(pattern: &str, limit: u64, window: i64) -> Self {
+        Self::new(pattern, RateLimitKey::Ip, limit, window)
+    }
+
+    /// 按登录 ID 限流 | Limit per login ID
+    pub fn per_login_id(pattern: &str, limit: u64, window: i64) -> Self {
+        Self::new(pattern, RateLimitKey::LoginId, limit, window)
+    }
+
+    /// 计数维度 | What the rule counts by
+    pub fn key(&self) -> RateLimitKey {
+        self.key
+    }
+
+    /// 路径是否适用此规则 | Whether the rule applies to a path
+    pub fn matches(&self, path: &str) -> bool {
+        self.pattern.matches(path)
+    }
+
+    /// 记录一次请求并检查是否超限 | Count one request and check the limit
+    ///
+    /// # 参数 | Parameters
+    /// * `storage` - 计数存储 | Counter storage
+    /// * `subject` - IP 或登录 ID | IP or login ID
+    ///
+    /// 超限时返回 `RateLimited`，携带建议的重试秒数
+    /// Returns `RateLimited` with the suggested retry delay in seconds when over the limit
+    pub async fn hit(&self, storage: &dyn SaStorage, subject: &str) -> SaTokenResult<()> {
+        let window_ms = self.window * 1000;
+        let now = Utc::now().timestamp_millis();
+        let (index, elapsed) = (now / window_ms, now % window_ms);
+
+        let current_key = self.counter_key(subject, index);
+        let current = storage.incr(&current_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if current == 1 {
+            storage.expire(&current_key, Duration::from_secs(self.window as u64 * 2)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        let previous = storage.get(&self.counter_key(subject, index - 1)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(0);
+
+        let estimate = previous as f64 * (window_ms - elapsed) as f64 / window_ms as f64 + current as f64;
+        if estimate > self.limit as f64 {
+            let retry_after = ((window_ms - elapsed) as u64).div_ceil(1000).max(1);
+            return Err(SaTokenError::RateLimited(retry_after));
+        }
+        Ok(())
+    }
+
+    fn counter_key(&self, subject: &str, index: i64) -> String {
+        format!("sa:rate:{}:{}:{}:{}", self.pattern.as_str(), self.key.as_str(), subject, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_rate_limit_rule() {
+        let storage = MemoryStorage::new();
+        let rule = RateLimitRule::per_ip("/api/login", 3, 60);
+        assert!(rule.matches("/api/login"));
+        assert!(!rule.matches("/api/user"));
+
+        for _ in 0..3 {
+            rule.hit(&storage, "10.0.0.1").await.unwrap();
+        }
+        match rule.hit(&storage, "10.0.0.1").await {
+            Err(SaTokenError::RateLimited(retry_after)) => assert!((1..=60).contains(&retry_after)),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+
+        // 其他 IP 单独计数 | Other IPs have their own counter
+        rule.hit(&storage, "10.0.0.2").await.unwrap();
+    }
+}
@@ -4,16 +4,39 @@
 //!
 //! Implements token refresh mechanism for long-term authentication
 //! 实现长期认证的 Token 刷新机制
+//!
+//! With rotation every refresh issues a new refresh token and invalidates the old one. All tokens
+//! descended from one login form a family; presenting a token that was already used means it was
+//! copied, so the whole family is revoked.
+//! 轮换模式下每次刷新都会签发新的 refresh token 并作废旧的。同一次登录派生的 token 属于同一个家族；
+//! 出现已使用过的 token 说明它已被复制，此时整个家族都会被撤销。
+//!
+//! ```text
+//! store(rt1)                      family F = {rt1}
+//! refresh_with_rotation(rt1) → rt2   F = {rt1(used), rt2}
+//! refresh_with_rotation(rt1)      → RefreshTokenReused, F revoked (rt2 no longer works)
+//! ```
 
 use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration};
 use sa_token_adapter::storage::SaStorage;
 use crate::error::{SaTokenError, SaTokenResult};
 use crate::token::TokenValue;
-use crate::token::TokenGenerator;
+use crate::token::DefaultTokenGenerator;
 use crate::config::SaTokenConfig;
 use uuid::Uuid;
 
+/// Result of a rotating refresh | 轮换刷新的结果
+#[derive(Debug, Clone)]
+pub struct RotatedTokens {
+    /// New access token | 新的访问令牌
+    pub access_token: TokenValue,
+    /// New refresh token, the presented one is now used up | 新的 refresh token，旧的已作废
+    pub refresh_token: String,
+    /// User login ID | 用户登录ID
+    pub login_id: String,
+}
+
 /// Refresh Token Manager | Refresh Token 管理器
 ///
 /// Manages refresh token generation, validation, and access token renewal
@@ -66,6 +89,25 @@ impl RefreshTokenManager {
         refresh_token: &str,
         access_token: &str,
         login_id: &str,
+    ) -> SaTokenResult<()> {
+        let family = Uuid::new_v4().simple().to_string();
+        self.store_in_family(refresh_token, access_token, login_id, &family).await
+    }
+
+    /// Store refresh token as a member of an existing token family | 将 refresh token 存入已有的 token 家族
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `refresh_token` - Refresh token | Refresh token
+    /// * `access_token` - Associated access token | 关联的访问令牌
+    /// * `login_id` - User login ID | 用户登录ID
+    /// * `family` - Token family ID | Token 家族 ID
+    pub async fn store_in_family(
+        &self,
+        refresh_token: &str,
+        access_token: &str,
+        login_id: &str,
+        family: &str,
     ) -> SaTokenResult<()> {
         let key = format!("sa:refresh:{}", refresh_token);
         let expire_time = if self.config.refresh_token_timeout > 0 {
@@ -77,6 +119,7 @@ impl RefreshTokenManager {
         let value = serde_json::json!({
             "access_token": access_token,
             "login_id": login_id,
+            "family": family,
             "created_at": Utc::now().to_rfc3339(),
             "expire_time": expire_time.map(|t| t.to_rfc3339()),
         }).to_string();
@@ -91,6 +134,16 @@ impl RefreshTokenManager {
             .await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
 
+        let family_key = Self::family_key(family);
+        self.storage.sadd(&family_key, refresh_token)
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if let Some(ttl) = ttl {
+            self.storage.expire(&family_key, ttl)
+                .await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+
         Ok(())
     }
 
@@ -109,13 +162,13 @@ impl RefreshTokenManager {
         let value_str = self.storage.get(&key)
             .await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::RefreshTokenNotFound)?;
+            .ok_or(SaTokenError::RefreshTokenNotFound)?;
 
         let value: serde_json::Value = serde_json::from_str(&value_str)
             .map_err(|_| SaTokenError::RefreshTokenInvalidData)?;
 
         let login_id = value["login_id"].as_str()
-            .ok_or_else(|| SaTokenError::RefreshTokenMissingLoginId)?
+            .ok_or(SaTokenError::RefreshTokenMissingLoginId)?
             .to_string();
 
         // Check expiration if set
@@ -151,14 +204,14 @@ impl RefreshTokenManager {
         let login_id = self.validate(refresh_token).await?;
 
         // Generate new access token
-        let new_access_token = TokenGenerator::generate_with_login_id(&self.config, &login_id);
+        let new_access_token = DefaultTokenGenerator::generate_with_login_id(&self.config, &login_id);
 
         // Update stored refresh token with new access token
         let key = format!("sa:refresh:{}", refresh_token);
         let value_str = self.storage.get(&key)
             .await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?
-            .ok_or_else(|| SaTokenError::RefreshTokenNotFound)?;
+            .ok_or(SaTokenError::RefreshTokenNotFound)?;
 
         let mut value: serde_json::Value = serde_json::from_str(&value_str)
             .map_err(|_| SaTokenError::RefreshTokenInvalidData)?;
@@ -179,6 +232,107 @@ impl RefreshTokenManager {
         Ok((new_access_token, login_id))
     }
 
+    /// Refresh with rotation | 轮换刷新
+    ///
+    /// Issues a new access token and a new refresh token in the same family, and marks the
+    /// presented refresh token as used. Presenting a used token revokes the whole family.
+    /// 签发新的访问令牌和同一家族的新 refresh token，并将传入的 refresh token 标记为已使用。
+    /// 传入已使用的 token 会撤销整个家族。
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `refresh_token` - Refresh token | Refresh token
+    ///
+    /// # Returns | 返回
+    ///
+    /// New token pair, or `RefreshTokenReused` on reuse | 新的令牌对，重复使用时返回 `RefreshTokenReused`
+    pub async fn refresh_with_rotation(&self, refresh_token: &str) -> SaTokenResult<RotatedTokens> {
+        let (login_id, family) = self.consume(refresh_token).await?;
+
+        let access_token = DefaultTokenGenerator::generate_with_login_id(&self.config, &login_id);
+        let new_refresh_token = self.generate(&login_id);
+        self.store_in_family(&new_refresh_token, access_token.as_str(), &login_id, &family).await?;
+
+        Ok(RotatedTokens {
+            access_token,
+            refresh_token: new_refresh_token,
+            login_id,
+        })
+    }
+
+    /// Mark a refresh token as used and return its login_id and family | 将 refresh token 标记为已使用，返回其 login_id 和家族
+    ///
+    /// Used by callers that issue the new access token themselves, followed by `store_in_family`.
+    /// 供自行签发访问令牌的调用方使用，之后调用 `store_in_family`。
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `refresh_token` - Refresh token | Refresh token
+    pub async fn consume(&self, refresh_token: &str) -> SaTokenResult<(String, String)> {
+        let login_id = self.validate(refresh_token).await?;
+
+        let key = format!("sa:refresh:{}", refresh_token);
+        let value_str = self.storage.get(&key)
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::RefreshTokenNotFound)?;
+        let mut value: serde_json::Value = serde_json::from_str(&value_str)
+            .map_err(|_| SaTokenError::RefreshTokenInvalidData)?;
+
+        // Tokens stored before rotation existed form a family of their own
+        // 轮换功能之前存储的 token 自成一个家族
+        let family = value["family"].as_str().unwrap_or(refresh_token).to_string();
+
+        // Concurrent refreshes with the same token count as reuse as well
+        // 同一 token 的并发刷新同样视为重复使用
+        let claim_key = format!("sa:refresh:claimed:{}", refresh_token);
+        let claimed = value["used_at"].is_null() && self.storage.set_nx(&claim_key, "1", self.ttl())
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            tracing::warn!("Refresh token reused for login_id {}, revoking token family {}", login_id, family);
+            self.revoke_family(&family).await?;
+            return Err(SaTokenError::RefreshTokenReused);
+        }
+
+        // Keep the used record so a later replay is detected
+        // 保留已使用的记录，以便识别之后的重放
+        value["used_at"] = serde_json::json!(Utc::now().to_rfc3339());
+        self.storage.set(&key, &value.to_string(), self.ttl())
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        Ok((login_id, family))
+    }
+
+    /// Revoke every refresh token of a token family | 撤销 token 家族中的全部 refresh token
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `family` - Token family ID | Token 家族 ID
+    pub async fn revoke_family(&self, family: &str) -> SaTokenResult<()> {
+        let family_key = Self::family_key(family);
+        let tokens = self.storage.smembers(&family_key)
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        for token in tokens.iter().chain(std::iter::once(&family.to_string())) {
+            self.delete(token).await?;
+        }
+        self.storage.delete(&family_key)
+            .await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn family_key(family: &str) -> String {
+        format!("sa:refresh:family:{}", family)
+    }
+
+    fn ttl(&self) -> Option<std::time::Duration> {
+        (self.config.refresh_token_timeout > 0)
+            .then(|| std::time::Duration::from_secs(self.config.refresh_token_timeout as u64))
+    }
+
     /// Delete refresh token | 删除 refresh token
     ///
     /// # Arguments | 参数
@@ -299,5 +453,25 @@ mod tests {
         let result = refresh_mgr.validate(&refresh_token).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_refresh_with_rotation() {
+        let storage = Arc::new(MemoryStorage::new());
+        let config = create_test_config();
+        let refresh_mgr = RefreshTokenManager::new(storage, config);
+
+        let rt1 = refresh_mgr.generate("user_123");
+        refresh_mgr.store(&rt1, "access", "user_123").await.unwrap();
+
+        let rotated = refresh_mgr.refresh_with_rotation(&rt1).await.unwrap();
+        assert_eq!(rotated.login_id, "user_123");
+        assert_ne!(rotated.refresh_token, rt1);
+        let rt3 = refresh_mgr.refresh_with_rotation(&rotated.refresh_token).await.unwrap().refresh_token;
+
+        // Replaying a used token revokes the whole family | 重放已使用的 token 会撤销整个家族
+        assert!(matches!(refresh_mgr.refresh_with_rotation(&rt1).await, Err(SaTokenError::RefreshTokenReused)));
+        assert!(refresh_mgr.validate(&rt3).await.is_err());
+        assert!(refresh_mgr.refresh_with_rotation(&rt3).await.is_err());
+    }
 }
 
@@ -0,0 +1,69 @@
+// Author: 金书记
+//
+//! 记住我 | Remember Me
+//!
+//! 登录时额外签发一个长期有效的 remember-me token，保存在单独的 cookie 中。
+//! 会话 token 过期后，插件用它静默重建会话，并每次轮换 remember-me token。
+//! At login an extra long-lived remember-me token is issued and kept in a separate cookie.
+//! Once the short-lived session token expires, the plugins use it to silently re-create a
+//! session, rotating the remember-me token on every use.
+//!
+//! ```text
+//! 登录 | Login      login(id) → session token
+//!                   remember_me(session) → remember token（sa:remember:{token}，TTL = remember_me_timeout）
+//!
+//! 请求 | Request    session 无效 | invalid session + remember cookie
+//!                   └─→ restore_remembered → 删除旧 token | delete the old token
+//!                                          → login(id) → 新 session | new session
+//!                                          → 新 remember token | new remember token
+//!                                          → Set-Cookie × 2
+//!
+//! 登出所有设备 | Logout all devices → 撤销该账号全部 remember token | revoke every remember token of the account
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sa_token_adapter::build_cookie_string;
+use crate::config::SaCookieConfig;
+use crate::token::TokenValue;
+
+/// Remember-me token 记录 | Remember-me token record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RememberMeRecord {
+    /// 登录 ID | Login ID
+    pub login_id: String,
+    /// 登录类型 | Login type
+    pub login_type: String,
+    /// 登录设备 | Login device
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// 签发时间 | Issue time
+    pub create_time: DateTime<Utc>,
+}
+
+impl RememberMeRecord {
+    pub(crate) fn key(token: &str) -> String {
+        format!("sa:remember:{}", token)
+    }
+
+    pub(crate) fn index_key(namespaced_login_id: &str) -> String {
+        format!("sa:remember:index:{}", namespaced_login_id)
+    }
+}
+
+/// 用 remember-me token 重建的会话 | Session re-created from a remember-me token
+#[derive(Debug, Clone)]
+pub struct RememberedSession {
+    /// 新的会话 token | New session token
+    pub token: TokenValue,
+    /// 轮换后的 remember-me token | Rotated remember-me token
+    pub remember_token: String,
+    /// 写回客户端的 `Set-Cookie` 值（会话 cookie 与 remember-me cookie）
+    /// `Set-Cookie` values for the client (session cookie and remember-me cookie)
+    pub set_cookies: Vec<String>,
+}
+
+/// 按 cookie 配置构建 `Set-Cookie` 值 | Build a `Set-Cookie` value from the cookie configuration
+pub(crate) fn cookie(config: &SaCookieConfig, name: &str, value: &str, max_age: Option<i64>) -> String {
+    build_cookie_string(name, value, config.options(max_age))
+}
@@ -0,0 +1,274 @@
+// Author: 金书记
+//
+//! 响应信封 | Response Envelope
+//!
+//! 内置中间件、提取器和拒绝响应使用的 JSON 结构，字段名可配置。
+//! JSON shape used by the built-in middleware, extractors and rejections, with configurable field names.
+//!
+//! ```text
+//! 默认 | Default:      {"code": 401, "message": "Authentication error"}
+//! 自定义 | Customized:  {"status": 401, "msg": "Authentication error"}
+//! ```
+//!
+//! 信封是全局的：通过 `SaTokenConfigBuilder::response_envelope` 或 `ResponseEnvelope::install`
+//! 设置一次后，所有插件的内置响应都会使用它。
+//! The envelope is global: once set through `SaTokenConfigBuilder::response_envelope` or
+//! `ResponseEnvelope::install`, every plugin's built-in responses use it.
+//!
+//! 只改字段名不够时，用 `ErrorRenderer` 完全接管拒绝响应的状态码、响应头与响应体。
+//! 渲染器保存在各插件的 `SaTokenState` 中，只作用于该状态下的中间件与提取器。
+//! When renaming fields is not enough, an `ErrorRenderer` takes over the status, headers and body
+//! of every rejection. The renderer lives on each plugin's `SaTokenState` and only applies to the
+//! middlewares and extractors of that state.
+
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::error::{messages, SaTokenError};
+
+static GLOBAL_ENVELOPE: Lazy<RwLock<Arc<ResponseEnvelope>>> =
+    Lazy::new(|| RwLock::new(Arc::new(ResponseEnvelope::default())));
+
+/// 响应信封配置 | Response envelope configuration
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::response::ResponseEnvelope;
+///
+/// let envelope = ResponseEnvelope::new()
+///     .code_field("status")
+///     .message_field("msg")
+///     .data_field("result");
+/// let body = envelope.error(401, "not logged in");
+/// assert_eq!(body["status"], 401);
+/// assert_eq!(body["msg"], "not logged in");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseEnvelope {
+    /// 状态码字段名 | Code field name
+    pub code_field: String,
+    /// 消息字段名 | Message field name
+    pub message_field: String,
+    /// 数据字段名 | Data field name
+    pub data_field: String,
+    /// 成功响应的状态码 | Code of successful responses
+    pub success_code: i64,
+    /// 成功响应的消息 | Message of successful responses
+    pub success_message: String,
+}
+
+impl Default for ResponseEnvelope {
+    fn default() -> Self {
+        Self {
+            code_field: "code".to_string(),
+            message_field: "message".to_string(),
+            data_field: "data".to_string(),
+            success_code: 200,
+            success_message: "ok".to_string(),
+        }
+    }
+}
+
+impl ResponseEnvelope {
+    /// 使用默认字段名创建（`code` / `message` / `data`）| Create with default field names
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置状态码字段名 | Set the code field name
+    pub fn code_field(mut self, name: impl Into<String>) -> Self {
+        self.code_field = name.into();
+        self
+    }
+
+    /// 设置消息字段名 | Set the message field name
+    pub fn message_field(mut self, name: impl Into<String>) -> Self {
+        self.message_field = name.into();
+        self
+    }
+
+    /// 设置数据字段名 | Set the data field name
+    pub fn data_field(mut self, name: impl Into<String>) -> Self {
+        self.data_field = name.into();
+        self
+    }
+
+    /// 设置成功响应的状态码 | Set the code of successful responses
+    pub fn success_code(mut self, code: i64) -> Self {
+        self.success_code = code;
+        self
+    }
+
+    /// 设置成功响应的消息 | Set the message of successful responses
+    pub fn success_message(mut self, message: impl Into<String>) -> Self {
+        self.success_message = message.into();
+        self
+    }
+
+    /// 构建错误响应体，状态码与 HTTP 状态一致 | Build an error body, the code equals the HTTP status
+    ///
+    /// # 参数 | Parameters
+    /// * `code` - HTTP 状态码 | HTTP status code
+    /// * `message` - 错误信息 | Error message
+    pub fn error(&self, code: u16, message: impl Display) -> Value {
+        let mut body = Map::new();
+        body.insert(self.code_field.clone(), Value::from(code));
+        body.insert(self.message_field.clone(), Value::from(message.to_string()));
+        Value::Object(body)
+    }
+
+    /// 构建成功响应体 | Build a success body
+    pub fn success(&self, data: Value) -> Value {
+        let mut body = Map::new();
+        body.insert(self.code_field.clone(), Value::from(self.success_code));
+        body.insert(self.message_field.clone(), Value::from(self.success_message.clone()));
+        body.insert(self.data_field.clone(), data);
+        Value::Object(body)
+    }
+
+    /// 设为全局信封 | Install as the global envelope
+    pub fn install(self) {
+        *GLOBAL_ENVELOPE.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(self);
+    }
+
+    /// 获取全局信封 | Get the global envelope
+    pub fn global() -> Arc<ResponseEnvelope> {
+        GLOBAL_ENVELOPE.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// 使用全局信封构建错误响应体 | Build an error body with the global envelope
+pub fn error_json(code: u16, message: impl Display) -> Value {
+    ResponseEnvelope::global().error(code, message)
+}
+
+/// 使用全局信封构建错误响应体字符串 | Build an error body string with the global envelope
+pub fn error_body(code: u16, message: impl Display) -> String {
+    error_json(code, message).to_string()
+}
+
+/// 使用全局信封构建成功响应体 | Build a success body with the global envelope
+pub fn success_json(data: Value) -> Value {
+    ResponseEnvelope::global().success(data)
+}
+
+/// 拒绝响应渲染器 | Rejection response renderer
+///
+/// 插件的中间件与提取器在拒绝请求时调用它，返回 HTTP 状态码、响应头与响应体。
+/// 通过 `SaTokenConfigBuilder::error_renderer`（或插件状态构建器的同名方法）设置，
+/// 保存在 `SaTokenManager` 与插件的 `SaTokenState` 中，不同状态可以使用不同的渲染器。
+/// Called by a plugin's middlewares and extractors when they reject a request;
+/// returns the HTTP status, headers and body. Set it with `SaTokenConfigBuilder::error_renderer`
+/// (or the plugin state builder's method of the same name); it is kept on the `SaTokenManager` and
+/// the plugin's `SaTokenState`, so different states can use different renderers.
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::response::ErrorRenderer;
+/// use sa_token_core::SaTokenError;
+///
+/// struct CompanyEnvelope;
+///
+/// impl ErrorRenderer for CompanyEnvelope {
+///     fn render(&self, error: &SaTokenError) -> (u16, Vec<(String, String)>, String) {
+///         let body = serde_json::json!({ "success": false, "error": { "reason": error.to_string() } });
+///         (error.http_status(), vec![("Content-Type".to_string(), "application/json".to_string())], body.to_string())
+///     }
+/// }
+///
+/// let (status, _, body) = CompanyEnvelope.render(&SaTokenError::NotLogin);
+/// assert_eq!(status, 401);
+/// assert!(body.contains("\"success\":false"));
+/// ```
+pub trait ErrorRenderer: Send + Sync {
+    /// 渲染拒绝响应 | Render a rejection
+    fn render(&self, error: &SaTokenError) -> (u16, Vec<(String, String)>, String);
+
+    /// 响应体的 Content-Type，用于 `PathAuthConfig::rejection_renderer` 生成的响应体
+    /// Content-Type of the bodies, used for the bodies produced by `PathAuthConfig::rejection_renderer`
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+}
+
+impl<F> ErrorRenderer for F
+where
+    F: Fn(&SaTokenError) -> (u16, Vec<(String, String)>, String) + Send + Sync,
+{
+    fn render(&self, error: &SaTokenError) -> (u16, Vec<(String, String)>, String) {
+        self(error)
+    }
+}
+
+/// 默认渲染器：全局信封包装的 JSON 错误 | Default renderer: a JSON error in the global envelope
+///
+/// `NotLogin` 与 `PermissionDenied` 沿用内置中间件一贯的提示；限流时附带 `Retry-After`。
+/// `NotLogin` and `PermissionDenied` keep the messages the built-in middlewares always used;
+/// rate limits add `Retry-After`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultErrorRenderer;
+
+impl ErrorRenderer for DefaultErrorRenderer {
+    fn render(&self, error: &SaTokenError) -> (u16, Vec<(String, String)>, String) {
+        let status = error.http_status();
+        let message = match error {
+            SaTokenError::NotLogin => messages::AUTH_ERROR.to_string(),
+            SaTokenError::PermissionDenied => messages::PERMISSION_REQUIRED.to_string(),
+            e => e.to_string(),
+        };
+        let mut headers = vec![("Content-Type".to_string(), self.content_type().to_string())];
+        if let SaTokenError::RateLimited(seconds) | SaTokenError::AccountLocked(seconds) = error {
+            headers.push(("Retry-After".to_string(), seconds.to_string()));
+        }
+        (status, headers, error_body(status, message))
+    }
+}
+
+/// 默认渲染器的共享实例 | Shared instance of the default renderer
+pub fn default_error_renderer() -> Arc<dyn ErrorRenderer> {
+    static DEFAULT: Lazy<Arc<dyn ErrorRenderer>> = Lazy::new(|| Arc::new(DefaultErrorRenderer));
+    DEFAULT.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_envelope() {
+        let envelope = ResponseEnvelope::new()
+            .code_field("status")
+            .message_field("msg")
+            .data_field("result")
+            .success_code(0);
+
+        assert_eq!(
+            envelope.error(403, "Permission required"),
+            serde_json::json!({"status": 403, "msg": "Permission required"})
+        );
+        assert_eq!(
+            envelope.success(serde_json::json!({"id": 1})),
+            serde_json::json!({"status": 0, "msg": "ok", "result": {"id": 1}})
+        );
+        assert_eq!(
+            ResponseEnvelope::default().error(401, "x"),
+            serde_json::json!({"code": 401, "message": "x"})
+        );
+    }
+
+    #[test]
+    fn test_default_error_renderer() {
+        let (status, headers, _) = DefaultErrorRenderer.render(&SaTokenError::RateLimited(30));
+        assert_eq!(status, 429);
+        assert!(headers.contains(&("Retry-After".to_string(), "30".to_string())));
+
+        let (status, _, body) = DefaultErrorRenderer.render(&SaTokenError::PermissionDeniedDetail("user:delete".to_string()));
+        assert_eq!(status, 403);
+        assert!(body.contains("user:delete"));
+
+        let renderer = |error: &SaTokenError| (error.http_status(), Vec::new(), format!("<error>{}</error>", error));
+        assert_eq!(renderer.render(&SaTokenError::InvalidCsrfToken).0, 403);
+    }
+}
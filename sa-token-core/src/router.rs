@@ -3,7 +3,14 @@
 // Path-based authentication router module
 // 基于路径的鉴权路由模块
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::guard::Guard;
+use crate::permission::{PermissionChecker, RoleChecker};
+use crate::rate_limit::{RateLimitKey, RateLimitRule};
+use crate::response::ErrorRenderer;
 
 /// Match a path against a pattern (Ant-style wildcard)
 /// 匹配路径与模式（Ant 风格通配符）
@@ -18,6 +25,10 @@ use std::sync::Arc;
 /// - `/api/*`: Match single-level paths under `/api/`
 /// - `*.html`: Match paths ending with `.html`
 /// - `/exact`: Exact match
+/// - `regex:<expr>`: Regular expression matching the whole path
+///
+/// A `regex:` pattern is compiled on first use and cached; an invalid one never matches.
+/// `regex:` 模式在首次使用时编译并缓存；不合法的正则不匹配任何路径。
 ///
 /// # Examples
 /// ```
@@ -25,28 +36,199 @@ use std::sync::Arc;
 /// assert!(match_path("/api/user", "/api/**"));
 /// assert!(match_path("/api/user", "/api/*"));
 /// assert!(!match_path("/api/user/profile", "/api/*"));
+/// assert!(match_path("/api/users/42", r"regex:/api/users/\d+"));
 /// ```
 pub fn match_path(path: &str, pattern: &str) -> bool {
-    if pattern == "/**" {
-        return true;
+    try_match_path(path, pattern).unwrap_or(false)
+}
+
+/// Match a path against a pattern, failing on an invalid `regex:` pattern
+/// 匹配路径与模式，`regex:` 模式不合法时返回错误
+fn try_match_path(path: &str, pattern: &str) -> SaTokenResult<bool> {
+    if pattern.starts_with(REGEX_PREFIX) {
+        return cached_regex(pattern).map(|regex| regex.is_match(path));
     }
-    if pattern.ends_with("/**") {
-        let prefix = &pattern[..pattern.len() - 3];
-        return path.starts_with(prefix);
+    // Ant 模式直接借用模式字符串，无需分配 | Ant patterns borrow the pattern string, no allocation
+    Ok(Matcher::ant(pattern).matches(path))
+}
+
+/// Regexes compiled for `match_path` and `SaRouter`, keyed by pattern (invalid ones keep their error)
+/// 为 `match_path` 与 `SaRouter` 编译的正则，以模式字符串为键（不合法的保存其错误信息）
+static REGEX_CACHE: Lazy<RwLock<HashMap<String, CachedRegex>>> = Lazy::new(Default::default);
+
+type CachedRegex = Result<Arc<Regex>, String>;
+
+fn cached_regex(pattern: &str) -> SaTokenResult<Arc<Regex>> {
+    let cached = REGEX_CACHE.read().unwrap_or_else(|e| e.into_inner()).get(pattern).cloned();
+    let compiled = match cached {
+        Some(compiled) => compiled,
+        None => {
+            let compiled = compile_regex(pattern).map(Arc::new);
+            if let Err(msg) = &compiled {
+                tracing::warn!(target: crate::logging::TARGET_REJECT, pattern, error = %msg, "invalid path pattern");
+            }
+            REGEX_CACHE.write().unwrap_or_else(|e| e.into_inner())
+                .insert(pattern.to_string(), compiled.clone());
+            compiled
+        }
+    };
+    compiled.map_err(SaTokenError::ConfigError)
+}
+
+/// Compile a `regex:` pattern so that it matches the whole path
+/// 编译 `regex:` 模式，使其匹配整个路径
+fn compile_regex(pattern: &str) -> Result<Regex, String> {
+    let expr = pattern.strip_prefix(REGEX_PREFIX).unwrap_or(pattern);
+    Regex::new(&format!("^(?:{})$", expr)).map_err(|e| format!("invalid path pattern '{}': {}", pattern, e))
+}
+
+/// Prefix marking a regex pattern
+/// 正则模式的前缀
+pub const REGEX_PREFIX: &str = "regex:";
+
+/// Segment matcher of a compiled pattern
+/// 编译后模式的匹配方式
+#[derive(Debug, Clone)]
+enum Matcher<S> {
+    /// `/**`
+    All,
+    /// `/api/**`
+    Prefix(S),
+    /// `*.html`
+    Suffix(S),
+    /// `/api/*`
+    SingleLevel(S),
+    /// `/exact`
+    Exact(S),
+    /// `regex:/api/users/\d+`
+    Regex(Regex),
+}
+
+impl<'a> Matcher<&'a str> {
+    /// Parse an Ant-style pattern, borrowing its parts
+    /// 解析 Ant 风格模式，借用其中的片段
+    fn ant(pattern: &'a str) -> Self {
+        if pattern == "/**" {
+            Matcher::All
+        } else if let Some(prefix) = pattern.strip_suffix("/**") {
+            Matcher::Prefix(prefix)
+        } else if let Some(suffix) = pattern.strip_prefix('*') {
+            Matcher::Suffix(suffix)
+        } else if let Some(prefix) = pattern.strip_suffix('*').filter(|p| p.ends_with('/')) {
+            Matcher::SingleLevel(prefix)
+        } else {
+            Matcher::Exact(pattern)
+        }
     }
-    if pattern.starts_with("*") {
-        let suffix = &pattern[1..];
-        return path.ends_with(suffix);
+
+    fn into_owned(self) -> Matcher<String> {
+        match self {
+            Matcher::All => Matcher::All,
+            Matcher::Prefix(s) => Matcher::Prefix(s.to_string()),
+            Matcher::Suffix(s) => Matcher::Suffix(s.to_string()),
+            Matcher::SingleLevel(s) => Matcher::SingleLevel(s.to_string()),
+            Matcher::Exact(s) => Matcher::Exact(s.to_string()),
+            Matcher::Regex(regex) => Matcher::Regex(regex),
+        }
     }
-    if pattern.ends_with("/*") {
-        let prefix = &pattern[..pattern.len() - 2];
-        if path.starts_with(prefix) {
-            let suffix = &path[prefix.len()..];
-            return !suffix.contains('/') || suffix == "/";
+}
+
+impl<S: AsRef<str>> Matcher<S> {
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            Matcher::All => true,
+            Matcher::Prefix(prefix) => path.starts_with(prefix.as_ref()),
+            Matcher::Suffix(suffix) => path.ends_with(suffix.as_ref()),
+            Matcher::SingleLevel(prefix) => path
+                .strip_prefix(prefix.as_ref())
+                .is_some_and(|rest| !rest.contains('/')),
+            Matcher::Exact(exact) => path == exact.as_ref(),
+            Matcher::Regex(regex) => regex.is_match(path),
         }
-        return false;
     }
-    path == pattern
+}
+
+/// Precompiled path pattern (Ant-style wildcard, or a regex with the `regex:` prefix)
+/// 预编译的路径模式（Ant 风格通配符，或带 `regex:` 前缀的正则表达式）
+///
+/// The pattern is parsed once, so matching a path does no string analysis of the pattern.
+/// A regex pattern must match the whole path.
+/// 模式只解析一次，匹配路径时无需再次分析模式字符串。正则模式需要匹配整个路径。
+///
+/// # Examples
+/// ```
+/// use sa_token_core::router::CompiledPattern;
+/// let pattern = CompiledPattern::new("/api/*");
+/// assert!(pattern.matches("/api/user"));
+/// assert!(!pattern.matches("/api/user/profile"));
+///
+/// let pattern = CompiledPattern::new(r"regex:/api/orders/\d+");
+/// assert!(pattern.matches("/api/orders/42"));
+/// assert!(!pattern.matches("/api/orders/42/items"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompiledPattern {
+    source: String,
+    matcher: Matcher<String>,
+}
+
+impl CompiledPattern {
+    /// Compile a pattern
+    /// 编译模式
+    ///
+    /// # Panics
+    /// Panics if a `regex:` pattern is not a valid regular expression, use `try_new` to handle it
+    /// `regex:` 模式不是合法的正则表达式时 panic，需要处理错误时使用 `try_new`
+    pub fn new(pattern: impl Into<String>) -> Self {
+        match Self::try_new(pattern) {
+            Ok(pattern) => pattern,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Compile a pattern, failing on an invalid `regex:` pattern
+    /// 编译模式，`regex:` 模式不合法时返回错误
+    pub fn try_new(pattern: impl Into<String>) -> SaTokenResult<Self> {
+        let source = pattern.into();
+        let matcher = if source.starts_with(REGEX_PREFIX) {
+            Matcher::Regex(compile_regex(&source).map_err(SaTokenError::ConfigError)?)
+        } else {
+            Matcher::ant(&source).into_owned()
+        };
+        Ok(Self { source, matcher })
+    }
+
+    /// Check if a path matches this pattern
+    /// 检查路径是否匹配此模式
+    pub fn matches(&self, path: &str) -> bool {
+        self.matcher.matches(path)
+    }
+
+    /// Whether this is a regex pattern
+    /// 是否为正则模式
+    pub fn is_regex(&self) -> bool {
+        matches!(self.matcher, Matcher::Regex(_))
+    }
+
+    /// The original pattern string
+    /// 原始模式字符串
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl PartialEq for CompiledPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for CompiledPattern {}
+
+impl std::fmt::Display for CompiledPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
 }
 
 /// Check if path matches any pattern in the list
@@ -64,6 +246,73 @@ pub fn need_auth(path: &str, include: &[&str], exclude: &[&str]) -> bool {
     match_any(path, include) && !match_any(path, exclude)
 }
 
+/// Route requirement: login plus a permission, for `PathAuthConfig::route`
+/// 路由要求：登录且拥有权限，用于 `PathAuthConfig::route`
+pub fn required_permission(permission: impl Into<String>) -> Guard {
+    Guard::permission(permission)
+}
+
+/// Route requirement: login plus a role, for `PathAuthConfig::route`
+/// 路由要求：登录且拥有角色，用于 `PathAuthConfig::route`
+pub fn required_role(role: impl Into<String>) -> Guard {
+    Guard::role(role)
+}
+
+/// Login ID validator function type
+/// 登录ID验证函数类型
+pub type LoginIdValidator = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Rejection renderer function type, returns the HTTP status and body of a rejected request
+/// 拒绝响应渲染函数类型，返回被拒绝请求的 HTTP 状态码与响应体
+pub type RejectionRenderer = Arc<dyn Fn(&AuthResult) -> (u16, String) + Send + Sync>;
+
+/// Kind of a path rule
+/// 路径规则类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteRuleKind {
+    /// Excluded from authentication
+    /// 排除鉴权
+    Exclude,
+    /// Requires authentication
+    /// 需要鉴权
+    Include,
+}
+
+/// An effective path rule of a `PathAuthConfig`
+/// `PathAuthConfig` 中生效的路径规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteRule {
+    /// Rule kind
+    /// 规则类型
+    pub kind: RouteRuleKind,
+    /// Compiled pattern
+    /// 编译后的模式
+    pub pattern: CompiledPattern,
+    /// HTTP methods the rule is limited to (upper case), empty for every method
+    /// 规则限定的 HTTP 方法（大写），为空表示所有方法
+    pub methods: Vec<String>,
+}
+
+impl RouteRule {
+    fn include(pattern: CompiledPattern, methods: Vec<String>) -> Self {
+        Self { kind: RouteRuleKind::Include, pattern, methods }
+    }
+
+    /// Check if a request matches this rule; a method-specific rule never matches an unknown method
+    /// 检查请求是否匹配此规则；方法未知时不匹配限定方法的规则
+    pub fn matches(&self, method: Option<&str>, path: &str) -> bool {
+        let method_matches = self.methods.is_empty()
+            || method.is_some_and(|m| self.methods.iter().any(|allowed| allowed.eq_ignore_ascii_case(m)));
+        method_matches && self.pattern.matches(path)
+    }
+
+    /// Whether a path matched by this rule requires authentication
+    /// 被此规则匹配的路径是否需要鉴权
+    pub fn requires_auth(&self) -> bool {
+        self.kind == RouteRuleKind::Include
+    }
+}
+
 /// Path-based authentication configuration
 /// 基于路径的鉴权配置
 ///
@@ -73,13 +322,49 @@ pub fn need_auth(path: &str, include: &[&str], exclude: &[&str]) -> bool {
 pub struct PathAuthConfig {
     /// Paths that require authentication (include patterns)
     /// 需要鉴权的路径（包含模式）
-    include: Vec<String>,
+    include: Vec<CompiledPattern>,
     /// Paths excluded from authentication (exclude patterns)
     /// 排除鉴权的路径（排除模式）
-    exclude: Vec<String>,
+    exclude: Vec<CompiledPattern>,
     /// Optional login ID validator function
     /// 可选的登录ID验证函数
-    validator: Option<Arc<dyn Fn(&str) -> bool + Send + Sync>>,
+    validator: Option<LoginIdValidator>,
+    /// Route-scoped guards, the first match in declaration order applies
+    /// 按路由生效的守卫，按声明顺序匹配第一条
+    guards: Vec<(RouteRule, Guard)>,
+    /// Per-API-version configurations, keyed by version (e.g. `v2`)
+    /// 按 API 版本区分的配置，键为版本号（如 `v2`）
+    versions: Vec<(String, PathAuthConfig)>,
+    /// Header carrying the API version when the path has none
+    /// 路径中没有版本号时携带 API 版本的请求头
+    version_header: Option<String>,
+    /// Optional rejection renderer
+    /// 可选的拒绝响应渲染函数
+    renderer: Option<RejectionRenderer>,
+    /// Rate limit rules, every matching rule applies
+    /// 限流规则，所有匹配的规则都会生效
+    rate_limits: Vec<RateLimitRule>,
+    /// Header carrying the client IP when behind a proxy (e.g. `X-Forwarded-For`)
+    /// 位于代理之后时携带客户端 IP 的请求头（如 `X-Forwarded-For`）
+    client_ip_header: Option<String>,
+    /// Patterns added through `route`, they require authentication like include patterns
+    /// 通过 `route` 添加的模式，与包含模式一样需要鉴权
+    route_include: Vec<CompiledPattern>,
+    /// Optional checker deciding the permissions of guards
+    /// 可选的权限检查器，用于判断守卫中的权限
+    permission_checker: Option<Arc<dyn PermissionChecker>>,
+    /// Optional checker deciding the roles of guards
+    /// 可选的角色检查器，用于判断守卫中的角色
+    role_checker: Option<Arc<dyn RoleChecker>>,
+    /// Rules limited to HTTP methods, they take priority over the other rules
+    /// 限定 HTTP 方法的规则，优先于其他规则
+    method_rules: Vec<RouteRule>,
+    /// Effective rules in priority order, rebuilt whenever a rule is added
+    /// 按优先级排列的生效规则，每次添加规则时重建
+    rules: Vec<RouteRule>,
+    /// First invalid pattern passed to a builder, see `validate`
+    /// 传给构建方法的第一个不合法模式，见 `validate`
+    pattern_error: Option<String>,
 }
 
 impl PathAuthConfig {
@@ -90,20 +375,73 @@ impl PathAuthConfig {
             include: Vec::new(),
             exclude: Vec::new(),
             validator: None,
+            guards: Vec::new(),
+            versions: Vec::new(),
+            version_header: None,
+            renderer: None,
+            rate_limits: Vec::new(),
+            client_ip_header: None,
+            route_include: Vec::new(),
+            permission_checker: None,
+            role_checker: None,
+            method_rules: Vec::new(),
+            rules: Vec::new(),
+            pattern_error: None,
         }
     }
 
     /// Set paths that require authentication
     /// 设置需要鉴权的路径
     pub fn include(mut self, patterns: Vec<String>) -> Self {
-        self.include = patterns;
+        self.include = self.compile_all(patterns);
+        self.rebuild_rules();
         self
     }
 
     /// Set paths excluded from authentication
     /// 设置排除鉴权的路径
     pub fn exclude(mut self, patterns: Vec<String>) -> Self {
-        self.exclude = patterns;
+        self.exclude = self.compile_all(patterns);
+        self.rebuild_rules();
+        self
+    }
+
+    /// Require authentication for paths matching the patterns, only for the given HTTP methods
+    /// 仅对指定的 HTTP 方法，匹配模式的路径需要鉴权
+    ///
+    /// Method-specific rules are checked before the other rules (exclude rules first).
+    /// 限定方法的规则先于其他规则检查（先排除规则）。
+    pub fn include_methods(self, methods: &[&str], patterns: Vec<String>) -> Self {
+        self.method_rule(RouteRuleKind::Include, methods, patterns)
+    }
+
+    /// Exclude paths matching the patterns from authentication, only for the given HTTP methods
+    /// 仅对指定的 HTTP 方法，匹配模式的路径排除鉴权
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::router::PathAuthConfig;
+    /// // GET /api/articles/** is public, writes require login
+    /// let config = PathAuthConfig::new()
+    ///     .include(vec!["/api/**".to_string()])
+    ///     .exclude_methods(&["GET", "HEAD"], vec!["/api/articles/**".to_string()]);
+    /// assert!(!config.check_request(Some("GET"), "/api/articles/1"));
+    /// assert!(config.check_request(Some("DELETE"), "/api/articles/1"));
+    /// assert!(config.check("/api/articles/1"));
+    /// ```
+    pub fn exclude_methods(self, methods: &[&str], patterns: Vec<String>) -> Self {
+        self.method_rule(RouteRuleKind::Exclude, methods, patterns)
+    }
+
+    fn method_rule(mut self, kind: RouteRuleKind, methods: &[&str], patterns: Vec<String>) -> Self {
+        let methods: Vec<String> = methods.iter().map(|m| m.to_ascii_uppercase()).collect();
+        let patterns = self.compile_all(patterns);
+        self.method_rules.extend(patterns.into_iter().map(|pattern| RouteRule {
+            kind,
+            pattern,
+            methods: methods.clone(),
+        }));
+        self.rebuild_rules();
         self
     }
 
@@ -117,18 +455,360 @@ impl PathAuthConfig {
         self
     }
 
-    /// Check if a path requires authentication
-    /// 检查路径是否需要鉴权
+    /// Protect paths matching a pattern with a guard
+    /// 为匹配模式的路径设置守卫
+    ///
+    /// The guard runs in `process_auth` regardless of include / exclude rules;
+    /// the first guard whose pattern matches applies.
+    /// 守卫在 `process_auth` 中执行，与包含 / 排除规则无关；按声明顺序匹配第一条。
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::{Guard, router::PathAuthConfig};
+    /// let config = PathAuthConfig::new()
+    ///     .include(vec!["/api/**".to_string()])
+    ///     .guard("/api/orders/**", Guard::login().and(Guard::permission("order:read")).or(Guard::role("admin")));
+    /// assert!(config.match_guard("/api/orders/1").is_some());
+    /// assert!(config.match_guard("/api/users/1").is_none());
+    /// ```
+    pub fn guard(mut self, pattern: impl Into<String>, guard: Guard) -> Self {
+        if let Some(pattern) = self.compile(pattern) {
+            self.guards.push((RouteRule::include(pattern, Vec::new()), guard));
+        }
+        self
+    }
+
+    /// Require authentication plus the given guard for paths matching a pattern
+    /// 匹配模式的路径需要鉴权，并满足指定的守卫
+    ///
+    /// Shorthand for an include pattern combined with `guard`; guards still apply in declaration order.
+    /// 相当于包含模式加上 `guard`；守卫仍按声明顺序匹配。
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::router::{PathAuthConfig, required_permission, required_role};
+    /// let config = PathAuthConfig::new()
+    ///     .route("/api/admin/**", required_permission("admin:*"))
+    ///     .route("/api/reports/**", required_role("auditor"))
+    ///     .include(vec!["/api/**".to_string()]);
+    /// assert!(config.check("/api/admin/users"));
+    /// assert!(config.match_guard("/api/admin/users").is_some());
+    /// assert!(config.match_guard("/api/orders").is_none());
+    /// ```
+    pub fn route(mut self, pattern: impl Into<String>, guard: Guard) -> Self {
+        if let Some(pattern) = self.compile(pattern) {
+            self.route_include.push(pattern.clone());
+            self.guards.push((RouteRule::include(pattern, Vec::new()), guard));
+            self.rebuild_rules();
+        }
+        self
+    }
+
+    /// Like `route`, but only for the given HTTP methods
+    /// 与 `route` 相同，但仅对指定的 HTTP 方法生效
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::router::{PathAuthConfig, required_permission};
+    /// let config = PathAuthConfig::new()
+    ///     .route_methods(&["DELETE"], "/api/users/*", required_permission("user:delete"));
+    /// assert!(config.check_request(Some("DELETE"), "/api/users/42"));
+    /// assert!(config.match_guard_request(Some("DELETE"), "/api/users/42").is_some());
+    /// assert!(config.match_guard_request(Some("GET"), "/api/users/42").is_none());
+    /// ```
+    pub fn route_methods(mut self, methods: &[&str], pattern: impl Into<String>, guard: Guard) -> Self {
+        let methods: Vec<String> = methods.iter().map(|m| m.to_ascii_uppercase()).collect();
+        if let Some(pattern) = self.compile(pattern) {
+            let rule = RouteRule::include(pattern, methods);
+            self.method_rules.push(rule.clone());
+            self.guards.push((rule, guard));
+            self.rebuild_rules();
+        }
+        self
+    }
+
+    /// Compile a pattern, recording the first invalid one instead of panicking
+    /// 编译模式，不合法时记录第一个错误而不是 panic
+    fn compile(&mut self, pattern: impl Into<String>) -> Option<CompiledPattern> {
+        match CompiledPattern::try_new(pattern) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => {
+                self.pattern_error.get_or_insert_with(|| match e {
+                    SaTokenError::ConfigError(msg) => msg,
+                    e => e.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn compile_all(&mut self, patterns: Vec<String>) -> Vec<CompiledPattern> {
+        patterns.into_iter().filter_map(|pattern| self.compile(pattern)).collect()
+    }
+
+    /// Check that every pattern passed to the builders is valid, including those of API versions
+    /// 检查传给构建方法的所有模式（含各 API 版本的配置）是否合法
+    ///
+    /// Requests handled by a configuration with an invalid pattern are rejected with `ConfigError`,
+    /// so call this at startup to fail early.
+    /// 含有不合法模式的配置会以 `ConfigError` 拒绝其处理的所有请求，建议在启动时调用以尽早发现错误。
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::router::PathAuthConfig;
+    /// let config = PathAuthConfig::new().include(vec!["regex:/api/(".to_string()]);
+    /// assert!(config.validate().is_err());
+    /// assert!(PathAuthConfig::new().include(vec![r"regex:/api/\d+".to_string()]).validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> SaTokenResult<()> {
+        if let Some(msg) = &self.pattern_error {
+            return Err(SaTokenError::ConfigError(msg.clone()));
+        }
+        self.versions.iter().try_for_each(|(_, config)| config.validate())
+    }
+
+    /// Decide the permissions of guards with a `PermissionChecker` instead of the manager's data
+    /// 使用 `PermissionChecker` 而不是管理器的数据判断守卫中的权限
+    pub fn permission_checker(mut self, checker: Arc<dyn PermissionChecker>) -> Self {
+        self.permission_checker = Some(checker);
+        self
+    }
+
+    /// Decide the roles of guards with a `RoleChecker` instead of the manager's data
+    /// 使用 `RoleChecker` 而不是管理器的数据判断守卫中的角色
+    pub fn role_checker(mut self, checker: Arc<dyn RoleChecker>) -> Self {
+        self.role_checker = Some(checker);
+        self
+    }
+
+    /// Find the guard that applies to a path, method-specific guards are skipped
+    /// 查找作用于某路径的守卫，跳过限定方法的守卫
+    pub fn match_guard(&self, path: &str) -> Option<&Guard> {
+        self.match_guard_request(None, path)
+    }
+
+    /// Find the guard that applies to a request with the given HTTP method
+    /// 查找作用于使用指定 HTTP 方法的请求的守卫
+    pub fn match_guard_request(&self, method: Option<&str>, path: &str) -> Option<&Guard> {
+        self.guards.iter().find(|(rule, _)| rule.matches(method, path)).map(|(_, guard)| guard)
+    }
+
+    /// Check if a path requires authentication, method-specific rules are skipped
+    /// 检查路径是否需要鉴权，跳过限定方法的规则
     pub fn check(&self, path: &str) -> bool {
-        let inc: Vec<&str> = self.include.iter().map(|s| s.as_str()).collect();
-        let exc: Vec<&str> = self.exclude.iter().map(|s| s.as_str()).collect();
-        need_auth(path, &inc, &exc)
+        self.check_request(None, path)
+    }
+
+    /// Check if a request with the given HTTP method requires authentication
+    /// 检查使用指定 HTTP 方法的请求是否需要鉴权
+    pub fn check_request(&self, method: Option<&str>, path: &str) -> bool {
+        self.find_rule(method, path).is_some_and(|rule| rule.requires_auth())
+    }
+
+    /// List the effective rules in priority order
+    /// (method-specific rules, then exclude rules, then include rules; exclude before include within method rules)
+    /// 按优先级列出生效的规则（限定方法的规则、排除规则、包含规则；限定方法的规则中排除规则在前）
+    pub fn routes(&self) -> Vec<RouteRule> {
+        self.rules.clone()
+    }
+
+    /// Rebuild the effective rules after a rule was added
+    /// 添加规则后重建生效规则
+    fn rebuild_rules(&mut self) {
+        let method_exclude = self.method_rules.iter().filter(|rule| !rule.requires_auth()).cloned();
+        let method_include = self.method_rules.iter().filter(|rule| rule.requires_auth()).cloned();
+        let exclude = self.exclude.iter().map(|pattern| RouteRule {
+            kind: RouteRuleKind::Exclude,
+            pattern: pattern.clone(),
+            methods: Vec::new(),
+        });
+        let include = self.include.iter().chain(&self.route_include).map(|pattern| RouteRule {
+            kind: RouteRuleKind::Include,
+            pattern: pattern.clone(),
+            methods: Vec::new(),
+        });
+        self.rules = method_exclude.chain(method_include).chain(exclude).chain(include).collect();
+    }
+
+    fn find_rule(&self, method: Option<&str>, path: &str) -> Option<&RouteRule> {
+        self.rules.iter().find(|rule| rule.matches(method, path))
+    }
+
+    /// Find the rule that decides a path, `None` means no rule matches and no authentication is required
+    /// 查找决定某路径的规则，`None` 表示没有规则匹配、无需鉴权
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::router::{PathAuthConfig, RouteRuleKind};
+    /// let config = PathAuthConfig::new()
+    ///     .include(vec!["/api/**".to_string()])
+    ///     .exclude(vec!["/api/login".to_string()]);
+    /// let rule = config.match_rule("/api/login").unwrap();
+    /// assert_eq!(rule.kind, RouteRuleKind::Exclude);
+    /// assert_eq!(rule.pattern.as_str(), "/api/login");
+    /// ```
+    pub fn match_rule(&self, path: &str) -> Option<RouteRule> {
+        self.match_request(None, path)
+    }
+
+    /// Find the rule that decides a request with the given HTTP method
+    /// 查找决定使用指定 HTTP 方法的请求的规则
+    pub fn match_request(&self, method: Option<&str>, path: &str) -> Option<RouteRule> {
+        self.find_rule(method, path).cloned()
     }
 
     /// Validate a login ID using the configured validator
     /// 使用配置的验证器验证登录ID
     pub fn validate_login_id(&self, login_id: &str) -> bool {
-        self.validator.as_ref().map_or(true, |v| v(login_id))
+        self.validator.as_ref().is_none_or(|v| v(login_id))
+    }
+
+    /// Use a separate configuration for an API version
+    /// 为某个 API 版本使用单独的配置
+    ///
+    /// A request belongs to a version when a path segment equals it (`/v2/orders`, `/api/v2/orders`),
+    /// or, failing that, when the version header holds it. Other requests use this configuration.
+    /// 路径中某一段等于版本号（`/v2/orders`、`/api/v2/orders`）时请求属于该版本，
+    /// 否则看版本请求头；其余请求使用当前配置。
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::{Guard, router::PathAuthConfig};
+    /// let config = PathAuthConfig::new()
+    ///     .include(vec!["/v1/orders/**".to_string()])
+    ///     .version("v2", PathAuthConfig::new()
+    ///         .include(vec!["/v2/**".to_string()])
+    ///         .guard("/v2/orders/**", Guard::login().and(Guard::safe())))
+    ///     .version_header("X-Api-Version");
+    /// assert_eq!(config.resolve("/v2/orders/1", None).1, Some("v2"));
+    /// assert_eq!(config.resolve("/orders/1", Some("v2")).1, Some("v2"));
+    /// assert_eq!(config.resolve("/v1/orders/1", None).1, None);
+    /// ```
+    pub fn version(mut self, version: impl Into<String>, config: PathAuthConfig) -> Self {
+        self.versions.push((version.into(), config));
+        self
+    }
+
+    /// Read the API version from a header when the path carries none
+    /// 路径中没有版本号时从请求头读取 API 版本
+    pub fn version_header(mut self, name: impl Into<String>) -> Self {
+        self.version_header = Some(name.into());
+        self
+    }
+
+    /// Name of the version header, if configured
+    /// 版本请求头名称（如已配置）
+    pub fn version_header_name(&self) -> Option<&str> {
+        self.version_header.as_deref()
+    }
+
+    /// Add a rate limit rule, rejected requests get 429
+    /// 添加限流规则，超限的请求返回 429
+    pub fn rate_limit(mut self, rule: RateLimitRule) -> Self {
+        self.rate_limits.push(rule);
+        self
+    }
+
+    /// Read the client IP from a header (first entry) instead of the peer address
+    /// 从请求头（取第一项）而不是对端地址读取客户端 IP
+    ///
+    /// Only set this when a trusted proxy overwrites the header.
+    /// 仅在可信代理会覆盖该请求头时设置。
+    pub fn client_ip_header(mut self, name: impl Into<String>) -> Self {
+        self.client_ip_header = Some(name.into());
+        self
+    }
+
+    /// Name of the client IP header, if configured
+    /// 客户端 IP 请求头名称（如已配置）
+    pub fn client_ip_header_name(&self) -> Option<&str> {
+        self.client_ip_header.as_deref()
+    }
+
+    /// Resolve the client IP from the configured header, falling back to the peer address
+    /// 从配置的请求头解析客户端 IP，未配置或缺失时使用对端地址
+    pub fn client_ip(&self, header_value: Option<&str>, peer: Option<String>) -> Option<String> {
+        header_value
+            .filter(|_| self.client_ip_header.is_some())
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(str::to_string)
+            .or(peer)
+    }
+
+    /// Set a custom renderer for rejected requests
+    /// 设置被拒绝请求的自定义渲染函数
+    pub fn rejection_renderer<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&AuthResult) -> (u16, String) + Send + Sync + 'static,
+    {
+        self.renderer = Some(Arc::new(f));
+        self
+    }
+
+    /// Resolve the configuration that applies to a request and its API version
+    /// 解析作用于请求的配置及其 API 版本
+    ///
+    /// # Arguments
+    /// - `path`: The request path
+    /// - `header_version`: Value of the version header, if any
+    pub fn resolve<'a>(&'a self, path: &str, header_version: Option<&str>) -> (&'a PathAuthConfig, Option<&'a str>) {
+        let by_path = path
+            .split('/')
+            .find_map(|segment| self.versions.iter().find(|(version, _)| version == segment));
+        let by_header = || {
+            header_version.and_then(|v| self.versions.iter().find(|(version, _)| version == v.trim()))
+        };
+        match by_path.or_else(by_header) {
+            Some((version, config)) => (config, Some(version.as_str())),
+            None => (self, None),
+        }
+    }
+
+    /// Render a rejected request as an HTTP status and body
+    /// 将被拒绝的请求渲染为 HTTP 状态码与响应体
+    ///
+    /// Uses the renderer of the request's API version, then this configuration's renderer,
+    /// then the plugin state's `ErrorRenderer`.
+    /// 依次使用请求所属 API 版本的渲染函数、当前配置的渲染函数、插件状态的 `ErrorRenderer`。
+    pub fn render_rejection(&self, result: &AuthResult, error_renderer: &dyn ErrorRenderer) -> (u16, String) {
+        let (status, _, body) = self.render_rejection_response(result, error_renderer);
+        (status, body)
+    }
+
+    /// Render a rejected request as an HTTP status, headers and body
+    /// 将被拒绝的请求渲染为 HTTP 状态码、响应头与响应体
+    ///
+    /// A body from `rejection_renderer` gets the Content-Type of `error_renderer`.
+    /// `rejection_renderer` 生成的响应体使用 `error_renderer` 的 Content-Type。
+    ///
+    /// # Examples
+    /// ```
+    /// use sa_token_core::DefaultErrorRenderer;
+    /// use sa_token_core::router::{AuthResult, PathAuthConfig};
+    /// let config = PathAuthConfig::new().rejection_renderer(|_| (401, r#"{"ok":false}"#.to_string()));
+    /// let result = AuthResult {
+    ///     need_auth: true, token: None, token_info: None, is_valid: false, guard_error: None,
+    ///     version: None, client_ip: None, fingerprint: None,
+    /// };
+    /// let (status, headers, _) = config.render_rejection_response(&result, &DefaultErrorRenderer);
+    /// assert_eq!(status, 401);
+    /// assert!(headers.contains(&("Content-Type".to_string(), "application/json".to_string())));
+    /// ```
+    pub fn render_rejection_response(&self, result: &AuthResult, error_renderer: &dyn ErrorRenderer) -> (u16, Vec<(String, String)>, String) {
+        let versioned = result.version.as_deref()
+            .and_then(|v| self.versions.iter().find(|(version, _)| version == v))
+            .and_then(|(_, config)| config.renderer.as_ref());
+        if let Some(renderer) = versioned.or(self.renderer.as_ref()) {
+            let (status, body) = renderer(result);
+            let mut headers = vec![("Content-Type".to_string(), error_renderer.content_type().to_string())];
+            if let Some(seconds) = result.retry_after() {
+                headers.push(("Retry-After".to_string(), seconds.to_string()));
+            }
+            return (status, headers, body);
+        }
+        error_renderer.render(result.guard_error.as_ref().unwrap_or(&SaTokenError::NotLogin))
     }
 }
 
@@ -138,7 +818,7 @@ impl Default for PathAuthConfig {
     }
 }
 
-use crate::{SaTokenManager, TokenValue, SaTokenContext, token::TokenInfo};
+use crate::{SaTokenManager, SaTokenError, SaTokenResult, TokenValue, SaTokenContext, token::TokenInfo};
 
 /// Authentication result after processing
 /// 处理后的鉴权结果
@@ -155,13 +835,46 @@ pub struct AuthResult {
     /// Whether the token is valid
     /// token是否有效
     pub is_valid: bool,
+    /// Error of the route guard, if it failed
+    /// 路由守卫未通过时的错误
+    pub guard_error: Option<SaTokenError>,
+    /// API version the request was resolved to, `None` for the unversioned configuration
+    /// 请求所属的 API 版本，`None` 表示使用未区分版本的配置
+    pub version: Option<String>,
+    /// Client IP of the request, if known
+    /// 请求的客户端 IP（如可确定）
+    pub client_ip: Option<String>,
+    /// Device fingerprint of the request, if `bind_fingerprint` is on
+    /// 请求的设备指纹（开启 `bind_fingerprint` 时）
+    pub fingerprint: Option<String>,
 }
 
 impl AuthResult {
     /// Check if the request should be rejected
     /// 检查请求是否应该被拒绝
     pub fn should_reject(&self) -> bool {
-        self.need_auth && (!self.is_valid || self.token.is_none())
+        self.guard_error.is_some() || (self.need_auth && (!self.is_valid || self.token.is_none()))
+    }
+
+    /// HTTP status of a rejected request: 403 when a guard denied permission or role,
+    /// 429 when a rate limit was hit, 500 for an invalid configuration, otherwise 401
+    /// 被拒绝请求的 HTTP 状态码：守卫因权限或角色拒绝时为 403，触发限流时为 429，配置不合法时为 500，否则为 401
+    pub fn reject_status(&self) -> u16 {
+        match &self.guard_error {
+            Some(e) if e.is_authz_error() => 403,
+            Some(SaTokenError::RateLimited(_)) => 429,
+            Some(SaTokenError::ConfigError(_)) => 500,
+            _ => 401,
+        }
+    }
+
+    /// Seconds to wait before retrying, set when a rate limit rejected the request
+    /// 被限流拒绝时建议的重试等待秒数
+    pub fn retry_after(&self) -> Option<u64> {
+        match &self.guard_error {
+            Some(SaTokenError::RateLimited(seconds)) => Some(*seconds),
+            _ => None,
+        }
     }
 
     /// Get the login ID from token info
@@ -169,6 +882,12 @@ impl AuthResult {
     pub fn login_id(&self) -> Option<&str> {
         self.token_info.as_ref().map(|t| t.login_id.as_str())
     }
+
+    /// Get the account type from token info
+    /// 从token信息中获取账号类型
+    pub fn account_type(&self) -> Option<&str> {
+        self.token_info.as_ref().map(|t| t.account_type())
+    }
 }
 
 /// Process authentication for a request path
@@ -189,11 +908,57 @@ pub async fn process_auth(
     config: &PathAuthConfig,
     manager: &SaTokenManager,
 ) -> AuthResult {
-    let need_auth = config.check(path);
+    process_auth_with_version(path, None, token_str, config, manager).await
+}
+
+/// Process authentication for a request path, taking the API version header into account
+/// 处理请求路径的鉴权，并考虑 API 版本请求头
+///
+/// # Arguments
+/// - `path`: The request path
+/// - `header_version`: Value of the header named by `PathAuthConfig::version_header`, if any
+/// - `token_str`: Optional token string from request
+/// - `config`: Path authentication configuration
+/// - `manager`: SaTokenManager instance
+pub async fn process_auth_with_version(
+    path: &str,
+    header_version: Option<&str>,
+    token_str: Option<String>,
+    config: &PathAuthConfig,
+    manager: &SaTokenManager,
+) -> AuthResult {
+    process_auth_request(path, None, header_version, None, None, token_str, config, manager).await
+}
+
+/// Process authentication and rate limits for a request
+/// 处理请求的鉴权与限流
+///
+/// # Arguments
+/// - `path`: The request path
+/// - `method`: HTTP method of the request; method-specific rules are skipped without it
+/// - `header_version`: Value of the header named by `PathAuthConfig::version_header`, if any
+/// - `client_ip`: Client IP, see `PathAuthConfig::client_ip`; per-IP rules are skipped without it
+/// - `fingerprint`: Device fingerprint, see `SaTokenConfig::fingerprint`
+/// - `token_str`: Optional token string from request
+/// - `config`: Path authentication configuration
+/// - `manager`: SaTokenManager instance
+#[allow(clippy::too_many_arguments)]
+pub async fn process_auth_request(
+    path: &str,
+    method: Option<&str>,
+    header_version: Option<&str>,
+    client_ip: Option<&str>,
+    fingerprint: Option<&str>,
+    token_str: Option<String>,
+    config: &PathAuthConfig,
+    manager: &SaTokenManager,
+) -> AuthResult {
+    let (config, version) = config.resolve(path, header_version);
+    let need_auth = config.check_request(method, path);
     
     let token = token_str.map(TokenValue::new);
     
-    let (is_valid, token_info) = if let Some(ref t) = token {
+    let (mut is_valid, mut token_info) = if let Some(ref t) = token {
         let valid = manager.is_valid(t).await;
         let info = if valid {
             manager.get_token_info(t).await.ok()
@@ -205,18 +970,97 @@ pub async fn process_auth(
         (false, None)
     };
 
+    // A token bound to another IP or device is treated as not logged in
+    // 绑定到其他 IP 或设备的 token 视为未登录
+    let mut binding_error = None;
+    if let Some(info) = &token_info
+        && let Err(e) = manager.check_binding(info, client_ip, fingerprint) {
+        binding_error = Some(e);
+        is_valid = false;
+        token_info = None;
+    }
+
     let is_valid = is_valid && if need_auth {
-        token_info.as_ref().map_or(false, |info| config.validate_login_id(&info.login_id))
+        token_info.as_ref().is_some_and(|info| config.validate_login_id(&info.login_id))
     } else {
         true
     };
+    
+    if is_valid && let Some(info) = &token_info {
+        manager.record_access(info, path).await;
+        manager.record_activity(info, client_ip).await;
+    }
+
+    let login_id = if is_valid { token_info.as_ref().map(|info| info.login_id.as_str()) } else { None };
+    // 含有不合法模式的配置拒绝所有请求 | A configuration with an invalid pattern rejects every request
+    let mut guard_error = config.validate().err();
+    if guard_error.is_none() {
+        guard_error = check_rate_limits(config, path, client_ip, login_id, manager).await;
+    }
+    if guard_error.is_none() && need_auth {
+        guard_error = binding_error;
+    }
+    if guard_error.is_none() && let Some(guard) = config.match_guard_request(method, path) {
+        let info = if is_valid { token_info.as_ref() } else { None };
+        guard_error = guard.check_with(manager, info, config.permission_checker.as_deref(), config.role_checker.as_deref()).await.err();
+    }
+
+    if token.is_some() {
+        tracing::debug!(
+            target: crate::logging::TARGET_VALIDATE,
+            path,
+            valid = is_valid,
+            login_id = token_info.as_ref().map(|info| info.login_id.as_str()),
+            "token validated"
+        );
+    }
 
-    AuthResult {
+    let result = AuthResult {
         need_auth,
         token,
         token_info,
         is_valid,
+        guard_error,
+        version: version.map(str::to_string),
+        client_ip: client_ip.map(str::to_string),
+        fingerprint: fingerprint.map(str::to_string),
+    };
+    if result.should_reject() {
+        let reason = match &result.guard_error {
+            Some(e) => e.to_string(),
+            None if result.token.is_none() => "token missing".to_string(),
+            None => "token invalid".to_string(),
+        };
+        crate::logging::rejected("path-auth", path, result.reject_status(), &reason);
     }
+    result
+}
+
+/// Count the request against every matching rate limit rule
+/// 按所有匹配的限流规则计数
+///
+/// Storage failures are logged and let the request through.
+/// 存储出错时记录日志并放行。
+async fn check_rate_limits(
+    config: &PathAuthConfig,
+    path: &str,
+    client_ip: Option<&str>,
+    login_id: Option<&str>,
+    manager: &SaTokenManager,
+) -> Option<SaTokenError> {
+    for rule in config.rate_limits.iter().filter(|rule| rule.matches(path)) {
+        let subject = match rule.key() {
+            RateLimitKey::Ip => client_ip,
+            RateLimitKey::LoginId => login_id,
+        };
+        let Some(subject) = subject else { continue };
+        match rule.hit(manager.storage.as_ref(), subject).await {
+            Ok(()) => {}
+            Err(e @ SaTokenError::RateLimited(_)) => return Some(e),
+            Err(e) => tracing::warn!(target: crate::logging::TARGET_REJECT, path, error = %e, "rate limit check failed"),
+        }
+    }
+    None
 }
 
 /// Create SaTokenContext from authentication result
@@ -228,6 +1072,464 @@ pub fn create_context(result: &AuthResult) -> SaTokenContext {
         ctx.token_info = Some(Arc::new(info.clone()));
         ctx.login_id = Some(info.login_id.clone());
     }
+    ctx.client_ip = result.client_ip.clone();
+    ctx.fingerprint = result.fingerprint.clone();
     ctx
 }
 
+/// Fluent route matcher for one request, in the style of Java's `SaRouter`
+/// 单个请求的链式路由匹配器，对应 Java 版的 `SaRouter`
+///
+/// Complements the declarative `PathAuthConfig` with imperative rules inside one catch-all middleware.
+/// A rule is a run of `matches` / `not_match` calls; `check` runs only when all of them hit.
+/// `stop` ends every later rule, `reset` starts the next rule, and the first failing check
+/// ends the chain and is returned by `finish`.
+/// 作为声明式 `PathAuthConfig` 的补充，在一个兜底中间件中以命令式写规则。
+/// 一条规则由连续的 `matches` / `not_match` 组成，全部命中时才执行 `check`；
+/// `stop` 结束之后的所有规则，`reset` 开始下一条规则，第一个未通过的检查会结束整个链并由 `finish` 返回。
+///
+/// # Examples
+/// ```
+/// use sa_token_core::{SaTokenError, router::SaRouter};
+///
+/// let check_login = || Err(SaTokenError::NotLogin);
+///
+/// let result = SaRouter::new("/api/login", "POST")
+///     .matches("/api/login").stop()
+///     .reset().matches("/api/**").check(check_login)
+///     .finish();
+/// assert!(result.is_ok());
+///
+/// let result = SaRouter::new("/api/orders", "GET")
+///     .matches("/api/**").not_match("/api/login").check(check_login)
+///     .finish();
+/// assert!(matches!(result, Err(SaTokenError::NotLogin)));
+/// ```
+#[derive(Debug)]
+pub struct SaRouter {
+    path: String,
+    method: String,
+    hit: bool,
+    stopped: bool,
+    error: Option<SaTokenError>,
+}
+
+impl SaRouter {
+    /// Create a router for a request path and HTTP method
+    /// 为请求路径与 HTTP 方法创建路由匹配器
+    pub fn new(path: impl Into<String>, method: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            method: method.into(),
+            hit: true,
+            stopped: false,
+            error: None,
+        }
+    }
+
+    /// Whether the chain has ended through `stop` or a failed check
+    /// 链是否已经因 `stop` 或检查未通过而结束
+    fn ended(&self) -> bool {
+        self.stopped || self.error.is_some()
+    }
+
+    /// Whether the current rule is hit and the chain has not ended
+    /// 当前规则是否命中且链尚未结束
+    pub fn is_hit(&self) -> bool {
+        self.hit && !self.ended()
+    }
+
+    /// Whether `stop` was reached
+    /// 是否已执行到 `stop`
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Keep the rule only if the path matches the pattern (same patterns as `match_path`)
+    /// 路径匹配模式时规则才继续命中（模式与 `match_path` 相同）
+    ///
+    /// An invalid `regex:` pattern ends the chain with `ConfigError`.
+    /// 不合法的 `regex:` 模式会以 `ConfigError` 结束整个链。
+    pub fn matches(self, pattern: &str) -> Self {
+        self.matches_any(&[pattern])
+    }
+
+    /// Keep the rule only if the path matches any of the patterns
+    /// 路径匹配任一模式时规则才继续命中
+    pub fn matches_any(self, patterns: &[&str]) -> Self {
+        let hit = self.match_patterns(patterns);
+        self.matches_result(hit)
+    }
+
+    /// Keep the rule only if the path does not match the pattern
+    /// 路径不匹配模式时规则才继续命中
+    pub fn not_match(self, pattern: &str) -> Self {
+        self.not_match_any(&[pattern])
+    }
+
+    /// Keep the rule only if the path matches none of the patterns
+    /// 路径不匹配任何模式时规则才继续命中
+    pub fn not_match_any(self, patterns: &[&str]) -> Self {
+        let hit = self.match_patterns(patterns).map(|hit| !hit);
+        self.matches_result(hit)
+    }
+
+    /// Keep the rule only if the path matches a precompiled pattern
+    /// 路径匹配预编译模式时规则才继续命中
+    pub fn matches_pattern(self, pattern: &CompiledPattern) -> Self {
+        let hit = pattern.matches(&self.path);
+        self.matches_if(hit)
+    }
+
+    fn match_patterns(&self, patterns: &[&str]) -> SaTokenResult<bool> {
+        for pattern in patterns {
+            if try_match_path(&self.path, pattern)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn matches_result(mut self, hit: SaTokenResult<bool>) -> Self {
+        match hit {
+            Ok(hit) => self.matches_if(hit),
+            Err(e) => {
+                if !self.ended() {
+                    self.error = Some(e);
+                }
+                self
+            }
+        }
+    }
+
+    /// Keep the rule only if the HTTP method is one of the given methods (case-insensitive)
+    /// HTTP 方法属于给定方法之一时规则才继续命中（不区分大小写）
+    pub fn matches_method(self, methods: &[&str]) -> Self {
+        let hit = methods.iter().any(|m| m.eq_ignore_ascii_case(&self.method));
+        self.matches_if(hit)
+    }
+
+    /// Keep the rule only if the HTTP method is none of the given methods (case-insensitive)
+    /// HTTP 方法不属于给定方法时规则才继续命中（不区分大小写）
+    pub fn not_match_method(self, methods: &[&str]) -> Self {
+        let hit = !methods.iter().any(|m| m.eq_ignore_ascii_case(&self.method));
+        self.matches_if(hit)
+    }
+
+    /// Keep the rule only if the condition holds
+    /// 条件成立时规则才继续命中
+    pub fn matches_if(mut self, condition: bool) -> Self {
+        self.hit = self.hit && condition;
+        self
+    }
+
+    /// Run a check when the rule is hit, a failure ends the chain
+    /// 规则命中时执行检查，未通过时结束整个链
+    pub fn check<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce() -> SaTokenResult<()>,
+    {
+        if self.is_hit() {
+            self.error = f().err();
+        }
+        self
+    }
+
+    /// Run an async check when the rule is hit, a failure ends the chain
+    /// 规则命中时执行异步检查，未通过时结束整个链
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let result = SaRouter::new(path, method)
+    ///     .matches("/api/admin/**")
+    ///     .check_async(|| async {
+    ///         let login_id = StpUtil::get_login_id_as_string().await?;
+    ///         StpUtil::check_permission(&login_id, "admin:*").await
+    ///     }).await
+    ///     .finish();
+    /// ```
+    pub async fn check_async<F, Fut>(mut self, f: F) -> Self
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = SaTokenResult<()>>,
+    {
+        if self.is_hit() {
+            self.error = f().await.err();
+        }
+        self
+    }
+
+    /// Skip all later rules when the rule is hit
+    /// 规则命中时跳过之后的所有规则
+    pub fn stop(mut self) -> Self {
+        if self.is_hit() {
+            self.stopped = true;
+        }
+        self
+    }
+
+    /// Start the next rule
+    /// 开始下一条规则
+    pub fn reset(mut self) -> Self {
+        self.hit = true;
+        self
+    }
+
+    /// End the chain, returning the first failed check
+    /// 结束链，返回第一个未通过的检查
+    pub fn finish(self) -> SaTokenResult<()> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compiled_pattern_matches_like_match_path() {
+        let cases = [
+            ("/api/user", "/**", true),
+            ("/api/user", "/api/**", true),
+            ("/web/user", "/api/**", false),
+            ("/api/user", "/api/*", true),
+            ("/api/user/profile", "/api/*", false),
+            ("/index.html", "*.html", true),
+            ("/index.htm", "*.html", false),
+            ("/exact", "/exact", true),
+            ("/exact/more", "/exact", false),
+        ];
+        for (path, pattern, expected) in cases {
+            assert_eq!(CompiledPattern::new(pattern).matches(path), expected, "{} vs {}", path, pattern);
+            assert_eq!(match_path(path, pattern), expected, "{} vs {}", path, pattern);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_regex_patterns() {
+        use crate::SaTokenConfig;
+        use sa_token_storage_memory::MemoryStorage;
+
+        let pattern = CompiledPattern::new(r"regex:/api/users/\d+(/profile)?");
+        assert!(pattern.is_regex());
+        assert!(pattern.matches("/api/users/42"));
+        assert!(pattern.matches("/api/users/42/profile"));
+        assert!(!pattern.matches("/api/users/me"));
+        // 正则需要匹配整个路径 | The regex must match the whole path
+        assert!(!pattern.matches("/v1/api/users/42"));
+        assert!(CompiledPattern::try_new("regex:/api/(").is_err());
+
+        assert!(match_any("/api/orders/7", &[r"regex:/api/orders/\d+", "/admin/**"]));
+        let config = PathAuthConfig::new()
+            .include(vec!["/api/**".to_string()])
+            .exclude(vec![r"regex:/api/articles/\d+".to_string()]);
+        assert!(!config.check("/api/articles/7"));
+        assert!(config.check("/api/articles/drafts"));
+        assert!(config.validate().is_ok());
+
+        // 不合法的正则在请求时不会 panic | Invalid regexes never panic at request time
+        assert!(!match_path("/api/(", "regex:/api/("));
+        assert!(!match_path("/api/(", "regex:/api/("));
+        let result = SaRouter::new("/api/users", "GET")
+            .matches("regex:/api/(").check(|| Ok(()))
+            .finish();
+        assert!(matches!(result, Err(SaTokenError::ConfigError(_))));
+
+        // 构建时记录错误，请求一律拒绝 | The error is recorded when building and every request is rejected
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        let config = PathAuthConfig::new()
+            .include(vec!["/api/**".to_string()])
+            .exclude(vec!["regex:/api/(".to_string()]);
+        assert!(matches!(config.validate(), Err(SaTokenError::ConfigError(_))));
+        let result = process_auth_request("/public", Some("GET"), None, None, None, None, &config, &manager).await;
+        assert!(result.should_reject());
+        assert_eq!(result.reject_status(), 500);
+        let versioned = PathAuthConfig::new().version("v2", config);
+        assert!(versioned.validate().is_err());
+    }
+
+    #[test]
+    fn test_routes_in_priority_order() {
+        let config = PathAuthConfig::new()
+            .include(vec!["/api/**".to_string()])
+            .exclude(vec!["/api/public/**".to_string()]);
+
+        let routes = config.routes();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].kind, RouteRuleKind::Exclude);
+        assert_eq!(routes[1].kind, RouteRuleKind::Include);
+
+        assert!(config.match_rule("/api/public/info").is_some_and(|r| !r.requires_auth()));
+        assert!(config.match_rule("/api/user").is_some_and(|r| r.requires_auth()));
+        assert!(config.match_rule("/web/index").is_none());
+        assert!(!config.check("/web/index"));
+    }
+
+    #[tokio::test]
+    async fn test_sa_router_chain() {
+        let calls = std::cell::Cell::new(0);
+        let check_login = |logged_in: bool| {
+            calls.set(calls.get() + 1);
+            if logged_in { Ok(()) } else { Err(SaTokenError::NotLogin) }
+        };
+
+        // 被排除的路径不执行检查 | Excluded paths run no check
+        let result = SaRouter::new("/api/login", "POST")
+            .matches("/api/**").not_match("/api/login").check(|| check_login(false))
+            .finish();
+        assert!(result.is_ok());
+        assert_eq!(calls.get(), 0);
+
+        // 第一个未通过的检查结束整个链 | The first failed check ends the chain
+        let result = SaRouter::new("/api/orders", "DELETE")
+            .matches("/api/**").check(|| check_login(false))
+            .reset().matches_method(&["delete"]).check(|| check_login(true))
+            .finish();
+        assert!(matches!(result, Err(SaTokenError::NotLogin)));
+        assert_eq!(calls.get(), 1);
+
+        // stop 跳过之后的规则 | stop skips later rules
+        let router = SaRouter::new("/api/articles/1", "GET")
+            .matches("/api/articles/**").matches_method(&["GET"]).stop()
+            .reset().matches("/api/**")
+            .check_async(|| async { Err(SaTokenError::NotLogin) }).await;
+        assert!(router.is_stopped());
+        assert!(!router.is_hit());
+        assert!(router.finish().is_ok());
+
+        let result = SaRouter::new("/api/articles/1", "PUT")
+            .matches("/api/articles/**").matches_method(&["GET"]).stop()
+            .reset().matches_any(&["/api/**", "/admin/**"]).not_match_any(&["/api/public/**"])
+            .check_async(|| async { Err(SaTokenError::PermissionDenied) }).await
+            .finish();
+        assert!(matches!(result, Err(SaTokenError::PermissionDenied)));
+    }
+
+    #[tokio::test]
+    async fn test_method_rules() {
+        use crate::SaTokenConfig;
+        use sa_token_storage_memory::MemoryStorage;
+
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        let config = PathAuthConfig::new()
+            .include(vec!["/api/**".to_string()])
+            .exclude(vec!["/api/public/**".to_string()])
+            .exclude_methods(&["get"], vec!["/api/articles/**".to_string()])
+            .include_methods(&["POST"], vec!["/api/public/feedback".to_string()]);
+
+        assert_eq!(config.routes()[0].methods, vec!["GET".to_string()]);
+        assert!(!config.check_request(Some("GET"), "/api/articles/1"));
+        assert!(config.check_request(Some("PUT"), "/api/articles/1"));
+        assert!(!config.check_request(Some("GET"), "/api/public/feedback"));
+        assert!(config.check_request(Some("POST"), "/api/public/feedback"));
+        // 方法未知时跳过限定方法的规则 | Method rules are skipped when the method is unknown
+        assert!(config.check("/api/articles/1"));
+
+        let result = process_auth_request("/api/articles/1", Some("GET"), None, None, None, None, &config, &manager).await;
+        assert!(!result.should_reject());
+        let result = process_auth_request("/api/articles/1", Some("DELETE"), None, None, None, None, &config, &manager).await;
+        assert!(result.should_reject());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_policies() {
+        use crate::SaTokenConfig;
+        use sa_token_storage_memory::MemoryStorage;
+
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        let token = manager.login("1001").await.unwrap();
+
+        let config = PathAuthConfig::new()
+            .include(vec!["/v1/orders/**".to_string()])
+            .version("v2", PathAuthConfig::new()
+                .include(vec!["/v2/**".to_string()])
+                .guard("/v2/orders/**", Guard::login().and(Guard::safe()))
+                .rejection_renderer(|result| (result.reject_status(), format!("v2:{}", result.reject_status()))))
+            .version_header("X-Api-Version");
+
+        // v1 只要求登录 | v1 only requires login
+        let result = process_auth("/v1/orders/1", Some(token.to_string()), &config, &manager).await;
+        assert!(!result.should_reject());
+        assert_eq!(result.version, None);
+
+        // v2 还要求二级认证 | v2 also requires safe-auth
+        let result = process_auth("/v2/orders/1", Some(token.to_string()), &config, &manager).await;
+        assert!(result.should_reject());
+        assert_eq!(result.version.as_deref(), Some("v2"));
+        assert_eq!(config.render_rejection(&result, manager.error_renderer().as_ref()), (401, "v2:401".to_string()));
+
+        // 自定义响应体使用渲染器的 Content-Type | Custom bodies get the renderer's Content-Type
+        struct PlainText;
+        impl ErrorRenderer for PlainText {
+            fn render(&self, error: &SaTokenError) -> (u16, Vec<(String, String)>, String) {
+                (error.http_status(), vec![("Content-Type".to_string(), "text/plain".to_string())], error.to_string())
+            }
+            fn content_type(&self) -> &str {
+                "text/plain"
+            }
+        }
+        let (_, headers, _) = config.render_rejection_response(&result, &PlainText);
+        assert_eq!(headers, vec![("Content-Type".to_string(), "text/plain".to_string())]);
+
+        // 版本也可以来自请求头 | The version may also come from the header
+        let result = process_auth_with_version("/v2/profile", None, None, &config, &manager).await;
+        assert!(result.should_reject());
+        let result = process_auth_with_version("/profile", Some("v2"), None, &config, &manager).await;
+        assert_eq!(result.version.as_deref(), Some("v2"));
+        assert!(!result.should_reject());
+
+        // 未区分版本的请求使用默认响应体 | Unversioned requests get the default body
+        let result = process_auth("/v1/orders/1", None, &config, &manager).await;
+        let (status, body) = config.render_rejection(&result, manager.error_renderer().as_ref());
+        assert_eq!(status, 401);
+        assert!(body.contains("Authentication error"));
+        assert_eq!(config.render_rejection(&result, &PlainText).1, "User not logged in");
+    }
+
+    #[tokio::test]
+    async fn test_route_requirements() {
+        use async_trait::async_trait;
+        use crate::{SaTokenConfig, SaTokenResult};
+        use sa_token_storage_memory::MemoryStorage;
+
+        struct AdminsOnly;
+
+        #[async_trait]
+        impl PermissionChecker for AdminsOnly {
+            async fn has_permission(&self, login_id: &str, permission: &str) -> SaTokenResult<bool> {
+                Ok(login_id == "admin" && permission == "admin:*")
+            }
+
+            async fn get_permissions(&self, _login_id: &str) -> SaTokenResult<Vec<String>> {
+                Ok(Vec::new())
+            }
+        }
+
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default());
+        manager.set_roles("1001", vec!["auditor".to_string()]).await;
+        let user = manager.login("1001").await.unwrap();
+        let admin = manager.login("admin").await.unwrap();
+
+        let config = PathAuthConfig::new()
+            .route("/api/admin/**", required_permission("admin:*"))
+            .route("/api/reports/**", required_role("auditor"))
+            .permission_checker(Arc::new(AdminsOnly));
+
+        let result = process_auth("/api/admin/users", None, &config, &manager).await;
+        assert_eq!(result.reject_status(), 401);
+        let result = process_auth("/api/admin/users", Some(user.to_string()), &config, &manager).await;
+        assert!(result.should_reject());
+        assert_eq!(result.reject_status(), 403);
+        let result = process_auth("/api/admin/users", Some(admin.to_string()), &config, &manager).await;
+        assert!(!result.should_reject());
+
+        // 未配置角色检查器时使用管理器的角色数据 | Roles fall back to the manager's data without a role checker
+        let result = process_auth("/api/reports/daily", Some(user.to_string()), &config, &manager).await;
+        assert!(!result.should_reject());
+        let result = process_auth("/api/reports/daily", Some(admin.to_string()), &config, &manager).await;
+        assert!(matches!(result.guard_error, Some(SaTokenError::RoleDenied(_))));
+    }
+}
@@ -0,0 +1,217 @@
+// Author: 金书记
+//
+//! Same-Token 内部服务调用凭证 | Same-Token for Internal Service Calls
+//!
+//! 同一套系统内的服务共用一个存储中的 Same-Token，调用方在请求头中携带，
+//! 被调方校验后才放行仅限内部访问的接口。
+//! Services of the same system share a Same-Token kept in storage; callers send it in a header
+//! and callees only serve internal-only routes when it is valid.
+//!
+//! ## 流程 | Flow
+//!
+//! ```text
+//! 调用方 | Caller: SameTokenManager::get_token()
+//!   ├─→ 存储中没有：生成并保存 | missing in storage: generate and save
+//!   └─→ 已超过 timeout：refresh() 轮换 | older than timeout: rotate with refresh()
+//!         └─→ 当前 token 降为旧 token，在 grace_period 内仍然有效
+//!             the current token becomes the past token, still valid within grace_period
+//!
+//! 被调方 | Callee: SameTokenManager::check(header)
+//!   ├─→ 等于当前 token 或宽限期内的旧 token → 放行 | equals the current or past token → pass
+//!   └─→ 否则 → InvalidSameToken | otherwise → InvalidSameToken
+//! ```
+
+use std::sync::Arc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 默认的 Same-Token 请求头 | Default Same-Token header
+pub const SAME_TOKEN_HEADER: &str = "SA-SAME-TOKEN";
+
+const SAME_TOKEN_KEY: &str = "sa:same-token";
+const PAST_SAME_TOKEN_KEY: &str = "sa:same-token:past";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSameToken {
+    token: String,
+    issued_at: i64,
+}
+
+/// Same-Token 管理器 | Same-Token manager
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let same_token = SameTokenManager::new(storage.clone())
+///     .timeout(86400)
+///     .grace_period(120);
+///
+/// // 调用方 | Caller
+/// let token = same_token.get_token().await?;
+/// client.get(url).header(SAME_TOKEN_HEADER, token).send().await?;
+///
+/// // 被调方 | Callee
+/// same_token.check(&header_value).await?;
+/// ```
+#[derive(Clone)]
+pub struct SameTokenManager {
+    storage: Arc<dyn SaStorage>,
+    timeout: i64,
+    grace_period: i64,
+    header: String,
+}
+
+impl SameTokenManager {
+    /// 创建管理器（每天轮换，旧 token 宽限 60 秒）| Create a manager (rotated daily, 60s grace for the past token)
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            storage,
+            timeout: 86400,
+            grace_period: 60,
+            header: SAME_TOKEN_HEADER.to_string(),
+        }
+    }
+
+    /// 自动轮换周期（秒），`<= 0` 表示不自动轮换 | Rotation period in seconds, `<= 0` disables automatic rotation
+    pub fn timeout(mut self, timeout: i64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 轮换后旧 token 的有效时长（秒）| How long the past token stays valid after a rotation, in seconds
+    pub fn grace_period(mut self, grace_period: i64) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// 设置请求头名称 | Set the header name
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// 请求头名称 | Header name
+    pub fn header_name(&self) -> &str {
+        &self.header
+    }
+
+    /// 获取当前 Same-Token，不存在或已到期时生成新的
+    /// Get the current Same-Token, generating a new one when missing or due
+    pub async fn get_token(&self) -> SaTokenResult<String> {
+        match self.load(SAME_TOKEN_KEY).await? {
+            Some(current) if !self.is_due(&current) => Ok(current.token),
+            _ => self.refresh().await,
+        }
+    }
+
+    /// 是否为有效的 Same-Token（当前 token 或宽限期内的旧 token）
+    /// Whether the value is a valid Same-Token (the current token or the past one within its grace period)
+    pub async fn is_valid(&self, token: &str) -> bool {
+        if token.is_empty() {
+            return false;
+        }
+        for key in [SAME_TOKEN_KEY, PAST_SAME_TOKEN_KEY] {
+            if let Ok(Some(stored)) = self.load(key).await
+                && stored.token == token {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 校验 Same-Token | Check a Same-Token
+    ///
+    /// # 参数 | Parameters
+    /// * `token` - 请求头中的值 | Value of the header
+    pub async fn check(&self, token: &str) -> SaTokenResult<()> {
+        if self.is_valid(token).await {
+            Ok(())
+        } else {
+            Err(SaTokenError::InvalidSameToken)
+        }
+    }
+
+    /// 立即轮换 Same-Token，旧 token 在宽限期内仍然有效
+    /// Rotate the Same-Token now, the past token stays valid within the grace period
+    pub async fn refresh(&self) -> SaTokenResult<String> {
+        if let Some(current) = self.load(SAME_TOKEN_KEY).await?
+            && self.grace_period > 0 {
+            self.save(PAST_SAME_TOKEN_KEY, &current, Some(self.grace_period as u64)).await?;
+        } else {
+            self.storage.delete(PAST_SAME_TOKEN_KEY).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+
+        let next = StoredSameToken {
+            token: format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple()),
+            issued_at: Utc::now().timestamp(),
+        };
+        self.save(SAME_TOKEN_KEY, &next, None).await?;
+        Ok(next.token)
+    }
+
+    fn is_due(&self, stored: &StoredSameToken) -> bool {
+        self.timeout > 0 && Utc::now().timestamp() - stored.issued_at >= self.timeout
+    }
+
+    async fn load(&self, key: &str) -> SaTokenResult<Option<StoredSameToken>> {
+        let value = self.storage.get(key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        value.map(|v| serde_json::from_str(&v).map_err(SaTokenError::from)).transpose()
+    }
+
+    async fn save(&self, key: &str, stored: &StoredSameToken, ttl: Option<u64>) -> SaTokenResult<()> {
+        let value = serde_json::to_string(stored)?;
+        self.storage.set(key, &value, ttl.map(std::time::Duration::from_secs)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_same_token_rotation() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let manager = SameTokenManager::new(storage.clone()).grace_period(60);
+
+        let first = manager.get_token().await.unwrap();
+        assert_eq!(manager.get_token().await.unwrap(), first);
+        assert!(manager.check(&first).await.is_ok());
+        assert!(matches!(manager.check("forged").await, Err(SaTokenError::InvalidSameToken)));
+        assert!(!manager.is_valid("").await);
+
+        // 另一个服务实例共享同一个 token | Another service instance shares the token
+        let other = SameTokenManager::new(storage.clone());
+        assert_eq!(other.get_token().await.unwrap(), first);
+
+        // 轮换后旧 token 在宽限期内仍然有效 | The past token stays valid within the grace period
+        let second = manager.refresh().await.unwrap();
+        assert_ne!(second, first);
+        assert!(manager.is_valid(&first).await);
+        assert!(manager.is_valid(&second).await);
+
+        // 没有宽限期时旧 token 立即失效 | Without a grace period the past token is invalid at once
+        let strict = SameTokenManager::new(storage).grace_period(0);
+        let third = strict.refresh().await.unwrap();
+        assert!(!strict.is_valid(&second).await);
+        assert!(!strict.is_valid(&first).await);
+        assert!(strict.is_valid(&third).await);
+    }
+
+    #[tokio::test]
+    async fn test_same_token_expires() {
+        let storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let manager = SameTokenManager::new(storage.clone()).timeout(3600);
+
+        let stale = StoredSameToken { token: "stale".to_string(), issued_at: Utc::now().timestamp() - 7200 };
+        manager.save(SAME_TOKEN_KEY, &stale, None).await.unwrap();
+
+        let fresh = manager.get_token().await.unwrap();
+        assert_ne!(fresh, "stale");
+        assert!(manager.is_valid("stale").await);
+    }
+}
@@ -0,0 +1,278 @@
+// Author: 金书记
+//
+//! SAML 报文的 XML 解析与排他规范化 | XML parsing and exclusive canonicalization of SAML messages
+//!
+//! 只实现 SAML 签名校验需要的子集：拒绝 DOCTYPE（杜绝 XXE 与实体膨胀），丢弃注释与处理指令，
+//! 规范化算法为不含注释的 Exclusive XML Canonicalization 1.0。
+//! Implements only the subset SAML signature checks need: DOCTYPE is rejected (no XXE or entity
+//! expansion), comments and processing instructions are dropped, and canonicalization is
+//! Exclusive XML Canonicalization 1.0 without comments.
+
+use std::collections::{BTreeMap, BTreeSet};
+use xml::reader::{ParserConfig, XmlEvent};
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// XML 元素 | XML element
+#[derive(Debug, Clone)]
+pub(crate) struct Element {
+    pub prefix: Option<String>,
+    pub local: String,
+    /// 命名空间 URI，无命名空间时为空 | Namespace URI, empty without a namespace
+    pub namespace: String,
+    pub attributes: Vec<Attribute>,
+    /// 作用域内的命名空间（不含 `xml`/`xmlns`）| In-scope namespaces (without `xml`/`xmlns`)
+    pub namespaces: BTreeMap<String, String>,
+    pub children: Vec<Node>,
+}
+
+/// XML 属性 | XML attribute
+#[derive(Debug, Clone)]
+pub(crate) struct Attribute {
+    pub prefix: Option<String>,
+    pub local: String,
+    pub namespace: String,
+    pub value: String,
+}
+
+/// XML 子节点 | XML child node
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// 解析 XML 文档，返回根元素 | Parse an XML document and return its root element
+pub(crate) fn parse(xml: &str) -> SaTokenResult<Element> {
+    let invalid = |reason: String| SaTokenError::SamlResponseInvalid(reason);
+    let reader = ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(true)
+        .cdata_to_characters(true)
+        .ignore_comments(true)
+        .coalesce_characters(true)
+        .ignore_root_level_whitespace(true)
+        .create_reader(xml.as_bytes());
+
+    let mut stack: Vec<Element> = Vec::new();
+    for event in reader {
+        match event.map_err(|e| invalid(e.to_string()))? {
+            XmlEvent::Doctype { .. } => return Err(invalid("DOCTYPE is not allowed".to_string())),
+            XmlEvent::StartElement { name, attributes, namespace } => stack.push(Element {
+                prefix: name.prefix,
+                local: name.local_name,
+                namespace: name.namespace.unwrap_or_default(),
+                attributes: attributes.into_iter().map(|a| Attribute {
+                    prefix: a.name.prefix,
+                    local: a.name.local_name,
+                    namespace: a.name.namespace.unwrap_or_default(),
+                    value: a.value,
+                }).collect(),
+                namespaces: namespace.0.into_iter().filter(|(prefix, _)| prefix != "xml" && prefix != "xmlns").collect(),
+                children: Vec::new(),
+            }),
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().ok_or_else(|| invalid("unbalanced element".to_string()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Node::Element(element)),
+                    None => return Ok(element),
+                }
+            }
+            XmlEvent::Characters(text) => {
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Text(text));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(invalid("document has no root element".to_string()))
+}
+
+impl Element {
+    /// 判断元素名 | Check the element name
+    pub fn is(&self, namespace: &str, local: &str) -> bool {
+        self.namespace == namespace && self.local == local
+    }
+
+    /// 无命名空间的属性值 | Value of an attribute without a namespace
+    pub fn attr(&self, local: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|a| a.namespace.is_empty() && a.local == local)
+            .map(|a| a.value.as_str())
+    }
+
+    /// 子元素 | Child elements
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// 指定名称的子元素 | Child elements with the given name
+    pub fn children_named<'a>(&'a self, namespace: &'a str, local: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |e| e.is(namespace, local))
+    }
+
+    /// 第一个指定名称的子元素 | First child element with the given name
+    pub fn child(&self, namespace: &str, local: &str) -> Option<&Element> {
+        self.elements().find(|e| e.is(namespace, local))
+    }
+
+    /// 直接文本内容 | Direct text content
+    pub fn text(&self) -> String {
+        self.children.iter().filter_map(|node| match node {
+            Node::Text(text) => Some(text.as_str()),
+            Node::Element(_) => None,
+        }).collect()
+    }
+
+    /// 深度优先遍历自身与所有后代元素 | Depth-first walk over this element and all descendants
+    pub fn descendants(&self) -> Vec<&Element> {
+        let mut all = vec![self];
+        let mut index = 0;
+        while index < all.len() {
+            let current = all[index];
+            all.extend(current.elements());
+            index += 1;
+        }
+        all
+    }
+}
+
+/// 排他规范化（不含注释）| Exclusive canonicalization without comments
+///
+/// # 参数 | Parameters
+/// * `element` - 规范化的子树根 | Root of the subtree to canonicalize
+/// * `inclusive_prefixes` - `InclusiveNamespaces` 的 `PrefixList`，`#default` 表示默认命名空间
+///   | `PrefixList` of `InclusiveNamespaces`, `#default` standing for the default namespace
+/// * `skip` - 要排除的后代元素（封装签名变换）| Descendant to leave out (enveloped-signature transform)
+pub(crate) fn exc_c14n(element: &Element, inclusive_prefixes: &[String], skip: Option<&Element>) -> String {
+    let inclusive: Vec<&str> = inclusive_prefixes.iter()
+        .map(|p| if p == "#default" { "" } else { p.as_str() })
+        .collect();
+    let mut out = String::new();
+    write_element(&mut out, element, &BTreeMap::new(), &inclusive, skip);
+    out
+}
+
+fn write_element(
+    out: &mut String,
+    element: &Element,
+    rendered: &BTreeMap<String, String>,
+    inclusive: &[&str],
+    skip: Option<&Element>,
+) {
+    // 可见使用的前缀加上包含列表中作用域内的前缀 | Visibly utilized prefixes plus in-scope inclusive ones
+    let mut prefixes = BTreeSet::new();
+    prefixes.insert(element.prefix.as_deref().unwrap_or(""));
+    for attribute in &element.attributes {
+        if let Some(prefix) = attribute.prefix.as_deref().filter(|p| *p != "xml") {
+            prefixes.insert(prefix);
+        }
+    }
+    for prefix in inclusive {
+        if prefix.is_empty() || element.namespaces.contains_key(*prefix) {
+            prefixes.insert(prefix);
+        }
+    }
+
+    let mut scope = rendered.clone();
+    let mut declarations = String::new();
+    for prefix in prefixes {
+        let uri = element.namespaces.get(prefix).map_or("", String::as_str);
+        let current = rendered.get(prefix).map(String::as_str);
+        let needed = if prefix.is_empty() { uri != current.unwrap_or("") } else { current != Some(uri) };
+        if !needed {
+            continue;
+        }
+        if prefix.is_empty() {
+            declarations.push_str(&format!(" xmlns=\"{}\"", escape_attr(uri)));
+        } else {
+            declarations.push_str(&format!(" xmlns:{}=\"{}\"", prefix, escape_attr(uri)));
+        }
+        scope.insert(prefix.to_string(), uri.to_string());
+    }
+
+    let name = qualified(element.prefix.as_deref(), &element.local);
+    out.push('<');
+    out.push_str(&name);
+    out.push_str(&declarations);
+    let mut attributes: Vec<&Attribute> = element.attributes.iter().collect();
+    attributes.sort_by(|a, b| (&a.namespace, &a.local).cmp(&(&b.namespace, &b.local)));
+    for attribute in attributes {
+        out.push_str(&format!(
+            " {}=\"{}\"",
+            qualified(attribute.prefix.as_deref(), &attribute.local),
+            escape_attr(&attribute.value),
+        ));
+    }
+    out.push('>');
+
+    for child in &element.children {
+        match child {
+            Node::Element(e) if skip.is_some_and(|s| std::ptr::eq(s, e)) => {}
+            Node::Element(e) => write_element(out, e, &scope, inclusive, skip),
+            Node::Text(text) => out.push_str(&escape_text(text)),
+        }
+    }
+    out.push_str(&format!("</{}>", name));
+}
+
+fn qualified(prefix: Option<&str>, local: &str) -> String {
+    match prefix {
+        Some(prefix) if !prefix.is_empty() => format!("{}:{}", prefix, local),
+        _ => local.to_string(),
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#xD;")
+}
+
+fn escape_attr(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+        .replace('\t', "&#x9;")
+        .replace('\n', "&#xA;")
+        .replace('\r', "&#xD;")
+}
+
+/// 转义写入 XML 的文本与属性值 | Escape text and attribute values written into XML
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exc_c14n() {
+        let root = parse(
+            "<r:Root xmlns:r=\"urn:r\" xmlns:u=\"urn:unused\" xmlns=\"urn:d\">\
+             <r:A b=\"2\" a=\"1&amp;&quot;\"><B x:y=\"z\" xmlns:x=\"urn:x\">t&gt;<!-- c --></B><C/></r:A></r:Root>",
+        ).unwrap();
+        let a = root.elements().next().unwrap();
+
+        // 只渲染可见使用的命名空间，默认命名空间逐个子树声明
+        // Only visibly utilized namespaces are rendered; the default namespace is declared per subtree
+        assert_eq!(
+            exc_c14n(a, &[], None),
+            "<r:A xmlns:r=\"urn:r\" a=\"1&amp;&quot;\" b=\"2\"><B xmlns=\"urn:d\" xmlns:x=\"urn:x\" x:y=\"z\">t&gt;</B><C xmlns=\"urn:d\"></C></r:A>",
+        );
+        assert_eq!(
+            exc_c14n(a, &["u".to_string(), "#default".to_string()], a.elements().next()),
+            "<r:A xmlns=\"urn:d\" xmlns:r=\"urn:r\" xmlns:u=\"urn:unused\" a=\"1&amp;&quot;\" b=\"2\"><C></C></r:A>",
+        );
+        assert!(parse("<!DOCTYPE r [<!ENTITY x \"y\">]><r>&x;</r>").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+// Author: 金书记
+//
+//! SAML 报文的 XML 签名校验 | XML signature checks for SAML messages
+//!
+//! 只接受 IdP 常用的组合：排他规范化、封装签名、RSA-SHA256/RSA-SHA1 与 SHA-256/SHA-1 摘要。
+//! 签名必须是被签元素的直接子元素，且唯一的 `Reference` 指向该元素的 `ID`，以防签名包装攻击。
+//! 公钥只取自 IdP 元数据，报文中的 `KeyInfo` 被忽略。
+//! Only the combination IdPs use is accepted: exclusive canonicalization, enveloped signatures,
+//! RSA-SHA256/RSA-SHA1 and SHA-256/SHA-1 digests. The signature must be a direct child of the
+//! signed element, with its single `Reference` pointing at that element's `ID`, to rule out
+//! signature wrapping. Keys come from the IdP metadata only; `KeyInfo` in the message is ignored.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ring::signature::{self, UnparsedPublicKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use simple_asn1::ASN1Block;
+use crate::error::{SaTokenError, SaTokenResult};
+use super::dom::{exc_c14n, Element};
+
+pub(crate) const DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+pub(super) const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+pub(super) const ENVELOPED_SIGNATURE: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+pub(super) const RSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+const RSA_SHA1: &str = "http://www.w3.org/2000/09/xmldsig#rsa-sha1";
+pub(super) const DIGEST_SHA256: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+const DIGEST_SHA1: &str = "http://www.w3.org/2000/09/xmldsig#sha1";
+
+/// 校验元素的封装签名，元素未签名时返回 `false`
+/// Verify an element's enveloped signature; `false` when the element is not signed
+///
+/// # 参数 | Parameters
+/// * `element` - 被签元素 | Signed element
+/// * `keys` - PKCS#1 格式的 RSA 公钥 | RSA public keys in PKCS#1 form
+///
+/// # 错误 | Errors
+/// * `SamlSignatureInvalid` - 签名结构不受支持、摘要不符或验签失败 | Unsupported signature layout, digest mismatch or failed verification
+pub(crate) fn verify_enveloped(element: &Element, keys: &[Vec<u8>]) -> SaTokenResult<bool> {
+    let mut signatures = element.children_named(DSIG_NS, "Signature");
+    let Some(signature) = signatures.next() else {
+        return Ok(false);
+    };
+    if signatures.next().is_some() {
+        return Err(SaTokenError::SamlSignatureInvalid);
+    }
+    let invalid = || SaTokenError::SamlSignatureInvalid;
+
+    let signed_info = signature.child(DSIG_NS, "SignedInfo").ok_or_else(invalid)?;
+    let c14n = signed_info.child(DSIG_NS, "CanonicalizationMethod").ok_or_else(invalid)?;
+    if c14n.attr("Algorithm") != Some(EXC_C14N) {
+        return Err(invalid());
+    }
+    let algorithm: &'static dyn signature::VerificationAlgorithm = match signed_info
+        .child(DSIG_NS, "SignatureMethod")
+        .and_then(|m| m.attr("Algorithm"))
+    {
+        Some(RSA_SHA256) => &signature::RSA_PKCS1_2048_8192_SHA256,
+        Some(RSA_SHA1) => &signature::RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY,
+        _ => return Err(invalid()),
+    };
+
+    // 唯一的 Reference 必须指向被签元素 | The single Reference must point at the signed element
+    let mut references = signed_info.children_named(DSIG_NS, "Reference");
+    let reference = references.next().ok_or_else(invalid)?;
+    let id = element.attr("ID").filter(|id| !id.is_empty()).ok_or_else(invalid)?;
+    if references.next().is_some() || reference.attr("URI") != Some(&format!("#{}", id)) {
+        return Err(invalid());
+    }
+
+    let mut canonicalized = false;
+    let mut reference_prefixes = Vec::new();
+    if let Some(transforms) = reference.child(DSIG_NS, "Transforms") {
+        for transform in transforms.children_named(DSIG_NS, "Transform") {
+            match transform.attr("Algorithm") {
+                Some(ENVELOPED_SIGNATURE) => {}
+                Some(EXC_C14N) => {
+                    canonicalized = true;
+                    reference_prefixes = inclusive_prefixes(transform);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+    }
+    if !canonicalized {
+        return Err(invalid());
+    }
+
+    let digest_value = decode(&reference.child(DSIG_NS, "DigestValue").ok_or_else(invalid)?.text())?;
+    let content = exc_c14n(element, &reference_prefixes, Some(signature));
+    let digest = match reference.child(DSIG_NS, "DigestMethod").and_then(|m| m.attr("Algorithm")) {
+        Some(DIGEST_SHA256) => Sha256::digest(content.as_bytes()).to_vec(),
+        Some(DIGEST_SHA1) => Sha1::digest(content.as_bytes()).to_vec(),
+        _ => return Err(invalid()),
+    };
+    if digest != digest_value {
+        return Err(invalid());
+    }
+
+    let signature_value = decode(&signature.child(DSIG_NS, "SignatureValue").ok_or_else(invalid)?.text())?;
+    let signed = exc_c14n(signed_info, &inclusive_prefixes(c14n), None);
+    if keys.iter().any(|key| UnparsedPublicKey::new(algorithm, key).verify(signed.as_bytes(), &signature_value).is_ok()) {
+        Ok(true)
+    } else {
+        Err(invalid())
+    }
+}
+
+fn inclusive_prefixes(method: &Element) -> Vec<String> {
+    method.child(EXC_C14N, "InclusiveNamespaces")
+        .and_then(|n| n.attr("PrefixList"))
+        .map(|list| list.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// 解码可能带换行的 base64 | Decode base64 that may contain line breaks
+pub(crate) fn decode(text: &str) -> SaTokenResult<Vec<u8>> {
+    STANDARD.decode(text.split_whitespace().collect::<String>())
+        .map_err(|_| SaTokenError::SamlSignatureInvalid)
+}
+
+/// 从 X.509 证书（DER）中取出 PKCS#1 格式的 RSA 公钥
+/// Extract the PKCS#1 RSA public key from an X.509 certificate (DER)
+pub(crate) fn rsa_public_key(certificate: &[u8]) -> Option<Vec<u8>> {
+    let blocks = simple_asn1::from_der(certificate).ok()?;
+    let Some(ASN1Block::Sequence(_, certificate)) = blocks.first() else {
+        return None;
+    };
+    let Some(ASN1Block::Sequence(_, tbs)) = certificate.first() else {
+        return None;
+    };
+    tbs.iter().find_map(|block| match block {
+        ASN1Block::Sequence(_, items) => match items.as_slice() {
+            [ASN1Block::Sequence(_, algorithm), ASN1Block::BitString(_, _, key)]
+                if matches!(algorithm.first(), Some(ASN1Block::ObjectIdentifier(_, oid)) if *oid == simple_asn1::oid!(1, 2, 840, 113549, 1, 1, 1)) =>
+            {
+                Some(key.clone())
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::dom::parse;
+    use super::super::tests::{idp_public_key, sign};
+
+    const TEMPLATE: &str = "<saml:Assertion xmlns:saml=\"urn:oasis:names:tc:SAML:2.0:assertion\" ID=\"_a1\">\
+                            <saml:Issuer>https://idp.example.com/saml</saml:Issuer>SIGNATURE\
+                            <saml:Subject><saml:NameID>alice@example.com</saml:NameID></saml:Subject></saml:Assertion>";
+    const STANDARD_TRANSFORMS: &[&str] = &[ENVELOPED_SIGNATURE, EXC_C14N];
+
+    fn verify(xml: &str) -> SaTokenResult<bool> {
+        verify_enveloped(&parse(xml).unwrap(), &[idp_public_key()])
+    }
+
+    fn rejected(xml: &str) -> bool {
+        matches!(verify(xml), Err(SaTokenError::SamlSignatureInvalid))
+    }
+
+    #[test]
+    fn test_verify_enveloped() {
+        let signed = sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, STANDARD_TRANSFORMS);
+        assert!(verify(&signed).unwrap());
+        assert!(!verify(&TEMPLATE.replace("SIGNATURE", "")).unwrap());
+
+        // 其他密钥与篡改内容都不能通过 | Other keys and tampered content fail
+        assert!(matches!(verify_enveloped(&parse(&signed).unwrap(), &[]), Err(SaTokenError::SamlSignatureInvalid)));
+        assert!(rejected(&signed.replace("alice@example.com", "mallory@example.com")));
+    }
+
+    #[test]
+    fn test_rejects_signature_wrapping() {
+        let signed = sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, STANDARD_TRANSFORMS);
+        let signature = &signed[signed.find("<ds:Signature").unwrap()..signed.find("</ds:Signature>").unwrap() + "</ds:Signature>".len()];
+
+        // 第二个签名 | A second signature
+        assert!(rejected(&signed.replacen(signature, &format!("{signature}{signature}"), 1)));
+
+        // Reference 指向其他 ID | A Reference pointing at a different ID
+        assert!(rejected(&sign(TEMPLATE, "#_other", RSA_SHA256, DIGEST_SHA256, STANDARD_TRANSFORMS)));
+        assert!(rejected(&sign(TEMPLATE, "", RSA_SHA256, DIGEST_SHA256, STANDARD_TRANSFORMS)));
+        let renamed = sign(&TEMPLATE.replace("ID=\"_a1\"", "ID=\"_other\""), "#_other", RSA_SHA256, DIGEST_SHA256, STANDARD_TRANSFORMS);
+        assert!(verify(&renamed).unwrap());
+        assert!(rejected(&renamed.replace("ID=\"_other\"", "ID=\"_a1\"")));
+
+        // 签名不是被签元素的直接子元素 | The signature is not a direct child of the signed element
+        let nested = signed.replacen(signature, "", 1).replace("</saml:Subject>", &format!("{signature}</saml:Subject>"));
+        assert!(!verify(&nested).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_algorithms() {
+        const RSA_SHA512: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha512";
+        const DIGEST_SHA512: &str = "http://www.w3.org/2001/04/xmlenc#sha512";
+        const XPATH: &str = "http://www.w3.org/TR/1999/REC-xpath-19991116";
+        const C14N: &str = "http://www.w3.org/TR/2001/REC-xml-c14n-20010315";
+
+        assert!(rejected(&sign(TEMPLATE, "#_a1", RSA_SHA512, DIGEST_SHA256, STANDARD_TRANSFORMS)));
+        assert!(rejected(&sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA512, STANDARD_TRANSFORMS)));
+        assert!(rejected(&sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, &[ENVELOPED_SIGNATURE, EXC_C14N, XPATH])));
+
+        // 缺少排他规范化变换 | The exclusive canonicalization transform is missing
+        assert!(rejected(&sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, &[ENVELOPED_SIGNATURE])));
+        assert!(rejected(&sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, &[])));
+        assert!(rejected(&sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, &[ENVELOPED_SIGNATURE, C14N])));
+
+        // 非排他的 SignedInfo 规范化 | Non-exclusive canonicalization of SignedInfo
+        let signed = sign(TEMPLATE, "#_a1", RSA_SHA256, DIGEST_SHA256, STANDARD_TRANSFORMS);
+        assert!(rejected(&signed.replacen(&format!("<ds:CanonicalizationMethod Algorithm=\"{EXC_C14N}\"/>"), &format!("<ds:CanonicalizationMethod Algorithm=\"{C14N}\"/>"), 1)));
+    }
+}
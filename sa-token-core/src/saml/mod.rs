@@ -0,0 +1,663 @@
+// Author: 金书记
+//
+//! SAML 2.0 服务提供者 | SAML 2.0 Service Provider
+//!
+//! 让部署在企业 IdP（Okta、ADFS、Azure AD 等）之后的应用用 SAML 完成认证，内部仍使用 sa-token 会话。
+//! 需要启用 `saml` 特性。
+//! Lets apps behind corporate IdPs (Okta, ADFS, Azure AD, ...) authenticate over SAML while using
+//! sa-token sessions internally. Requires the `saml` feature.
+//!
+//! ```text
+//! IdpMetadata::from_xml(idp_metadata)      entityID、SSO 地址与签名证书 | entityID, SSO URL and signing certificates
+//!
+//! GET /saml/login
+//!   └─> authn_request(relay_state)         HTTP-Redirect 绑定 | HTTP-Redirect binding
+//!         └─> 302 IdP ?SAMLRequest=...&RelayState=...
+//!
+//! POST /saml/acs  SAMLResponse=...         HTTP-POST 绑定 | HTTP-POST binding
+//!   └─> login(saml_response)
+//!         ├─> 校验签名、Issuer、Audience、Recipient、有效期、InResponseTo 与重放
+//!         │   check signature, Issuer, Audience, Recipient, validity window, InResponseTo and replay
+//!         └─> manager.login(NameID) → Token
+//! ```
+//!
+//! 签名只支持排他规范化的封装签名（IdP 的默认配置），不支持加密断言。IdP 元数据本身的签名不做校验，
+//! 请从可信渠道获取。
+//! Only enveloped signatures with exclusive canonicalization (the IdP default) are supported, and
+//! encrypted assertions are not. The IdP metadata's own signature is not checked, so load it from a
+//! trusted source.
+//!
+//! ```text
+//! sa:saml:request:{request_id}       未完成的 AuthnRequest | Outstanding AuthnRequest
+//! sa:saml:assertion:{assertion_id}   已使用的断言 | Consumed assertion
+//! ```
+
+mod dom;
+mod dsig;
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::write::DeflateEncoder;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::id::IdKind;
+use crate::manager::SaTokenManager;
+use crate::page::{DefaultPageRenderer, PageRenderer};
+use crate::token::TokenValue;
+use dom::{escape, Element};
+
+const PROTOCOL_NS: &str = "urn:oasis:names:tc:SAML:2.0:protocol";
+const ASSERTION_NS: &str = "urn:oasis:names:tc:SAML:2.0:assertion";
+const METADATA_NS: &str = "urn:oasis:names:tc:SAML:2.0:metadata";
+const HTTP_REDIRECT_BINDING: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-Redirect";
+const HTTP_POST_BINDING: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST";
+const STATUS_SUCCESS: &str = "urn:oasis:names:tc:SAML:2.0:status:Success";
+const BEARER: &str = "urn:oasis:names:tc:SAML:2.0:cm:bearer";
+
+/// IdP 元数据 | IdP metadata
+#[derive(Debug, Clone)]
+pub struct IdpMetadata {
+    /// IdP 的 entityID，须与断言的 Issuer 一致 | IdP entityID, must equal the assertion Issuer
+    pub entity_id: String,
+    /// HTTP-Redirect 绑定的单点登录地址 | Single sign-on URL of the HTTP-Redirect binding
+    pub sso_url: String,
+    /// 签名证书（DER）| Signing certificates (DER)
+    pub certificates: Vec<Vec<u8>>,
+}
+
+impl IdpMetadata {
+    /// 解析 IdP 元数据 XML，支持 `EntityDescriptor` 与包含它的 `EntitiesDescriptor`
+    /// Parse IdP metadata XML, either an `EntityDescriptor` or an `EntitiesDescriptor` holding one
+    ///
+    /// # 错误 | Errors
+    /// * `ConfigError` - 元数据无法解析、缺少 HTTP-Redirect 单点登录地址或 RSA 签名证书
+    ///   | Unparsable metadata, or no HTTP-Redirect SSO URL or RSA signing certificate
+    pub fn from_xml(xml: &str) -> SaTokenResult<Self> {
+        let invalid = |reason: &str| SaTokenError::ConfigError(format!("Invalid IdP metadata: {}", reason));
+        let root = dom::parse(xml).map_err(|e| invalid(&e.to_string()))?;
+        let (entity, idp) = root.descendants().into_iter()
+            .filter(|e| e.is(METADATA_NS, "EntityDescriptor"))
+            .find_map(|e| e.child(METADATA_NS, "IDPSSODescriptor").map(|idp| (e, idp)))
+            .ok_or_else(|| invalid("no EntityDescriptor with an IDPSSODescriptor"))?;
+
+        let entity_id = entity.attr("entityID").ok_or_else(|| invalid("missing entityID"))?.to_string();
+        let sso_url = idp.children_named(METADATA_NS, "SingleSignOnService")
+            .find(|s| s.attr("Binding") == Some(HTTP_REDIRECT_BINDING))
+            .and_then(|s| s.attr("Location"))
+            .ok_or_else(|| invalid("no HTTP-Redirect SingleSignOnService"))?
+            .to_string();
+
+        let mut certificates = Vec::new();
+        for key in idp.children_named(METADATA_NS, "KeyDescriptor").filter(|k| k.attr("use").is_none_or(|u| u == "signing")) {
+            let data = key.child(dsig::DSIG_NS, "KeyInfo").and_then(|i| i.child(dsig::DSIG_NS, "X509Data"));
+            for certificate in data.iter().flat_map(|d| d.children_named(dsig::DSIG_NS, "X509Certificate")) {
+                let der = dsig::decode(&certificate.text()).map_err(|_| invalid("X509Certificate is not base64"))?;
+                dsig::rsa_public_key(&der).ok_or_else(|| invalid("signing certificate has no RSA public key"))?;
+                certificates.push(der);
+            }
+        }
+        if certificates.is_empty() {
+            return Err(invalid("no signing certificate"));
+        }
+        Ok(Self { entity_id, sso_url, certificates })
+    }
+}
+
+/// 发往 IdP 的认证请求 | Authentication request sent to the IdP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamlAuthnRequest {
+    /// 请求 ID，IdP 在响应的 `InResponseTo` 中返回 | Request ID, echoed by the IdP in `InResponseTo`
+    pub id: String,
+    /// 携带 `SAMLRequest` 的 IdP 跳转地址 | IdP redirect URL carrying the `SAMLRequest`
+    pub url: String,
+}
+
+/// 校验通过的断言 | Validated assertion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamlAssertion {
+    /// 断言 ID | Assertion ID
+    pub id: String,
+    /// 签发断言的 IdP | IdP that issued the assertion
+    pub issuer: String,
+    /// 主体标识，默认作为 login_id | Subject identifier, used as login_id by default
+    pub name_id: String,
+    /// NameID 格式 | NameID format
+    pub name_id_format: Option<String>,
+    /// IdP 会话索引 | IdP session index
+    pub session_index: Option<String>,
+    /// 属性，每个属性可有多个值 | Attributes; each may have several values
+    pub attributes: BTreeMap<String, Vec<String>>,
+}
+
+/// SAML 2.0 服务提供者 | SAML 2.0 service provider
+#[derive(Clone)]
+pub struct SamlServiceProvider {
+    manager: Arc<SaTokenManager>,
+    entity_id: String,
+    acs_url: String,
+    idp: IdpMetadata,
+    keys: Vec<Vec<u8>>,
+    request_timeout: i64,
+    clock_skew: i64,
+    allow_idp_initiated: bool,
+    page_renderer: Arc<dyn PageRenderer>,
+}
+
+impl SamlServiceProvider {
+    /// 创建服务提供者 | Create a service provider
+    ///
+    /// # 参数 | Parameters
+    /// * `manager` - 完成本地登录的管理器 | Manager performing the local login
+    /// * `entity_id` - SP 的 entityID，即断言的 Audience | SP entityID, the assertion Audience
+    /// * `acs_url` - 断言消费地址（ACS），即断言的 Recipient | Assertion consumer service URL, the assertion Recipient
+    /// * `idp` - IdP 元数据 | IdP metadata
+    pub fn new(manager: Arc<SaTokenManager>, entity_id: impl Into<String>, acs_url: impl Into<String>, idp: IdpMetadata) -> Self {
+        let keys = idp.certificates.iter().filter_map(|c| dsig::rsa_public_key(c)).collect();
+        Self {
+            manager,
+            entity_id: entity_id.into(),
+            acs_url: acs_url.into(),
+            idp,
+            keys,
+            request_timeout: 300,
+            clock_skew: 120,
+            allow_idp_initiated: false,
+            page_renderer: Arc::new(DefaultPageRenderer::new()),
+        }
+    }
+
+    /// AuthnRequest 的有效期（秒），默认 300 | Lifetime of an AuthnRequest in seconds, 300 by default
+    pub fn with_request_timeout(mut self, seconds: i64) -> Self {
+        self.request_timeout = seconds;
+        self
+    }
+
+    /// 允许的时钟偏差（秒），默认 120 | Allowed clock skew in seconds, 120 by default
+    pub fn with_clock_skew(mut self, seconds: i64) -> Self {
+        self.clock_skew = seconds;
+        self
+    }
+
+    /// 是否接受 IdP 发起（无 `InResponseTo`）的响应，默认拒绝
+    /// Whether IdP-initiated responses (without `InResponseTo`) are accepted; rejected by default
+    pub fn with_idp_initiated(mut self, allow: bool) -> Self {
+        self.allow_idp_initiated = allow;
+        self
+    }
+
+    /// 设置 ACS 错误页的渲染器 | Set the renderer of ACS error pages
+    pub fn with_page_renderer(mut self, renderer: Arc<dyn PageRenderer>) -> Self {
+        self.page_renderer = renderer;
+        self
+    }
+
+    /// 获取页面渲染器 | Get the page renderer
+    pub fn page_renderer(&self) -> &Arc<dyn PageRenderer> {
+        &self.page_renderer
+    }
+
+    /// SP 的 entityID | SP entityID
+    pub fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    /// 断言消费地址 | Assertion consumer service URL
+    pub fn acs_url(&self) -> &str {
+        &self.acs_url
+    }
+
+    /// IdP 元数据 | IdP metadata
+    pub fn idp(&self) -> &IdpMetadata {
+        &self.idp
+    }
+
+    /// 生成 HTTP-Redirect 绑定的 AuthnRequest，请求 ID 在有效期内保存以校验 `InResponseTo`
+    /// Build an HTTP-Redirect binding AuthnRequest; its ID is kept for its lifetime to check `InResponseTo`
+    ///
+    /// # 参数 | Parameters
+    /// * `relay_state` - 原样带回 ACS 的状态，通常为登录后回跳的站内路径
+    ///   | State returned untouched to the ACS, usually the site path to return to after login
+    pub async fn authn_request(&self, relay_state: Option<&str>) -> SaTokenResult<SamlAuthnRequest> {
+        let id = format!("_{}", self.manager.id_generator().generate(IdKind::SamlRequest));
+        let xml = format!(
+            "<samlp:AuthnRequest xmlns:samlp=\"{}\" xmlns:saml=\"{}\" ID=\"{}\" Version=\"2.0\" IssueInstant=\"{}\" \
+             Destination=\"{}\" AssertionConsumerServiceURL=\"{}\" ProtocolBinding=\"{}\">\
+             <saml:Issuer>{}</saml:Issuer><samlp:NameIDPolicy AllowCreate=\"true\"/></samlp:AuthnRequest>",
+            PROTOCOL_NS,
+            ASSERTION_NS,
+            id,
+            Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            escape(&self.idp.sso_url),
+            escape(&self.acs_url),
+            HTTP_POST_BINDING,
+            escape(&self.entity_id),
+        );
+        self.manager.storage.set(&Self::request_key(&id), "1", Some(Duration::from_secs(self.request_timeout.max(1) as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        // HTTP-Redirect 绑定：DEFLATE → base64 → URL 编码 | HTTP-Redirect binding: DEFLATE → base64 → URL-encode
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes())
+            .and_then(|_| encoder.finish())
+            .map(|deflated| {
+                let separator = if self.idp.sso_url.contains('?') { '&' } else { '?' };
+                let mut url = format!("{}{}SAMLRequest={}", self.idp.sso_url, separator, urlencoding::encode(&STANDARD.encode(deflated)));
+                if let Some(relay_state) = relay_state {
+                    url.push_str(&format!("&RelayState={}", urlencoding::encode(relay_state)));
+                }
+                SamlAuthnRequest { id, url }
+            })
+            .map_err(|e| SaTokenError::InternalError(e.to_string()))
+    }
+
+    /// SP 元数据 XML，供在 IdP 注册应用 | SP metadata XML, for registering the app with the IdP
+    pub fn sp_metadata(&self) -> String {
+        format!(
+            "<md:EntityDescriptor xmlns:md=\"{}\" entityID=\"{}\">\n    \
+             <md:SPSSODescriptor AuthnRequestsSigned=\"false\" WantAssertionsSigned=\"true\" protocolSupportEnumeration=\"{}\">\n        \
+             <md:AssertionConsumerService Binding=\"{}\" Location=\"{}\" index=\"0\" isDefault=\"true\"/>\n    \
+             </md:SPSSODescriptor>\n</md:EntityDescriptor>\n",
+            METADATA_NS,
+            escape(&self.entity_id),
+            PROTOCOL_NS,
+            HTTP_POST_BINDING,
+            escape(&self.acs_url),
+        )
+    }
+
+    /// 校验 HTTP-POST 绑定送达的 `SAMLResponse` | Validate a `SAMLResponse` delivered over the HTTP-POST binding
+    ///
+    /// # 参数 | Parameters
+    /// * `saml_response` - 表单中的 base64 `SAMLResponse` | Base64 `SAMLResponse` from the form
+    ///
+    /// # 错误 | Errors
+    /// * `SamlResponseInvalid` - 报文格式、状态、Issuer、Audience、Recipient 或 `InResponseTo` 不符
+    ///   | Malformed message, or wrong status, Issuer, Audience, Recipient or `InResponseTo`
+    /// * `SamlSignatureInvalid` - 响应与断言均未签名，或签名无效 | Neither response nor assertion signed, or a bad signature
+    /// * `SamlAssertionExpired` - 断言不在有效期内 | Assertion outside its validity window
+    /// * `SamlAssertionReplayed` - 断言已被使用过 | Assertion already used
+    pub async fn validate_response(&self, saml_response: &str) -> SaTokenResult<SamlAssertion> {
+        let invalid = |reason: &str| SaTokenError::SamlResponseInvalid(reason.to_string());
+        let decoded = STANDARD.decode(saml_response.split_whitespace().collect::<String>())
+            .map_err(|_| invalid("SAMLResponse is not valid base64"))?;
+        let xml = String::from_utf8(decoded).map_err(|_| invalid("SAMLResponse is not UTF-8"))?;
+        let response = dom::parse(&xml)?;
+        if !response.is(PROTOCOL_NS, "Response") {
+            return Err(invalid("root element is not a Response"));
+        }
+
+        // 重复的 ID 会让签名引用指向另一个元素 | Duplicate IDs would let a signature reference another element
+        let mut ids = HashSet::new();
+        if !response.descendants().into_iter().filter_map(|e| e.attr("ID")).all(|id| ids.insert(id)) {
+            return Err(invalid("duplicate ID"));
+        }
+        if response.attr("Destination").is_some_and(|d| d != self.acs_url) {
+            return Err(invalid("Destination mismatch"));
+        }
+        let status = response.child(PROTOCOL_NS, "Status")
+            .and_then(|s| s.child(PROTOCOL_NS, "StatusCode"))
+            .and_then(|c| c.attr("Value"))
+            .unwrap_or_default();
+        if status != STATUS_SUCCESS {
+            return Err(SaTokenError::SamlResponseInvalid(format!("status '{}'", status)));
+        }
+        if response.child(ASSERTION_NS, "EncryptedAssertion").is_some() {
+            return Err(invalid("encrypted assertions are not supported"));
+        }
+        let mut assertions = response.children_named(ASSERTION_NS, "Assertion");
+        let (Some(assertion), None) = (assertions.next(), assertions.next()) else {
+            return Err(invalid("expected exactly one Assertion"));
+        };
+
+        let assertion_signed = dsig::verify_enveloped(assertion, &self.keys)?;
+        let response_signed = dsig::verify_enveloped(&response, &self.keys)?;
+        if !assertion_signed && !response_signed {
+            return Err(SaTokenError::SamlSignatureInvalid);
+        }
+
+        let issuer = assertion.child(ASSERTION_NS, "Issuer").map(|i| i.text().trim().to_string()).unwrap_or_default();
+        let response_issuer = response.child(ASSERTION_NS, "Issuer").map(|i| i.text().trim().to_string());
+        if issuer != self.idp.entity_id || response_issuer.is_some_and(|i| i != self.idp.entity_id) {
+            return Err(invalid("Issuer mismatch"));
+        }
+
+        let now = Utc::now();
+        let conditions = assertion.child(ASSERTION_NS, "Conditions").ok_or_else(|| invalid("missing Conditions"))?;
+        let mut expires = self.check_window(conditions, now)?;
+        let mut restrictions = conditions.children_named(ASSERTION_NS, "AudienceRestriction").peekable();
+        if restrictions.peek().is_none() {
+            return Err(invalid("missing AudienceRestriction"));
+        }
+        for restriction in restrictions {
+            if !restriction.children_named(ASSERTION_NS, "Audience").any(|a| a.text().trim() == self.entity_id) {
+                return Err(invalid("Audience mismatch"));
+            }
+        }
+
+        let subject = assertion.child(ASSERTION_NS, "Subject").ok_or_else(|| invalid("missing Subject"))?;
+        let name_id = subject.child(ASSERTION_NS, "NameID").ok_or_else(|| invalid("missing NameID"))?;
+        let subject_id = name_id.text().trim().to_string();
+        if subject_id.is_empty() {
+            return Err(invalid("empty NameID"));
+        }
+        let confirmation = subject.children_named(ASSERTION_NS, "SubjectConfirmation")
+            .find(|c| c.attr("Method") == Some(BEARER))
+            .and_then(|c| c.child(ASSERTION_NS, "SubjectConfirmationData"))
+            .ok_or_else(|| invalid("missing bearer SubjectConfirmationData"))?;
+        if confirmation.attr("Recipient") != Some(self.acs_url.as_str()) {
+            return Err(invalid("Recipient mismatch"));
+        }
+        if confirmation.attr("NotOnOrAfter").is_none() {
+            return Err(invalid("SubjectConfirmationData has no NotOnOrAfter"));
+        }
+        expires = expires.max(self.check_window(confirmation, now)?);
+        let id = assertion.attr("ID").ok_or_else(|| invalid("Assertion has no ID"))?;
+
+        let in_response_to = match (confirmation.attr("InResponseTo"), response.attr("InResponseTo")) {
+            (Some(a), Some(b)) if a != b => return Err(invalid("InResponseTo mismatch")),
+            (a, b) => a.or(b),
+        };
+        let request_key = match in_response_to {
+            Some(request_id) => {
+                let key = Self::request_key(request_id);
+                let outstanding = self.manager.storage.get(&key).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+                if outstanding.is_none() {
+                    return Err(invalid("unknown or expired InResponseTo"));
+                }
+                Some(key)
+            }
+            None if self.allow_idp_initiated => None,
+            None => return Err(invalid("unsolicited responses are not allowed")),
+        };
+
+        // 全部检查通过后才记录断言 ID，保留到过期，防止同一断言被再次提交
+        // Only once every check passed, keep the assertion ID until expiry so it cannot be posted twice
+        let ttl = (expires - now).num_seconds().max(0) + self.clock_skew.max(0) + 1;
+        let fresh = self.manager.storage.set_nx(&format!("sa:saml:assertion:{}", id), "1", Some(Duration::from_secs(ttl as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !fresh {
+            return Err(SaTokenError::SamlAssertionReplayed);
+        }
+        if let Some(key) = request_key {
+            self.manager.storage.delete(&key).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+
+        let mut attributes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for statement in assertion.children_named(ASSERTION_NS, "AttributeStatement") {
+            for attribute in statement.children_named(ASSERTION_NS, "Attribute") {
+                let Some(name) = attribute.attr("Name") else { continue };
+                attributes.entry(name.to_string()).or_default()
+                    .extend(attribute.children_named(ASSERTION_NS, "AttributeValue").map(|v| v.text().trim().to_string()));
+            }
+        }
+        Ok(SamlAssertion {
+            id: id.to_string(),
+            issuer,
+            name_id: subject_id,
+            name_id_format: name_id.attr("Format").map(str::to_string),
+            session_index: assertion.child(ASSERTION_NS, "AuthnStatement").and_then(|s| s.attr("SessionIndex")).map(str::to_string),
+            attributes,
+        })
+    }
+
+    /// 校验响应并以 NameID 作为 login_id 完成本地登录
+    /// Validate a response and log in locally with the NameID as login_id
+    ///
+    /// 需要把主体映射为其他 login_id 时，先调用 `validate_response`，再自行 `StpUtil::login`。
+    /// To map the subject to a different login_id, call `validate_response` and then `StpUtil::login` yourself.
+    ///
+    /// # 返回 | Returns
+    /// 断言与新 Token | The assertion and the new token
+    pub async fn login(&self, saml_response: &str) -> SaTokenResult<(SamlAssertion, TokenValue)> {
+        let assertion = self.validate_response(saml_response).await?;
+        let token = self.manager.login(assertion.name_id.clone()).await?;
+        Ok((assertion, token))
+    }
+
+    /// 保存本地 Token 的 `Set-Cookie` 值 | `Set-Cookie` value holding the local token
+    pub fn token_cookie(&self, token: &str) -> String {
+        self.manager.token_cookie(&TokenValue::new(token))
+    }
+
+    /// 校验 `NotBefore`/`NotOnOrAfter`，返回过期时间 | Check `NotBefore`/`NotOnOrAfter` and return the expiry
+    fn check_window(&self, element: &Element, now: DateTime<Utc>) -> SaTokenResult<DateTime<Utc>> {
+        let skew = chrono::Duration::seconds(self.clock_skew);
+        if let Some(not_before) = element.attr("NotBefore")
+            && now + skew < instant(not_before)?
+        {
+            return Err(SaTokenError::SamlAssertionExpired);
+        }
+        match element.attr("NotOnOrAfter") {
+            Some(not_on_or_after) => {
+                let expires = instant(not_on_or_after)?;
+                if now - skew >= expires {
+                    return Err(SaTokenError::SamlAssertionExpired);
+                }
+                Ok(expires)
+            }
+            None => Ok(now),
+        }
+    }
+
+    fn request_key(request_id: &str) -> String {
+        format!("sa:saml:request:{}", request_id)
+    }
+}
+
+fn instant(value: &str) -> SaTokenResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| SaTokenError::SamlResponseInvalid(format!("invalid timestamp '{}'", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use flate2::read::DeflateDecoder;
+    use ring::rand::SystemRandom;
+    use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+    use sha2::{Digest, Sha256};
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+    use dsig::{DIGEST_SHA256, ENVELOPED_SIGNATURE, EXC_C14N, RSA_SHA256};
+
+    const IDP_METADATA: &str = include_str!("testdata/idp_metadata.xml");
+    const IDP_KEY: &str = include_str!("../token/testdata/rsa_private.pem");
+    const SP_ENTITY_ID: &str = "https://sp.example.com";
+    const ACS_URL: &str = "https://sp.example.com/saml/acs";
+
+    /// 模拟 IdP 签发带签名断言的响应 | Issue a response with a signed assertion, as the IdP would
+    fn signed_response(in_response_to: &str, assertion_id: &str, audience: &str) -> String {
+        let assertion = sign(
+            &assertion_template(in_response_to, assertion_id, audience),
+            &format!("#{assertion_id}"), RSA_SHA256, DIGEST_SHA256, &[ENVELOPED_SIGNATURE, EXC_C14N],
+        );
+        STANDARD.encode(response_template(in_response_to, &format!("_response{assertion_id}"), "", &assertion))
+    }
+
+    /// 模拟 IdP 只对响应签名，断言本身不签名 | Sign only the response, leaving the assertion unsigned, as some IdPs do
+    fn response_signed_xml(in_response_to: &str, assertion_id: &str) -> String {
+        let assertion = assertion_template(in_response_to, assertion_id, SP_ENTITY_ID).replace("SIGNATURE", "");
+        let response_id = format!("_response{assertion_id}");
+        sign(
+            &response_template(in_response_to, &response_id, "SIGNATURE", &assertion),
+            &format!("#{response_id}"), RSA_SHA256, DIGEST_SHA256, &[ENVELOPED_SIGNATURE, EXC_C14N],
+        )
+    }
+
+    /// 断言模板，签名位置为 `SIGNATURE` | Assertion template with `SIGNATURE` marking the signature slot
+    fn assertion_template(in_response_to: &str, assertion_id: &str, audience: &str) -> String {
+        let now = Utc::now();
+        let time = |t: DateTime<Utc>| t.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let (issued, later) = (time(now - chrono::Duration::seconds(5)), time(now + chrono::Duration::seconds(300)));
+        format!(
+            "<saml:Assertion xmlns:saml=\"{ASSERTION_NS}\" ID=\"{assertion_id}\" Version=\"2.0\" IssueInstant=\"{issued}\">\n  \
+             <saml:Issuer>https://idp.example.com/saml</saml:Issuer>SIGNATURE\n  \
+             <saml:Subject><saml:NameID Format=\"urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress\">alice@example.com</saml:NameID>\
+             <saml:SubjectConfirmation Method=\"{BEARER}\"><saml:SubjectConfirmationData InResponseTo=\"{in_response_to}\" \
+             NotOnOrAfter=\"{later}\" Recipient=\"{ACS_URL}\"/></saml:SubjectConfirmation></saml:Subject>\n  \
+             <saml:Conditions NotBefore=\"{issued}\" NotOnOrAfter=\"{later}\"><saml:AudienceRestriction>\
+             <saml:Audience>{audience}</saml:Audience></saml:AudienceRestriction></saml:Conditions>\n  \
+             <saml:AuthnStatement AuthnInstant=\"{issued}\" SessionIndex=\"_session\"/>\n  \
+             <saml:AttributeStatement><saml:Attribute Name=\"groups\"><saml:AttributeValue>admin</saml:AttributeValue>\
+             <saml:AttributeValue>r&amp;d</saml:AttributeValue></saml:Attribute></saml:AttributeStatement>\n\
+             </saml:Assertion>"
+        )
+    }
+
+    /// 包住断言的响应，`signature` 放在 Issuer 之后 | Response around the assertion, `signature` placed after the Issuer
+    fn response_template(in_response_to: &str, response_id: &str, signature: &str, body: &str) -> String {
+        let issued = Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+        format!(
+            "<samlp:Response xmlns:samlp=\"{PROTOCOL_NS}\" ID=\"{response_id}\" Version=\"2.0\" IssueInstant=\"{issued}\" \
+             Destination=\"{ACS_URL}\" InResponseTo=\"{in_response_to}\">\
+             <saml:Issuer xmlns:saml=\"{ASSERTION_NS}\">https://idp.example.com/saml</saml:Issuer>{signature}\
+             <samlp:Status><samlp:StatusCode Value=\"{STATUS_SUCCESS}\"/></samlp:Status>{body}</samlp:Response>",
+        )
+    }
+
+    /// IdP 签名密钥 | The IdP signing key
+    fn idp_key() -> RsaKeyPair {
+        let pkcs8 = dsig::decode(&IDP_KEY.lines().filter(|l| !l.starts_with("-----")).collect::<String>()).unwrap();
+        RsaKeyPair::from_pkcs8(&pkcs8).unwrap()
+    }
+
+    /// IdP 的 PKCS#1 公钥 | The IdP's PKCS#1 public key
+    pub(super) fn idp_public_key() -> Vec<u8> {
+        idp_key().public().as_ref().to_vec()
+    }
+
+    /// 用 IdP 密钥生成封装签名，替换模板中的 `SIGNATURE`；摘要总是按模板去掉签名后计算
+    /// Produce an enveloped signature with the IdP key in place of `SIGNATURE` in the template;
+    /// the digest always covers the template without the signature
+    ///
+    /// # 参数 | Parameters
+    /// * `template` - 含 `SIGNATURE` 占位的被签元素 | Signed element holding the `SIGNATURE` placeholder
+    /// * `uri` - `Reference` 的 URI | URI of the `Reference`
+    /// * `signature_method` / `digest_method` - 声明的算法，实际总是 RSA-SHA256 / SHA-256 | Declared algorithms, always RSA-SHA256 / SHA-256 in fact
+    /// * `transforms` - `Reference` 的变换 | Transforms of the `Reference`
+    pub(super) fn sign(template: &str, uri: &str, signature_method: &str, digest_method: &str, transforms: &[&str]) -> String {
+        let c14n = |xml: &str| dom::exc_c14n(&dom::parse(xml).unwrap(), &[], None);
+        let digest = STANDARD.encode(Sha256::digest(c14n(&template.replace("SIGNATURE", "")).as_bytes()));
+        let transforms: String = transforms.iter().map(|t| format!("<ds:Transform Algorithm=\"{t}\"/>")).collect();
+        let signed_info = format!(
+            "<ds:SignedInfo xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\">\
+             <ds:CanonicalizationMethod Algorithm=\"{EXC_C14N}\"/>\
+             <ds:SignatureMethod Algorithm=\"{signature_method}\"/>\
+             <ds:Reference URI=\"{uri}\"><ds:Transforms>{transforms}</ds:Transforms>\
+             <ds:DigestMethod Algorithm=\"{digest_method}\"/>\
+             <ds:DigestValue>{digest}</ds:DigestValue></ds:Reference></ds:SignedInfo>"
+        );
+        let key = idp_key();
+        let mut signature = vec![0; key.public().modulus_len()];
+        key.sign(&RSA_PKCS1_SHA256, &SystemRandom::new(), c14n(&signed_info).as_bytes(), &mut signature).unwrap();
+        let signature = format!(
+            "<ds:Signature xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\">{}<ds:SignatureValue>{}</ds:SignatureValue></ds:Signature>",
+            signed_info.replacen(" xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\"", "", 1),
+            STANDARD.encode(signature),
+        );
+        template.replace("SIGNATURE", &signature)
+    }
+
+    #[tokio::test]
+    async fn test_saml_login() {
+        let idp = IdpMetadata::from_xml(IDP_METADATA).unwrap();
+        assert_eq!(idp.entity_id, "https://idp.example.com/saml");
+        assert_eq!(idp.sso_url, "https://idp.example.com/saml/sso");
+
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let sp = SamlServiceProvider::new(manager.clone(), SP_ENTITY_ID, ACS_URL, idp);
+        let request = sp.authn_request(Some("/dashboard")).await.unwrap();
+        let (query, relay_state) = request.url.strip_prefix("https://idp.example.com/saml/sso?SAMLRequest=").unwrap()
+            .split_once("&RelayState=").unwrap();
+        assert_eq!(relay_state, "%2Fdashboard");
+        let mut xml = String::new();
+        DeflateDecoder::new(&STANDARD.decode(urlencoding::decode(query).unwrap().as_bytes()).unwrap()[..])
+            .read_to_string(&mut xml).unwrap();
+        assert!(xml.contains(&format!("ID=\"{}\"", request.id)) && xml.contains(ACS_URL));
+
+        let response = signed_response(&request.id, "_assertion1", SP_ENTITY_ID);
+        let (assertion, token) = sp.login(&response).await.unwrap();
+        assert_eq!(assertion.name_id, "alice@example.com");
+        assert_eq!(assertion.session_index.as_deref(), Some("_session"));
+        assert_eq!(assertion.attributes["groups"], vec!["admin".to_string(), "r&d".to_string()]);
+        assert_eq!(manager.get_token_info(&token).await.unwrap().login_id, "alice@example.com");
+        // 请求已被消费，重放的响应在 InResponseTo 处被拒绝；换一个请求也不能再用同一断言
+        // The request was consumed, so a replay fails on InResponseTo; another request cannot reuse the assertion either
+        assert!(matches!(sp.login(&response).await, Err(SaTokenError::SamlResponseInvalid(_))));
+        let other = sp.authn_request(None).await.unwrap();
+        let response = signed_response(&other.id, "_assertion1", SP_ENTITY_ID);
+        assert!(matches!(sp.login(&response).await, Err(SaTokenError::SamlAssertionReplayed)));
+
+        // 篡改断言、错误的受众与未知请求都被拒绝
+        // Tampered assertions, wrong audiences and unknown requests are rejected
+        let request = sp.authn_request(None).await.unwrap();
+        let tampered = String::from_utf8(STANDARD.decode(signed_response(&request.id, "_assertion2", SP_ENTITY_ID)).unwrap()).unwrap()
+            .replace("alice@example.com", "mallory@example.com");
+        assert!(matches!(sp.validate_response(&STANDARD.encode(tampered)).await, Err(SaTokenError::SamlSignatureInvalid)));
+        let response = signed_response(&request.id, "_assertion3", "https://other.example.com");
+        assert!(matches!(sp.validate_response(&response).await, Err(SaTokenError::SamlResponseInvalid(_))));
+        let response = signed_response("_unknown", "_assertion4", SP_ENTITY_ID);
+        assert!(matches!(sp.validate_response(&response).await, Err(SaTokenError::SamlResponseInvalid(_))));
+        assert!(sp.validate_response(&signed_response(&request.id, "_assertion5", SP_ENTITY_ID)).await.is_ok());
+
+        // 被拒绝的响应不会占用断言 ID | A rejected response does not use up the assertion ID
+        let request = sp.authn_request(None).await.unwrap();
+        assert!(sp.validate_response(&signed_response(&request.id, "_assertion4", SP_ENTITY_ID)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_saml_signature_wrapping() {
+        let idp = IdpMetadata::from_xml(IDP_METADATA).unwrap();
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let sp = SamlServiceProvider::new(manager, SP_ENTITY_ID, ACS_URL, idp);
+        let assertion_of = |xml: &str| {
+            let start = xml.find("<saml:Assertion").unwrap();
+            let end = xml.find("</saml:Assertion>").unwrap() + "</saml:Assertion>".len();
+            xml[start..end].to_string()
+        };
+        let forge = |assertion: &str, id: &str| {
+            let unsigned = match (assertion.find("<ds:Signature"), assertion.find("</ds:Signature>")) {
+                (Some(start), Some(end)) => format!("{}{}", &assertion[..start], &assertion[end + "</ds:Signature>".len()..]),
+                _ => assertion.to_string(),
+            };
+            unsigned.replacen(&format!("ID=\"{id}\""), "ID=\"_evil\"", 1).replace("alice@example.com", "mallory@example.com")
+        };
+
+        // 对响应签名、断言未签名是合法的 | A signed response around an unsigned assertion is accepted
+        let request = sp.authn_request(None).await.unwrap();
+        let response = response_signed_xml(&request.id, "_assertion1");
+        assert_eq!(sp.validate_response(&STANDARD.encode(&response)).await.unwrap().name_id, "alice@example.com");
+
+        // 把断言移出已签名的响应，换上伪造的断言 | Moving the assertion out of the signed response for a forged one
+        let request = sp.authn_request(None).await.unwrap();
+        let response = response_signed_xml(&request.id, "_assertion2");
+        let assertion = assertion_of(&response);
+        let moved = response.replace(&assertion, &format!("<samlp:Extensions>{assertion}</samlp:Extensions>{}", forge(&assertion, "_assertion2")));
+        assert!(matches!(sp.validate_response(&STANDARD.encode(&moved)).await, Err(SaTokenError::SamlSignatureInvalid)));
+
+        // 把整个已签名的响应藏进新的未签名响应 | Hiding the whole signed response inside a new unsigned one
+        let wrapped = response_template(
+            &request.id, "_wrapper", "",
+            &format!("{}<samlp:Extensions>{response}</samlp:Extensions>", forge(&assertion, "_assertion2")),
+        );
+        assert!(matches!(sp.validate_response(&STANDARD.encode(&wrapped)).await, Err(SaTokenError::SamlSignatureInvalid)));
+
+        // 把已签名的断言藏起来，换上未签名的伪造断言 | Hiding the signed assertion behind an unsigned forged one
+        let response = String::from_utf8(STANDARD.decode(signed_response(&request.id, "_assertion3", SP_ENTITY_ID)).unwrap()).unwrap();
+        let assertion = assertion_of(&response);
+        let moved = response.replace(&assertion, &format!("{}<samlp:Extensions>{assertion}</samlp:Extensions>", forge(&assertion, "_assertion3")));
+        assert!(matches!(sp.validate_response(&STANDARD.encode(&moved)).await, Err(SaTokenError::SamlSignatureInvalid)));
+
+        // 上面被拒绝的请求仍可完成 | The request rejected above can still complete
+        assert!(sp.validate_response(&STANDARD.encode(&response)).await.is_ok());
+    }
+}
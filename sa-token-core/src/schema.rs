@@ -0,0 +1,160 @@
+// Author: 金书记
+//
+//! 存储数据版本 | Storage Schema Version
+//!
+//! ## 流程 | Flow
+//!
+//! ```text
+//! 启动 | Startup: SaTokenManager::ensure_schema()
+//!   └─→ 读取 sa:schema:version | read sa:schema:version
+//!         ├─→ 不存在：新部署或标记之前的数据（兼容），写入当前版本
+//!         │   missing: fresh deployment or data from before markers (compatible), write the current version
+//!         ├─→ 等于 SCHEMA_VERSION：直接通过 | equal to SCHEMA_VERSION: ok
+//!         ├─→ 低于当前版本：依次执行 source_version = v, v+1, ... 的迁移器，每步后更新标记
+//!         │   older: run migrators with source_version = v, v+1, ... and update the marker after each step
+//!         │     └─→ 缺少某一步的迁移器 → SchemaVersionMismatch | a step without migrator → SchemaVersionMismatch
+//!         └─→ 高于当前版本（新版本写入的数据）→ SchemaVersionMismatch
+//!             newer (written by a newer release) → SchemaVersionMismatch
+//! ```
+//!
+//! 在启动时失败并给出明确的错误，而不是在运行中反序列化失败、随机把用户登出。
+//! Failing at startup with a clear error beats deserialization failures at runtime that log users out at random.
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+
+/// 当前存储数据版本 | Current storage schema version
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// 版本标记的存储键 | Storage key of the version marker
+pub const SCHEMA_VERSION_KEY: &str = "sa:schema:version";
+
+/// 数据迁移器 | Schema migrator
+///
+/// 每个迁移器把数据从 `source_version` 升级到 `source_version + 1`
+/// Every migrator upgrades the data from `source_version` to `source_version + 1`
+///
+/// # 使用示例 | Usage Example
+///
+/// ```rust,ignore
+/// struct RenameDeviceField;
+///
+/// #[async_trait]
+/// impl SchemaMigrator for RenameDeviceField {
+///     fn source_version(&self) -> u32 { 1 }
+///
+///     async fn migrate(&self, storage: &dyn SaStorage) -> SaTokenResult<()> {
+///         for key in storage.keys("sa:token:*").await.map_err(|e| SaTokenError::StorageError(e.to_string()))? {
+///             // 读取、转换并写回 | read, transform and write back
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait SchemaMigrator: Send + Sync {
+    /// 迁移前的版本 | Version before the migration
+    fn source_version(&self) -> u32;
+
+    /// 执行迁移 | Run the migration
+    ///
+    /// # 参数 | Parameters
+    /// - `storage`: 存储后端 | Storage backend
+    async fn migrate(&self, storage: &dyn SaStorage) -> SaTokenResult<()>;
+}
+
+/// 读取存储中的数据版本，`None` 表示没有标记 | Read the schema version in storage, `None` when unmarked
+pub async fn stored_version(storage: &dyn SaStorage) -> SaTokenResult<Option<u32>> {
+    let value = storage.get(SCHEMA_VERSION_KEY).await
+        .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+    value.map(|v| v.trim().parse::<u32>()
+        .map_err(|_| SaTokenError::ConfigError(format!("invalid schema version marker '{}'", v))))
+        .transpose()
+}
+
+/// 检查数据版本并按需执行迁移，返回最终版本 | Check the schema version, migrate if needed and return the final version
+///
+/// # 参数 | Parameters
+/// - `storage`: 存储后端 | Storage backend
+/// - `migrators`: 已注册的迁移器 | Registered migrators
+pub async fn ensure_schema(storage: &dyn SaStorage, migrators: &[Arc<dyn SchemaMigrator>]) -> SaTokenResult<u32> {
+    let Some(mut version) = stored_version(storage).await? else {
+        write_version(storage, SCHEMA_VERSION).await?;
+        return Ok(SCHEMA_VERSION);
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(SaTokenError::SchemaVersionMismatch { found: version, expected: SCHEMA_VERSION });
+    }
+
+    while version < SCHEMA_VERSION {
+        let migrator = migrators.iter()
+            .find(|m| m.source_version() == version)
+            .ok_or(SaTokenError::SchemaVersionMismatch { found: version, expected: SCHEMA_VERSION })?;
+        tracing::info!("Migrating sa-token storage schema from v{} to v{}", version, version + 1);
+        migrator.migrate(storage).await?;
+        version += 1;
+        write_version(storage, version).await?;
+    }
+
+    Ok(version)
+}
+
+async fn write_version(storage: &dyn SaStorage, version: u32) -> SaTokenResult<()> {
+    storage.set(SCHEMA_VERSION_KEY, &version.to_string(), None).await
+        .map_err(|e| SaTokenError::StorageError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use sa_token_storage_memory::MemoryStorage;
+
+    struct CountingMigrator {
+        from: u32,
+        runs: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SchemaMigrator for CountingMigrator {
+        fn source_version(&self) -> u32 {
+            self.from
+        }
+
+        async fn migrate(&self, _storage: &dyn SaStorage) -> SaTokenResult<()> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensure_schema() {
+        let storage = MemoryStorage::new();
+
+        // 新部署写入当前版本 | A fresh deployment writes the current version
+        assert_eq!(ensure_schema(&storage, &[]).await.unwrap(), SCHEMA_VERSION);
+        assert_eq!(stored_version(&storage).await.unwrap(), Some(SCHEMA_VERSION));
+
+        // 新版本写入的数据不能被旧版本读取 | Data written by a newer release is rejected
+        storage.set(SCHEMA_VERSION_KEY, &(SCHEMA_VERSION + 1).to_string(), None).await.unwrap();
+        assert!(matches!(
+            ensure_schema(&storage, &[]).await,
+            Err(SaTokenError::SchemaVersionMismatch { found, expected }) if found == SCHEMA_VERSION + 1 && expected == SCHEMA_VERSION
+        ));
+
+        // 旧数据缺少迁移器时报错，注册后迁移 | Older data fails without a migrator and migrates with one
+        storage.set(SCHEMA_VERSION_KEY, &(SCHEMA_VERSION - 1).to_string(), None).await.unwrap();
+        assert!(ensure_schema(&storage, &[]).await.is_err());
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let migrators: Vec<Arc<dyn SchemaMigrator>> = vec![
+            Arc::new(CountingMigrator { from: SCHEMA_VERSION - 1, runs: runs.clone() }),
+        ];
+        assert_eq!(ensure_schema(&storage, &migrators).await.unwrap(), SCHEMA_VERSION);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(stored_version(&storage).await.unwrap(), Some(SCHEMA_VERSION));
+    }
+}
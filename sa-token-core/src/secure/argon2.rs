@@ -0,0 +1,148 @@
+// Author: 金书记
+//
+//! Argon2id 密码哈希（RFC 9106，基于 `argon2` crate）| Argon2id password hashing (RFC 9106, backed by the `argon2` crate)
+//!
+//! 哈希以 PHC 字符串格式保存：`$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`
+//! Hashes are stored as PHC strings: `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`
+
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordVerifier, Version};
+use argon2::password_hash::{PasswordHasher as _, SaltString};
+use crate::error::{SaTokenError, SaTokenResult};
+use super::{PasswordHasher, Pbkdf2Hasher};
+
+/// 默认内存成本（KiB，OWASP 推荐 19 MiB）| Default memory cost in KiB (19 MiB as recommended by OWASP)
+pub const DEFAULT_ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+
+/// 默认迭代次数 | Default iteration count
+pub const DEFAULT_ARGON2_ITERATIONS: u32 = 2;
+
+/// 默认并行度 | Default parallelism
+pub const DEFAULT_ARGON2_PARALLELISM: u32 = 1;
+
+/// Argon2id 哈希器（默认）| Argon2id hasher (the default)
+///
+/// 验证时也接受早期版本生成的 PBKDF2 哈希，并通过 `needs_rehash` 提示升级为 Argon2id。
+/// Verification also accepts PBKDF2 hashes created by earlier versions, and `needs_rehash`
+/// asks for them to be upgraded to Argon2id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Hasher {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Argon2Hasher {
+    /// PHC 算法标识 | PHC algorithm identifier
+    pub const ID: &'static str = "argon2id";
+
+    /// 创建哈希器 | Create a hasher
+    ///
+    /// # 参数 | Parameters
+    /// * `memory_kib` - 内存成本（KiB），至少为 `8 * parallelism` | Memory cost in KiB, at least `8 * parallelism`
+    /// * `iterations` - 迭代次数，至少为 1 | Iteration count, at least 1
+    /// * `parallelism` - 并行度（lane 数），至少为 1 | Parallelism (number of lanes), at least 1
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        let parallelism = parallelism.clamp(1, 0xFF_FFFF);
+        Self {
+            memory_kib: memory_kib.max(8 * parallelism),
+            iterations: iterations.max(1),
+            parallelism,
+        }
+    }
+
+    /// 内存成本（KiB）| Memory cost in KiB
+    pub fn memory_kib(&self) -> u32 {
+        self.memory_kib
+    }
+
+    /// 迭代次数 | Iteration count
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// 并行度 | Parallelism
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+
+    /// 解析本哈希器生成的 PHC 字符串 | Parse a PHC string produced by this hasher
+    fn parse(hash: &str) -> Option<PasswordHash<'_>> {
+        PasswordHash::new(hash).ok()
+            .filter(|parsed| parsed.algorithm == argon2::ARGON2ID_IDENT && parsed.version == Some(Version::V0x13.into()))
+    }
+
+    fn argon2(&self) -> SaTokenResult<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+impl Default for Argon2Hasher {
+    fn default() -> Self {
+        Self::new(DEFAULT_ARGON2_MEMORY_KIB, DEFAULT_ARGON2_ITERATIONS, DEFAULT_ARGON2_PARALLELISM)
+    }
+}
+
+impl PasswordHasher for Argon2Hasher {
+    fn hash(&self, password: &str) -> SaTokenResult<String> {
+        let mut salt = [0u8; 16];
+        getrandom::fill(&mut salt).map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        let salt = SaltString::encode_b64(&salt).map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        self.argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| SaTokenError::InternalError(e.to_string()))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> SaTokenResult<bool> {
+        let Some(parsed) = Self::parse(hash) else {
+            // 早期版本生成的 PBKDF2 哈希 | PBKDF2 hashes created by earlier versions
+            return Ok(Pbkdf2Hasher::verify(password, hash));
+        };
+        // 使用哈希中记录的参数 | Uses the parameters recorded in the hash
+        Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        Self::parse(hash)
+            .and_then(|parsed| Params::try_from(&parsed).ok())
+            .is_none_or(|params| {
+                params.m_cost() < self.memory_kib
+                    || params.t_cost() < self.iterations
+                    || params.p_cost() < self.parallelism
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argon2id() {
+        let hasher = Argon2Hasher::new(64, 2, 2);
+        let hash = hasher.hash("s3cret").unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=64,t=2,p=2$"));
+        assert_ne!(hash, hasher.hash("s3cret").unwrap());
+        assert!(hasher.verify("s3cret", &hash).unwrap());
+        assert!(!hasher.verify("s3cret!", &hash).unwrap());
+        assert!(!hasher.needs_rehash(&hash));
+        assert!(Argon2Hasher::new(128, 2, 2).needs_rehash(&hash));
+        assert!(Argon2Hasher::new(64, 3, 2).needs_rehash(&hash));
+
+        // OpenSSL 生成的参考哈希 | Reference hash produced by OpenSSL
+        let reference = "$argon2id$v=19$m=64,t=2,p=2$c29tZXNhbHRzb21lc2FsdA$wmMxfQAvhLU0L6ZvoWnkvOajVqE/GRkzRjIu81IuBwI";
+        assert!(hasher.verify("password", reference).unwrap());
+        assert!(!hasher.verify("password!", reference).unwrap());
+        // argon2i 与 argon2d 哈希不被当作 Argon2id 接受 | argon2i and argon2d hashes are not accepted as Argon2id
+        assert!(!hasher.verify("password", &reference.replacen("argon2id", "argon2i", 1)).unwrap());
+
+        // 早期版本的 PBKDF2 哈希仍可验证，并需要升级 | Legacy PBKDF2 hashes still verify and need an upgrade
+        let legacy = "$pbkdf2-sha256$i=1000$MDEyMzQ1Njc4OWFiY2RlZg$\
+                      Pz0s4HPO+TyHnyTxIR8Lx+fEZojc3Lc26DQkf4ZOWCk";
+        assert!(hasher.verify("s3cret", legacy).unwrap());
+        assert!(!hasher.verify("s3cret!", legacy).unwrap());
+        assert!(hasher.needs_rehash(legacy));
+    }
+}
@@ -0,0 +1,99 @@
+// Author: 金书记
+//
+//! bcrypt 密码哈希（需启用 `bcrypt` feature，基于 `bcrypt` crate）| bcrypt password hashing (requires the `bcrypt` feature, backed by the `bcrypt` crate)
+//!
+//! 哈希格式为 `$2b$<cost>$<22 位盐><31 位哈希>`，可与其他语言的 bcrypt 实现互通。
+//! 注意 bcrypt 只使用密码的前 72 个字节。
+//! Hashes use the `$2b$<cost>$<22-char salt><31-char hash>` format shared with bcrypt
+//! implementations in other languages. Note that bcrypt only uses the first 72 bytes of a password.
+
+use bcrypt::{HashParts, Version};
+use crate::error::{SaTokenError, SaTokenResult};
+use super::PasswordHasher;
+
+/// 默认成本（2^12 轮密钥扩展）| Default cost (2^12 key expansion rounds)
+pub const DEFAULT_BCRYPT_COST: u32 = 12;
+
+/// bcrypt 哈希器 | bcrypt hasher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BcryptHasher {
+    cost: u32,
+}
+
+impl BcryptHasher {
+    /// 生成哈希时使用的版本前缀 | Version prefix used for new hashes
+    pub const ID: &'static str = "2b";
+
+    /// 创建哈希器 | Create a hasher
+    ///
+    /// # 参数 | Parameters
+    /// * `cost` - 成本（对数轮数），限制在 4..=31 | Cost (log2 of the rounds), clamped to 4..=31
+    pub fn new(cost: u32) -> Self {
+        Self { cost: cost.clamp(4, 31) }
+    }
+
+    /// 成本 | Cost
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    /// 解析哈希，接受 2a / 2b / 2y 版本 | Parse a hash; accepts the 2a / 2b / 2y versions
+    fn parse(hash: &str) -> Option<HashParts> {
+        if !["$2a$", "$2b$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+            return None;
+        }
+        hash.parse().ok()
+    }
+}
+
+impl Default for BcryptHasher {
+    fn default() -> Self {
+        Self::new(DEFAULT_BCRYPT_COST)
+    }
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn hash(&self, password: &str) -> SaTokenResult<String> {
+        bcrypt::hash_with_result(password, self.cost)
+            .map(|parts| parts.format_for_version(Version::TwoB))
+            .map_err(|e| SaTokenError::InternalError(e.to_string()))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> SaTokenResult<bool> {
+        if Self::parse(hash).is_none() {
+            return Ok(false);
+        }
+        Ok(bcrypt::verify(password, hash).unwrap_or(false))
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        Self::parse(hash).is_none_or(|parts| parts.get_cost() < self.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bcrypt() {
+        // OpenBSD / libxcrypt 生成的参考哈希 | Reference hash produced by OpenBSD / libxcrypt
+        let reference = "$2b$04$abcdefghijklmnopqrstuughE8Ev8uGFaUgY2cNEySvxngrb/Jzdm";
+        let hasher = BcryptHasher::new(4);
+        assert!(hasher.verify("password", reference).unwrap());
+        assert!(hasher.verify("password", &reference.replacen("2b", "2y", 1)).unwrap());
+        assert!(!hasher.verify("password!", reference).unwrap());
+
+        let hash = hasher.hash("s3cret").unwrap();
+        assert!(hash.starts_with("$2b$04$"));
+        assert_eq!(hash.len(), 60);
+        assert_ne!(hash, hasher.hash("s3cret").unwrap());
+        assert!(hasher.verify("s3cret", &hash).unwrap());
+        assert!(!hasher.verify("s3cret!", &hash).unwrap());
+        assert!(!hasher.verify("s3cret", "$argon2id$v=19$m=64,t=2,p=2$x$y").unwrap());
+
+        assert!(!hasher.needs_rehash(&hash));
+        assert!(BcryptHasher::new(5).needs_rehash(&hash));
+        assert_eq!(BcryptHasher::new(1).cost(), 4);
+    }
+}
@@ -0,0 +1,250 @@
+// Author: 金书记
+//
+//! 密码哈希与安全工具 | Password Hashing & Security Helpers
+//!
+//! `SaSecureUtil` 提供登录时最常用的密码哈希、校验与常量时间比较，不必再引入第三个 crate。
+//! `SaSecureUtil` covers the most common login step — hashing and verifying passwords, plus
+//! constant-time comparison — without wiring in a third crate.
+//!
+//! ```text
+//! 注册 | Sign-up   SaSecureUtil::hash_password("secret")
+//!                   → $argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>
+//! 登录 | Login     SaSecureUtil::verify_password("secret", &stored)
+//!                   └─→ needs_rehash(&stored)? → 用新参数重新哈希 | rehash with the current cost
+//! ```
+//!
+//! 默认算法为 Argon2id（OWASP 推荐参数 m=19 MiB, t=2, p=1），哈希以 PHC 字符串格式保存；
+//! 启用 `bcrypt` feature 后可改用 `BcryptHasher`。早期版本生成的 PBKDF2 哈希仍可验证，
+//! `needs_rehash` 会提示在下次登录时升级。
+//! The default algorithm is Argon2id (OWASP parameters m=19 MiB, t=2, p=1), stored as a PHC
+//! string; `BcryptHasher` is available with the `bcrypt` feature. PBKDF2 hashes created by earlier
+//! versions still verify, and `needs_rehash` asks for them to be upgraded on the next login.
+
+use std::sync::{Arc, RwLock};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use crate::error::{SaTokenError, SaTokenResult};
+
+mod argon2;
+#[cfg(feature = "bcrypt")]
+mod bcrypt;
+
+pub use argon2::{Argon2Hasher, DEFAULT_ARGON2_ITERATIONS, DEFAULT_ARGON2_MEMORY_KIB, DEFAULT_ARGON2_PARALLELISM};
+#[cfg(feature = "bcrypt")]
+pub use bcrypt::{BcryptHasher, DEFAULT_BCRYPT_COST};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 全局密码哈希器 | Global password hasher
+static PASSWORD_HASHER: Lazy<RwLock<Arc<dyn PasswordHasher>>> =
+    Lazy::new(|| RwLock::new(Arc::new(Argon2Hasher::default())));
+
+/// 密码哈希算法 | Password hashing algorithm
+pub trait PasswordHasher: Send + Sync {
+    /// 哈希密码，结果自带算法、参数与盐 | Hash a password; the result carries the algorithm, cost and salt
+    fn hash(&self, password: &str) -> SaTokenResult<String>;
+
+    /// 校验密码，哈希格式不属于本算法时返回 false | Verify a password; false when the hash is not in this algorithm's format
+    fn verify(&self, password: &str, hash: &str) -> SaTokenResult<bool>;
+
+    /// 哈希是否需要按当前参数重新计算（算法或成本已变化）
+    /// Whether the hash should be recomputed with the current settings (algorithm or cost changed)
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let _ = hash;
+        false
+    }
+}
+
+/// PBKDF2-HMAC-SHA256 旧哈希校验器 | Verifier for legacy PBKDF2-HMAC-SHA256 hashes
+///
+/// 仅用于验证早期版本生成的 `$pbkdf2-sha256$` 哈希，不再生成新哈希；`Argon2Hasher` 验证时会自动回退到这里。
+/// Only verifies `$pbkdf2-sha256$` hashes created by earlier versions and never produces new ones;
+/// `Argon2Hasher` falls back to it during verification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pbkdf2Hasher;
+
+impl Pbkdf2Hasher {
+    /// PHC 算法标识 | PHC algorithm identifier
+    pub const ID: &'static str = "pbkdf2-sha256";
+
+    /// 校验密码，哈希格式不是 PBKDF2 时返回 false | Verify a password; false when the hash is not a PBKDF2 hash
+    pub fn verify(password: &str, hash: &str) -> bool {
+        let Some((iterations, salt, expected)) = Self::parse(hash) else {
+            return false;
+        };
+        let key = pbkdf2_sha256(password.as_bytes(), &salt, iterations, expected.len());
+        SaSecureUtil::constant_time_eq(&key, &expected)
+    }
+
+    fn parse(hash: &str) -> Option<(u32, Vec<u8>, Vec<u8>)> {
+        let mut parts = hash.strip_prefix('$')?.split('$');
+        if parts.next()? != Self::ID {
+            return None;
+        }
+        let iterations = parts.next()?.strip_prefix("i=")?.parse().ok()?;
+        let salt = STANDARD_NO_PAD.decode(parts.next()?).ok()?;
+        let key = STANDARD_NO_PAD.decode(parts.next()?).ok()?;
+        if parts.next().is_some() || key.is_empty() || iterations == 0 {
+            return None;
+        }
+        Some((iterations, salt, key))
+    }
+}
+
+/// 高熵密钥哈希器（加盐 SHA-256）| Hasher for high-entropy secrets (salted SHA-256)
+///
+/// 适用于随机生成的客户端密钥、API Key 等；这类密钥无法被字典猜测，单次 SHA-256 即可，
+/// 也不会让每次令牌请求都付出 Argon2 的计算成本。人设定的密码请使用 `Argon2Hasher`。
+/// Meant for randomly generated client secrets and API keys: they cannot be guessed from a
+/// dictionary, so one SHA-256 round is enough and token requests avoid the Argon2 cost. Use
+/// `Argon2Hasher` for passwords chosen by people.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SecretHasher;
+
+impl SecretHasher {
+    /// PHC 算法标识 | PHC algorithm identifier
+    pub const ID: &'static str = "sha256";
+
+    fn digest(salt: &[u8], secret: &str) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        hasher.update(secret.as_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+impl PasswordHasher for SecretHasher {
+    fn hash(&self, secret: &str) -> SaTokenResult<String> {
+        let mut salt = [0u8; 16];
+        getrandom::fill(&mut salt).map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        Ok(format!(
+            "${}${}${}",
+            Self::ID,
+            STANDARD_NO_PAD.encode(salt),
+            STANDARD_NO_PAD.encode(Self::digest(&salt, secret)),
+        ))
+    }
+
+    fn verify(&self, secret: &str, hash: &str) -> SaTokenResult<bool> {
+        let parsed = hash.strip_prefix(&format!("${}$", Self::ID))
+            .and_then(|rest| rest.split_once('$'))
+            .and_then(|(salt, key)| Some((STANDARD_NO_PAD.decode(salt).ok()?, STANDARD_NO_PAD.decode(key).ok()?)));
+        let Some((salt, expected)) = parsed else {
+            return Ok(false);
+        };
+        Ok(SaSecureUtil::constant_time_eq(&Self::digest(&salt, secret), &expected))
+    }
+}
+
+/// PBKDF2-HMAC-SHA256（RFC 8018）
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+    let prf = HmacSha256::new_from_slice(password).expect("HMAC accepts keys of any length");
+    let mut out = Vec::with_capacity(len);
+    for block in 1..=len.div_ceil(32) as u32 {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&block.to_be_bytes());
+        let mut u = mac.finalize().into_bytes();
+        let mut t = u;
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize().into_bytes();
+            t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+        }
+        out.extend_from_slice(&t);
+    }
+    out.truncate(len);
+    out
+}
+
+/// 安全工具类 | Security helpers
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// // 注册 | Sign-up
+/// let stored = SaSecureUtil::hash_password(&password)?;
+///
+/// // 登录 | Login
+/// if !SaSecureUtil::verify_password(&password, &user.password_hash) {
+///     StpUtil::record_failed_login(&user.id).await?;
+///     return Err(ApiError::Unauthorized(messages::INVALID_CREDENTIALS.to_string()));
+/// }
+/// if SaSecureUtil::needs_rehash(&user.password_hash) {
+///     user.password_hash = SaSecureUtil::hash_password(&password)?;
+/// }
+/// let token = StpUtil::login(&user.id).await?;
+/// ```
+pub struct SaSecureUtil;
+
+impl SaSecureUtil {
+    /// 设置全局密码哈希器（如自定义 Argon2 成本，或 `BcryptHasher`）
+    /// Set the global password hasher (e.g. a custom Argon2 cost, or `BcryptHasher`)
+    pub fn set_password_hasher(hasher: Arc<dyn PasswordHasher>) {
+        *PASSWORD_HASHER.write().unwrap_or_else(|e| e.into_inner()) = hasher;
+    }
+
+    /// 获取全局密码哈希器 | Get the global password hasher
+    pub fn password_hasher() -> Arc<dyn PasswordHasher> {
+        PASSWORD_HASHER.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 哈希密码 | Hash a password
+    pub fn hash_password(password: &str) -> SaTokenResult<String> {
+        Self::password_hasher().hash(password)
+    }
+
+    /// 校验密码，哈希格式错误时返回 false | Verify a password, false for a malformed hash
+    pub fn verify_password(password: &str, hash: &str) -> bool {
+        Self::password_hasher().verify(password, hash).unwrap_or(false)
+    }
+
+    /// 哈希是否需要按当前哈希器重新计算 | Whether the hash should be recomputed with the current hasher
+    pub fn needs_rehash(hash: &str) -> bool {
+        Self::password_hasher().needs_rehash(hash)
+    }
+
+    /// 常量时间比较，耗时与内容无关（长度不同时立即返回 false）
+    /// Constant-time comparison whose timing does not depend on the content (returns false at once for different lengths)
+    pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hashing() {
+        // RFC 7914 第 11 节的 PBKDF2-HMAC-SHA256 测试向量 | PBKDF2-HMAC-SHA256 vector from RFC 7914 section 11
+        let key = pbkdf2_sha256(b"passwd", b"salt", 1, 64);
+        assert_eq!(
+            hex::encode(key),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783",
+        );
+
+        let legacy = format!(
+            "$pbkdf2-sha256$i=1000${}${}",
+            STANDARD_NO_PAD.encode(b"0123456789abcdef"),
+            STANDARD_NO_PAD.encode(pbkdf2_sha256(b"s3cret", b"0123456789abcdef", 1000, 32)),
+        );
+        assert!(Pbkdf2Hasher::verify("s3cret", &legacy));
+        assert!(!Pbkdf2Hasher::verify("s3cret!", &legacy));
+        assert!(!Pbkdf2Hasher::verify("s3cret", "$2b$12$not-pbkdf2"));
+
+        let hash = SecretHasher.hash("client-secret").unwrap();
+        assert!(hash.starts_with("$sha256$"));
+        assert!(SecretHasher.verify("client-secret", &hash).unwrap());
+        assert!(!SecretHasher.verify("client-secret!", &hash).unwrap());
+        assert!(!SecretHasher.verify("client-secret", "client-secret").unwrap());
+
+        assert!(SaSecureUtil::constant_time_eq(b"abc", b"abc"));
+        assert!(!SaSecureUtil::constant_time_eq(b"abc", b"abd"));
+        assert!(!SaSecureUtil::constant_time_eq(b"abc", b"ab"));
+    }
+}
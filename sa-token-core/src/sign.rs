@@ -0,0 +1,361 @@
+// Author: 金书记
+//
+//! API Signing | API 签名
+//!
+//! HMAC-SHA256 signature verification for request bodies (webhooks, callbacks).
+//! 请求体的 HMAC-SHA256 签名校验（用于 Webhook、回调等接口）。
+//!
+//! ## Streaming Verification | 流式校验
+//!
+//! ```text
+//! 1. BodySignVerifier.start()          → BodySignState
+//! 2. For each body chunk | 对每个数据块
+//!    BodySignState.update(chunk)
+//!    ├─→ Feed chunk into HMAC | 写入 HMAC
+//!    ├─→ Buffer chunk | 缓存数据块
+//!    └─→ Reject when total > max_body_size | 超过上限立即拒绝
+//! 3. BodySignState.finish(signature)
+//!    ├─→ Constant-time compare | 常量时间比较
+//!    └─→ Return buffered body | 返回缓存的请求体
+//! ```
+//!
+//! The body is hashed while it is buffered, so an oversized payload is rejected as soon as
+//! the cap is crossed instead of being loaded into memory first.
+//! 请求体在缓存的同时计算摘要，超出上限时立即拒绝，而不是先整体读入内存。
+//!
+//! ## Parameter Signing | 参数签名
+//!
+//! ```text
+//! 调用方 | Caller: SaSignManager.sign(params)
+//!   └─→ 追加 timestamp、nonce，对排序后的 k=v&k=v 计算 HMAC-SHA256 得到 sign
+//!       add timestamp and nonce, HMAC-SHA256 over the sorted k=v&k=v gives sign
+//!
+//! 被调方 | Callee: SaSignManager.verify(params)
+//!   ├─→ 缺少 sign / timestamp / nonce → SignatureMissing
+//!   ├─→ timestamp 超出时间窗口 | outside the window → SignTimestampExpired
+//!   ├─→ 摘要不匹配 | digest mismatch → InvalidSignature
+//!   └─→ nonce 已使用 | nonce already used → NonceAlreadyUsed
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::nonce::NonceManager;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 默认签名请求头 | Default signature header
+pub const DEFAULT_SIGN_HEADER: &str = "X-Sa-Sign";
+
+/// 默认请求体大小上限（1 MiB）| Default body size cap (1 MiB)
+pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// 请求体签名校验器 | Request body signature verifier
+///
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::sign::BodySignVerifier;
+///
+/// let verifier = BodySignVerifier::new("webhook-secret").max_body_size(64 * 1024);
+/// let signature = verifier.sign(b"{\"event\":\"paid\"}");
+///
+/// let mut state = verifier.start();
+/// state.update(b"{\"event\":").unwrap();
+/// state.update(b"\"paid\"}").unwrap();
+/// let body = state.finish(&signature).unwrap();
+/// assert_eq!(body, b"{\"event\":\"paid\"}");
+/// ```
+#[derive(Clone)]
+pub struct BodySignVerifier {
+    secret: Vec<u8>,
+    header: String,
+    max_body_size: usize,
+}
+
+impl BodySignVerifier {
+    /// 使用签名密钥创建校验器 | Create a verifier with the signing secret
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+            header: DEFAULT_SIGN_HEADER.to_string(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// 设置携带签名的请求头 | Set the header that carries the signature
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// 设置请求体大小上限（字节）| Set the body size cap in bytes
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// 签名请求头名称 | Signature header name
+    pub fn header_name(&self) -> &str {
+        &self.header
+    }
+
+    /// 请求体大小上限 | Body size cap
+    pub fn body_limit(&self) -> usize {
+        self.max_body_size
+    }
+
+    /// 计算请求体签名（小写十六进制）| Compute the body signature (lowercase hex)
+    pub fn sign(&self, body: &[u8]) -> String {
+        let mut mac = self.new_mac();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// 开始一次流式校验 | Start a streaming verification
+    pub fn start(&self) -> BodySignState {
+        BodySignState {
+            mac: self.new_mac(),
+            body: Vec::new(),
+            max_body_size: self.max_body_size,
+        }
+    }
+
+    /// 一次性校验完整请求体 | Verify a complete body at once
+    pub fn verify(&self, body: &[u8], signature: &str) -> SaTokenResult<()> {
+        let mut state = self.start();
+        state.update(body)?;
+        state.finish(signature).map(|_| ())
+    }
+
+    fn new_mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.secret)
+            .expect("HMAC accepts keys of any length")
+    }
+}
+
+/// 流式校验状态 | Streaming verification state
+pub struct BodySignState {
+    mac: HmacSha256,
+    body: Vec<u8>,
+    max_body_size: usize,
+}
+
+impl BodySignState {
+    /// 写入一个数据块，超出上限时返回 `BodyTooLarge`
+    /// Feed a chunk, returns `BodyTooLarge` once the cap is exceeded
+    pub fn update(&mut self, chunk: &[u8]) -> SaTokenResult<()> {
+        if self.body.len() + chunk.len() > self.max_body_size {
+            return Err(SaTokenError::BodyTooLarge(self.max_body_size));
+        }
+        self.mac.update(chunk);
+        self.body.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    /// 完成校验并返回缓存的请求体 | Finish verification and return the buffered body
+    ///
+    /// 签名支持纯十六进制或 `sha256=<hex>` 格式
+    /// The signature may be plain hex or in `sha256=<hex>` form
+    pub fn finish(self, signature: &str) -> SaTokenResult<Vec<u8>> {
+        let signature = signature.trim();
+        let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+        let expected = hex::decode(signature)
+            .map_err(|_| SaTokenError::InvalidSignature)?;
+
+        self.mac.verify_slice(&expected)
+            .map_err(|_| SaTokenError::InvalidSignature)?;
+
+        Ok(self.body)
+    }
+}
+
+/// 签名参数名：时间戳（毫秒）| Signature parameter: timestamp in milliseconds
+pub const SIGN_TIMESTAMP_PARAM: &str = "timestamp";
+
+/// 签名参数名：随机数 | Signature parameter: nonce
+pub const SIGN_NONCE_PARAM: &str = "nonce";
+
+/// 签名参数名：摘要 | Signature parameter: digest
+pub const SIGN_PARAM: &str = "sign";
+
+/// 参数签名管理器 | Parameter signing manager
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let sign = SaSignManager::new("shared-secret", storage.clone()).timestamp_window(300);
+///
+/// // 调用方 | Caller
+/// let params = sign.sign(BTreeMap::from([("amount".to_string(), "100".to_string())]));
+///
+/// // 被调方 | Callee
+/// sign.verify(&params).await?;
+/// ```
+#[derive(Clone)]
+pub struct SaSignManager {
+    secret: Vec<u8>,
+    timestamp_window: i64,
+    nonces: NonceManager,
+}
+
+impl SaSignManager {
+    /// 创建管理器（时间窗口 15 分钟）| Create a manager (15-minute timestamp window)
+    ///
+    /// # 参数 | Parameters
+    /// * `secret` - 双方共享的签名密钥 | Secret shared by caller and callee
+    /// * `storage` - 记录已使用 nonce 的存储 | Storage that records used nonces
+    pub fn new(secret: impl AsRef<[u8]>, storage: Arc<dyn SaStorage>) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+            timestamp_window: 900,
+            nonces: NonceManager::new(storage, 900).window(900),
+        }
+    }
+
+    /// 允许的时间戳偏差（秒）| Allowed timestamp skew in seconds
+    pub fn timestamp_window(mut self, seconds: i64) -> Self {
+        self.timestamp_window = seconds;
+        self.nonces = self.nonces.window(seconds);
+        self
+    }
+
+    /// 为参数签名，返回追加了 timestamp、nonce、sign 的参数
+    /// Sign parameters, returns them with timestamp, nonce and sign added
+    pub fn sign(&self, mut params: BTreeMap<String, String>) -> BTreeMap<String, String> {
+        params.insert(SIGN_TIMESTAMP_PARAM.to_string(), Utc::now().timestamp_millis().to_string());
+        params.insert(SIGN_NONCE_PARAM.to_string(), uuid::Uuid::new_v4().simple().to_string());
+        let sign = self.digest(&params);
+        params.insert(SIGN_PARAM.to_string(), sign);
+        params
+    }
+
+    /// 计算参数摘要（不含 sign 参数，小写十六进制）| Compute the parameter digest (sign excluded, lowercase hex)
+    pub fn digest(&self, params: &BTreeMap<String, String>) -> String {
+        hex::encode(self.mac(params).finalize().into_bytes())
+    }
+
+    /// 校验签名参数，通过后消费 nonce | Verify signed parameters, consuming the nonce on success
+    ///
+    /// # 参数 | Parameters
+    /// * `params` - 请求参数（含 timestamp、nonce、sign）| Request parameters (with timestamp, nonce and sign)
+    pub async fn verify(&self, params: &BTreeMap<String, String>) -> SaTokenResult<()> {
+        let (Some(sign), Some(timestamp), Some(nonce)) = (
+            params.get(SIGN_PARAM),
+            params.get(SIGN_TIMESTAMP_PARAM),
+            params.get(SIGN_NONCE_PARAM),
+        ) else {
+            return Err(SaTokenError::SignatureMissing);
+        };
+
+        let timestamp: i64 = timestamp.parse().map_err(|_| SaTokenError::SignTimestampExpired)?;
+        if (Utc::now().timestamp_millis() - timestamp).abs() > self.timestamp_window * 1000 {
+            return Err(SaTokenError::SignTimestampExpired);
+        }
+
+        let expected = hex::decode(sign).map_err(|_| SaTokenError::InvalidSignature)?;
+        self.mac(params).verify_slice(&expected).map_err(|_| SaTokenError::InvalidSignature)?;
+
+        // 摘要通过后才消费 nonce，篡改的请求不会占用合法调用方的 nonce
+        // Consume the nonce only after the digest passes, so tampered requests cannot burn it
+        self.nonces.validate_and_consume(&format!("sign:{}", nonce), "sign").await
+    }
+
+    /// 把查询字符串解析为参数 | Parse a query string into parameters
+    pub fn params_from_query(query: &str) -> BTreeMap<String, String> {
+        query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                let decode = |s: &str| urlencoding::decode(&s.replace('+', " "))
+                    .map(|s| s.into_owned())
+                    .unwrap_or_else(|_| s.to_string());
+                (decode(key), decode(value))
+            })
+            .collect()
+    }
+
+    fn mac(&self, params: &BTreeMap<String, String>) -> HmacSha256 {
+        let canonical = params.iter()
+            .filter(|(key, _)| key.as_str() != SIGN_PARAM)
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&");
+        let mut mac = HmacSha256::new_from_slice(&self.secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(canonical.as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[test]
+    fn test_streaming_verify() {
+        let verifier = BodySignVerifier::new("secret");
+        let signature = verifier.sign(b"hello world");
+
+        let mut state = verifier.start();
+        state.update(b"hello ").unwrap();
+        state.update(b"world").unwrap();
+        assert_eq!(state.finish(&format!("sha256={}", signature)).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_reject_tampered_body() {
+        let verifier = BodySignVerifier::new("secret");
+        let signature = verifier.sign(b"amount=100");
+
+        assert!(matches!(
+            verifier.verify(b"amount=999", &signature),
+            Err(SaTokenError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verifier.verify(b"amount=100", "not-hex"),
+            Err(SaTokenError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_reject_oversized_body() {
+        let verifier = BodySignVerifier::new("secret").max_body_size(8);
+
+        let mut state = verifier.start();
+        state.update(b"1234").unwrap();
+        assert!(matches!(state.update(b"56789"), Err(SaTokenError::BodyTooLarge(8))));
+    }
+
+    #[tokio::test]
+    async fn test_sign_params() {
+        let manager = SaSignManager::new("secret", Arc::new(MemoryStorage::new()));
+        let params = manager.sign(BTreeMap::from([("amount".to_string(), "100".to_string())]));
+        assert!(params.contains_key(SIGN_NONCE_PARAM));
+
+        // 重放被拒绝 | A replay is rejected
+        assert!(manager.verify(&params).await.is_ok());
+        assert!(matches!(manager.verify(&params).await, Err(SaTokenError::NonceAlreadyUsed)));
+
+        let mut tampered = manager.sign(BTreeMap::from([("amount".to_string(), "100".to_string())]));
+        tampered.insert("amount".to_string(), "999".to_string());
+        assert!(matches!(manager.verify(&tampered).await, Err(SaTokenError::InvalidSignature)));
+
+        let mut stale = BTreeMap::from([
+            (SIGN_TIMESTAMP_PARAM.to_string(), (Utc::now().timestamp_millis() - 3_600_000).to_string()),
+            (SIGN_NONCE_PARAM.to_string(), "n1".to_string()),
+        ]);
+        stale.insert(SIGN_PARAM.to_string(), manager.digest(&stale));
+        assert!(matches!(manager.verify(&stale).await, Err(SaTokenError::SignTimestampExpired)));
+
+        assert!(matches!(manager.verify(&BTreeMap::new()).await, Err(SaTokenError::SignatureMissing)));
+
+        let query = SaSignManager::params_from_query("name=a%20b&flag&x=1+2");
+        assert_eq!(query["name"], "a b");
+        assert_eq!(query["flag"], "");
+        assert_eq!(query["x"], "1 2");
+    }
+}
@@ -0,0 +1,729 @@
+// Author: 金书记
+//
+//! 第三方登录 | Third-Party Social Login
+//!
+//! `SocialLoginManager` 管理多个第三方平台（GitHub、Google、微信、Gitee 或自定义 `SocialProvider`）：
+//! 生成授权地址、用授权码换取用户资料并统一字段，再把第三方身份绑定到本地 login_id。
+//! `SocialLoginManager` drives several providers (GitHub, Google, WeChat, Gitee or a custom
+//! `SocialProvider`): it builds authorize URLs, exchanges codes for a normalized profile, and binds
+//! third-party identities to local login ids.
+//!
+//! ```text
+//! authorize_url("github")            ──▶ 302 github.com/login/oauth/authorize?...&state=...
+//! callback("github", code, state)    ──▶ 校验 state，换取 SocialProfile | check state, fetch SocialProfile
+//!   ├─> login(&profile)   已绑定 | bound   → Some(token)
+//!   └─> login(&profile)   未绑定 | unbound → None → 注册或登录后 bind(login_id, &profile)
+//!                                                   register or log in, then bind(login_id, &profile)
+//! ```
+//!
+//! 平台接口通过 `SocialHttpClient` 调用；启用 `social-http` 特性后默认使用 `HttpSocialClient`。
+//! Provider APIs are called through `SocialHttpClient`; with the `social-http` feature,
+//! `HttpSocialClient` is used by default.
+//!
+//! ```text
+//! sa:social:state:{state}                  授权 state，值为平台名 | Authorize state, holding the provider name
+//! sa:social:bind:{provider}:{open_id}      绑定关系 | Binding
+//! sa:social:user:{login_id}                用户的 {provider}:{open_id} 集合 | Set of the user's {provider}:{open_id}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::id::IdKind;
+use crate::manager::SaTokenManager;
+use crate::token::TokenValue;
+
+/// 统一后的第三方用户资料 | Third-party profile, normalized across providers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SocialProfile {
+    /// 平台名称 | Provider name
+    pub provider: String,
+    /// 用户在该平台的唯一标识 | User's unique id on the provider
+    pub open_id: String,
+    /// 跨应用的统一标识（微信 unionid）| Cross-app id (WeChat unionid)
+    pub union_id: Option<String>,
+    /// 昵称 | Nickname
+    pub nickname: Option<String>,
+    /// 头像地址 | Avatar URL
+    pub avatar: Option<String>,
+    /// 已验证的邮箱 | Verified email
+    pub email: Option<String>,
+    /// 平台返回的原始资料 | Raw profile returned by the provider
+    pub raw: Value,
+}
+
+/// 第三方身份与本地账号的绑定 | Binding of a third-party identity to a local account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SocialBinding {
+    /// 本地登录 ID | Local login ID
+    pub login_id: String,
+    /// 平台名称 | Provider name
+    pub provider: String,
+    /// 用户在该平台的唯一标识 | User's unique id on the provider
+    pub open_id: String,
+    /// 绑定时的昵称 | Nickname at binding time
+    pub nickname: Option<String>,
+    /// 绑定时的头像 | Avatar at binding time
+    pub avatar: Option<String>,
+    /// 绑定时间 | Binding time
+    pub bind_time: DateTime<Utc>,
+}
+
+/// 调用第三方平台接口的 HTTP 客户端 | HTTP client calling provider APIs
+///
+/// 启用 `social-http` feature 后可使用 `HttpSocialClient`；也可自行实现以使用其他传输方式或在测试中模拟。
+/// With the `social-http` feature, `HttpSocialClient` talks HTTP; implement the trait yourself for
+/// other transports or to mock providers in tests.
+#[async_trait]
+pub trait SocialHttpClient: Send + Sync {
+    /// 以表单 POST，返回 JSON | POST a form and return the JSON body
+    async fn post_form(&self, url: &str, params: &[(&str, &str)]) -> SaTokenResult<Value>;
+
+    /// GET 并返回 JSON，可带 Bearer token | GET and return the JSON body, optionally with a bearer token
+    async fn get_json(&self, url: &str, bearer: Option<&str>) -> SaTokenResult<Value>;
+}
+
+/// 基于 reqwest 的第三方平台客户端 | reqwest-based provider client
+#[cfg(feature = "social-http")]
+#[derive(Clone, Default)]
+pub struct HttpSocialClient {
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "social-http")]
+impl HttpSocialClient {
+    /// 创建客户端 | Create a client
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用自定义的 reqwest 客户端（超时、代理等）| Use a custom reqwest client (timeouts, proxies, ...)
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    async fn read(request: reqwest::RequestBuilder) -> SaTokenResult<Value> {
+        // GitHub API 要求 User-Agent | The GitHub API requires a User-Agent
+        let response = request
+            .header(reqwest::header::ACCEPT, "application/json")
+            .header(reqwest::header::USER_AGENT, "sa-token-rust")
+            .send().await
+            .map_err(|e| SaTokenError::SocialLoginFailed(format!("request failed: {}", e)))?;
+        let status = response.status();
+        let body = response.text().await
+            .map_err(|e| SaTokenError::SocialLoginFailed(format!("request failed: {}", e)))?;
+        match serde_json::from_str(&body) {
+            Ok(value) => Ok(value),
+            Err(_) if !status.is_success() => Err(SaTokenError::SocialLoginFailed(format!("provider returned {}", status))),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(feature = "social-http")]
+#[async_trait]
+impl SocialHttpClient for HttpSocialClient {
+    async fn post_form(&self, url: &str, params: &[(&str, &str)]) -> SaTokenResult<Value> {
+        Self::read(self.http.post(url).form(params)).await
+    }
+
+    async fn get_json(&self, url: &str, bearer: Option<&str>) -> SaTokenResult<Value> {
+        let mut request = self.http.get(url);
+        if let Some(token) = bearer {
+            request = request.bearer_auth(token);
+        }
+        Self::read(request).await
+    }
+}
+
+/// 第三方登录平台 | Social login provider
+#[async_trait]
+pub trait SocialProvider: Send + Sync {
+    /// 平台名称，如 `github` | Provider name such as `github`
+    fn name(&self) -> &str;
+
+    /// 生成授权地址 | Build the authorize URL
+    fn authorize_url(&self, state: &str) -> String;
+
+    /// 用授权码换取统一后的用户资料 | Exchange an authorization code for the normalized profile
+    async fn exchange_code(&self, code: &str) -> SaTokenResult<SocialProfile>;
+}
+
+/// 在第三方平台注册的应用 | App registered with a provider
+#[derive(Clone)]
+pub struct SocialApp {
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    scopes: Option<Vec<String>>,
+    http: Option<Arc<dyn SocialHttpClient>>,
+}
+
+impl SocialApp {
+    /// 创建应用配置 | Create the app configuration
+    ///
+    /// # 参数 | Parameters
+    /// * `client_id` - 平台分配的应用 ID（微信为 appid）| App id issued by the provider (appid on WeChat)
+    /// * `client_secret` - 应用密钥 | App secret
+    /// * `redirect_uri` - 授权回调地址 | Authorization callback URL
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<String>, redirect_uri: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: redirect_uri.into(),
+            scopes: None,
+            http: None,
+        }
+    }
+
+    /// 覆盖平台默认的授权范围 | Override the provider's default scopes
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = Some(scopes);
+        self
+    }
+
+    /// 设置 HTTP 客户端 | Set the HTTP client
+    pub fn with_http_client(mut self, http: Arc<dyn SocialHttpClient>) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    fn scope(&self, default: &str, separator: &str) -> String {
+        self.scopes.as_ref().map_or_else(|| default.to_string(), |s| s.join(separator))
+    }
+
+    fn http(&self) -> SaTokenResult<Arc<dyn SocialHttpClient>> {
+        match &self.http {
+            Some(http) => Ok(http.clone()),
+            #[cfg(feature = "social-http")]
+            None => Ok(Arc::new(HttpSocialClient::new())),
+            #[cfg(not(feature = "social-http"))]
+            None => Err(SaTokenError::ConfigError(
+                "social login requires the social-http feature or a SocialHttpClient".to_string(),
+            )),
+        }
+    }
+}
+
+/// GitHub 登录 | GitHub login
+#[derive(Clone)]
+pub struct GitHubProvider {
+    app: SocialApp,
+}
+
+impl GitHubProvider {
+    /// 创建 GitHub 平台，默认授权范围 `read:user user:email` | Create the GitHub provider, `read:user user:email` by default
+    pub fn new(app: SocialApp) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait]
+impl SocialProvider for GitHubProvider {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn authorize_url(&self, state: &str) -> String {
+        format!("https://github.com/login/oauth/authorize?{}", query(&[
+            ("client_id", &self.app.client_id),
+            ("redirect_uri", &self.app.redirect_uri),
+            ("scope", &self.app.scope("read:user user:email", " ")),
+            ("state", state),
+        ]))
+    }
+
+    async fn exchange_code(&self, code: &str) -> SaTokenResult<SocialProfile> {
+        let http = self.app.http()?;
+        let token = http.post_form("https://github.com/login/oauth/access_token", &[
+            ("client_id", &self.app.client_id),
+            ("client_secret", &self.app.client_secret),
+            ("code", code),
+            ("redirect_uri", &self.app.redirect_uri),
+        ]).await?;
+        let access_token = required(&token, "access_token")?;
+        let user = http.get_json("https://api.github.com/user", Some(&access_token)).await?;
+        Ok(SocialProfile {
+            provider: self.name().to_string(),
+            open_id: required(&user, "id")?,
+            union_id: None,
+            nickname: field(&user, "name").or_else(|| field(&user, "login")),
+            avatar: field(&user, "avatar_url"),
+            email: field(&user, "email"),
+            raw: user,
+        })
+    }
+}
+
+/// Google 登录 | Google login
+#[derive(Clone)]
+pub struct GoogleProvider {
+    app: SocialApp,
+}
+
+impl GoogleProvider {
+    /// 创建 Google 平台，默认授权范围 `openid email profile` | Create the Google provider, `openid email profile` by default
+    pub fn new(app: SocialApp) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait]
+impl SocialProvider for GoogleProvider {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    fn authorize_url(&self, state: &str) -> String {
+        format!("https://accounts.google.com/o/oauth2/v2/auth?{}", query(&[
+            ("client_id", &self.app.client_id),
+            ("redirect_uri", &self.app.redirect_uri),
+            ("response_type", "code"),
+            ("scope", &self.app.scope("openid email profile", " ")),
+            ("state", state),
+        ]))
+    }
+
+    async fn exchange_code(&self, code: &str) -> SaTokenResult<SocialProfile> {
+        let http = self.app.http()?;
+        let token = http.post_form("https://oauth2.googleapis.com/token", &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", &self.app.client_id),
+            ("client_secret", &self.app.client_secret),
+            ("redirect_uri", &self.app.redirect_uri),
+        ]).await?;
+        let access_token = required(&token, "access_token")?;
+        let user = http.get_json("https://openidconnect.googleapis.com/v1/userinfo", Some(&access_token)).await?;
+        // 只采用已验证的邮箱 | Only verified emails are taken
+        let verified = user.get("email_verified").and_then(Value::as_bool).unwrap_or(false);
+        Ok(SocialProfile {
+            provider: self.name().to_string(),
+            open_id: required(&user, "sub")?,
+            union_id: None,
+            nickname: field(&user, "name"),
+            avatar: field(&user, "picture"),
+            email: field(&user, "email").filter(|_| verified),
+            raw: user,
+        })
+    }
+}
+
+/// 微信网站应用扫码登录 | WeChat website QR login
+#[derive(Clone)]
+pub struct WeChatProvider {
+    app: SocialApp,
+}
+
+impl WeChatProvider {
+    /// 创建微信平台，默认授权范围 `snsapi_login` | Create the WeChat provider, `snsapi_login` by default
+    pub fn new(app: SocialApp) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait]
+impl SocialProvider for WeChatProvider {
+    fn name(&self) -> &str {
+        "wechat"
+    }
+
+    fn authorize_url(&self, state: &str) -> String {
+        format!("https://open.weixin.qq.com/connect/qrconnect?{}#wechat_redirect", query(&[
+            ("appid", &self.app.client_id),
+            ("redirect_uri", &self.app.redirect_uri),
+            ("response_type", "code"),
+            ("scope", &self.app.scope("snsapi_login", ",")),
+            ("state", state),
+        ]))
+    }
+
+    async fn exchange_code(&self, code: &str) -> SaTokenResult<SocialProfile> {
+        let http = self.app.http()?;
+        let token = http.get_json(&format!("https://api.weixin.qq.com/sns/oauth2/access_token?{}", query(&[
+            ("appid", &self.app.client_id),
+            ("secret", &self.app.client_secret),
+            ("code", code),
+            ("grant_type", "authorization_code"),
+        ])), None).await?;
+        let (access_token, open_id) = (required(&token, "access_token")?, required(&token, "openid")?);
+        let user = http.get_json(&format!("https://api.weixin.qq.com/sns/userinfo?{}", query(&[
+            ("access_token", &access_token),
+            ("openid", &open_id),
+        ])), None).await?;
+        if user.get("errcode").is_some() {
+            return Err(provider_error(&user));
+        }
+        Ok(SocialProfile {
+            provider: self.name().to_string(),
+            union_id: field(&user, "unionid").or_else(|| field(&token, "unionid")),
+            open_id,
+            nickname: field(&user, "nickname"),
+            avatar: field(&user, "headimgurl"),
+            email: None,
+            raw: user,
+        })
+    }
+}
+
+/// Gitee 登录 | Gitee login
+#[derive(Clone)]
+pub struct GiteeProvider {
+    app: SocialApp,
+}
+
+impl GiteeProvider {
+    /// 创建 Gitee 平台，默认授权范围 `user_info` | Create the Gitee provider, `user_info` by default
+    pub fn new(app: SocialApp) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait]
+impl SocialProvider for GiteeProvider {
+    fn name(&self) -> &str {
+        "gitee"
+    }
+
+    fn authorize_url(&self, state: &str) -> String {
+        format!("https://gitee.com/oauth/authorize?{}", query(&[
+            ("client_id", &self.app.client_id),
+            ("redirect_uri", &self.app.redirect_uri),
+            ("response_type", "code"),
+            ("scope", &self.app.scope("user_info", " ")),
+            ("state", state),
+        ]))
+    }
+
+    async fn exchange_code(&self, code: &str) -> SaTokenResult<SocialProfile> {
+        let http = self.app.http()?;
+        let token = http.post_form("https://gitee.com/oauth/token", &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", &self.app.client_id),
+            ("client_secret", &self.app.client_secret),
+            ("redirect_uri", &self.app.redirect_uri),
+        ]).await?;
+        let access_token = required(&token, "access_token")?;
+        let user = http.get_json(
+            &format!("https://gitee.com/api/v5/user?{}", query(&[("access_token", &access_token)])),
+            None,
+        ).await?;
+        Ok(SocialProfile {
+            provider: self.name().to_string(),
+            open_id: required(&user, "id")?,
+            union_id: None,
+            nickname: field(&user, "name").or_else(|| field(&user, "login")),
+            avatar: field(&user, "avatar_url"),
+            email: field(&user, "email"),
+            raw: user,
+        })
+    }
+}
+
+fn query(params: &[(&str, &str)]) -> String {
+    params.iter()
+        .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 读取字符串或数字字段，空值视为缺失 | Read a string or number field; empty values count as missing
+fn field(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn required(value: &Value, key: &str) -> SaTokenResult<String> {
+    field(value, key).ok_or_else(|| provider_error(value))
+}
+
+/// 平台返回的错误信息 | Error message returned by the provider
+fn provider_error(value: &Value) -> SaTokenError {
+    let message = ["error_description", "errmsg", "message", "error"].iter()
+        .find_map(|key| field(value, key))
+        .unwrap_or_else(|| "unexpected provider response".to_string());
+    SaTokenError::SocialLoginFailed(message)
+}
+
+/// 第三方登录管理器 | Social login manager
+#[derive(Clone)]
+pub struct SocialLoginManager {
+    manager: Arc<SaTokenManager>,
+    providers: HashMap<String, Arc<dyn SocialProvider>>,
+    state_timeout: i64,
+}
+
+impl SocialLoginManager {
+    /// 创建管理器（state 有效期 600 秒）| Create a manager (states live 600 seconds)
+    pub fn new(manager: Arc<SaTokenManager>) -> Self {
+        Self {
+            manager,
+            providers: HashMap::new(),
+            state_timeout: 600,
+        }
+    }
+
+    /// 注册平台，以 `name()` 区分 | Register a provider, keyed by `name()`
+    pub fn with_provider(mut self, provider: Arc<dyn SocialProvider>) -> Self {
+        self.providers.insert(provider.name().to_string(), provider);
+        self
+    }
+
+    /// 设置授权 state 的有效期（秒）| Set the lifetime of authorize states in seconds
+    pub fn with_state_timeout(mut self, seconds: i64) -> Self {
+        self.state_timeout = seconds.max(1);
+        self
+    }
+
+    /// 获取已注册的平台 | Get a registered provider
+    ///
+    /// # 错误 | Errors
+    /// * `SocialProviderNotFound` - 平台未注册 | Provider not registered
+    pub fn provider(&self, name: &str) -> SaTokenResult<&Arc<dyn SocialProvider>> {
+        self.providers.get(name).ok_or_else(|| SaTokenError::SocialProviderNotFound(name.to_string()))
+    }
+
+    /// 生成授权地址，附带一次性 state 防止 CSRF | Build the authorize URL with a single-use state against CSRF
+    ///
+    /// # 参数 | Parameters
+    /// * `provider` - 平台名称 | Provider name
+    pub async fn authorize_url(&self, provider: &str) -> SaTokenResult<String> {
+        let provider = self.provider(provider)?;
+        let state = self.manager.id_generator().generate(IdKind::SocialState);
+        self.manager.storage.set(&Self::state_key(&state), provider.name(), Some(Duration::from_secs(self.state_timeout as u64))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(provider.authorize_url(&state))
+    }
+
+    /// 处理授权回调：校验并消费 state，用授权码换取用户资料
+    /// Handle the authorize callback: check and consume the state, then exchange the code for the profile
+    ///
+    /// # 参数 | Parameters
+    /// * `provider` - 平台名称 | Provider name
+    /// * `code` - 授权码 | Authorization code
+    /// * `state` - 回调携带的 state | State carried by the callback
+    ///
+    /// # 错误 | Errors
+    /// * `SocialProviderNotFound` - 平台未注册 | Provider not registered
+    /// * `SocialStateInvalid` - state 不存在、已过期或属于其他平台 | State missing, expired or for another provider
+    /// * `SocialLoginFailed` - 平台拒绝授权码或返回异常 | Provider rejected the code or answered unexpectedly
+    pub async fn callback(&self, provider: &str, code: &str, state: &str) -> SaTokenResult<SocialProfile> {
+        let provider = self.provider(provider)?;
+        let key = Self::state_key(state);
+        let expected = self.manager.storage.get(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if expected.as_deref() != Some(provider.name()) {
+            return Err(SaTokenError::SocialStateInvalid);
+        }
+        self.manager.storage.delete(&key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        provider.exchange_code(code).await
+    }
+
+    /// 以已绑定的本地账号登录，未绑定时返回 `None` | Log in as the bound local account; `None` when unbound
+    pub async fn login(&self, profile: &SocialProfile) -> SaTokenResult<Option<TokenValue>> {
+        match self.find_login_id(&profile.provider, &profile.open_id).await? {
+            Some(login_id) => Ok(Some(self.manager.login(login_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 查询第三方身份绑定的本地 login_id | Look up the local login_id bound to a third-party identity
+    pub async fn find_login_id(&self, provider: &str, open_id: &str) -> SaTokenResult<Option<String>> {
+        Ok(self.binding(provider, open_id).await?.map(|b| b.login_id))
+    }
+
+    /// 绑定第三方身份，同一平台只保留最新绑定的身份 | Bind a third-party identity; one identity per provider is kept
+    ///
+    /// # 错误 | Errors
+    /// * `SocialIdentityBound` - 该身份已绑定其他账号 | Identity already bound to another account
+    pub async fn bind(&self, login_id: &str, profile: &SocialProfile) -> SaTokenResult<SocialBinding> {
+        let binding = SocialBinding {
+            login_id: login_id.to_string(),
+            provider: profile.provider.clone(),
+            open_id: profile.open_id.clone(),
+            nickname: profile.nickname.clone(),
+            avatar: profile.avatar.clone(),
+            bind_time: Utc::now(),
+        };
+        let key = Self::bind_key(&binding.provider, &binding.open_id);
+        let value = serde_json::to_string(&binding)?;
+
+        // set_nx 保证并发绑定同一身份时只有一个账号成功 | set_nx lets only one account win concurrent binds of an identity
+        let claimed = self.manager.storage.set_nx(&key, &value, None).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            match self.binding(&binding.provider, &binding.open_id).await? {
+                Some(existing) if existing.login_id != login_id => return Err(SaTokenError::SocialIdentityBound),
+                _ => self.manager.storage.set(&key, &value, None).await
+                    .map_err(|e| SaTokenError::StorageError(e.to_string()))?,
+            }
+        }
+
+        let member = format!("{}:{}", binding.provider, binding.open_id);
+        for previous in self.members(login_id).await? {
+            if previous != member && previous.split_once(':').is_some_and(|(p, _)| p == binding.provider) {
+                self.remove_member(login_id, &previous).await?;
+            }
+        }
+        self.manager.storage.sadd(&Self::user_key(login_id), &member).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(binding)
+    }
+
+    /// 解除账号在某平台的绑定，返回是否存在绑定 | Unbind the account from a provider; returns whether a binding existed
+    pub async fn unbind(&self, login_id: &str, provider: &str) -> SaTokenResult<bool> {
+        let mut removed = false;
+        for member in self.members(login_id).await? {
+            if member.split_once(':').is_some_and(|(p, _)| p == provider) {
+                self.remove_member(login_id, &member).await?;
+                removed = true;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// 列出账号的全部绑定 | List all bindings of an account
+    pub async fn bindings(&self, login_id: &str) -> SaTokenResult<Vec<SocialBinding>> {
+        let mut bindings = Vec::new();
+        for member in self.members(login_id).await? {
+            if let Some((provider, open_id)) = member.split_once(':')
+                && let Some(binding) = self.binding(provider, open_id).await?
+                && binding.login_id == login_id
+            {
+                bindings.push(binding);
+            }
+        }
+        bindings.sort_by(|a, b| a.provider.cmp(&b.provider));
+        Ok(bindings)
+    }
+
+    async fn binding(&self, provider: &str, open_id: &str) -> SaTokenResult<Option<SocialBinding>> {
+        let value = self.manager.storage.get(&Self::bind_key(provider, open_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+    }
+
+    async fn members(&self, login_id: &str) -> SaTokenResult<Vec<String>> {
+        self.manager.storage.smembers(&Self::user_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn remove_member(&self, login_id: &str, member: &str) -> SaTokenResult<()> {
+        if let Some((provider, open_id)) = member.split_once(':')
+            && self.find_login_id(provider, open_id).await?.as_deref() == Some(login_id)
+        {
+            self.manager.storage.delete(&Self::bind_key(provider, open_id)).await
+                .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        }
+        self.manager.storage.srem(&Self::user_key(login_id), member).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    fn state_key(state: &str) -> String {
+        format!("sa:social:state:{}", state)
+    }
+
+    fn bind_key(provider: &str, open_id: &str) -> String {
+        format!("sa:social:bind:{}:{}", provider, open_id)
+    }
+
+    fn user_key(login_id: &str) -> String {
+        format!("sa:social:user:{}", login_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    /// 按 URL 前缀返回预置 JSON 的模拟平台 | Mock provider answering canned JSON by URL prefix
+    struct MockHttp(Vec<(&'static str, Value)>);
+
+    impl MockHttp {
+        fn answer(&self, url: &str) -> SaTokenResult<Value> {
+            self.0.iter().find(|(prefix, _)| url.starts_with(prefix)).map(|(_, v)| v.clone())
+                .ok_or_else(|| SaTokenError::SocialLoginFailed(format!("unexpected request {}", url)))
+        }
+    }
+
+    #[async_trait]
+    impl SocialHttpClient for MockHttp {
+        async fn post_form(&self, url: &str, params: &[(&str, &str)]) -> SaTokenResult<Value> {
+            match params.iter().find(|(k, _)| *k == "code") {
+                Some((_, "bad")) => Ok(serde_json::json!({"error": "bad_verification_code", "error_description": "The code is incorrect"})),
+                _ => self.answer(url),
+            }
+        }
+
+        async fn get_json(&self, url: &str, _bearer: Option<&str>) -> SaTokenResult<Value> {
+            self.answer(url)
+        }
+    }
+
+    fn app(http: Vec<(&'static str, Value)>) -> SocialApp {
+        SocialApp::new("client", "secret", "https://app.com/oauth/callback").with_http_client(Arc::new(MockHttp(http)))
+    }
+
+    #[tokio::test]
+    async fn test_social_login() {
+        let github = GitHubProvider::new(app(vec![
+            ("https://github.com/login/oauth/access_token", serde_json::json!({"access_token": "gho_1"})),
+            ("https://api.github.com/user", serde_json::json!({"id": 583231, "login": "octocat", "name": null, "avatar_url": "https://a/1"})),
+        ]));
+        let wechat = WeChatProvider::new(app(vec![
+            ("https://api.weixin.qq.com/sns/oauth2/access_token", serde_json::json!({"access_token": "w1", "openid": "o_1"})),
+            ("https://api.weixin.qq.com/sns/userinfo", serde_json::json!({"openid": "o_1", "nickname": "小明", "unionid": "u_1"})),
+        ]));
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let social = SocialLoginManager::new(manager.clone())
+            .with_provider(Arc::new(github))
+            .with_provider(Arc::new(wechat));
+
+        let url = social.authorize_url("github").await.unwrap();
+        assert!(url.starts_with("https://github.com/login/oauth/authorize?client_id=client&redirect_uri=https%3A%2F%2Fapp.com"));
+        let state = url.split_once("&state=").unwrap().1;
+        assert!(matches!(social.callback("wechat", "code", state).await, Err(SaTokenError::SocialStateInvalid)));
+        let profile = social.callback("github", "code", state).await.unwrap();
+        assert_eq!((profile.open_id.as_str(), profile.nickname.as_deref()), ("583231", Some("octocat")));
+        assert!(matches!(social.callback("github", "code", state).await, Err(SaTokenError::SocialStateInvalid)));
+
+        let url = social.authorize_url("github").await.unwrap();
+        let state = url.split_once("&state=").unwrap().1;
+        assert!(matches!(social.callback("github", "bad", state).await, Err(SaTokenError::SocialLoginFailed(m)) if m == "The code is incorrect"));
+        assert!(matches!(social.authorize_url("gitlab").await, Err(SaTokenError::SocialProviderNotFound(_))));
+
+        // 绑定后可登录，同一身份不能绑定到第二个账号 | Bound identities log in; an identity cannot be bound to a second account
+        assert!(social.login(&profile).await.unwrap().is_none());
+        social.bind("10001", &profile).await.unwrap();
+        let token = social.login(&profile).await.unwrap().unwrap();
+        assert_eq!(manager.get_token_info(&token).await.unwrap().login_id, "10001");
+        assert!(matches!(social.bind("10002", &profile).await, Err(SaTokenError::SocialIdentityBound)));
+
+        let url = social.authorize_url("wechat").await.unwrap();
+        assert!(url.ends_with("#wechat_redirect"));
+        let state = url.split_once("&state=").unwrap().1.trim_end_matches("#wechat_redirect");
+        let wechat_profile = social.callback("wechat", "code", state).await.unwrap();
+        assert_eq!(wechat_profile.union_id.as_deref(), Some("u_1"));
+        social.bind("10001", &wechat_profile).await.unwrap();
+        let providers: Vec<String> = social.bindings("10001").await.unwrap().into_iter().map(|b| b.provider).collect();
+        assert_eq!(providers, vec!["github", "wechat"]);
+
+        assert!(social.unbind("10001", "github").await.unwrap());
+        assert!(!social.unbind("10001", "github").await.unwrap());
+        assert_eq!(social.find_login_id("github", "583231").await.unwrap(), None);
+        social.bind("10002", &profile).await.unwrap();
+    }
+}
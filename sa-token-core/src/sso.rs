@@ -94,6 +94,17 @@
 //!   └─> for each client_url
 //!       └─> client.handle_logout(login_id)
 //!           └─> 清除本地会话 | Clear local session
+//!
+//! 跨域部署时由 SsoManager 推送登出回调：
+//! Across domains, SsoManager pushes logout callbacks:
+//!
+//! 校验票据时客户端登记回调地址 | Clients register a callback URL while checking a ticket
+//!   └─> sa:sso:logout-url:{login_id}
+//! SsoManager::logout(login_id)
+//!   ├─> server.logout(login_id)
+//!   └─> 向每个回调地址 POST 签名的 login_id | POST the signed login_id to every callback URL
+//!       └─> client.handle_logout_request(params) 验签后清除本地会话
+//!           verifies the signature, then clears the local session
 //! ```
 //!
 //! ### 5. 票据生命周期 | Ticket Lifecycle
@@ -133,14 +144,50 @@
 //!
 //! 5. UUID 票据 ID | UUID ticket ID
 //!    └─> 使用 UUID 防止票据 ID 被猜测
+//!
+//! 6. 回跳地址白名单 | Redirect URL allow list
+//!    └─> build_redirect_url() 只签发到 allow_urls 中地址的票据
+//!        build_redirect_url() only issues tickets to addresses in allow_urls
+//!
+//! 7. 签名校验请求 | Signed check requests
+//!    └─> 模式三的票据校验请求使用 SaSignManager 签名，防篡改与重放
+//!        Mode 3 check requests are signed with SaSignManager against tampering and replay
+//! ```
+//!
+//! ### 7. 跨域模式 | Cross-domain Modes
+//!
+//! 票据保存在 `TicketStore` 中，默认为 `SaTokenManager` 的存储（`sa:sso:ticket:{ticket_id}`），随票据超时自动过期。
+//! Tickets live in a `TicketStore`, by default the `SaTokenManager` storage (`sa:sso:ticket:{ticket_id}`),
+//! and expire with the ticket timeout.
+//!
+//! ```text
+//! 服务端 | Server: build_redirect_url(login_id, redirect)
+//!   ├─> 校验 redirect 是否在白名单 | check redirect against the allow list
+//!   └─> 302 {redirect}?ticket=ST-...
+//!
+//! 模式二：客户端与服务端共享存储（如同一个 Redis）
+//! Mode 2: the client shares the server's storage (e.g. the same Redis)
+//!   └─> client.check_ticket(ticket) 直接读取票据存储 | reads the ticket store directly
+//!
+//! 模式三：客户端与服务端存储隔离
+//! Mode 3: the client has its own storage
+//!   └─> client.check_ticket_url(ticket) 生成签名的校验地址 | builds a signed check URL
+//!       └─> 服务端 | Server: check_ticket_request(params) 验签后消费票据 | verifies the signature, then consumes the ticket
 //! ```
 
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc, Duration as ChronoDuration};
 use serde::{Serialize, Deserialize};
 use tokio::sync::RwLock;
-use crate::{SaTokenError, SaTokenResult, SaTokenManager};
+use sa_token_adapter::storage::SaStorage;
+use crate::{SaTokenError, SaTokenResult, SaTokenManager, TokenValue};
+use crate::sign::SaSignManager;
+use crate::page::{DefaultPageRenderer, LoginPage, PageRenderer, SaPage};
+use crate::id::{IdGenerator, IdKind, RandomIdGenerator};
+use crate::ticket_store::{StorageTicketStore, TicketStore};
 
 /// SSO 票据结构 | SSO Ticket Structure
 ///
@@ -170,9 +217,20 @@ impl SsoTicket {
     /// * `service` - 目标服务 URL | Target service URL
     /// * `timeout_seconds` - 票据有效期（秒）| Ticket validity period (seconds)
     pub fn new(login_id: String, service: String, timeout_seconds: i64) -> Self {
+        Self::with_id(RandomIdGenerator::default().generate(IdKind::SsoTicket), login_id, service, timeout_seconds)
+    }
+
+    /// 使用指定的票据 ID 创建 | Create with a given ticket ID
+    ///
+    /// # 参数 | Parameters
+    /// * `ticket_id` - 票据 ID，通常来自 `IdGenerator` | Ticket ID, usually from an `IdGenerator`
+    /// * `login_id` - 用户登录 ID | User login ID
+    /// * `service` - 目标服务 URL | Target service URL
+    /// * `timeout_seconds` - 票据有效期（秒）| Ticket validity period (seconds)
+    pub fn with_id(ticket_id: String, login_id: String, service: String, timeout_seconds: i64) -> Self {
         let now = Utc::now();
         Self {
-            ticket_id: uuid::Uuid::new_v4().to_string(),
+            ticket_id,
             service,
             login_id,
             create_time: now,
@@ -190,6 +248,60 @@ impl SsoTicket {
     pub fn is_valid(&self) -> bool {
         !self.used && !self.is_expired()
     }
+
+    /// 距离过期的剩余时间，至少 1 秒 | Time left before expiry, at least one second
+    pub(crate) fn remaining(&self) -> Duration {
+        Duration::from_secs((self.expire_time - Utc::now()).num_seconds().max(1) as u64)
+    }
+
+    /// 读取并消费票据，服务端与共享票据存储的客户端共用
+    /// Load and consume a ticket, shared by the server and clients on the same ticket store
+    async fn consume(store: &dyn TicketStore, ticket_id: &str, service: &str) -> SaTokenResult<String> {
+        let ticket = store.get(ticket_id).await?.ok_or(SaTokenError::InvalidTicket)?;
+
+        if ticket.is_expired() {
+            return Err(SaTokenError::TicketExpired);
+        }
+        if ticket.service != service {
+            return Err(SaTokenError::ServiceMismatch);
+        }
+
+        // 并发校验时只有一个成功 | Only one of several concurrent checks succeeds
+        if !store.consume(ticket_id).await? {
+            return Err(SaTokenError::TicketExpired);
+        }
+        Ok(ticket.login_id)
+    }
+}
+
+/// 用户已登记的登出回调地址集合 | Set of logout callback URLs registered for a user
+fn logout_urls_key(login_id: &str) -> String {
+    format!("sa:sso:logout-url:{}", login_id)
+}
+
+async fn register_logout_url(storage: &dyn SaStorage, login_id: &str, url: &str) -> SaTokenResult<()> {
+    storage.sadd(&logout_urls_key(login_id), url).await
+        .map_err(|e| SaTokenError::StorageError(e.to_string()))
+}
+
+/// 去掉查询串与片段，得到票据绑定的服务地址 | Strip the query and fragment, leaving the service URL a ticket is bound to
+pub(crate) fn service_of(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// 在回跳地址上追加 ticket 参数 | Append the ticket parameter to a redirect URL
+fn append_ticket(url: &str, ticket_id: &str) -> String {
+    let (base, fragment) = match url.split_once('#') {
+        Some((base, fragment)) => (base, Some(fragment)),
+        None => (url, None),
+    };
+    let separator = if base.contains('?') { '&' } else { '?' };
+    let mut redirect = format!("{}{}ticket={}", base, separator, urlencoding::encode(ticket_id));
+    if let Some(fragment) = fragment {
+        redirect.push('#');
+        redirect.push_str(fragment);
+    }
+    redirect
 }
 
 /// SSO 全局会话 | SSO Global Session
@@ -244,9 +356,12 @@ impl SsoSession {
 /// Central authentication service responsible for ticket generation, validation, and session management
 pub struct SsoServer {
     manager: Arc<SaTokenManager>,
-    tickets: Arc<RwLock<HashMap<String, SsoTicket>>>,
     sessions: Arc<RwLock<HashMap<String, SsoSession>>>,
     ticket_timeout: i64,
+    page_renderer: Arc<dyn PageRenderer>,
+    allow_urls: Vec<String>,
+    sign: Option<SaSignManager>,
+    ticket_store: Arc<dyn TicketStore>,
 }
 
 impl SsoServer {
@@ -256,10 +371,13 @@ impl SsoServer {
     /// * `manager` - SaTokenManager 实例 | SaTokenManager instance
     pub fn new(manager: Arc<SaTokenManager>) -> Self {
         Self {
-            manager,
-            tickets: Arc::new(RwLock::new(HashMap::new())),
             sessions: Arc::new(RwLock::new(HashMap::new())),
             ticket_timeout: 300, // 默认 5 分钟 | Default 5 minutes
+            page_renderer: Arc::new(DefaultPageRenderer::new()),
+            allow_urls: Vec::new(),
+            sign: None,
+            ticket_store: Arc::new(StorageTicketStore::new(manager.storage.clone())),
+            manager,
         }
     }
 
@@ -272,6 +390,168 @@ impl SsoServer {
         self
     }
 
+    /// 设置票据存储 | Set the ticket store
+    ///
+    /// 默认使用 `SaTokenManager` 的存储；模式二的客户端需使用同一个票据存储。
+    /// Defaults to the `SaTokenManager` storage; mode 2 clients must use the same ticket store.
+    ///
+    /// # 参数 | Parameters
+    /// * `store` - 票据存储 | Ticket store
+    pub fn with_ticket_store(mut self, store: Arc<dyn TicketStore>) -> Self {
+        self.ticket_store = store;
+        self
+    }
+
+    /// 设置页面渲染器 | Set the page renderer
+    ///
+    /// # 参数 | Parameters
+    /// * `renderer` - 登录页与错误页的渲染器 | Renderer for the login and error pages
+    pub fn with_page_renderer(mut self, renderer: Arc<dyn PageRenderer>) -> Self {
+        self.page_renderer = renderer;
+        self
+    }
+
+    /// 设置允许回跳的地址白名单 | Set the allow list of redirect URLs
+    ///
+    /// 支持精确地址、以 `*` 结尾的前缀和表示全部放行的 `*`；只比较地址中查询串之前的部分。
+    /// 默认为空，即 `build_redirect_url` 拒绝所有地址。
+    /// Accepts exact URLs, prefixes ending in `*`, and `*` to allow everything; only the part before
+    /// the query string is compared. Empty by default, so `build_redirect_url` rejects every URL.
+    ///
+    /// # 参数 | Parameters
+    /// * `urls` - 允许的地址或前缀，如 `https://app1.example.com/sso/*` | Allowed URLs or prefixes, e.g. `https://app1.example.com/sso/*`
+    pub fn with_allow_urls(mut self, urls: Vec<String>) -> Self {
+        self.allow_urls = urls;
+        self
+    }
+
+    /// 设置模式三票据校验请求的签名管理器 | Set the sign manager for mode 3 ticket check requests
+    ///
+    /// # 参数 | Parameters
+    /// * `sign` - 与客户端共享密钥的签名管理器 | Sign manager sharing its secret with the clients
+    pub fn with_sign(mut self, sign: SaSignManager) -> Self {
+        self.sign = Some(sign);
+        self
+    }
+
+    /// 获取页面渲染器 | Get the page renderer
+    pub fn page_renderer(&self) -> &Arc<dyn PageRenderer> {
+        &self.page_renderer
+    }
+
+    /// 回跳地址是否在白名单中 | Whether a redirect URL is in the allow list
+    pub fn is_allowed_url(&self, url: &str) -> bool {
+        if url.chars().any(|c| c.is_whitespace() || c.is_control() || c == '\\') {
+            return false;
+        }
+        let service = service_of(url);
+        self.allow_urls.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => service.starts_with(prefix),
+            None => service == pattern,
+        })
+    }
+
+    /// 校验回跳地址 | Check a redirect URL
+    ///
+    /// # 错误 | Errors
+    /// * `SsoRedirectNotAllowed` - 地址不在白名单中 | URL is not in the allow list
+    pub fn check_redirect_url(&self, url: &str) -> SaTokenResult<()> {
+        if self.is_allowed_url(url) {
+            Ok(())
+        } else {
+            Err(SaTokenError::SsoRedirectNotAllowed(url.to_string()))
+        }
+    }
+
+    /// 为已登录用户签发票据并生成回跳地址 | Issue a ticket for a logged-in user and build the redirect URL
+    ///
+    /// 票据绑定到回跳地址去掉查询串后的部分，客户端以此作为 `service_url` 校验票据。
+    /// The ticket is bound to the redirect URL without its query string; clients check it with that as their `service_url`.
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 用户登录 ID | User login ID
+    /// * `redirect` - 客户端回跳地址 | Client redirect URL
+    ///
+    /// # 返回 | Returns
+    /// 追加了 `ticket` 参数的回跳地址 | Redirect URL with the `ticket` parameter appended
+    ///
+    /// # 错误 | Errors
+    /// * `SsoRedirectNotAllowed` - 地址不在白名单中 | URL is not in the allow list
+    pub async fn build_redirect_url(&self, login_id: &str, redirect: &str) -> SaTokenResult<String> {
+        self.check_redirect_url(redirect)?;
+        let ticket = self.create_ticket(login_id.to_string(), service_of(redirect).to_string()).await?;
+        Ok(append_ticket(redirect, &ticket.ticket_id))
+    }
+
+    /// 处理模式三的票据校验请求 | Handle a mode 3 ticket check request
+    ///
+    /// 先校验签名（含时间戳与 nonce 防重放），再消费 `ticket` 参数对应的票据。
+    /// 携带 `logout_url` 时，该地址须在白名单中，校验成功后登记为登出回调地址。
+    /// Verifies the signature (with timestamp and nonce against replay) first, then consumes the ticket in the `ticket` parameter.
+    /// A `logout_url` must be in the allow list; it is registered as a logout callback once the ticket checks out.
+    ///
+    /// # 参数 | Parameters
+    /// * `params` - 请求参数：ticket、service、logout_url（可选）、timestamp、nonce、sign | Request parameters: ticket, service, logout_url (optional), timestamp, nonce, sign
+    ///
+    /// # 返回 | Returns
+    /// 用户登录 ID | User login ID
+    pub async fn check_ticket_request(&self, params: &BTreeMap<String, String>) -> SaTokenResult<String> {
+        let sign = self.sign.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("SSO sign manager is not set".to_string()))?;
+        sign.verify(params).await?;
+        let ticket = params.get("ticket").ok_or(SaTokenError::InvalidTicket)?;
+        let service = params.get("service").map(String::as_str).unwrap_or_default();
+        let logout_url = params.get("logout_url");
+        if let Some(url) = logout_url {
+            self.check_redirect_url(url)?;
+        }
+
+        let login_id = self.validate_ticket(ticket, service).await?;
+        if let Some(url) = logout_url {
+            self.register_logout_url(&login_id, url).await?;
+        }
+        Ok(login_id)
+    }
+
+    /// 登记用户的登出回调地址 | Register a logout callback URL for a user
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 用户登录 ID | User login ID
+    /// * `url` - 客户端接收登出通知的地址 | Client URL receiving logout notifications
+    pub async fn register_logout_url(&self, login_id: &str, url: &str) -> SaTokenResult<()> {
+        register_logout_url(self.manager.storage.as_ref(), login_id, url).await
+    }
+
+    /// 获取用户已登记的登出回调地址 | Get the logout callback URLs registered for a user
+    pub async fn logout_urls(&self, login_id: &str) -> SaTokenResult<Vec<String>> {
+        let mut urls = self.manager.storage.smembers(&logout_urls_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        urls.sort();
+        Ok(urls)
+    }
+
+    /// 渲染登录页 | Render the login page
+    ///
+    /// # 参数 | Parameters
+    /// * `action` - 登录表单提交地址 | URL the login form posts to
+    /// * `service` - 登录后回跳的服务地址 | Service URL to return to after login
+    /// * `error` - 上次登录失败的信息 | Message of the previous failed attempt
+    /// * `accept_language` - 用于语言协商的 `Accept-Language` 请求头 | `Accept-Language` header for locale negotiation
+    pub fn render_login_page(
+        &self,
+        action: &str,
+        service: Option<&str>,
+        error: Option<&str>,
+        accept_language: Option<&str>,
+    ) -> SaTokenResult<String> {
+        let page = SaPage::Login(LoginPage {
+            action: action.to_string(),
+            service: service.map(str::to_string),
+            error: error.map(str::to_string),
+        });
+        self.page_renderer.render_negotiated(&page, accept_language)
+    }
+
     /// 检查用户是否已登录 | Check if user is logged in
     ///
     /// 通过检查 SSO 会话是否存在来判断
@@ -303,11 +583,11 @@ impl SsoServer {
     /// 新创建的票据 | Newly created ticket
     pub async fn create_ticket(&self, login_id: String, service: String) -> SaTokenResult<SsoTicket> {
         // 生成票据 | Generate ticket
-        let ticket = SsoTicket::new(login_id.clone(), service.clone(), self.ticket_timeout);
+        let ticket_id = format!("ST-{}", self.manager.id_generator().generate(IdKind::SsoTicket));
+        let ticket = SsoTicket::with_id(ticket_id, login_id.clone(), service.clone(), self.ticket_timeout);
         
         // 存储票据 | Store ticket
-        let mut tickets = self.tickets.write().await;
-        tickets.insert(ticket.ticket_id.clone(), ticket.clone());
+        self.ticket_store.save(&ticket, ticket.remaining()).await?;
 
         // 更新会话，添加客户端 | Update session, add client
         let mut sessions = self.sessions.write().await;
@@ -335,27 +615,7 @@ impl SsoServer {
     /// * `TicketExpired` - 票据已过期或已使用 | Ticket expired or used
     /// * `ServiceMismatch` - 服务 URL 不匹配 | Service URL mismatch
     pub async fn validate_ticket(&self, ticket_id: &str, service: &str) -> SaTokenResult<String> {
-        let mut tickets = self.tickets.write().await;
-        
-        // 1. 检查票据是否存在 | Check if ticket exists
-        let ticket = tickets.get_mut(ticket_id)
-            .ok_or(SaTokenError::InvalidTicket)?;
-
-        // 2. 验证票据有效性（未过期、未使用）| Validate ticket (not expired, not used)
-        if !ticket.is_valid() {
-            return Err(SaTokenError::TicketExpired);
-        }
-
-        // 3. 验证服务 URL 匹配 | Verify service URL matches
-        if ticket.service != service {
-            return Err(SaTokenError::ServiceMismatch);
-        }
-
-        // 4. 标记票据为已使用（一次性使用）| Mark ticket as used (one-time use)
-        ticket.used = true;
-        let login_id = ticket.login_id.clone();
-
-        Ok(login_id)
+        SsoTicket::consume(self.ticket_store.as_ref(), ticket_id, service).await
     }
 
     /// 用户登录 | User login
@@ -453,11 +713,13 @@ impl SsoServer {
 
     /// 清理过期票据 | Cleanup expired tickets
     ///
-    /// 删除所有过期或已使用的票据
-    /// Removes all expired or used tickets
+    /// 默认的票据存储依赖存储 TTL 自动过期，无需手动清理；`InMemoryTicketStore` 在此移除过期票据
+    /// The default ticket store relies on storage TTLs, so nothing needs cleaning;
+    /// `InMemoryTicketStore` drops its expired tickets here
     pub async fn cleanup_expired_tickets(&self) {
-        let mut tickets = self.tickets.write().await;
-        tickets.retain(|_, ticket| ticket.is_valid());
+        if let Err(e) = self.ticket_store.cleanup_expired().await {
+            tracing::warn!("SSO ticket cleanup failed: {}", e);
+        }
     }
 
     /// 获取活跃客户端列表 | Get active clients list
@@ -475,6 +737,9 @@ impl SsoServer {
     }
 }
 
+/// 登出回调函数类型 | Logout callback function type
+pub type LogoutCallback = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
 /// SSO 客户端 | SSO Client
 ///
 /// 每个应用作为 SSO 客户端，处理本地会话和票据验证
@@ -487,7 +752,17 @@ pub struct SsoClient {
     /// 当前服务 URL | Current service URL
     service_url: String,
     /// 登出回调函数 | Logout callback function
-    logout_callback: Option<Arc<dyn Fn(&str) -> bool + Send + Sync>>,
+    logout_callback: Option<LogoutCallback>,
+    /// 模式三的服务端票据校验地址 | Server ticket check URL for mode 3
+    check_ticket_url: Option<String>,
+    /// 模式三的签名管理器 | Sign manager for mode 3
+    sign: Option<SaSignManager>,
+    /// 接收服务端登出通知的地址 | URL receiving logout notifications from the server
+    logout_callback_url: Option<String>,
+    /// 模式二的票据存储 | Ticket store for mode 2
+    ticket_store: Arc<dyn TicketStore>,
+    #[cfg(feature = "sso-http")]
+    http: reqwest::Client,
 }
 
 impl SsoClient {
@@ -503,13 +778,64 @@ impl SsoClient {
         service_url: String,
     ) -> Self {
         Self {
+            ticket_store: Arc::new(StorageTicketStore::new(manager.storage.clone())),
             manager,
             server_url,
             service_url,
             logout_callback: None,
+            check_ticket_url: None,
+            sign: None,
+            logout_callback_url: None,
+            #[cfg(feature = "sso-http")]
+            http: reqwest::Client::new(),
         }
     }
 
+    /// 设置接收服务端登出通知的地址，校验票据时登记到服务端
+    /// Set the URL receiving logout notifications from the server, registered while checking tickets
+    ///
+    /// # 参数 | Parameters
+    /// * `url` - 客户端登出回调地址，如 `https://app1.example.com/sso/logout-callback` | Client logout callback URL, e.g. `https://app1.example.com/sso/logout-callback`
+    pub fn with_logout_callback_url(mut self, url: impl Into<String>) -> Self {
+        self.logout_callback_url = Some(url.into());
+        self
+    }
+
+    /// 设置模式二的票据存储，需与服务端使用同一个票据存储
+    /// Set the ticket store for mode 2; it must be the one the server uses
+    ///
+    /// # 参数 | Parameters
+    /// * `store` - 票据存储 | Ticket store
+    pub fn with_ticket_store(mut self, store: Arc<dyn TicketStore>) -> Self {
+        self.ticket_store = store;
+        self
+    }
+
+    /// 设置模式三的服务端票据校验地址 | Set the server ticket check URL for mode 3
+    ///
+    /// # 参数 | Parameters
+    /// * `url` - 服务端校验接口，如 `https://sso.example.com/sso/checkTicket` | Server check endpoint, e.g. `https://sso.example.com/sso/checkTicket`
+    pub fn with_check_ticket_url(mut self, url: impl Into<String>) -> Self {
+        self.check_ticket_url = Some(url.into());
+        self
+    }
+
+    /// 设置模式三的签名管理器 | Set the sign manager for mode 3
+    ///
+    /// # 参数 | Parameters
+    /// * `sign` - 与服务端共享密钥的签名管理器 | Sign manager sharing its secret with the server
+    pub fn with_sign(mut self, sign: SaSignManager) -> Self {
+        self.sign = Some(sign);
+        self
+    }
+
+    /// 使用自定义的 reqwest 客户端（超时、代理等）| Use a custom reqwest client (timeouts, proxies, ...)
+    #[cfg(feature = "sso-http")]
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
     /// 设置登出回调函数 | Set logout callback
     ///
     /// # 参数 | Parameters
@@ -531,6 +857,66 @@ impl SsoClient {
         format!("{}?service={}", self.server_url, urlencoding::encode(&self.service_url))
     }
 
+    /// 生成带回跳页面的登录 URL | Generate a login URL carrying the page to return to
+    ///
+    /// 回跳页面以 `back` 参数附加在服务 URL 上，票据仍绑定到服务 URL 本身。
+    /// The page is appended to the service URL as `back`; the ticket stays bound to the service URL itself.
+    ///
+    /// # 参数 | Parameters
+    /// * `back` - 登录完成后返回的站内路径 | Site-relative path to return to after login
+    pub fn get_login_url_with_back(&self, back: &str) -> String {
+        let separator = if self.service_url.contains('?') { '&' } else { '?' };
+        let service = format!("{}{}back={}", self.service_url, separator, urlencoding::encode(back));
+        format!("{}?service={}", self.server_url, urlencoding::encode(&service))
+    }
+
+    /// 服务 URL 的路径部分，即票据回跳的地址 | Path of the service URL, where tickets come back to
+    pub fn callback_path(&self) -> &str {
+        let rest = self.service_url.split_once("://").map_or(self.service_url.as_str(), |(_, rest)| rest);
+        rest.find('/').map_or("/", |i| service_of(&rest[i..]))
+    }
+
+    /// 校验 `back` 参数，只接受站内路径，其他情况返回 `/`
+    /// Validate a `back` parameter; only site-relative paths are accepted, anything else yields `/`
+    ///
+    /// 防止登录回跳被用作开放重定向。
+    /// Keeps the login round trip from being used as an open redirect.
+    pub fn safe_back(back: Option<&str>) -> &str {
+        match back {
+            Some(back) if back.starts_with('/')
+                && !back.starts_with("//")
+                && !back.chars().any(|c| c == '\\' || c.is_whitespace() || c.is_control()) => back,
+            _ => "/",
+        }
+    }
+
+    /// 用票据完成本地登录 | Complete the local login with a ticket
+    ///
+    /// 配置了票据校验地址且启用 `sso-http` 特性时按模式三远程校验，否则按模式二在共享存储中校验。
+    /// Checks the ticket remotely (mode 3) when a check ticket URL is set and the `sso-http` feature
+    /// is enabled, otherwise in the shared storage (mode 2).
+    ///
+    /// # 返回 | Returns
+    /// `(login_id, token)` 用户登录 ID 与本地 Token | User login ID and local token
+    pub async fn exchange_ticket(&self, ticket: &str) -> SaTokenResult<(String, String)> {
+        let login_id = match &self.check_ticket_url {
+            None => self.check_ticket(ticket).await?,
+            #[cfg(feature = "sso-http")]
+            Some(_) => self.check_ticket_remote(ticket).await?,
+            #[cfg(not(feature = "sso-http"))]
+            Some(_) => {
+                return Err(SaTokenError::ConfigError("SSO check ticket URL requires the sso-http feature".to_string()));
+            }
+        };
+        let token = self.login_by_ticket(login_id.clone()).await?;
+        Ok((login_id, token))
+    }
+
+    /// 保存本地 Token 的 `Set-Cookie` 值 | `Set-Cookie` value holding the local token
+    pub fn token_cookie(&self, token: &str) -> String {
+        self.manager.token_cookie(&TokenValue::new(token))
+    }
+
     /// 生成登出 URL | Generate logout URL
     ///
     /// # 返回 | Returns
@@ -580,6 +966,71 @@ impl SsoClient {
         Ok(ticket.to_string())
     }
 
+    /// 模式二：直接在共享存储中校验并消费票据 | Mode 2: check and consume a ticket directly in the shared storage
+    ///
+    /// 要求客户端的 `SaTokenManager` 与服务端使用同一个存储。
+    /// Requires the client's `SaTokenManager` to use the same storage as the server.
+    ///
+    /// # 参数 | Parameters
+    /// * `ticket` - 回跳地址中的票据 | Ticket from the redirect URL
+    ///
+    /// # 返回 | Returns
+    /// 用户登录 ID | User login ID
+    pub async fn check_ticket(&self, ticket: &str) -> SaTokenResult<String> {
+        let login_id = SsoTicket::consume(self.ticket_store.as_ref(), ticket, &self.service_url).await?;
+        if let Some(url) = &self.logout_callback_url {
+            register_logout_url(self.manager.storage.as_ref(), &login_id, url).await?;
+        }
+        Ok(login_id)
+    }
+
+    /// 模式三：生成签名的票据校验地址 | Mode 3: build the signed ticket check URL
+    ///
+    /// 客户端以 GET 请求该地址，服务端用 `SsoServer::check_ticket_request` 处理。
+    /// The client GETs this URL; the server handles it with `SsoServer::check_ticket_request`.
+    ///
+    /// # 参数 | Parameters
+    /// * `ticket` - 回跳地址中的票据 | Ticket from the redirect URL
+    pub fn check_ticket_url(&self, ticket: &str) -> SaTokenResult<String> {
+        let (Some(url), Some(sign)) = (&self.check_ticket_url, &self.sign) else {
+            return Err(SaTokenError::ConfigError("SSO check ticket URL and sign manager must be set".to_string()));
+        };
+        let mut params = BTreeMap::from([
+            ("ticket".to_string(), ticket.to_string()),
+            ("service".to_string(), self.service_url.clone()),
+        ]);
+        if let Some(logout_url) = &self.logout_callback_url {
+            params.insert("logout_url".to_string(), logout_url.clone());
+        }
+        let params = sign.sign(params);
+        let query = params.iter()
+            .map(|(k, v)| format!("{}={}", urlencoding::encode(k), urlencoding::encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        Ok(format!("{}?{}", url, query))
+    }
+
+    /// 模式三：通过 HTTP 向服务端校验票据 | Mode 3: check a ticket with the server over HTTP
+    ///
+    /// 服务端应以 `{"login_id": "..."}` 响应成功的校验，失败时返回非 2xx 状态码。
+    /// The server answers a successful check with `{"login_id": "..."}` and a non-2xx status otherwise.
+    ///
+    /// # 参数 | Parameters
+    /// * `ticket` - 回跳地址中的票据 | Ticket from the redirect URL
+    #[cfg(feature = "sso-http")]
+    pub async fn check_ticket_remote(&self, ticket: &str) -> SaTokenResult<String> {
+        let url = self.check_ticket_url(ticket)?;
+        let response = self.http.get(url).send().await
+            .map_err(|e| SaTokenError::InternalError(format!("SSO ticket check failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(SaTokenError::InvalidTicket);
+        }
+        let body = response.text().await
+            .map_err(|e| SaTokenError::InternalError(format!("SSO ticket check failed: {}", e)))?;
+        let body: serde_json::Value = serde_json::from_str(&body)?;
+        body["login_id"].as_str().map(str::to_string).ok_or(SaTokenError::InvalidTicket)
+    }
+
     /// 通过票据登录（客户端本地登录）| Login by ticket (client-side local login)
     ///
     /// # 参数 | Parameters
@@ -623,6 +1074,25 @@ impl SsoClient {
         Ok(())
     }
 
+    /// 处理服务端推送的登出通知 | Handle a logout notification pushed by the server
+    ///
+    /// 使用与服务端共享密钥的签名管理器验签，通过后清除该用户的本地会话。
+    /// Verifies the signature with the sign manager shared with the server, then clears the user's local session.
+    ///
+    /// # 参数 | Parameters
+    /// * `params` - 请求参数：login_id、timestamp、nonce、sign | Request parameters: login_id, timestamp, nonce, sign
+    ///
+    /// # 返回 | Returns
+    /// 已登出的用户登录 ID | Login ID that was logged out
+    pub async fn handle_logout_request(&self, params: &BTreeMap<String, String>) -> SaTokenResult<String> {
+        let sign = self.sign.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("SSO sign manager is not set".to_string()))?;
+        sign.verify(params).await?;
+        let login_id = params.get("login_id").ok_or(SaTokenError::SsoSessionNotFound)?;
+        self.handle_logout(login_id).await?;
+        Ok(login_id.clone())
+    }
+
     /// 获取 SSO 服务端 URL | Get SSO Server URL
     pub fn server_url(&self) -> &str {
         &self.server_url
@@ -699,21 +1169,84 @@ impl SsoConfigBuilder {
     }
 }
 
+/// 登出通知发送器 | Logout notifier
+///
+/// 启用 `sso-http` feature 后默认使用基于 HTTP 的 `HttpSsoLogoutNotifier`；
+/// 也可自行实现以使用其他传输方式。
+/// With the `sso-http` feature, `HttpSsoLogoutNotifier` is used by default;
+/// implement the trait yourself for other transports.
+#[async_trait]
+pub trait SsoLogoutNotifier: Send + Sync {
+    /// 向客户端回调地址发送签名的登出通知 | Send a signed logout notification to a client callback URL
+    async fn notify(&self, url: &str, params: &BTreeMap<String, String>) -> SaTokenResult<()>;
+}
+
+/// 基于 HTTP 的登出通知发送器 | HTTP logout notifier
+///
+/// 以 `application/x-www-form-urlencoded` POST 签名参数到客户端回调地址，2xx 视为送达。
+/// POSTs the signed parameters as `application/x-www-form-urlencoded` to the client callback URL; a 2xx counts as delivered.
+#[cfg(feature = "sso-http")]
+#[derive(Clone, Default)]
+pub struct HttpSsoLogoutNotifier {
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "sso-http")]
+impl HttpSsoLogoutNotifier {
+    /// 创建发送器 | Create a notifier
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用自定义的 reqwest 客户端（超时、代理等）| Use a custom reqwest client (timeouts, proxies, ...)
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+}
+
+#[cfg(feature = "sso-http")]
+#[async_trait]
+impl SsoLogoutNotifier for HttpSsoLogoutNotifier {
+    async fn notify(&self, url: &str, params: &BTreeMap<String, String>) -> SaTokenResult<()> {
+        let response = self.http.post(url).form(params).send().await
+            .map_err(|e| SaTokenError::InternalError(format!("SSO logout notification failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(SaTokenError::InternalError(
+                format!("SSO logout callback returned {}", response.status()),
+            ));
+        }
+        Ok(())
+    }
+}
+
 pub struct SsoManager {
     server: Option<Arc<SsoServer>>,
     client: Option<Arc<SsoClient>>,
     config: SsoConfig,
+    notifier: Option<Arc<dyn SsoLogoutNotifier>>,
 }
 
 impl SsoManager {
     pub fn new(config: SsoConfig) -> Self {
+        #[cfg(feature = "sso-http")]
+        let notifier: Option<Arc<dyn SsoLogoutNotifier>> = Some(Arc::new(HttpSsoLogoutNotifier::new()));
+        #[cfg(not(feature = "sso-http"))]
+        let notifier: Option<Arc<dyn SsoLogoutNotifier>> = None;
         Self {
             server: None,
             client: None,
             config,
+            notifier,
         }
     }
 
+    /// 设置登出通知发送器 | Set the logout notifier
+    pub fn with_logout_notifier(mut self, notifier: Arc<dyn SsoLogoutNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
     pub fn with_server(mut self, server: Arc<SsoServer>) -> Self {
         self.server = Some(server);
         self
@@ -744,5 +1277,209 @@ impl SsoManager {
         self.config.allowed_origins.contains(&"*".to_string()) ||
         self.config.allowed_origins.contains(&origin.to_string())
     }
+
+    /// 单点登出并通知所有已登记的客户端 | Single logout, notifying every registered client
+    ///
+    /// 先在服务端登出，再并发向每个登出回调地址 POST 签名的 `login_id`。通知失败不会中断登出。
+    /// Logs out at the server first, then POSTs the signed `login_id` to every logout callback URL concurrently.
+    /// Failed notifications do not abort the logout.
+    ///
+    /// # 参数 | Parameters
+    /// * `login_id` - 用户登录 ID | User login ID
+    ///
+    /// # 返回 | Returns
+    /// 未能送达的回调地址；未配置发送器时为全部地址 | Callback URLs that could not be notified; all of them when no notifier is set
+    pub async fn logout(&self, login_id: &str) -> SaTokenResult<Vec<String>> {
+        let server = self.server.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("SSO server is not set".to_string()))?;
+        let sign = server.sign.as_ref()
+            .ok_or_else(|| SaTokenError::ConfigError("SSO sign manager is not set".to_string()))?;
+
+        let urls = server.logout_urls(login_id).await?;
+        server.logout(login_id).await?;
+        server.manager.storage.delete(&logout_urls_key(login_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+
+        let Some(notifier) = &self.notifier else {
+            return Ok(urls);
+        };
+        let results = futures_util::future::join_all(urls.iter().map(|url| async move {
+            let params = sign.sign(BTreeMap::from([("login_id".to_string(), login_id.to_string())]));
+            let result = notifier.notify(url, &params).await;
+            if let Err(e) = &result {
+                tracing::warn!(url = %url, error = %e, "SSO logout notification failed");
+            }
+            result
+        })).await;
+
+        Ok(urls.into_iter()
+            .zip(results)
+            .filter_map(|(url, result)| result.is_err().then_some(url))
+            .collect())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn manager(storage: Arc<dyn SaStorage>) -> Arc<SaTokenManager> {
+        Arc::new(SaTokenManager::new(storage, SaTokenConfig::default()))
+    }
+
+    #[tokio::test]
+    async fn test_cross_domain_modes() {
+        let shared: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let sign = SaSignManager::new("sso-secret", shared.clone());
+        let server = SsoServer::new(manager(shared.clone()))
+            .with_allow_urls(vec!["http://app1.com/sso/*".to_string(), "http://app2.com/sso/login".to_string()])
+            .with_sign(sign);
+
+        assert!(server.is_allowed_url("http://app2.com/sso/login?back=/orders"));
+        assert!(!server.is_allowed_url("http://app2.com/sso/login/evil"));
+        assert!(matches!(
+            server.build_redirect_url("alice", "http://evil.com/sso/login").await,
+            Err(SaTokenError::SsoRedirectNotAllowed(_))
+        ));
+
+        // 模式二：共享存储 | Mode 2: shared storage
+        let redirect = server.build_redirect_url("alice", "http://app1.com/sso/login?back=%2F#top").await.unwrap();
+        let (base, rest) = redirect.split_once("&ticket=").unwrap();
+        assert_eq!(base, "http://app1.com/sso/login?back=%2F");
+        let ticket = rest.strip_suffix("#top").unwrap();
+        let client1 = SsoClient::new(manager(shared.clone()), "http://sso.com/auth".to_string(), "http://app1.com/sso/login".to_string());
+        assert_eq!(client1.check_ticket(ticket).await.unwrap(), "alice");
+        assert!(matches!(client1.check_ticket(ticket).await, Err(SaTokenError::TicketExpired)));
+
+        // 模式三：独立存储，签名的 HTTP 校验 | Mode 3: separate storage, signed HTTP check
+        let client2 = SsoClient::new(
+            manager(Arc::new(MemoryStorage::new())),
+            "http://sso.com/auth".to_string(),
+            "http://app2.com/sso/login".to_string(),
+        )
+        .with_check_ticket_url("http://sso.com/sso/checkTicket")
+        .with_sign(SaSignManager::new("sso-secret", Arc::new(MemoryStorage::new())));
+        let redirect = server.build_redirect_url("alice", "http://app2.com/sso/login").await.unwrap();
+        let ticket = redirect.split_once("?ticket=").unwrap().1;
+        assert!(matches!(client2.check_ticket(ticket).await, Err(SaTokenError::InvalidTicket)));
+
+        let url = client2.check_ticket_url(ticket).unwrap();
+        let params = SaSignManager::params_from_query(url.split_once('?').unwrap().1);
+        assert_eq!(server.check_ticket_request(&params).await.unwrap(), "alice");
+        // 重放被拒绝 | A replay is rejected
+        assert!(server.check_ticket_request(&params).await.is_err());
+
+        let mut tampered = SaSignManager::params_from_query(client2.check_ticket_url(ticket).unwrap().split_once('?').unwrap().1);
+        tampered.insert("service".to_string(), "http://app1.com/sso/login".to_string());
+        assert!(matches!(server.check_ticket_request(&tampered).await, Err(SaTokenError::InvalidSignature)));
+    }
+
+    #[tokio::test]
+    async fn test_custom_ticket_store() {
+        let store: Arc<dyn TicketStore> = Arc::new(crate::ticket_store::InMemoryTicketStore::new());
+        let server_storage: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let server = SsoServer::new(manager(server_storage.clone()))
+            .with_allow_urls(vec!["http://app1.com/sso/login".to_string()])
+            .with_ticket_store(store.clone());
+        let client = SsoClient::new(manager(Arc::new(MemoryStorage::new())), "http://sso.com/auth".to_string(), "http://app1.com/sso/login".to_string())
+            .with_ticket_store(store);
+
+        let redirect = server.build_redirect_url("alice", "http://app1.com/sso/login").await.unwrap();
+        let ticket = redirect.split_once("?ticket=").unwrap().1;
+        assert!(server_storage.get(&format!("sa:sso:ticket:{}", ticket)).await.unwrap().is_none());
+        assert_eq!(client.check_ticket(ticket).await.unwrap(), "alice");
+        assert!(matches!(server.validate_ticket(ticket, "http://app1.com/sso/login").await, Err(SaTokenError::TicketExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_login_round_trip_with_back() {
+        let shared: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let server = SsoServer::new(manager(shared.clone()))
+            .with_allow_urls(vec!["http://app1.com/sso/login".to_string()]);
+        let client = SsoClient::new(manager(shared), "http://sso.com/auth".to_string(), "http://app1.com/sso/login".to_string());
+        assert_eq!(client.callback_path(), "/sso/login");
+
+        let login_url = client.get_login_url_with_back("/orders?page=2");
+        let params = SaSignManager::params_from_query(login_url.split_once('?').unwrap().1);
+        assert_eq!(params["service"], "http://app1.com/sso/login?back=%2Forders%3Fpage%3D2");
+
+        // 票据绑定到服务 URL，back 参数原样带回 | The ticket is bound to the service URL and back comes along
+        let redirect = server.build_redirect_url("alice", &params["service"]).await.unwrap();
+        let params = SaSignManager::params_from_query(redirect.split_once('?').unwrap().1);
+        let (login_id, token) = client.exchange_ticket(&params["ticket"]).await.unwrap();
+        assert_eq!(login_id, "alice");
+        assert!(client.token_cookie(&token).starts_with(&format!("sa-token={}; Path=/", token)));
+        assert_eq!(SsoClient::safe_back(params.get("back").map(String::as_str)), "/orders?page=2");
+
+        for back in ["//evil.com", "https://evil.com", "/\\evil.com", "/a b"] {
+            assert_eq!(SsoClient::safe_back(Some(back)), "/");
+        }
+    }
+
+    /// 直接调用客户端处理器的通知发送器 | Notifier calling the client handler directly
+    struct InProcessNotifier {
+        clients: HashMap<String, Arc<SsoClient>>,
+    }
+
+    #[async_trait]
+    impl SsoLogoutNotifier for InProcessNotifier {
+        async fn notify(&self, url: &str, params: &BTreeMap<String, String>) -> SaTokenResult<()> {
+            let client = self.clients.get(url).ok_or(SaTokenError::InternalError(format!("unreachable: {}", url)))?;
+            client.handle_logout_request(params).await.map(|_| ())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_logout_propagation() {
+        let shared: Arc<dyn SaStorage> = Arc::new(MemoryStorage::new());
+        let server = Arc::new(
+            SsoServer::new(manager(shared.clone()))
+                .with_allow_urls(vec!["http://app1.com/*".to_string(), "http://app2.com/*".to_string(), "http://gone.com/*".to_string()])
+                .with_sign(SaSignManager::new("sso-secret", shared.clone())),
+        );
+        let client = |service: &str| {
+            Arc::new(
+                SsoClient::new(manager(Arc::new(MemoryStorage::new())), "http://sso.com/auth".to_string(), format!("{}/sso/login", service))
+                    .with_check_ticket_url("http://sso.com/sso/checkTicket")
+                    .with_sign(SaSignManager::new("sso-secret", Arc::new(MemoryStorage::new())))
+                    .with_logout_callback_url(format!("{}/sso/logout-callback", service)),
+            )
+        };
+        let (app1, app2) = (client("http://app1.com"), client("http://app2.com"));
+
+        server.login("alice".to_string(), "http://sso.com".to_string()).await.unwrap();
+        for app in [&app1, &app2] {
+            let redirect = server.build_redirect_url("alice", app.service_url()).await.unwrap();
+            let ticket = redirect.split_once("?ticket=").unwrap().1;
+            let url = app.check_ticket_url(ticket).unwrap();
+            let login_id = server.check_ticket_request(&SaSignManager::params_from_query(url.split_once('?').unwrap().1)).await.unwrap();
+            app.login_by_ticket(login_id).await.unwrap();
+            assert!(app.check_local_login("alice").await);
+        }
+        server.register_logout_url("alice", "http://gone.com/sso/logout-callback").await.unwrap();
+        assert_eq!(server.logout_urls("alice").await.unwrap().len(), 3);
+
+        let notifier = InProcessNotifier {
+            clients: HashMap::from([
+                ("http://app1.com/sso/logout-callback".to_string(), app1.clone()),
+                ("http://app2.com/sso/logout-callback".to_string(), app2.clone()),
+            ]),
+        };
+        let sso = SsoManager::new(SsoConfig::default())
+            .with_server(server.clone())
+            .with_logout_notifier(Arc::new(notifier));
+        let undelivered = sso.logout("alice").await.unwrap();
+
+        assert_eq!(undelivered, ["http://gone.com/sso/logout-callback"]);
+        assert!(!app1.check_local_login("alice").await);
+        assert!(!app2.check_local_login("alice").await);
+        assert!(server.logout_urls("alice").await.unwrap().is_empty());
+
+        // 伪造的登出通知被拒绝 | A forged logout notification is rejected
+        let forged = SaSignManager::new("wrong-secret", Arc::new(MemoryStorage::new()))
+            .sign(BTreeMap::from([("login_id".to_string(), "bob".to_string())]));
+        assert!(matches!(app1.handle_logout_request(&forged).await, Err(SaTokenError::InvalidSignature)));
+    }
+}
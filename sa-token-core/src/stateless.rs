@@ -0,0 +1,143 @@
+// Author: 金书记
+//
+//! 自包含 JWT（Mixin / Stateless 模式）| Self-contained JWTs (Mixin / Stateless modes)
+//!
+//! `JwtMode::Mixin` 与 `JwtMode::Stateless` 下，登录签发的 JWT 自带登录 ID、账号类型、设备、过期时间、
+//! 权限与角色，token 信息不再写入 `SaStorage`。插件中间件无需任何改动。
+//! In `JwtMode::Mixin` and `JwtMode::Stateless`, the JWT issued at login carries the login ID, account type,
+//! device, expiry, permissions and roles, and the token info is no longer written to `SaStorage`.
+//! Plugin middleware works unchanged.
+//!
+//! ```text
+//! login(id) → 权限与角色写入声明 | permissions and roles written into the claims → JWT
+//! get_token_info(jwt) → 验证签名与 exp | verify signature and exp
+//!                       └─→ Mixin: 查询吊销标记 | look up revocation markers → TokenInfo
+//! has_permission(id, p) → 当前请求 token 的 perms 声明 | perms claim of the current request's token
+//!
+//! Mixin  logout(jwt)          → sa:jwt:revoked:{jti}（TTL = token 剩余有效期 | remaining lifetime）
+//!        kick_out(id)         → sa:jwt:revoked-before:{id} = now，更早签发的 token 失效 | earlier tokens revoked
+//! ```
+//!
+//! Stateless 的代价：token 在过期前无法吊销（登出只触发事件），冻结、软登出、按登录 ID 查找 token
+//! 等依赖存储的功能不可用。两种模式都必须配置有限的 `timeout`。
+//! The Stateless trade-off: a token cannot be revoked before it expires (logout only publishes the event),
+//! and storage-backed features such as freezing, soft logout or looking tokens up by login ID are unavailable.
+//! Both modes require a finite `timeout`.
+//!
+//! `TokenInfo` 与声明的映射由 `SaTokenConfig::jwt_claims_mapper` 决定，默认为 `DefaultClaimsMapper`。
+//! The `TokenInfo` ↔ claims mapping comes from `SaTokenConfig::jwt_claims_mapper`, `DefaultClaimsMapper` by default.
+
+use std::sync::Arc;
+use serde_json::Value;
+use crate::config::SaTokenConfig;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::token::{ClaimsMapper, DefaultClaimsMapper, JwksKeyRing, JwtClaims, JwtManager, TokenInfo, TokenValue};
+
+/// 权限声明名（同时保存在 `TokenInfo::extra` 中）| Permissions claim name (also kept in `TokenInfo::extra`)
+pub const PERMISSIONS_CLAIM: &str = "perms";
+
+/// 角色声明名（同时保存在 `TokenInfo::extra` 中）| Roles claim name (also kept in `TokenInfo::extra`)
+pub const ROLES_CLAIM: &str = "roles";
+
+/// 单个 token 的吊销标记键 | Revocation marker key of a single token
+pub(crate) fn revoked_key(jti: &str) -> String {
+    format!("sa:jwt:revoked:{}", jti)
+}
+
+/// 账号级吊销标记键，值为毫秒时间戳，更早签发的 token 失效
+/// Per-account revocation marker key; its value is a millisecond timestamp and earlier tokens are revoked
+pub(crate) fn revoked_before_key(namespaced_login_id: &str) -> String {
+    format!("sa:jwt:revoked-before:{}", namespaced_login_id)
+}
+
+/// 解码后的自包含 token | A decoded self-contained token
+pub(crate) struct DecodedToken {
+    pub(crate) info: TokenInfo,
+    pub(crate) jti: Option<String>,
+}
+
+/// 签名与验证密钥 | Signing and verification keys
+#[derive(Clone)]
+enum Signer {
+    KeyRing(JwksKeyRing),
+    Manager(Arc<JwtManager>),
+    Invalid(String),
+}
+
+/// 签名密钥与声明映射，创建管理器时从配置解析一次 | Keys and claims mapping, parsed from the config once per manager
+#[derive(Clone)]
+pub(crate) struct StatelessKeys {
+    signer: Signer,
+    mapper: Arc<dyn ClaimsMapper>,
+}
+
+impl StatelessKeys {
+    pub(crate) fn from_config(config: &SaTokenConfig) -> Self {
+        let signer = match &config.jwt_key_ring {
+            Some(key_ring) => Signer::KeyRing(key_ring.clone()),
+            None => match JwtManager::from_config(config) {
+                Ok(manager) => Signer::Manager(Arc::new(manager)),
+                Err(e) => Signer::Invalid(e.to_string()),
+            },
+        };
+        let mapper = config.jwt_claims_mapper.clone().unwrap_or_else(|| Arc::new(DefaultClaimsMapper));
+        Self { signer, mapper }
+    }
+}
+
+impl Signer {
+    fn generate(&self, claims: &JwtClaims) -> SaTokenResult<String> {
+        match self {
+            Self::KeyRing(key_ring) => key_ring.generate(claims),
+            Self::Manager(manager) => manager.generate(claims),
+            Self::Invalid(e) => Err(SaTokenError::ConfigError(e.clone())),
+        }
+    }
+
+    fn validate(&self, token: &str) -> SaTokenResult<JwtClaims> {
+        match self {
+            Self::KeyRing(key_ring) => key_ring.validate(token),
+            Self::Manager(manager) => manager.validate(token),
+            Self::Invalid(e) => Err(SaTokenError::ConfigError(e.clone())),
+        }
+    }
+}
+
+/// 将 token 信息、权限与角色编码为 JWT | Encode token info, permissions and roles as a JWT
+pub(crate) fn encode(
+    keys: &StatelessKeys,
+    token_info: &TokenInfo,
+    permissions: Vec<String>,
+    roles: Vec<String>,
+) -> SaTokenResult<TokenValue> {
+    let expire_time = token_info.expire_time.ok_or_else(|| SaTokenError::ConfigError(
+        "Mixin and Stateless JWT modes require a finite timeout".to_string(),
+    ))?;
+
+    let mut claims = JwtClaims::from_map(keys.mapper.to_claims(token_info))?;
+    claims.set_expiration_at(expire_time);
+    claims.set_jti(uuid::Uuid::new_v4().simple().to_string());
+    claims.add_claim(PERMISSIONS_CLAIM, Value::from(permissions));
+    claims.add_claim(ROLES_CLAIM, Value::from(roles));
+
+    Ok(TokenValue::new(keys.signer.generate(&claims)?))
+}
+
+/// 验证 JWT 并还原 token 信息 | Verify a JWT and rebuild the token info
+pub(crate) fn decode(keys: &StatelessKeys, token: &TokenValue) -> SaTokenResult<DecodedToken> {
+    let claims = keys.signer.validate(token.as_str()).map_err(|e| match e {
+        SaTokenError::TokenExpired | SaTokenError::ConfigError(_) => e,
+        _ => SaTokenError::TokenNotFound,
+    })?;
+
+    let jti = claims.jti.clone();
+    let mut token_info = keys.mapper.from_claims(claims.to_map());
+    token_info.token = token.clone();
+    Ok(DecodedToken { info: token_info, jti })
+}
+
+/// 读取 token 信息中的权限或角色声明 | Read the permissions or roles claim of a token info
+pub(crate) fn claim_list(token_info: &TokenInfo, claim: &str) -> Option<Vec<String>> {
+    let list = token_info.get_extra(claim)?.as_array()?;
+    Some(list.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+}
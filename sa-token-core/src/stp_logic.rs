@@ -0,0 +1,255 @@
+// Author: 金书记
+//
+//! 多账号体系 | Multiple Account Systems
+//!
+//! 每个 `StpLogic` 是一个独立的认证域（如 `admin` 与 `user`），拥有自己的管理器、
+//! token 名称和有效期，签发的 token 的 `login_type` 即为该域的账号类型。
+//! Every `StpLogic` is a separate authentication realm (e.g. `admin` and `user`) with its own
+//! manager, token name and timeout; tokens it issues carry the realm's account type as `login_type`.
+//!
+//! ```text
+//! 启动 | Startup: SaTokenState::builder().logic(StpLogic::new("admin", manager))
+//!   └─→ StpUtil::register_logic → 全局注册表 | global registry
+//!
+//! 请求 | Request
+//!   └─→ 插件层按每个已注册域的 token_name 读取 token → SaTokenContext::typed_tokens
+//!       plugin layer reads a token per registered realm's token_name → SaTokenContext::typed_tokens
+//!         └─→ StpUtil::logic("admin")?.check_login_current()
+//!               └─→ 只认可 login_type 为 admin 的 token | only accepts tokens whose login_type is admin
+//! ```
+
+use std::sync::Arc;
+use sa_token_adapter::context::SaRequest;
+use crate::context::SaTokenContext;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::token::{TokenInfo, TokenValue};
+use crate::util::LoginId;
+use crate::SaTokenManager;
+
+/// 账号体系逻辑 | Account system logic
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let admin = StpLogic::new(
+///     "admin",
+///     SaTokenManager::new(
+///         storage.clone(),
+///         SaTokenConfig::builder().token_name("admin-token").timeout(3600).build_config(),
+///     ),
+/// );
+/// StpUtil::register_logic(admin);
+///
+/// let token = StpUtil::logic("admin")?.login(10001).await?;
+/// ```
+#[derive(Clone)]
+pub struct StpLogic {
+    login_type: String,
+    manager: Arc<SaTokenManager>,
+}
+
+impl StpLogic {
+    /// 创建账号体系 | Create an account system
+    ///
+    /// # 参数 | Parameters
+    /// * `login_type` - 账号类型 | Account type
+    /// * `manager` - 该账号体系专用的管理器 | Manager dedicated to this account system
+    pub fn new(login_type: impl Into<String>, manager: impl Into<Arc<SaTokenManager>>) -> Self {
+        Self {
+            login_type: login_type.into(),
+            manager: manager.into(),
+        }
+    }
+
+    /// 账号类型 | Account type
+    pub fn login_type(&self) -> &str {
+        &self.login_type
+    }
+
+    /// 管理器 | Manager
+    pub fn manager(&self) -> &Arc<SaTokenManager> {
+        &self.manager
+    }
+
+    /// token 名称（请求头 / Cookie / 参数名）| Token name (header / cookie / parameter name)
+    pub fn token_name(&self) -> &str {
+        &self.manager.config.token_name
+    }
+
+    // ==================== 登录相关 | Login ====================
+
+    /// 以该账号类型登录 | Log in with this account type
+    pub async fn login(&self, login_id: impl LoginId) -> SaTokenResult<TokenValue> {
+        self.manager.login_with_options(
+            login_id.to_login_id(),
+            Some(self.login_type.clone()),
+            None,
+            None,
+            None,
+            None,
+        ).await
+    }
+
+    /// 登出 | Log out
+    pub async fn logout(&self, token: &TokenValue) -> SaTokenResult<()> {
+        self.get_token_info(token).await?;
+        self.manager.logout(token).await
+    }
+
+    /// 踢人下线 | Kick out
+    pub async fn kick_out(&self, login_id: impl LoginId) -> SaTokenResult<()> {
+        self.manager.kick_out_by_type(&login_id.to_login_id(), &self.login_type).await
+    }
+
+    /// 获取 token 信息，其他账号类型的 token 视为未登录
+    /// Get token info, tokens of other account types count as not logged in
+    pub async fn get_token_info(&self, token: &TokenValue) -> SaTokenResult<TokenInfo> {
+        let info = self.manager.get_token_info(token).await?;
+        if info.account_type() != self.login_type {
+            return Err(SaTokenError::NotLogin);
+        }
+        Ok(info)
+    }
+
+    /// token 是否已登录 | Whether the token is logged in
+    pub async fn is_login(&self, token: &TokenValue) -> bool {
+        self.get_token_info(token).await.is_ok()
+    }
+
+    /// 获取 token 的登录 ID | Get the login ID of a token
+    pub async fn get_login_id(&self, token: &TokenValue) -> SaTokenResult<String> {
+        Ok(self.get_token_info(token).await?.login_id)
+    }
+
+    // ==================== 权限与角色 | Permissions & Roles ====================
+
+    /// 设置权限 | Set the permissions of a login ID
+    pub async fn set_permissions(&self, login_id: impl LoginId, permissions: Vec<String>) {
+        self.manager.set_permissions_by_type(&login_id.to_login_id(), &self.login_type, permissions).await
+    }
+
+    /// 设置角色 | Set the roles of a login ID
+    pub async fn set_roles(&self, login_id: impl LoginId, roles: Vec<String>) {
+        self.manager.set_roles_by_type(&login_id.to_login_id(), &self.login_type, roles).await
+    }
+
+    /// 是否拥有权限 | Whether the login ID has a permission
+    pub async fn has_permission(&self, login_id: impl LoginId, permission: &str) -> bool {
+        self.manager.has_permission_by_type(&login_id.to_login_id(), &self.login_type, permission).await
+    }
+
+    /// 是否拥有角色 | Whether the login ID has a role
+    pub async fn has_role(&self, login_id: impl LoginId, role: &str) -> bool {
+        self.manager.has_role_by_type(&login_id.to_login_id(), &self.login_type, role).await
+    }
+
+    /// 检查权限 | Check a permission
+    pub async fn check_permission(&self, login_id: impl LoginId, permission: &str) -> SaTokenResult<()> {
+        if !self.has_permission(login_id, permission).await {
+            return Err(SaTokenError::PermissionDeniedDetail(permission.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 检查角色 | Check a role
+    pub async fn check_role(&self, login_id: impl LoginId, role: &str) -> SaTokenResult<()> {
+        if !self.has_role(login_id, role).await {
+            return Err(SaTokenError::RoleDenied(role.to_string()));
+        }
+        Ok(())
+    }
+
+    // ==================== 当前请求 | Current Request ====================
+
+    /// 获取当前请求中该账号类型的 token | Get the token of this account type in the current request
+    pub fn get_token_value_current(&self) -> SaTokenResult<TokenValue> {
+        SaTokenContext::get_current()
+            .and_then(|ctx| ctx.token_for_type(&self.login_type))
+            .ok_or(SaTokenError::NotLogin)
+    }
+
+    /// 当前请求是否已登录该账号类型 | Whether the current request is logged in with this account type
+    pub async fn is_login_current(&self) -> bool {
+        self.check_login_current().await.is_ok()
+    }
+
+    /// 检查当前请求已登录该账号类型 | Check the current request is logged in with this account type
+    pub async fn check_login_current(&self) -> SaTokenResult<()> {
+        let token = self.get_token_value_current()?;
+        self.get_token_info(&token).await.map(|_| ())
+    }
+
+    /// 获取当前请求的登录 ID | Get the login ID of the current request
+    pub async fn get_login_id_current(&self) -> SaTokenResult<String> {
+        let token = self.get_token_value_current()?;
+        self.get_login_id(&token).await
+    }
+
+    /// 检查当前请求的权限 | Check a permission of the current request
+    pub async fn check_permission_current(&self, permission: &str) -> SaTokenResult<()> {
+        let login_id = self.get_login_id_current().await?;
+        self.check_permission(login_id, permission).await
+    }
+
+    /// 检查当前请求的角色 | Check a role of the current request
+    pub async fn check_role_current(&self, role: &str) -> SaTokenResult<()> {
+        let login_id = self.get_login_id_current().await?;
+        self.check_role(login_id, role).await
+    }
+
+    /// 从请求中读取该账号类型的 token（请求头 → Cookie → 参数）
+    /// Read the token of this account type from a request (header → cookie → parameter)
+    pub fn collect_token<R: SaRequest + ?Sized>(&self, req: &R) -> Option<TokenValue> {
+        let token_name = self.token_name();
+        req.get_header(token_name)
+            .map(|value| value.strip_prefix("Bearer ").unwrap_or(&value).trim().to_string())
+            .or_else(|| req.get_cookie(token_name))
+            .or_else(|| req.get_param(token_name))
+            .filter(|token| !token.is_empty())
+            .map(TokenValue::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    fn logic(login_type: &str, token_name: &str, timeout: i64, storage: Arc<MemoryStorage>) -> StpLogic {
+        let config = SaTokenConfig::builder().token_name(token_name).timeout(timeout).build_config();
+        StpLogic::new(login_type, SaTokenManager::new(storage, config))
+    }
+
+    #[tokio::test]
+    async fn test_separate_realms() {
+        let storage = Arc::new(MemoryStorage::new());
+        let admin = logic("admin", "admin-token", 600, storage.clone());
+        let user = logic("user", "user-token", 86400, storage);
+
+        let admin_token = admin.login(1001).await.unwrap();
+        let user_token = user.login(1001).await.unwrap();
+
+        assert!(admin.is_login(&admin_token).await);
+        assert!(!admin.is_login(&user_token).await);
+        assert!(!user.is_login(&admin_token).await);
+        assert_eq!(admin.manager().config.timeout, 600);
+
+        admin.set_roles(1001, vec!["super".to_string()]).await;
+        assert!(!user.has_role(1001, "super").await);
+        let mut ctx = SaTokenContext::new();
+        ctx.typed_tokens = HashMap::from([
+            ("admin".to_string(), admin_token.clone()),
+            ("user".to_string(), user_token.clone()),
+        ]);
+        SaTokenContext::set_current(ctx);
+        assert!(admin.check_role_current("super").await.is_ok());
+        assert_eq!(user.get_login_id_current().await.unwrap(), "1001");
+        SaTokenContext::clear();
+        assert!(matches!(admin.check_login_current().await, Err(SaTokenError::NotLogin)));
+
+        admin.kick_out(1001).await.unwrap();
+        assert!(!admin.is_login(&admin_token).await);
+        assert!(user.is_login(&user_token).await);
+    }
+}
@@ -0,0 +1,283 @@
+// Author: 金书记
+//
+//! 多租户隔离 | Multi-tenant isolation
+//!
+//! 一个部署可以服务多个相互隔离的客户域。设置 `SaTokenConfig::tenant_resolver` 后：
+//! One deployment can serve many isolated customer realms. With `SaTokenConfig::tenant_resolver` set:
+//!
+//! - 插件的层从请求头或子域名解析租户，并在 [`scope`] 中处理请求，`SaTokenContext::tenant` 中可读取
+//!   Plugin layers resolve the tenant from a header or the subdomain and handle the request inside
+//!   [`scope`]; it is available as `SaTokenContext::tenant`
+//! - `SaTokenManager` 的存储被 [`TenantStorage`] 包装，每个键都加上租户前缀，token 与会话不会跨租户泄露
+//!   The storage of `SaTokenManager` is wrapped in [`TenantStorage`], which prefixes every key with the
+//!   tenant, so tokens and sessions never leak across tenants
+//! - `SaTokenConfig::tenant_timeouts` 为单个租户覆盖 token 有效期
+//!   `SaTokenConfig::tenant_timeouts` overrides the token timeout per tenant
+//!
+//! ```text
+//! Host: acme.example.com  ──▶ tenant "acme"
+//! set("sa:token:abc", ..) ──▶ inner.set("tenant:acme:sa:token:abc", ..)
+//! ```
+//!
+//! 租户只在 [`scope`] 内的同一任务中可见，`tokio::spawn` 出的任务需要自行用 [`scope`] 包装。
+//! 不在任何租户中时使用不带前缀的键。
+//! The tenant is only visible within the same task inside [`scope`]; wrap futures handed to
+//! `tokio::spawn` in [`scope`] yourself. Outside of any tenant the keys carry no prefix.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sa_token_adapter::storage::{SaStorage, StorageResult};
+
+/// 租户存储键的前缀 | Prefix of tenant storage keys
+pub const TENANT_KEY_PREFIX: &str = "tenant:";
+
+/// 租户 ID 的最大长度 | Maximum length of a tenant ID
+const MAX_TENANT_LEN: usize = 64;
+
+tokio::task_local! {
+    static CURRENT_TENANT: String;
+}
+
+/// 从请求中解析租户的方式 | How the tenant is resolved from a request
+///
+/// 租户 ID 只能包含字母、数字、`-` 与 `_`，其他值视为未解析到租户
+/// Tenant IDs may only contain letters, digits, `-` and `_`; anything else resolves to no tenant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TenantResolver {
+    /// 读取指定请求头（如 `X-Tenant-Id`）| Read the given header (e.g. `X-Tenant-Id`)
+    Header(String),
+    /// 取 `Host` 的第一段子域名（`acme.example.com` → `acme`）
+    /// Take the first label of a subdomain `Host` (`acme.example.com` → `acme`)
+    Subdomain,
+}
+
+impl TenantResolver {
+    /// 按请求头解析租户 | Resolve the tenant from a header lookup
+    pub fn resolve(&self, header: impl Fn(&str) -> Option<String>) -> Option<String> {
+        let tenant = match self {
+            Self::Header(name) => header(name)?,
+            Self::Subdomain => {
+                let host = header("Host")?;
+                let host = host.rsplit_once(':').map_or(host.as_str(), |(host, _)| host);
+                if host.parse::<std::net::Ipv4Addr>().is_ok() {
+                    return None;
+                }
+                let (label, domain) = host.split_once('.')?;
+                // `example.com` 本身不带子域名 | `example.com` itself has no subdomain
+                if !domain.contains('.') {
+                    return None;
+                }
+                label.to_string()
+            }
+        };
+        let tenant = tenant.trim();
+        is_valid_tenant(tenant).then(|| tenant.to_string())
+    }
+}
+
+/// 租户 ID 是否合法 | Whether a tenant ID is valid
+pub fn is_valid_tenant(tenant: &str) -> bool {
+    !tenant.is_empty()
+        && tenant.len() <= MAX_TENANT_LEN
+        && tenant.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 在租户中运行 future，`tenant` 为 None 时直接运行
+/// Run a future within a tenant, or directly when `tenant` is None
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let token = tenant::scope(Some("acme".to_string()), StpUtil::login(10001)).await?;
+/// ```
+pub async fn scope<F: Future>(tenant: Option<String>, future: F) -> F::Output {
+    match tenant {
+        Some(tenant) => CURRENT_TENANT.scope(tenant, future).await,
+        None => future.await,
+    }
+}
+
+/// 当前任务所在的租户 | Tenant of the current task
+///
+/// 不在 [`scope`] 中时回退到当前 `SaTokenContext` 的租户（如 Rocket 的 fairing 无法包装处理函数）
+/// Outside of [`scope`] falls back to the tenant of the current `SaTokenContext` (e.g. Rocket
+/// fairings cannot wrap the handler)
+pub fn current() -> Option<String> {
+    CURRENT_TENANT.try_with(Clone::clone).ok()
+        .or_else(|| crate::SaTokenContext::get_current().and_then(|ctx| ctx.tenant))
+}
+
+/// 为键加上当前租户，与 [`TenantStorage`] 写入的键一致
+/// Qualify a key with the current tenant, the same way [`TenantStorage`] writes it
+pub fn scoped(key: &str) -> String {
+    match current() {
+        Some(tenant) => format!("{}{}:{}", TENANT_KEY_PREFIX, tenant, key),
+        None => key.to_string(),
+    }
+}
+
+/// 按租户隔离键的存储包装器 | Storage wrapper isolating keys per tenant
+///
+/// 设置了 `tenant_resolver` 时 `SaTokenManager::new` 会自动包装存储，一般无需手动使用
+/// `SaTokenManager::new` wraps the storage automatically when `tenant_resolver` is set, so it is
+/// rarely used directly
+pub struct TenantStorage {
+    inner: Arc<dyn SaStorage>,
+}
+
+impl TenantStorage {
+    /// 包装存储后端 | Wrap a storage backend
+    pub fn new(inner: Arc<dyn SaStorage>) -> Self {
+        Self { inner }
+    }
+
+    /// 内部存储后端 | Inner storage backend
+    pub fn inner(&self) -> &Arc<dyn SaStorage> {
+        &self.inner
+    }
+
+    fn key(&self, key: &str) -> String {
+        scoped(key)
+    }
+
+    fn keys_of(&self, keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| self.key(key)).collect()
+    }
+}
+
+#[async_trait]
+impl SaStorage for TenantStorage {
+    async fn get(&self, key: &str) -> StorageResult<Option<String>> {
+        self.inner.get(&self.key(key)).await
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<()> {
+        self.inner.set(&self.key(key), value, ttl).await
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        self.inner.set_nx(&self.key(key), value, ttl).await
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<()> {
+        self.inner.delete(&self.key(key)).await
+    }
+
+    async fn exists(&self, key: &str) -> StorageResult<bool> {
+        self.inner.exists(&self.key(key)).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> StorageResult<()> {
+        self.inner.expire(&self.key(key), ttl).await
+    }
+
+    async fn ttl(&self, key: &str) -> StorageResult<Option<Duration>> {
+        self.inner.ttl(&self.key(key)).await
+    }
+
+    async fn mget(&self, keys: &[&str]) -> StorageResult<Vec<Option<String>>> {
+        let keys = self.keys_of(keys);
+        self.inner.mget(&keys.iter().map(String::as_str).collect::<Vec<_>>()).await
+    }
+
+    async fn mset(&self, items: &[(&str, &str)], ttl: Option<Duration>) -> StorageResult<()> {
+        let keys: Vec<String> = items.iter().map(|(key, _)| self.key(key)).collect();
+        let items: Vec<(&str, &str)> = keys.iter().zip(items).map(|(key, (_, value))| (key.as_str(), *value)).collect();
+        self.inner.mset(&items, ttl).await
+    }
+
+    async fn mdel(&self, keys: &[&str]) -> StorageResult<()> {
+        let keys = self.keys_of(keys);
+        self.inner.mdel(&keys.iter().map(String::as_str).collect::<Vec<_>>()).await
+    }
+
+    async fn incr(&self, key: &str) -> StorageResult<i64> {
+        self.inner.incr(&self.key(key)).await
+    }
+
+    async fn decr(&self, key: &str) -> StorageResult<i64> {
+        self.inner.decr(&self.key(key)).await
+    }
+
+    async fn sadd(&self, key: &str, member: &str) -> StorageResult<()> {
+        self.inner.sadd(&self.key(key), member).await
+    }
+
+    async fn srem(&self, key: &str, member: &str) -> StorageResult<()> {
+        self.inner.srem(&self.key(key), member).await
+    }
+
+    async fn smembers(&self, key: &str) -> StorageResult<Vec<String>> {
+        self.inner.smembers(&self.key(key)).await
+    }
+
+    /// 在租户中只清空该租户的键 | Within a tenant only that tenant's keys are cleared
+    async fn clear(&self) -> StorageResult<()> {
+        if current().is_none() {
+            return self.inner.clear().await;
+        }
+        let keys = self.inner.keys(&format!("{}*", self.key(""))).await?;
+        self.inner.mdel(&keys.iter().map(String::as_str).collect::<Vec<_>>()).await
+    }
+
+    async fn warm_up(&self) -> StorageResult<()> {
+        self.inner.warm_up().await
+    }
+
+    /// 返回的键不带租户前缀；不在租户中时不返回任何租户的键
+    /// Returned keys carry no tenant prefix; outside of a tenant no tenant's keys are returned
+    async fn keys(&self, pattern: &str) -> StorageResult<Vec<String>> {
+        let prefix = self.key("");
+        let keys = self.inner.keys(&format!("{}{}", prefix, pattern)).await?;
+        Ok(keys.into_iter()
+            .filter_map(|key| key.strip_prefix(&prefix).map(str::to_string))
+            .filter(|key| !prefix.is_empty() || !key.starts_with(TENANT_KEY_PREFIX))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SaTokenConfig, SaTokenManager};
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_tenant_isolation() {
+        let resolver = TenantResolver::Subdomain;
+        let host = |value: &'static str| move |name: &str| (name == "Host").then(|| value.to_string());
+        assert_eq!(resolver.resolve(host("acme.example.com:8080")).as_deref(), Some("acme"));
+        assert_eq!(resolver.resolve(host("example.com")), None);
+        assert_eq!(resolver.resolve(host("127.0.0.1:8080")), None);
+        let header = TenantResolver::Header("X-Tenant-Id".to_string());
+        assert_eq!(header.resolve(|_| Some("acme:sa:*".to_string())), None);
+
+        let storage = Arc::new(MemoryStorage::new());
+        let config = SaTokenConfig::builder()
+            .tenant_resolver(header)
+            .tenant_timeout("globex", 600)
+            .build_config();
+        let manager = SaTokenManager::new(storage.clone(), config);
+
+        let acme = scope(Some("acme".to_string()), manager.login("1001")).await.unwrap();
+        let globex = scope(Some("globex".to_string()), manager.login("1001")).await.unwrap();
+
+        // 每个租户只能看到自己的 token | Each tenant only sees its own tokens
+        assert!(scope(Some("acme".to_string()), manager.is_valid(&acme)).await);
+        assert!(!scope(Some("acme".to_string()), manager.is_valid(&globex)).await);
+        assert!(!manager.is_valid(&acme).await);
+        assert!(storage.exists(&format!("tenant:acme:sa:token:{}", acme.as_str())).await.unwrap());
+        assert!(manager.storage.keys("sa:token:*").await.unwrap().is_empty());
+
+        // 按租户覆盖有效期 | Per-tenant timeouts
+        let info = scope(Some("globex".to_string()), manager.get_token_info(&globex)).await.unwrap();
+        let remaining = (info.expire_time.unwrap() - chrono::Utc::now()).num_seconds();
+        assert!(remaining <= 600 && remaining > 590);
+
+        scope(Some("acme".to_string()), manager.logout_by_login_id("1001")).await.unwrap();
+        assert!(scope(Some("globex".to_string()), manager.is_valid(&globex)).await);
+    }
+}
@@ -0,0 +1,172 @@
+// Author: 金书记
+//
+//! SSO 票据存储 | SSO Ticket Store
+//!
+//! `TicketStore` 保存 SSO 服务端签发的票据。`SsoServer` 与 `SsoClient` 默认使用基于 `SaStorage` 的
+//! `StorageTicketStore`，多实例部署时使用 Redis 存储即可共享票据；单实例或测试可使用 `InMemoryTicketStore`。
+//! `TicketStore` keeps the tickets issued by the SSO server. `SsoServer` and `SsoClient` use the
+//! `SaStorage`-backed `StorageTicketStore` by default, so multi-instance servers share tickets through
+//! Redis; single instances and tests can use `InMemoryTicketStore`.
+//!
+//! 票据在 TTL 到期后失效，`consume` 保证每张票据只能被成功消费一次，即使多个实例并发校验。
+//! Tickets lapse when their TTL runs out, and `consume` guarantees a ticket is consumed successfully
+//! only once, even when several instances check it concurrently.
+//!
+//! ```text
+//! sa:sso:ticket:{ticket_id}        票据信息 | Ticket information
+//! sa:sso:ticket:{ticket_id}:used   消费标记 | Consumed marker
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::sso::SsoTicket;
+
+/// SSO 票据存储 | SSO ticket store
+#[async_trait]
+pub trait TicketStore: Send + Sync {
+    /// 保存票据，`ttl` 后过期 | Save a ticket that expires after `ttl`
+    async fn save(&self, ticket: &SsoTicket, ttl: Duration) -> SaTokenResult<()>;
+
+    /// 读取票据，不消费；不存在或 TTL 已过时返回 `None`
+    /// Read a ticket without consuming it; `None` when it does not exist or its TTL has run out
+    async fn get(&self, ticket_id: &str) -> SaTokenResult<Option<SsoTicket>>;
+
+    /// 原子地消费票据，并发调用中只有一个返回 `true`
+    /// Atomically consume a ticket; only one of several concurrent calls returns `true`
+    async fn consume(&self, ticket_id: &str) -> SaTokenResult<bool>;
+
+    /// 清理过期票据，依赖存储 TTL 的实现无需处理
+    /// Remove expired tickets; implementations relying on storage TTLs have nothing to do
+    async fn cleanup_expired(&self) -> SaTokenResult<()> {
+        Ok(())
+    }
+}
+
+/// 基于 `SaStorage` 的票据存储 | `SaStorage`-backed ticket store
+///
+/// 消费标记用 `set_nx` 写入，因此 Redis 等存储上的并发消费也只有一个成功。
+/// The consumed marker is written with `set_nx`, so concurrent consumes on Redis and the like
+/// also succeed only once.
+#[derive(Clone)]
+pub struct StorageTicketStore {
+    storage: Arc<dyn SaStorage>,
+}
+
+impl StorageTicketStore {
+    /// 创建票据存储 | Create a ticket store
+    pub fn new(storage: Arc<dyn SaStorage>) -> Self {
+        Self { storage }
+    }
+
+    fn ticket_key(ticket_id: &str) -> String {
+        format!("sa:sso:ticket:{}", ticket_id)
+    }
+
+    fn used_key(ticket_id: &str) -> String {
+        format!("sa:sso:ticket:{}:used", ticket_id)
+    }
+}
+
+#[async_trait]
+impl TicketStore for StorageTicketStore {
+    async fn save(&self, ticket: &SsoTicket, ttl: Duration) -> SaTokenResult<()> {
+        let value = serde_json::to_string(ticket)?;
+        self.storage.set(&Self::ticket_key(&ticket.ticket_id), &value, Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    async fn get(&self, ticket_id: &str) -> SaTokenResult<Option<SsoTicket>> {
+        let value = self.storage.get(&Self::ticket_key(ticket_id)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+    }
+
+    async fn consume(&self, ticket_id: &str) -> SaTokenResult<bool> {
+        let Some(ticket) = self.get(ticket_id).await?.filter(|t| !t.is_expired()) else {
+            return Ok(false);
+        };
+        self.storage.set_nx(&Self::used_key(ticket_id), "1", Some(ticket.remaining())).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+}
+
+/// 内存票据存储 | In-memory ticket store
+///
+/// 票据只在当前进程内可见，适合单实例部署与测试。
+/// Tickets are only visible to the current process; suited to single instances and tests.
+#[derive(Clone, Default)]
+pub struct InMemoryTicketStore {
+    tickets: Arc<Mutex<HashMap<String, SsoTicket>>>,
+}
+
+impl InMemoryTicketStore {
+    /// 创建内存票据存储 | Create an in-memory ticket store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TicketStore for InMemoryTicketStore {
+    async fn save(&self, ticket: &SsoTicket, ttl: Duration) -> SaTokenResult<()> {
+        let mut ticket = ticket.clone();
+        ticket.expire_time = ticket.expire_time.min(chrono::Utc::now() + ttl);
+        let mut tickets = self.tickets.lock().await;
+        tickets.retain(|_, t| !t.is_expired());
+        tickets.insert(ticket.ticket_id.clone(), ticket);
+        Ok(())
+    }
+
+    async fn get(&self, ticket_id: &str) -> SaTokenResult<Option<SsoTicket>> {
+        Ok(self.tickets.lock().await.get(ticket_id).filter(|t| !t.is_expired()).cloned())
+    }
+
+    async fn consume(&self, ticket_id: &str) -> SaTokenResult<bool> {
+        let mut tickets = self.tickets.lock().await;
+        match tickets.get_mut(ticket_id) {
+            Some(ticket) if ticket.is_valid() => {
+                ticket.used = true;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn cleanup_expired(&self) -> SaTokenResult<()> {
+        self.tickets.lock().await.retain(|_, t| !t.is_expired());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    async fn check_store(store: Arc<dyn TicketStore>) {
+        let ticket = SsoTicket::new("alice".to_string(), "http://app/sso/login".to_string(), 60);
+        store.save(&ticket, Duration::from_secs(60)).await.unwrap();
+        assert_eq!(store.get(&ticket.ticket_id).await.unwrap().unwrap().login_id, "alice");
+
+        // 并发消费只有一个成功 | Only one concurrent consume succeeds
+        let results = futures_util::future::join_all((0..8).map(|_| store.consume(&ticket.ticket_id))).await;
+        assert_eq!(results.into_iter().filter(|r| *r.as_ref().unwrap()).count(), 1);
+        assert!(!store.consume("missing").await.unwrap());
+
+        let expired = SsoTicket::new("bob".to_string(), "http://app/sso/login".to_string(), -1);
+        store.save(&expired, Duration::from_secs(60)).await.unwrap();
+        assert!(!store.consume(&expired.ticket_id).await.unwrap());
+        store.cleanup_expired().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ticket_stores() {
+        check_store(Arc::new(StorageTicketStore::new(Arc::new(MemoryStorage::new())))).await;
+        check_store(Arc::new(InMemoryTicketStore::new())).await;
+    }
+}
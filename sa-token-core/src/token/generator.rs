@@ -2,19 +2,65 @@
 //
 //! Token Generator | Token 生成器
 //!
-//! Supports multiple token styles including UUID, Random, and JWT
-//! 支持多种 Token 风格，包括 UUID、随机字符串和 JWT
+//! Supports multiple token styles including UUID, Random, and JWT,
+//! plus custom generators through `TokenStyle::Custom`
+//! 支持多种 Token 风格，包括 UUID、随机字符串和 JWT，
+//! 以及通过 `TokenStyle::Custom` 接入的自定义生成器
 
+use std::fmt;
 use uuid::Uuid;
-use crate::config::{TokenStyle, SaTokenConfig};
+use crate::config::{JwtMode, TokenStyle, SaTokenConfig};
 use crate::token::TokenValue;
-use crate::token::jwt::{JwtManager, JwtClaims, JwtAlgorithm};
+use crate::token::jwt::{JwtManager, JwtClaims};
 use chrono::Utc;
 use sha2::{Sha256, Digest};
 
-pub struct TokenGenerator;
+/// Custom token generator | 自定义 Token 生成器
+///
+/// Used through `TokenStyle::Custom`, e.g. for tokens with an organisation prefix and checksum.
+/// 通过 `TokenStyle::Custom` 使用，例如生成带组织前缀和校验位的 token。
+///
+/// # Example | 示例
+/// ```
+/// use std::sync::Arc;
+/// use sa_token_core::config::{SaTokenConfig, TokenStyle};
+/// use sa_token_core::token::{TokenGenerator, DefaultTokenGenerator};
+///
+/// struct AcmeTokenGenerator;
+///
+/// impl TokenGenerator for AcmeTokenGenerator {
+///     fn generate(&self, login_id: &str, device: Option<&str>) -> String {
+///         format!("acme_{}_{}", device.unwrap_or("web"), login_id)
+///     }
+/// }
+///
+/// let config = SaTokenConfig {
+///     token_style: TokenStyle::Custom(Arc::new(AcmeTokenGenerator)),
+///     ..Default::default()
+/// };
+/// let token = DefaultTokenGenerator::generate_with_device(&config, "42", Some("ios"));
+/// assert_eq!(token.as_str(), "acme_ios_42");
+/// ```
+pub trait TokenGenerator: Send + Sync {
+    /// Generate a token value | 生成 token 值
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `login_id` - User login ID | 用户登录ID
+    /// * `device` - Login device, if any | 登录设备（可选）
+    fn generate(&self, login_id: &str, device: Option<&str>) -> String;
+}
+
+impl fmt::Debug for dyn TokenGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenGenerator")
+    }
+}
+
+/// Built-in token generator driven by `TokenStyle` | 由 `TokenStyle` 驱动的内置 Token 生成器
+pub struct DefaultTokenGenerator;
 
-impl TokenGenerator {
+impl DefaultTokenGenerator {
     /// Generate token based on configuration | 根据配置生成 token
     ///
     /// # Arguments | 参数
@@ -22,7 +68,22 @@ impl TokenGenerator {
     /// * `config` - Sa-token configuration | Sa-token 配置
     /// * `login_id` - User login ID (required for JWT) | 用户登录ID（JWT 必需）
     pub fn generate_with_login_id(config: &SaTokenConfig, login_id: &str) -> TokenValue {
-        match config.token_style {
+        Self::generate_with_device(config, login_id, None)
+    }
+
+    /// Generate token based on configuration and login device | 根据配置和登录设备生成 token
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `config` - Sa-token configuration | Sa-token 配置
+    /// * `login_id` - User login ID (required for JWT) | 用户登录ID（JWT 必需）
+    /// * `device` - Login device, passed to custom generators | 登录设备，传给自定义生成器
+    pub fn generate_with_device(config: &SaTokenConfig, login_id: &str, device: Option<&str>) -> TokenValue {
+        // Simple JWT 模式总是签发 JWT | The Simple JWT mode always issues JWTs
+        if config.jwt_mode == Some(JwtMode::Simple) {
+            return Self::generate_jwt(config, login_id);
+        }
+        match &config.token_style {
             TokenStyle::Uuid => Self::generate_uuid(),
             TokenStyle::SimpleUuid => Self::generate_simple_uuid(),
             TokenStyle::Random32 => Self::generate_random(32),
@@ -32,6 +93,7 @@ impl TokenGenerator {
             TokenStyle::Hash => Self::generate_hash(login_id),
             TokenStyle::Timestamp => Self::generate_timestamp(),
             TokenStyle::Tik => Self::generate_tik(),
+            TokenStyle::Custom(generator) => TokenValue::new(generator.generate(login_id, device)),
         }
     }
     
@@ -74,36 +136,25 @@ impl TokenGenerator {
             login_id.to_string()
         };
         
-        // Get JWT secret key | 获取 JWT 密钥
-        let secret = config.jwt_secret_key.as_ref()
-            .expect("JWT secret key is required when using JWT token style");
-        
-        // Parse algorithm | 解析算法
-        let algorithm = config.jwt_algorithm.as_ref()
-            .and_then(|alg| Self::parse_jwt_algorithm(alg))
-            .unwrap_or(JwtAlgorithm::HS256);
-        
-        // Create JWT manager | 创建 JWT 管理器
-        let mut jwt_manager = JwtManager::with_algorithm(secret, algorithm);
-        
-        if let Some(ref issuer) = config.jwt_issuer {
-            jwt_manager = jwt_manager.set_issuer(issuer);
-        }
-        
-        if let Some(ref audience) = config.jwt_audience {
-            jwt_manager = jwt_manager.set_audience(audience);
-        }
-        
         // Create claims | 创建声明
         let mut claims = JwtClaims::new(effective_login_id);
         
         // Set expiration | 设置过期时间
-        if config.timeout > 0 {
-            claims.set_expiration(config.timeout);
+        let timeout = config.effective_timeout();
+        if timeout > 0 {
+            claims.set_expiration(timeout);
         }
         
-        // Generate JWT token | 生成 JWT token
-        match jwt_manager.generate(&claims) {
+        // Generate JWT token, preferring the rotating key ring (secret for HMAC, PEM keys for RSA / ECDSA)
+        // 生成 JWT token，优先使用可轮换的密钥环（HMAC 用密钥，RSA / ECDSA 用 PEM 密钥）
+        let generated = match &config.jwt_key_ring {
+            Some(key_ring) => key_ring.generate(&claims),
+            None => match JwtManager::from_config(config) {
+                Ok(jwt_manager) => jwt_manager.generate(&claims),
+                Err(e) => panic!("Invalid JWT configuration for JWT token style: {}", e),
+            },
+        };
+        match generated {
             Ok(token) => TokenValue::new(token),
             Err(e) => {
                 eprintln!("Failed to generate JWT token: {:?}", e);
@@ -113,21 +164,6 @@ impl TokenGenerator {
         }
     }
     
-    /// Parse JWT algorithm from string | 从字符串解析 JWT 算法
-    fn parse_jwt_algorithm(alg: &str) -> Option<JwtAlgorithm> {
-        match alg.to_uppercase().as_str() {
-            "HS256" => Some(JwtAlgorithm::HS256),
-            "HS384" => Some(JwtAlgorithm::HS384),
-            "HS512" => Some(JwtAlgorithm::HS512),
-            "RS256" => Some(JwtAlgorithm::RS256),
-            "RS384" => Some(JwtAlgorithm::RS384),
-            "RS512" => Some(JwtAlgorithm::RS512),
-            "ES256" => Some(JwtAlgorithm::ES256),
-            "ES384" => Some(JwtAlgorithm::ES384),
-            _ => None,
-        }
-    }
-    
     /// Generate Hash style token | 生成 Hash 风格 token
     ///
     /// Uses SHA256 hash of login_id + timestamp + random UUID
@@ -0,0 +1,120 @@
+// Author: 金书记
+//
+//! ECDH-ES+A256KW 密钥协商（P-256，RFC 7518 4.6，基于 `p256` crate）
+//! ECDH-ES+A256KW key agreement (P-256, RFC 7518 4.6, backed by the `p256` crate)
+
+use p256::{EncodedPoint, PublicKey, SecretKey};
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p256::ecdh::EphemeralSecret;
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+
+/// 坐标字节长度 | Coordinate length in bytes
+pub(super) const COORDINATE_LEN: usize = 32;
+
+/// P-256 密钥，私钥可缺省 | A P-256 key, the private half optional
+#[derive(Clone)]
+pub(super) struct EcKey {
+    public: PublicKey,
+    private: Option<SecretKey>,
+}
+
+impl EcKey {
+    /// 解析 PKCS#8 / SEC1 私钥或 SPKI 公钥，只接受 P-256
+    /// Parse a PKCS#8 / SEC1 private key or an SPKI public key, P-256 only
+    pub(super) fn from_pem(pem: &str) -> Option<Self> {
+        match SecretKey::from_pkcs8_pem(pem).or_else(|_| SecretKey::from_sec1_pem(pem)) {
+            Ok(private) => Some(Self { public: private.public_key(), private: Some(private) }),
+            Err(_) => Some(Self { public: PublicKey::from_public_key_pem(pem).ok()?, private: None }),
+        }
+    }
+
+    /// 是否持有私钥 | Whether the private key is present
+    pub(super) fn has_private(&self) -> bool {
+        self.private.is_some()
+    }
+
+    /// 发送方：生成临时密钥并与本公钥协商，返回（临时公钥的 x、y, Z）
+    /// Sender side: generate an ephemeral key and agree with this public key, returning (ephemeral x, y, Z)
+    pub(super) fn agree_ephemeral(&self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let ephemeral = EphemeralSecret::random(&mut OsRng);
+        let point = ephemeral.public_key().to_encoded_point(false);
+        let z = ephemeral.diffie_hellman(&self.public).raw_secret_bytes().to_vec();
+        let coordinate = |c: Option<&p256::FieldBytes>| c.map(|c| c.to_vec()).unwrap_or_default();
+        (coordinate(point.x()), coordinate(point.y()), z)
+    }
+
+    /// 接收方：与发送方的临时公钥协商共享密钥 Z，点不在曲线上时返回 None
+    /// Recipient side: agree on the shared secret Z with the sender's ephemeral key, None for points off the curve
+    pub(super) fn agree(&self, x: &[u8], y: &[u8]) -> Option<Vec<u8>> {
+        let private = self.private.as_ref()?;
+        if x.len() != COORDINATE_LEN || y.len() != COORDINATE_LEN {
+            return None;
+        }
+        let point = EncodedPoint::from_affine_coordinates(x.into(), y.into(), false);
+        let peer = Option::<PublicKey>::from(PublicKey::from_encoded_point(&point))?;
+        let z = p256::ecdh::diffie_hellman(private.to_nonzero_scalar(), peer.as_affine());
+        Some(z.raw_secret_bytes().to_vec())
+    }
+}
+
+/// Concat KDF（NIST SP 800-56A，RFC 7518 4.6.2）| Concat KDF (NIST SP 800-56A, RFC 7518 4.6.2)
+///
+/// # 参数 | Parameters
+/// * `z` - 共享密钥 | Shared secret
+/// * `algorithm_id` - `alg`（密钥包装）或 `enc`（直接协商）| `alg` (key wrapping) or `enc` (direct agreement)
+/// * `apu` / `apv` - 双方信息 | Party U / V info
+/// * `key_bits` - 派生密钥的位数 | Derived key length in bits
+pub(super) fn concat_kdf(z: &[u8], algorithm_id: &str, apu: &[u8], apv: &[u8], key_bits: usize) -> Vec<u8> {
+    let length_prefixed = |hasher: Sha256, data: &[u8]| hasher.chain_update((data.len() as u32).to_be_bytes()).chain_update(data);
+    let mut key = Vec::with_capacity(key_bits / 8 + 32);
+    let mut counter = 1u32;
+    while key.len() < key_bits / 8 {
+        let hasher = Sha256::new().chain_update(counter.to_be_bytes()).chain_update(z);
+        let hasher = length_prefixed(length_prefixed(length_prefixed(hasher, algorithm_id.as_bytes()), apu), apv);
+        key.extend_from_slice(&hasher.chain_update((key_bits as u32).to_be_bytes()).finalize());
+        counter += 1;
+    }
+    key.truncate(key_bits / 8);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::tests::{EC_PRIVATE_PEM, EC_PRIVATE_SEC1_PEM, EC_PUBLIC_PEM};
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+    #[test]
+    fn test_concat_kdf() {
+        // RFC 7518 附录 C | RFC 7518 Appendix C
+        let z = [
+            158, 86, 217, 29, 129, 113, 53, 211, 114, 131, 66, 131, 191, 132, 38, 156,
+            251, 49, 110, 163, 218, 128, 106, 72, 246, 218, 167, 121, 140, 254, 144, 196,
+        ];
+        let key = concat_kdf(&z, "A128GCM", b"Alice", b"Bob", 128);
+        assert_eq!(URL_SAFE_NO_PAD.encode(key), "VqqN6vgjbSBcIijNcacQGg");
+        assert_eq!(concat_kdf(&z, "ECDH-ES+A256KW", b"", b"", 256).len(), 32);
+    }
+
+    #[test]
+    fn test_p256_agreement() {
+        let private = EcKey::from_pem(EC_PRIVATE_PEM).unwrap();
+        let sec1 = EcKey::from_pem(EC_PRIVATE_SEC1_PEM).unwrap();
+        let public = EcKey::from_pem(EC_PUBLIC_PEM).unwrap();
+        assert_eq!(private.public, public.public);
+        assert_eq!(sec1.public, public.public);
+        assert!(private.has_private() && !public.has_private());
+
+        let (x, y, z) = public.agree_ephemeral();
+        assert_eq!(private.agree(&x, &y), Some(z));
+        assert_eq!(public.agree(&x, &y), None);
+
+        // 不在曲线上的点被拒绝 | Points off the curve are rejected
+        let mut bad_y = y.clone();
+        bad_y[31] ^= 1;
+        assert_eq!(private.agree(&x, &bad_y), None);
+    }
+}
@@ -0,0 +1,543 @@
+// Author: 金书记
+//
+//! JWE 加密 JWT | JWE (Encrypted JWT)
+//!
+//! 签名只能防篡改，声明仍可被任何人读取。为 `JwtManager` 配置 `JweEncryption` 后，签名后的 JWT
+//! 再被加密为 JWE 紧凑格式（嵌套 JWT，`cty: "JWT"`），声明因此对客户端与中间方保密。
+//! A signature only prevents tampering, anyone can still read the claims. Once `JwtManager` has a
+//! `JweEncryption`, the signed JWT is additionally encrypted into the JWE compact form (a nested JWT,
+//! `cty: "JWT"`), so the claims stay confidential to clients and intermediaries.
+//!
+//! ```text
+//! header.claims.signature                       ← JWS（签名 | signed）
+//!   └─→ A256GCM(CEK) → header.encrypted_key.iv.ciphertext.tag   ← JWE（加密 | encrypted）
+//!
+//! dir             CEK = 共享密钥，encrypted_key 为空 | the shared key, empty encrypted_key
+//! A256KW          CEK = 随机生成，用共享密钥按 RFC 3394 包装 | random, wrapped with the shared key (RFC 3394)
+//! RSA-OAEP-256    CEK = 随机生成，用接收方 RSA 公钥加密 | random, encrypted to the recipient's RSA key
+//! ECDH-ES+A256KW  CEK = 随机生成，用临时 P-256 协商出的密钥包装 | random, wrapped with a key agreed via an ephemeral P-256 key
+//! ```
+//!
+//! 内容加密固定为 A256GCM，受保护头作为附加认证数据。非对称算法用 `JweEncryption::from_pem` 创建：
+//! 公钥只能加密，私钥可加密也可解密，签发方因此无需持有解密能力。
+//! Content encryption is always A256GCM, with the protected header as additional authenticated data.
+//! Asymmetric algorithms are created with `JweEncryption::from_pem`: a public key can only encrypt,
+//! a private key can encrypt and decrypt, so an issuer does not need to be able to decrypt.
+
+mod ecdh;
+mod rsa;
+
+use std::fmt;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::aes::Aes256;
+use aes_gcm::aes::cipher::{Block, BlockDecrypt, BlockEncrypt};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use crate::error::{SaTokenError, SaTokenResult};
+use self::ecdh::EcKey;
+use self::rsa::RsaKey;
+
+/// 内容加密算法 | Content encryption algorithm
+const CONTENT_ENCRYPTION: &str = "A256GCM";
+
+/// AES-GCM IV 长度 | AES-GCM IV length
+const IV_LEN: usize = 12;
+
+/// AES-GCM 认证标签长度 | AES-GCM authentication tag length
+const TAG_LEN: usize = 16;
+
+/// RFC 3394 默认初始值 | RFC 3394 default initial value
+const KW_IV: [u8; 8] = [0xA6; 8];
+
+/// JWE 密钥管理算法 | JWE key management algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum JweAlgorithm {
+    /// 直接使用共享密钥作为内容加密密钥 | Use the shared key directly as the content encryption key
+    #[default]
+    #[serde(rename = "dir")]
+    Dir,
+    /// 每个 token 随机生成内容加密密钥，用 AES-256 Key Wrap 包装
+    /// A random content encryption key per token, wrapped with AES-256 Key Wrap
+    A256KW,
+    /// 每个 token 随机生成内容加密密钥，用 RSAES-OAEP（SHA-256）加密
+    /// A random content encryption key per token, encrypted with RSAES-OAEP (SHA-256)
+    #[serde(rename = "RSA-OAEP-256")]
+    RsaOaep256,
+    /// 每个 token 随机生成内容加密密钥，用临时 P-256 密钥协商出的密钥包装
+    /// A random content encryption key per token, wrapped with a key agreed via an ephemeral P-256 key
+    #[serde(rename = "ECDH-ES+A256KW")]
+    EcdhEsA256KW,
+}
+
+impl JweAlgorithm {
+    /// `alg` 头的取值 | Value of the `alg` header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dir => "dir",
+            Self::A256KW => "A256KW",
+            Self::RsaOaep256 => "RSA-OAEP-256",
+            Self::EcdhEsA256KW => "ECDH-ES+A256KW",
+        }
+    }
+
+    /// 从 `alg` 头的取值解析 | Parse from the value of the `alg` header
+    pub fn parse(alg: &str) -> Option<Self> {
+        match alg {
+            "dir" => Some(Self::Dir),
+            "A256KW" => Some(Self::A256KW),
+            "RSA-OAEP-256" => Some(Self::RsaOaep256),
+            "ECDH-ES+A256KW" => Some(Self::EcdhEsA256KW),
+            _ => None,
+        }
+    }
+
+    /// 是否使用共享密钥 | Whether the algorithm uses a shared key
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, Self::Dir | Self::A256KW)
+    }
+}
+
+/// JWE 受保护头 | JWE protected header
+#[derive(Serialize, Deserialize)]
+struct JweHeader {
+    alg: String,
+    enc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    epk: Option<EphemeralKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    apu: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    apv: Option<String>,
+}
+
+/// ECDH-ES 发送方的临时公钥（EC JWK）| The ECDH-ES sender's ephemeral public key (EC JWK)
+#[derive(Serialize, Deserialize)]
+struct EphemeralKey {
+    kty: String,
+    crv: String,
+    x: String,
+    y: String,
+}
+
+/// 加密器持有的密钥 | Key held by the encrypter
+#[derive(Clone)]
+enum KeyMaterial {
+    Symmetric([u8; 32]),
+    Rsa(Box<RsaKey>),
+    Ec(EcKey),
+}
+
+/// JWE 加密器，持有 256 位共享密钥或接收方的 RSA / P-256 密钥
+/// JWE encrypter holding a 256-bit shared key or the recipient's RSA / P-256 key
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let key = JweEncryption::generate_key();
+/// let manager = JwtManager::new("secret").set_encryption(JweEncryption::new(JweAlgorithm::A256KW, &key));
+/// let token = manager.generate(&claims)?;   // 5 段的 JWE | a five-part JWE
+/// let claims = manager.validate(&token)?;
+///
+/// // 签发方只持有公钥 | The issuer only holds the public key
+/// let issuer = JwtManager::new("secret").set_encryption(JweEncryption::from_pem(JweAlgorithm::RsaOaep256, public_pem)?);
+/// ```
+#[derive(Clone)]
+pub struct JweEncryption {
+    algorithm: JweAlgorithm,
+    key: KeyMaterial,
+}
+
+impl fmt::Debug for JweEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JweEncryption").field("algorithm", &self.algorithm).finish_non_exhaustive()
+    }
+}
+
+impl JweEncryption {
+    /// 创建 JWE 加密器 | Create a JWE encrypter
+    ///
+    /// # 参数 | Parameters
+    /// * `algorithm` - 对称的密钥管理算法 | Symmetric key management algorithm
+    /// * `key` - 256 位共享密钥 | 256-bit shared key
+    pub fn new(algorithm: JweAlgorithm, key: &[u8; 32]) -> Self {
+        Self { algorithm, key: KeyMaterial::Symmetric(*key) }
+    }
+
+    /// 从 PEM 密钥创建非对称的 JWE 加密器 | Create an asymmetric JWE encrypter from a PEM key
+    ///
+    /// `RSA-OAEP-256` 接受 2048 位以上的 RSA 密钥（SPKI、PKCS#1 或 PKCS#8），`ECDH-ES+A256KW` 接受
+    /// P-256 密钥（SPKI、SEC1 或 PKCS#8）。公钥只能加密，私钥可加密也可解密。
+    /// `RSA-OAEP-256` takes an RSA key of at least 2048 bits (SPKI, PKCS#1 or PKCS#8), `ECDH-ES+A256KW`
+    /// takes a P-256 key (SPKI, SEC1 or PKCS#8). A public key can only encrypt, a private key can encrypt and decrypt.
+    pub fn from_pem(algorithm: JweAlgorithm, pem: &str) -> SaTokenResult<Self> {
+        let key = match algorithm {
+            JweAlgorithm::RsaOaep256 => KeyMaterial::Rsa(Box::new(RsaKey::from_pem(pem).ok_or_else(|| {
+                SaTokenError::ConfigError("RSA-OAEP-256 requires an RSA key of at least 2048 bits".to_string())
+            })?)),
+            JweAlgorithm::EcdhEsA256KW => KeyMaterial::Ec(EcKey::from_pem(pem).ok_or_else(|| {
+                SaTokenError::ConfigError("ECDH-ES+A256KW requires a P-256 key".to_string())
+            })?),
+            JweAlgorithm::Dir | JweAlgorithm::A256KW => {
+                return Err(SaTokenError::ConfigError(format!("{} uses a shared key, not a PEM key", algorithm.as_str())));
+            }
+        };
+        Ok(Self { algorithm, key })
+    }
+
+    /// 从 Base64 编码的密钥创建（`jwt_encryption_key` 配置使用）
+    /// Create from a Base64-encoded key (used by the `jwt_encryption_key` config)
+    pub fn from_base64(algorithm: JweAlgorithm, key: &str) -> SaTokenResult<Self> {
+        let invalid = || SaTokenError::ConfigError("JWE key must be 32 bytes encoded as Base64".to_string());
+        let key = STANDARD.decode(key.trim()).or_else(|_| URL_SAFE_NO_PAD.decode(key.trim())).map_err(|_| invalid())?;
+        let key: [u8; 32] = key.try_into().map_err(|_| invalid())?;
+        Ok(Self::new(algorithm, &key))
+    }
+
+    /// 生成随机的 256 位密钥 | Generate a random 256-bit key
+    pub fn generate_key() -> [u8; 32] {
+        let mut key = [0u8; 32];
+        getrandom::fill(&mut key).expect("system random number generator is unavailable");
+        key
+    }
+
+    /// 密钥管理算法 | Key management algorithm
+    pub fn algorithm(&self) -> JweAlgorithm {
+        self.algorithm
+    }
+
+    /// 是否为 JWE 紧凑格式（5 段）| Whether a token is in the JWE compact form (five parts)
+    pub fn is_jwe(token: &str) -> bool {
+        token.split('.').count() == 5
+    }
+
+    /// 将签名后的 JWT 加密为 JWE | Encrypt a signed JWT into a JWE
+    ///
+    /// # 参数 | Parameters
+    /// * `jwt` - 签名后的 JWT | The signed JWT
+    /// * `kid` - 写入 `kid` 头的密钥 ID | Key ID written to the `kid` header
+    pub fn encrypt(&self, jwt: &str, kid: Option<&str>) -> SaTokenResult<String> {
+        let mut header = JweHeader {
+            alg: self.algorithm.as_str().to_string(),
+            enc: CONTENT_ENCRYPTION.to_string(),
+            cty: Some("JWT".to_string()),
+            kid: kid.map(str::to_string),
+            epk: None,
+            apu: None,
+            apv: None,
+        };
+
+        let failed = || SaTokenError::InternalError("Failed to encrypt JWE key".to_string());
+        let (cek, encrypted_key) = match (&self.key, self.algorithm) {
+            (KeyMaterial::Symmetric(key), JweAlgorithm::Dir) => (*key, Vec::new()),
+            (KeyMaterial::Symmetric(key), JweAlgorithm::A256KW) => {
+                let cek = Self::generate_key();
+                (cek, aes_key_wrap(key, &cek))
+            }
+            (KeyMaterial::Rsa(key), JweAlgorithm::RsaOaep256) => {
+                let cek = Self::generate_key();
+                (cek, key.encrypt(&cek).ok_or_else(failed)?)
+            }
+            (KeyMaterial::Ec(key), JweAlgorithm::EcdhEsA256KW) => {
+                let (x, y, z) = key.agree_ephemeral();
+                header.epk = Some(EphemeralKey {
+                    kty: "EC".to_string(),
+                    crv: "P-256".to_string(),
+                    x: URL_SAFE_NO_PAD.encode(x),
+                    y: URL_SAFE_NO_PAD.encode(y),
+                });
+                let cek = Self::generate_key();
+                (cek, aes_key_wrap(&ecdh_kek(&z, b"", b""), &cek))
+            }
+            _ => return Err(self.mismatch()),
+        };
+        let header = serde_json::to_vec(&header)?;
+        let header = URL_SAFE_NO_PAD.encode(header);
+
+        let mut iv = [0u8; IV_LEN];
+        getrandom::fill(&mut iv).map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        let mut ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&cek))
+            .encrypt(Nonce::from_slice(&iv), Payload { msg: jwt.as_bytes(), aad: header.as_bytes() })
+            .map_err(|_| SaTokenError::InternalError("Failed to encrypt JWE".to_string()))?;
+        let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+
+        Ok(format!(
+            "{}.{}.{}.{}.{}",
+            header,
+            URL_SAFE_NO_PAD.encode(encrypted_key),
+            URL_SAFE_NO_PAD.encode(iv),
+            URL_SAFE_NO_PAD.encode(ciphertext),
+            URL_SAFE_NO_PAD.encode(tag),
+        ))
+    }
+
+    /// 解密 JWE，返回内部签名的 JWT | Decrypt a JWE and return the signed JWT inside
+    ///
+    /// 任何格式、算法或认证错误都返回 `InvalidToken`；只持有公钥时返回 `ConfigError`。
+    /// Any format, algorithm or authentication error returns `InvalidToken`; holding only a public key
+    /// returns `ConfigError`.
+    pub fn decrypt(&self, token: &str) -> SaTokenResult<String> {
+        let invalid = |reason: &str| SaTokenError::InvalidToken(format!("JWE decryption failed: {}", reason));
+        let parts: Vec<&str> = token.split('.').collect();
+        let [header_b64, encrypted_key, iv, ciphertext, tag] = parts[..] else {
+            return Err(invalid("not a JWE compact token"));
+        };
+        let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|_| invalid("invalid Base64"));
+
+        let header: JweHeader = serde_json::from_slice(&decode(header_b64)?)
+            .map_err(|_| invalid("invalid header"))?;
+        if JweAlgorithm::parse(&header.alg) != Some(self.algorithm) || header.enc != CONTENT_ENCRYPTION {
+            return Err(invalid("unexpected algorithm"));
+        }
+
+        let encrypted_key = decode(encrypted_key)?;
+        let public_only = || SaTokenError::ConfigError("JWE decryption requires a private key".to_string());
+        let cek: [u8; 32] = match (&self.key, self.algorithm) {
+            (KeyMaterial::Symmetric(key), JweAlgorithm::Dir) if encrypted_key.is_empty() => *key,
+            (KeyMaterial::Symmetric(_), JweAlgorithm::Dir) => return Err(invalid("unexpected encrypted key")),
+            (KeyMaterial::Symmetric(key), JweAlgorithm::A256KW) => aes_key_unwrap(key, &encrypted_key)
+                .and_then(|cek| cek.try_into().ok())
+                .ok_or_else(|| invalid("key unwrap failed"))?,
+            // 密钥解密失败时改用随机 CEK，错误与认证失败无法区分（RFC 7516 11.5）
+            // A failed key decryption falls back to a random CEK, indistinguishable from an authentication failure (RFC 7516 11.5)
+            (KeyMaterial::Rsa(key), JweAlgorithm::RsaOaep256) if !key.has_private() => return Err(public_only()),
+            (KeyMaterial::Rsa(key), JweAlgorithm::RsaOaep256) => key
+                .decrypt(&encrypted_key)
+                .and_then(|cek| cek.try_into().ok())
+                .unwrap_or_else(Self::generate_key),
+            (KeyMaterial::Ec(key), JweAlgorithm::EcdhEsA256KW) => {
+                if !key.has_private() {
+                    return Err(public_only());
+                }
+                let epk = header.epk.as_ref()
+                    .filter(|epk| epk.kty == "EC" && epk.crv == "P-256")
+                    .ok_or_else(|| invalid("missing or unsupported epk"))?;
+                let z = key.agree(&decode(&epk.x)?, &decode(&epk.y)?).ok_or_else(|| invalid("invalid epk"))?;
+                let apu = header.apu.as_deref().map(decode).transpose()?.unwrap_or_default();
+                let apv = header.apv.as_deref().map(decode).transpose()?.unwrap_or_default();
+                aes_key_unwrap(&ecdh_kek(&z, &apu, &apv), &encrypted_key)
+                    .and_then(|cek| cek.try_into().ok())
+                    .ok_or_else(|| invalid("key unwrap failed"))?
+            }
+            _ => return Err(self.mismatch()),
+        };
+
+        let iv = decode(iv)?;
+        if iv.len() != IV_LEN {
+            return Err(invalid("invalid IV"));
+        }
+        let mut data = decode(ciphertext)?;
+        data.extend_from_slice(&decode(tag)?);
+        let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&cek))
+            .decrypt(Nonce::from_slice(&iv), Payload { msg: &data, aad: header_b64.as_bytes() })
+            .map_err(|_| invalid("authentication failed"))?;
+        String::from_utf8(plaintext).map_err(|_| invalid("payload is not UTF-8"))
+    }
+
+    /// 密钥类型与算法不符（用 `new` 创建了非对称算法）| Key type does not match the algorithm (`new` used with an asymmetric algorithm)
+    fn mismatch(&self) -> SaTokenError {
+        SaTokenError::ConfigError(format!("{} requires a key created with JweEncryption::from_pem", self.algorithm.as_str()))
+    }
+}
+
+/// 由 ECDH 共享密钥派生 A256KW 的 KEK | Derive the A256KW KEK from the ECDH shared secret
+fn ecdh_kek(z: &[u8], apu: &[u8], apv: &[u8]) -> [u8; 32] {
+    let kek = ecdh::concat_kdf(z, JweAlgorithm::EcdhEsA256KW.as_str(), apu, apv, 256);
+    kek.try_into().expect("Concat KDF returns the requested length")
+}
+
+/// AES Key Wrap（RFC 3394）| AES Key Wrap (RFC 3394)
+fn aes_key_wrap(kek: &[u8; 32], key: &[u8]) -> Vec<u8> {
+    let cipher = Aes256::new(Key::<Aes256>::from_slice(kek));
+    let n = key.len() / 8;
+    let mut a = KW_IV;
+    let mut r: Vec<[u8; 8]> = key.chunks_exact(8).map(|c| c.try_into().unwrap()).collect();
+    for j in 0..6 {
+        for (i, ri) in r.iter_mut().enumerate() {
+            let mut block = Block::<Aes256>::default();
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(ri);
+            cipher.encrypt_block(&mut block);
+            let t = (n * j + i + 1) as u64;
+            a.copy_from_slice(&block[..8]);
+            for (byte, t) in a.iter_mut().zip(t.to_be_bytes()) {
+                *byte ^= t;
+            }
+            ri.copy_from_slice(&block[8..]);
+        }
+    }
+    let mut wrapped = a.to_vec();
+    r.iter().for_each(|ri| wrapped.extend_from_slice(ri));
+    wrapped
+}
+
+/// AES Key Unwrap（RFC 3394），完整性校验失败返回 None
+/// AES Key Unwrap (RFC 3394), None when the integrity check fails
+fn aes_key_unwrap(kek: &[u8; 32], wrapped: &[u8]) -> Option<Vec<u8>> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return None;
+    }
+    let cipher = Aes256::new(Key::<Aes256>::from_slice(kek));
+    let n = wrapped.len() / 8 - 1;
+    let mut a: [u8; 8] = wrapped[..8].try_into().ok()?;
+    let mut r: Vec<[u8; 8]> = wrapped[8..].chunks_exact(8).map(|c| c.try_into().unwrap()).collect();
+    for j in (0..6).rev() {
+        for i in (0..n).rev() {
+            let t = (n * j + i + 1) as u64;
+            for (byte, t) in a.iter_mut().zip(t.to_be_bytes()) {
+                *byte ^= t;
+            }
+            let mut block = Block::<Aes256>::default();
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(&r[i]);
+            cipher.decrypt_block(&mut block);
+            a.copy_from_slice(&block[..8]);
+            r[i].copy_from_slice(&block[8..]);
+        }
+    }
+    (a == KW_IV).then(|| r.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::jwt::{JwtClaims, JwtManager};
+
+    pub(super) const RSA_PRIVATE_PEM: &str = include_str!("../testdata/jwe_rsa_private.pem");
+    pub(super) const RSA_PRIVATE_PKCS1_PEM: &str = include_str!("../testdata/jwe_rsa_private_pkcs1.pem");
+    pub(super) const RSA_PUBLIC_PEM: &str = include_str!("../testdata/jwe_rsa_public.pem");
+    pub(super) const EC_PRIVATE_PEM: &str = include_str!("../testdata/jwe_ec_private.pem");
+    pub(super) const EC_PRIVATE_SEC1_PEM: &str = include_str!("../testdata/jwe_ec_private_sec1.pem");
+    pub(super) const EC_PUBLIC_PEM: &str = include_str!("../testdata/jwe_ec_public.pem");
+    const OTHER_RSA_PRIVATE_PEM: &str = include_str!("../testdata/rsa_private.pem");
+    const OTHER_EC_PRIVATE_PEM: &str = include_str!("../testdata/ec_private.pem");
+
+    /// 其他实现生成的 JWE，内容为 `INTEROP_JWT` | JWEs produced by another implementation, wrapping `INTEROP_JWT`
+    const RSA_JWE: &str = "eyJhbGciOiJSU0EtT0FFUC0yNTYiLCJlbmMiOiJBMjU2R0NNIiwiY3R5IjoiSldUIn0.hPqHh7IHFrmVzpUpoP_mnlbCnnT3PLuccDKkzzT5vIPeyWDISn2zfi52PPi4eWOj0uDSCF9sauUjc9-iKN9z9XX--4T1KrFEdvFrSLhJNovZ0wK2P_I0F3S4vpo_4H7jTWyaFmZp1SB4zKy7Cp8mctZwWIGglfOOwKJi4H28tfOYDcgHPbamRmTZslwsoZSFRFPfxPNzpaZNAtw8c6D2TOvrgpEHd_3wYEW2crS7oBAYNDnUD_H9FbnXePy-vAukhCdWe63WHVsZKPSa8sPpI4mbIIxEReqnKOBbc06H1cNTpD5RM4uX2jsVZ_ALBsrOQMOMNGIC0NKTuII63KxiwQ.zbI2HgITQitnsTDc.-6QSpvcCGi4zk4hP8Q4niEO4c8vU9CZQLQ_Lxm9uBcIdELyr_PhdEifvkoY9QTE3jOKHJ1I.ACHxU3FKXR2gknZU4YRFtA";
+    const EC_JWE: &str = "eyJhbGciOiJFQ0RILUVTK0EyNTZLVyIsImVuYyI6IkEyNTZHQ00iLCJjdHkiOiJKV1QiLCJlcGsiOnsia3R5IjoiRUMiLCJjcnYiOiJQLTI1NiIsIngiOiJnOTQzdGVsNmdnWDRsdTBiYjduak11OHVuc3BTRHRsSnlYcGI2RG52N3NVIiwieSI6ImVXbkVFbkM3eWRNLUNqQjZFUnppMC1kVkhISGppUVhiYUNhanlTQ2YwRzgifSwiYXB1IjoiUVd4cFkyVSIsImFwdiI6IlFtOWkifQ.8QEMIzl5zZJPRteud0Djnee6tL9nIQtAsKGv6r2g0JMeciHd2T-nZQ.cZa2xUEZ6gN9y3wy.E4cY0Uo3hXP33zEJeqbm5Au9j6YkEd7JBZ_s8hHYgjiuX0m7bcUB1mnfhcY485VBT4qkDTY.w6w9CScIuRyaGv9mmghOHw";
+    const INTEROP_JWT: &str = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiJ1c2VyXzEyMyJ9.interop";
+
+    #[test]
+    fn test_jwe_encrypted_jwt() {
+        // RFC 3394 4.6：256 位 KEK 包装 256 位密钥 | RFC 3394 4.6: wrap 256 bits of key data with a 256-bit KEK
+        let kek: [u8; 32] = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F").unwrap().try_into().unwrap();
+        let key = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = aes_key_wrap(&kek, &key);
+        assert_eq!(hex::encode_upper(&wrapped), "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21");
+        assert_eq!(aes_key_unwrap(&kek, &wrapped), Some(key));
+
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+        claims.add_claim("ssn", serde_json::json!("123-45-6789"));
+
+        let keys = [
+            (JweEncryption::new(JweAlgorithm::Dir, &JweEncryption::generate_key()), JweEncryption::new(JweAlgorithm::Dir, &JweEncryption::generate_key())),
+            (JweEncryption::new(JweAlgorithm::A256KW, &JweEncryption::generate_key()), JweEncryption::new(JweAlgorithm::A256KW, &JweEncryption::generate_key())),
+            (JweEncryption::from_pem(JweAlgorithm::RsaOaep256, RSA_PRIVATE_PEM).unwrap(), other_rsa_key()),
+            (JweEncryption::from_pem(JweAlgorithm::EcdhEsA256KW, EC_PRIVATE_PEM).unwrap(), other_ec_key()),
+        ];
+        for (encryption, other) in keys {
+            let manager = JwtManager::new("test-secret-key").set_encryption(encryption);
+            let token = manager.generate(&claims).unwrap();
+            assert!(JweEncryption::is_jwe(&token));
+
+            // 声明不可读 | The claims cannot be read
+            let payload = token.split('.').nth(3).unwrap();
+            assert!(!String::from_utf8_lossy(&URL_SAFE_NO_PAD.decode(payload).unwrap()).contains("123-45-6789"));
+            assert!(JwtManager::new("test-secret-key").validate(&token).is_err());
+
+            let decoded = manager.validate(&token).unwrap();
+            assert_eq!(decoded.login_id, "user_123");
+            assert_eq!(decoded.get_claim("ssn"), Some(&serde_json::json!("123-45-6789")));
+            assert_eq!(manager.extract_login_id(&token).unwrap(), "user_123");
+
+            // 其他密钥或被篡改的密文无法解密 | Another key or a tampered ciphertext does not decrypt
+            let other = JwtManager::new("test-secret-key").set_encryption(other);
+            assert!(matches!(other.validate(&token), Err(SaTokenError::InvalidToken(_))));
+            let mut parts: Vec<String> = token.split('.').map(str::to_string).collect();
+            parts[3] = URL_SAFE_NO_PAD.encode(b"tampered");
+            assert!(manager.validate(&parts.join(".")).is_err());
+
+            // 未加密的 JWT 被拒绝 | Unencrypted JWTs are rejected
+            let plain = JwtManager::new("test-secret-key").generate(&claims).unwrap();
+            assert!(manager.validate(&plain).is_err());
+        }
+
+        let encoded = STANDARD.encode(JweEncryption::generate_key());
+        assert!(JweEncryption::from_base64(JweAlgorithm::RsaOaep256, &encoded).unwrap().encrypt("jwt", None).is_err());
+        assert!(JweEncryption::from_base64(JweAlgorithm::Dir, &encoded).is_ok());
+        assert!(JweEncryption::from_base64(JweAlgorithm::Dir, "c2hvcnQ=").is_err());
+    }
+
+    #[test]
+    fn test_jwe_asymmetric_keys() {
+        // 其他实现生成的 JWE 可以解密 | JWEs from another implementation decrypt
+        for pem in [RSA_PRIVATE_PEM, RSA_PRIVATE_PKCS1_PEM] {
+            let encryption = JweEncryption::from_pem(JweAlgorithm::RsaOaep256, pem).unwrap();
+            assert_eq!(encryption.decrypt(RSA_JWE).unwrap(), INTEROP_JWT);
+        }
+        for pem in [EC_PRIVATE_PEM, EC_PRIVATE_SEC1_PEM] {
+            let encryption = JweEncryption::from_pem(JweAlgorithm::EcdhEsA256KW, pem).unwrap();
+            assert_eq!(encryption.decrypt(EC_JWE).unwrap(), INTEROP_JWT);
+        }
+
+        // 公钥只能加密 | A public key can only encrypt
+        for (algorithm, public, private) in [
+            (JweAlgorithm::RsaOaep256, RSA_PUBLIC_PEM, RSA_PRIVATE_PEM),
+            (JweAlgorithm::EcdhEsA256KW, EC_PUBLIC_PEM, EC_PRIVATE_PEM),
+        ] {
+            let issuer = JweEncryption::from_pem(algorithm, public).unwrap();
+            let token = issuer.encrypt(INTEROP_JWT, Some("kid-1")).unwrap();
+            assert!(matches!(issuer.decrypt(&token), Err(SaTokenError::ConfigError(_))));
+            let recipient = JweEncryption::from_pem(algorithm, private).unwrap();
+            assert_eq!(recipient.decrypt(&token).unwrap(), INTEROP_JWT);
+            // 另一种算法的 JWE 被拒绝 | A JWE of another algorithm is rejected
+            let foreign = if algorithm == JweAlgorithm::RsaOaep256 { EC_JWE } else { RSA_JWE };
+            assert!(matches!(recipient.decrypt(foreign), Err(SaTokenError::InvalidToken(_))));
+        }
+
+        // 被篡改的加密 CEK 无法解密 | A tampered encrypted CEK does not decrypt
+        for (algorithm, pem, token) in [
+            (JweAlgorithm::RsaOaep256, RSA_PRIVATE_PEM, RSA_JWE),
+            (JweAlgorithm::EcdhEsA256KW, EC_PRIVATE_PEM, EC_JWE),
+        ] {
+            let recipient = JweEncryption::from_pem(algorithm, pem).unwrap();
+            let mut parts: Vec<String> = token.split('.').map(str::to_string).collect();
+            let mut encrypted_key = URL_SAFE_NO_PAD.decode(&parts[1]).unwrap();
+            encrypted_key[7] ^= 1;
+            parts[1] = URL_SAFE_NO_PAD.encode(encrypted_key);
+            assert!(matches!(recipient.decrypt(&parts.join(".")), Err(SaTokenError::InvalidToken(_))));
+        }
+
+        // 不在曲线上的 epk 被拒绝 | An epk off the curve is rejected
+        let recipient = JweEncryption::from_pem(JweAlgorithm::EcdhEsA256KW, EC_PRIVATE_PEM).unwrap();
+        let parts: Vec<&str> = EC_JWE.split('.').collect();
+        let mut header: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[0]).unwrap()).unwrap();
+        let mut y = URL_SAFE_NO_PAD.decode(header["epk"]["y"].as_str().unwrap()).unwrap();
+        y[31] ^= 1;
+        header["epk"]["y"] = serde_json::json!(URL_SAFE_NO_PAD.encode(y));
+        let header = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header).unwrap());
+        let off_curve = [header.as_str(), parts[1], parts[2], parts[3], parts[4]].join(".");
+        assert!(matches!(recipient.decrypt(&off_curve), Err(SaTokenError::InvalidToken(_))));
+
+        // 密钥类型与算法必须匹配 | The key type must match the algorithm
+        assert!(JweEncryption::from_pem(JweAlgorithm::RsaOaep256, EC_PUBLIC_PEM).is_err());
+        assert!(JweEncryption::from_pem(JweAlgorithm::EcdhEsA256KW, RSA_PUBLIC_PEM).is_err());
+        assert!(JweEncryption::from_pem(JweAlgorithm::A256KW, RSA_PUBLIC_PEM).is_err());
+        assert!(JweEncryption::from_pem(JweAlgorithm::RsaOaep256, "not a pem").is_err());
+        assert_eq!(JweAlgorithm::parse("ECDH-ES+A256KW"), Some(JweAlgorithm::EcdhEsA256KW));
+        assert_eq!(JweAlgorithm::RsaOaep256.as_str(), "RSA-OAEP-256");
+    }
+
+    fn other_rsa_key() -> JweEncryption {
+        JweEncryption::from_pem(JweAlgorithm::RsaOaep256, OTHER_RSA_PRIVATE_PEM).unwrap()
+    }
+
+    fn other_ec_key() -> JweEncryption {
+        JweEncryption::from_pem(JweAlgorithm::EcdhEsA256KW, OTHER_EC_PRIVATE_PEM).unwrap()
+    }
+}
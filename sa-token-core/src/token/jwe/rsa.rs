@@ -0,0 +1,78 @@
+// Author: 金书记
+//
+//! RSA-OAEP-256 密钥管理（RFC 8017 RSAES-OAEP，SHA-256 与 MGF1-SHA-256，基于 `rsa` crate）
+//! RSA-OAEP-256 key management (RFC 8017 RSAES-OAEP with SHA-256 and MGF1-SHA-256, backed by the `rsa` crate)
+
+use rand_core::OsRng;
+use rsa::{Oaep, RsaPrivateKey, RsaPublicKey};
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::traits::PublicKeyParts;
+use sha2::Sha256;
+
+/// 接受的最小模数位数 | Smallest accepted modulus size in bits
+const MIN_MODULUS_BITS: usize = 2048;
+
+/// RSA 密钥，私钥可缺省 | An RSA key, the private half optional
+#[derive(Clone)]
+pub(super) struct RsaKey {
+    public: RsaPublicKey,
+    private: Option<RsaPrivateKey>,
+}
+
+impl RsaKey {
+    /// 解析 PKCS#8 / PKCS#1 私钥或 SPKI / PKCS#1 公钥，模数不足 2048 位时返回 None
+    /// Parse a PKCS#8 / PKCS#1 private key or an SPKI / PKCS#1 public key; None below 2048 bits
+    pub(super) fn from_pem(pem: &str) -> Option<Self> {
+        let private = RsaPrivateKey::from_pkcs8_pem(pem).or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem)).ok();
+        let key = match private {
+            Some(private) => Self { public: private.to_public_key(), private: Some(private) },
+            None => Self {
+                public: RsaPublicKey::from_public_key_pem(pem).or_else(|_| RsaPublicKey::from_pkcs1_pem(pem)).ok()?,
+                private: None,
+            },
+        };
+        (key.public.size() * 8 >= MIN_MODULUS_BITS).then_some(key)
+    }
+
+    /// 是否持有私钥 | Whether the private key is present
+    pub(super) fn has_private(&self) -> bool {
+        self.private.is_some()
+    }
+
+    /// RSAES-OAEP 加密 | RSAES-OAEP encryption
+    pub(super) fn encrypt(&self, message: &[u8]) -> Option<Vec<u8>> {
+        self.public.encrypt(&mut OsRng, Oaep::new::<Sha256>(), message).ok()
+    }
+
+    /// RSAES-OAEP 解密（带盲化），没有私钥或任何错误都返回 None
+    /// RSAES-OAEP decryption (blinded), None without a private key or on any error
+    pub(super) fn decrypt(&self, ciphertext: &[u8]) -> Option<Vec<u8>> {
+        self.private.as_ref()?.decrypt_blinded(&mut OsRng, Oaep::new::<Sha256>(), ciphertext).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::tests::{RSA_PRIVATE_PEM, RSA_PRIVATE_PKCS1_PEM, RSA_PUBLIC_PEM};
+
+    #[test]
+    fn test_rsa_oaep_256() {
+        let private = RsaKey::from_pem(RSA_PRIVATE_PEM).unwrap();
+        let pkcs1 = RsaKey::from_pem(RSA_PRIVATE_PKCS1_PEM).unwrap();
+        let public = RsaKey::from_pem(RSA_PUBLIC_PEM).unwrap();
+        assert!(private.has_private() && pkcs1.has_private() && !public.has_private());
+
+        let ciphertext = public.encrypt(b"a 256-bit content encryption key").unwrap();
+        assert_eq!(ciphertext.len(), 256);
+        assert_eq!(private.decrypt(&ciphertext).as_deref(), Some(&b"a 256-bit content encryption key"[..]));
+        assert_eq!(pkcs1.decrypt(&ciphertext).as_deref(), Some(&b"a 256-bit content encryption key"[..]));
+        assert!(public.decrypt(&ciphertext).is_none());
+
+        let mut tampered = ciphertext.clone();
+        tampered[100] ^= 1;
+        assert!(private.decrypt(&tampered).is_none());
+        assert!(RsaKey::from_pem("not a pem").is_none());
+    }
+}
@@ -0,0 +1,615 @@
+// Author: 金书记
+//
+//! JWKS (JSON Web Key Set) | JWKS（JSON Web 密钥集）
+//!
+//! 认证服务器用 `JwksKeyRing` 以当前密钥签名，并在 `/.well-known/jwks.json` 公开全部公钥；
+//! 资源服务器用 `RemoteJwksValidator` 拉取并缓存该密钥集，按 token 头部的 `kid` 选择公钥验证。
+//! The auth server signs with the current key of a `JwksKeyRing` and publishes every public key at
+//! `/.well-known/jwks.json`; resource servers fetch and cache that set with `RemoteJwksValidator`
+//! and pick the verification key by the `kid` in the token header.
+//!
+//! ```text
+//! 轮换 | Rotation   rotate("2025-02", ...) → 新 token 用 2025-02 签名 | new tokens signed with 2025-02
+//!                    2025-01 仍在密钥集中，已签发的 token 继续有效 | 2025-01 stays published, issued tokens remain valid
+//!                    retire_key("2025-01") → 旧 token 全部过期后移除 | removed once its tokens have expired
+//!
+//! 验证 | Verify     RemoteJwksValidator.validate(token)
+//!                    ├─→ kid 在缓存中 | kid cached       → 验证 | verify
+//!                    └─→ 未知 kid 或缓存过期 | unknown kid or stale cache
+//!                          → 重新拉取（受 min_refresh_interval 限制）| refetch (throttled by min_refresh_interval)
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use jsonwebtoken::decode_header;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+    EllipticCurveKeyType, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+    RSAKeyParameters, RSAKeyType,
+};
+use simple_asn1::ASN1Block;
+use tokio::sync::RwLock as AsyncRwLock;
+use crate::error::{SaTokenError, SaTokenResult};
+use super::jwt::{JwtAlgorithm, JwtClaims, JwtManager};
+
+pub use jsonwebtoken::jwk::{Jwk, JwkSet};
+
+/// 从 PEM 公钥构建带 `kid` 的公开 JWK | Build a public JWK with a `kid` from a PEM public key
+///
+/// # 参数 | Parameters
+/// * `kid` - 密钥 ID | Key ID
+/// * `algorithm` - RS256/384/512、ES256/384 或 EdDSA | RS256/384/512, ES256/384 or EdDSA
+/// * `public_key_pem` - SPKI 公钥（RSA 也可用 PKCS#1）| SPKI public key (PKCS#1 also accepted for RSA)
+pub fn jwk_from_pem(kid: &str, algorithm: JwtAlgorithm, public_key_pem: &str) -> SaTokenResult<Jwk> {
+    let invalid = |msg: &str| SaTokenError::ConfigError(format!("Invalid JWT public key PEM for {:?}: {}", algorithm, msg));
+    let der = pem_to_der(public_key_pem).ok_or_else(|| invalid("not a PEM document"))?;
+    let blocks = simple_asn1::from_der(&der).map_err(|e| invalid(&e.to_string()))?;
+
+    let params = if algorithm.is_rsa() {
+        let (n, e) = match blocks.first() {
+            // PKCS#1: SEQUENCE { n, e }
+            Some(ASN1Block::Sequence(_, items)) if matches!(items.as_slice(), [ASN1Block::Integer(..), ASN1Block::Integer(..)]) => {
+                rsa_components(items)
+            }
+            // SPKI: SEQUENCE { algorithm, BIT STRING { SEQUENCE { n, e } } }
+            Some(ASN1Block::Sequence(_, items)) => match items.as_slice() {
+                [_, ASN1Block::BitString(_, _, key)] => match simple_asn1::from_der(key).ok().as_deref() {
+                    Some([ASN1Block::Sequence(_, items)]) => rsa_components(items),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+        .ok_or_else(|| invalid("not an RSA public key"))?;
+        AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(n),
+            e: URL_SAFE_NO_PAD.encode(e),
+        })
+    } else if algorithm.is_ecdsa() {
+        let (curve, coordinate_len) = match algorithm {
+            JwtAlgorithm::ES384 => (EllipticCurve::P384, 48),
+            _ => (EllipticCurve::P256, 32),
+        };
+        // SPKI: SEQUENCE { algorithm, BIT STRING { 04 || x || y } }
+        let point = match blocks.first() {
+            Some(ASN1Block::Sequence(_, items)) => match items.as_slice() {
+                [_, ASN1Block::BitString(_, _, point)] => Some(point),
+                _ => None,
+            },
+            _ => None,
+        }
+        .filter(|point| point.len() == 1 + 2 * coordinate_len && point[0] == 0x04)
+        .ok_or_else(|| invalid("not an uncompressed EC public key on the algorithm's curve"))?;
+        AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+            key_type: EllipticCurveKeyType::EC,
+            curve,
+            x: URL_SAFE_NO_PAD.encode(&point[1..=coordinate_len]),
+            y: URL_SAFE_NO_PAD.encode(&point[1 + coordinate_len..]),
+        })
+    } else if algorithm.is_eddsa() {
+        // SPKI: SEQUENCE { SEQUENCE { OID 1.3.101.112 }, BIT STRING { x } }
+        let x = match blocks.first() {
+            Some(ASN1Block::Sequence(_, items)) => match items.as_slice() {
+                [ASN1Block::Sequence(_, alg), ASN1Block::BitString(_, _, x)]
+                    if matches!(alg.first(), Some(ASN1Block::ObjectIdentifier(_, oid)) if *oid == simple_asn1::oid!(1, 3, 101, 112)) => Some(x),
+                _ => None,
+            },
+            _ => None,
+        }
+        .filter(|x| x.len() == 32)
+        .ok_or_else(|| invalid("not an Ed25519 public key"))?;
+        AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(x),
+        })
+    } else {
+        return Err(SaTokenError::ConfigError(format!(
+            "{:?} is an HMAC algorithm; its secret must never be published in a JWKS", algorithm
+        )));
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm(algorithm)),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: params,
+    })
+}
+
+fn pem_to_der(pem: &str) -> Option<Vec<u8>> {
+    if !pem.trim_start().starts_with("-----BEGIN ") {
+        return None;
+    }
+    let body: String = pem.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    STANDARD.decode(body).ok()
+}
+
+fn rsa_components(items: &[ASN1Block]) -> Option<(Vec<u8>, Vec<u8>)> {
+    match items {
+        [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => Some((n.to_bytes_be().1, e.to_bytes_be().1)),
+        _ => None,
+    }
+}
+
+fn key_algorithm(algorithm: JwtAlgorithm) -> KeyAlgorithm {
+    match algorithm {
+        JwtAlgorithm::HS256 => KeyAlgorithm::HS256,
+        JwtAlgorithm::HS384 => KeyAlgorithm::HS384,
+        JwtAlgorithm::HS512 => KeyAlgorithm::HS512,
+        JwtAlgorithm::RS256 => KeyAlgorithm::RS256,
+        JwtAlgorithm::RS384 => KeyAlgorithm::RS384,
+        JwtAlgorithm::RS512 => KeyAlgorithm::RS512,
+        JwtAlgorithm::ES256 => KeyAlgorithm::ES256,
+        JwtAlgorithm::ES384 => KeyAlgorithm::ES384,
+        JwtAlgorithm::EdDSA => KeyAlgorithm::EdDSA,
+    }
+}
+
+/// 读取 token 头部的 `kid` | Read the `kid` from a token header
+fn token_kid(token: &str) -> SaTokenResult<String> {
+    decode_header(token)
+        .map_err(|e| SaTokenError::InvalidToken(format!("Invalid JWT header: {}", e)))?
+        .kid
+        .ok_or_else(|| SaTokenError::InvalidToken("JWT header has no kid".to_string()))
+}
+
+struct KeyEntry {
+    jwk: Jwk,
+    manager: JwtManager,
+}
+
+#[derive(Default)]
+struct KeyRingState {
+    keys: Vec<KeyEntry>,
+    current: Option<String>,
+}
+
+impl KeyRingState {
+    fn get(&self, kid: &str) -> Option<&KeyEntry> {
+        self.keys.iter().find(|entry| entry.manager.kid() == Some(kid))
+    }
+}
+
+/// 可轮换的签名密钥环 | Signing key ring with rotation
+///
+/// 克隆共享同一组密钥，运行时轮换对所有克隆可见。
+/// Clones share the same keys, so a rotation at runtime is seen by every clone.
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let key_ring = JwksKeyRing::new().set_issuer("https://auth.example.com");
+/// key_ring.rotate("2025-01", JwtAlgorithm::RS256, &private_pem, &public_pem)?;
+///
+/// let token = key_ring.generate(&claims)?;          // kid = 2025-01
+/// key_ring.rotate("2025-02", JwtAlgorithm::ES256, &ec_private, &ec_public)?;
+/// key_ring.validate(&token)?;                       // 仍然有效 | still valid
+/// let jwks = key_ring.jwks_json();                  // 两个公钥 | both public keys
+/// ```
+#[derive(Clone, Default)]
+pub struct JwksKeyRing {
+    state: Arc<RwLock<KeyRingState>>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl fmt::Debug for JwksKeyRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwksKeyRing")
+            .field("kids", &self.kids())
+            .field("current", &self.current_kid())
+            .finish()
+    }
+}
+
+impl JwksKeyRing {
+    /// 创建空密钥环 | Create an empty key ring
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置签发者（在添加密钥前设置）| Set the issuer (before adding keys)
+    pub fn set_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// 设置受众（在添加密钥前设置）| Set the audience (before adding keys)
+    pub fn set_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    /// 添加一个密钥，不改变当前签名密钥 | Add a key without changing the current signing key
+    ///
+    /// 只有公钥时仅用于验证（如其他实例签发的 token）；密钥环还没有签名密钥且提供了私钥时，它成为当前签名密钥。
+    /// A key without a private part only verifies (e.g. tokens issued by another instance); when the ring
+    /// has no signing key yet and a private key is given, it becomes the current signing key.
+    ///
+    /// # 参数 | Parameters
+    /// * `kid` - 密钥 ID，在密钥环中唯一 | Key ID, unique within the ring
+    /// * `algorithm` - RS256/384/512、ES256/384 或 EdDSA | RS256/384/512, ES256/384 or EdDSA
+    /// * `private_key_pem` - 签名私钥，None 表示仅验证 | Signing key, None for verify-only
+    /// * `public_key_pem` - 验证公钥，会公开在 JWKS 中 | Verification key, published in the JWKS
+    pub fn add_key(
+        &self,
+        kid: &str,
+        algorithm: JwtAlgorithm,
+        private_key_pem: Option<&str>,
+        public_key_pem: &str,
+    ) -> SaTokenResult<()> {
+        let jwk = jwk_from_pem(kid, algorithm, public_key_pem)?;
+        let mut manager = JwtManager::from_pem(algorithm, private_key_pem, Some(public_key_pem))?.set_kid(kid);
+        if let Some(ref issuer) = self.issuer {
+            manager = manager.set_issuer(issuer);
+        }
+        if let Some(ref audience) = self.audience {
+            manager = manager.set_audience(audience);
+        }
+
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if state.get(kid).is_some() {
+            return Err(SaTokenError::ConfigError(format!("JWKS key id already exists: {}", kid)));
+        }
+        if state.current.is_none() && manager.can_sign() {
+            state.current = Some(kid.to_string());
+        }
+        state.keys.push(KeyEntry { jwk, manager });
+        Ok(())
+    }
+
+    /// 轮换签名密钥：添加新密钥并用它签名，旧密钥继续用于验证
+    /// Rotate the signing key: add a new key and sign with it, older keys keep verifying
+    pub fn rotate(
+        &self,
+        kid: &str,
+        algorithm: JwtAlgorithm,
+        private_key_pem: &str,
+        public_key_pem: &str,
+    ) -> SaTokenResult<()> {
+        self.add_key(kid, algorithm, Some(private_key_pem), public_key_pem)?;
+        self.set_current(kid)
+    }
+
+    /// 切换当前签名密钥 | Switch the current signing key
+    pub fn set_current(&self, kid: &str) -> SaTokenResult<()> {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        match state.get(kid) {
+            Some(entry) if entry.manager.can_sign() => {
+                state.current = Some(kid.to_string());
+                Ok(())
+            }
+            Some(_) => Err(SaTokenError::ConfigError(format!("JWKS key {} has no private key", kid))),
+            None => Err(SaTokenError::ConfigError(format!("Unknown JWKS key id: {}", kid))),
+        }
+    }
+
+    /// 移除旧密钥（其签发的 token 应已全部过期），不能移除当前签名密钥
+    /// Remove an old key (its tokens should all have expired); the current signing key cannot be removed
+    pub fn retire_key(&self, kid: &str) -> SaTokenResult<()> {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if state.current.as_deref() == Some(kid) {
+            return Err(SaTokenError::ConfigError(format!("Cannot retire the current signing key: {}", kid)));
+        }
+        let before = state.keys.len();
+        state.keys.retain(|entry| entry.manager.kid() != Some(kid));
+        if state.keys.len() == before {
+            return Err(SaTokenError::ConfigError(format!("Unknown JWKS key id: {}", kid)));
+        }
+        Ok(())
+    }
+
+    /// 当前签名密钥 ID | Current signing key ID
+    pub fn current_kid(&self) -> Option<String> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).current.clone()
+    }
+
+    /// 全部密钥 ID | Every key ID
+    pub fn kids(&self) -> Vec<String> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+            .keys.iter()
+            .filter_map(|entry| entry.manager.kid().map(str::to_string))
+            .collect()
+    }
+
+    /// 公开的密钥集 | The public key set
+    pub fn jwks(&self) -> JwkSet {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        JwkSet { keys: state.keys.iter().map(|entry| entry.jwk.clone()).collect() }
+    }
+
+    /// JWKS JSON 文档，用于 `/.well-known/jwks.json` | JWKS JSON document for `/.well-known/jwks.json`
+    pub fn jwks_json(&self) -> String {
+        serde_json::to_string(&self.jwks()).unwrap_or_else(|_| r#"{"keys":[]}"#.to_string())
+    }
+
+    /// 用当前签名密钥生成 JWT（头部带 `kid`）| Generate a JWT with the current signing key (`kid` in the header)
+    pub fn generate(&self, claims: &JwtClaims) -> SaTokenResult<String> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let entry = state.current.as_deref()
+            .and_then(|kid| state.get(kid))
+            .ok_or_else(|| SaTokenError::ConfigError("JWKS key ring has no signing key".to_string()))?;
+        entry.manager.generate(claims)
+    }
+
+    /// 按 `kid` 选择公钥验证 JWT | Validate a JWT with the public key selected by its `kid`
+    pub fn validate(&self, token: &str) -> SaTokenResult<JwtClaims> {
+        let kid = token_kid(token)?;
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let entry = state.get(&kid)
+            .ok_or_else(|| SaTokenError::InvalidToken(format!("Unknown JWT kid: {}", kid)))?;
+        entry.manager.validate(token)
+    }
+}
+
+/// JWKS 客户端：获取远程密钥集 | JWKS client: fetches a remote key set
+///
+/// 启用 `remote-jwks` feature 后可使用基于 HTTP 的 `HttpJwksClient`；也可自行实现以使用其他传输方式。
+/// With the `remote-jwks` feature, `HttpJwksClient` fetches over HTTP; implement the trait yourself
+/// for other transports.
+#[async_trait]
+pub trait JwksClient: Send + Sync {
+    /// 获取密钥集 | Fetch the key set
+    async fn fetch(&self) -> SaTokenResult<JwkSet>;
+}
+
+/// 基于 HTTP 的 JWKS 客户端 | HTTP JWKS client
+#[cfg(feature = "remote-jwks")]
+#[derive(Clone)]
+pub struct HttpJwksClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "remote-jwks")]
+impl HttpJwksClient {
+    /// 创建客户端 | Create a client
+    ///
+    /// # 参数 | Parameters
+    /// * `url` - JWKS 地址，如 `https://auth.example.com/.well-known/jwks.json` | JWKS URL, e.g. `https://auth.example.com/.well-known/jwks.json`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// 使用自定义的 reqwest 客户端（超时、代理等）| Use a custom reqwest client (timeouts, proxies, ...)
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+}
+
+#[cfg(feature = "remote-jwks")]
+#[async_trait]
+impl JwksClient for HttpJwksClient {
+    async fn fetch(&self) -> SaTokenResult<JwkSet> {
+        let response = self.http.get(&self.url).send().await
+            .map_err(|e| SaTokenError::InternalError(format!("JWKS request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(SaTokenError::InternalError(format!("JWKS endpoint returned {}", response.status())));
+        }
+        let body = response.text().await
+            .map_err(|e| SaTokenError::InternalError(format!("JWKS request failed: {}", e)))?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+#[derive(Default)]
+struct JwksCache {
+    keys: HashMap<String, JwtManager>,
+    fetched_at: Option<Instant>,
+}
+
+/// 远程 JWKS 校验器 | Remote JWKS validator
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let validator = RemoteJwksValidator::new(
+///     HttpJwksClient::new("https://auth.example.com/.well-known/jwks.json"),
+/// )
+/// .set_issuer("https://auth.example.com")
+/// .cache_ttl(3600);
+///
+/// let claims = validator.validate(&token).await?;
+/// ```
+#[derive(Clone)]
+pub struct RemoteJwksValidator {
+    client: Arc<dyn JwksClient>,
+    cache_ttl: i64,
+    min_refresh_interval: i64,
+    issuer: Option<String>,
+    audience: Option<String>,
+    cache: Arc<AsyncRwLock<JwksCache>>,
+}
+
+impl RemoteJwksValidator {
+    /// 创建校验器（密钥集缓存 1 小时，未知 kid 最多每 30 秒重新拉取一次）
+    /// Create a validator (key set cached for 1 hour, refetched for an unknown kid at most every 30s)
+    pub fn new(client: impl JwksClient + 'static) -> Self {
+        Self::with_client(Arc::new(client))
+    }
+
+    /// 使用共享的客户端创建校验器 | Create a validator with a shared client
+    pub fn with_client(client: Arc<dyn JwksClient>) -> Self {
+        Self {
+            client,
+            cache_ttl: 3600,
+            min_refresh_interval: 30,
+            issuer: None,
+            audience: None,
+            cache: Arc::new(AsyncRwLock::new(JwksCache::default())),
+        }
+    }
+
+    /// 密钥集缓存时长（秒）| Cache time of the key set in seconds
+    pub fn cache_ttl(mut self, seconds: i64) -> Self {
+        self.cache_ttl = seconds;
+        self
+    }
+
+    /// 遇到未知 kid 时两次拉取的最短间隔（秒），防止伪造 kid 压垮认证服务器
+    /// Minimum seconds between refetches for unknown kids, so forged kids cannot flood the auth server
+    pub fn min_refresh_interval(mut self, seconds: i64) -> Self {
+        self.min_refresh_interval = seconds;
+        self
+    }
+
+    /// 要求的签发者 | Required issuer
+    pub fn set_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// 要求的受众 | Required audience
+    pub fn set_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    /// 按 `kid` 选择远程公钥验证 JWT | Validate a JWT with the remote public key selected by its `kid`
+    pub async fn validate(&self, token: &str) -> SaTokenResult<JwtClaims> {
+        let kid = token_kid(token)?;
+
+        let (stale, refresh_allowed) = {
+            let cache = self.cache.read().await;
+            let age = cache.fetched_at.map(|at| at.elapsed());
+            let stale = age.is_none_or(|age| age >= Duration::from_secs(self.cache_ttl.max(0) as u64));
+            if let Some(manager) = cache.keys.get(&kid) && !stale {
+                return manager.validate(token);
+            }
+            let refresh_allowed = age.is_none_or(|age| age >= Duration::from_secs(self.min_refresh_interval.max(0) as u64));
+            (stale, refresh_allowed)
+        };
+
+        if stale || refresh_allowed {
+            // 拉取失败时退回到旧缓存 | Fall back to the old cache when the fetch fails
+            if let Err(e) = self.refresh().await && !self.cache.read().await.keys.contains_key(&kid) {
+                return Err(e);
+            }
+        }
+
+        let cache = self.cache.read().await;
+        let manager = cache.keys.get(&kid)
+            .ok_or_else(|| SaTokenError::InvalidToken(format!("Unknown JWT kid: {}", kid)))?;
+        manager.validate(token)
+    }
+
+    /// 立即重新拉取密钥集 | Refetch the key set now
+    ///
+    /// 不支持的密钥（如 `oct`）会被跳过 | Unsupported keys (such as `oct`) are skipped
+    pub async fn refresh(&self) -> SaTokenResult<()> {
+        let jwks = self.client.fetch().await?;
+        let mut keys = HashMap::new();
+        for jwk in &jwks.keys {
+            let Some(kid) = jwk.common.key_id.clone() else { continue };
+            let Ok(mut manager) = JwtManager::from_jwk(jwk) else { continue };
+            if let Some(ref issuer) = self.issuer {
+                manager = manager.set_issuer(issuer);
+            }
+            if let Some(ref audience) = self.audience {
+                manager = manager.set_audience(audience);
+            }
+            keys.insert(kid, manager);
+        }
+
+        let mut cache = self.cache.write().await;
+        cache.keys = keys;
+        cache.fetched_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// 缓存中的密钥 ID | Key IDs in the cache
+    pub async fn kids(&self) -> Vec<String> {
+        self.cache.read().await.keys.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // 仅用于测试的密钥对 | Test-only key pairs
+    const RSA_PRIVATE_PEM: &str = include_str!("testdata/rsa_private.pem");
+    const RSA_PUBLIC_PEM: &str = include_str!("testdata/rsa_public.pem");
+    const EC_PRIVATE_PEM: &str = include_str!("testdata/ec_private.pem");
+    const EC_PUBLIC_PEM: &str = include_str!("testdata/ec_public.pem");
+
+    struct StaticJwksClient {
+        jwks: String,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl JwksClient for StaticJwksClient {
+        async fn fetch(&self) -> SaTokenResult<JwkSet> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(serde_json::from_str(&self.jwks)?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwks_rotation_and_remote_validation() {
+        let key_ring = JwksKeyRing::new().set_issuer("auth-server");
+        key_ring.rotate("k1", JwtAlgorithm::RS256, RSA_PRIVATE_PEM, RSA_PUBLIC_PEM).unwrap();
+
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+        let old_token = key_ring.generate(&claims).unwrap();
+
+        // 轮换后旧 token 仍然有效 | Tokens signed by the previous key survive the rotation
+        key_ring.rotate("k2", JwtAlgorithm::ES256, EC_PRIVATE_PEM, EC_PUBLIC_PEM).unwrap();
+        let new_token = key_ring.generate(&claims).unwrap();
+        assert_eq!(key_ring.current_kid().as_deref(), Some("k2"));
+        assert_eq!(key_ring.validate(&old_token).unwrap().login_id, "user_123");
+        assert_eq!(key_ring.validate(&new_token).unwrap().login_id, "user_123");
+        assert!(key_ring.rotate("k2", JwtAlgorithm::ES256, EC_PRIVATE_PEM, EC_PUBLIC_PEM).is_err());
+        assert!(key_ring.retire_key("k2").is_err());
+
+        // 公开的密钥集不含私钥参数 | The published set carries no private key material
+        let jwks_json = key_ring.jwks_json();
+        let document: serde_json::Value = serde_json::from_str(&jwks_json).unwrap();
+        assert_eq!(document["keys"].as_array().unwrap().len(), 2);
+        assert_eq!(document["keys"][0]["kid"], "k1");
+        assert_eq!(document["keys"][0]["kty"], "RSA");
+        assert_eq!(document["keys"][1]["crv"], "P-256");
+        assert!(document["keys"].as_array().unwrap().iter().all(|key| key.get("d").is_none()));
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let validator = RemoteJwksValidator::new(StaticJwksClient { jwks: jwks_json, calls: calls.clone() })
+            .set_issuer("auth-server");
+        assert_eq!(validator.validate(&old_token).await.unwrap().login_id, "user_123");
+        assert_eq!(validator.validate(&new_token).await.unwrap().login_id, "user_123");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 未知 kid 的重新拉取受最短间隔限制 | Refetches for unknown kids are throttled
+        let forged = JwtManager::from_pem(JwtAlgorithm::ES256, Some(EC_PRIVATE_PEM), None)
+            .unwrap()
+            .set_kid("k3")
+            .generate(&claims)
+            .unwrap();
+        assert!(validator.validate(&forged).await.is_err());
+        assert!(validator.validate(&forged).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 旧 token 全部过期后移除旧密钥 | Retire the old key once its tokens have expired
+        key_ring.retire_key("k1").unwrap();
+        assert!(key_ring.validate(&old_token).is_err());
+        assert_eq!(key_ring.kids(), vec!["k2".to_string()]);
+    }
+}
@@ -7,9 +7,14 @@
 //!
 //! ## Features | 功能特性
 //!
-//! - Multiple algorithms support (HS256, HS384, HS512, RS256, etc.)
-//!   支持多种算法（HS256, HS384, HS512, RS256 等）
-//! - Custom claims support | 支持自定义声明
+//! - Multiple algorithms support (HS256, HS384, HS512, RS256, ES256, EdDSA, etc.)
+//!   支持多种算法（HS256, HS384, HS512, RS256, ES256, EdDSA 等）
+//! - Asymmetric keys loaded from PEM; resource servers verify with the public key only
+//!   从 PEM 加载非对称密钥；资源服务器仅凭公钥即可验证
+//! - Optional JWE encryption (A256GCM) so the claims stay confidential
+//!   可选的 JWE 加密（A256GCM），声明内容对外保密
+//! - Custom claims support, and `ClaimsMapper` to map `TokenInfo` to and from claims
+//!   支持自定义声明，`ClaimsMapper` 负责 `TokenInfo` 与声明之间的映射
 //! - Expiration time validation | 过期时间验证
 //! - Token refresh | Token 刷新
 //!
@@ -29,22 +34,38 @@
 //! // Validate and parse JWT token | 验证并解析 JWT token
 //! let decoded_claims = jwt_manager.validate(&token)?;
 //! println!("User ID: {}", decoded_claims.login_id);
+//!
+//! // RS256: sign on the auth server, verify elsewhere with the public key only
+//! // RS256：在认证服务器签名，其他服务仅用公钥验证
+//! let signer = JwtManager::from_pem(JwtAlgorithm::RS256, Some(PRIVATE_PEM), Some(PUBLIC_PEM))?;
+//! let verifier = JwtManager::from_pem(JwtAlgorithm::RS256, None, Some(PUBLIC_PEM))?;
+//! let claims = verifier.validate(&signer.generate(&claims)?)?;
 //! ```
 
 use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{
     decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
 };
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
+use crate::config::SaTokenConfig;
 use crate::error::{SaTokenError, SaTokenResult};
+use super::jwe::{JweAlgorithm, JweEncryption};
+use super::{TokenInfo, TokenValue};
 
 /// JWT Algorithm | JWT 算法
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default)]
 pub enum JwtAlgorithm {
     /// HMAC using SHA-256 | 使用 SHA-256 的 HMAC
+    #[default]
     HS256,
     /// HMAC using SHA-384 | 使用 SHA-384 的 HMAC
     HS384,
@@ -60,11 +81,61 @@ pub enum JwtAlgorithm {
     ES256,
     /// ECDSA using SHA-384 | 使用 SHA-384 的 ECDSA
     ES384,
+    /// EdDSA using Ed25519: small keys and fast, deterministic signatures | 使用 Ed25519 的 EdDSA：密钥小、签名快且确定
+    EdDSA,
 }
 
-impl Default for JwtAlgorithm {
-    fn default() -> Self {
-        Self::HS256
+
+impl JwtAlgorithm {
+    /// Whether the algorithm uses a shared secret (HMAC) | 是否为共享密钥算法（HMAC）
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, Self::HS256 | Self::HS384 | Self::HS512)
+    }
+
+    /// Whether the algorithm uses RSA keys | 是否使用 RSA 密钥
+    pub fn is_rsa(&self) -> bool {
+        matches!(self, Self::RS256 | Self::RS384 | Self::RS512)
+    }
+
+    /// Whether the algorithm uses ECDSA keys | 是否使用 ECDSA 密钥
+    pub fn is_ecdsa(&self) -> bool {
+        matches!(self, Self::ES256 | Self::ES384)
+    }
+
+    /// Whether the algorithm uses Ed25519 keys | 是否使用 Ed25519 密钥
+    pub fn is_eddsa(&self) -> bool {
+        matches!(self, Self::EdDSA)
+    }
+
+    /// Parse an algorithm name such as "RS256" (case-insensitive) | 解析算法名称，如 "RS256"（不区分大小写）
+    pub fn parse(alg: &str) -> Option<Self> {
+        match alg.to_uppercase().as_str() {
+            "HS256" => Some(Self::HS256),
+            "HS384" => Some(Self::HS384),
+            "HS512" => Some(Self::HS512),
+            "RS256" => Some(Self::RS256),
+            "RS384" => Some(Self::RS384),
+            "RS512" => Some(Self::RS512),
+            "ES256" => Some(Self::ES256),
+            "ES384" => Some(Self::ES384),
+            "EDDSA" | "ED25519" => Some(Self::EdDSA),
+            _ => None,
+        }
+    }
+
+    /// JOSE `alg` name, e.g. "RS256" | JOSE `alg` 名称，如 "RS256"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HS256 => "HS256",
+            Self::HS384 => "HS384",
+            Self::HS512 => "HS512",
+            Self::RS256 => "RS256",
+            Self::RS384 => "RS384",
+            Self::RS512 => "RS512",
+            Self::ES256 => "ES256",
+            Self::ES384 => "ES384",
+            Self::EdDSA => "EdDSA",
+        }
     }
 }
 
@@ -79,6 +150,7 @@ impl From<JwtAlgorithm> for Algorithm {
             JwtAlgorithm::RS512 => Algorithm::RS512,
             JwtAlgorithm::ES256 => Algorithm::ES256,
             JwtAlgorithm::ES384 => Algorithm::ES384,
+            JwtAlgorithm::EdDSA => Algorithm::EdDSA,
         }
     }
 }
@@ -128,7 +200,7 @@ pub struct JwtClaims {
     pub device: Option<String>,
 
     /// Custom data | 自定义数据
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub extra: HashMap<String, Value>,
 }
 
@@ -242,14 +314,275 @@ impl JwtClaims {
     }
 }
 
+
+/// Claim names stored in `JwtClaims` fields rather than `extra` | 保存在 `JwtClaims` 字段而不是 `extra` 中的声明名
+const REGISTERED_CLAIMS: [&str; 9] = ["sub", "iss", "aud", "exp", "nbf", "iat", "jti", "login_type", "device"];
+
+impl JwtClaims {
+    /// Flatten into one claims map, custom claims next to the registered ones
+    /// 展开为单个声明映射，自定义声明与注册声明并列
+    pub fn to_map(&self) -> Map<String, Value> {
+        let mut map = match serde_json::to_value(self) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        map.remove("extra");
+        for (key, value) in &self.extra {
+            map.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        map
+    }
+
+    /// Build from a flat claims map; names other than the registered ones go to `extra`
+    /// 从展开的声明映射构建，注册声明以外的名称放入 `extra`
+    pub fn from_map(mut map: Map<String, Value>) -> SaTokenResult<Self> {
+        let registered: Map<String, Value> = REGISTERED_CLAIMS.iter()
+            .filter_map(|name| map.remove(*name).map(|value| (name.to_string(), value)))
+            .collect();
+        let mut claims: Self = serde_json::from_value(Value::Object(registered))
+            .map_err(|e| SaTokenError::InvalidToken(format!("Invalid JWT claims: {}", e)))?;
+        claims.extra = map.into_iter().collect();
+        Ok(claims)
+    }
+}
+
+/// Maps `TokenInfo` to JWT claims and back | `TokenInfo` 与 JWT 声明之间的映射
+///
+/// Register one on `JwtManager` (or `SaTokenConfig::jwt_claims_mapper`) so typed custom claims such
+/// as `tenant_id` or `scopes` round-trip without manual serde at every call site. Custom mappers
+/// usually wrap `DefaultClaimsMapper` and add or read their own claims.
+/// 注册到 `JwtManager`（或 `SaTokenConfig::jwt_claims_mapper`）后，`tenant_id`、`scopes` 等自定义声明
+/// 可以完整往返，无需在每个调用处手动序列化。自定义映射器通常包装 `DefaultClaimsMapper`，再增减自己的声明。
+///
+/// # Example | 示例
+/// ```
+/// use serde_json::{Map, Value};
+/// use sa_token_core::token::{ClaimsMapper, DefaultClaimsMapper, TokenInfo};
+///
+/// struct TenantClaimsMapper;
+///
+/// impl ClaimsMapper for TenantClaimsMapper {
+///     fn to_claims(&self, token_info: &TokenInfo) -> Map<String, Value> {
+///         let mut claims = DefaultClaimsMapper.to_claims(token_info);
+///         let tenant = token_info.login_id.split('@').nth(1).unwrap_or("public");
+///         claims.insert("tenant_id".to_string(), Value::from(tenant));
+///         claims
+///     }
+///
+///     fn from_claims(&self, claims: Map<String, Value>) -> TokenInfo {
+///         DefaultClaimsMapper.from_claims(claims)
+///     }
+/// }
+/// ```
+pub trait ClaimsMapper: Send + Sync {
+    /// Claims to issue for a token | 为 token 签发的声明
+    ///
+    /// Must contain `sub`; `exp` is required to validate the token later.
+    /// 必须包含 `sub`；之后验证 token 需要 `exp`。
+    fn to_claims(&self, token_info: &TokenInfo) -> Map<String, Value>;
+
+    /// Token info rebuilt from validated claims; the token value is filled in by the caller
+    ///
+    /// Claims it does not recognise should go to `TokenInfo::extra`; the Mixin / Stateless modes
+    /// read the `perms` and `roles` claims from there.
+    /// 从验证后的声明还原 token 信息，token 值由调用方填入。无法识别的声明应放入 `TokenInfo::extra`，
+    /// Mixin / Stateless 模式从中读取 `perms` 与 `roles` 声明。
+    #[allow(clippy::wrong_self_convention)]
+    fn from_claims(&self, claims: Map<String, Value>) -> TokenInfo;
+}
+
+impl fmt::Debug for dyn ClaimsMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ClaimsMapper")
+    }
+}
+
+/// Built-in claims mapping | 内置的声明映射
+///
+/// | TokenInfo | Claim |
+/// |-----------|-------|
+/// | `login_id` / `login_type` / `device` | `sub` / `login_type` / `device` |
+/// | `expire_time` / `create_time` | `exp` / `iat`, plus `lt` in milliseconds |
+/// | `login_ip` / `fingerprint` | `ip` / `fp` |
+/// | `extra` | one claim per entry（每项一个声明）|
+///
+/// `iss`, `aud`, `nbf` and `jti` are left to the issuer and dropped when mapping back.
+/// `iss`、`aud`、`nbf` 与 `jti` 由签发方处理，映射回来时丢弃。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultClaimsMapper;
+
+impl DefaultClaimsMapper {
+    /// Login time in milliseconds | 登录时间（毫秒）
+    const LOGIN_TIME_CLAIM: &'static str = "lt";
+    /// Login IP (`bind_ip`) | 登录 IP（`bind_ip`）
+    const LOGIN_IP_CLAIM: &'static str = "ip";
+    /// Device fingerprint (`bind_fingerprint`) | 设备指纹（`bind_fingerprint`）
+    const FINGERPRINT_CLAIM: &'static str = "fp";
+}
+
+impl ClaimsMapper for DefaultClaimsMapper {
+    fn to_claims(&self, token_info: &TokenInfo) -> Map<String, Value> {
+        let mut claims: Map<String, Value> = token_info.extra.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        claims.insert("sub".to_string(), Value::from(token_info.login_id.as_str()));
+        claims.insert("login_type".to_string(), Value::from(token_info.login_type.as_str()));
+        if let Some(ref device) = token_info.device {
+            claims.insert("device".to_string(), Value::from(device.as_str()));
+        }
+        if let Some(expire_time) = token_info.expire_time {
+            claims.insert("exp".to_string(), Value::from(expire_time.timestamp()));
+        }
+        claims.insert("iat".to_string(), Value::from(token_info.create_time.timestamp()));
+        claims.insert(Self::LOGIN_TIME_CLAIM.to_string(), Value::from(token_info.create_time.timestamp_millis()));
+        if let Some(ref ip) = token_info.login_ip {
+            claims.insert(Self::LOGIN_IP_CLAIM.to_string(), Value::from(ip.as_str()));
+        }
+        if let Some(ref fingerprint) = token_info.fingerprint {
+            claims.insert(Self::FINGERPRINT_CLAIM.to_string(), Value::from(fingerprint.as_str()));
+        }
+        claims
+    }
+
+    fn from_claims(&self, mut claims: Map<String, Value>) -> TokenInfo {
+        let mut take_str = |name: &str| claims.remove(name).and_then(|v| v.as_str().map(str::to_string));
+        let login_id = take_str("sub").unwrap_or_default();
+        let login_type = take_str("login_type");
+        let device = take_str("device");
+        let login_ip = take_str(Self::LOGIN_IP_CLAIM);
+        let fingerprint = take_str(Self::FINGERPRINT_CLAIM);
+
+        let mut token_info = TokenInfo::new(TokenValue::new(String::new()), login_id);
+        if let Some(login_type) = login_type {
+            token_info.login_type = login_type;
+        }
+        token_info.device = device;
+        token_info.login_ip = login_ip;
+        token_info.fingerprint = fingerprint;
+        token_info.expire_time = claims.remove("exp")
+            .and_then(|v| v.as_i64())
+            .and_then(|exp| DateTime::<Utc>::from_timestamp(exp, 0));
+        let iat = claims.remove("iat").and_then(|v| v.as_i64());
+        let create_time = match claims.remove(Self::LOGIN_TIME_CLAIM).and_then(|v| v.as_i64()) {
+            Some(millis) => DateTime::<Utc>::from_timestamp_millis(millis),
+            None => iat.and_then(|iat| DateTime::<Utc>::from_timestamp(iat, 0)),
+        };
+        if let Some(create_time) = create_time {
+            token_info.create_time = create_time;
+        }
+        for name in ["iss", "aud", "nbf", "jti"] {
+            claims.remove(name);
+        }
+        token_info.extra = claims.into_iter().collect();
+        token_info
+    }
+}
+
+
+/// JWT validation options | JWT 验证选项
+///
+/// Rejects tokens minted by other services or environments: an expected issuer, accepted
+/// audiences, clock leeway, whether `exp` is mandatory and whether `nbf` is checked. Each failure
+/// maps to its own `SaTokenError` (`JwtIssuerMismatch`, `JwtAudienceMismatch`, `JwtNotYetValid`,
+/// `JwtMissingClaim`, `TokenExpired`).
+/// 拒绝其他服务或环境签发的 token：期望的签发者、接受的受众、时钟偏差、是否必须有 `exp`、是否检查 `nbf`。
+/// 每种失败对应独立的 `SaTokenError`。
+///
+/// # Example | 示例
+/// ```
+/// use sa_token_core::token::{JwtManager, JwtValidationOptions};
+///
+/// let manager = JwtManager::new("secret").set_validation_options(
+///     JwtValidationOptions::new()
+///         .issuer("https://auth.example.com")
+///         .audience(["orders-api", "billing-api"])
+///         .leeway(30),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JwtValidationOptions {
+    /// Expected `iss`, None falls back to the manager's issuer | 期望的 `iss`，None 时使用管理器的签发者
+    pub issuer: Option<String>,
+    /// Accepted `aud` values, empty falls back to the manager's audience | 接受的 `aud`，为空时使用管理器的受众
+    pub audience: Vec<String>,
+    /// Clock leeway for `exp` and `nbf` in seconds, default 0 | `exp` 与 `nbf` 的时钟偏差（秒），默认 0
+    pub leeway: u64,
+    /// Reject tokens without `exp`, default true | 拒绝没有 `exp` 的 token，默认 true
+    pub require_exp: bool,
+    /// Reject tokens whose `nbf` is in the future, default true | 拒绝 `nbf` 在未来的 token，默认 true
+    pub validate_nbf: bool,
+}
+
+impl Default for JwtValidationOptions {
+    fn default() -> Self {
+        Self {
+            issuer: None,
+            audience: Vec::new(),
+            leeway: 0,
+            require_exp: true,
+            validate_nbf: true,
+        }
+    }
+}
+
+impl JwtValidationOptions {
+    /// Strict defaults: no leeway, `exp` required, `nbf` checked | 严格的默认值：无时钟偏差、必须有 `exp`、检查 `nbf`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the expected issuer | 设置期望的签发者
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Set the accepted audiences | 设置接受的受众
+    pub fn audience<I, S>(mut self, audience: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.audience = audience.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the clock leeway in seconds | 设置时钟偏差（秒）
+    pub fn leeway(mut self, seconds: u64) -> Self {
+        self.leeway = seconds;
+        self
+    }
+
+    /// Set whether `exp` is required | 设置是否必须有 `exp`
+    pub fn require_exp(mut self, require: bool) -> Self {
+        self.require_exp = require;
+        self
+    }
+
+    /// Set whether `nbf` is checked | 设置是否检查 `nbf`
+    pub fn validate_nbf(mut self, validate: bool) -> Self {
+        self.validate_nbf = validate;
+        self
+    }
+}
+
 /// JWT Manager | JWT 管理器
 ///
 /// Manages JWT token generation, validation, and parsing
 /// 管理 JWT token 的生成、验证和解析
+///
+/// HMAC managers sign and verify with one shared secret. RSA / ECDSA managers keep the
+/// signing (private) and verification (public) keys apart; a manager built with only the
+/// public key can validate tokens but not issue them.
+/// HMAC 管理器用同一个共享密钥签名和验证。RSA / ECDSA 管理器将签名（私钥）与验证（公钥）
+/// 分开；只加载公钥的管理器可以验证 token，但不能签发。
 #[derive(Clone)]
 pub struct JwtManager {
-    /// Secret key for HMAC algorithms | HMAC 算法的密钥
-    secret: String,
+    /// Signing key, None for verify-only managers | 签名密钥，仅验证的管理器为 None
+    encoding_key: Option<EncodingKey>,
+
+    /// Verification key, None for sign-only managers | 验证密钥，仅签名的管理器为 None
+    decoding_key: Option<DecodingKey>,
 
     /// Algorithm to use | 使用的算法
     algorithm: JwtAlgorithm,
@@ -259,6 +592,18 @@ pub struct JwtManager {
 
     /// Audience | 受众
     audience: Option<String>,
+
+    /// Key ID written to the `kid` header | 写入 `kid` 头的密钥 ID
+    kid: Option<String>,
+
+    /// JWE encryption of signed tokens, None for plain JWS | 签名后 token 的 JWE 加密，None 表示普通 JWS
+    encryption: Option<JweEncryption>,
+
+    /// `TokenInfo` ↔ claims mapping, None for `DefaultClaimsMapper` | `TokenInfo` 与声明的映射，None 表示 `DefaultClaimsMapper`
+    claims_mapper: Option<Arc<dyn ClaimsMapper>>,
+
+    /// Issuer / audience / timing checks of `validate` | `validate` 的签发者、受众与时间检查
+    validation: JwtValidationOptions,
 }
 
 impl JwtManager {
@@ -268,24 +613,277 @@ impl JwtManager {
     ///
     /// * `secret` - Secret key | 密钥
     pub fn new(secret: impl Into<String>) -> Self {
+        Self::with_algorithm(secret, JwtAlgorithm::HS256)
+    }
+
+    /// Create JWT manager with custom algorithm | 创建使用自定义算法的 JWT 管理器
+    ///
+    /// The secret is used as an HMAC key; use `from_pem` for RSA / ECDSA.
+    /// 密钥按 HMAC 密钥使用；RSA / ECDSA 请使用 `from_pem`。
+    pub fn with_algorithm(secret: impl Into<String>, algorithm: JwtAlgorithm) -> Self {
+        let secret = secret.into();
         Self {
-            secret: secret.into(),
-            algorithm: JwtAlgorithm::HS256,
+            encoding_key: Some(EncodingKey::from_secret(secret.as_bytes())),
+            decoding_key: Some(DecodingKey::from_secret(secret.as_bytes())),
+            algorithm,
             issuer: None,
             audience: None,
+            kid: None,
+            encryption: None,
+            claims_mapper: None,
+            validation: JwtValidationOptions::default(),
         }
     }
 
-    /// Create JWT manager with custom algorithm | 创建使用自定义算法的 JWT 管理器
-    pub fn with_algorithm(secret: impl Into<String>, algorithm: JwtAlgorithm) -> Self {
-        Self {
-            secret: secret.into(),
+    /// Create an RSA / ECDSA / EdDSA JWT manager from PEM keys | 从 PEM 密钥创建 RSA / ECDSA / EdDSA JWT 管理器
+    ///
+    /// # Arguments | 参数
+    ///
+    /// * `algorithm` - RS256/384/512, ES256/384 or EdDSA | RS256/384/512、ES256/384 或 EdDSA
+    /// * `private_key_pem` - Signing key (PKCS#8, or PKCS#1 for RSA); None for verify-only | 签名私钥（PKCS#8，RSA 也可用 PKCS#1）；仅验证时为 None
+    /// * `public_key_pem` - Verification key (SPKI, or PKCS#1 for RSA); None for sign-only | 验证公钥（SPKI，RSA 也可用 PKCS#1）；仅签名时为 None
+    pub fn from_pem(
+        algorithm: JwtAlgorithm,
+        private_key_pem: Option<&str>,
+        public_key_pem: Option<&str>,
+    ) -> SaTokenResult<Self> {
+        if algorithm.is_symmetric() {
+            return Err(SaTokenError::ConfigError(format!(
+                "{:?} is an HMAC algorithm and takes a secret, not PEM keys", algorithm
+            )));
+        }
+        if private_key_pem.is_none() && public_key_pem.is_none() {
+            return Err(SaTokenError::ConfigError(
+                "At least one of the JWT private or public key is required".to_string(),
+            ));
+        }
+
+        let pem_error = |kind: &str, e: jsonwebtoken::errors::Error| {
+            SaTokenError::ConfigError(format!("Invalid JWT {} key PEM for {:?}: {}", kind, algorithm, e))
+        };
+        let encoding_key = private_key_pem
+            .map(|pem| if algorithm.is_rsa() {
+                EncodingKey::from_rsa_pem(pem.as_bytes())
+            } else if algorithm.is_eddsa() {
+                EncodingKey::from_ed_pem(pem.as_bytes())
+            } else {
+                EncodingKey::from_ec_pem(pem.as_bytes())
+            })
+            .transpose()
+            .map_err(|e| pem_error("private", e))?;
+        let decoding_key = public_key_pem
+            .map(|pem| if algorithm.is_rsa() {
+                DecodingKey::from_rsa_pem(pem.as_bytes())
+            } else if algorithm.is_eddsa() {
+                DecodingKey::from_ed_pem(pem.as_bytes())
+            } else {
+                DecodingKey::from_ec_pem(pem.as_bytes())
+            })
+            .transpose()
+            .map_err(|e| pem_error("public", e))?;
+
+        Ok(Self {
+            encoding_key,
+            decoding_key,
+            algorithm,
+            issuer: None,
+            audience: None,
+            kid: None,
+            encryption: None,
+            claims_mapper: None,
+            validation: JwtValidationOptions::default(),
+        })
+    }
+
+    /// Create a JWT manager from the `jwt_*` configuration | 从 `jwt_*` 配置创建 JWT 管理器
+    ///
+    /// HMAC algorithms read `jwt_secret_key`; RSA / ECDSA algorithms read
+    /// `jwt_private_key` and `jwt_public_key`.
+    /// HMAC 算法读取 `jwt_secret_key`；RSA / ECDSA / EdDSA 算法读取 `jwt_private_key` 与 `jwt_public_key`。
+    pub fn from_config(config: &SaTokenConfig) -> SaTokenResult<Self> {
+        let algorithm = match config.jwt_algorithm.as_deref() {
+            Some(alg) => JwtAlgorithm::parse(alg).ok_or_else(|| {
+                SaTokenError::ConfigError(format!("Unsupported JWT algorithm: {}", alg))
+            })?,
+            None => JwtAlgorithm::HS256,
+        };
+
+        let mut manager = if algorithm.is_symmetric() {
+            let secret = config.jwt_secret_key.as_ref().ok_or_else(|| {
+                SaTokenError::ConfigError(format!("jwt_secret_key is required for {:?}", algorithm))
+            })?;
+            Self::with_algorithm(secret, algorithm)
+        } else {
+            Self::from_pem(algorithm, config.jwt_private_key.as_deref(), config.jwt_public_key.as_deref())?
+        };
+
+        if let Some(ref issuer) = config.jwt_issuer {
+            manager = manager.set_issuer(issuer);
+        }
+        if let Some(ref audience) = config.jwt_audience {
+            manager = manager.set_audience(audience);
+        }
+        if let Some(ref key) = config.jwt_encryption_key {
+            let algorithm = match config.jwt_encryption_algorithm.as_deref() {
+                Some(alg) => JweAlgorithm::parse(alg).ok_or_else(|| {
+                    SaTokenError::ConfigError(format!("Unsupported JWE algorithm: {}", alg))
+                })?,
+                None => JweAlgorithm::Dir,
+            };
+            let encryption = if algorithm.is_symmetric() {
+                JweEncryption::from_base64(algorithm, key)?
+            } else {
+                JweEncryption::from_pem(algorithm, key)?
+            };
+            manager = manager.set_encryption(encryption);
+        }
+        if let Some(ref mapper) = config.jwt_claims_mapper {
+            manager = manager.set_claims_mapper(mapper.clone());
+        }
+        Ok(manager)
+    }
+
+    /// Create a verify-only JWT manager from a public JWK (RSA, EC or OKP) | 从公开的 JWK（RSA、EC 或 OKP）创建仅验证的 JWT 管理器
+    ///
+    /// The algorithm comes from the JWK `alg` member, or is inferred from the key type
+    /// (RSA → RS256, P-256 → ES256, P-384 → ES384, Ed25519 → EdDSA). Symmetric `oct` keys are rejected.
+    /// 算法取自 JWK 的 `alg` 字段，缺失时按密钥类型推断（RSA → RS256，P-256 → ES256，P-384 → ES384，Ed25519 → EdDSA）。
+    /// 对称的 `oct` 密钥会被拒绝。
+    pub fn from_jwk(jwk: &Jwk) -> SaTokenResult<Self> {
+        let inferred = match &jwk.algorithm {
+            AlgorithmParameters::RSA(_) => JwtAlgorithm::RS256,
+            AlgorithmParameters::EllipticCurve(params) => match params.curve {
+                EllipticCurve::P256 => JwtAlgorithm::ES256,
+                EllipticCurve::P384 => JwtAlgorithm::ES384,
+                ref curve => {
+                    return Err(SaTokenError::InvalidToken(format!("Unsupported JWK curve: {:?}", curve)));
+                }
+            },
+            AlgorithmParameters::OctetKeyPair(params) => match params.curve {
+                EllipticCurve::Ed25519 => JwtAlgorithm::EdDSA,
+                ref curve => {
+                    return Err(SaTokenError::InvalidToken(format!("Unsupported JWK curve: {:?}", curve)));
+                }
+            },
+            _ => return Err(SaTokenError::InvalidToken("Only RSA, EC and OKP JWKs are supported".to_string())),
+        };
+        let algorithm = match jwk.common.key_algorithm {
+            Some(alg) => JwtAlgorithm::parse(&alg.to_string())
+                .filter(|alg| !alg.is_symmetric())
+                .ok_or_else(|| SaTokenError::InvalidToken(format!("Unsupported JWK algorithm: {}", alg)))?,
+            None => inferred,
+        };
+        if algorithm.is_rsa() != inferred.is_rsa() || algorithm.is_eddsa() != inferred.is_eddsa() {
+            return Err(SaTokenError::InvalidToken(format!("JWK key type does not match {:?}", algorithm)));
+        }
+
+        let decoding_key = DecodingKey::from_jwk(jwk)
+            .map_err(|e| SaTokenError::InvalidToken(format!("Invalid JWK: {}", e)))?;
+        Ok(Self {
+            encoding_key: None,
+            decoding_key: Some(decoding_key),
             algorithm,
             issuer: None,
             audience: None,
+            kid: jwk.common.key_id.clone(),
+            encryption: None,
+            claims_mapper: None,
+            validation: JwtValidationOptions::default(),
+        })
+    }
+
+    /// Generate a new Ed25519 key pair for `JwtAlgorithm::EdDSA` | 为 `JwtAlgorithm::EdDSA` 生成新的 Ed25519 密钥对
+    ///
+    /// # Returns | 返回
+    ///
+    /// `(private_key_pem, public_key_pem)` in PKCS#8 and SPKI form, ready for `from_pem`,
+    /// `jwt_private_key` / `jwt_public_key` or `JwksKeyRing::rotate`
+    /// PKCS#8 与 SPKI 格式的 `(私钥 PEM, 公钥 PEM)`，可直接用于 `from_pem`、`jwt_private_key` / `jwt_public_key`
+    /// 或 `JwksKeyRing::rotate`
+    pub fn generate_ed25519_key_pair() -> SaTokenResult<(String, String)> {
+        use ring::signature::KeyPair;
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng)
+            .map_err(|_| SaTokenError::InternalError("Failed to generate Ed25519 key".to_string()))?;
+        let key_pair = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .map_err(|_| SaTokenError::InternalError("Failed to parse generated Ed25519 key".to_string()))?;
+
+        // SPKI: SEQUENCE { SEQUENCE { OID 1.3.101.112 }, BIT STRING { public key } }
+        let mut spki = vec![0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+        spki.extend_from_slice(key_pair.public_key().as_ref());
+        Ok((to_pem("PRIVATE KEY", pkcs8.as_ref()), to_pem("PUBLIC KEY", &spki)))
+    }
+
+    /// Set the key ID written to the `kid` header | 设置写入 `kid` 头的密钥 ID
+    pub fn set_kid(mut self, kid: impl Into<String>) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    /// Key ID | 密钥 ID
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    /// Encrypt issued tokens as JWE and require JWE on validation | 签发的 token 加密为 JWE，验证时要求 JWE
+    ///
+    /// The signed JWT is nested inside the JWE, so the claims are both signed and confidential.
+    /// 签名后的 JWT 嵌套在 JWE 中，声明既有签名又被加密。
+    pub fn set_encryption(mut self, encryption: JweEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// JWE encryption, if any | JWE 加密配置
+    pub fn encryption(&self) -> Option<&JweEncryption> {
+        self.encryption.as_ref()
+    }
+
+    /// Register the `TokenInfo` ↔ claims mapping | 注册 `TokenInfo` 与声明的映射
+    pub fn set_claims_mapper(mut self, mapper: Arc<dyn ClaimsMapper>) -> Self {
+        self.claims_mapper = Some(mapper);
+        self
+    }
+
+    /// Claims mapping in use | 使用的声明映射
+    pub fn claims_mapper(&self) -> &dyn ClaimsMapper {
+        self.claims_mapper.as_deref().unwrap_or(&DefaultClaimsMapper)
+    }
+
+    /// Issue a JWT for a token info through the claims mapper | 通过声明映射为 token 信息签发 JWT
+    pub fn generate_for(&self, token_info: &TokenInfo) -> SaTokenResult<String> {
+        let claims = JwtClaims::from_map(self.claims_mapper().to_claims(token_info))?;
+        self.generate(&claims)
+    }
+
+    /// Validate a JWT and rebuild its token info through the claims mapper | 验证 JWT 并通过声明映射还原 token 信息
+    pub fn validate_to_token_info(&self, token: &str) -> SaTokenResult<TokenInfo> {
+        let claims = self.validate(token)?;
+        let mut token_info = self.claims_mapper().from_claims(claims.to_map());
+        token_info.token = TokenValue::new(token);
+        Ok(token_info)
+    }
+
+    /// Decrypt a JWE when encryption is on, otherwise return the token as is
+    /// 开启加密时解密 JWE，否则原样返回
+    fn unwrap_token(&self, token: &str) -> SaTokenResult<String> {
+        match &self.encryption {
+            Some(encryption) => encryption.decrypt(token),
+            None => Ok(token.to_string()),
         }
     }
 
+    /// Algorithm in use | 使用的算法
+    pub fn algorithm(&self) -> JwtAlgorithm {
+        self.algorithm
+    }
+
+    /// Whether this manager can issue tokens | 是否可以签发 token
+    pub fn can_sign(&self) -> bool {
+        self.encoding_key.is_some()
+    }
+
     /// Set issuer | 设置签发者
     pub fn set_issuer(mut self, issuer: impl Into<String>) -> Self {
         self.issuer = Some(issuer.into());
@@ -298,6 +896,17 @@ impl JwtManager {
         self
     }
 
+    /// Set how `validate` checks issuer, audience and timing claims | 设置 `validate` 对签发者、受众与时间声明的检查方式
+    pub fn set_validation_options(mut self, options: JwtValidationOptions) -> Self {
+        self.validation = options;
+        self
+    }
+
+    /// Validation options in use | 使用的验证选项
+    pub fn validation_options(&self) -> &JwtValidationOptions {
+        &self.validation
+    }
+
     /// Generate JWT token | 生成 JWT token
     ///
     /// # Arguments | 参数
@@ -319,12 +928,30 @@ impl JwtManager {
             final_claims.aud = self.audience.clone();
         }
 
-        let header = Header::new(self.algorithm.into());
-        let encoding_key = EncodingKey::from_secret(self.secret.as_bytes());
+        self.sign(&final_claims)
+    }
+
+    /// Sign an arbitrary claims set | 签名任意声明集
+    ///
+    /// Unlike `generate`, the configured issuer and audience are not filled in and every
+    /// field of `claims` is written as a top-level claim (e.g. OpenID Connect ID tokens).
+    /// 与 `generate` 不同，不会填充配置的签发者和受众，`claims` 的每个字段都作为顶层声明写入
+    /// （如 OpenID Connect ID Token）。
+    pub fn sign<T: Serialize>(&self, claims: &T) -> SaTokenResult<String> {
+        let mut header = Header::new(self.algorithm.into());
+        header.kid = self.kid.clone();
+        let encoding_key = self.encoding_key.as_ref().ok_or_else(|| {
+            SaTokenError::ConfigError("JWT manager has no signing key (verify-only)".to_string())
+        })?;
 
-        encode(&header, &final_claims, &encoding_key).map_err(|e| {
+        let token = encode(&header, claims, encoding_key).map_err(|e| {
             SaTokenError::InvalidToken(format!("Failed to generate JWT: {}", e))
-        })
+        })?;
+
+        match &self.encryption {
+            Some(encryption) => encryption.encrypt(&token, self.kid.as_deref()),
+            None => Ok(token),
+        }
     }
 
     /// Validate and parse JWT token | 验证并解析 JWT token
@@ -337,29 +964,48 @@ impl JwtManager {
     ///
     /// Decoded JWT claims | 解码的 JWT 声明
     pub fn validate(&self, token: &str) -> SaTokenResult<JwtClaims> {
+        self.validate_as(token)
+    }
+
+    /// Validate a JWT and decode its claims into `T` | 验证 JWT 并将声明解码为 `T`
+    ///
+    /// Applies the same signature, timing, issuer and audience checks as `validate`.
+    /// 执行与 `validate` 相同的签名、时间、签发者和受众检查。
+    pub fn validate_as<T: DeserializeOwned>(&self, token: &str) -> SaTokenResult<T> {
+        let options = &self.validation;
         let mut validation = Validation::new(self.algorithm.into());
 
-        // Explicitly enable expiration validation | 明确启用过期验证
+        // Expiration is always checked when present | 存在过期时间时总是验证
         validation.validate_exp = true;
-        
-        // Set leeway to 0 for strict validation | 设置时间偏差为0以进行严格验证
-        validation.leeway = 0;
+        if !options.require_exp {
+            validation.required_spec_claims.remove("exp");
+        }
+        validation.validate_nbf = options.validate_nbf;
+        validation.leeway = options.leeway;
 
-        // Configure validation | 配置验证
-        if let Some(ref iss) = self.issuer {
+        // Expected issuer and audience, falling back to the ones this manager issues with
+        // 期望的签发者与受众，未设置时使用本管理器签发时的值
+        if let Some(iss) = options.issuer.as_ref().or(self.issuer.as_ref()) {
             validation.set_issuer(&[iss]);
         }
-        if let Some(ref aud) = self.audience {
+        if !options.audience.is_empty() {
+            validation.set_audience(&options.audience);
+        } else if let Some(ref aud) = self.audience {
             validation.set_audience(&[aud]);
         }
 
-        let decoding_key = DecodingKey::from_secret(self.secret.as_bytes());
+        let decoding_key = self.decoding_key.as_ref().ok_or_else(|| {
+            SaTokenError::ConfigError("JWT manager has no verification key (sign-only)".to_string())
+        })?;
 
-        let token_data = decode::<JwtClaims>(token, &decoding_key, &validation).map_err(|e| {
+        let token = self.unwrap_token(token)?;
+        let token_data = decode::<T>(&token, decoding_key, &validation).map_err(|e| {
             match e.kind() {
-                jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
-                    SaTokenError::TokenExpired
-                }
+                ErrorKind::ExpiredSignature => SaTokenError::TokenExpired,
+                ErrorKind::InvalidIssuer => SaTokenError::JwtIssuerMismatch,
+                ErrorKind::InvalidAudience => SaTokenError::JwtAudienceMismatch,
+                ErrorKind::ImmatureSignature => SaTokenError::JwtNotYetValid,
+                ErrorKind::MissingRequiredClaim(claim) => SaTokenError::JwtMissingClaim(claim.clone()),
                 _ => SaTokenError::InvalidToken(format!("JWT validation failed: {}", e)),
             }
         })?;
@@ -369,16 +1015,18 @@ impl JwtManager {
 
     /// Decode JWT without validation (unsafe) | 不验证解码 JWT（不安全）
     ///
-    /// Warning: This does not validate the signature!
-    /// 警告：这不会验证签名！
+    /// Warning: This does not validate the signature! An encrypted token is still decrypted first.
+    /// 警告：这不会验证签名！加密的 token 仍会先解密。
     pub fn decode_without_validation(&self, token: &str) -> SaTokenResult<JwtClaims> {
         let mut validation = Validation::new(self.algorithm.into());
         validation.insecure_disable_signature_validation();
         validation.validate_exp = false;
 
-        let decoding_key = DecodingKey::from_secret(self.secret.as_bytes());
+        // The key is ignored once signature validation is disabled | 关闭签名验证后不会使用密钥
+        let decoding_key = DecodingKey::from_secret(&[]);
 
-        let token_data = decode::<JwtClaims>(token, &decoding_key, &validation).map_err(|e| {
+        let token = self.unwrap_token(token)?;
+        let token_data = decode::<JwtClaims>(&token, &decoding_key, &validation).map_err(|e| {
             SaTokenError::InvalidToken(format!("Failed to decode JWT: {}", e))
         })?;
 
@@ -416,10 +1064,32 @@ impl JwtManager {
     }
 }
 
+/// Encode DER as PEM with 64-character lines | 将 DER 编码为每行 64 字符的 PEM
+fn to_pem(label: &str, der: &[u8]) -> String {
+    use base64::Engine;
+
+    let body = base64::engine::general_purpose::STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Test-only key pairs | 仅用于测试的密钥对
+    const RSA_PRIVATE_PEM: &str = include_str!("testdata/rsa_private.pem");
+    const RSA_PUBLIC_PEM: &str = include_str!("testdata/rsa_public.pem");
+    const EC_PRIVATE_PEM: &str = include_str!("testdata/ec_private.pem");
+    const EC_PUBLIC_PEM: &str = include_str!("testdata/ec_public.pem");
+    const ED25519_PRIVATE_PEM: &str = include_str!("testdata/ed25519_private.pem");
+    const ED25519_PUBLIC_PEM: &str = include_str!("testdata/ed25519_public.pem");
+
     #[test]
     fn test_jwt_claims_creation() {
         let mut claims = JwtClaims::new("user_123");
@@ -525,5 +1195,180 @@ mod tests {
 
         assert_eq!(login_id, "user_123");
     }
-}
 
+    #[test]
+    fn test_jwt_asymmetric_keys() {
+        for (algorithm, private_pem, public_pem) in [
+            (JwtAlgorithm::RS256, RSA_PRIVATE_PEM, RSA_PUBLIC_PEM),
+            (JwtAlgorithm::ES256, EC_PRIVATE_PEM, EC_PUBLIC_PEM),
+        ] {
+            let signer = JwtManager::from_pem(algorithm, Some(private_pem), Some(public_pem)).unwrap();
+            let verifier = JwtManager::from_pem(algorithm, None, Some(public_pem)).unwrap();
+
+            let mut claims = JwtClaims::new("user_123");
+            claims.set_expiration(3600);
+            let token = signer.generate(&claims).unwrap();
+
+            // The resource server verifies with the public key alone | 资源服务器仅凭公钥验证
+            assert_eq!(verifier.validate(&token).unwrap().login_id, "user_123");
+            assert_eq!(verifier.extract_login_id(&token).unwrap(), "user_123");
+            assert!(!verifier.can_sign());
+            assert!(matches!(verifier.generate(&claims), Err(SaTokenError::ConfigError(_))));
+
+            // A token signed with the shared secret is rejected | 使用共享密钥签名的 token 被拒绝
+            let forged = JwtManager::new("test-secret-key").generate(&claims).unwrap();
+            assert!(verifier.validate(&forged).is_err());
+        }
+
+        // Key families must match the algorithm | 密钥类型必须与算法匹配
+        assert!(JwtManager::from_pem(JwtAlgorithm::RS256, None, Some(EC_PUBLIC_PEM)).is_err());
+        assert!(JwtManager::from_pem(JwtAlgorithm::HS256, None, Some(RSA_PUBLIC_PEM)).is_err());
+
+        let config = SaTokenConfig::builder()
+            .jwt_algorithm("es256")
+            .jwt_public_key(EC_PUBLIC_PEM)
+            .jwt_issuer("auth-server")
+            .build_config();
+        let verifier = JwtManager::from_config(&config).unwrap();
+        assert_eq!(verifier.algorithm(), JwtAlgorithm::ES256);
+        let signer = JwtManager::from_pem(JwtAlgorithm::ES256, Some(EC_PRIVATE_PEM), None)
+            .unwrap()
+            .set_issuer("auth-server");
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+        assert!(verifier.validate(&signer.generate(&claims).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_claims_mapper() {
+        // 租户与 scope 映射为独立声明 | Tenant and scopes mapped to their own claims
+        struct TenantClaimsMapper;
+
+        impl ClaimsMapper for TenantClaimsMapper {
+            fn to_claims(&self, token_info: &TokenInfo) -> Map<String, Value> {
+                let mut claims = DefaultClaimsMapper.to_claims(token_info);
+                let tenant = token_info.extra.get("tenant").cloned().unwrap_or(Value::Null);
+                claims.remove("tenant");
+                claims.insert("tenant_id".to_string(), tenant);
+                claims.insert("scopes".to_string(), serde_json::json!(["read", "write"]));
+                claims
+            }
+
+            fn from_claims(&self, mut claims: Map<String, Value>) -> TokenInfo {
+                let tenant = claims.remove("tenant_id");
+                let mut token_info = DefaultClaimsMapper.from_claims(claims);
+                if let Some(tenant) = tenant {
+                    token_info.extra.insert("tenant".to_string(), tenant);
+                }
+                token_info
+            }
+        }
+
+        let mut token_info = TokenInfo::new(TokenValue::new(""), "user_123");
+        token_info.login_type = "admin".to_string();
+        token_info.device = Some("ios".to_string());
+        token_info.expire_time = Some(Utc::now() + Duration::seconds(3600));
+        token_info.extra.insert("tenant".to_string(), serde_json::json!("acme"));
+
+        // 默认映射 | Default mapping
+        let manager = JwtManager::new("test-secret-key");
+        let token = manager.generate_for(&token_info).unwrap();
+        let decoded = manager.validate_to_token_info(&token).unwrap();
+        assert_eq!(decoded.token.as_str(), token);
+        assert_eq!(decoded.login_id, "user_123");
+        assert_eq!(decoded.login_type, "admin");
+        assert_eq!(decoded.device.as_deref(), Some("ios"));
+        assert_eq!(decoded.expire_time.map(|t| t.timestamp()), token_info.expire_time.map(|t| t.timestamp()));
+        assert_eq!(decoded.create_time.timestamp_millis(), token_info.create_time.timestamp_millis());
+        assert_eq!(decoded.extra.get("tenant"), Some(&serde_json::json!("acme")));
+
+        // 自定义映射 | Custom mapping
+        let manager = JwtManager::new("test-secret-key").set_claims_mapper(Arc::new(TenantClaimsMapper));
+        let token = manager.generate_for(&token_info).unwrap();
+        let claims = manager.validate(&token).unwrap().to_map();
+        assert_eq!(claims.get("tenant_id"), Some(&serde_json::json!("acme")));
+        assert!(!claims.contains_key("tenant"));
+        let decoded = manager.validate_to_token_info(&token).unwrap();
+        assert_eq!(decoded.extra.get("tenant"), Some(&serde_json::json!("acme")));
+        assert_eq!(decoded.extra.get("scopes"), Some(&serde_json::json!(["read", "write"])));
+
+        // 缺少 sub 的声明无效 | Claims without sub are invalid
+        assert!(JwtClaims::from_map(Map::new()).is_err());
+    }
+
+    #[test]
+    fn test_jwt_validation_options() {
+        let signer = JwtManager::new("test-secret-key");
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+        claims.set_issuer("https://auth.staging.example.com");
+        claims.set_audience("orders-api");
+        let token = signer.generate(&claims).unwrap();
+
+        let options = JwtValidationOptions::new().issuer("https://auth.example.com").audience(["orders-api"]);
+        let verifier = JwtManager::new("test-secret-key").set_validation_options(options.clone());
+        assert!(matches!(verifier.validate(&token), Err(SaTokenError::JwtIssuerMismatch)));
+
+        claims.set_issuer("https://auth.example.com");
+        claims.set_audience("billing-api");
+        let token = signer.generate(&claims).unwrap();
+        assert!(matches!(verifier.validate(&token), Err(SaTokenError::JwtAudienceMismatch)));
+        let verifier = JwtManager::new("test-secret-key")
+            .set_validation_options(options.clone().audience(["orders-api", "billing-api"]));
+        assert_eq!(verifier.validate(&token).unwrap().login_id, "user_123");
+
+        // nbf 在未来 | nbf in the future
+        claims.nbf = Some(Utc::now().timestamp() + 60);
+        let token = signer.generate(&claims).unwrap();
+        assert!(matches!(verifier.validate(&token), Err(SaTokenError::JwtNotYetValid)));
+        let lenient = JwtManager::new("test-secret-key")
+            .set_validation_options(verifier.validation_options().clone().leeway(120));
+        assert!(lenient.validate(&token).is_ok());
+
+        // 缺少 exp | Missing exp
+        let mut claims = JwtClaims::new("user_123");
+        claims.exp = None;
+        let token = signer.generate(&claims).unwrap();
+        assert!(matches!(signer.validate(&token), Err(SaTokenError::JwtMissingClaim(claim)) if claim == "exp"));
+        let no_exp = JwtManager::new("test-secret-key")
+            .set_validation_options(JwtValidationOptions::new().require_exp(false));
+        assert!(no_exp.validate(&token).is_ok());
+    }
+
+    #[test]
+    fn test_jwt_eddsa() {
+        let mut claims = JwtClaims::new("user_123");
+        claims.set_expiration(3600);
+
+        // PEM 密钥 | PEM keys
+        assert_eq!(JwtAlgorithm::parse("Ed25519"), Some(JwtAlgorithm::EdDSA));
+        let signer = JwtManager::from_pem(JwtAlgorithm::EdDSA, Some(ED25519_PRIVATE_PEM), None).unwrap();
+        let verifier = JwtManager::from_pem(JwtAlgorithm::EdDSA, None, Some(ED25519_PUBLIC_PEM)).unwrap();
+        let token = signer.generate(&claims).unwrap();
+        assert_eq!(verifier.validate(&token).unwrap().login_id, "user_123");
+        assert!(JwtManager::from_pem(JwtAlgorithm::EdDSA, None, Some(EC_PUBLIC_PEM)).is_err());
+
+        // 生成的密钥对 | Generated key pair
+        let (private_pem, public_pem) = JwtManager::generate_ed25519_key_pair().unwrap();
+        let signer = JwtManager::from_pem(JwtAlgorithm::EdDSA, Some(&private_pem), Some(&public_pem)).unwrap();
+        let token = signer.generate(&claims).unwrap();
+        assert_eq!(signer.validate(&token).unwrap().login_id, "user_123");
+        assert!(verifier.validate(&token).is_err());
+
+        // JWKS 导出 OKP 密钥 | JWKS exports an OKP key
+        let jwk = crate::token::jwks::jwk_from_pem("ed-1", JwtAlgorithm::EdDSA, &public_pem).unwrap();
+        let json = serde_json::to_value(&jwk).unwrap();
+        assert_eq!(json["kty"], "OKP");
+        assert_eq!(json["crv"], "Ed25519");
+        assert_eq!(json["alg"], "EdDSA");
+        let from_jwk = JwtManager::from_jwk(&jwk).unwrap();
+        assert_eq!(from_jwk.algorithm(), JwtAlgorithm::EdDSA);
+        assert_eq!(from_jwk.validate(&token).unwrap().login_id, "user_123");
+
+        let key_ring = crate::token::JwksKeyRing::new();
+        key_ring.rotate("ed-1", JwtAlgorithm::EdDSA, &private_pem, &public_pem).unwrap();
+        let token = key_ring.generate(&claims).unwrap();
+        assert_eq!(key_ring.validate(&token).unwrap().login_id, "user_123");
+        assert!(key_ring.jwks_json().contains("\"OKP\""));
+    }
+}
@@ -2,16 +2,59 @@
 //
 //! Token 管理模块
 
+use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 pub mod generator;
 pub mod validator;
 pub mod jwt;
+pub mod jwks;
+pub mod jwe;
 
-pub use generator::TokenGenerator;
+pub use generator::{TokenGenerator, DefaultTokenGenerator};
 pub use validator::TokenValidator;
-pub use jwt::{JwtManager, JwtClaims, JwtAlgorithm};
+pub use jwt::{JwtManager, JwtClaims, JwtAlgorithm, JwtValidationOptions, ClaimsMapper, DefaultClaimsMapper};
+pub use jwks::{JwksKeyRing, JwksClient, RemoteJwksValidator, Jwk, JwkSet};
+#[cfg(feature = "remote-jwks")]
+pub use jwks::HttpJwksClient;
+pub use jwe::{JweAlgorithm, JweEncryption};
+
+/// 默认账号类型 | Default account type
+pub const DEFAULT_ACCOUNT_TYPE: &str = "default";
+
+/// 按账号类型为登录 ID 添加命名空间 | Namespace a login ID by account type
+/// 
+/// 默认账号类型保持原样，其他账号类型追加 `:{account_type}` 后缀，
+/// 这样 admin 的 `1001` 与 user 的 `1001` 不会共用存储键、Session 或在线索引。
+/// 登录 ID 与账号类型中的 `%` 和 `:` 会被转义为 `%25` 和 `%3A`，分隔符 `:` 因此不会产生歧义。
+/// The default account type is kept as-is, other account types get a `:{account_type}` suffix,
+/// so admin `1001` and user `1001` never share storage keys, sessions or online indexes.
+/// `%` and `:` in login IDs and account types are escaped as `%25` and `%3A`, so the `:` separator is unambiguous.
+/// 
+/// # 示例 | Example
+/// ```
+/// use sa_token_core::token::namespaced_login_id;
+/// assert_eq!(namespaced_login_id("1001", "default"), "1001");
+/// assert_eq!(namespaced_login_id("1001", "admin"), "1001:admin");
+/// assert_eq!(namespaced_login_id("1001:admin", "default"), "1001%3Aadmin");
+/// ```
+pub fn namespaced_login_id(login_id: &str, account_type: &str) -> String {
+    if account_type.is_empty() || account_type == DEFAULT_ACCOUNT_TYPE {
+        escape_key_part(login_id).into_owned()
+    } else {
+        format!("{}:{}", escape_key_part(login_id), escape_key_part(account_type))
+    }
+}
+
+/// 转义键片段中的 `%` 与 `:` | Escape `%` and `:` in a key part
+pub(crate) fn escape_key_part(part: &str) -> std::borrow::Cow<'_, str> {
+    if part.contains(['%', ':']) {
+        part.replace('%', "%25").replace(':', "%3A").into()
+    } else {
+        part.into()
+    }
+}
 
 /// Token 值
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -58,7 +101,9 @@ impl std::fmt::Display for TokenValue {
 /// - `last_active_time`: 最后活跃时间 | Last active time
 /// - `expire_time`: 过期时间（None 表示永不过期）| Expiration time (None means never expires)
 /// - `device`: 设备标识 | Device identifier
+/// - `last_ip`: 最近一次请求的客户端 IP | Client IP of the latest request
 /// - `extra_data`: 额外数据 | Extra data
+/// - `extra`: 登录时附加的键值数据 | Key-value data attached at login
 /// - `nonce`: 防重放攻击的一次性令牌 | One-time token for replay attack prevention
 /// - `refresh_token`: 用于刷新的长期令牌 | Long-term token for refresh
 /// - `refresh_token_expire_time`: Refresh Token 过期时间 | Refresh token expiration time
@@ -85,9 +130,27 @@ pub struct TokenInfo {
     /// 设备标识 | Device identifier
     pub device: Option<String>,
     
+    /// 登录时的客户端 IP（开启 `bind_ip` 时记录）| Client IP at login (recorded when `bind_ip` is on)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login_ip: Option<String>,
+    
+    /// 登录时的设备指纹（开启 `bind_fingerprint` 时记录）| Device fingerprint at login (recorded when `bind_fingerprint` is on)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    
+    /// 最近一次请求的客户端 IP（由中间件按 `activity_record_interval` 写回）
+    /// Client IP of the latest request (written back by the middleware per `activity_record_interval`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_ip: Option<String>,
+    
     /// 额外数据 | Extra data
     pub extra_data: Option<serde_json::Value>,
     
+    /// 登录时附加的键值数据（租户 ID、语言、客户端版本等），随 token 一起读取，无需查询 Session
+    /// Key-value data attached at login (tenant id, locale, client version...), read with the token without a session lookup
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>,
+    
     /// Nonce（用于防重放攻击）| Nonce (for replay attack prevention)
     pub nonce: Option<String>,
     
@@ -104,12 +167,16 @@ impl TokenInfo {
         Self {
             token,
             login_id: login_id.into(),
-            login_type: "default".to_string(),
+            login_type: DEFAULT_ACCOUNT_TYPE.to_string(),
             create_time: now,
             last_active_time: now,
             expire_time: None,
             device: None,
+            login_ip: None,
+            fingerprint: None,
+            last_ip: None,
             extra_data: None,
+            extra: HashMap::new(),
             nonce: None,
             refresh_token: None,
             refresh_token_expire_time: None,
@@ -127,6 +194,36 @@ impl TokenInfo {
     pub fn update_active_time(&mut self) {
         self.last_active_time = Utc::now();
     }
+    
+    /// 账号类型（即 `login_type`）| Account type (alias of `login_type`)
+    pub fn account_type(&self) -> &str {
+        &self.login_type
+    }
+    
+    /// 是否为后台任务签发的离线令牌 | Whether this is an off-session token minted for background work
+    pub fn is_off_session(&self) -> bool {
+        self.login_type == crate::off_session::OFF_SESSION_LOGIN_TYPE
+    }
+    
+    /// 按账号类型命名空间化的登录 ID | Login ID namespaced by account type
+    pub fn namespaced_login_id(&self) -> String {
+        namespaced_login_id(&self.login_id, &self.login_type)
+    }
+    
+    /// 获取附加数据 | Get an extra value
+    pub fn get_extra(&self, key: &str) -> Option<&serde_json::Value> {
+        self.extra.get(key)
+    }
+    
+    /// 设置附加数据 | Set an extra value
+    pub fn set_extra(&mut self, key: impl Into<String>, value: serde_json::Value) {
+        self.extra.insert(key.into(), value);
+    }
+    
+    /// 将登录 ID 转换为指定类型 | Convert the login ID to the given type
+    pub fn login_id_as<T: serde::de::DeserializeOwned>(&self) -> crate::SaTokenResult<T> {
+        crate::util::parse_login_id(&self.login_id)
+    }
 }
 
 /// Token 签名
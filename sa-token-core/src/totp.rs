@@ -0,0 +1,342 @@
+// Author: 金书记
+//
+//! TOTP 双因素认证 | TOTP Two-Factor Authentication
+//!
+//! 基于 RFC 6238（HMAC-SHA1，默认 6 位、30 秒步长），兼容 Google Authenticator、Microsoft Authenticator 等应用。
+//! Implements RFC 6238 (HMAC-SHA1, 6 digits and 30-second steps by default), compatible with
+//! Google Authenticator, Microsoft Authenticator and similar apps.
+//!
+//! ```text
+//! 绑定 | Enroll
+//!   enroll(subject, account) → { secret, otpauth:// URI（二维码 | QR code）}
+//!     └─→ sa:2fa:pending:{subject}（TTL 10 分钟 | 10 minutes）
+//!   confirm(subject, code)    → sa:2fa:secret:{subject}
+//!
+//! 校验 | Verify
+//!   verify(subject, code)
+//!     ├─→ 已锁定 → AccountLocked | locked → AccountLocked (sa:2fa-guard:lock:{subject})
+//!     ├─→ 当前步长 ± skew 内任一验证码匹配，否则计一次失败 | any code within the current step ± skew matches, otherwise a failure is counted
+//!     └─→ 原子地占用该步长，同一验证码只能成功一次 | the step is claimed atomically, so a code succeeds only once
+//!           (sa:2fa:used:{step}:{subject})
+//! ```
+//!
+//! 默认 5 分钟内错误 5 次锁定 5 分钟，再次锁定时长翻倍（最长 1 小时），防止在线穷举 6 位验证码。
+//! By default 5 wrong codes within 5 minutes lock the subject for 5 minutes, doubling on each further
+//! lockout up to an hour, which stops online brute force of the 6-digit code.
+//!
+//! 管理器在校验成功后把 token 标记为“本会话已完成双因素认证”，配合 `Guard::two_factor()` 使用。
+//! After a successful verification the manager marks the token as two-factor verified for the
+//! rest of the session, which `Guard::two_factor()` checks.
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sa_token_adapter::storage::SaStorage;
+use crate::error::{SaTokenError, SaTokenResult};
+use crate::login_guard::LoginGuard;
+use crate::secure::SaSecureUtil;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// 待确认密钥的有效期（秒）| Lifetime of a pending secret in seconds
+const PENDING_TIMEOUT: u64 = 600;
+
+/// 绑定信息 | Enrollment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotpEnrollment {
+    /// Base32 密钥，供手动输入 | Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` 地址，生成二维码供扫描 | `otpauth://` URI to render as a QR code
+    pub provisioning_uri: String,
+}
+
+/// TOTP 管理器 | TOTP manager
+#[derive(Clone)]
+pub struct TotpManager {
+    storage: Arc<dyn SaStorage>,
+    issuer: String,
+    digits: u32,
+    period: u64,
+    skew: u64,
+    guard: LoginGuard,
+}
+
+impl TotpManager {
+    /// 创建管理器（6 位、30 秒步长、前后各容忍 1 个步长）
+    /// Create a manager (6 digits, 30-second steps, one step of drift either way)
+    ///
+    /// # 参数 | Parameters
+    /// * `storage` - 密钥存储 | Secret storage
+    /// * `issuer` - 显示在验证器应用中的发行方 | Issuer shown in the authenticator app
+    pub fn new(storage: Arc<dyn SaStorage>, issuer: impl Into<String>) -> Self {
+        Self {
+            guard: LoginGuard::new(storage.clone(), 5, 300, 300, 3600).namespace("2fa-guard"),
+            storage,
+            issuer: issuer.into(),
+            digits: 6,
+            period: 30,
+            skew: 1,
+        }
+    }
+
+    /// 设置错误验证码的锁定策略（参数同 `LoginGuard::new`，`max_failures` 为 0 时关闭）
+    /// Set the lockout policy for wrong codes (parameters as in `LoginGuard::new`, `max_failures` 0 disables it)
+    pub fn attempt_limit(mut self, max_failures: u32, window: i64, lockout: i64, max_lockout: i64) -> Self {
+        self.guard = LoginGuard::new(self.storage.clone(), max_failures, window, lockout, max_lockout).namespace("2fa-guard");
+        self
+    }
+
+    /// 设置验证码位数（6 到 8）| Set the number of digits (6 to 8)
+    pub fn digits(mut self, digits: u32) -> Self {
+        self.digits = digits.clamp(6, 8);
+        self
+    }
+
+    /// 设置步长（秒）| Set the step length in seconds
+    pub fn period(mut self, seconds: u64) -> Self {
+        self.period = seconds.max(1);
+        self
+    }
+
+    /// 设置允许的时钟漂移步数 | Set how many steps of clock drift are accepted
+    pub fn skew(mut self, steps: u64) -> Self {
+        self.skew = steps;
+        self
+    }
+
+    /// 生成 160 位随机密钥（Base32）| Generate a random 160-bit secret (Base32)
+    pub fn generate_secret() -> String {
+        let mut buf = [0u8; 20];
+        getrandom::fill(&mut buf).expect("OS random source is unavailable");
+        BASE32_NOPAD.encode(&buf)
+    }
+
+    /// 生成 `otpauth://` 地址 | Build the `otpauth://` provisioning URI
+    ///
+    /// # 参数 | Parameters
+    /// * `secret` - Base32 密钥 | Base32 secret
+    /// * `account` - 显示在验证器应用中的账号名 | Account name shown in the authenticator app
+    pub fn provisioning_uri(&self, secret: &str, account: &str) -> String {
+        let issuer = urlencoding::encode(&self.issuer);
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            issuer,
+            urlencoding::encode(account),
+            secret,
+            issuer,
+            self.digits,
+            self.period,
+        )
+    }
+
+    /// 计算指定时间的验证码 | Compute the code at a given time
+    ///
+    /// # 参数 | Parameters
+    /// * `secret` - Base32 密钥 | Base32 secret
+    /// * `timestamp` - Unix 时间戳（秒）| Unix timestamp in seconds
+    pub fn code_at(&self, secret: &str, timestamp: u64) -> SaTokenResult<String> {
+        let key = Self::decode_secret(secret)?;
+        Ok(self.code_for_step(&key, timestamp / self.period))
+    }
+
+    /// 校验验证码（不防重放）| Verify a code without replay protection
+    pub fn verify_code(&self, secret: &str, code: &str) -> SaTokenResult<bool> {
+        let key = Self::decode_secret(secret)?;
+        Ok(self.matching_step(&key, code).is_some())
+    }
+
+    /// 开始绑定：生成待确认的密钥 | Start enrollment with a pending secret
+    ///
+    /// # 参数 | Parameters
+    /// * `subject` - 密钥归属，通常为登录 ID | Secret owner, usually the login ID
+    /// * `account` - 显示在验证器应用中的账号名 | Account name shown in the authenticator app
+    pub async fn enroll(&self, subject: &str, account: &str) -> SaTokenResult<TotpEnrollment> {
+        let secret = Self::generate_secret();
+        self.storage.set(&Self::pending_key(subject), &secret, Some(Duration::from_secs(PENDING_TIMEOUT))).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        Ok(TotpEnrollment {
+            provisioning_uri: self.provisioning_uri(&secret, account),
+            secret,
+        })
+    }
+
+    /// 用验证器应用的第一个验证码确认绑定 | Confirm enrollment with the first code from the app
+    pub async fn confirm(&self, subject: &str, code: &str) -> SaTokenResult<()> {
+        let pending_key = Self::pending_key(subject);
+        let secret = self.storage.get(&pending_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::TwoFactorNotEnabled)?;
+        let key = Self::decode_secret(&secret)?;
+        let step = self.matching_step(&key, code).ok_or(SaTokenError::InvalidTotpCode)?;
+
+        self.storage.set(&Self::secret_key(subject), &secret, None).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.claim_step(subject, step).await?;
+        self.storage.delete(&pending_key).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 是否已绑定 | Whether the subject has a confirmed secret
+    pub async fn is_enabled(&self, subject: &str) -> SaTokenResult<bool> {
+        self.storage.exists(&Self::secret_key(subject)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    /// 校验验证码，同一步长的验证码只能使用一次 | Verify a code; each step's code can only be used once
+    ///
+    /// 未绑定时返回 `TwoFactorNotEnabled`，验证码错误或已使用时返回 `InvalidTotpCode`，
+    /// 错误次数过多被锁定时返回 `AccountLocked`
+    /// Returns `TwoFactorNotEnabled` without a confirmed secret, `InvalidTotpCode` for a wrong or reused
+    /// code and `AccountLocked` once too many wrong codes locked the subject
+    pub async fn verify(&self, subject: &str, code: &str) -> SaTokenResult<()> {
+        let secret = self.storage.get(&Self::secret_key(subject)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .ok_or(SaTokenError::TwoFactorNotEnabled)?;
+        if let Some(remaining) = self.guard.is_locked(subject).await? {
+            return Err(SaTokenError::AccountLocked(remaining));
+        }
+        let key = Self::decode_secret(&secret)?;
+        let Some(step) = self.matching_step(&key, code) else {
+            self.guard.record_failure(subject).await?;
+            return Err(SaTokenError::InvalidTotpCode);
+        };
+
+        let last_step = self.storage.get(&Self::step_key(subject)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?
+            .and_then(|v| v.parse::<u64>().ok());
+        if last_step.is_some_and(|last| step <= last) {
+            return Err(SaTokenError::InvalidTotpCode);
+        }
+        self.claim_step(subject, step).await?;
+        self.guard.clear_failures(subject).await
+    }
+
+    /// 解除绑定 | Remove the secret
+    pub async fn disable(&self, subject: &str) -> SaTokenResult<()> {
+        let keys = [Self::secret_key(subject), Self::pending_key(subject), Self::step_key(subject)];
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.storage.mdel(&keys).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        self.guard.unlock(subject).await
+    }
+
+    /// 解除错误验证码导致的锁定 | Lift a lockout caused by wrong codes
+    pub async fn unlock(&self, subject: &str) -> SaTokenResult<()> {
+        self.guard.unlock(subject).await
+    }
+
+    fn matching_step(&self, key: &[u8], code: &str) -> Option<u64> {
+        let current = Utc::now().timestamp().max(0) as u64 / self.period;
+        let first = current.saturating_sub(self.skew);
+        (first..=current + self.skew)
+            .find(|step| SaSecureUtil::constant_time_eq(self.code_for_step(key, *step).as_bytes(), code.as_bytes()))
+    }
+
+    fn code_for_step(&self, key: &[u8], step: u64) -> String {
+        let mut mac = HmacSha1::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        // RFC 4226 动态截断 | RFC 4226 dynamic truncation
+        let offset = (digest[19] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+        format!("{:0width$}", binary % 10u32.pow(self.digits), width = self.digits as usize)
+    }
+
+    /// 原子地占用步长，已被使用时返回 `InvalidTotpCode` | Claim a step atomically, `InvalidTotpCode` if it was already used
+    async fn claim_step(&self, subject: &str, step: u64) -> SaTokenResult<()> {
+        let ttl = Duration::from_secs(self.period * (2 * self.skew + 1));
+        let claimed = self.storage.set_nx(&Self::used_key(subject, step), "1", Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
+        if !claimed {
+            return Err(SaTokenError::InvalidTotpCode);
+        }
+        // 记录最近使用的步长，更早的验证码不再有效 | Remember the latest step so older codes stop working
+        self.storage.set(&Self::step_key(subject), &step.to_string(), Some(ttl)).await
+            .map_err(|e| SaTokenError::StorageError(e.to_string()))
+    }
+
+    fn decode_secret(secret: &str) -> SaTokenResult<Vec<u8>> {
+        let normalized: String = secret.chars()
+            .filter(|c| !c.is_whitespace() && *c != '=')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        BASE32_NOPAD.decode(normalized.as_bytes())
+            .map_err(|e| SaTokenError::InvalidTotpSecret(e.to_string()))
+    }
+
+    fn secret_key(subject: &str) -> String {
+        format!("sa:2fa:secret:{}", subject)
+    }
+
+    fn pending_key(subject: &str) -> String {
+        format!("sa:2fa:pending:{}", subject)
+    }
+
+    fn step_key(subject: &str) -> String {
+        format!("sa:2fa:step:{}", subject)
+    }
+
+    fn used_key(subject: &str, step: u64) -> String {
+        format!("sa:2fa:used:{}:{}", step, subject)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_token_storage_memory::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_totp_enroll_and_verify() {
+        let totp = TotpManager::new(Arc::new(MemoryStorage::new()), "Sa Token");
+
+        // RFC 6238 附录 B 的 SHA1 测试向量 | SHA1 test vectors from RFC 6238 appendix B
+        let rfc_secret = BASE32_NOPAD.encode(b"12345678901234567890");
+        let rfc = totp.clone().digits(8);
+        assert_eq!(rfc.code_at(&rfc_secret, 59).unwrap(), "94287082");
+        assert_eq!(rfc.code_at(&rfc_secret, 1111111109).unwrap(), "07081804");
+        assert_eq!(totp.code_at(&rfc_secret, 59).unwrap(), "287082");
+
+        let enrollment = totp.enroll("1001", "alice@example.com").await.unwrap();
+        assert!(enrollment.provisioning_uri.starts_with("otpauth://totp/Sa%20Token:alice%40example.com?secret="));
+        assert!(matches!(totp.verify("1001", "000000").await, Err(SaTokenError::TwoFactorNotEnabled)));
+
+        let now = Utc::now().timestamp() as u64;
+        assert!(totp.confirm("1001", "bad").await.is_err());
+        totp.confirm("1001", &totp.code_at(&enrollment.secret, now).unwrap()).await.unwrap();
+        assert!(totp.is_enabled("1001").await.unwrap());
+
+        // 确认用过的步长不能再用，下一步长的验证码在漂移窗口内
+        // The step used to confirm cannot be reused; the next step's code is within the drift window
+        let current = totp.code_at(&enrollment.secret, now).unwrap();
+        assert!(matches!(totp.verify("1001", &current).await, Err(SaTokenError::InvalidTotpCode)));
+        totp.verify("1001", &totp.code_at(&enrollment.secret, now + 30).unwrap()).await.unwrap();
+
+        totp.disable("1001").await.unwrap();
+        assert!(!totp.is_enabled("1001").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_totp_lockout_and_replay() {
+        let totp = TotpManager::new(Arc::new(MemoryStorage::new()), "Sa Token").attempt_limit(3, 300, 60, 600);
+        let enrollment = totp.enroll("1001", "alice").await.unwrap();
+        let now = Utc::now().timestamp() as u64;
+        totp.confirm("1001", &totp.code_at(&enrollment.secret, now).unwrap()).await.unwrap();
+
+        // 连续错误后锁定，正确验证码也被拒绝 | Repeated wrong codes lock the subject, even the right code is refused
+        let next = totp.code_at(&enrollment.secret, now + 30).unwrap();
+        for _ in 0..3 {
+            assert!(matches!(totp.verify("1001", "bad").await, Err(SaTokenError::InvalidTotpCode)));
+        }
+        assert!(matches!(totp.verify("1001", &next).await, Err(SaTokenError::AccountLocked(_))));
+        totp.unlock("1001").await.unwrap();
+
+        // 同一验证码并发提交只有一次成功 | Concurrent submissions of the same code succeed only once
+        let (a, b) = tokio::join!(totp.verify("1001", &next), totp.verify("1001", &next));
+        assert!(a.is_ok() != b.is_ok());
+    }
+}
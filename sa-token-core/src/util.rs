@@ -21,17 +21,31 @@
 //! ```
 
 use std::sync::Arc;
+use std::collections::HashMap;
 use std::fmt::Display;
-use once_cell::sync::OnceCell;
+use std::future::Future;
+use std::sync::RwLock;
+use once_cell::sync::{Lazy, OnceCell};
+use sa_token_adapter::context::SaRequest;
+use serde::de::DeserializeOwned;
 use crate::{SaTokenManager, SaTokenResult, SaTokenError};
-use crate::token::{TokenValue, TokenInfo};
+use crate::token::{TokenValue, TokenInfo, DEFAULT_ACCOUNT_TYPE};
+use crate::remember_me::RememberedSession;
+use crate::refresh::RotatedTokens;
 use crate::session::SaSession;
 use crate::context::SaTokenContext;
-use crate::event::{SaTokenEventBus, SaTokenListener};
+use crate::event::{SaTokenEvent, SaTokenEventBus, SaTokenListener};
+use crate::permission::{MenuItem, PermissionProvider, PermissionSnapshot, RoleHierarchy};
+use crate::stp_logic::StpLogic;
+use crate::login_guard::LoginFailure;
+use crate::totp::TotpEnrollment;
 
 /// 全局 SaTokenManager 实例
 static GLOBAL_MANAGER: OnceCell<Arc<SaTokenManager>> = OnceCell::new();
 
+/// 已注册的账号体系 | Registered account systems
+static LOGICS: Lazy<RwLock<HashMap<String, Arc<StpLogic>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
 /// LoginId trait - 支持任何可以转换为字符串的类型作为登录 ID
 /// 
 /// 自动实现了 String, &str, i32, i64, u32, u64 等常用类型
@@ -46,6 +60,23 @@ impl<T: Display> LoginId for T {
     }
 }
 
+/// 将字符串形式的登录 ID 转换为指定类型（如 i64、Uuid）
+/// 
+/// 先按 JSON 字符串解析（适用于 String、Uuid 等），再按 JSON 值解析（适用于数字等）
+/// 
+/// # 示例
+/// ```
+/// use sa_token_core::util::parse_login_id;
+/// 
+/// let id: i64 = parse_login_id("10001").unwrap();
+/// assert_eq!(id, 10001);
+/// ```
+pub fn parse_login_id<T: DeserializeOwned>(login_id: &str) -> SaTokenResult<T> {
+    serde_json::from_value::<T>(serde_json::Value::String(login_id.to_string()))
+        .or_else(|_| serde_json::from_str::<T>(login_id))
+        .map_err(|_| SaTokenError::LoginIdTypeMismatch(std::any::type_name::<T>().to_string()))
+}
+
 /// StpUtil - 权限认证工具类
 /// 
 /// 提供便捷的认证和授权操作方法，类似于 Java 版 sa-token 的 StpUtil
@@ -107,6 +138,55 @@ impl StpUtil {
         Self::event_bus().register(listener);
     }
     
+    // ==================== 多账号体系 | Multiple Account Systems ====================
+    
+    /// 注册账号体系，同一账号类型重复注册时覆盖 | Register an account system, replacing one with the same type
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let config = SaTokenConfig::builder().token_name("admin-token").timeout(3600).build_config();
+    /// StpUtil::register_logic(StpLogic::new("admin", SaTokenManager::new(storage, config)));
+    /// ```
+    pub fn register_logic(logic: StpLogic) -> Arc<StpLogic> {
+        let logic = Arc::new(logic);
+        LOGICS.write().unwrap_or_else(|e| e.into_inner())
+            .insert(logic.login_type().to_string(), logic.clone());
+        logic
+    }
+    
+    /// 获取已注册的账号体系 | Get a registered account system
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// StpUtil::logic("admin")?.check_login_current().await?;
+    /// ```
+    pub fn logic(login_type: &str) -> SaTokenResult<Arc<StpLogic>> {
+        LOGICS.read().unwrap_or_else(|e| e.into_inner())
+            .get(login_type)
+            .cloned()
+            .ok_or_else(|| SaTokenError::ConfigError(format!("account system '{}' is not registered", login_type)))
+    }
+    
+    /// 批量注册账号体系，返回按账号类型索引的表（供插件状态构建器使用）
+    /// Register several account systems and return them keyed by account type (used by plugin state builders)
+    pub fn register_logics(logics: Vec<StpLogic>) -> HashMap<String, Arc<StpLogic>> {
+        logics.into_iter()
+            .map(|logic| {
+                let logic = Self::register_logic(logic);
+                (logic.login_type().to_string(), logic)
+            })
+            .collect()
+    }
+    
+    /// 按每个已注册账号体系的 token 名称读取请求中的 token（供插件填充上下文）
+    /// Read the request's token of every registered account system by its token name (used by plugins to fill the context)
+    pub fn collect_typed_tokens<R: SaRequest + ?Sized>(req: &R) -> HashMap<String, TokenValue> {
+        LOGICS.read().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(|(login_type, logic)| logic.collect_token(req).map(|token| (login_type.clone(), token)))
+            .collect()
+    }
+    
     // ==================== 登录相关 ====================
     
     /// 会话登录
@@ -124,8 +204,14 @@ impl StpUtil {
         Self::get_manager().login(login_id.to_login_id()).await
     }
 
-    pub async fn login_with_type(login_id: impl LoginId, _login_type: impl Into<String>) -> SaTokenResult<TokenValue> {
-        Self::get_manager().login(login_id.to_login_id()).await
+    /// 以指定账号类型登录 | Login as the given account type
+    /// 
+    /// 不同账号类型的相同 login_id 互不影响
+    /// The same login_id under different account types do not affect each other
+    pub async fn login_with_type(login_id: impl LoginId, login_type: impl Into<String>) -> SaTokenResult<TokenValue> {
+        Self::get_manager()
+            .login_with_options(login_id.to_login_id(), Some(login_type.into()), None, None, None, None)
+            .await
     }
     
     /// 登录并设置额外数据 | Login with extra data
@@ -152,11 +238,9 @@ impl StpUtil {
     
     /// 会话登出
     pub async fn logout(token: &TokenValue) -> SaTokenResult<()> {
-        tracing::debug!("开始执行 logout，token: {}", token);
         let result = Self::get_manager().logout(token).await;
-        match &result {
-            Ok(_) => tracing::debug!("logout 执行成功，token: {}", token),
-            Err(e) => tracing::debug!("logout 执行失败，token: {}, 错误: {}", token, e),
+        if let Err(e) = &result {
+            tracing::debug!(target: crate::logging::TARGET_SESSION, token = %token, error = %e, "logout failed");
         }
         result
     }
@@ -180,11 +264,36 @@ impl StpUtil {
         manager.kick_out(login_id).await
     }
     
+    /// 踢人下线（根据登录ID和账号类型）
+    pub async fn kick_out_by_type(login_id: impl LoginId, account_type: &str) -> SaTokenResult<()> {
+        Self::get_manager().kick_out_by_type(&login_id.to_login_id(), account_type).await
+    }
+    
+    /// 踢下线某个设备上的全部会话，返回被踢下线的 token 数量
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let kicked = StpUtil::kick_out_device("user_123", "ios").await?;
+    /// ```
+    pub async fn kick_out_device(login_id: impl LoginId, device: &str) -> SaTokenResult<usize> {
+        Self::get_manager().kick_out_device(&login_id.to_login_id(), device).await
+    }
+    
+    /// 踢下线单个 token（推送踢出消息并触发 `KickOut` 事件）
+    pub async fn kick_out_token(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().kick_out_token(token).await
+    }
+    
     /// 强制登出（根据登录ID）
     pub async fn logout_by_login_id(login_id: impl LoginId) -> SaTokenResult<()> {
         Self::get_manager().logout_by_login_id(&login_id.to_login_id()).await
     }
     
+    /// 强制登出（根据登录ID和账号类型）
+    pub async fn logout_by_login_id_and_type(login_id: impl LoginId, account_type: &str) -> SaTokenResult<()> {
+        Self::get_manager().logout_by_login_id_and_type(&login_id.to_login_id(), account_type).await
+    }
+    
     /// 根据 token 登出（别名方法，更直观）
     pub async fn logout_by_token(token: &TokenValue) -> SaTokenResult<()> {
         Self::logout(token).await
@@ -205,6 +314,16 @@ impl StpUtil {
         ctx.token.ok_or(SaTokenError::NotLogin)
     }
     
+    /// 获取当前请求所属的租户，未开启多租户或未解析到租户时返回 None
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let tenant = StpUtil::get_tenant();
+    /// ```
+    pub fn get_tenant() -> Option<String> {
+        crate::tenant::current()
+    }
+    
     /// 当前会话登出（无参数，从上下文获取 token）
     /// 
     /// # 示例
@@ -214,14 +333,7 @@ impl StpUtil {
     /// ```
     pub async fn logout_current() -> SaTokenResult<()> {
         let token = Self::get_token_value()?;
-        tracing::debug!("成功获取 token: {}", token);
-        
-        let result = Self::logout(&token).await;
-        match &result {
-            Ok(_) => tracing::debug!("logout_current 执行成功，token: {}", token),
-            Err(e) => tracing::debug!("logout_current 执行失败，token: {}, 错误: {}", token, e),
-        }
-        result
+        Self::logout(&token).await
     }
     
     /// 检查当前会话是否登录（无参数，返回 bool）
@@ -257,12 +369,19 @@ impl StpUtil {
     
     /// 获取当前会话的 login_id（String 类型，无参数）
     /// 
+    /// 处于身份切换中时返回切换后的 login_id
+    /// 
     /// # 示例
     /// ```rust,ignore
     /// // 在请求处理函数中
     /// let login_id = StpUtil::get_login_id_as_string().await?;
     /// ```
     pub async fn get_login_id_as_string() -> SaTokenResult<String> {
+        if let Some(ctx) = SaTokenContext::get_current()
+            && ctx.is_switched()
+            && let Some(login_id) = ctx.login_id {
+            return Ok(login_id);
+        }
         let token = Self::get_token_value()?;
         Self::get_login_id(&token).await
     }
@@ -280,6 +399,33 @@ impl StpUtil {
             .map_err(|_| SaTokenError::LoginIdNotNumber)
     }
     
+    /// 获取当前会话的 login_id（i64 类型，`get_login_id_as_long` 的别名）
+    pub async fn get_login_id_as_i64() -> SaTokenResult<i64> {
+        Self::get_login_id_as_long().await
+    }
+    
+    /// 获取当前会话的 login_id（Uuid 类型，无参数）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let user_id: uuid::Uuid = StpUtil::get_login_id_as_uuid().await?;
+    /// ```
+    pub async fn get_login_id_as_uuid() -> SaTokenResult<uuid::Uuid> {
+        Self::get_login_id_as::<uuid::Uuid>().await
+    }
+    
+    /// 获取当前会话的 login_id 并转换为任意可反序列化的类型（无参数）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let user_id: u64 = StpUtil::get_login_id_as().await?;
+    /// let user_id = StpUtil::get_login_id_as::<UserId>().await?;
+    /// ```
+    pub async fn get_login_id_as<T: DeserializeOwned>() -> SaTokenResult<T> {
+        let login_id_str = Self::get_login_id_as_string().await?;
+        parse_login_id(&login_id_str)
+    }
+    
     /// 获取当前会话的 token 信息（无参数）
     /// 
     /// # 示例
@@ -294,6 +440,343 @@ impl StpUtil {
         ctx.token_info.ok_or(SaTokenError::NotLogin)
     }
     
+    // ==================== 身份切换 | Identity Switching ====================
+    
+    /// 临时切换为其他 login_id 执行闭包，结束后自动恢复原身份
+    /// 
+    /// 切换期间 `get_login_id_as_string` 及权限/角色检查都以切换后的 login_id 为准，
+    /// 切换时会发布 `SwitchTo` 事件用于审计。切换只对 `f` 返回的 future 生效，
+    /// 同一线程上的其他任务不受影响
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 管理员以用户 10044 的身份执行操作
+    /// let orders = StpUtil::switch_to(10044, || async {
+    ///     list_my_orders().await
+    /// }).await?;
+    /// ```
+    pub async fn switch_to<F, Fut, T>(login_id: impl LoginId, f: F) -> SaTokenResult<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let ctx = SaTokenContext::get_current()
+            .ok_or(SaTokenError::NotLogin)?;
+        let token = ctx.token.clone().ok_or(SaTokenError::NotLogin)?;
+        
+        // 发起切换的始终是最初登录的身份
+        let operator = match ctx.switched_from {
+            Some(login_id) => login_id,
+            None => Self::get_login_id(&token).await?,
+        };
+        let switch_to = login_id.to_login_id();
+        
+        let mut event = SaTokenEvent::switch_to(&operator, token.as_str(), &switch_to);
+        if let Some(info) = &ctx.token_info {
+            event = event.with_login_type(&info.login_type);
+        }
+        Self::event_bus().publish(event).await;
+        
+        Ok(SaTokenContext::switch_scope(switch_to, f()).await)
+    }
+    
+    /// 当前上下文是否处于身份切换中
+    pub fn is_switch() -> bool {
+        SaTokenContext::get_current().is_some_and(|ctx| ctx.is_switched())
+    }
+    
+    // ==================== 冻结 | Freeze ====================
+    
+    /// 冻结 token（不删除，冻结期间校验返回 `TokenFrozen`）
+    /// 
+    /// # 参数
+    /// - `token`: 要冻结的 token
+    /// - `freeze_time`: 冻结时长（秒），`-1` 表示直到手动解冻，0 与其他负数返回 `ConfigError`
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 等待风控审核期间冻结 token
+    /// StpUtil::freeze_token(&token, -1).await?;
+    /// ```
+    pub async fn freeze_token(token: &TokenValue, freeze_time: i64) -> SaTokenResult<()> {
+        Self::get_manager().freeze_token(token, freeze_time).await
+    }
+    
+    /// 解冻 token
+    pub async fn unfreeze_token(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().unfreeze_token(token).await
+    }
+    
+    /// 检查 token 是否被冻结
+    pub async fn is_frozen(token: &TokenValue) -> bool {
+        Self::get_manager().is_frozen(token).await
+    }
+    
+    // ==================== 登录保护 | Login Guard ====================
+    
+    /// 记录一次登录失败，窗口内失败次数达到 `login_max_failures` 后锁定账号
+    /// 
+    /// 锁定期间 `StpUtil::login` 返回 `AccountLocked`，锁定时长按指数退避递增
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// if !verify_password(&username, &password) {
+    ///     StpUtil::record_failed_login(&username).await?;
+    ///     return Err(ApiError::Unauthorized(messages::INVALID_CREDENTIALS.to_string()));
+    /// }
+    /// let token = StpUtil::login(&username).await?;
+    /// ```
+    pub async fn record_failed_login(login_id: impl LoginId) -> SaTokenResult<LoginFailure> {
+        Self::get_manager().record_failed_login(&login_id.to_login_id()).await
+    }
+    
+    /// 查询账号是否被锁定，返回剩余锁定秒数
+    pub async fn is_locked(login_id: impl LoginId) -> SaTokenResult<Option<u64>> {
+        Self::get_manager().is_locked(&login_id.to_login_id()).await
+    }
+    
+    /// 解除账号锁定并重置退避等级
+    pub async fn unlock_login(login_id: impl LoginId) -> SaTokenResult<()> {
+        Self::get_manager().unlock_login(&login_id.to_login_id()).await
+    }
+    
+    /// 记录指定账号类型下的一次登录失败
+    pub async fn record_failed_login_by_type(login_id: impl LoginId, account_type: &str) -> SaTokenResult<LoginFailure> {
+        Self::get_manager().record_failed_login_by_type(&login_id.to_login_id(), account_type).await
+    }
+    
+    /// 查询指定账号类型下的账号是否被锁定
+    pub async fn is_locked_by_type(login_id: impl LoginId, account_type: &str) -> SaTokenResult<Option<u64>> {
+        Self::get_manager().is_locked_by_type(&login_id.to_login_id(), account_type).await
+    }
+    
+    // ==================== 软登出 | Soft Logout ====================
+    
+    /// 软登出：保留会话 `grace_time` 秒，期间校验返回 `ReauthRequired`
+    /// 
+    /// # 参数
+    /// - `token`: 已登录的 token
+    /// - `grace_time`: 宽限期（秒），超时后彻底登出
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 空闲超时：要求重新输入密码，10 分钟内可恢复原会话
+    /// StpUtil::soft_logout(&token, 600).await?;
+    /// 
+    /// // 用户重新输入密码并校验通过后
+    /// StpUtil::reauthenticate(&token).await?;
+    /// ```
+    pub async fn soft_logout(token: &TokenValue, grace_time: i64) -> SaTokenResult<()> {
+        Self::get_manager().soft_logout(token, grace_time).await
+    }
+    
+    /// 重新认证后恢复软登出的 token（宽限期已过返回 `TokenExpired`）
+    pub async fn reauthenticate(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().reauthenticate(token).await
+    }
+    
+    /// 检查 token 是否处于软登出状态
+    pub async fn is_soft_logged_out(token: &TokenValue) -> bool {
+        Self::get_manager().is_soft_logged_out(token).await
+    }
+    
+    // ==================== 二级认证 | Second-level Authentication ====================
+    
+    /// 开启二级认证（safe_time 秒内有效）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 用户再次输入密码后
+    /// let token = StpUtil::open_safe(&token, 300).await?;
+    /// ```
+    /// 
+    /// 返回之后应使用的 token，开启 `regenerate_token_on_elevation` 时为新 token
+    pub async fn open_safe(token: &TokenValue, safe_time: i64) -> SaTokenResult<TokenValue> {
+        Self::get_manager().open_safe(token, safe_time).await
+    }
+    
+    /// 检查是否处于二级认证有效期内
+    pub async fn is_safe(token: &TokenValue) -> bool {
+        Self::get_manager().is_safe(token).await
+    }
+    
+    /// 校验二级认证，未通过则返回 `SaTokenError::NotSafe`
+    pub async fn check_safe(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().check_safe(token).await
+    }
+    
+    /// 校验当前会话的二级认证（无参数，从上下文获取 token）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 在修改密码、提现等敏感接口中
+    /// StpUtil::check_safe_current().await?;
+    /// ```
+    pub async fn check_safe_current() -> SaTokenResult<()> {
+        let token = Self::get_token_value()?;
+        Self::check_safe(&token).await
+    }
+    
+    /// 关闭二级认证
+    pub async fn close_safe(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().close_safe(token).await
+    }
+    
+    // ==================== 登录链接 | Login Links ====================
+    
+    /// 签发一次性登录链接 token（默认账号类型），`ttl` 秒内有效
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let link = StpUtil::create_login_link(&user_id, 900).await?;
+    /// send_mail(&email, &format!("https://example.com/magic?token={}", link)).await?;
+    /// 
+    /// // 用户点击链接后
+    /// let token = StpUtil::consume_login_link(&query.token).await?;
+    /// ```
+    pub async fn create_login_link(login_id: impl LoginId, ttl: i64) -> SaTokenResult<String> {
+        Self::get_manager().create_login_link(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE, ttl).await
+    }
+    
+    /// 使用登录链接登录，链接随即作废；过期返回 `LoginLinkInvalid`，重复使用返回 `LoginLinkReused`
+    pub async fn consume_login_link(link_token: &str) -> SaTokenResult<TokenValue> {
+        Self::get_manager().consume_login_link(link_token).await
+    }
+    
+    /// 作废尚未使用的登录链接
+    pub async fn revoke_login_link(link_token: &str) -> SaTokenResult<()> {
+        Self::get_manager().revoke_login_link(link_token).await
+    }
+    
+    // ==================== 会话固定防护 | Session Fixation Protection ====================
+    
+    /// 重新生成 token：签发新的 token 值并迁移会话数据，旧 token 立即失效
+    /// 
+    /// 权限变化（如提升为管理员）后调用，防止会话固定攻击；
+    /// 二级认证与双因素认证可通过 `regenerate_token_on_elevation` 自动执行
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// StpUtil::add_role(&user_id, "admin").await?;
+    /// let token = StpUtil::regenerate_token(&token).await?;
+    /// // 把新 token 返回给客户端
+    /// ```
+    pub async fn regenerate_token(token: &TokenValue) -> SaTokenResult<TokenValue> {
+        Self::get_manager().regenerate_token(token).await
+    }
+    
+    // ==================== 记住我 | Remember Me ====================
+    
+    /// 为会话签发 remember-me token，需配置 `remember_me_timeout`
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let token = StpUtil::login(&user_id).await?;
+    /// if req.remember {
+    ///     let remember = StpUtil::remember_me(&token).await?;
+    ///     response.insert_header("Set-Cookie", StpUtil::get_manager().remember_me_cookie(&remember));
+    /// }
+    /// ```
+    pub async fn remember_me(token: &TokenValue) -> SaTokenResult<String> {
+        Self::get_manager().remember_me(token).await
+    }
+    
+    /// 用 remember-me token 重建会话，并轮换 remember-me token
+    pub async fn restore_session(remember_token: &str) -> SaTokenResult<RememberedSession> {
+        Self::get_manager().restore_session(remember_token).await
+    }
+    
+    /// 作废 remember-me token（当前设备登出时使用）
+    pub async fn forget_me(remember_token: &str) -> SaTokenResult<()> {
+        Self::get_manager().forget_me(remember_token).await
+    }
+    
+    /// 撤销账号的全部 remember-me token（`logout_by_login_id` 会自动调用）
+    pub async fn revoke_remember_tokens(login_id: impl LoginId) -> SaTokenResult<()> {
+        Self::get_manager().revoke_remember_tokens(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    // ==================== 刷新令牌 | Refresh Tokens ====================
+    
+    /// 为会话签发 refresh token
+    pub async fn create_refresh_token(token: &TokenValue) -> SaTokenResult<String> {
+        Self::get_manager().create_refresh_token(token).await
+    }
+    
+    /// 轮换刷新：返回新的会话 token 与新的 refresh token，旧 refresh token 作废
+    /// 
+    /// 已使用过的 refresh token 再次出现时视为被盗，整个 token 家族被撤销并返回 `RefreshTokenReused`
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let rotated = StpUtil::refresh_with_rotation(&body.refresh_token).await?;
+    /// Json(json!({ "token": rotated.access_token.as_str(), "refresh_token": rotated.refresh_token }))
+    /// ```
+    pub async fn refresh_with_rotation(refresh_token: &str) -> SaTokenResult<RotatedTokens> {
+        Self::get_manager().refresh_with_rotation(refresh_token).await
+    }
+    
+    // ==================== 双因素认证 | Two-Factor Authentication ====================
+    
+    /// 开始绑定 TOTP，返回密钥与 `otpauth://` 地址（生成二维码供验证器应用扫描）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let enrollment = StpUtil::enroll_two_factor(&token).await?;
+    /// // 展示 enrollment.provisioning_uri 的二维码，用户输入第一个验证码后
+    /// StpUtil::confirm_two_factor(&token, &code).await?;
+    /// ```
+    pub async fn enroll_two_factor(token: &TokenValue) -> SaTokenResult<TotpEnrollment> {
+        Self::get_manager().enroll_two_factor(token).await
+    }
+    
+    /// 确认 TOTP 绑定，并将当前会话标记为已完成双因素认证，返回之后应使用的 token
+    pub async fn confirm_two_factor(token: &TokenValue, code: &str) -> SaTokenResult<TokenValue> {
+        Self::get_manager().confirm_two_factor(token, code).await
+    }
+    
+    /// 校验 TOTP 验证码，通过后本会话视为已完成双因素认证
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let token = StpUtil::login(&user_id).await?;
+    /// if StpUtil::is_two_factor_enabled(&user_id).await? {
+    ///     // 返回 token，前端再提交验证码
+    ///     let token = StpUtil::verify_two_factor(&token, &code).await?;
+    /// }
+    /// ```
+    /// 
+    /// 返回之后应使用的 token，开启 `regenerate_token_on_elevation` 时为新 token
+    pub async fn verify_two_factor(token: &TokenValue, code: &str) -> SaTokenResult<TokenValue> {
+        Self::get_manager().verify_two_factor(token, code).await
+    }
+    
+    /// 账号是否已绑定 TOTP（默认账号类型）
+    pub async fn is_two_factor_enabled(login_id: impl LoginId) -> SaTokenResult<bool> {
+        Self::get_manager().is_two_factor_enabled(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE).await
+    }
+    
+    /// 本会话是否已完成双因素认证
+    pub async fn is_two_factor_verified(token: &TokenValue) -> bool {
+        Self::get_manager().is_two_factor_verified(token).await
+    }
+    
+    /// 校验双因素认证，未通过则返回 `SaTokenError::TwoFactorRequired`
+    pub async fn check_two_factor(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().check_two_factor(token).await
+    }
+    
+    /// 校验当前会话的双因素认证（无参数，从上下文获取 token）
+    pub async fn check_two_factor_current() -> SaTokenResult<()> {
+        let token = Self::get_token_value()?;
+        Self::check_two_factor(&token).await
+    }
+    
+    /// 解除当前账号的 TOTP 绑定
+    pub async fn disable_two_factor(token: &TokenValue) -> SaTokenResult<()> {
+        Self::get_manager().disable_two_factor(token).await
+    }
+    
     // ==================== Token 验证 ====================
     
     /// 检查当前 token 是否已登录
@@ -371,6 +854,20 @@ impl StpUtil {
         }
     }
     
+    /// 获取账号的最后活跃时间（最近一次登录的 token），未登录时返回 None
+    /// 
+    /// 活跃时间由中间件按 `activity_record_interval` 写回，可用于"活跃会话"页面与清理空闲会话
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// if let Some(at) = StpUtil::get_last_active(10001).await? {
+    ///     println!("last active {}", at);
+    /// }
+    /// ```
+    pub async fn get_last_active(login_id: impl LoginId) -> SaTokenResult<Option<chrono::DateTime<chrono::Utc>>> {
+        Self::get_manager().get_last_active(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE).await
+    }
+    
     /// 根据登录 ID 获取所有在线的 token 列表（支持多设备登录）
     /// 
     /// # 示例
@@ -386,7 +883,7 @@ impl StpUtil {
         match manager.storage.get(&key).await {
             Ok(Some(tokens_str)) => {
                 let token_strings: Vec<String> = serde_json::from_str(&tokens_str)
-                    .map_err(|e| SaTokenError::SerializationError(e))?;
+                    .map_err(SaTokenError::SerializationError)?;
                 Ok(token_strings.into_iter().map(TokenValue::new).collect())
             }
             Ok(None) => Ok(Vec::new()),
@@ -401,6 +898,11 @@ impl StpUtil {
         Self::get_manager().get_session(&login_id.to_login_id()).await
     }
     
+    /// 获取指定账号类型的 Session
+    pub async fn get_session_by_type(login_id: impl LoginId, account_type: &str) -> SaTokenResult<SaSession> {
+        Self::get_manager().get_session_by_type(&login_id.to_login_id(), account_type).await
+    }
+    
     /// 保存 Session
     pub async fn save_session(session: &SaSession) -> SaTokenResult<()> {
         Self::get_manager().save_session(session).await
@@ -454,9 +956,17 @@ impl StpUtil {
         login_id: impl LoginId,
         permissions: Vec<String>,
     ) -> SaTokenResult<()> {
-        let manager = Self::get_manager();
-        let mut map = manager.user_permissions.write().await;
-        map.insert(login_id.to_login_id(), permissions);
+        Self::get_manager().set_permissions(&login_id.to_login_id(), permissions).await;
+        Ok(())
+    }
+    
+    /// 为指定账号类型下的用户设置权限
+    pub async fn set_permissions_by_type(
+        login_id: impl LoginId,
+        account_type: &str,
+        permissions: Vec<String>,
+    ) -> SaTokenResult<()> {
+        Self::get_manager().set_permissions_by_type(&login_id.to_login_id(), account_type, permissions).await;
         Ok(())
     }
     
@@ -467,7 +977,7 @@ impl StpUtil {
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
         let mut map = manager.user_permissions.write().await;
-        let login_id_str = login_id.to_login_id();
+        let login_id_str = SaTokenManager::grant_key(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE);
         let permissions = map.entry(login_id_str).or_insert_with(Vec::new);
         let perm = permission.into();
         if !permissions.contains(&perm) {
@@ -483,7 +993,7 @@ impl StpUtil {
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
         let mut map = manager.user_permissions.write().await;
-        if let Some(permissions) = map.get_mut(&login_id.to_login_id()) {
+        if let Some(permissions) = map.get_mut(&SaTokenManager::grant_key(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE)) {
             permissions.retain(|p| p != permission);
         }
         Ok(())
@@ -493,15 +1003,13 @@ impl StpUtil {
     pub async fn clear_permissions(login_id: impl LoginId) -> SaTokenResult<()> {
         let manager = Self::get_manager();
         let mut map = manager.user_permissions.write().await;
-        map.remove(&login_id.to_login_id());
+        map.remove(&SaTokenManager::grant_key(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE));
         Ok(())
     }
     
     /// 获取用户的所有权限
     pub async fn get_permissions(login_id: impl LoginId) -> Vec<String> {
-        let manager = Self::get_manager();
-        let map = manager.user_permissions.read().await;
-        map.get(&login_id.to_login_id()).cloned().unwrap_or_default()
+        Self::get_manager().get_permissions(&login_id.to_login_id()).await
     }
     
     /// 检查用户是否拥有指定权限
@@ -509,25 +1017,40 @@ impl StpUtil {
         login_id: impl LoginId,
         permission: &str,
     ) -> bool {
-        let manager = Self::get_manager();
-        let map = manager.user_permissions.read().await;
-        if let Some(permissions) = map.get(&login_id.to_login_id()) {
-            // 精确匹配
-            if permissions.contains(&permission.to_string()) {
-                return true;
-            }
-            
-            // 通配符匹配（例如 admin:* 匹配 admin:read）
-            for perm in permissions {
-                if perm.ends_with(":*") {
-                    let prefix = &perm[..perm.len() - 2];
-                    if permission.starts_with(prefix) {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+        Self::get_manager().has_permission(&login_id.to_login_id(), permission).await
+    }
+    
+    /// 注册权限数据提供者，之后的权限与角色检查会自动调用它
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// StpUtil::set_permission_provider(Arc::new(DbPermissionProvider::new(pool)));
+    /// assert!(StpUtil::has_permission("1001", "user:read").await);
+    /// ```
+    pub fn set_permission_provider(provider: Arc<dyn PermissionProvider>) {
+        Self::get_manager().set_permission_provider(provider);
+    }
+    
+    /// 使用户的权限与角色缓存失效
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// // 后台修改了用户的角色后
+    /// StpUtil::invalidate_permission_cache("1001").await;
+    /// ```
+    pub async fn invalidate_permission_cache(login_id: impl LoginId) {
+        Self::get_manager().invalidate_permission_cache(&login_id.to_login_id()).await;
+    }
+    
+    /// 设置角色层级，`None` 表示关闭继承
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// StpUtil::set_role_hierarchy(Some(RoleHierarchy::parse("admin > manager > user")?));
+    /// assert!(StpUtil::has_role("admin_user", "user").await);
+    /// ```
+    pub fn set_role_hierarchy(hierarchy: Option<RoleHierarchy>) {
+        Self::get_manager().set_role_hierarchy(hierarchy);
     }
     
     /// 检查用户是否拥有所有指定权限（AND 逻辑）
@@ -535,13 +1058,9 @@ impl StpUtil {
         login_id: impl LoginId,
         permissions: &[&str],
     ) -> bool {
-        let login_id_str = login_id.to_login_id();
-        for permission in permissions {
-            if !Self::has_permission(&login_id_str, permission).await {
-                return false;
-            }
-        }
-        true
+        Self::get_manager().has_permissions_bulk(&login_id.to_login_id(), permissions).await
+            .into_iter()
+            .all(|granted| granted)
     }
     
     /// 检查用户是否拥有所有指定权限（别名，AND 逻辑）
@@ -557,13 +1076,9 @@ impl StpUtil {
         login_id: impl LoginId,
         permissions: &[&str],
     ) -> bool {
-        let login_id_str = login_id.to_login_id();
-        for permission in permissions {
-            if Self::has_permission(&login_id_str, permission).await {
-                return true;
-            }
-        }
-        false
+        Self::get_manager().has_permissions_bulk(&login_id.to_login_id(), permissions).await
+            .into_iter()
+            .any(|granted| granted)
     }
     
     /// 检查用户是否拥有任一指定权限（别名，OR 逻辑）
@@ -574,6 +1089,72 @@ impl StpUtil {
         Self::has_any_permission(login_id, permissions).await
     }
     
+    /// 批量检查当前会话的权限（无参数获取 login_id），结果与 `permissions` 一一对应
+    /// 
+    /// 只加载一次权限列表，适合一次计算几十个菜单项的可见性；未登录时全部为 false
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let visible = StpUtil::check_permissions_bulk(&["menu:user", "menu:order", "menu:system"]).await;
+    /// ```
+    pub async fn check_permissions_bulk(permissions: &[&str]) -> Vec<bool> {
+        let Ok(login_id) = Self::get_login_id_as_string().await else {
+            return vec![false; permissions.len()];
+        };
+        let login_type = Self::get_token_info_current()
+            .map(|info| info.login_type.clone())
+            .unwrap_or_else(|_| DEFAULT_ACCOUNT_TYPE.to_string());
+        Self::get_manager().has_permissions_bulk_by_type(&login_id, &login_type, permissions).await
+    }
+    
+    /// 获取当前会话缺少的权限（保持传入顺序）
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let missing = StpUtil::get_missing_permissions(&["order:read", "order:refund"]).await;
+    /// if !missing.is_empty() {
+    ///     return Err(SaTokenError::PermissionDeniedDetail(missing.join(",")));
+    /// }
+    /// ```
+    pub async fn get_missing_permissions(permissions: &[&str]) -> Vec<String> {
+        let granted = Self::check_permissions_bulk(permissions).await;
+        permissions.iter()
+            .zip(granted)
+            .filter(|(_, granted)| !granted)
+            .map(|(permission, _)| permission.to_string())
+            .collect()
+    }
+    
+    /// 一次加载指定账号类型下用户的权限与角色，之后在内存中检查
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let snapshot = StpUtil::get_permission_snapshot_by_type("10001", "default").await;
+    /// if snapshot.has_any(&["order:refund", "order:*"]) && snapshot.has_role("manager") {
+    ///     // ...
+    /// }
+    /// ```
+    pub async fn get_permission_snapshot_by_type(login_id: impl LoginId, account_type: &str) -> PermissionSnapshot {
+        PermissionSnapshot::load(Self::get_manager(), &login_id.to_login_id(), account_type).await
+    }
+    
+    /// 按当前会话的权限过滤菜单 / 路由清单，未登录时只保留无需权限的项
+    /// 
+    /// # 示例
+    /// ```rust,ignore
+    /// let menu: Vec<MenuItem> = serde_json::from_str(include_str!("menu.json"))?;
+    /// let visible = StpUtil::get_menu(&menu).await;
+    /// ```
+    pub async fn get_menu(items: &[MenuItem]) -> Vec<MenuItem> {
+        let Ok(login_id) = Self::get_login_id_as_string().await else {
+            return crate::permission::filter_menu(items, &|_| false);
+        };
+        let login_type = Self::get_token_info_current()
+            .map(|info| info.login_type.clone())
+            .unwrap_or_else(|_| DEFAULT_ACCOUNT_TYPE.to_string());
+        Self::get_manager().filter_menu_by_type(&login_id, &login_type, items).await
+    }
+    
     /// 检查权限，如果没有则抛出异常
     pub async fn check_permission(
         login_id: impl LoginId,
@@ -594,9 +1175,17 @@ impl StpUtil {
         login_id: impl LoginId,
         roles: Vec<String>,
     ) -> SaTokenResult<()> {
-        let manager = Self::get_manager();
-        let mut map = manager.user_roles.write().await;
-        map.insert(login_id.to_login_id(), roles);
+        Self::get_manager().set_roles(&login_id.to_login_id(), roles).await;
+        Ok(())
+    }
+    
+    /// 为指定账号类型下的用户设置角色
+    pub async fn set_roles_by_type(
+        login_id: impl LoginId,
+        account_type: &str,
+        roles: Vec<String>,
+    ) -> SaTokenResult<()> {
+        Self::get_manager().set_roles_by_type(&login_id.to_login_id(), account_type, roles).await;
         Ok(())
     }
     
@@ -607,7 +1196,7 @@ impl StpUtil {
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
         let mut map = manager.user_roles.write().await;
-        let login_id_str = login_id.to_login_id();
+        let login_id_str = SaTokenManager::grant_key(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE);
         let roles = map.entry(login_id_str).or_insert_with(Vec::new);
         let r = role.into();
         if !roles.contains(&r) {
@@ -623,7 +1212,7 @@ impl StpUtil {
     ) -> SaTokenResult<()> {
         let manager = Self::get_manager();
         let mut map = manager.user_roles.write().await;
-        if let Some(roles) = map.get_mut(&login_id.to_login_id()) {
+        if let Some(roles) = map.get_mut(&SaTokenManager::grant_key(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE)) {
             roles.retain(|r| r != role);
         }
         Ok(())
@@ -633,15 +1222,13 @@ impl StpUtil {
     pub async fn clear_roles(login_id: impl LoginId) -> SaTokenResult<()> {
         let manager = Self::get_manager();
         let mut map = manager.user_roles.write().await;
-        map.remove(&login_id.to_login_id());
+        map.remove(&SaTokenManager::grant_key(&login_id.to_login_id(), DEFAULT_ACCOUNT_TYPE));
         Ok(())
     }
     
     /// 获取用户的所有角色
     pub async fn get_roles(login_id: impl LoginId) -> Vec<String> {
-        let manager = Self::get_manager();
-        let map = manager.user_roles.read().await;
-        map.get(&login_id.to_login_id()).cloned().unwrap_or_default()
+        Self::get_manager().get_roles(&login_id.to_login_id()).await
     }
     
     /// 检查用户是否拥有指定角色
@@ -649,13 +1236,7 @@ impl StpUtil {
         login_id: impl LoginId,
         role: &str,
     ) -> bool {
-        let manager = Self::get_manager();
-        let map = manager.user_roles.read().await;
-        if let Some(roles) = map.get(&login_id.to_login_id()) {
-            roles.contains(&role.to_string())
-        } else {
-            false
-        }
+        Self::get_manager().has_role(&login_id.to_login_id(), role).await
     }
     
     /// 检查用户是否拥有所有指定角色（AND 逻辑）
@@ -758,7 +1339,7 @@ impl StpUtil {
         // 保存更新后的 token 信息
         let key = format!("sa:token:{}", token.as_str());
         let value = serde_json::to_string(&token_info)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         let timeout = std::time::Duration::from_secs(timeout_seconds as u64);
         manager.storage.set(&key, &value, Some(timeout)).await
@@ -784,7 +1365,7 @@ impl StpUtil {
         
         let key = format!("sa:token:{}", token.as_str());
         let value = serde_json::to_string(&token_info)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         manager.storage.set(&key, &value, manager.config.timeout_duration()).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
@@ -802,6 +1383,61 @@ impl StpUtil {
         Ok(token_info.extra_data)
     }
     
+    /// 设置 Token 的单个附加数据 | Set a single extra value of a token
+    /// 
+    /// # 参数 | Arguments
+    /// * `token` - Token值 | Token value
+    /// * `key` - 键 | Key
+    /// * `value` - 值 | Value
+    pub async fn set_extra(
+        token: &TokenValue,
+        key: impl Into<String>,
+        value: serde_json::Value,
+    ) -> SaTokenResult<()> {
+        Self::get_manager().set_token_extra(token, key, value).await
+    }
+    
+    /// 获取当前会话的单个附加数据（从上下文读取，不查询存储）
+    /// Get a single extra value of the current session (read from the context, no storage lookup)
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let tenant_id = StpUtil::get_extra("tenant_id")?;
+    /// ```
+    pub fn get_extra(key: &str) -> SaTokenResult<Option<serde_json::Value>> {
+        let token_info = Self::get_token_info_current()?;
+        Ok(token_info.get_extra(key).cloned())
+    }
+    
+    /// 获取指定 Token 的单个附加数据 | Get a single extra value of a token
+    pub async fn get_extra_by_token(token: &TokenValue, key: &str) -> SaTokenResult<Option<serde_json::Value>> {
+        let token_info = Self::get_manager().get_token_info(token).await?;
+        Ok(token_info.get_extra(key).cloned())
+    }
+    
+    /// 按已索引的会话属性列出会话 | List sessions by an indexed session attribute
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let sessions = StpUtil::list_sessions_by_attribute("department", &json!("sales")).await?;
+    /// ```
+    pub async fn list_sessions_by_attribute(
+        attribute: &str,
+        value: &serde_json::Value,
+    ) -> SaTokenResult<Vec<TokenInfo>> {
+        Self::get_manager().list_sessions_by_attribute(attribute, value).await
+    }
+    
+    /// 登出某个已索引会话属性值下的所有会话 | Log out all sessions with an indexed attribute value
+    /// 
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let revoked = StpUtil::logout_by_attribute("tenant_id", &json!(42)).await?;
+    /// ```
+    pub async fn logout_by_attribute(attribute: &str, value: &serde_json::Value) -> SaTokenResult<usize> {
+        Self::get_manager().logout_by_attribute(attribute, value).await
+    }
+    
     // ==================== 链式调用 | Chain Call ====================
     
     /// 创建 Token 构建器，用于链式调用 | Create token builder for chain calls
@@ -813,6 +1449,7 @@ impl StpUtil {
     /// // 链式调用示例
     /// let token = StpUtil::builder("user_123")
     ///     .extra_data(json!({"ip": "192.168.1.1"}))
+    ///     .extra("tenant_id", json!(42))
     ///     .device("pc")
     ///     .login_type("admin")
     ///     .login()
@@ -827,6 +1464,7 @@ impl StpUtil {
 pub struct TokenBuilder {
     login_id: String,
     extra_data: Option<serde_json::Value>,
+    extra: HashMap<String, serde_json::Value>,
     device: Option<String>,
     login_type: Option<String>,
 }
@@ -837,6 +1475,7 @@ impl TokenBuilder {
         Self {
             login_id,
             extra_data: None,
+            extra: HashMap::new(),
             device: None,
             login_type: None,
         }
@@ -848,6 +1487,12 @@ impl TokenBuilder {
         self
     }
     
+    /// 添加单个附加数据 | Add a single extra value
+    pub fn extra(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.extra.insert(key.into(), value);
+        self
+    }
+    
     /// 设置设备信息 | Set device info
     pub fn device(mut self, device: impl Into<String>) -> Self {
         self.device = Some(device.into());
@@ -881,6 +1526,8 @@ impl TokenBuilder {
             token_info.extra_data = Some(data);
         }
         
+        token_info.extra.extend(self.extra);
+        
         if let Some(device) = self.device {
             token_info.device = Some(device);
         }
@@ -892,7 +1539,7 @@ impl TokenBuilder {
         // 保存更新后的 token 信息
         let key = format!("sa:token:{}", token.as_str());
         let value = serde_json::to_string(&token_info)
-            .map_err(|e| SaTokenError::SerializationError(e))?;
+            .map_err(SaTokenError::SerializationError)?;
         
         manager.storage.set(&key, &value, manager.config.timeout_duration()).await
             .map_err(|e| SaTokenError::StorageError(e.to_string()))?;
@@ -917,4 +1564,18 @@ mod tests {
         let token = StpUtil::create_token("test-token-123");
         assert_eq!(token.as_str(), "test-token-123");
     }
+    
+    #[test]
+    fn test_parse_login_id() {
+        assert_eq!(parse_login_id::<i64>("10001").unwrap(), 10001);
+        assert_eq!(parse_login_id::<String>("10001").unwrap(), "10001");
+        
+        let uuid = uuid::Uuid::new_v4();
+        assert_eq!(parse_login_id::<uuid::Uuid>(&uuid.to_string()).unwrap(), uuid);
+        
+        assert!(matches!(
+            parse_login_id::<u32>("user_1"),
+            Err(SaTokenError::LoginIdTypeMismatch(_))
+        ));
+    }
 }
@@ -0,0 +1,158 @@
+// Author: 金书记
+//
+//! 启动预热 | Startup Warm-up
+//!
+//! 部署后的第一批请求不应承担建立连接、加载权限等冷启动开销。
+//! The first requests after a deploy should not pay for opening connections or loading permissions.
+//!
+//! ```text
+//! 启动 | Startup: SaTokenManager::warm_up()
+//!   ├─→ storage: SaStorage::warm_up()，建立连接并确认后端可用
+//!   │            open connections and make sure the backend answers
+//!   ├─→ permission-cache: 为 warmup_login_ids 加载权限与角色（需要 PermissionProvider 和缓存）
+//!   │            load permissions and roles of warmup_login_ids (needs a PermissionProvider and the cache)
+//!   └─→ 已注册的 WarmupTask（如预取 JWKS）| registered WarmupTasks (e.g. prefetching JWKS)
+//!
+//! 某一步失败只记录到报告和日志中，不影响其他步骤和后续启动
+//! A failing step is only recorded in the report and the log; other steps and the startup go on
+//! ```
+
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::error::SaTokenResult;
+use crate::SaTokenManager;
+
+/// 预热任务 | Warm-up task
+///
+/// # 使用示例 | Usage Example
+///
+/// ```rust,ignore
+/// struct PrefetchJwks(Arc<JwksCache>);
+///
+/// #[async_trait]
+/// impl WarmupTask for PrefetchJwks {
+///     fn name(&self) -> &str { "jwks" }
+///
+///     async fn warm_up(&self, _manager: &SaTokenManager) -> SaTokenResult<()> {
+///         self.0.refresh().await
+///     }
+/// }
+///
+/// manager.register_warmup_task(Arc::new(PrefetchJwks(jwks)));
+/// ```
+#[async_trait]
+pub trait WarmupTask: Send + Sync {
+    /// 任务名称，出现在报告和日志中 | Task name, shown in the report and the log
+    fn name(&self) -> &str;
+
+    /// 执行预热 | Run the warm-up
+    ///
+    /// # 参数 | Parameters
+    /// - `manager`: 管理器 | Manager
+    async fn warm_up(&self, manager: &SaTokenManager) -> SaTokenResult<()>;
+}
+
+/// 预热步骤的结果 | Result of a warm-up step
+#[derive(Debug, Clone)]
+pub struct WarmupStep {
+    /// 步骤名称 | Step name
+    pub name: String,
+    /// 耗时 | Time taken
+    pub elapsed: Duration,
+    /// 失败原因，`None` 表示成功 | Failure reason, `None` on success
+    pub error: Option<String>,
+}
+
+/// 预热报告 | Warm-up report
+#[derive(Debug, Clone, Default)]
+pub struct WarmupReport {
+    /// 按执行顺序排列的步骤 | Steps in execution order
+    pub steps: Vec<WarmupStep>,
+}
+
+impl WarmupReport {
+    /// 是否所有步骤都成功 | Whether every step succeeded
+    pub fn is_ok(&self) -> bool {
+        self.steps.iter().all(|step| step.error.is_none())
+    }
+
+    /// 失败的步骤 | Failed steps
+    pub fn failures(&self) -> impl Iterator<Item = &WarmupStep> {
+        self.steps.iter().filter(|step| step.error.is_some())
+    }
+
+    /// 总耗时 | Total time taken
+    pub fn elapsed(&self) -> Duration {
+        self.steps.iter().map(|step| step.elapsed).sum()
+    }
+
+    pub(crate) fn record(&mut self, name: &str, elapsed: Duration, result: SaTokenResult<()>) {
+        let error = result.err().map(|e| e.to_string());
+        match &error {
+            Some(e) => tracing::warn!("Warm-up step '{}' failed after {:?}: {}", name, elapsed, e),
+            None => tracing::debug!("Warm-up step '{}' finished in {:?}", name, elapsed),
+        }
+        self.steps.push(WarmupStep { name: name.to_string(), elapsed, error });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::error::SaTokenError;
+    use crate::permission::PermissionProvider;
+    use crate::SaTokenConfig;
+    use sa_token_storage_memory::MemoryStorage;
+
+    struct CountingProvider(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl PermissionProvider for CountingProvider {
+        async fn get_permission_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(vec!["order:read".to_string()])
+        }
+
+        async fn get_role_list(&self, _login_id: &str, _login_type: &str) -> SaTokenResult<Vec<String>> {
+            Ok(Vec::new())
+        }
+    }
+
+    struct FailingTask;
+
+    #[async_trait]
+    impl WarmupTask for FailingTask {
+        fn name(&self) -> &str {
+            "jwks"
+        }
+
+        async fn warm_up(&self, _manager: &SaTokenManager) -> SaTokenResult<()> {
+            Err(SaTokenError::InternalError("endpoint unreachable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_up() {
+        let config = SaTokenConfig::builder()
+            .permission_cache_timeout(60)
+            .warmup_login_ids(vec!["1001".to_string()])
+            .build_config();
+        let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), config);
+        let calls = Arc::new(AtomicUsize::new(0));
+        manager.set_permission_provider(Arc::new(CountingProvider(calls.clone())));
+        manager.register_warmup_task(Arc::new(FailingTask));
+
+        let report = manager.warm_up().await;
+        let names: Vec<_> = report.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["storage", "permission-cache", "jwks"]);
+        assert!(!report.is_ok());
+        assert_eq!(report.failures().count(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 热点用户的第一次请求命中缓存 | The first request of a hot user hits the cache
+        assert!(manager.has_permission("1001", "order:read").await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -111,9 +111,12 @@ use crate::error::SaTokenError;
 use crate::manager::SaTokenManager;
 use crate::token::TokenValue;
 use crate::event::SaTokenEvent;
+use crate::online::{OnlineManager, OnlineUser, TimedOutConnection};
 use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// WebSocket authentication information
 /// WebSocket 认证信息
@@ -174,13 +177,13 @@ impl WsTokenExtractor for DefaultWsTokenExtractor {
     async fn extract_token(&self, headers: &HashMap<String, String>, query: &HashMap<String, String>) -> Option<String> {
         // Priority 1: Authorization header with Bearer scheme
         // 优先级 1: Authorization 请求头（Bearer 方式）
-        if let Some(token) = headers.get("Authorization") {
+        if let Some(token) = get_header(headers, "Authorization") {
             return Some(token.trim_start_matches("Bearer ").to_string());
         }
         
         // Priority 2: WebSocket Protocol header
         // 优先级 2: WebSocket Protocol 请求头
-        if let Some(token) = headers.get("Sec-WebSocket-Protocol") {
+        if let Some(token) = get_header(headers, "Sec-WebSocket-Protocol") {
             return Some(token.to_string());
         }
         
@@ -194,11 +197,30 @@ impl WsTokenExtractor for DefaultWsTokenExtractor {
     }
 }
 
+/// Look up a header ignoring case, frameworks lowercase header names
+/// 忽略大小写查找请求头，各框架会把请求头名称转为小写
+fn get_header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a String> {
+    headers.get(name).or_else(|| {
+        headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    })
+}
+
+/// Default interval between revocation checks of a realtime connection
+/// 实时连接吊销检查的默认间隔
+pub const DEFAULT_REVOCATION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default silence period after which a connection without heartbeats goes offline
+/// 连接无心跳后被视为离线的默认静默期
+pub const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// WebSocket authentication manager
 /// WebSocket 认证管理器
 ///
 /// Provides authentication and verification for WebSocket connections
 /// 为 WebSocket 连接提供认证和验证功能
+#[derive(Clone)]
 pub struct WsAuthManager {
     /// Reference to the token manager | Token 管理器引用
     manager: Arc<SaTokenManager>,
@@ -262,6 +284,7 @@ impl WsAuthManager {
     /// * `NotLogin` - No token found | 未找到 Token
     /// * `TokenNotFound` - Token not found in storage | 存储中未找到 Token
     /// * `TokenExpired` - Token has expired | Token 已过期
+    /// * `OffSessionNotAllowed` - Off-session tokens cannot connect | 离线令牌不能建立连接
     ///
     /// # Events | 事件
     /// Publishes `SaTokenEvent::Login` with login_type = "websocket"
@@ -295,10 +318,15 @@ impl WsAuthManager {
         
         // Step 3: Validate token expiration
         // 步骤 3: 验证 Token 过期时间
-        if let Some(expire_time) = token_info.expire_time {
-            if chrono::Utc::now() > expire_time {
-                return Err(SaTokenError::TokenExpired);
-            }
+        if let Some(expire_time) = token_info.expire_time
+            && chrono::Utc::now() > expire_time {
+            return Err(SaTokenError::TokenExpired);
+        }
+
+        // Off-session tokens belong to background jobs and never count as online users
+        // 离线令牌属于后台任务，不计入在线用户
+        if token_info.is_off_session() {
+            return Err(SaTokenError::OffSessionNotAllowed);
         }
 
         // Step 4: Generate unique WebSocket session ID
@@ -347,10 +375,9 @@ impl WsAuthManager {
         let token_info = self.manager.get_token_info(&token_value).await?;
         
         // Validate expiration | 验证过期时间
-        if let Some(expire_time) = token_info.expire_time {
-            if chrono::Utc::now() > expire_time {
-                return Err(SaTokenError::TokenExpired);
-            }
+        if let Some(expire_time) = token_info.expire_time
+            && chrono::Utc::now() > expire_time {
+            return Err(SaTokenError::TokenExpired);
         }
 
         Ok(token_info.login_id)
@@ -374,6 +401,164 @@ impl WsAuthManager {
         self.verify_token(&auth_info.token).await?;
         Ok(())
     }
+
+    /// Wait until the token of a realtime connection is revoked
+    /// 等待实时连接的 Token 被吊销（登出、踢下线或过期）
+    ///
+    /// # Arguments | 参数
+    /// * `token` - Token of the connection | 连接使用的 Token
+    /// * `interval` - Interval between checks | 检查间隔
+    ///
+    /// # Returns | 返回值
+    /// The error that made the token invalid | 导致 Token 失效的错误
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// tokio::select! {
+    ///     _ = handle_socket(&mut socket) => {}
+    ///     _ = ws_auth.wait_for_revocation(&auth_info.token, DEFAULT_REVOCATION_CHECK_INTERVAL) => {
+    ///         // Token revoked, close the connection | Token 已吊销，关闭连接
+    ///     }
+    /// }
+    /// ```
+    pub async fn wait_for_revocation(&self, token: &str, interval: Duration) -> SaTokenError {
+        loop {
+            if let Err(e) = self.verify_token(token).await {
+                return e;
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Register an authenticated connection with the `OnlineManager` for heartbeat tracking
+    /// 将已认证的连接登记到 `OnlineManager` 以跟踪心跳
+    ///
+    /// The connection is keyed by `auth_info.session_id`.
+    /// 连接以 `auth_info.session_id` 为键。
+    ///
+    /// # Arguments | 参数
+    /// * `auth_info` - WebSocket authentication info | WebSocket 认证信息
+    /// * `device` - Device identifier | 设备标识
+    ///
+    /// # Errors | 错误
+    /// * `ConfigError` - No `OnlineManager` is configured | 未配置 `OnlineManager`
+    /// * Token errors as in `get_token_info` | 同 `get_token_info` 的 Token 错误
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let auth_info = ws_auth.authenticate(&headers, &query).await?;
+    /// ws_auth.register_connection(&auth_info, "web").await?;
+    /// ```
+    pub async fn register_connection(&self, auth_info: &WsAuthInfo, device: &str) -> Result<(), SaTokenError> {
+        let online = self.online_manager()?;
+        let token_info = self.manager.get_token_info(&TokenValue::new(auth_info.token.clone())).await?;
+        let user = OnlineUser {
+            login_id: auth_info.login_id.clone(),
+            token: auth_info.token.clone(),
+            device: device.to_string(),
+            connect_time: auth_info.connect_time,
+            last_activity: chrono::Utc::now(),
+            metadata: auth_info.metadata.clone(),
+        };
+        online.register_connection(&auth_info.session_id, user, token_info.account_type()).await;
+        Ok(())
+    }
+
+    /// Record a heartbeat (ping, message) of a registered connection
+    /// 记录已登记连接的一次心跳（ping、消息）
+    ///
+    /// # Returns | 返回值
+    /// `false` when the connection is unknown or was swept; close it then
+    /// 连接未知或已被清理时返回 `false`，此时应关闭连接
+    pub async fn touch(&self, auth_info: &WsAuthInfo) -> bool {
+        match self.manager.online_manager() {
+            Some(online) => online.touch(&auth_info.session_id).await,
+            None => false,
+        }
+    }
+
+    /// Unregister a connection when it closes
+    /// 连接关闭时注销连接
+    pub async fn unregister_connection(&self, auth_info: &WsAuthInfo) {
+        if let Some(online) = self.manager.online_manager() {
+            online.unregister_connection(&auth_info.session_id).await;
+        }
+    }
+
+    /// Mark connections silent for longer than `silence` offline and publish `ConnectionTimeout` events
+    /// 将静默超过 `silence` 的连接标记离线，并发布 `ConnectionTimeout` 事件
+    ///
+    /// # Arguments | 参数
+    /// * `silence` - Silence period | 静默期
+    ///
+    /// # Returns | 返回值
+    /// The removed connections | 被移除的连接
+    pub async fn sweep_silent_connections(&self, silence: Duration) -> Vec<TimedOutConnection> {
+        let Some(online) = self.manager.online_manager() else {
+            return Vec::new();
+        };
+        let timed_out = online.sweep_silent_connections(silence).await;
+        for connection in &timed_out {
+            let event = SaTokenEvent::connection_timeout(&connection.user.login_id, &connection.user.token, &connection.connection_id)
+                .with_login_type(&connection.account_type);
+            self.manager.event_bus().publish(event).await;
+        }
+        timed_out
+    }
+
+    /// Spawn a background task sweeping silent connections every `interval`
+    /// 启动后台任务，每隔 `interval` 清理一次静默连接
+    ///
+    /// Abort the returned handle to stop the sweeper.
+    /// 中止返回的句柄即可停止清理任务。
+    ///
+    /// # Arguments | 参数
+    /// * `silence` - Silence period | 静默期
+    /// * `interval` - Interval between sweeps | 清理间隔
+    ///
+    /// # Example | 示例
+    /// ```rust,ignore
+    /// let sweeper = ws_auth.spawn_heartbeat_sweeper(DEFAULT_HEARTBEAT_TIMEOUT, Duration::from_secs(10));
+    /// ```
+    pub fn spawn_heartbeat_sweeper(&self, silence: Duration, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let ws_auth = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                ws_auth.sweep_silent_connections(silence).await;
+            }
+        })
+    }
+
+    fn online_manager(&self) -> Result<&Arc<OnlineManager>, SaTokenError> {
+        self.manager.online_manager()
+            .ok_or_else(|| SaTokenError::ConfigError("OnlineManager is not configured".to_string()))
+    }
+}
+
+/// End a realtime stream (SSE events, WebSocket messages) once its token is revoked
+/// Token 被吊销后结束实时流（SSE 事件、WebSocket 消息）
+///
+/// # Arguments | 参数
+/// * `stream` - Stream to wrap | 要包装的流
+/// * `ws_auth` - WebSocket authentication manager | WebSocket 认证管理器
+/// * `token` - Token of the connection | 连接使用的 Token
+/// * `interval` - Interval between revocation checks | 吊销检查间隔
+///
+/// # Example | 示例
+/// ```rust,ignore
+/// let events = until_revoked(events, ws_auth.clone(), auth_info.token.clone(), DEFAULT_REVOCATION_CHECK_INTERVAL);
+/// ```
+pub fn until_revoked<S: Stream>(
+    stream: S,
+    ws_auth: Arc<WsAuthManager>,
+    token: impl Into<String>,
+    interval: Duration,
+) -> impl Stream<Item = S::Item> {
+    let token = token.into();
+    stream.take_until(Box::pin(async move {
+        ws_auth.wait_for_revocation(&token, interval).await
+    }))
 }
 
 #[cfg(test)]
@@ -429,4 +614,69 @@ mod tests {
         let login_id = ws_manager.verify_token(token.as_str()).await.unwrap();
         assert_eq!(login_id, "user789");
     }
+
+    #[tokio::test]
+    async fn test_until_revoked() {
+        let config = SaTokenConfig::default();
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = Arc::new(SaTokenManager::new(storage, config));
+        
+        let ws_manager = Arc::new(WsAuthManager::new(manager.clone()));
+        
+        let token = manager.login("user321").await.unwrap();
+        
+        let mut headers = HashMap::new();
+        headers.insert("authorization".to_string(), format!("Bearer {}", token.as_str()));
+        let auth_info = ws_manager.authenticate(&headers, &HashMap::new()).await.unwrap();
+        
+        let events = futures_util::stream::repeat(()).then(|_| async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        });
+        let events = until_revoked(events, ws_manager, auth_info.token, Duration::from_millis(10));
+        
+        manager.logout(&token).await.unwrap();
+        let ended = tokio::time::timeout(Duration::from_secs(1), events.count()).await;
+        assert!(ended.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_sweeper() {
+        use std::sync::Mutex;
+        use crate::event::SaTokenListener;
+        
+        struct TimeoutListener(Arc<Mutex<Vec<String>>>);
+        
+        #[async_trait]
+        impl SaTokenListener for TimeoutListener {
+            async fn on_connection_timeout(&self, login_id: &str, _token: &str, login_type: &str, connection_id: &str) {
+                self.0.lock().unwrap().push(format!("{}:{}:{}", login_id, login_type, connection_id));
+            }
+        }
+        
+        let online = Arc::new(OnlineManager::new());
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default())
+            .with_online_manager(online.clone()));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        manager.event_bus().register(Arc::new(TimeoutListener(seen.clone())));
+        let ws_manager = WsAuthManager::new(manager.clone());
+        
+        let token = manager.login("user654").await.unwrap();
+        let mut query = HashMap::new();
+        query.insert("token".to_string(), token.as_str().to_string());
+        let auth_info = ws_manager.authenticate(&HashMap::new(), &query).await.unwrap();
+        ws_manager.register_connection(&auth_info, "web").await.unwrap();
+        assert!(online.is_online("user654").await);
+        
+        let sweeper = ws_manager.spawn_heartbeat_sweeper(Duration::from_millis(50), Duration::from_millis(10));
+        for _ in 0..5 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(ws_manager.touch(&auth_info).await);
+        }
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        sweeper.abort();
+        
+        assert!(!online.is_online("user654").await);
+        assert!(!ws_manager.touch(&auth_info).await);
+        assert_eq!(*seen.lock().unwrap(), vec![format!("user654:default:{}", auth_info.session_id)]);
+    }
 }
@@ -3,77 +3,102 @@
 //! sa-token-macro 基础使用示例
 
 use sa_token_macro::*;
+use sa_token_core::SaTokenError;
 
 // ============ 登录检查示例 ============
 
 #[sa_check_login]
-async fn user_info() -> String {
-    "User info - requires login".to_string()
+async fn user_info() -> Result<String, SaTokenError> {
+    Ok("User info - requires login".to_string())
 }
 
 // ============ 权限检查示例 ============
 
 #[sa_check_permission("user:read")]
-async fn get_user(id: u64) -> String {
-    format!("Get user {} - requires user:read permission", id)
+async fn get_user(id: u64) -> Result<String, SaTokenError> {
+    Ok(format!("Get user {} - requires user:read permission", id))
 }
 
 #[sa_check_permission("user:write")]
-async fn update_user(id: u64, name: String) -> String {
-    format!("Update user {} to {} - requires user:write permission", id, name)
+async fn update_user(id: u64, name: String) -> Result<String, SaTokenError> {
+    Ok(format!("Update user {} to {} - requires user:write permission", id, name))
 }
 
 #[sa_check_permission("user:delete")]
-async fn delete_user(id: u64) -> String {
-    format!("Delete user {} - requires user:delete permission", id)
+async fn delete_user(id: u64) -> Result<String, SaTokenError> {
+    Ok(format!("Delete user {} - requires user:delete permission", id))
 }
 
 // ============ 角色检查示例 ============
 
 #[sa_check_role("admin")]
-async fn admin_panel() -> String {
-    "Admin panel - requires admin role".to_string()
+async fn admin_panel() -> Result<String, SaTokenError> {
+    Ok("Admin panel - requires admin role".to_string())
 }
 
 #[sa_check_role("moderator")]
-async fn moderate_content(content_id: u64) -> String {
-    format!("Moderate content {} - requires moderator role", content_id)
+async fn moderate_content(content_id: u64) -> Result<String, SaTokenError> {
+    Ok(format!("Moderate content {} - requires moderator role", content_id))
 }
 
 // ============ 多权限检查示例 ============
 
 #[sa_check_permissions_and("user:read", "user:write")]
-async fn manage_user() -> String {
-    "Manage user - requires both user:read AND user:write permissions".to_string()
+async fn manage_user() -> Result<String, SaTokenError> {
+    Ok("Manage user - requires both user:read AND user:write permissions".to_string())
 }
 
 #[sa_check_permissions_or("admin:all", "super:all")]
-async fn super_admin_action() -> String {
-    "Super admin action - requires admin:all OR super:all permission".to_string()
+async fn super_admin_action() -> Result<String, SaTokenError> {
+    Ok("Super admin action - requires admin:all OR super:all permission".to_string())
 }
 
 // ============ 多角色检查示例 ============
 
 #[sa_check_roles_and("admin", "super")]
-async fn super_admin_panel() -> String {
-    "Super admin panel - requires both admin AND super roles".to_string()
+async fn super_admin_panel() -> Result<String, SaTokenError> {
+    Ok("Super admin panel - requires both admin AND super roles".to_string())
 }
 
 #[sa_check_roles_or("admin", "moderator")]
-async fn moderate_or_admin() -> String {
-    "Moderate or admin - requires admin OR moderator role".to_string()
+async fn moderate_or_admin() -> Result<String, SaTokenError> {
+    Ok("Moderate or admin - requires admin OR moderator role".to_string())
+}
+
+// ============ 二级认证检查示例 ============
+
+#[sa_check_safe]
+async fn change_password() -> Result<String, SaTokenError> {
+    Ok("Change password - requires recent second-level authentication".to_string())
+}
+
+// ============ 多账号体系示例 ============
+
+#[sa_check_login(type = "admin")]
+async fn admin_dashboard() -> Result<String, SaTokenError> {
+    Ok("Admin dashboard - requires admin login".to_string())
+}
+
+#[sa_check_permission("config:write", type = "admin")]
+async fn update_config() -> Result<String, SaTokenError> {
+    Ok("Update config - requires config:write in the admin account system".to_string())
+}
+
+#[sa_check_role("super", type = "admin")]
+async fn system_settings() -> Result<String, SaTokenError> {
+    Ok("System settings - requires super role in the admin account system".to_string())
 }
 
 // ============ 忽略认证示例 ============
 
 #[sa_ignore]
-async fn public_api() -> String {
-    "Public API - no authentication required".to_string()
+async fn public_api() -> Result<String, SaTokenError> {
+    Ok("Public API - no authentication required".to_string())
 }
 
 #[sa_ignore]
-async fn health_check() -> String {
-    "OK - health check doesn't need auth".to_string()
+async fn health_check() -> Result<String, SaTokenError> {
+    Ok("OK - health check doesn't need auth".to_string())
 }
 
 // ============ 结构体级别的忽略认证 ============
@@ -82,12 +107,12 @@ async fn health_check() -> String {
 struct PublicController;
 
 impl PublicController {
-    async fn home() -> String {
-        "Home page - public access".to_string()
+    async fn home() -> Result<String, SaTokenError> {
+        Ok("Home page - public access".to_string())
     }
     
-    async fn about() -> String {
-        "About page - public access".to_string()
+    async fn about() -> Result<String, SaTokenError> {
+        Ok("About page - public access".to_string())
     }
 }
 
@@ -97,12 +122,12 @@ struct ApiController;
 
 #[sa_ignore]
 impl ApiController {
-    async fn version() -> String {
-        "v1.0.0 - version API is public".to_string()
+    async fn version() -> Result<String, SaTokenError> {
+        Ok("v1.0.0 - version API is public".to_string())
     }
     
-    async fn status() -> String {
-        "running - status API is public".to_string()
+    async fn status() -> Result<String, SaTokenError> {
+        Ok("running - status API is public".to_string())
     }
 }
 
@@ -113,26 +138,26 @@ struct UserController;
 impl UserController {
     // 公开接口
     #[sa_ignore]
-    async fn register(username: String) -> String {
-        format!("Register user: {} - public", username)
+    async fn register(username: String) -> Result<String, SaTokenError> {
+        Ok(format!("Register user: {} - public", username))
     }
     
     // 需要登录
     #[sa_check_login]
-    async fn profile() -> String {
-        "User profile - requires login".to_string()
+    async fn profile() -> Result<String, SaTokenError> {
+        Ok("User profile - requires login".to_string())
     }
     
     // 需要特定权限
     #[sa_check_permission("user:update_profile")]
-    async fn update_profile(data: String) -> String {
-        format!("Update profile: {} - requires permission", data)
+    async fn update_profile(data: String) -> Result<String, SaTokenError> {
+        Ok(format!("Update profile: {} - requires permission", data))
     }
     
     // 需要管理员角色
     #[sa_check_role("admin")]
-    async fn list_all_users() -> String {
-        "List all users - requires admin role".to_string()
+    async fn list_all_users() -> Result<String, SaTokenError> {
+        Ok("List all users - requires admin role".to_string())
     }
 }
 
@@ -141,26 +166,36 @@ async fn main() {
     println!("=== sa-token-macro 示例 ===\n");
     
     println!("1. 登录检查:");
-    println!("   {}", user_info().await);
+    println!("   {:?}", user_info().await);
     
     println!("\n2. 权限检查:");
-    println!("   {}", get_user(123).await);
-    println!("   {}", update_user(123, "Alice".to_string()).await);
+    println!("   {:?}", get_user(123).await);
+    println!("   {:?}", update_user(123, "Alice".to_string()).await);
     
     println!("\n3. 角色检查:");
-    println!("   {}", admin_panel().await);
+    println!("   {:?}", admin_panel().await);
     
     println!("\n4. 多权限检查:");
-    println!("   {}", manage_user().await);
+    println!("   {:?}", manage_user().await);
+    
+    println!("\n5. 二级认证检查:");
+    println!("   {:?}", change_password().await);
+    
+    println!("\n6. 多账号体系（未注册 admin 时返回配置错误）:");
+    println!("   {:?}", admin_dashboard().await);
+    println!("   {:?}", update_config().await);
+    println!("   {:?}", system_settings().await);
     
-    println!("\n5. 公开API（忽略认证）:");
-    println!("   {}", public_api().await);
-    println!("   {}", health_check().await);
+    println!("\n7. 公开API（忽略认证）:");
+    println!("   {:?}", public_api().await);
+    println!("   {:?}", health_check().await);
     
-    println!("\n6. 控制器示例:");
-    println!("   {}", PublicController::home().await);
-    println!("   {}", ApiController::version().await);
-    println!("   {}", UserController::register("Bob".to_string()).await);
+    println!("\n8. 控制器示例:");
+    println!("   {:?}", PublicController::home().await);
+    println!("   {:?}", PublicController::about().await);
+    println!("   {:?}", ApiController::version().await);
+    println!("   {:?}", ApiController::status().await);
+    println!("   {:?}", UserController::register("Bob".to_string()).await);
     
     println!("\n注意：这些宏主要是添加编译时标记，实际的认证逻辑在框架中间件中执行。");
 }
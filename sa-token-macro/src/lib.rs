@@ -8,8 +8,12 @@
 //! - `#[sa_check_login]` - 检查登录
 //! - `#[sa_check_permission("permission")]` - 检查权限
 //! - `#[sa_check_role("role")]` - 检查角色
+//! - `#[sa_check_safe]` - 检查二级认证
 //! - `#[sa_ignore]` - 忽略认证（跳过所有认证检查）
 //! 
+//! `sa_check_login`、`sa_check_permission`、`sa_check_role` 支持 `type = "admin"` 参数，
+//! 按 `StpUtil::register_logic` 注册的账号体系检查
+//! 
 //! ## 使用示例
 //! 
 //! ```rust,ignore
@@ -58,6 +62,7 @@ use macros::{
     check_permissions_or::sa_check_permissions_or_impl,
     check_roles_and::sa_check_roles_and_impl,
     check_roles_or::sa_check_roles_or_impl,
+    check_safe::sa_check_safe_impl,
     ignore::sa_ignore_impl,
 };
 
@@ -103,6 +108,12 @@ pub fn sa_check_roles_or(attr: TokenStream, item: TokenStream) -> TokenStream {
     sa_check_roles_or_impl(attr, item)
 }
 
+/// 检查二级认证的宏
+#[proc_macro_attribute]
+pub fn sa_check_safe(attr: TokenStream, item: TokenStream) -> TokenStream {
+    sa_check_safe_impl(attr, item)
+}
+
 /// 忽略认证检查的宏
 #[proc_macro_attribute]
 pub fn sa_ignore(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -7,7 +7,8 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use syn::{parse_macro_input, ItemFn, Error};
+use crate::utils::CheckArgs;
 
 /// Login check macro
 /// 
@@ -41,13 +42,28 @@ use syn::{parse_macro_input, ItemFn};
 /// }
 /// ```
 /// 
+/// With `type = "..."` the login is checked against the account system registered
+/// under that type (see `StpLogic`) instead of the default one:
+/// 
+/// ```rust,ignore
+/// #[sa_check_login(type = "admin")]
+/// async fn admin_dashboard() -> Result<&'static str, SaTokenError> {
+///     Ok("Admin dashboard")
+/// }
+/// ```
+/// 
 /// # Notes
 /// 
 /// - Must be used with framework middleware (e.g., Axum's SaTokenLayer) which sets up context
 /// - Only supports async functions
 /// - Function must return Result type for `?` operator to work
 /// - Supports generic parameters and lifetime annotations
-pub fn sa_check_login_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn sa_check_login_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as CheckArgs);
+    if let Some(value) = &args.value {
+        return Error::new_spanned(value, "sa_check_login only accepts `type = \"...\"`")
+            .to_compile_error().into();
+    }
     let input = parse_macro_input!(item as ItemFn);
     
     // Extract function signature components
@@ -71,12 +87,18 @@ pub fn sa_check_login_impl(_attr: TokenStream, item: TokenStream) -> TokenStream
     
     // Generate authentication check code
     // Insert login check at the beginning of function body
-    let auth_check = quote! {
-        // Login check - automatically inserted by sa_check_login macro
-        // Returns SaTokenError::NotLogin if not logged in, error is propagated via ? operator
-        if !sa_token_core::StpUtil::is_login_current() {
-            return Err(sa_token_core::SaTokenError::NotLogin.into());
-        }
+    let auth_check = match &args.login_type {
+        Some(login_type) => quote! {
+            // Login check against the account system registered under this type
+            sa_token_core::StpUtil::logic(#login_type)?.check_login_current().await?;
+        },
+        None => quote! {
+            // Login check - automatically inserted by sa_check_login macro
+            // Returns SaTokenError::NotLogin if not logged in, error is propagated via ? operator
+            if !sa_token_core::StpUtil::is_login_current() {
+                return Err(sa_token_core::SaTokenError::NotLogin.into());
+            }
+        },
     };
     
     // Generate expanded function
@@ -7,7 +7,8 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn, LitStr, Error};
+use syn::{parse_macro_input, ItemFn, Error};
+use crate::utils::CheckArgs;
 
 /// 检查权限的宏
 /// 
@@ -19,6 +20,7 @@ use syn::{parse_macro_input, ItemFn, LitStr, Error};
 ///   - 精确匹配: `"user:delete"`
 ///   - 通配符: `"admin:*"` (表示 admin 模块的所有权限)
 ///   - 全局通配符: `"*"` (表示所有权限)
+/// - `type` - 可选，账号类型，使用 `StpUtil::logic(type)` 注册的账号体系检查
 /// 
 /// # 工作原理
 /// 
@@ -49,6 +51,12 @@ use syn::{parse_macro_input, ItemFn, LitStr, Error};
 /// async fn super_admin() -> &'static str {
 ///     "Super admin area"
 /// }
+/// 
+/// // 检查 admin 账号体系下的权限
+/// #[sa_check_permission("config:write", type = "admin")]
+/// async fn update_config() -> &'static str {
+///     "Config updated"
+/// }
 /// ```
 /// 
 /// # 权限命名规范
@@ -60,7 +68,11 @@ use syn::{parse_macro_input, ItemFn, LitStr, Error};
 /// - `user:delete` - 删除用户
 /// - `order:*` - 订单模块所有权限
 pub fn sa_check_permission_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let permission = parse_macro_input!(attr as LitStr);
+    let args = parse_macro_input!(attr as CheckArgs);
+    let Some(permission) = args.value else {
+        return Error::new(proc_macro2::Span::call_site(), "Permission identifier cannot be empty")
+            .to_compile_error().into();
+    };
     let perm_value = permission.value();
     
     // 编译时验证：权限标识符不能为空
@@ -86,9 +98,14 @@ pub fn sa_check_permission_impl(attr: TokenStream, item: TokenStream) -> TokenSt
             .to_compile_error().into();
     }
     
-    let check_code = quote! {
-        let __login_id = sa_token_core::StpUtil::get_login_id_as_string().await?;
-        sa_token_core::StpUtil::check_permission(&__login_id, #perm_value).await?;
+    let check_code = match &args.login_type {
+        Some(login_type) => quote! {
+            sa_token_core::StpUtil::logic(#login_type)?.check_permission_current(#perm_value).await?;
+        },
+        None => quote! {
+            let __login_id = sa_token_core::StpUtil::get_login_id_as_string().await?;
+            sa_token_core::StpUtil::check_permission(&__login_id, #perm_value).await?;
+        },
     };
     
     let expanded: TokenStream2 = quote! {
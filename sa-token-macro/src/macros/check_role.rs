@@ -5,7 +5,8 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn, LitStr};
+use syn::{parse_macro_input, ItemFn, Error};
+use crate::utils::CheckArgs;
 
 /// 检查角色的宏
 /// 
@@ -14,6 +15,7 @@ use syn::{parse_macro_input, ItemFn, LitStr};
 /// # 参数
 /// 
 /// - `role` - 角色名称，如 "admin"、"user"、"vip"
+/// - `type` - 可选，账号类型，使用 `StpUtil::logic(type)` 注册的账号体系检查
 /// 
 /// # 示例
 /// 
@@ -23,9 +25,19 @@ use syn::{parse_macro_input, ItemFn, LitStr};
 ///     // 只有 admin 角色才能访问
 ///     "Admin panel"
 /// }
+/// 
+/// // 检查 admin 账号体系下的角色
+/// #[sa_check_role("super", type = "admin")]
+/// async fn system_settings() -> impl Responder {
+///     "System settings"
+/// }
 /// ```
 pub fn sa_check_role_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let role = parse_macro_input!(attr as LitStr);
+    let args = parse_macro_input!(attr as CheckArgs);
+    let Some(role) = args.value else {
+        return Error::new(proc_macro2::Span::call_site(), "Role name is required")
+            .to_compile_error().into();
+    };
     let input = parse_macro_input!(item as ItemFn);
     let fn_name = &input.sig.ident;
     let fn_inputs = &input.sig.inputs;
@@ -43,9 +55,14 @@ pub fn sa_check_role_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             .to_compile_error().into();
     }
     
-    let check_code = quote! {
-        let __login_id = sa_token_core::StpUtil::get_login_id_as_string().await?;
-        sa_token_core::StpUtil::check_role(&__login_id, #role_value).await?;
+    let check_code = match &args.login_type {
+        Some(login_type) => quote! {
+            sa_token_core::StpUtil::logic(#login_type)?.check_role_current(#role_value).await?;
+        },
+        None => quote! {
+            let __login_id = sa_token_core::StpUtil::get_login_id_as_string().await?;
+            sa_token_core::StpUtil::check_role(&__login_id, #role_value).await?;
+        },
     };
     
     let expanded: TokenStream2 = quote! {
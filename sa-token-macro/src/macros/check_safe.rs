@@ -0,0 +1,55 @@
+// Author: 金书记
+//
+//! 二级认证检查宏
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, ItemFn};
+
+/// 检查二级认证的宏
+/// 
+/// 使用此宏标注的函数会在执行前检查当前 token 是否处于二级认证有效期内，
+/// 适用于修改密码、提现等敏感操作
+/// 
+/// # 示例
+/// 
+/// ```rust,ignore
+/// #[sa_check_safe]
+/// async fn change_password() -> Result<String, SaTokenError> {
+///     // 只有最近完成过 StpUtil::open_safe 的 token 才能访问
+///     Ok("Password changed".to_string())
+/// }
+/// ```
+pub fn sa_check_safe_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemFn);
+    let fn_name = &input.sig.ident;
+    let fn_inputs = &input.sig.inputs;
+    let fn_output = &input.sig.output;
+    let fn_body = &input.block;
+    let fn_attrs = &input.attrs;
+    let fn_vis = &input.vis;
+    let fn_asyncness = &input.sig.asyncness;
+    let fn_generics = &input.sig.generics;
+    let fn_where_clause = &input.sig.generics.where_clause;
+    
+    if fn_asyncness.is_none() {
+        return syn::Error::new_spanned(fn_name, "Macro requires async function")
+            .to_compile_error().into();
+    }
+    
+    let check_code = quote! {
+        sa_token_core::StpUtil::check_safe_current().await?;
+    };
+    
+    let expanded: TokenStream2 = quote! {
+        #(#fn_attrs)*
+        #[doc(hidden)]
+        #fn_vis #fn_asyncness fn #fn_name #fn_generics(#fn_inputs) #fn_output #fn_where_clause {
+            #check_code
+            #fn_body
+        }
+    };
+    
+    expanded.into()
+}
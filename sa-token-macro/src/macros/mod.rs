@@ -9,4 +9,5 @@ pub mod check_permissions_and;
 pub mod check_permissions_or;
 pub mod check_roles_and;
 pub mod check_roles_or;
+pub mod check_safe;
 pub mod ignore;
@@ -3,6 +3,7 @@
 //! 宏工具函数
 
 use syn::{ItemFn, punctuated::Punctuated, Token, LitStr};
+use syn::parse::{Parse, ParseStream};
 use quote::quote;
 use proc_macro2::TokenStream;
 
@@ -75,3 +76,35 @@ pub fn generate_auth_metadata(check_type: &str, value: Option<&str>) -> TokenStr
         #[cfg_attr(feature = "sa-token-metadata", sa_token_auth_check = #metadata)]
     }
 }
+
+/// 检查宏参数：可选的检查值与账号类型
+/// 
+/// 支持 `()`、`("value")`、`(type = "admin")`、`("value", type = "admin")`
+pub struct CheckArgs {
+    pub value: Option<LitStr>,
+    pub login_type: Option<LitStr>,
+}
+
+impl Parse for CheckArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let value = if input.peek(LitStr) {
+            Some(input.parse::<LitStr>()?)
+        } else {
+            None
+        };
+        if value.is_some() && !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        let login_type = if input.is_empty() {
+            None
+        } else {
+            input.parse::<Token![type]>()?;
+            input.parse::<Token![=]>()?;
+            Some(input.parse::<LitStr>()?)
+        };
+        if !input.is_empty() {
+            return Err(input.error("expected `type = \"...\"`"));
+        }
+        Ok(Self { value, login_type })
+    }
+}
@@ -6,6 +6,9 @@ use actix_web::{HttpRequest, HttpResponse};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::Arc;
+use actix_web::dev::Extensions;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// Actix-web请求适配器
 pub struct ActixRequestAdapter<'a> {
@@ -154,11 +157,65 @@ impl SaResponse for ActixResponseAdapter {
 fn parse_query_string(query: &str) -> HashMap<String, String> {
     let mut params = HashMap::new();
     for pair in query.split('&') {
-        if let Some((key, value)) = pair.split_once('=') {
-            if let Ok(decoded_value) = urlencoding::decode(value) {
-                params.insert(key.to_string(), decoded_value.to_string());
-            }
+        if let Some((key, value)) = pair.split_once('=')
+            && let Ok(decoded_value) = urlencoding::decode(value) {
+            params.insert(key.to_string(), decoded_value.to_string());
         }
     }
     params
 }
+
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+///
+/// # 示例
+/// ```rust,ignore
+/// async fn login(state: web::Data<SaTokenState>) -> HttpResponse {
+///     let token = StpUtil::login(10001).await.unwrap();
+///     write_token(&state.manager, &token, HttpResponse::Ok().json(json!({ "token": token.as_str() })))
+/// }
+/// ```
+pub fn write_token<B>(manager: &SaTokenManager, token: &TokenValue, response: HttpResponse<B>) -> HttpResponse<B> {
+    append_headers(response, manager.token_response_headers(token))
+}
+
+/// 登出后清除响应中的 token cookie
+pub fn clear_token<B>(manager: &SaTokenManager, response: HttpResponse<B>) -> HttpResponse<B> {
+    append_headers(response, manager.logout_response_headers())
+}
+
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenMiddleware` 放入请求扩展），缺省为 `DefaultErrorRenderer`
+pub fn error_renderer(extensions: &Extensions) -> Arc<dyn ErrorRenderer> {
+    extensions.get::<Arc<dyn ErrorRenderer>>().cloned().unwrap_or_else(default_error_renderer)
+}
+
+/// 用给定的 `ErrorRenderer` 把错误渲染为拒绝响应，中间件与提取器共用
+pub fn error_response(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> HttpResponse {
+    rendered_response(renderer.render(error))
+}
+
+/// 由渲染结果（状态码、响应头、响应体）构建响应
+pub(crate) fn rendered_response((status, headers, body): (u16, Vec<(String, String)>, String)) -> HttpResponse {
+    let status = actix_web::http::StatusCode::from_u16(status)
+        .unwrap_or(actix_web::http::StatusCode::UNAUTHORIZED);
+    let mut builder = HttpResponse::build(status);
+    for header in headers {
+        builder.append_header(header);
+    }
+    builder.body(body)
+}
+
+/// 用请求所属状态的渲染器把错误渲染为拒绝响应并包装为 actix 错误，供中间件与提取器返回
+pub(crate) fn rejection(extensions: &Extensions, error: &SaTokenError) -> actix_web::Error {
+    actix_web::error::InternalError::from_response(error.to_string(), error_response(error_renderer(extensions).as_ref(), error)).into()
+}
+
+fn append_headers<B>(mut response: HttpResponse<B>, headers: Vec<(String, String)>) -> HttpResponse<B> {
+    use actix_web::http::header::{HeaderName, HeaderValue};
+    for (name, value) in headers {
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
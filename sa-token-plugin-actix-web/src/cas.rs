@@ -0,0 +1,124 @@
+// Author: 金书记
+//
+//! CAS 3.0 协议端点 | CAS 3.0 protocol endpoints
+//!
+//! `cas_routes` 挂载 `/login`、`/serviceValidate` 与 `/p3/serviceValidate`，现有的 CAS 客户端库
+//! 把 CAS 服务地址指向挂载前缀即可接入。
+//! `cas_routes` mounts `/login`, `/serviceValidate` and `/p3/serviceValidate`; existing CAS client
+//! libraries only need their CAS server URL pointed at the mount prefix.
+//!
+//! ```rust,ignore
+//! let sso_server = Arc::new(SsoServer::new(manager).with_allow_urls(vec!["https://app.example.com/*".to_string()]));
+//! let cas = Arc::new(CasServer::new(sso_server));
+//!
+//! App::new()
+//!     .wrap(SaTokenLayer::new(sa_token_state))
+//!     .service(web::scope("/cas").configure(cas_routes(CasServerState::new(cas).login_url("/login"))))
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Route};
+use actix_web::http::{header, StatusCode};
+use sa_token_core::{CasLoginAction, CasServer, ErrorPage, SaPage, SaTokenError};
+
+/// CAS 路由状态 | State of the CAS routes
+#[derive(Clone)]
+pub struct CasServerState {
+    cas: Arc<CasServer>,
+    login_url: Option<String>,
+}
+
+impl CasServerState {
+    /// 创建路由状态 | Create the route state
+    ///
+    /// # 参数 | Parameters
+    /// * `cas` - CAS 服务端 | CAS server
+    pub fn new(cas: Arc<CasServer>) -> Self {
+        Self { cas, login_url: None }
+    }
+
+    /// 未登录用户访问 `/login` 时跳转的登录页，原地址以 `redirect` 参数传递；未设置时返回 401
+    /// Login page for users hitting `/login` without a login, with the original URL in the
+    /// `redirect` parameter; 401 when unset
+    pub fn login_url(mut self, url: impl Into<String>) -> Self {
+        self.login_url = Some(url.into());
+        self
+    }
+}
+
+/// 创建 CAS 路由，配合 `web::scope(..).configure(..)` 使用 | Build the CAS routes, for `web::scope(..).configure(..)`
+///
+/// ```text
+/// GET /login                登录并携带票据回跳 service | log in and return to service with a ticket
+/// GET /serviceValidate      CAS 2.0 票据校验 | CAS 2.0 ticket validation
+/// GET /p3/serviceValidate   CAS 3.0 票据校验，含用户属性 | CAS 3.0 ticket validation with user attributes
+/// ```
+///
+/// 登录用户从 `SaTokenLayer` 写入的请求扩展中读取，因此需要在外层应用 `SaTokenLayer`。
+/// The logged-in user is read from the request extensions set by `SaTokenLayer`, so wrap the
+/// app in `SaTokenLayer`.
+///
+/// # 参数 | Parameters
+/// * `state` - 路由状态 | Route state
+pub fn cas_routes(state: CasServerState) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        let cas = state.cas.clone();
+        cfg.route("/login", web::get().to(move |req: HttpRequest, query: web::Query<BTreeMap<String, String>>| {
+                let state = state.clone();
+                async move { login(&state, &req, &query).await }
+            }))
+            .route("/serviceValidate", cas_service_validate_handler(cas.clone()))
+            .route("/p3/serviceValidate", cas_p3_service_validate_handler(cas));
+    }
+}
+
+async fn login(state: &CasServerState, req: &HttpRequest, params: &BTreeMap<String, String>) -> HttpResponse {
+    let accept_language = req.headers().get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let login_id = req.extensions().get::<String>().cloned();
+    match state.cas.login_request(login_id.as_deref(), params).await {
+        Ok(CasLoginAction::Redirect(location)) => HttpResponse::SeeOther()
+            .insert_header((header::LOCATION, location))
+            .finish(),
+        Ok(CasLoginAction::Login) => match &state.login_url {
+            Some(login_url) => {
+                let separator = if login_url.contains('?') { '&' } else { '?' };
+                HttpResponse::SeeOther()
+                    .insert_header((header::LOCATION, format!("{}{}redirect={}", login_url, separator, urlencoding::encode(&req.uri().to_string()))))
+                    .finish()
+            }
+            None => error_page(&state.cas, StatusCode::UNAUTHORIZED, &SaTokenError::NotLogin.to_string(), accept_language),
+        },
+        Err(e) => error_page(&state.cas, StatusCode::BAD_REQUEST, &e.to_string(), accept_language),
+    }
+}
+
+/// 渲染错误页 | Render an error page
+fn error_page(cas: &CasServer, status: StatusCode, message: &str, accept_language: Option<&str>) -> HttpResponse {
+    let page = SaPage::Error(ErrorPage { status: status.as_u16(), message: message.to_string() });
+    match cas.sso().page_renderer().render_negotiated(&page, accept_language) {
+        Ok(html) => HttpResponse::build(status).content_type("text/html; charset=utf-8").body(html),
+        Err(_) => HttpResponse::build(status).finish(),
+    }
+}
+
+/// 构建 CAS XML 响应，校验失败也以 200 返回 | Build a CAS XML response; failures are answered with 200 as well
+pub fn cas_xml_response(xml: String) -> HttpResponse {
+    HttpResponse::Ok().content_type("application/xml;charset=UTF-8").body(xml)
+}
+
+/// GET 路由处理器，CAS 2.0 `/serviceValidate` | GET route handler for CAS 2.0 `/serviceValidate`
+pub fn cas_service_validate_handler(cas: Arc<CasServer>) -> Route {
+    web::get().to(move |query: web::Query<BTreeMap<String, String>>| {
+        let cas = cas.clone();
+        async move { cas_xml_response(cas.service_validate(&query).await) }
+    })
+}
+
+/// GET 路由处理器，CAS 3.0 `/p3/serviceValidate` | GET route handler for CAS 3.0 `/p3/serviceValidate`
+pub fn cas_p3_service_validate_handler(cas: Arc<CasServer>) -> Route {
+    web::get().to(move |query: web::Query<BTreeMap<String, String>>| {
+        let cas = cas.clone();
+        async move { cas_xml_response(cas.p3_service_validate(&query).await) }
+    })
+}
@@ -2,9 +2,12 @@
 //
 //! Actix-web提取器
 
-use actix_web::{FromRequest, HttpRequest, HttpMessage, dev::Payload, error::ErrorUnauthorized};
+use actix_web::{FromRequest, HttpRequest, HttpMessage, dev::Payload};
+use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
-use sa_token_core::{token::TokenValue, error::messages};
+use serde::de::DeserializeOwned;
+use sa_token_core::{token::{TokenValue, DEFAULT_ACCOUNT_TYPE}, PermissionSnapshot, SaTokenError, StpUtil, parse_login_id};
+use crate::adapter::rejection;
 
 /// Token 提取器 - 必须存在，否则返回错误
 pub struct SaTokenExtractor(pub TokenValue);
@@ -16,10 +19,7 @@ impl FromRequest for SaTokenExtractor {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         match req.extensions().get::<TokenValue>() {
             Some(token) => ready(Ok(SaTokenExtractor(token.clone()))),
-            None => ready(Err(ErrorUnauthorized(serde_json::json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            })))),
+            None => ready(Err(rejection(&req.extensions(), &SaTokenError::NotLogin))),
         }
     }
 }
@@ -47,10 +47,76 @@ impl FromRequest for LoginIdExtractor {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         match req.extensions().get::<String>() {
             Some(login_id) => ready(Ok(LoginIdExtractor(login_id.clone()))),
-            None => ready(Err(ErrorUnauthorized(serde_json::json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            })))),
+            None => ready(Err(rejection(&req.extensions(), &SaTokenError::NotLogin))),
         }
     }
 }
+
+/// 类型化登录 ID 提取器 - 将登录 ID 转换为 i64、Uuid 等类型，转换失败返回 400
+/// 
+/// # 示例
+/// ```rust,ignore
+/// async fn handler(TypedLoginId(user_id): TypedLoginId<i64>) -> String {
+///     format!("user {}", user_id)
+/// }
+/// ```
+pub struct TypedLoginId<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest for TypedLoginId<T> {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+    
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let extensions = req.extensions();
+        let Some(login_id) = extensions.get::<String>() else {
+            return ready(Err(rejection(&req.extensions(), &SaTokenError::NotLogin)));
+        };
+        
+        ready(parse_login_id(login_id).map(TypedLoginId).map_err(|e| rejection(&req.extensions(), &e)))
+    }
+}
+
+/// 当前用户权限提取器 - 每个请求只加载一次权限与角色列表（缓存在请求扩展中）
+/// 
+/// # 示例
+/// ```rust,ignore
+/// async fn refund(perms: CurrentPermissions) -> HttpResponse {
+///     if !perms.has_any(&["order:refund", "order:*"]) {
+///         return HttpResponse::Forbidden().finish();
+///     }
+///     HttpResponse::Ok().body(format!("refunded by {}", perms.login_id()))
+/// }
+/// ```
+pub struct CurrentPermissions(pub PermissionSnapshot);
+
+impl std::ops::Deref for CurrentPermissions {
+    type Target = PermissionSnapshot;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for CurrentPermissions {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+    
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        if let Some(snapshot) = req.extensions().get::<PermissionSnapshot>() {
+            return Box::pin(ready(Ok(CurrentPermissions(snapshot.clone()))));
+        }
+        let Some(login_id) = req.extensions().get::<String>().cloned() else {
+            return Box::pin(ready(Err(rejection(&req.extensions(), &SaTokenError::NotLogin))));
+        };
+        let account_type = StpUtil::get_token_info_current()
+            .map(|info| info.account_type().to_string())
+            .unwrap_or_else(|_| DEFAULT_ACCOUNT_TYPE.to_string());
+        let req = req.clone();
+        
+        Box::pin(async move {
+            let snapshot = StpUtil::get_permission_snapshot_by_type(&login_id, &account_type).await;
+            req.extensions_mut().insert(snapshot.clone());
+            Ok(CurrentPermissions(snapshot))
+        })
+    }
+}
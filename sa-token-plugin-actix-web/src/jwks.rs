@@ -0,0 +1,35 @@
+// Author: 金书记
+//
+//! JWKS 端点 | JWKS endpoint
+//!
+//! ```rust,ignore
+//! let key_ring = JwksKeyRing::new();
+//! key_ring.rotate("2025-01", JwtAlgorithm::RS256, &private_pem, &public_pem)?;
+//!
+//! App::new()
+//!     .route("/.well-known/jwks.json", jwks_handler(key_ring.clone()))
+//! ```
+
+use actix_web::{web, HttpResponse, Route};
+use actix_web::http::header;
+use sa_token_core::JwksKeyRing;
+
+/// 公钥集缓存时长（秒），轮换后资源服务器最迟在此之后看到新密钥
+/// Cache lifetime of the key set in seconds; resource servers see a rotated key at most this late
+pub const JWKS_MAX_AGE: u32 = 300;
+
+/// 构建 JWKS JSON 响应 | Build the JWKS JSON response
+pub fn jwks_response(key_ring: &JwksKeyRing) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header((header::CACHE_CONTROL, format!("public, max-age={}", JWKS_MAX_AGE)))
+        .body(key_ring.jwks_json())
+}
+
+/// GET 路由处理器，返回密钥环的当前公钥集 | GET route handler serving the key ring's current public key set
+pub fn jwks_handler(key_ring: JwksKeyRing) -> Route {
+    web::get().to(move || {
+        let key_ring = key_ring.clone();
+        async move { jwks_response(&key_ring) }
+    })
+}
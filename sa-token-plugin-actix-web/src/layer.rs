@@ -7,8 +7,9 @@ use actix_web::{
 };
 use crate::SaTokenState;
 use crate::adapter::ActixRequestAdapter;
+use crate::middleware::append_set_cookies;
 use sa_token_adapter::context::SaRequest;
-use sa_token_core::{token::TokenValue, SaTokenContext};
+use sa_token_core::{token::TokenValue, SaTokenContext, StpUtil};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -64,33 +65,48 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let service = Rc::clone(&self.service);
         let state = self.state.clone();
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = state.manager.config.tenant(&ActixRequestAdapter::new(req.request()));
         
-        Box::pin(async move {
+        Box::pin(sa_token_core::tenant::scope(tenant, async move {
             let mut ctx = SaTokenContext::new();
+            let client_ip = state.manager.config.client_ip(&ActixRequestAdapter::new(req.request()));
+            let fingerprint = state.manager.config.fingerprint(&ActixRequestAdapter::new(req.request()));
+            let mut token_str = extract_token_from_request(&req, &state);
+            // 会话失效时用 remember-me cookie 静默重建
+            let remember = ActixRequestAdapter::new(req.request()).get_cookie(&state.manager.config.remember_me_cookie);
+            let remembered = state.manager.restore_remembered(token_str.as_deref(), remember.as_deref()).await;
+            if let Some(session) = &remembered {
+                token_str = Some(session.token.as_str().to_string());
+            }
             
-            if let Some(token_str) = extract_token_from_request(&req, &state) {
-                tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+            if let Some(token_str) = token_str {
+                sa_token_core::logging::token_extracted(&state.manager.config, "layer", &token_str);
                 let token = TokenValue::new(token_str);
                 
-                if state.manager.is_valid(&token).await {
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if state.manager.is_valid(&token).await
+                    && let Ok(token_info) = state.manager.get_token_info(&token).await
+                    && state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
+                    let login_id = token_info.login_id.clone();
+                    state.manager.record_activity(&token_info, client_ip.as_deref()).await;
                     req.extensions_mut().insert(token.clone());
+                    req.extensions_mut().insert(login_id.clone());
                     
-                    if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        req.extensions_mut().insert(login_id.clone());
-                        
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                    }
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                 }
             }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+            ctx.typed_tokens = StpUtil::collect_typed_tokens(&ActixRequestAdapter::new(req.request()));
             
             SaTokenContext::set_current(ctx);
             let result = service.call(req).await;
             SaTokenContext::clear();
-            result
-        })
+            result.map(|response| append_set_cookies(response, remembered))
+        }))
     }
 }
 
@@ -104,10 +120,9 @@ fn extract_token_from_request(req: &ServiceRequest, state: &SaTokenState) -> Opt
     }
     
     // 2. 如果 token_name 不是 "Authorization"，也尝试从 "Authorization" 头获取
-    if token_name != "Authorization" {
-        if let Some(token) = adapter.get_header("Authorization") {
-            return Some(extract_bearer_token(&token));
-        }
+    if token_name != "Authorization"
+        && let Some(token) = adapter.get_header("Authorization") {
+        return Some(extract_bearer_token(&token));
     }
     
     // 3. 从 Cookie 中获取
@@ -118,10 +133,9 @@ fn extract_token_from_request(req: &ServiceRequest, state: &SaTokenState) -> Opt
     // 4. 从 Query 参数中获取
     if let Some(query) = req.query_string().split('&').find_map(|pair| {
         let mut parts = pair.split('=');
-        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
-            if key == token_name {
-                return urlencoding::decode(value).ok().map(|s| s.to_string());
-            }
+        if let (Some(key), Some(value)) = (parts.next(), parts.next())
+            && key == token_name {
+            return urlencoding::decode(value).ok().map(|s| s.to_string());
         }
         None
     }) {
@@ -132,8 +146,8 @@ fn extract_token_from_request(req: &ServiceRequest, state: &SaTokenState) -> Opt
 }
 
 fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
+    if let Some(stripped) = token.strip_prefix("Bearer ") {
+        stripped.to_string()
     } else {
         token.to_string()
     }
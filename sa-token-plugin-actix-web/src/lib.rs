@@ -45,11 +45,27 @@ pub mod middleware;
 pub mod extractor;
 pub mod adapter;
 pub mod layer;
+pub mod jwks;
+pub mod oauth2;
+pub mod sso;
+pub mod cas;
+#[cfg(feature = "saml")]
+pub mod saml;
 
-pub use middleware::{SaCheckLoginMiddleware, SaTokenMiddleware};
+pub use middleware::{SaCheckLoginMiddleware, SaCheckSameTokenMiddleware, SaApiKeyMiddleware, SaTokenMiddleware, SaVerifySignMiddleware, SaCheckSignMiddleware, SaCheckBasicMiddleware, SaCheckCsrfMiddleware};
 pub use layer::SaTokenLayer;
-pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor};
-pub use adapter::{ActixRequestAdapter, ActixResponseAdapter};
+pub use jwks::{jwks_handler, jwks_response};
+pub use oauth2::{
+    introspection_handler, introspection_response, revocation_handler, revocation_response,
+    userinfo_handler, userinfo_response, discovery_handler, discovery_response,
+    token_handler, token_response, oauth2_routes, OAuth2ServerState,
+};
+pub use cas::{cas_routes, cas_service_validate_handler, cas_p3_service_validate_handler, cas_xml_response, CasServerState};
+#[cfg(feature = "saml")]
+pub use saml::saml_routes;
+pub use sso::{sso_logout_handler, sso_logout_response, SsoClientMiddleware};
+pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, TypedLoginId, CurrentPermissions};
+pub use adapter::{ActixRequestAdapter, ActixResponseAdapter, write_token, clear_token, error_renderer, error_response};
 
 pub use sa_token_core::{self, prelude::*};
 pub use sa_token_adapter::{self, storage::SaStorage, framework::FrameworkAdapter};
@@ -72,6 +88,7 @@ pub use sa_token_storage_redis::RedisStorage;
 pub use sa_token_storage_database::DatabaseStorage;
 
 use std::sync::Arc;
+use std::collections::HashMap;
 use actix_web::web::Data;
 
 /// Actix-web应用数据
@@ -81,6 +98,12 @@ pub type SaTokenData = Data<SaTokenState>;
 #[derive(Clone)]
 pub struct SaTokenState {
     pub manager: Arc<SaTokenManager>,
+    
+    /// 本状态的中间件与提取器使用的拒绝响应渲染器 | Rejection renderer of this state's middlewares and extractors
+    pub error_renderer: Arc<dyn sa_token_core::ErrorRenderer>,
+    
+    /// 其他账号体系，按账号类型索引 | Other account systems keyed by account type
+    pub logics: HashMap<String, Arc<StpLogic>>,
 }
 
 impl SaTokenState {
@@ -88,12 +111,18 @@ impl SaTokenState {
     pub fn builder() -> SaTokenStateBuilder {
         SaTokenStateBuilder::new()
     }
+    
+    /// 获取指定账号类型的账号体系 | Get the account system of an account type
+    pub fn logic(&self, login_type: &str) -> Option<&Arc<StpLogic>> {
+        self.logics.get(login_type)
+    }
 }
 
 /// 状态构建器
 #[derive(Default)]
 pub struct SaTokenStateBuilder {
     config_builder: sa_token_core::config::SaTokenConfigBuilder,
+    logics: Vec<StpLogic>,
 }
 
 impl SaTokenStateBuilder {
@@ -153,11 +182,25 @@ impl SaTokenStateBuilder {
         self
     }
     
+    /// 设置本状态的拒绝响应渲染器，`SaTokenLayer` 把它交给之后的中间件与提取器 | Set this state's rejection renderer, handed by `SaTokenLayer` to the middlewares and extractors after it
+    pub fn error_renderer(mut self, renderer: Arc<dyn sa_token_core::ErrorRenderer>) -> Self {
+        self.config_builder = self.config_builder.error_renderer(renderer);
+        self
+    }
+    
+    /// 添加账号体系（如 admin），构建时注册到 StpUtil | Add an account system (e.g. admin), registered with StpUtil on build
+    pub fn logic(mut self, logic: StpLogic) -> Self {
+        self.logics.push(logic);
+        self
+    }
+    
     pub fn build(self) -> Data<SaTokenState> {
         let manager = self.config_builder.build();
                 
         Data::new(SaTokenState {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: StpUtil::register_logics(self.logics),
         })
     }
 }
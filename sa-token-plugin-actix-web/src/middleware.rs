@@ -6,17 +6,19 @@ use std::future::{ready, Ready, Future};
 use std::pin::Pin;
 use std::rc::Rc;
 use actix_web::{
-    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, error::ErrorUnauthorized,
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    Error, HttpMessage,
+    web::Bytes,
 };
+use futures_util::StreamExt;
 use crate::SaTokenState;
-use crate::adapter::ActixRequestAdapter;
+use crate::adapter::{ActixRequestAdapter, error_renderer, error_response, rejection, rendered_response};
 use sa_token_adapter::context::SaRequest;
-use sa_token_core::{token::TokenValue, SaTokenContext, error::messages};
+use sa_token_core::{RememberedSession, api_key::ApiKeyManager, basic::HttpBasicAuth, csrf::CsrfManager, token::TokenValue, SaTokenContext, StpUtil, error::SaTokenError, same_token::SameTokenManager, sign::{BodySignVerifier, SaSignManager}};
 use std::sync::Arc;
 
 /// sa-token 基础中间件 - 提取并验证 token
-use sa_token_core::router::PathAuthConfig;
+use sa_token_core::router::{CompiledPattern, PathAuthConfig};
 
 /// Sa-Token middleware with optional path-based authentication
 /// 支持可选路径鉴权的 Sa-Token 中间件
@@ -88,15 +90,38 @@ where
         let service = Rc::clone(&self.service);
         let state = self.state.clone();
         let path_config = self.path_config.clone();
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = state.manager.config.tenant(&ActixRequestAdapter::new(req.request()));
+        // 之后的中间件与提取器用本状态的渲染器生成拒绝响应
+        req.extensions_mut().insert(state.error_renderer.clone());
         
-        Box::pin(async move {
+        Box::pin(sa_token_core::tenant::scope(tenant, async move {
+            let mut token_str = extract_token_from_request(&req, &state);
+            // 会话失效时用 remember-me cookie 静默重建
+            let remember = ActixRequestAdapter::new(req.request()).get_cookie(&state.manager.config.remember_me_cookie);
+            let remembered = state.manager.restore_remembered(token_str.as_deref(), remember.as_deref()).await;
+            if let Some(session) = &remembered {
+                token_str = Some(session.token.as_str().to_string());
+            }
+            
             if let Some(config) = path_config {
                 let path = req.path();
-                let token_str = extract_token_from_request(&req, &state);
-                let result = sa_token_core::router::process_auth(path, token_str, &config, &state.manager).await;
+                let header_version = config.version_header_name()
+                    .and_then(|name| req.headers().get(name))
+                    .and_then(|v| v.to_str().ok());
+                let client_ip = config.client_ip(
+                    config.client_ip_header_name()
+                        .and_then(|name| req.headers().get(name))
+                        .and_then(|v| v.to_str().ok()),
+                    state.manager.config.client_ip(&ActixRequestAdapter::new(req.request())),
+                );
+                let fingerprint = state.manager.config.fingerprint(&ActixRequestAdapter::new(req.request()));
+                let result = sa_token_core::router::process_auth_request(path, Some(req.method().as_str()), header_version, client_ip.as_deref(), fingerprint.as_deref(), token_str, &config, &state.manager).await;
                 
                 if result.should_reject() {
-                    return Err(ErrorUnauthorized(serde_json::json!({"code": 401, "message": messages::AUTH_ERROR}).to_string()));
+                    let (status, headers, body) = config.render_rejection_response(&result, state.error_renderer.as_ref());
+                    let response = rendered_response((status, headers, body.clone()));
+                    return Err(actix_web::error::InternalError::from_response(body, response).into());
                 }
                 
                 if let Some(token) = &result.token {
@@ -106,34 +131,52 @@ where
                     req.extensions_mut().insert(login_id.to_string());
                 }
                 
-                let ctx = sa_token_core::router::create_context(&result);
+                let mut ctx = sa_token_core::router::create_context(&result);
+                ctx.typed_tokens = StpUtil::collect_typed_tokens(&ActixRequestAdapter::new(req.request()));
                 SaTokenContext::set_current(ctx);
                 let response = service.call(req).await;
                 SaTokenContext::clear();
-                return response;
+                return response.map(|response| append_set_cookies(response, remembered));
             }
             
             let mut ctx = SaTokenContext::new();
-            if let Some(token_str) = extract_token_from_request(&req, &state) {
+            let client_ip = state.manager.config.client_ip(&ActixRequestAdapter::new(req.request()));
+            let fingerprint = state.manager.config.fingerprint(&ActixRequestAdapter::new(req.request()));
+            if let Some(token_str) = token_str {
                 let token = TokenValue::new(token_str);
-                if state.manager.is_valid(&token).await {
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if state.manager.is_valid(&token).await
+                    && let Ok(token_info) = state.manager.get_token_info(&token).await
+                    && state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
+                    let login_id = token_info.login_id.clone();
+                    state.manager.record_activity(&token_info, client_ip.as_deref()).await;
                     req.extensions_mut().insert(token.clone());
-                    if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        req.extensions_mut().insert(login_id.clone());
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                    }
+                    req.extensions_mut().insert(login_id.clone());
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                 }
             }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+            ctx.typed_tokens = StpUtil::collect_typed_tokens(&ActixRequestAdapter::new(req.request()));
             
             SaTokenContext::set_current(ctx);
             let result = service.call(req).await;
             SaTokenContext::clear();
-            result
-        })
+            result.map(|response| append_set_cookies(response, remembered))
+        }))
+    }
+}
+
+/// 把 remember-me 重建会话产生的 cookie 写入响应
+pub(crate) fn append_set_cookies<B>(mut response: ServiceResponse<B>, remembered: Option<RememberedSession>) -> ServiceResponse<B> {
+    for cookie in remembered.into_iter().flat_map(|session| session.set_cookies) {
+        if let Ok(value) = actix_web::http::header::HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(actix_web::http::header::SET_COOKIE, value);
+        }
     }
+    response
 }
 
 /// sa-token 登录检查中间件 - 强制要求登录
@@ -192,7 +235,7 @@ where
             let mut ctx = SaTokenContext::new();
             // 提取 token
             if let Some(token_str) = extract_token_from_request(&req, &state) {
-                tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
+                sa_token_core::logging::token_extracted(&state.manager.config, "login-check", &token_str);
                 let token = TokenValue::new(token_str);
 
                 // 验证 token
@@ -217,10 +260,7 @@ where
             }
 
             // 未登录，返回 401
-            Err(ErrorUnauthorized(serde_json::json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            }).to_string()))
+            Err(rejection(&req.extensions(), &SaTokenError::NotLogin))
         })
     }
 }
@@ -228,52 +268,517 @@ where
 /// 从请求中提取 token
 pub fn extract_token_from_request(req: &ServiceRequest, state: &SaTokenState) -> Option<String> {
     let adapter = ActixRequestAdapter::new(req.request());
-    let token_name = &state.manager.config.token_name;
-    
-    tracing::debug!("Sa-Token: 尝试从请求提取 token，token_name: {}", token_name);
+    let config = &state.manager.config;
+    let token_name = &config.token_name;
     
     // 1. 优先从 Header 中获取（检查 token_name 配置的头）
     if let Some(token) = adapter.get_header(token_name) {
-        tracing::debug!("Sa-Token: 从 Header[{}] 获取到 token", token_name);
+        sa_token_core::logging::token_source(config, "header", token_name);
         return Some(extract_bearer_token(&token));
     }
     
     // 2. 如果 token_name 不是 "Authorization"，也尝试从 "Authorization" 头获取
-    if token_name != "Authorization" {
-        if let Some(token) = adapter.get_header("Authorization") {
-            tracing::debug!("Sa-Token: 从 Header[Authorization] 获取到 token");
-            return Some(extract_bearer_token(&token));
-        }
+    if token_name != "Authorization"
+        && let Some(token) = adapter.get_header("Authorization") {
+        sa_token_core::logging::token_source(config, "header", "Authorization");
+        return Some(extract_bearer_token(&token));
     }
     
     // 3. 从 Cookie 中获取
     if let Some(token) = adapter.get_cookie(token_name) {
-        tracing::debug!("Sa-Token: 从 Cookie[{}] 获取到 token", token_name);
+        sa_token_core::logging::token_source(config, "cookie", token_name);
         return Some(token);
     }
     
     // 4. 从 Query 参数中获取
     if let Some(query) = req.query_string().split('&').find_map(|pair| {
         let mut parts = pair.split('=');
-        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
-            if key == token_name {
-                return urlencoding::decode(value).ok().map(|s| s.to_string());
-            }
+        if let (Some(key), Some(value)) = (parts.next(), parts.next())
+            && key == token_name {
+            return urlencoding::decode(value).ok().map(|s| s.to_string());
         }
         None
     }) {
-        tracing::debug!("Sa-Token: 从 Query[{}] 获取到 token", token_name);
+        sa_token_core::logging::token_source(config, "query", token_name);
         return Some(query);
     }
     
-    tracing::debug!("Sa-Token: 所有位置都未找到 token");
+    sa_token_core::logging::token_missing(config);
     None
 }
 
 fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
+    if let Some(stripped) = token.strip_prefix("Bearer ") {
+        stripped.to_string()
     } else {
         token.to_string()
     }
 }
+
+/// sa-token 请求体签名校验中间件 - 用于 Webhook、回调等签名接口
+/// 
+/// 边读取请求体边计算 HMAC-SHA256，超过大小上限立即返回413，签名缺失或不匹配返回401，
+/// 校验通过后将缓存的请求体放回请求，下游提取器可照常读取
+pub struct SaVerifySignMiddleware {
+    pub verifier: BodySignVerifier,
+}
+
+impl SaVerifySignMiddleware {
+    pub fn new(verifier: BodySignVerifier) -> Self {
+        Self { verifier }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaVerifySignMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaVerifySignMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaVerifySignMiddlewareService {
+            service: Rc::new(service),
+            verifier: self.verifier.clone(),
+        }))
+    }
+}
+
+pub struct SaVerifySignMiddlewareService<S> {
+    service: Rc<S>,
+    verifier: BodySignVerifier,
+}
+
+impl<S, B> Service<ServiceRequest> for SaVerifySignMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let verifier = self.verifier.clone();
+
+        Box::pin(async move {
+            match verify_body(&verifier, &mut req).await {
+                Ok(body) => {
+                    // 放回已校验的请求体
+                    req.set_payload(Payload::from(Bytes::from(body)));
+                    service.call(req).await
+                }
+                Err(e) => Err(rejection(&req.extensions(), &e)),
+            }
+        })
+    }
+}
+
+/// sa-token Same-Token 校验中间件 - 用于仅限内部服务调用的接口
+/// 
+/// 请求头缺少 Same-Token 或 Same-Token 无效时返回401
+pub struct SaCheckSameTokenMiddleware {
+    pub manager: SameTokenManager,
+}
+
+impl SaCheckSameTokenMiddleware {
+    pub fn new(manager: SameTokenManager) -> Self {
+        Self { manager }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaCheckSameTokenMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaCheckSameTokenMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaCheckSameTokenMiddlewareService {
+            service: Rc::new(service),
+            manager: self.manager.clone(),
+        }))
+    }
+}
+
+pub struct SaCheckSameTokenMiddlewareService<S> {
+    service: Rc<S>,
+    manager: SameTokenManager,
+}
+
+impl<S, B> Service<ServiceRequest> for SaCheckSameTokenMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let manager = self.manager.clone();
+
+        Box::pin(async move {
+            let token = ActixRequestAdapter::new(req.request())
+                .get_header(manager.header_name())
+                .unwrap_or_default();
+
+            match manager.check(&token).await {
+                Ok(()) => service.call(req).await,
+                Err(e) => Err(rejection(&req.extensions(), &e)),
+            }
+        })
+    }
+}
+
+/// sa-token API Key 认证中间件 - 接受 `X-API-Key` 请求头作为会话 token 之外的凭证
+/// 
+/// 放在 `SaTokenMiddleware` 之内，用 `LoginIdExtractor` 要求登录；请求未登录且携带 Key 时校验，
+/// 有效时把绑定的 login_id 和 `ApiKeyInfo` 写入请求扩展，无效时返回401
+pub struct SaApiKeyMiddleware {
+    pub manager: ApiKeyManager,
+}
+
+impl SaApiKeyMiddleware {
+    pub fn new(manager: ApiKeyManager) -> Self {
+        Self { manager }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaApiKeyMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaApiKeyMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaApiKeyMiddlewareService {
+            service: Rc::new(service),
+            manager: self.manager.clone(),
+        }))
+    }
+}
+
+pub struct SaApiKeyMiddlewareService<S> {
+    service: Rc<S>,
+    manager: ApiKeyManager,
+}
+
+impl<S, B> Service<ServiceRequest> for SaApiKeyMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let manager = self.manager.clone();
+
+        Box::pin(async move {
+            let key = ActixRequestAdapter::new(req.request()).get_header(manager.header_name());
+            let Some(key) = key.filter(|_| req.extensions().get::<String>().is_none()) else {
+                return service.call(req).await;
+            };
+
+            match manager.verify(&key).await {
+                Ok(info) => {
+                    let mut ctx = SaTokenContext::get_current().unwrap_or_default();
+                    ctx.login_id = Some(info.login_id.clone());
+                    SaTokenContext::set_current(ctx);
+                    req.extensions_mut().insert(info.login_id.clone());
+                    req.extensions_mut().insert(info);
+                    service.call(req).await
+                }
+                Err(e) => Err(rejection(&req.extensions(), &e)),
+            }
+        })
+    }
+}
+
+/// sa-token 参数签名校验中间件 - 用于开放接口
+/// 
+/// 校验查询参数中的 timestamp、nonce、sign，未签名、签名过期、被篡改或重放时返回401；
+/// 未配置路径时校验所有请求
+pub struct SaCheckSignMiddleware {
+    pub manager: SaSignManager,
+    pub include: Vec<CompiledPattern>,
+}
+
+impl SaCheckSignMiddleware {
+    pub fn new(manager: SaSignManager) -> Self {
+        Self { manager, include: Vec::new() }
+    }
+
+    /// 只校验匹配这些模式的路径
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaCheckSignMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaCheckSignMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaCheckSignMiddlewareService {
+            service: Rc::new(service),
+            manager: self.manager.clone(),
+            include: self.include.clone(),
+        }))
+    }
+}
+
+pub struct SaCheckSignMiddlewareService<S> {
+    service: Rc<S>,
+    manager: SaSignManager,
+    include: Vec<CompiledPattern>,
+}
+
+impl<S, B> Service<ServiceRequest> for SaCheckSignMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let manager = self.manager.clone();
+        let include = self.include.clone();
+
+        Box::pin(async move {
+            if !include.is_empty() && !include.iter().any(|p| p.matches(req.path())) {
+                return service.call(req).await;
+            }
+
+            let params = SaSignManager::params_from_query(req.query_string());
+            match manager.verify(&params).await {
+                Ok(()) => service.call(req).await,
+                Err(e) => Err(rejection(&req.extensions(), &e)),
+            }
+        })
+    }
+}
+
+/// sa-token HTTP Basic 认证中间件 - 用于 `/metrics`、`/admin` 等运维接口
+/// 
+/// 凭证缺失或无效时返回401，并带上 `WWW-Authenticate` 响应头；未配置路径时校验所有请求
+pub struct SaCheckBasicMiddleware {
+    pub basic: HttpBasicAuth,
+    pub include: Vec<CompiledPattern>,
+}
+
+impl SaCheckBasicMiddleware {
+    pub fn new(basic: HttpBasicAuth) -> Self {
+        Self { basic, include: Vec::new() }
+    }
+
+    /// 只校验匹配这些模式的路径
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaCheckBasicMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaCheckBasicMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaCheckBasicMiddlewareService {
+            service: Rc::new(service),
+            basic: self.basic.clone(),
+            include: self.include.clone(),
+        }))
+    }
+}
+
+pub struct SaCheckBasicMiddlewareService<S> {
+    service: Rc<S>,
+    basic: HttpBasicAuth,
+    include: Vec<CompiledPattern>,
+}
+
+impl<S, B> Service<ServiceRequest> for SaCheckBasicMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let basic = self.basic.clone();
+        let include = self.include.clone();
+
+        Box::pin(async move {
+            if !include.is_empty() && !include.iter().any(|p| p.matches(req.path())) {
+                return service.call(req).await;
+            }
+
+            let authorization = ActixRequestAdapter::new(req.request()).get_header("Authorization");
+            match basic.check(authorization.as_deref()) {
+                Ok(_) => service.call(req).await,
+                Err(e) => {
+                    let mut response = error_response(error_renderer(&req.extensions()).as_ref(), &e);
+                    if let Ok(challenge) = actix_web::http::header::HeaderValue::from_str(&basic.challenge()) {
+                        response.headers_mut().insert(actix_web::http::header::WWW_AUTHENTICATE, challenge);
+                    }
+                    Err(actix_web::error::InternalError::from_response(e.to_string(), response).into())
+                }
+            }
+        })
+    }
+}
+
+/// sa-token 防 CSRF 中间件 - 用于通过 Cookie 传递 token 的会话
+/// 
+/// 携带会话 Cookie 的 POST / PUT / PATCH / DELETE 等写请求，
+/// `X-CSRF-Token` 请求头缺失或与会话的 CSRF 令牌不一致时返回403
+pub struct SaCheckCsrfMiddleware {
+    pub csrf: CsrfManager,
+}
+
+impl SaCheckCsrfMiddleware {
+    pub fn new(csrf: CsrfManager) -> Self {
+        Self { csrf }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SaCheckCsrfMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SaCheckCsrfMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SaCheckCsrfMiddlewareService {
+            service: Rc::new(service),
+            csrf: self.csrf.clone(),
+        }))
+    }
+}
+
+pub struct SaCheckCsrfMiddlewareService<S> {
+    service: Rc<S>,
+    csrf: CsrfManager,
+}
+
+impl<S, B> Service<ServiceRequest> for SaCheckCsrfMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let csrf = self.csrf.clone();
+
+        Box::pin(async move {
+            let adapter = ActixRequestAdapter::new(req.request());
+            let session = adapter.get_cookie(csrf.session_cookie());
+            let Some(session) = session.filter(|_| CsrfManager::requires_check(req.method().as_str())) else {
+                return service.call(req).await;
+            };
+
+            let header = adapter.get_header(csrf.header_name());
+            match csrf.verify(&TokenValue::new(session), header.as_deref()).await {
+                Ok(()) => service.call(req).await,
+                Err(e) => Err(rejection(&req.extensions(), &e)),
+            }
+        })
+    }
+}
+
+/// 逐块读取请求体并校验签名
+async fn verify_body(verifier: &BodySignVerifier, req: &mut ServiceRequest) -> Result<Vec<u8>, SaTokenError> {
+    let adapter = ActixRequestAdapter::new(req.request());
+    let signature = adapter.get_header(verifier.header_name())
+        .ok_or(SaTokenError::SignatureMissing)?;
+
+    // Content-Length 已声明超限时直接拒绝，不读取请求体
+    if let Some(len) = adapter.get_header("Content-Length")
+        .and_then(|v| v.parse::<usize>().ok())
+        && len > verifier.body_limit() {
+        return Err(SaTokenError::BodyTooLarge(verifier.body_limit()));
+    }
+
+    let mut payload = req.take_payload();
+    let mut state = verifier.start();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        state.update(&chunk)?;
+    }
+
+    state.finish(&signature)
+}
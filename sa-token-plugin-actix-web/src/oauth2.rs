@@ -0,0 +1,453 @@
+// Author: 金书记
+//
+//! OAuth2 授权服务器端点：授权、令牌、内省（RFC 7662）、撤销（RFC 7009）与 OpenID Connect | OAuth2 authorization server endpoints: authorize, token, introspection (RFC 7662), revocation (RFC 7009) and OpenID Connect
+//!
+//! `oauth2_routes` 一次挂载全部端点；也可以用单独的处理器自行组合。
+//! 资源服务器以机密客户端身份 POST `token` 内省，得到 `{"active": ...}` 形式的结果；
+//! 客户端 POST `token` 撤销自己的令牌，成功时返回空的 200。
+//! 启用 OIDC 后，userinfo 端点以 Bearer access_token 返回用户声明，发现端点返回提供方元数据。
+//! `oauth2_routes` mounts every endpoint at once; the individual handlers can also be combined by hand.
+//! Resource servers POST `token` as a confidential client to introspect it and get an
+//! `{"active": ...}` answer; clients POST `token` to revoke their own tokens, answered with an empty 200.
+//! With OIDC enabled, the userinfo endpoint returns the user's claims for a Bearer access token and
+//! the discovery endpoint returns the provider metadata.
+//!
+//! ```rust,ignore
+//! let oauth2 = Arc::new(OAuth2Manager::new(storage).with_oidc(provider));
+//!
+//! // GET/POST /oauth2/authorize, POST /oauth2/token, /oauth2/introspect, /oauth2/revoke, GET /oauth2/userinfo
+//! App::new()
+//!     .wrap(SaTokenLayer::new(sa_token_state))
+//!     .service(web::scope("/oauth2").configure(oauth2_routes(OAuth2ServerState::new(oauth2.clone()).login_url("/login"))))
+//!     .route("/.well-known/openid-configuration", discovery_handler(oauth2.clone()))
+//! ```
+
+use std::sync::Arc;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Route};
+use actix_web::http::{header, StatusCode};
+use sa_token_core::{
+    ErrorPage, OAuth2AuthorizeRequest, OAuth2AuthorizeResponse, OAuth2ConsentDecision, OAuth2ErrorResponse,
+    OAuth2IntrospectionRequest, OAuth2Manager, OAuth2RevocationRequest, OAuth2TokenRequest, SaPage, SaTokenError,
+};
+
+/// OAuth2 授权服务器路由状态 | State of the OAuth2 authorization server routes
+#[derive(Clone)]
+pub struct OAuth2ServerState {
+    oauth2: Arc<OAuth2Manager>,
+    authorize_url: String,
+    login_url: Option<String>,
+}
+
+impl OAuth2ServerState {
+    /// 创建路由状态 | Create the route state
+    ///
+    /// # 参数 | Parameters
+    /// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+    pub fn new(oauth2: Arc<OAuth2Manager>) -> Self {
+        Self {
+            oauth2,
+            authorize_url: "/oauth2/authorize".to_string(),
+            login_url: None,
+        }
+    }
+
+    /// 授权确认表单的提交地址（默认 `/oauth2/authorize`），挂载在其他前缀下时需要修改
+    /// URL the consent form posts to (default `/oauth2/authorize`); change it when mounting under another prefix
+    pub fn authorize_url(mut self, url: impl Into<String>) -> Self {
+        self.authorize_url = url.into();
+        self
+    }
+
+    /// 未登录用户访问授权端点时跳转的登录页，原地址以 `redirect` 参数传递；未设置时返回 401
+    /// Login page for users hitting the authorization endpoint without a login, with the original URL
+    /// in the `redirect` parameter; 401 when unset
+    pub fn login_url(mut self, url: impl Into<String>) -> Self {
+        self.login_url = Some(url.into());
+        self
+    }
+}
+
+/// 创建 OAuth2 授权服务器路由，配合 `web::scope(..).configure(..)` 使用 | Build the OAuth2 authorization server routes, for `web::scope(..).configure(..)`
+///
+/// ```text
+/// GET  /authorize    校验请求，展示确认页或直接携带 code 回调 | validate, then show the consent page or redirect with a code
+/// POST /authorize    确认页提交 | consent page submission
+/// POST /token        令牌端点，支持 HTTP Basic 与表单客户端认证 | token endpoint, HTTP Basic or form client authentication
+/// POST /introspect   令牌内省（RFC 7662）| token introspection (RFC 7662)
+/// POST /revoke       令牌撤销（RFC 7009）| token revocation (RFC 7009)
+/// GET  /userinfo     OpenID Connect 用户信息 | OpenID Connect userinfo
+/// ```
+///
+/// 登录用户从 `SaTokenLayer` 写入的请求扩展中读取，因此需要在外层应用 `SaTokenLayer`。
+/// The logged-in user is read from the request extensions set by `SaTokenLayer`, so wrap the
+/// app in `SaTokenLayer`.
+///
+/// # 参数 | Parameters
+/// * `state` - 路由状态 | Route state
+pub fn oauth2_routes(state: OAuth2ServerState) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        let oauth2 = state.oauth2.clone();
+        let decision_state = state.clone();
+        cfg.route("/authorize", web::get().to(move |req: HttpRequest, query: web::Query<OAuth2AuthorizeRequest>| {
+                let state = state.clone();
+                async move { authorize(&state, &req, &query).await }
+            }))
+            .route("/authorize", web::post().to(move |req: HttpRequest, form: web::Form<OAuth2ConsentDecision>| {
+                let state = decision_state.clone();
+                async move { authorize_decision(&state, &req, &form).await }
+            }))
+            .route("/token", token_handler(oauth2.clone()))
+            .route("/introspect", introspection_handler(oauth2.clone()))
+            .route("/revoke", revocation_handler(oauth2.clone()))
+            .route("/userinfo", userinfo_handler(oauth2));
+    }
+}
+
+async fn authorize(state: &OAuth2ServerState, req: &HttpRequest, request: &OAuth2AuthorizeRequest) -> HttpResponse {
+    let accept_language = req.headers().get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let Some(login_id) = req.extensions().get::<String>().cloned() else {
+        return match &state.login_url {
+            Some(login_url) => {
+                let separator = if login_url.contains('?') { '&' } else { '?' };
+                HttpResponse::SeeOther()
+                    .insert_header((header::LOCATION, format!("{}{}redirect={}", login_url, separator, urlencoding::encode(&req.uri().to_string()))))
+                    .finish()
+            }
+            None => error_page(&state.oauth2, StatusCode::UNAUTHORIZED, &SaTokenError::NotLogin.to_string(), accept_language),
+        };
+    };
+    let result = state.oauth2.authorize_request(&login_id, request, &state.authorize_url, accept_language).await;
+    authorize_response(&state.oauth2, result, accept_language)
+}
+
+async fn authorize_decision(state: &OAuth2ServerState, req: &HttpRequest, decision: &OAuth2ConsentDecision) -> HttpResponse {
+    let accept_language = req.headers().get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let Some(login_id) = req.extensions().get::<String>().cloned() else {
+        return error_page(&state.oauth2, StatusCode::UNAUTHORIZED, &SaTokenError::NotLogin.to_string(), accept_language);
+    };
+    let result = state.oauth2.authorize_decision(&login_id, decision).await;
+    authorize_response(&state.oauth2, result, accept_language)
+}
+
+/// 构建授权端点响应：回调重定向、确认页，或无法回调时的错误页
+/// Build the authorization endpoint response: the redirect, the consent page, or an error page when
+/// the client cannot be redirected to
+fn authorize_response(
+    oauth2: &OAuth2Manager,
+    result: Result<OAuth2AuthorizeResponse, SaTokenError>,
+    accept_language: Option<&str>,
+) -> HttpResponse {
+    match result {
+        Ok(OAuth2AuthorizeResponse::Redirect(location)) => HttpResponse::SeeOther()
+            .insert_header((header::LOCATION, location))
+            .finish(),
+        // 禁止缓存与嵌入，防止点击劫持 | No caching or framing, against clickjacking
+        Ok(OAuth2AuthorizeResponse::Consent(html)) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .insert_header((header::X_FRAME_OPTIONS, "DENY"))
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+        Err(e) => {
+            let status = if OAuth2ErrorResponse::from(&e).error == "server_error" {
+                StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            error_page(oauth2, status, &e.to_string(), accept_language)
+        }
+    }
+}
+
+/// 渲染错误页 | Render an error page
+fn error_page(oauth2: &OAuth2Manager, status: StatusCode, message: &str, accept_language: Option<&str>) -> HttpResponse {
+    let page = SaPage::Error(ErrorPage { status: status.as_u16(), message: message.to_string() });
+    match oauth2.page_renderer().render_negotiated(&page, accept_language) {
+        Ok(html) => HttpResponse::build(status).content_type("text/html; charset=utf-8").body(html),
+        Err(_) => HttpResponse::build(status).finish(),
+    }
+}
+
+/// 处理令牌请求并构建 JSON 响应 | Handle a token request and build the JSON response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+/// * `request` - 令牌表单参数 | Token form parameters
+pub async fn token_response(
+    oauth2: &OAuth2Manager,
+    authorization: Option<&str>,
+    request: &OAuth2TokenRequest,
+) -> HttpResponse {
+    match oauth2.token_endpoint_request(authorization, request).await {
+        // RFC 6749 §5.1
+        Ok(token) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .insert_header((header::PRAGMA, "no-cache"))
+            .json(token),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 处理内省请求并构建 JSON 响应 | Handle an introspection request and build the JSON response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+/// * `request` - 内省表单参数 | Introspection form parameters
+pub async fn introspection_response(
+    oauth2: &OAuth2Manager,
+    authorization: Option<&str>,
+    request: &OAuth2IntrospectionRequest,
+) -> HttpResponse {
+    match oauth2.introspection_request(authorization, request).await {
+        Ok(info) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .json(info),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 处理撤销请求并构建响应 | Handle a revocation request and build the response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+/// * `request` - 撤销表单参数 | Revocation form parameters
+pub async fn revocation_response(
+    oauth2: &OAuth2Manager,
+    authorization: Option<&str>,
+    request: &OAuth2RevocationRequest,
+) -> HttpResponse {
+    match oauth2.revocation_request(authorization, request).await {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 处理 userinfo 请求并构建 JSON 响应 | Handle a userinfo request and build the JSON response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值，形如 `Bearer <access_token>` | Value of the `Authorization` header, `Bearer <access_token>`
+pub async fn userinfo_response(oauth2: &OAuth2Manager, authorization: Option<&str>) -> HttpResponse {
+    let Some(access_token) = authorization.and_then(|v| v.strip_prefix("Bearer ")) else {
+        return HttpResponse::Unauthorized()
+            .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+            .finish();
+    };
+    match oauth2.userinfo(access_token.trim()).await {
+        Ok(claims) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .json(claims),
+        Err(e) => bearer_error_response(&e),
+    }
+}
+
+/// 构建发现文档响应，未启用 OIDC 时返回 404 | Build the discovery document response, 404 when OIDC is disabled
+pub fn discovery_response(oauth2: &OAuth2Manager) -> HttpResponse {
+    match oauth2.oidc() {
+        Some(oidc) => HttpResponse::Ok().json(oidc.discovery()),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+/// 构建 Bearer 错误响应（RFC 6750），401 / 403 时附带质询 | Build a Bearer error response (RFC 6750), with a challenge on 401 / 403
+fn bearer_error_response(err: &SaTokenError) -> HttpResponse {
+    let error = OAuth2ErrorResponse::from(err);
+    let status = StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+    let mut response = HttpResponse::build(status);
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        response.insert_header((header::WWW_AUTHENTICATE, format!("Bearer error=\"{}\"", error.error)));
+    }
+    response.json(error)
+}
+
+/// 构建 OAuth2 错误响应，401 时附带 Basic 质询 | Build an OAuth2 error response, with a Basic challenge on 401
+fn error_response(err: &SaTokenError) -> HttpResponse {
+    let error = OAuth2ErrorResponse::from(err);
+    let status = StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+    let mut response = HttpResponse::build(status);
+    if status == StatusCode::UNAUTHORIZED {
+        response.insert_header((header::WWW_AUTHENTICATE, "Basic"));
+    }
+    response.json(error)
+}
+
+/// POST 路由处理器，处理令牌端点请求 | POST route handler for token endpoint requests
+pub fn token_handler(oauth2: Arc<OAuth2Manager>) -> Route {
+    web::post().to(move |req: HttpRequest, form: Result<web::Form<OAuth2TokenRequest>, actix_web::Error>| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let request = match form {
+                Ok(form) => form.into_inner(),
+                Err(e) => {
+                    return HttpResponse::BadRequest().json(OAuth2ErrorResponse {
+                        error: "invalid_request".to_string(),
+                        error_description: Some(e.to_string()),
+                    });
+                }
+            };
+            let authorization = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            token_response(&oauth2, authorization, &request).await
+        }
+    })
+}
+
+/// POST 路由处理器，内省表单中的 `token` | POST route handler introspecting the form's `token`
+pub fn introspection_handler(oauth2: Arc<OAuth2Manager>) -> Route {
+    web::post().to(move |req: HttpRequest, form: web::Form<OAuth2IntrospectionRequest>| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let authorization = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            introspection_response(&oauth2, authorization, &form).await
+        }
+    })
+}
+
+/// POST 路由处理器，撤销表单中的 `token` | POST route handler revoking the form's `token`
+pub fn revocation_handler(oauth2: Arc<OAuth2Manager>) -> Route {
+    web::post().to(move |req: HttpRequest, form: web::Form<OAuth2RevocationRequest>| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let authorization = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            revocation_response(&oauth2, authorization, &form).await
+        }
+    })
+}
+
+/// GET 路由处理器，返回 Bearer access_token 对应的用户信息 | GET route handler returning the userinfo of the Bearer access token
+pub fn userinfo_handler(oauth2: Arc<OAuth2Manager>) -> Route {
+    web::get().to(move |req: HttpRequest| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let authorization = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            userinfo_response(&oauth2, authorization).await
+        }
+    })
+}
+
+/// GET 路由处理器，返回 OpenID Connect 发现文档 | GET route handler returning the OpenID Connect discovery document
+pub fn discovery_handler(oauth2: Arc<OAuth2Manager>) -> Route {
+    web::get().to(move || {
+        let oauth2 = oauth2.clone();
+        async move { discovery_response(&oauth2) }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::MessageBody;
+    use actix_web::dev::{Service, ServiceResponse};
+    use actix_web::{test, App};
+    use sa_token_core::OAuth2Client;
+
+    /// 测试中以 `X-Login-Id` 模拟 `SaTokenLayer` 写入的登录用户 | Tests use `X-Login-Id` in place of the user `SaTokenLayer` sets
+    const LOGIN_HEADER: &str = "X-Login-Id";
+
+    fn request(method: &str, uri: &str, login_id: Option<&str>, form: &str) -> test::TestRequest {
+        let mut request = test::TestRequest::default()
+            .method(method.parse().unwrap())
+            .uri(uri)
+            .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+            .insert_header((header::AUTHORIZATION, "Basic d2ViOnNlY3JldA=="))
+            .set_payload(form.to_string());
+        if let Some(login_id) = login_id {
+            request = request.insert_header((LOGIN_HEADER, login_id));
+        }
+        request
+    }
+
+    async fn body<B: MessageBody>(response: ServiceResponse<B>) -> String {
+        String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+    }
+
+    async fn oauth2_manager() -> Arc<OAuth2Manager> {
+        let oauth2 = Arc::new(OAuth2Manager::new(Arc::new(sa_token_storage_memory::MemoryStorage::new())));
+        oauth2.register_client(&OAuth2Client {
+            client_id: "web".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uris: vec!["https://app/cb".to_string()],
+            grant_types: vec!["authorization_code".to_string()],
+            scope: vec!["read".to_string()],
+        }).await.unwrap();
+        oauth2
+    }
+
+    #[actix_web::test]
+    async fn test_oauth2_routes() {
+        let oauth2 = oauth2_manager().await;
+        let app = test::init_service(
+            App::new()
+                .service(web::scope("/oauth2").configure(oauth2_routes(OAuth2ServerState::new(oauth2).login_url("/login"))))
+                .wrap_fn(|req, srv| {
+                    if let Some(login_id) = req.headers().get(LOGIN_HEADER).and_then(|v| v.to_str().ok()) {
+                        req.extensions_mut().insert(login_id.to_string());
+                    }
+                    srv.call(req)
+                }),
+        ).await;
+        let authorize = "/oauth2/authorize?response_type=code&client_id=web&redirect_uri=https://app/cb&scope=read";
+
+        let response = test::call_service(&app, request("GET", authorize, None, "").to_request()).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert!(response.headers().get(header::LOCATION).unwrap().to_str().unwrap().starts_with("/login?redirect=%2Foauth2%2Fauthorize%3F"));
+
+        let response = test::call_service(&app, request("GET", authorize, Some("alice"), "").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::X_FRAME_OPTIONS).unwrap(), "DENY");
+        let html = body(response).await;
+        assert!(html.contains(r#"action="/oauth2/authorize""#));
+        let ticket = html.split(r#"name="ticket" value=""#).nth(1).unwrap().split('"').next().unwrap();
+
+        let form = format!("ticket={}&decision=approve", ticket);
+        let response = test::call_service(&app, request("POST", "/oauth2/authorize", Some("alice"), &form).to_request()).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let location = response.headers().get(header::LOCATION).unwrap().to_str().unwrap();
+        let code = location.strip_prefix("https://app/cb?code=").unwrap().to_string();
+
+        let form = format!("grant_type=authorization_code&code={}&redirect_uri=https://app/cb", code);
+        let response = test::call_service(&app, request("POST", "/oauth2/token", None, &form).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::CACHE_CONTROL).unwrap(), "no-store");
+        let token: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        let access_token = token["access_token"].as_str().unwrap().to_string();
+
+        // 授权码已被使用 | The code was already used
+        let response = test::call_service(&app, request("POST", "/oauth2/token", None, &form).to_request()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body(response).await.contains(r#""error":"invalid_grant""#));
+
+        let response = test::call_service(&app, request("POST", "/oauth2/token", None, "code=x").to_request()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body(response).await.contains(r#""error":"invalid_request""#));
+
+        // 内省与撤销 | Introspection and revocation
+        let form = format!("token={}", access_token);
+        let response = test::call_service(&app, request("POST", "/oauth2/introspect", None, &form).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::CACHE_CONTROL).unwrap(), "no-store");
+        let info: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        assert_eq!(info["active"], true);
+        assert_eq!(info["client_id"], "web");
+
+        let unauthenticated = test::TestRequest::post()
+            .uri("/oauth2/introspect")
+            .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+            .set_payload(form.clone())
+            .to_request();
+        let response = test::call_service(&app, unauthenticated).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers().get(header::WWW_AUTHENTICATE).unwrap(), "Basic");
+
+        let response = test::call_service(&app, request("POST", "/oauth2/revoke", None, &form).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body(response).await.is_empty());
+        let response = test::call_service(&app, request("POST", "/oauth2/introspect", None, &form).to_request()).await;
+        assert_eq!(body(response).await, r#"{"active":false}"#);
+
+        // 未知令牌的撤销同样成功 | Revoking an unknown token succeeds too
+        let response = test::call_service(&app, request("POST", "/oauth2/revoke", None, "token=unknown").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,85 @@
+// Author: 金书记
+//
+//! SAML 2.0 服务提供者端点 | SAML 2.0 service provider endpoints
+//!
+//! `saml_routes` 挂载 `/login`、`/acs` 与 `/metadata`。IdP 中登记的 ACS 地址须与
+//! `SamlServiceProvider` 的 `acs_url` 一致。需要启用 `saml` 特性。
+//! `saml_routes` mounts `/login`, `/acs` and `/metadata`. The ACS URL registered with the IdP must
+//! equal the `acs_url` of the `SamlServiceProvider`. Requires the `saml` feature.
+//!
+//! ```rust,ignore
+//! let idp = IdpMetadata::from_xml(&std::fs::read_to_string("idp-metadata.xml")?)?;
+//! let sp = Arc::new(SamlServiceProvider::new(manager, "https://app.example.com", "https://app.example.com/saml/acs", idp));
+//!
+//! App::new()
+//!     .wrap(SaTokenLayer::new(sa_token_state))
+//!     .service(web::scope("/saml").configure(saml_routes(sp)))
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::http::{header, StatusCode};
+use sa_token_core::{ErrorPage, SaPage, SamlServiceProvider, SsoClient};
+
+/// 创建 SAML 路由，配合 `web::scope(..).configure(..)` 使用 | Build the SAML routes, for `web::scope(..).configure(..)`
+///
+/// ```text
+/// GET  /login?back=/path   跳转到 IdP 登录 | redirect to the IdP login
+/// POST /acs                校验 SAMLResponse，写入 Token Cookie 并回跳 RelayState
+///                          | validate the SAMLResponse, set the token cookie and return to RelayState
+/// GET  /metadata           SP 元数据 | SP metadata
+/// ```
+///
+/// # 参数 | Parameters
+/// * `sp` - SAML 服务提供者 | SAML service provider
+pub fn saml_routes(sp: Arc<SamlServiceProvider>) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        let (login_sp, acs_sp) = (sp.clone(), sp.clone());
+        cfg.route("/login", web::get().to(move |req: HttpRequest, query: web::Query<BTreeMap<String, String>>| {
+                let sp = login_sp.clone();
+                async move { login(&sp, &req, &query).await }
+            }))
+            .route("/acs", web::post().to(move |req: HttpRequest, form: web::Form<BTreeMap<String, String>>| {
+                let sp = acs_sp.clone();
+                async move { acs(&sp, &req, &form).await }
+            }))
+            .route("/metadata", web::get().to(move || {
+                let sp = sp.clone();
+                async move {
+                    HttpResponse::Ok().content_type("application/samlmetadata+xml").body(sp.sp_metadata())
+                }
+            }));
+    }
+}
+
+async fn login(sp: &SamlServiceProvider, req: &HttpRequest, params: &BTreeMap<String, String>) -> HttpResponse {
+    let back = SsoClient::safe_back(params.get("back").map(String::as_str));
+    match sp.authn_request(Some(back)).await {
+        Ok(request) => HttpResponse::SeeOther().insert_header((header::LOCATION, request.url)).finish(),
+        Err(e) => error_page(sp, StatusCode::INTERNAL_SERVER_ERROR, &e.to_string(), req),
+    }
+}
+
+async fn acs(sp: &SamlServiceProvider, req: &HttpRequest, params: &BTreeMap<String, String>) -> HttpResponse {
+    let Some(saml_response) = params.get("SAMLResponse") else {
+        return error_page(sp, StatusCode::BAD_REQUEST, "Missing SAMLResponse", req);
+    };
+    match sp.login(saml_response).await {
+        Ok((_, token)) => HttpResponse::SeeOther()
+            .insert_header((header::LOCATION, SsoClient::safe_back(params.get("RelayState").map(String::as_str))))
+            .insert_header((header::SET_COOKIE, sp.token_cookie(token.as_str())))
+            .finish(),
+        Err(e) => error_page(sp, StatusCode::UNAUTHORIZED, &e.to_string(), req),
+    }
+}
+
+/// 渲染错误页 | Render an error page
+fn error_page(sp: &SamlServiceProvider, status: StatusCode, message: &str, req: &HttpRequest) -> HttpResponse {
+    let accept_language = req.headers().get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let page = SaPage::Error(ErrorPage { status: status.as_u16(), message: message.to_string() });
+    match sp.page_renderer().render_negotiated(&page, accept_language) {
+        Ok(html) => HttpResponse::build(status).content_type("text/html; charset=utf-8").body(html),
+        Err(_) => HttpResponse::build(status).finish(),
+    }
+}
@@ -0,0 +1,282 @@
+// Author: 金书记
+//
+//! SSO 客户端集成 | SSO client integration
+//!
+//! `SsoClientMiddleware` 把未登录的浏览器请求 302 重定向到 SSO 登录中心，并在票据回跳时透明地完成本地登录；
+//! 需注册在 `SaTokenMiddleware` 内层，以便读取已登录用户。
+//! `SsoClientMiddleware` 302-redirects unauthenticated browser requests to the SSO login center and
+//! transparently completes the local login when the ticket comes back; register it inside
+//! `SaTokenMiddleware` so it can see the logged-in user.
+//!
+//! `sso_logout_handler` 接收 SSO 服务端推送的签名登出通知，验签后清除该用户的本地会话。
+//! `sso_logout_handler` receives the signed logout notifications pushed by the SSO server and,
+//! once the signature checks out, clears the user's local session.
+//!
+//! ```rust,ignore
+//! let sso_client = Arc::new(
+//!     SsoClient::new(manager, server_auth_url, service_url)
+//!         .with_sign(SaSignManager::new("shared-secret", storage.clone()))
+//!         .with_logout_callback_url("https://app1.example.com/sso/logout-callback"),
+//! );
+//!
+//! App::new()
+//!     .wrap(SsoClientMiddleware::new(sso_client.clone()).include(vec!["/orders/**".to_string()]))
+//!     .wrap(SaTokenMiddleware::new(state))
+//!     .route("/sso/logout-callback", sso_logout_handler(sso_client.clone()))
+//! ```
+
+use std::collections::BTreeMap;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::{ErrorUnauthorized, InternalError},
+    http::{header, Method},
+    web, Error, HttpMessage, HttpResponse, Route,
+};
+use sa_token_core::response::{error_json, success_json};
+use sa_token_core::router::CompiledPattern;
+use sa_token_core::{SaSignManager, SaTokenError, SsoClient};
+
+/// 处理登出通知并构建 JSON 响应 | Handle a logout notification and build the JSON response
+///
+/// 成功返回 200，签名无效返回 401，客户端未配置签名管理器返回 500
+/// 200 on success, 401 for an invalid signature, 500 when the client has no sign manager
+pub async fn sso_logout_response(client: &SsoClient, params: &BTreeMap<String, String>) -> HttpResponse {
+    match client.handle_logout_request(params).await {
+        Ok(login_id) => HttpResponse::Ok().json(success_json(serde_json::json!({ "login_id": login_id }))),
+        Err(e @ SaTokenError::ConfigError(_)) => HttpResponse::InternalServerError().json(error_json(500, e)),
+        Err(e) => HttpResponse::Unauthorized().json(error_json(401, e)),
+    }
+}
+
+/// POST 路由处理器，接收 SSO 服务端的登出通知 | POST route handler receiving logout notifications from the SSO server
+pub fn sso_logout_handler(client: Arc<SsoClient>) -> Route {
+    web::post().to(move |form: web::Form<BTreeMap<String, String>>| {
+        let client = client.clone();
+        async move { sso_logout_response(&client, &form).await }
+    })
+}
+
+/// SSO 客户端中间件 | SSO client middleware
+///
+/// 未配置路径时保护所有请求；票据回跳地址（服务 URL 的路径）总是由该中间件处理。
+/// Protects every request when no paths are configured; the ticket callback
+/// (the path of the service URL) is always handled by this middleware.
+///
+/// - 已登录：放行 | Logged in: passed through
+/// - 回跳地址带 `ticket`：换取本地 Token 写入 Cookie，再 302 回到 `back` 页面，票据无效时返回401
+///   | Callback carrying `ticket`: the ticket is exchanged for a local token stored in a cookie, then
+///   302 back to the `back` page; an invalid ticket gets 401
+/// - 未登录的浏览器 GET 请求：302 到 SSO 登录中心，并带上当前页面 | Unauthenticated browser GET:
+///   302 to the SSO login center, carrying the current page
+/// - 其他未登录请求：返回401 | Any other unauthenticated request: 401
+pub struct SsoClientMiddleware {
+    pub client: Arc<SsoClient>,
+    pub include: Vec<CompiledPattern>,
+    pub exclude: Vec<CompiledPattern>,
+}
+
+impl SsoClientMiddleware {
+    pub fn new(client: Arc<SsoClient>) -> Self {
+        Self { client, include: Vec::new(), exclude: Vec::new() }
+    }
+
+    /// 只保护匹配这些模式的路径 | Only protect paths matching these patterns
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+
+    /// 不保护匹配这些模式的路径 | Never protect paths matching these patterns
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.exclude = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SsoClientMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SsoClientMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SsoClientMiddlewareService {
+            service: Rc::new(service),
+            client: self.client.clone(),
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+        }))
+    }
+}
+
+pub struct SsoClientMiddlewareService<S> {
+    service: Rc<S>,
+    client: Arc<SsoClient>,
+    include: Vec<CompiledPattern>,
+    exclude: Vec<CompiledPattern>,
+}
+
+impl<S, B> Service<ServiceRequest> for SsoClientMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let client = self.client.clone();
+        let include = self.include.clone();
+        let exclude = self.exclude.clone();
+
+        Box::pin(async move {
+            if req.extensions().get::<String>().is_some() {
+                return service.call(req).await;
+            }
+
+            let path = req.path().to_string();
+            if path == client.callback_path() {
+                let params = SaSignManager::params_from_query(req.query_string());
+                if let Some(ticket) = params.get("ticket") {
+                    let (_, token) = client.exchange_ticket(ticket).await
+                        .map_err(|e| ErrorUnauthorized(sa_token_core::response::error_body(401, &e)))?;
+                    let back = SsoClient::safe_back(params.get("back").map(String::as_str));
+                    let response = HttpResponse::Found()
+                        .insert_header((header::LOCATION, back))
+                        .insert_header((header::SET_COOKIE, client.token_cookie(&token)))
+                        .finish();
+                    return Err(InternalError::from_response("SSO ticket accepted", response).into());
+                }
+            }
+
+            let protected = (include.is_empty() || include.iter().any(|p| p.matches(&path)))
+                && !exclude.iter().any(|p| p.matches(&path));
+            if !protected {
+                return service.call(req).await;
+            }
+
+            let accepts_html = req.headers().get(header::ACCEPT)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("text/html"));
+            if req.method() == Method::GET && accepts_html {
+                let back = req.uri().path_and_query().map_or(path.as_str(), |pq| pq.as_str());
+                let response = HttpResponse::Found()
+                    .insert_header((header::LOCATION, client.get_login_url_with_back(back)))
+                    .finish();
+                return Err(InternalError::from_response("SSO login required", response).into());
+            }
+            Err(ErrorUnauthorized(sa_token_core::response::error_body(401, &SaTokenError::NotLogin)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use sa_token_core::{SaTokenConfig, SaTokenManager, SsoServer};
+    use sa_token_storage_memory::MemoryStorage;
+
+    /// 将中间件返回的错误转换为响应，与 actix 服务端的处理一致 | Turn middleware errors into responses, as the actix server does
+    fn respond(result: Result<ServiceResponse, Error>) -> HttpResponse {
+        match result {
+            Ok(response) => response.into_parts().1,
+            Err(e) => e.error_response(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_sso_logout_handler() {
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let client = Arc::new(
+            SsoClient::new(manager, "http://sso.com/auth".to_string(), "http://app.com/sso/login".to_string())
+                .with_sign(SaSignManager::new("sso-secret", Arc::new(MemoryStorage::new()))),
+        );
+        client.login_by_ticket("alice".to_string()).await.unwrap();
+        let app = test::init_service(App::new().route("/sso/logout-callback", sso_logout_handler(client.clone()))).await;
+
+        let post = |params: BTreeMap<String, String>| {
+            let body = params.iter()
+                .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+                .collect::<Vec<_>>()
+                .join("&");
+            test::TestRequest::post()
+                .uri("/sso/logout-callback")
+                .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+                .set_payload(body)
+                .to_request()
+        };
+        let params = |secret: &str| SaSignManager::new(secret, Arc::new(MemoryStorage::new()))
+            .sign(BTreeMap::from([("login_id".to_string(), "alice".to_string())]));
+
+        let response = test::call_service(&app, post(params("wrong-secret"))).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(client.check_local_login("alice").await);
+
+        let response = test::call_service(&app, post(params("sso-secret"))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!client.check_local_login("alice").await);
+    }
+
+    #[actix_web::test]
+    async fn test_sso_client_middleware() {
+        let storage: Arc<dyn sa_token_adapter::storage::SaStorage> = Arc::new(MemoryStorage::new());
+        let manager = Arc::new(SaTokenManager::new(storage, SaTokenConfig::default()));
+        let server = SsoServer::new(manager.clone()).with_allow_urls(vec!["http://app.com/sso/login".to_string()]);
+        let client = Arc::new(SsoClient::new(manager, "http://sso.com/auth".to_string(), "http://app.com/sso/login".to_string()));
+        let service = SsoClientMiddleware::new(client)
+            .include(vec!["/orders/**".to_string()])
+            .new_transform(test::ok_service())
+            .await
+            .unwrap();
+        let get = |uri: &str, accept: &str| test::TestRequest::get()
+            .uri(uri)
+            .insert_header((header::ACCEPT, accept))
+            .to_srv_request();
+
+        let response = respond(service.call(get("/public", "text/html")).await);
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = respond(service.call(get("/orders/1", "application/json")).await);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // 浏览器请求重定向到登录中心 | A browser request is redirected to the login center
+        let response = respond(service.call(get("/orders/1?tab=items", "text/html")).await);
+        assert_eq!(response.status(), StatusCode::FOUND);
+        let location = response.headers().get(header::LOCATION).unwrap().to_str().unwrap();
+        let service_url = SaSignManager::params_from_query(location.split_once('?').unwrap().1).remove("service").unwrap();
+        assert_eq!(service_url, "http://app.com/sso/login?back=%2Forders%2F1%3Ftab%3Ditems");
+
+        // 票据回跳：写入 Cookie 并回到原页面 | Ticket callback: cookie set and back to the original page
+        let redirect = server.build_redirect_url("alice", &service_url).await.unwrap();
+        let callback = redirect.strip_prefix("http://app.com").unwrap();
+        let response = respond(service.call(get(callback, "text/html")).await);
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers().get(header::LOCATION).unwrap(), "/orders/1?tab=items");
+        assert!(response.headers().get(header::SET_COOKIE).unwrap().to_str().unwrap().starts_with("sa-token="));
+
+        // 票据只能使用一次 | A ticket is single use
+        let response = respond(service.call(get(callback, "text/html")).await);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let logged_in = get("/orders/1", "text/html");
+        logged_in.extensions_mut().insert("alice".to_string());
+        let response = respond(service.call(logged_in).await);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -6,6 +6,8 @@ use std::collections::HashMap;
 use http::{Request, Response};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
+use std::sync::Arc;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// Axum请求适配器
 pub struct AxumRequestAdapter<'a, T> {
@@ -45,6 +47,13 @@ impl<'a, T> SaRequest for AxumRequestAdapter<'a, T> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    /// 需要使用 `into_make_service_with_connect_info::<SocketAddr>()` 启动服务
+    fn get_client_ip(&self) -> Option<String> {
+        self.request.extensions()
+            .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+            .map(|info| info.0.ip().to_string())
+    }
 }
 
 /// Axum响应适配器
@@ -64,10 +73,9 @@ impl<T> AxumResponseAdapter<T> {
 
 impl<T> SaResponse for AxumResponseAdapter<T> {
     fn set_header(&mut self, name: &str, value: &str) {
-        if let Ok(header_name) = http::header::HeaderName::from_bytes(name.as_bytes()) {
-            if let Ok(header_value) = http::header::HeaderValue::from_str(value) {
-                self.response.headers_mut().insert(header_name, header_value);
-            }
+        if let Ok(header_name) = http::header::HeaderName::from_bytes(name.as_bytes())
+            && let Ok(header_value) = http::header::HeaderValue::from_str(value) {
+            self.response.headers_mut().insert(header_name, header_value);
         }
     }
     
@@ -103,6 +111,59 @@ impl<T> SaResponse for AxumResponseAdapter<T> {
     }
 }
 
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+///
+/// # 示例
+/// ```rust,ignore
+/// async fn login(State(state): State<SaTokenState>) -> Response {
+///     let token = StpUtil::login(10001).await.unwrap();
+///     write_token(&state.manager, &token, Json(json!({ "token": token.as_str() })).into_response())
+/// }
+/// ```
+pub fn write_token<B>(manager: &SaTokenManager, token: &TokenValue, response: Response<B>) -> Response<B> {
+    append_headers(response, manager.token_response_headers(token))
+}
+
+/// 登出后清除响应中的 token cookie
+pub fn clear_token<B>(manager: &SaTokenManager, response: Response<B>) -> Response<B> {
+    append_headers(response, manager.logout_response_headers())
+}
+
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenLayer` 放入请求扩展），缺省为 `DefaultErrorRenderer`
+pub fn error_renderer(extensions: &http::Extensions) -> Arc<dyn ErrorRenderer> {
+    extensions.get::<Arc<dyn ErrorRenderer>>().cloned().unwrap_or_else(default_error_renderer)
+}
+
+/// 用给定的 `ErrorRenderer` 把错误渲染为拒绝响应，中间件与提取器共用
+///
+/// 为兼容旧版本，响应体同时写入 `X-Sa-Token-Error` 响应头
+pub fn error_response<B: From<String>>(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> Response<B> {
+    let (status, headers, body) = renderer.render(error);
+    let legacy = http::header::HeaderValue::from_str(&body).ok();
+    let mut response = rendered_response((status, headers, body));
+    if let Some(value) = legacy {
+        response.headers_mut().insert("X-Sa-Token-Error", value);
+    }
+    response
+}
+
+/// 由渲染结果（状态码、响应头、响应体）构建响应
+pub(crate) fn rendered_response<B: From<String>>((status, headers, body): (u16, Vec<(String, String)>, String)) -> Response<B> {
+    let mut response = Response::new(B::from(body));
+    *response.status_mut() = http::StatusCode::from_u16(status).unwrap_or(http::StatusCode::UNAUTHORIZED);
+    append_headers(response, headers)
+}
+
+fn append_headers<B>(mut response: Response<B>, headers: Vec<(String, String)>) -> Response<B> {
+    for (name, value) in headers {
+        if let Ok(name) = http::header::HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = http::header::HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
+
 /// 解析 Cookie 字符串
 fn parse_cookies(cookie_header: &str) -> HashMap<String, String> {
     let mut cookies = HashMap::new();
@@ -0,0 +1,178 @@
+// Author: 金书记
+//
+//! CAS 3.0 协议端点 | CAS 3.0 protocol endpoints
+//!
+//! `cas_routes` 挂载 `/login`、`/serviceValidate` 与 `/p3/serviceValidate`，现有的 CAS 客户端库
+//! 把 CAS 服务地址指向挂载前缀即可接入。
+//! `cas_routes` mounts `/login`, `/serviceValidate` and `/p3/serviceValidate`; existing CAS client
+//! libraries only need their CAS server URL pointed at the mount prefix.
+//!
+//! ```rust,ignore
+//! let sso_server = Arc::new(SsoServer::new(manager).with_allow_urls(vec!["https://app.example.com/*".to_string()]));
+//! let cas = Arc::new(CasServer::new(sso_server));
+//!
+//! let app = Router::new()
+//!     .nest("/cas", cas_routes(CasServerState::new(cas).login_url("/login")))
+//!     .layer(SaTokenLayer::new(sa_token_state));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use axum::Router;
+use axum::extract::{OriginalUri, Query, State};
+use axum::http::{header, Extensions, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::routing::{get, MethodRouter};
+use sa_token_core::{CasLoginAction, CasServer, ErrorPage, SaPage, SaTokenError};
+
+/// CAS 路由状态 | State of the CAS routes
+#[derive(Clone)]
+pub struct CasServerState {
+    cas: Arc<CasServer>,
+    login_url: Option<String>,
+}
+
+impl CasServerState {
+    /// 创建路由状态 | Create the route state
+    ///
+    /// # 参数 | Parameters
+    /// * `cas` - CAS 服务端 | CAS server
+    pub fn new(cas: Arc<CasServer>) -> Self {
+        Self { cas, login_url: None }
+    }
+
+    /// 未登录用户访问 `/login` 时跳转的登录页，原地址以 `redirect` 参数传递；未设置时返回 401
+    /// Login page for users hitting `/login` without a login, with the original URL in the
+    /// `redirect` parameter; 401 when unset
+    pub fn login_url(mut self, url: impl Into<String>) -> Self {
+        self.login_url = Some(url.into());
+        self
+    }
+}
+
+/// 创建 CAS 路由 | Build the CAS routes
+///
+/// ```text
+/// GET /login                登录并携带票据回跳 service | log in and return to service with a ticket
+/// GET /serviceValidate      CAS 2.0 票据校验 | CAS 2.0 ticket validation
+/// GET /p3/serviceValidate   CAS 3.0 票据校验，含用户属性 | CAS 3.0 ticket validation with user attributes
+/// ```
+///
+/// 登录用户从 `SaTokenLayer` 写入的请求扩展中读取，因此需要在外层路由上应用 `SaTokenLayer`。
+/// The logged-in user is read from the request extensions set by `SaTokenLayer`, so apply
+/// `SaTokenLayer` on the outer router.
+///
+/// # 参数 | Parameters
+/// * `state` - 路由状态 | Route state
+pub fn cas_routes<S>(state: CasServerState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let cas = state.cas.clone();
+    Router::new()
+        .route("/login", get(login))
+        .with_state(state)
+        .route("/serviceValidate", cas_service_validate_handler(cas.clone()))
+        .route("/p3/serviceValidate", cas_p3_service_validate_handler(cas))
+}
+
+async fn login(
+    State(state): State<CasServerState>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    extensions: Extensions,
+    Query(params): Query<BTreeMap<String, String>>,
+) -> Response {
+    let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let login_id = extensions.get::<String>().map(String::as_str);
+    match state.cas.login_request(login_id, &params).await {
+        Ok(CasLoginAction::Redirect(location)) => Redirect::to(&location).into_response(),
+        Ok(CasLoginAction::Login) => match &state.login_url {
+            Some(login_url) => {
+                let separator = if login_url.contains('?') { '&' } else { '?' };
+                Redirect::to(&format!("{}{}redirect={}", login_url, separator, urlencoding::encode(&uri.to_string())))
+                    .into_response()
+            }
+            None => error_page(&state.cas, StatusCode::UNAUTHORIZED, &SaTokenError::NotLogin.to_string(), accept_language),
+        },
+        Err(e) => error_page(&state.cas, StatusCode::BAD_REQUEST, &e.to_string(), accept_language),
+    }
+}
+
+/// 渲染错误页 | Render an error page
+fn error_page(cas: &CasServer, status: StatusCode, message: &str, accept_language: Option<&str>) -> Response {
+    let page = SaPage::Error(ErrorPage { status: status.as_u16(), message: message.to_string() });
+    match cas.sso().page_renderer().render_negotiated(&page, accept_language) {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(_) => status.into_response(),
+    }
+}
+
+/// 构建 CAS XML 响应，校验失败也以 200 返回 | Build a CAS XML response; failures are answered with 200 as well
+pub fn cas_xml_response(xml: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/xml;charset=UTF-8")], xml).into_response()
+}
+
+/// GET 路由处理器，CAS 2.0 `/serviceValidate` | GET route handler for CAS 2.0 `/serviceValidate`
+pub fn cas_service_validate_handler<S>(cas: Arc<CasServer>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    get(move |Query(params): Query<BTreeMap<String, String>>| {
+        let cas = cas.clone();
+        async move { cas_xml_response(cas.service_validate(&params).await) }
+    })
+}
+
+/// GET 路由处理器，CAS 3.0 `/p3/serviceValidate` | GET route handler for CAS 3.0 `/p3/serviceValidate`
+pub fn cas_p3_service_validate_handler<S>(cas: Arc<CasServer>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    get(move |Query(params): Query<BTreeMap<String, String>>| {
+        let cas = cas.clone();
+        async move { cas_xml_response(cas.p3_service_validate(&params).await) }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use sa_token_core::{SaTokenConfig, SaTokenManager, SsoServer};
+    use sa_token_storage_memory::MemoryStorage;
+    use tower::ServiceExt;
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cas_routes() {
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let sso = Arc::new(SsoServer::new(manager).with_allow_urls(vec!["https://app.com/*".to_string()]));
+        let app: Router = Router::new()
+            .nest("/cas", cas_routes(CasServerState::new(Arc::new(CasServer::new(sso))).login_url("/login")));
+        let service = urlencoding::encode("https://app.com/cas");
+
+        let response = app.clone()
+            .oneshot(Request::get(format!("/cas/login?service={}", service)).body(Body::empty()).unwrap())
+            .await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert!(response.headers()[header::LOCATION].to_str().unwrap().starts_with("/login?redirect=%2Fcas%2Flogin"));
+
+        let mut request = Request::get(format!("/cas/login?service={}", service)).body(Body::empty()).unwrap();
+        request.extensions_mut().insert("alice".to_string());
+        let response = app.clone().oneshot(request).await.unwrap();
+        let location = response.headers()[header::LOCATION].to_str().unwrap().to_string();
+        let ticket = location.strip_prefix("https://app.com/cas?ticket=").unwrap();
+
+        let response = app
+            .oneshot(Request::get(format!("/cas/p3/serviceValidate?service={}&ticket={}", service, ticket)).body(Body::empty()).unwrap())
+            .await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/xml;charset=UTF-8");
+        assert!(body_string(response).await.contains("<cas:user>alice</cas:user>"));
+    }
+}
@@ -4,12 +4,12 @@
 
 use axum::{
     extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
-    response::{IntoResponse, Response},
-    Json,
+    http::request::Parts,
+    response::Response,
 };
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::{TokenInfo, TokenValue, DEFAULT_ACCOUNT_TYPE}, PermissionSnapshot, SaSession, SaTokenError, StpUtil, parse_login_id};
+use serde::de::DeserializeOwned;
+use crate::adapter::{error_renderer, error_response};
 
 pub struct SaTokenExtractor(pub TokenValue);
 
@@ -22,13 +22,7 @@ where
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
         match parts.extensions.get::<TokenValue>() {
             Some(token) => Ok(SaTokenExtractor(token.clone())),
-            None => Err((
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "code": 401,
-                    "message": messages::AUTH_ERROR
-                }))
-            ).into_response()),
+            None => Err(reject(parts, &SaTokenError::NotLogin)),
         }
     }
 }
@@ -58,13 +52,224 @@ where
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
         match parts.extensions.get::<String>() {
             Some(login_id) => Ok(LoginIdExtractor(login_id.clone())),
-            None => Err((
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "code": 401,
-                    "message": messages::AUTH_ERROR
-                }))
-            ).into_response()),
+            None => Err(reject(parts, &SaTokenError::NotLogin)),
         }
     }
 }
+
+/// 账号类型提取器 | Account type extractor
+/// 
+/// 提取当前 token 所属的账号类型（如 "default"、"admin"）
+/// Extracts the account type the current token belongs to (e.g. "default", "admin")
+pub struct AccountTypeExtractor(pub String);
+
+impl<S> FromRequestParts<S> for AccountTypeExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+    
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match parts.extensions.get::<TokenInfo>() {
+            Some(token_info) => Ok(AccountTypeExtractor(token_info.account_type().to_string())),
+            None => Err(reject(parts, &SaTokenError::NotLogin)),
+        }
+    }
+}
+
+/// 类型化登录 ID 提取器 | Typed login ID extractor
+/// 
+/// 将登录 ID 转换为 i64、Uuid 等类型，转换失败返回 400
+/// Converts the login ID to i64, Uuid, etc., responds 400 if the conversion fails
+/// 
+/// # 示例 | Example
+/// ```rust,ignore
+/// async fn handler(TypedLoginId(user_id): TypedLoginId<i64>) -> String {
+///     format!("user {}", user_id)
+/// }
+/// ```
+pub struct TypedLoginId<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for TypedLoginId<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+    
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(login_id) = parts.extensions.get::<String>() else {
+            return Err(reject(parts, &SaTokenError::NotLogin));
+        };
+        
+        parse_login_id(login_id).map(TypedLoginId).map_err(|e| reject(parts, &e))
+    }
+}
+
+/// 账号 Session 提取器 | Account session extractor
+/// 
+/// 按当前 token 的账号类型加载 `SaSession`；同一请求只读取一次存储，结果缓存在请求扩展中。
+/// 修改后调用 `StpUtil::save_session` 写回
+/// Loads the `SaSession` for the account type of the current token; storage is read once per
+/// request and the result is cached in the request extensions. Call `StpUtil::save_session` to write changes back
+/// 
+/// # 示例 | Example
+/// ```rust,ignore
+/// async fn handler(SaSessionExt(session): SaSessionExt) -> String {
+///     session.get::<String>("nickname").unwrap_or_default()
+/// }
+/// ```
+pub struct SaSessionExt(pub SaSession);
+
+impl<S> FromRequestParts<S> for SaSessionExt
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+    
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(session) = parts.extensions.get::<SaSession>() {
+            return Ok(SaSessionExt(session.clone()));
+        }
+        let Some(login_id) = parts.extensions.get::<String>().cloned() else {
+            return Err(reject(parts, &SaTokenError::NotLogin));
+        };
+        let account_type = parts.extensions.get::<TokenInfo>()
+            .map_or(DEFAULT_ACCOUNT_TYPE, |info| info.account_type())
+            .to_string();
+        
+        let session = StpUtil::get_session_by_type(&login_id, &account_type).await
+            .map_err(|e| reject(parts, &e))?;
+        parts.extensions.insert(session.clone());
+        Ok(SaSessionExt(session))
+    }
+}
+
+/// 当前用户权限提取器 | Current permissions extractor
+/// 
+/// 每个请求只加载一次权限与角色列表（缓存在请求扩展中），
+/// 通过 `has()`、`has_any()`、`has_all()`、`has_role()` 等在内存中检查
+/// Loads the permission and role lists once per request (cached in the request extensions);
+/// check them in memory through `has()`, `has_any()`, `has_all()`, `has_role()` and friends
+/// 
+/// # 示例 | Example
+/// ```rust,ignore
+/// async fn refund(perms: CurrentPermissions) -> Result<String, StatusCode> {
+///     if !perms.has_any(&["order:refund", "order:*"]) {
+///         return Err(StatusCode::FORBIDDEN);
+///     }
+///     Ok(format!("refunded by {}", perms.login_id()))
+/// }
+/// ```
+pub struct CurrentPermissions(pub PermissionSnapshot);
+
+impl std::ops::Deref for CurrentPermissions {
+    type Target = PermissionSnapshot;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S> FromRequestParts<S> for CurrentPermissions
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+    
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(snapshot) = parts.extensions.get::<PermissionSnapshot>() {
+            return Ok(CurrentPermissions(snapshot.clone()));
+        }
+        let Some(login_id) = parts.extensions.get::<String>().cloned() else {
+            return Err(reject(parts, &SaTokenError::NotLogin));
+        };
+        let account_type = parts.extensions.get::<TokenInfo>()
+            .map_or(DEFAULT_ACCOUNT_TYPE, |info| info.account_type())
+            .to_string();
+        
+        let snapshot = StpUtil::get_permission_snapshot_by_type(&login_id, &account_type).await;
+        parts.extensions.insert(snapshot.clone());
+        Ok(CurrentPermissions(snapshot))
+    }
+}
+
+/// 用请求所属状态的渲染器生成拒绝响应 | Build a rejection with the renderer of the request's state
+fn reject(parts: &Parts, error: &SaTokenError) -> Response {
+    error_response(error_renderer(&parts.extensions).as_ref(), error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use axum::http::{Request, StatusCode};
+    use crate::{MemoryStorage, SaTokenManager, SaTokenState};
+    
+    /// StpUtil 只能初始化一次，本模块的测试共用 | StpUtil initializes once, shared by the tests of this module
+    fn init() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            SaTokenState::builder()
+                .storage(Arc::new(MemoryStorage::new()))
+                .build();
+        });
+    }
+    
+    #[tokio::test]
+    async fn test_session_extractor() {
+        init();
+        
+        let (mut parts, _) = Request::new(()).into_parts();
+        let rejection = SaSessionExt::from_request_parts(&mut parts, &()).await.err().unwrap();
+        assert_eq!(rejection.status(), StatusCode::UNAUTHORIZED);
+        
+        let mut session = StpUtil::get_session("10001").await.unwrap();
+        session.set("nickname", "alice").unwrap();
+        StpUtil::save_session(&session).await.unwrap();
+        
+        let (mut parts, _) = Request::builder().extension("10001".to_string()).body(()).unwrap().into_parts();
+        let SaSessionExt(session) = SaSessionExt::from_request_parts(&mut parts, &()).await.ok().unwrap();
+        assert_eq!(session.get::<String>("nickname"), Some("alice".to_string()));
+        
+        // 第二次从请求扩展读取，不再访问存储 | The second extraction reads the cached copy
+        StpUtil::delete_session("10001").await.unwrap();
+        let SaSessionExt(cached) = SaSessionExt::from_request_parts(&mut parts, &()).await.ok().unwrap();
+        assert_eq!(cached.get::<String>("nickname"), Some("alice".to_string()));
+    }
+    
+    #[tokio::test]
+    async fn test_current_permissions_extractor() {
+        init();
+        StpUtil::set_permissions("10002", vec!["order:*".to_string()]).await.unwrap();
+        StpUtil::set_roles("10002", vec!["manager".to_string()]).await.unwrap();
+        
+        let (mut parts, _) = Request::builder().extension("10002".to_string()).body(()).unwrap().into_parts();
+        let perms = CurrentPermissions::from_request_parts(&mut parts, &()).await.ok().unwrap();
+        assert!(perms.has("order:refund"));
+        assert!(!perms.has_all(&["order:read", "user:delete"]));
+        assert!(perms.has_role("manager"));
+        assert!(parts.extensions.get::<PermissionSnapshot>().is_some());
+    }    
+    #[tokio::test]
+    async fn test_rejection_uses_state_renderer() {
+        // 两个状态各自保留渲染器，互不覆盖 | Two states keep their own renderers without overwriting each other
+        let state_for = |status: u16| {
+            let manager = SaTokenManager::new(Arc::new(MemoryStorage::new()), Default::default());
+            manager.set_error_renderer(Arc::new(move |_: &SaTokenError| (status, Vec::new(), String::new())));
+            SaTokenState::from_manager(manager)
+        };
+        let (teapot, gone) = (state_for(418), state_for(410));
+        
+        for (state, status) in [(&teapot, 418), (&gone, 410), (&teapot, 418)] {
+            let (mut parts, _) = Request::builder().extension(state.error_renderer.clone()).body(()).unwrap().into_parts();
+            let rejection = SaTokenExtractor::from_request_parts(&mut parts, &()).await.err().unwrap();
+            assert_eq!(rejection.status().as_u16(), status);
+        }
+        
+        // 未经过 SaTokenLayer 的请求使用默认渲染器 | Requests that skipped SaTokenLayer use the default renderer
+        let (mut parts, _) = Request::new(()).into_parts();
+        let rejection = SaTokenExtractor::from_request_parts(&mut parts, &()).await.err().unwrap();
+        assert_eq!(rejection.status(), StatusCode::UNAUTHORIZED);
+    }
+}
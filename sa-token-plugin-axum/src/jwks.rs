@@ -0,0 +1,42 @@
+// Author: 金书记
+//
+//! JWKS 端点 | JWKS endpoint
+//!
+//! ```rust,ignore
+//! let key_ring = JwksKeyRing::new();
+//! key_ring.rotate("2025-01", JwtAlgorithm::RS256, &private_pem, &public_pem)?;
+//!
+//! let app = Router::new()
+//!     .route("/.well-known/jwks.json", jwks_handler(key_ring.clone()));
+//! ```
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, MethodRouter};
+use sa_token_core::JwksKeyRing;
+
+/// 公钥集缓存时长（秒），轮换后资源服务器最迟在此之后看到新密钥
+/// Cache lifetime of the key set in seconds; resource servers see a rotated key at most this late
+pub const JWKS_MAX_AGE: u32 = 300;
+
+/// 构建 JWKS JSON 响应 | Build the JWKS JSON response
+pub fn jwks_response(key_ring: &JwksKeyRing) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CACHE_CONTROL, format!("public, max-age={}", JWKS_MAX_AGE)),
+        ],
+        key_ring.jwks_json(),
+    ).into_response()
+}
+
+/// GET 路由处理器，返回密钥环的当前公钥集 | GET route handler serving the key ring's current public key set
+pub fn jwks_handler<S>(key_ring: JwksKeyRing) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    get(move || {
+        let key_ring = key_ring.clone();
+        async move { jwks_response(&key_ring) }
+    })
+}
@@ -6,8 +6,8 @@ use std::task::{Context, Poll};
 use tower::{Layer, Service};
 use http::{Request, Response};
 use sa_token_adapter::context::SaRequest;
-use crate::{SaTokenState, adapter::AxumRequestAdapter};
-use sa_token_core::{SaTokenContext, router::PathAuthConfig};
+use crate::{SaTokenState, adapter::{AxumRequestAdapter, rendered_response}};
+use sa_token_core::{SaTokenContext, StpUtil, RememberedSession, router::PathAuthConfig};
 use std::sync::Arc;
 
 /// Sa-Token layer for Axum with optional path-based authentication
@@ -56,7 +56,7 @@ where
     S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
     S::Future: Send + 'static,
     ReqBody: Send + 'static,
-    ResBody: Default + Send + 'static,
+    ResBody: From<String> + Send + 'static,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -70,17 +70,37 @@ where
         let mut inner = self.inner.clone();
         let state = self.state.clone();
         let path_config = self.path_config.clone();
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = state.manager.config.tenant(&AxumRequestAdapter::new(&request));
         
-        Box::pin(async move {
+        // 提取器与内层中间件使用本状态的渲染器 | Extractors and inner middlewares use this state's renderer
+        request.extensions_mut().insert(state.error_renderer.clone());
+        
+        Box::pin(sa_token_core::tenant::scope(tenant, async move {
+            let mut token_str = extract_token_from_request(&request, &state);
+            // 会话失效时用 remember-me cookie 静默重建
+            let remember = AxumRequestAdapter::new(&request).get_cookie(&state.manager.config.remember_me_cookie);
+            let remembered = state.manager.restore_remembered(token_str.as_deref(), remember.as_deref()).await;
+            if let Some(session) = &remembered {
+                token_str = Some(session.token.as_str().to_string());
+            }
+            
             if let Some(config) = path_config {
                 let path = request.uri().path();
-                let token_str = extract_token_from_request(&request, &state);
-                let result = sa_token_core::router::process_auth(path, token_str, &config, &state.manager).await;
+                let header_version = config.version_header_name()
+                    .and_then(|name| request.headers().get(name))
+                    .and_then(|v| v.to_str().ok());
+                let client_ip = config.client_ip(
+                    config.client_ip_header_name()
+                        .and_then(|name| request.headers().get(name))
+                        .and_then(|v| v.to_str().ok()),
+                    state.manager.config.client_ip(&AxumRequestAdapter::new(&request)),
+                );
+                let fingerprint = state.manager.config.fingerprint(&AxumRequestAdapter::new(&request));
+                let result = sa_token_core::router::process_auth_request(path, Some(request.method().as_str()), header_version, client_ip.as_deref(), fingerprint.as_deref(), token_str, &config, &state.manager).await;
                 
                 if result.should_reject() {
-                    let mut response = Response::new(ResBody::default());
-                    *response.status_mut() = http::StatusCode::UNAUTHORIZED;
-                    return Ok(response);
+                    return Ok(rendered_response(config.render_rejection_response(&result, state.error_renderer.as_ref())));
                 }
                 
                 if let Some(token) = &result.token {
@@ -89,37 +109,59 @@ where
                 if let Some(login_id) = result.login_id() {
                     request.extensions_mut().insert(login_id.to_string());
                 }
+                if let Some(token_info) = &result.token_info {
+                    request.extensions_mut().insert(token_info.clone());
+                }
                 
-                let ctx = sa_token_core::router::create_context(&result);
+                let mut ctx = sa_token_core::router::create_context(&result);
+                ctx.typed_tokens = StpUtil::collect_typed_tokens(&AxumRequestAdapter::new(&request));
                 SaTokenContext::set_current(ctx);
                 let response = inner.call(request).await;
                 SaTokenContext::clear();
-                return response;
+                return response.map(|response| append_set_cookies(response, remembered));
             }
             
             // No path auth config, use default token extraction and validation
             // 没有路径鉴权配置，使用默认的 token 提取和验证
             let mut ctx = SaTokenContext::new();
-            if let Some(token_str) = extract_token_from_request(&request, &state) {
+            let client_ip = state.manager.config.client_ip(&AxumRequestAdapter::new(&request));
+            let fingerprint = state.manager.config.fingerprint(&AxumRequestAdapter::new(&request));
+            if let Some(token_str) = token_str {
                 let token = sa_token_core::token::TokenValue::new(token_str);
-                if state.manager.is_valid(&token).await {
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if state.manager.is_valid(&token).await
+                    && let Ok(token_info) = state.manager.get_token_info(&token).await
+                    && state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
+                    let login_id = token_info.login_id.clone();
+                    state.manager.record_activity(&token_info, client_ip.as_deref()).await;
                     request.extensions_mut().insert(token.clone());
-                    if let Ok(token_info) = state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
-                        request.extensions_mut().insert(login_id.clone());
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
-                    }
+                    request.extensions_mut().insert(login_id.clone());
+                    request.extensions_mut().insert(token_info.clone());
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                 }
             }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+            ctx.typed_tokens = StpUtil::collect_typed_tokens(&AxumRequestAdapter::new(&request));
             
             SaTokenContext::set_current(ctx);
             let response = inner.call(request).await;
             SaTokenContext::clear();
-            response
-        })
+            response.map(|response| append_set_cookies(response, remembered))
+        }))
+    }
+}
+
+/// 把 remember-me 重建会话产生的 cookie 写入响应
+fn append_set_cookies<B>(mut response: Response<B>, remembered: Option<RememberedSession>) -> Response<B> {
+    for cookie in remembered.into_iter().flat_map(|session| session.set_cookies) {
+        if let Ok(value) = http::HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(http::header::SET_COOKIE, value);
+        }
     }
+    response
 }
 
 /// 从请求中提取 Token
@@ -148,10 +190,9 @@ pub fn extract_token_from_request<T>(request: &Request<T>, state: &SaTokenState)
     }
     
     // 2. 如果 token_name 不是 "Authorization"，也尝试从 "Authorization" 头获取
-    if token_name != "Authorization" {
-        if let Some(token) = adapter.get_header("Authorization") {
-            return Some(extract_bearer_token(&token));
-        }
+    if token_name != "Authorization"
+        && let Some(token) = adapter.get_header("Authorization") {
+        return Some(extract_bearer_token(&token));
     }
     
     // 3. 从 Cookie 中获取
@@ -160,10 +201,9 @@ pub fn extract_token_from_request<T>(request: &Request<T>, state: &SaTokenState)
     }
     
     // 4. 从 Query 参数中获取
-    if let Some(query) = request.uri().query() {
-        if let Some(token) = parse_query_param(query, token_name) {
-            return Some(token);
-        }
+    if let Some(query) = request.uri().query()
+        && let Some(token) = parse_query_param(query, token_name) {
+        return Some(token);
     }
     
     None
@@ -177,9 +217,9 @@ pub fn extract_token_from_request<T>(request: &Request<T>, state: &SaTokenState)
 fn extract_bearer_token(header_value: &str) -> String {
     const BEARER_PREFIX: &str = "Bearer ";
     
-    if header_value.starts_with(BEARER_PREFIX) {
+    if let Some(stripped) = header_value.strip_prefix(BEARER_PREFIX) {
         // 去除 "Bearer " 前缀
-        header_value[BEARER_PREFIX.len()..].trim().to_string()
+        stripped.trim().to_string()
     } else {
         // 直接返回 token
         header_value.trim().to_string()
@@ -47,13 +47,32 @@ pub mod layer;
 pub mod extractor;
 pub mod middleware;
 pub mod adapter;
+pub mod jwks;
+pub mod oauth2;
+pub mod sso;
+pub mod cas;
+pub mod realtime;
+#[cfg(feature = "saml")]
+pub mod saml;
 
 // ============================================================================
 // Axum 框架集成（本插件特有）
 // ============================================================================
 pub use layer::SaTokenLayer;
-pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor};
-pub use middleware::{SaTokenMiddleware, SaCheckLoginLayer, SaCheckLoginMiddleware, SaCheckPermissionLayer, SaCheckPermissionMiddleware};
+pub use adapter::{write_token, clear_token, error_renderer, error_response};
+pub use jwks::{jwks_handler, jwks_response};
+pub use oauth2::{
+    introspection_handler, introspection_response, revocation_handler, revocation_response,
+    userinfo_handler, userinfo_response, discovery_handler, discovery_response,
+    token_handler, token_response, oauth2_routes, OAuth2ServerState,
+};
+pub use cas::{cas_routes, cas_service_validate_handler, cas_p3_service_validate_handler, cas_xml_response, CasServerState};
+#[cfg(feature = "saml")]
+pub use saml::saml_routes;
+pub use sso::{sso_logout_handler, sso_logout_response, SsoClientLayer, SsoClientMiddleware};
+pub use realtime::WsAuth;
+pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, AccountTypeExtractor, TypedLoginId, SaSessionExt, CurrentPermissions};
+pub use middleware::{SaTokenMiddleware, SaCheckLoginLayer, SaCheckLoginMiddleware, SaCheckPermissionLayer, SaCheckPermissionMiddleware, SaCheckSafeLayer, SaCheckSafeMiddleware, SaCheckSameTokenLayer, SaCheckSameTokenMiddleware, SaApiKeyLayer, SaApiKeyMiddleware, SaVerifySignLayer, SaVerifySignMiddleware, SaCheckSignLayer, SaCheckSignMiddleware, SaCheckBasicLayer, SaCheckBasicMiddleware, SaCheckCsrfLayer, SaCheckCsrfMiddleware};
 
 pub use sa_token_core::{self, prelude::*};
 pub use sa_token_adapter::{self, storage::SaStorage, framework::FrameworkAdapter};
@@ -76,25 +95,32 @@ pub use sa_token_storage_redis::RedisStorage;
 pub use sa_token_storage_database::DatabaseStorage;
 
 use std::sync::Arc;
+use std::collections::HashMap;
 
 /// Axum应用状态
 #[derive(Clone)]
 pub struct SaTokenState {
     pub manager: Arc<SaTokenManager>,
+    
+    /// 本状态的中间件与提取器使用的拒绝响应渲染器 | Rejection renderer of this state's middlewares and extractors
+    pub error_renderer: Arc<dyn sa_token_core::ErrorRenderer>,
+    
+    /// 其他账号体系，按账号类型索引 | Other account systems keyed by account type
+    pub logics: HashMap<String, Arc<StpLogic>>,
 }
 
 impl SaTokenState {
     /// 从存储和配置创建状态
     pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
-        Self {
-            manager: Arc::new(SaTokenManager::new(storage, config)),
-        }
+        Self::from_manager(SaTokenManager::new(storage, config))
     }
     
     /// 从 SaTokenManager 创建状态
     pub fn from_manager(manager: SaTokenManager) -> Self {
         Self {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: HashMap::new(),
         }
     }
     
@@ -115,12 +141,18 @@ impl SaTokenState {
     pub fn builder() -> SaTokenStateBuilder {
         SaTokenStateBuilder::default()
     }
+    
+    /// 获取指定账号类型的账号体系 | Get the account system of an account type
+    pub fn logic(&self, login_type: &str) -> Option<&Arc<StpLogic>> {
+        self.logics.get(login_type)
+    }
 }
 
 /// SaTokenState 构建器
 #[derive(Default)]
 pub struct SaTokenStateBuilder {
     config_builder: sa_token_core::config::SaTokenConfigBuilder,
+    logics: Vec<StpLogic>,
 }
 
 impl SaTokenStateBuilder {
@@ -175,6 +207,18 @@ impl SaTokenStateBuilder {
         self
     }
     
+    /// 设置本状态的拒绝响应渲染器，`SaTokenLayer` 把它交给之后的中间件与提取器 | Set this state's rejection renderer, handed by `SaTokenLayer` to the middlewares and extractors after it
+    pub fn error_renderer(mut self, renderer: Arc<dyn sa_token_core::ErrorRenderer>) -> Self {
+        self.config_builder = self.config_builder.error_renderer(renderer);
+        self
+    }
+    
+    /// 添加账号体系（如 admin），构建时注册到 StpUtil | Add an account system (e.g. admin), registered with StpUtil on build
+    pub fn logic(mut self, logic: StpLogic) -> Self {
+        self.logics.push(logic);
+        self
+    }
+    
     pub fn build(self) -> SaTokenState {
         // config_builder.build() 已经自动初始化了 StpUtil
         // config_builder.build() already auto-initializes StpUtil
@@ -182,7 +226,9 @@ impl SaTokenStateBuilder {
         // 直接创建 SaTokenState，不再调用 from_manager 避免重复初始化
         // Create SaTokenState directly, don't call from_manager to avoid duplicate initialization
         SaTokenState {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: StpUtil::register_logics(self.logics),
         }
     }
 }
@@ -5,13 +5,24 @@
 //! 提供两种中间件：
 //! - `SaTokenMiddleware`：基础中间件，从请求中提取token并设置上下文
 //! - `SaCheckLoginMiddleware`：检查登录中间件，未登录时返回401错误
+//! - `SaCheckSafeMiddleware`：检查二级认证中间件，未通过时返回401错误
+//! - `SaVerifySignMiddleware`：请求体签名校验中间件，签名无效时返回401错误，请求体超限时返回413错误
+//! - `SaCheckSameTokenMiddleware`：内部服务调用的 Same-Token 校验中间件，无效时返回401错误
+//! - `SaApiKeyMiddleware`：API Key 认证中间件，未登录时接受 `X-API-Key` 请求头作为凭证，Key 无效时返回401错误
+//! - `SaCheckSignMiddleware`：参数签名校验中间件，未签名、签名过期、被篡改或重放时返回401错误
+//! - `SaCheckBasicMiddleware`：HTTP Basic 认证中间件，凭证无效时返回401错误并要求浏览器弹出认证框
+//! - `SaCheckCsrfMiddleware`：防 CSRF 中间件，携带会话 Cookie 的写请求缺少有效 CSRF 令牌时返回403错误
 
+use std::pin::Pin;
 use std::task::{Context, Poll};
+use axum::body::Body;
 use tower::{Layer, Service};
-use http::{Request, Response, StatusCode};
-use http_body;
-use serde_json::json;
-use sa_token_core::error::messages;
+use http::{Request, Response};
+use http_body::{self, Body as _};
+use sa_token_core::{api_key::ApiKeyManager, basic::HttpBasicAuth, csrf::CsrfManager, error::SaTokenError, same_token::SameTokenManager, sign::{BodySignVerifier, SaSignManager}, token::TokenValue, SaTokenContext};
+use sa_token_core::router::CompiledPattern;
+use sa_token_adapter::context::SaRequest;
+use crate::adapter::{AxumRequestAdapter, error_renderer, error_response};
 
 pub use crate::layer::SaTokenMiddleware;
 
@@ -19,6 +30,12 @@ pub use crate::layer::SaTokenMiddleware;
 #[derive(Clone)]
 pub struct SaCheckLoginLayer;
 
+impl Default for SaCheckLoginLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SaCheckLoginLayer {
     pub fn new() -> Self {
         Self
@@ -80,7 +97,7 @@ where
     S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
     S::Future: Send + 'static,
     ReqBody: Send + 'static,
-    ResBody: http_body::Body + Default + Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -92,30 +109,13 @@ where
     
     fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
         let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
         
         Box::pin(async move {
             // 检查是否有登录ID
             if request.extensions().get::<String>().is_none() {
                 // 未登录，返回401错误
-                // 由于我们无法直接返回AxumResponse，这里使用一个hack方法
-                // 创建一个错误响应
-                let mut response = Response::builder()
-                    .status(StatusCode::UNAUTHORIZED)
-                    .body(ResBody::default())
-                    .expect("Unable to create response");
-                
-                // 添加错误信息
-                let error_json = serde_json::to_string(&json!({
-                    "code": 401,
-                    "message": messages::AUTH_ERROR
-                })).unwrap_or_default();
-                
-                // 添加到响应头中，这样上层可以读取
-                if let Ok(header_value) = http::header::HeaderValue::from_str(&error_json) {
-                    response.headers_mut().insert("X-Sa-Token-Error", header_value);
-                }
-                
-                return Ok(response);
+                return Ok(error_response(renderer.as_ref(), &SaTokenError::NotLogin));
             }
             
             // 已登录，继续处理
@@ -129,7 +129,7 @@ where
     S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
     S::Future: Send + 'static,
     ReqBody: Send + 'static,
-    ResBody: http_body::Body + Default + Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
 {
     type Response = S::Response;
     type Error = S::Error;
@@ -141,6 +141,7 @@ where
     
     fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
         let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
         let permission = self.permission.clone();
         
         Box::pin(async move {
@@ -154,23 +155,736 @@ where
             }
             
             // 无权限或未登录，返回403错误
-            let mut response = Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .body(ResBody::default())
-                .expect("Unable to create response");
+            Ok(error_response(renderer.as_ref(), &SaTokenError::PermissionDenied))
+        })
+    }
+}
+
+/// 检查二级认证中间件层
+/// 
+/// 用于修改密码、提现等敏感接口，要求 token 在近期完成过二级认证
+#[derive(Clone)]
+pub struct SaCheckSafeLayer;
+
+impl Default for SaCheckSafeLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaCheckSafeLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for SaCheckSafeLayer {
+    type Service = SaCheckSafeMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckSafeMiddleware { inner }
+    }
+}
+
+/// 检查二级认证中间件
+/// 
+/// 如果请求未登录或未通过二级认证，直接返回401错误
+#[derive(Clone)]
+pub struct SaCheckSafeMiddleware<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckSafeMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        
+        Box::pin(async move {
+            // 检查 token 是否处于二级认证有效期内
+            if let Some(token) = request.extensions().get::<TokenValue>()
+                && sa_token_core::StpUtil::is_safe(token).await {
+                return inner.call(request).await;
+            }
+            
+            // 未通过二级认证，返回401错误
+            Ok(error_response(renderer.as_ref(), &SaTokenError::NotSafe))
+        })
+    }
+}
+
+/// Same-Token 校验中间件层
+/// 
+/// 用于仅限内部服务调用的接口，要求请求头携带有效的 Same-Token
+#[derive(Clone)]
+pub struct SaCheckSameTokenLayer {
+    manager: SameTokenManager,
+}
+
+impl SaCheckSameTokenLayer {
+    pub fn new(manager: SameTokenManager) -> Self {
+        Self { manager }
+    }
+}
+
+impl<S> Layer<S> for SaCheckSameTokenLayer {
+    type Service = SaCheckSameTokenMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckSameTokenMiddleware {
+            inner,
+            manager: self.manager.clone(),
+        }
+    }
+}
+
+/// Same-Token 校验中间件
+/// 
+/// 请求头缺少 Same-Token 或 Same-Token 无效时，直接返回401错误
+#[derive(Clone)]
+pub struct SaCheckSameTokenMiddleware<S> {
+    inner: S,
+    manager: SameTokenManager,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckSameTokenMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        let manager = self.manager.clone();
+        
+        Box::pin(async move {
+            let token = request.headers()
+                .get(manager.header_name())
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
             
-            // 添加错误信息
-            let error_json = serde_json::to_string(&json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            })).unwrap_or_default();
+            match manager.check(token).await {
+                Ok(()) => inner.call(request).await,
+                Err(e) => Ok(error_response(renderer.as_ref(), &e)),
+            }
+        })
+    }
+}
+
+/// API Key 认证中间件层
+/// 
+/// 放在 `SaTokenLayer` 之内、`SaCheckLoginLayer` 之外：请求没有有效会话 token 时，
+/// 接受 `X-API-Key` 请求头作为替代凭证
+#[derive(Clone)]
+pub struct SaApiKeyLayer {
+    manager: ApiKeyManager,
+}
+
+impl SaApiKeyLayer {
+    pub fn new(manager: ApiKeyManager) -> Self {
+        Self { manager }
+    }
+}
+
+impl<S> Layer<S> for SaApiKeyLayer {
+    type Service = SaApiKeyMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaApiKeyMiddleware {
+            inner,
+            manager: self.manager.clone(),
+        }
+    }
+}
+
+/// API Key 认证中间件
+/// 
+/// Key 有效时把绑定的 login_id 和 `ApiKeyInfo` 写入请求扩展；Key 无效时直接返回401错误；
+/// 已登录或未携带 Key 的请求原样放行
+#[derive(Clone)]
+pub struct SaApiKeyMiddleware<S> {
+    inner: S,
+    manager: ApiKeyManager,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaApiKeyMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        let manager = self.manager.clone();
+        
+        Box::pin(async move {
+            let key = request.headers()
+                .get(manager.header_name())
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            let Some(key) = key.filter(|_| request.extensions().get::<String>().is_none()) else {
+                return inner.call(request).await;
+            };
             
-            // 添加到响应头中，这样上层可以读取
-            if let Ok(header_value) = http::header::HeaderValue::from_str(&error_json) {
-                response.headers_mut().insert("X-Sa-Token-Error", header_value);
+            match manager.verify(&key).await {
+                Ok(info) => {
+                    let mut ctx = SaTokenContext::get_current().unwrap_or_default();
+                    ctx.login_id = Some(info.login_id.clone());
+                    SaTokenContext::set_current(ctx);
+                    request.extensions_mut().insert(info.login_id.clone());
+                    request.extensions_mut().insert(info);
+                    inner.call(request).await
+                }
+                Err(e) => Ok(error_response(renderer.as_ref(), &e)),
             }
+        })
+    }
+}
+
+/// 请求体签名校验中间件层
+/// 
+/// 用于 Webhook、回调等签名接口：边读取请求体边计算 HMAC-SHA256，
+/// 超过大小上限立即返回413，签名缺失或不匹配返回401
+#[derive(Clone)]
+pub struct SaVerifySignLayer {
+    verifier: BodySignVerifier,
+}
+
+impl SaVerifySignLayer {
+    pub fn new(verifier: BodySignVerifier) -> Self {
+        Self { verifier }
+    }
+}
+
+impl<S> Layer<S> for SaVerifySignLayer {
+    type Service = SaVerifySignMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaVerifySignMiddleware {
+            inner,
+            verifier: self.verifier.clone(),
+        }
+    }
+}
+
+/// 请求体签名校验中间件
+/// 
+/// 校验通过后，用已缓存的请求体重建请求交给下游处理
+#[derive(Clone)]
+pub struct SaVerifySignMiddleware<S> {
+    inner: S,
+    verifier: BodySignVerifier,
+}
+
+impl<S, ResBody> Service<Request<Body>> for SaVerifySignMiddleware<S>
+where
+    S: Service<Request<Body>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ResBody: http_body::Body + From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        let verifier = self.verifier.clone();
+        
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
             
-            Ok(response)
+            match verify_body(&verifier, &parts.headers, body).await {
+                Ok(bytes) => {
+                    let request = Request::from_parts(parts, Body::from(bytes));
+                    inner.call(request).await
+                }
+                Err(e) => Ok(error_response(renderer.as_ref(), &e)),
+            }
         })
     }
 }
+
+/// 参数签名校验中间件层
+/// 
+/// 校验查询参数中的 timestamp、nonce、sign，未配置路径时校验所有请求
+#[derive(Clone)]
+pub struct SaCheckSignLayer {
+    manager: SaSignManager,
+    include: Vec<CompiledPattern>,
+}
+
+impl SaCheckSignLayer {
+    pub fn new(manager: SaSignManager) -> Self {
+        Self { manager, include: Vec::new() }
+    }
+    
+    /// 只校验匹配这些模式的路径
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+}
+
+impl<S> Layer<S> for SaCheckSignLayer {
+    type Service = SaCheckSignMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckSignMiddleware {
+            inner,
+            manager: self.manager.clone(),
+            include: self.include.clone(),
+        }
+    }
+}
+
+/// 参数签名校验中间件
+/// 
+/// 未签名、签名过期、被篡改或 nonce 重放时，直接返回401错误
+#[derive(Clone)]
+pub struct SaCheckSignMiddleware<S> {
+    inner: S,
+    manager: SaSignManager,
+    include: Vec<CompiledPattern>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckSignMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        let manager = self.manager.clone();
+        let include = self.include.clone();
+        
+        Box::pin(async move {
+            let path = request.uri().path();
+            if !include.is_empty() && !include.iter().any(|p| p.matches(path)) {
+                return inner.call(request).await;
+            }
+            
+            let params = SaSignManager::params_from_query(request.uri().query().unwrap_or_default());
+            match manager.verify(&params).await {
+                Ok(()) => inner.call(request).await,
+                Err(e) => Ok(error_response(renderer.as_ref(), &e)),
+            }
+        })
+    }
+}
+
+/// HTTP Basic 认证中间件层
+/// 
+/// 用于 `/metrics`、`/admin` 等运维接口，未配置路径时校验所有请求
+#[derive(Clone)]
+pub struct SaCheckBasicLayer {
+    basic: HttpBasicAuth,
+    include: Vec<CompiledPattern>,
+}
+
+impl SaCheckBasicLayer {
+    pub fn new(basic: HttpBasicAuth) -> Self {
+        Self { basic, include: Vec::new() }
+    }
+    
+    /// 只校验匹配这些模式的路径
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+}
+
+impl<S> Layer<S> for SaCheckBasicLayer {
+    type Service = SaCheckBasicMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckBasicMiddleware {
+            inner,
+            basic: self.basic.clone(),
+            include: self.include.clone(),
+        }
+    }
+}
+
+/// HTTP Basic 认证中间件
+/// 
+/// 凭证缺失或无效时返回401错误，并带上 `WWW-Authenticate` 响应头
+#[derive(Clone)]
+pub struct SaCheckBasicMiddleware<S> {
+    inner: S,
+    basic: HttpBasicAuth,
+    include: Vec<CompiledPattern>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckBasicMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        let basic = self.basic.clone();
+        let include = self.include.clone();
+        
+        Box::pin(async move {
+            let path = request.uri().path();
+            if !include.is_empty() && !include.iter().any(|p| p.matches(path)) {
+                return inner.call(request).await;
+            }
+            
+            let authorization = request.headers()
+                .get(http::header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok());
+            match basic.check(authorization) {
+                Ok(_) => inner.call(request).await,
+                Err(e) => {
+                    let mut response = error_response::<ResBody>(renderer.as_ref(), &e);
+                    if let Ok(header_value) = http::header::HeaderValue::from_str(&basic.challenge()) {
+                        response.headers_mut().insert(http::header::WWW_AUTHENTICATE, header_value);
+                    }
+                    Ok(response)
+                }
+            }
+        })
+    }
+}
+
+/// 防 CSRF 中间件层
+/// 
+/// 只检查携带会话 Cookie 的 POST / PUT / PATCH / DELETE 等写请求；
+/// 通过请求头传递 token 的 API 客户端不受影响
+#[derive(Clone)]
+pub struct SaCheckCsrfLayer {
+    csrf: CsrfManager,
+}
+
+impl SaCheckCsrfLayer {
+    pub fn new(csrf: CsrfManager) -> Self {
+        Self { csrf }
+    }
+}
+
+impl<S> Layer<S> for SaCheckCsrfLayer {
+    type Service = SaCheckCsrfMiddleware<S>;
+    
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckCsrfMiddleware {
+            inner,
+            csrf: self.csrf.clone(),
+        }
+    }
+}
+
+/// 防 CSRF 中间件
+/// 
+/// `X-CSRF-Token` 请求头缺失或与会话的 CSRF 令牌不一致时，直接返回403错误
+#[derive(Clone)]
+pub struct SaCheckCsrfMiddleware<S> {
+    inner: S,
+    csrf: CsrfManager,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckCsrfMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: From<String> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+    
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let renderer = error_renderer(request.extensions());
+        let csrf = self.csrf.clone();
+        
+        Box::pin(async move {
+            let session = AxumRequestAdapter::new(&request).get_cookie(csrf.session_cookie());
+            let Some(session) = session.filter(|_| CsrfManager::requires_check(request.method().as_str())) else {
+                return inner.call(request).await;
+            };
+            
+            let header = request.headers()
+                .get(csrf.header_name())
+                .and_then(|v| v.to_str().ok());
+            match csrf.verify(&TokenValue::new(session), header).await {
+                Ok(()) => inner.call(request).await,
+                Err(e) => Ok(error_response(renderer.as_ref(), &e)),
+            }
+        })
+    }
+}
+
+/// 逐帧读取请求体并校验签名 | Read the body frame by frame and verify its signature
+async fn verify_body(
+    verifier: &BodySignVerifier,
+    headers: &http::HeaderMap,
+    mut body: Body,
+) -> Result<Vec<u8>, SaTokenError> {
+    let signature = headers
+        .get(verifier.header_name())
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or(SaTokenError::SignatureMissing)?;
+    
+    // Content-Length 已声明超限时直接拒绝，不读取请求体
+    if let Some(len) = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok())
+        && len > verifier.body_limit() {
+        return Err(SaTokenError::BodyTooLarge(verifier.body_limit()));
+    }
+    
+    let mut state = verifier.start();
+    while let Some(frame) = std::future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+        let frame = frame.map_err(|e| SaTokenError::InternalError(e.to_string()))?;
+        if let Some(chunk) = frame.data_ref() {
+            state.update(chunk)?;
+        }
+    }
+    
+    state.finish(&signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use http::StatusCode;
+    use tower::ServiceExt;
+    
+    async fn echo(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        Ok(Response::new(request.into_body()))
+    }
+    
+    #[tokio::test]
+    async fn test_verify_sign_layer() {
+        let verifier = BodySignVerifier::new("secret").max_body_size(16);
+        let service = SaVerifySignLayer::new(verifier.clone()).layer(tower::service_fn(echo));
+        
+        let signed = Request::builder()
+            .header("X-Sa-Sign", verifier.sign(b"ok"))
+            .body(Body::from("ok"))
+            .unwrap();
+        let response = service.clone().oneshot(signed).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        let unsigned = Request::builder().body(Body::from("ok")).unwrap();
+        let response = service.clone().oneshot(unsigned).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        
+        let oversized = Request::builder()
+            .header("X-Sa-Sign", verifier.sign(&[0u8; 32]))
+            .body(Body::from(vec![0u8; 32]))
+            .unwrap();
+        let response = service.oneshot(oversized).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    
+    #[tokio::test]
+    async fn test_check_same_token_layer() {
+        let manager = SameTokenManager::new(std::sync::Arc::new(sa_token_storage_memory::MemoryStorage::new()));
+        let token = manager.get_token().await.unwrap();
+        let service = SaCheckSameTokenLayer::new(manager).layer(tower::service_fn(echo));
+        
+        let internal = Request::builder()
+            .header(sa_token_core::SAME_TOKEN_HEADER, token)
+            .body(Body::empty())
+            .unwrap();
+        let response = service.clone().oneshot(internal).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        let external = Request::builder().body(Body::empty()).unwrap();
+        let response = service.oneshot(external).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    
+    #[tokio::test]
+    async fn test_api_key_layer() {
+        let manager = ApiKeyManager::new(std::sync::Arc::new(sa_token_storage_memory::MemoryStorage::new()));
+        let (key, _) = manager.create("10001", "ci", vec![], None).await.unwrap();
+        let service = SaApiKeyLayer::new(manager)
+            .layer(SaCheckLoginLayer::new().layer(tower::service_fn(echo)));
+        
+        let with_key = Request::builder()
+            .header(sa_token_core::API_KEY_HEADER, key)
+            .body(Body::empty())
+            .unwrap();
+        let response = service.clone().oneshot(with_key).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        let forged = Request::builder()
+            .header(sa_token_core::API_KEY_HEADER, "sk_forged_key")
+            .body(Body::empty())
+            .unwrap();
+        let response = service.clone().oneshot(forged).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().contains_key("X-Sa-Token-Error"));
+        
+        // 已有会话时忽略 API Key | The API key is ignored when a session exists
+        let mut logged_in = Request::builder()
+            .header(sa_token_core::API_KEY_HEADER, "sk_forged_key")
+            .body(Body::empty())
+            .unwrap();
+        logged_in.extensions_mut().insert("10002".to_string());
+        let response = service.clone().oneshot(logged_in).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        let anonymous = Request::builder().body(Body::empty()).unwrap();
+        let response = service.oneshot(anonymous).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    
+    #[tokio::test]
+    async fn test_check_sign_layer() {
+        let manager = SaSignManager::new("secret", std::sync::Arc::new(sa_token_storage_memory::MemoryStorage::new()));
+        let service = SaCheckSignLayer::new(manager.clone())
+            .include(vec!["/open/**".to_string()])
+            .layer(tower::service_fn(echo));
+        
+        let params = manager.sign(std::collections::BTreeMap::from([("id".to_string(), "1".to_string())]));
+        let query = params.iter()
+            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let signed = Request::builder().uri(format!("/open/order?{}", query)).body(Body::empty()).unwrap();
+        let response = service.clone().oneshot(signed).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        // 重放被拒绝 | A replay is rejected
+        let replay = Request::builder().uri(format!("/open/order?{}", query)).body(Body::empty()).unwrap();
+        let response = service.clone().oneshot(replay).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        
+        let tampered = Request::builder()
+            .uri(format!("/open/order?{}", query.replace("id=1", "id=2")))
+            .body(Body::empty())
+            .unwrap();
+        let response = service.clone().oneshot(tampered).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        
+        let unprotected = Request::builder().uri("/home").body(Body::empty()).unwrap();
+        let response = service.oneshot(unprotected).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_check_basic_layer() {
+        let service = SaCheckBasicLayer::new(HttpBasicAuth::new("ops", "secret").realm("metrics"))
+            .include(vec!["/metrics".to_string()])
+            .layer(tower::service_fn(echo));
+        
+        let authorized = Request::builder()
+            .uri("/metrics")
+            .header("Authorization", "Basic b3BzOnNlY3JldA==")
+            .body(Body::empty())
+            .unwrap();
+        let response = service.clone().oneshot(authorized).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        let anonymous = Request::builder().uri("/metrics").body(Body::empty()).unwrap();
+        let response = service.clone().oneshot(anonymous).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[http::header::WWW_AUTHENTICATE], "Basic realm=\"metrics\"");
+        
+        let unprotected = Request::builder().uri("/health").body(Body::empty()).unwrap();
+        let response = service.oneshot(unprotected).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_check_csrf_layer() {
+        let csrf = CsrfManager::new(std::sync::Arc::new(sa_token_storage_memory::MemoryStorage::new()));
+        let token = csrf.get_token(&TokenValue::new("session-1")).await.unwrap();
+        let service = SaCheckCsrfLayer::new(csrf).layer(tower::service_fn(echo));
+        
+        let post = |csrf_header: Option<&str>| {
+            let mut builder = Request::builder().method("POST").header("Cookie", "sa-token=session-1");
+            if let Some(value) = csrf_header {
+                builder = builder.header("X-CSRF-Token", value);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+        
+        let response = service.clone().oneshot(post(Some(&token))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        
+        let response = service.clone().oneshot(post(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        
+        let response = service.clone().oneshot(post(Some("forged"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        
+        // 读请求和不带 Cookie 的请求不检查 | Reads and cookie-less requests are not checked
+        let get = Request::builder().header("Cookie", "sa-token=session-1").body(Body::empty()).unwrap();
+        assert_eq!(service.clone().oneshot(get).await.unwrap().status(), StatusCode::OK);
+        let api = Request::builder().method("POST").body(Body::empty()).unwrap();
+        assert_eq!(service.oneshot(api).await.unwrap().status(), StatusCode::OK);
+    }
+}
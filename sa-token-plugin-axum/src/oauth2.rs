@@ -0,0 +1,450 @@
+// Author: 金书记
+//
+//! OAuth2 授权服务器端点：授权、令牌、内省（RFC 7662）、撤销（RFC 7009）与 OpenID Connect | OAuth2 authorization server endpoints: authorize, token, introspection (RFC 7662), revocation (RFC 7009) and OpenID Connect
+//!
+//! `oauth2_routes` 一次挂载全部端点；也可以用单独的处理器自行组合。
+//! 资源服务器以机密客户端身份 POST `token` 内省，得到 `{"active": ...}` 形式的结果；
+//! 客户端 POST `token` 撤销自己的令牌，成功时返回空的 200。
+//! 启用 OIDC 后，userinfo 端点以 Bearer access_token 返回用户声明，发现端点返回提供方元数据。
+//! `oauth2_routes` mounts every endpoint at once; the individual handlers can also be combined by hand.
+//! Resource servers POST `token` as a confidential client to introspect it and get an
+//! `{"active": ...}` answer; clients POST `token` to revoke their own tokens, answered with an empty 200.
+//! With OIDC enabled, the userinfo endpoint returns the user's claims for a Bearer access token and
+//! the discovery endpoint returns the provider metadata.
+//!
+//! ```rust,ignore
+//! let oauth2 = Arc::new(OAuth2Manager::new(storage).with_oidc(provider));
+//!
+//! // GET/POST /oauth2/authorize, POST /oauth2/token, /oauth2/introspect, /oauth2/revoke, GET /oauth2/userinfo
+//! let app = Router::new()
+//!     .nest("/oauth2", oauth2_routes(OAuth2ServerState::new(oauth2.clone()).login_url("/login")))
+//!     .route("/.well-known/openid-configuration", discovery_handler(oauth2.clone()))
+//!     .layer(SaTokenLayer::new(sa_token_state));
+//! ```
+
+use std::sync::Arc;
+use axum::{Form, Router};
+use axum::extract::{OriginalUri, Query, State};
+use axum::extract::rejection::FormRejection;
+use axum::http::{header, Extensions, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Json, Redirect, Response};
+use axum::routing::{get, post, MethodRouter};
+use sa_token_core::{
+    ErrorPage, OAuth2AuthorizeRequest, OAuth2AuthorizeResponse, OAuth2ConsentDecision, OAuth2ErrorResponse,
+    OAuth2IntrospectionRequest, OAuth2Manager, OAuth2RevocationRequest, OAuth2TokenRequest, SaPage, SaTokenError,
+};
+
+/// OAuth2 授权服务器路由状态 | State of the OAuth2 authorization server routes
+#[derive(Clone)]
+pub struct OAuth2ServerState {
+    oauth2: Arc<OAuth2Manager>,
+    authorize_url: String,
+    login_url: Option<String>,
+}
+
+impl OAuth2ServerState {
+    /// 创建路由状态 | Create the route state
+    ///
+    /// # 参数 | Parameters
+    /// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+    pub fn new(oauth2: Arc<OAuth2Manager>) -> Self {
+        Self {
+            oauth2,
+            authorize_url: "/oauth2/authorize".to_string(),
+            login_url: None,
+        }
+    }
+
+    /// 授权确认表单的提交地址（默认 `/oauth2/authorize`），挂载在其他前缀下时需要修改
+    /// URL the consent form posts to (default `/oauth2/authorize`); change it when nesting under another prefix
+    pub fn authorize_url(mut self, url: impl Into<String>) -> Self {
+        self.authorize_url = url.into();
+        self
+    }
+
+    /// 未登录用户访问授权端点时跳转的登录页，原地址以 `redirect` 参数传递；未设置时返回 401
+    /// Login page for users hitting the authorization endpoint without a login, with the original URL
+    /// in the `redirect` parameter; 401 when unset
+    pub fn login_url(mut self, url: impl Into<String>) -> Self {
+        self.login_url = Some(url.into());
+        self
+    }
+}
+
+/// 创建 OAuth2 授权服务器路由 | Build the OAuth2 authorization server routes
+///
+/// ```text
+/// GET  /authorize    校验请求，展示确认页或直接携带 code 回调 | validate, then show the consent page or redirect with a code
+/// POST /authorize    确认页提交 | consent page submission
+/// POST /token        令牌端点，支持 HTTP Basic 与表单客户端认证 | token endpoint, HTTP Basic or form client authentication
+/// POST /introspect   令牌内省（RFC 7662）| token introspection (RFC 7662)
+/// POST /revoke       令牌撤销（RFC 7009）| token revocation (RFC 7009)
+/// GET  /userinfo     OpenID Connect 用户信息 | OpenID Connect userinfo
+/// ```
+///
+/// 登录用户从 `SaTokenLayer` 写入的请求扩展中读取，因此需要在外层路由上应用 `SaTokenLayer`。
+/// The logged-in user is read from the request extensions set by `SaTokenLayer`, so apply
+/// `SaTokenLayer` on the outer router.
+///
+/// # 参数 | Parameters
+/// * `state` - 路由状态 | Route state
+pub fn oauth2_routes<S>(state: OAuth2ServerState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let oauth2 = state.oauth2.clone();
+    Router::new()
+        .route("/authorize", get(authorize).post(authorize_decision))
+        .with_state(state)
+        .route("/token", token_handler(oauth2.clone()))
+        .route("/introspect", introspection_handler(oauth2.clone()))
+        .route("/revoke", revocation_handler(oauth2.clone()))
+        .route("/userinfo", userinfo_handler(oauth2))
+}
+
+async fn authorize(
+    State(state): State<OAuth2ServerState>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    extensions: Extensions,
+    Query(request): Query<OAuth2AuthorizeRequest>,
+) -> Response {
+    let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let Some(login_id) = extensions.get::<String>() else {
+        return match &state.login_url {
+            Some(login_url) => {
+                let separator = if login_url.contains('?') { '&' } else { '?' };
+                Redirect::to(&format!("{}{}redirect={}", login_url, separator, urlencoding::encode(&uri.to_string())))
+                    .into_response()
+            }
+            None => error_page(&state.oauth2, StatusCode::UNAUTHORIZED, &SaTokenError::NotLogin.to_string(), accept_language),
+        };
+    };
+    let result = state.oauth2.authorize_request(login_id, &request, &state.authorize_url, accept_language).await;
+    authorize_response(&state.oauth2, result, accept_language)
+}
+
+async fn authorize_decision(
+    State(state): State<OAuth2ServerState>,
+    headers: HeaderMap,
+    extensions: Extensions,
+    Form(decision): Form<OAuth2ConsentDecision>,
+) -> Response {
+    let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let Some(login_id) = extensions.get::<String>() else {
+        return error_page(&state.oauth2, StatusCode::UNAUTHORIZED, &SaTokenError::NotLogin.to_string(), accept_language);
+    };
+    let result = state.oauth2.authorize_decision(login_id, &decision).await;
+    authorize_response(&state.oauth2, result, accept_language)
+}
+
+/// 构建授权端点响应：回调重定向、确认页，或无法回调时的错误页
+/// Build the authorization endpoint response: the redirect, the consent page, or an error page when
+/// the client cannot be redirected to
+fn authorize_response(
+    oauth2: &OAuth2Manager,
+    result: Result<OAuth2AuthorizeResponse, SaTokenError>,
+    accept_language: Option<&str>,
+) -> Response {
+    match result {
+        Ok(OAuth2AuthorizeResponse::Redirect(location)) => Redirect::to(&location).into_response(),
+        // 禁止缓存与嵌入，防止点击劫持 | No caching or framing, against clickjacking
+        Ok(OAuth2AuthorizeResponse::Consent(html)) => (
+            [(header::CACHE_CONTROL, "no-store"), (header::X_FRAME_OPTIONS, "DENY")],
+            Html(html),
+        ).into_response(),
+        Err(e) => {
+            let status = if OAuth2ErrorResponse::from(&e).error == "server_error" {
+                StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            error_page(oauth2, status, &e.to_string(), accept_language)
+        }
+    }
+}
+
+/// 渲染错误页 | Render an error page
+fn error_page(oauth2: &OAuth2Manager, status: StatusCode, message: &str, accept_language: Option<&str>) -> Response {
+    let page = SaPage::Error(ErrorPage { status: status.as_u16(), message: message.to_string() });
+    match oauth2.page_renderer().render_negotiated(&page, accept_language) {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(_) => status.into_response(),
+    }
+}
+
+/// 处理令牌请求并构建 JSON 响应 | Handle a token request and build the JSON response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+/// * `request` - 令牌表单参数 | Token form parameters
+pub async fn token_response(
+    oauth2: &OAuth2Manager,
+    authorization: Option<&str>,
+    request: &OAuth2TokenRequest,
+) -> Response {
+    match oauth2.token_endpoint_request(authorization, request).await {
+        // RFC 6749 §5.1
+        Ok(token) => ([(header::CACHE_CONTROL, "no-store"), (header::PRAGMA, "no-cache")], Json(token)).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 处理内省请求并构建 JSON 响应 | Handle an introspection request and build the JSON response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+/// * `request` - 内省表单参数 | Introspection form parameters
+pub async fn introspection_response(
+    oauth2: &OAuth2Manager,
+    authorization: Option<&str>,
+    request: &OAuth2IntrospectionRequest,
+) -> Response {
+    match oauth2.introspection_request(authorization, request).await {
+        Ok(info) => ([(header::CACHE_CONTROL, "no-store")], Json(info)).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 处理撤销请求并构建响应 | Handle a revocation request and build the response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值 | Value of the `Authorization` header
+/// * `request` - 撤销表单参数 | Revocation form parameters
+pub async fn revocation_response(
+    oauth2: &OAuth2Manager,
+    authorization: Option<&str>,
+    request: &OAuth2RevocationRequest,
+) -> Response {
+    match oauth2.revocation_request(authorization, request).await {
+        Ok(()) => StatusCode::OK.into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// 处理 userinfo 请求并构建 JSON 响应 | Handle a userinfo request and build the JSON response
+///
+/// # 参数 | Parameters
+/// * `oauth2` - OAuth2 管理器 | OAuth2 manager
+/// * `authorization` - `Authorization` 请求头的值，形如 `Bearer <access_token>` | Value of the `Authorization` header, `Bearer <access_token>`
+pub async fn userinfo_response(oauth2: &OAuth2Manager, authorization: Option<&str>) -> Response {
+    let Some(access_token) = authorization.and_then(|v| v.strip_prefix("Bearer ")) else {
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response();
+    };
+    match oauth2.userinfo(access_token.trim()).await {
+        Ok(claims) => ([(header::CACHE_CONTROL, "no-store")], Json(claims)).into_response(),
+        Err(e) => bearer_error_response(&e),
+    }
+}
+
+/// 构建发现文档响应，未启用 OIDC 时返回 404 | Build the discovery document response, 404 when OIDC is disabled
+pub fn discovery_response(oauth2: &OAuth2Manager) -> Response {
+    match oauth2.oidc() {
+        Some(oidc) => Json(oidc.discovery()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// 构建 Bearer 错误响应（RFC 6750），401 / 403 时附带质询 | Build a Bearer error response (RFC 6750), with a challenge on 401 / 403
+fn bearer_error_response(err: &SaTokenError) -> Response {
+    let error = OAuth2ErrorResponse::from(err);
+    let status = StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        let challenge = format!("Bearer error=\"{}\"", error.error);
+        (status, [(header::WWW_AUTHENTICATE, challenge)], Json(error)).into_response()
+    } else {
+        (status, Json(error)).into_response()
+    }
+}
+
+/// 构建 OAuth2 错误响应，401 时附带 Basic 质询 | Build an OAuth2 error response, with a Basic challenge on 401
+fn error_response(err: &SaTokenError) -> Response {
+    let error = OAuth2ErrorResponse::from(err);
+    let status = StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+    if status == StatusCode::UNAUTHORIZED {
+        (status, [(header::WWW_AUTHENTICATE, "Basic")], Json(error)).into_response()
+    } else {
+        (status, Json(error)).into_response()
+    }
+}
+
+/// POST 路由处理器，处理令牌端点请求 | POST route handler for token endpoint requests
+pub fn token_handler<S>(oauth2: Arc<OAuth2Manager>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    post(move |headers: HeaderMap, form: Result<Form<OAuth2TokenRequest>, FormRejection>| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let Ok(Form(request)) = form else {
+                let error = OAuth2ErrorResponse {
+                    error: "invalid_request".to_string(),
+                    error_description: form.err().map(|e| e.body_text()),
+                };
+                return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+            };
+            let authorization = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            token_response(&oauth2, authorization, &request).await
+        }
+    })
+}
+
+/// POST 路由处理器，内省表单中的 `token` | POST route handler introspecting the form's `token`
+pub fn introspection_handler<S>(oauth2: Arc<OAuth2Manager>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    post(move |headers: HeaderMap, Form(request): Form<OAuth2IntrospectionRequest>| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let authorization = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            introspection_response(&oauth2, authorization, &request).await
+        }
+    })
+}
+
+/// POST 路由处理器，撤销表单中的 `token` | POST route handler revoking the form's `token`
+pub fn revocation_handler<S>(oauth2: Arc<OAuth2Manager>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    post(move |headers: HeaderMap, Form(request): Form<OAuth2RevocationRequest>| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let authorization = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            revocation_response(&oauth2, authorization, &request).await
+        }
+    })
+}
+
+/// GET 路由处理器，返回 Bearer access_token 对应的用户信息 | GET route handler returning the userinfo of the Bearer access token
+pub fn userinfo_handler<S>(oauth2: Arc<OAuth2Manager>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    get(move |headers: HeaderMap| {
+        let oauth2 = oauth2.clone();
+        async move {
+            let authorization = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+            userinfo_response(&oauth2, authorization).await
+        }
+    })
+}
+
+/// GET 路由处理器，返回 OpenID Connect 发现文档 | GET route handler returning the OpenID Connect discovery document
+pub fn discovery_handler<S>(oauth2: Arc<OAuth2Manager>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    get(move || {
+        let oauth2 = oauth2.clone();
+        async move { discovery_response(&oauth2) }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use sa_token_core::OAuth2Client;
+    use tower::ServiceExt;
+
+    fn request(method: &str, uri: &str, login_id: Option<&str>, form: &str) -> Request<Body> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::AUTHORIZATION, "Basic d2ViOnNlY3JldA==")
+            .body(Body::from(form.to_string()))
+            .unwrap();
+        if let Some(login_id) = login_id {
+            request.extensions_mut().insert(login_id.to_string());
+        }
+        request
+    }
+
+    async fn body(response: Response) -> String {
+        String::from_utf8(to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_routes() {
+        let oauth2 = Arc::new(OAuth2Manager::new(Arc::new(sa_token_storage_memory::MemoryStorage::new())));
+        oauth2.register_client(&OAuth2Client {
+            client_id: "web".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uris: vec!["https://app/cb".to_string()],
+            grant_types: vec!["authorization_code".to_string()],
+            scope: vec!["read".to_string()],
+        }).await.unwrap();
+        let app: Router = Router::new()
+            .nest("/oauth2", oauth2_routes(OAuth2ServerState::new(oauth2).login_url("/login")));
+        let authorize = "/oauth2/authorize?response_type=code&client_id=web&redirect_uri=https://app/cb&scope=read";
+
+        let response = app.clone().oneshot(request("GET", authorize, None, "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert!(response.headers()[header::LOCATION].to_str().unwrap().starts_with("/login?redirect=%2Foauth2%2Fauthorize%3F"));
+
+        let response = app.clone().oneshot(request("GET", authorize, Some("alice"), "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::X_FRAME_OPTIONS], "DENY");
+        let html = body(response).await;
+        assert!(html.contains(r#"action="/oauth2/authorize""#));
+        let ticket = html.split(r#"name="ticket" value=""#).nth(1).unwrap().split('"').next().unwrap();
+
+        let form = format!("ticket={}&decision=approve", ticket);
+        let response = app.clone().oneshot(request("POST", "/oauth2/authorize", Some("alice"), &form)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let location = response.headers()[header::LOCATION].to_str().unwrap();
+        let code = location.strip_prefix("https://app/cb?code=").unwrap().to_string();
+
+        let form = format!("grant_type=authorization_code&code={}&redirect_uri=https://app/cb", code);
+        let response = app.clone().oneshot(request("POST", "/oauth2/token", None, &form)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        let token: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        let access_token = token["access_token"].as_str().unwrap().to_string();
+
+        // 授权码已被使用 | The code was already used
+        let response = app.clone().oneshot(request("POST", "/oauth2/token", None, &form)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body(response).await.contains(r#""error":"invalid_grant""#));
+
+        let response = app.clone().oneshot(request("POST", "/oauth2/token", None, "code=x")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body(response).await.contains(r#""error":"invalid_request""#));
+
+        // 内省与撤销 | Introspection and revocation
+        let form = format!("token={}", access_token);
+        let response = app.clone().oneshot(request("POST", "/oauth2/introspect", None, &form)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        let info: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        assert_eq!(info["active"], true);
+        assert_eq!(info["client_id"], "web");
+
+        let unauthenticated = Request::builder()
+            .method("POST")
+            .uri("/oauth2/introspect")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(form.clone()))
+            .unwrap();
+        let response = app.clone().oneshot(unauthenticated).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Basic");
+
+        let response = app.clone().oneshot(request("POST", "/oauth2/revoke", None, &form)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body(response).await.is_empty());
+        let response = app.clone().oneshot(request("POST", "/oauth2/introspect", None, &form)).await.unwrap();
+        assert_eq!(body(response).await, r#"{"active":false}"#);
+
+        // 未知令牌的撤销同样成功 | Revoking an unknown token succeeds too
+        let response = app.oneshot(request("POST", "/oauth2/revoke", None, "token=unknown")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,115 @@
+// Author: 金书记
+//
+//! Axum 实时连接认证 | Axum realtime connection authentication
+//!
+//! 在 WebSocket 升级握手时校验 token，认证结果以 `WsAuthInfo` 交给处理函数
+//! Validates the token during the WebSocket upgrade handshake and hands the result to the handler as `WsAuthInfo`
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sa_token_adapter::utils::parse_query_string;
+use sa_token_core::{SaTokenError, ws::{WsAuthInfo, WsAuthManager}};
+use sa_token_core::response::error_json;
+use crate::adapter::{error_renderer, error_response};
+
+/// WebSocket 握手认证提取器 | WebSocket handshake authentication extractor
+///
+/// 从请求扩展中取出 `Arc<WsAuthManager>`，用握手请求的请求头和查询参数认证。
+/// token 可放在 `Authorization`、`Sec-WebSocket-Protocol` 请求头或 `token` 查询参数中。
+/// 认证失败返回 401，升级不会发生；未注册 `WsAuthManager` 时返回 500。
+/// Takes the `Arc<WsAuthManager>` from the request extensions and authenticates with the
+/// handshake headers and query. The token may be sent in the `Authorization` or
+/// `Sec-WebSocket-Protocol` header, or the `token` query parameter.
+/// Responds 401 on failure so no upgrade happens; 500 when no `WsAuthManager` is registered.
+///
+/// 浏览器通过 `Sec-WebSocket-Protocol` 传递 token 时，须用 `protocols` 回显该子协议，否则浏览器会断开连接。
+/// When a browser sends the token in `Sec-WebSocket-Protocol`, echo it back with `protocols`,
+/// or the browser drops the connection.
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// use axum::{Extension, Router, routing::get, extract::ws::WebSocketUpgrade};
+/// use sa_token_plugin_axum::*;
+///
+/// async fn ws_handler(WsAuth(auth_info): WsAuth, ws: WebSocketUpgrade) -> Response {
+///     ws.protocols([auth_info.token.clone()])
+///         .on_upgrade(move |socket| chat(socket, auth_info))
+/// }
+///
+/// let ws_auth = Arc::new(WsAuthManager::new(state.manager.clone()));
+/// let app = Router::new()
+///     .route("/ws", get(ws_handler))
+///     .layer(Extension(ws_auth));
+/// ```
+pub struct WsAuth(pub WsAuthInfo);
+
+impl<S> FromRequestParts<S> for WsAuth
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(ws_auth) = parts.extensions.get::<Arc<WsAuthManager>>().cloned() else {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(error_json(500, "WsAuthManager extension is not registered"))
+            ).into_response());
+        };
+
+        let headers: HashMap<String, String> = parts.headers
+            .iter()
+            .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
+            .collect();
+        let query = parts.uri.query().map(parse_query_string).unwrap_or_default();
+
+        match ws_auth.authenticate(&headers, &query).await {
+            Ok(auth_info) => Ok(WsAuth(auth_info)),
+            Err(e) => {
+                sa_token_core::logging::rejected("realtime", parts.uri.path(), 401, &e);
+                Err(error_response(error_renderer(&parts.extensions).as_ref(), &SaTokenError::NotLogin))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Extension, Router, body::Body, http::Request, routing::get};
+    use sa_token_core::{SaTokenConfig, SaTokenManager};
+    use sa_token_storage_memory::MemoryStorage;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_ws_auth_extractor() {
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let token = manager.login("10001").await.unwrap();
+        let app: Router = Router::new()
+            .route("/ws", get(|WsAuth(auth_info): WsAuth| async move { auth_info.login_id }))
+            .layer(Extension(Arc::new(WsAuthManager::new(manager))));
+
+        let response = app.clone()
+            .oneshot(Request::get("/ws").body(Body::empty()).unwrap())
+            .await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.clone()
+            .oneshot(Request::get(format!("/ws?token={}", token.as_str())).body(Body::empty()).unwrap())
+            .await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"10001");
+
+        let request = Request::get("/ws")
+            .header("Sec-WebSocket-Protocol", token.as_str())
+            .body(Body::empty()).unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), StatusCode::OK);
+    }
+}
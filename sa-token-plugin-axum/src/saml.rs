@@ -0,0 +1,91 @@
+// Author: 金书记
+//
+//! SAML 2.0 服务提供者端点 | SAML 2.0 service provider endpoints
+//!
+//! `saml_routes` 挂载 `/login`、`/acs` 与 `/metadata`。IdP 中登记的 ACS 地址须与
+//! `SamlServiceProvider` 的 `acs_url` 一致。需要启用 `saml` 特性。
+//! `saml_routes` mounts `/login`, `/acs` and `/metadata`. The ACS URL registered with the IdP must
+//! equal the `acs_url` of the `SamlServiceProvider`. Requires the `saml` feature.
+//!
+//! ```rust,ignore
+//! let idp = IdpMetadata::from_xml(&std::fs::read_to_string("idp-metadata.xml")?)?;
+//! let sp = Arc::new(SamlServiceProvider::new(manager, "https://app.example.com", "https://app.example.com/saml/acs", idp));
+//!
+//! let app = Router::new()
+//!     .nest("/saml", saml_routes(sp))
+//!     .layer(SaTokenLayer::new(sa_token_state));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use axum::{Form, Router};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{AppendHeaders, Html, IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use sa_token_core::{ErrorPage, SaPage, SamlServiceProvider, SsoClient};
+
+/// 创建 SAML 路由 | Build the SAML routes
+///
+/// ```text
+/// GET  /login?back=/path   跳转到 IdP 登录 | redirect to the IdP login
+/// POST /acs                校验 SAMLResponse，写入 Token Cookie 并回跳 RelayState
+///                          | validate the SAMLResponse, set the token cookie and return to RelayState
+/// GET  /metadata           SP 元数据 | SP metadata
+/// ```
+///
+/// # 参数 | Parameters
+/// * `sp` - SAML 服务提供者 | SAML service provider
+pub fn saml_routes<S>(sp: Arc<SamlServiceProvider>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/login", get(login))
+        .route("/acs", post(acs))
+        .route("/metadata", get(metadata))
+        .with_state(sp)
+}
+
+async fn login(
+    State(sp): State<Arc<SamlServiceProvider>>,
+    headers: HeaderMap,
+    Query(params): Query<BTreeMap<String, String>>,
+) -> Response {
+    let back = SsoClient::safe_back(params.get("back").map(String::as_str));
+    match sp.authn_request(Some(back)).await {
+        Ok(request) => Redirect::to(&request.url).into_response(),
+        Err(e) => error_page(&sp, StatusCode::INTERNAL_SERVER_ERROR, &e.to_string(), &headers),
+    }
+}
+
+async fn acs(
+    State(sp): State<Arc<SamlServiceProvider>>,
+    headers: HeaderMap,
+    Form(params): Form<BTreeMap<String, String>>,
+) -> Response {
+    let Some(saml_response) = params.get("SAMLResponse") else {
+        return error_page(&sp, StatusCode::BAD_REQUEST, "Missing SAMLResponse", &headers);
+    };
+    match sp.login(saml_response).await {
+        Ok((_, token)) => {
+            let back = SsoClient::safe_back(params.get("RelayState").map(String::as_str));
+            (AppendHeaders([(header::SET_COOKIE, sp.token_cookie(token.as_str()))]), Redirect::to(back)).into_response()
+        }
+        Err(e) => error_page(&sp, StatusCode::UNAUTHORIZED, &e.to_string(), &headers),
+    }
+}
+
+async fn metadata(State(sp): State<Arc<SamlServiceProvider>>) -> Response {
+    ([(header::CONTENT_TYPE, "application/samlmetadata+xml")], sp.sp_metadata()).into_response()
+}
+
+/// 渲染错误页 | Render an error page
+fn error_page(sp: &SamlServiceProvider, status: StatusCode, message: &str, headers: &HeaderMap) -> Response {
+    let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    let page = SaPage::Error(ErrorPage { status: status.as_u16(), message: message.to_string() });
+    match sp.page_renderer().render_negotiated(&page, accept_language) {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(_) => status.into_response(),
+    }
+}
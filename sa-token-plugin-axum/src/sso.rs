@@ -0,0 +1,290 @@
+// Author: 金书记
+//
+//! SSO 客户端集成 | SSO client integration
+//!
+//! `SsoClientLayer` 把未登录的浏览器请求 302 重定向到 SSO 登录中心，并在票据回跳时透明地完成本地登录；
+//! 需放在 `SaTokenLayer` 内层，以便读取已登录用户。
+//! `SsoClientLayer` 302-redirects unauthenticated browser requests to the SSO login center and
+//! transparently completes the local login when the ticket comes back; place it inside
+//! `SaTokenLayer` so it can see the logged-in user.
+//!
+//! `sso_logout_handler` 接收 SSO 服务端推送的签名登出通知，验签后清除该用户的本地会话。
+//! `sso_logout_handler` receives the signed logout notifications pushed by the SSO server and,
+//! once the signature checks out, clears the user's local session.
+//!
+//! ```rust,ignore
+//! let sso_client = Arc::new(
+//!     SsoClient::new(manager, server_auth_url, service_url)
+//!         .with_sign(SaSignManager::new("shared-secret", storage.clone()))
+//!         .with_logout_callback_url("https://app1.example.com/sso/logout-callback"),
+//! );
+//!
+//! let app = Router::new()
+//!     .route("/sso/logout-callback", sso_logout_handler(sso_client.clone()))
+//!     .route("/orders", get(orders))
+//!     .layer(SsoClientLayer::new(sso_client.clone()).include(vec!["/orders/**".to_string()]))
+//!     .layer(SaTokenLayer::new(state));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use axum::Form;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{post, MethodRouter};
+use http::{header, HeaderValue, Method, Request};
+use tower::{Layer, Service};
+use sa_token_core::response::{error_json, success_json};
+use sa_token_core::router::CompiledPattern;
+use sa_token_core::{SaSignManager, SaTokenError, SsoClient};
+
+/// 处理登出通知并构建 JSON 响应 | Handle a logout notification and build the JSON response
+///
+/// 成功返回 200，签名无效返回 401，客户端未配置签名管理器返回 500
+/// 200 on success, 401 for an invalid signature, 500 when the client has no sign manager
+pub async fn sso_logout_response(client: &SsoClient, params: &BTreeMap<String, String>) -> Response {
+    match client.handle_logout_request(params).await {
+        Ok(login_id) => Json(success_json(serde_json::json!({ "login_id": login_id }))).into_response(),
+        Err(e @ SaTokenError::ConfigError(_)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_json(500, e))).into_response()
+        }
+        Err(e) => (StatusCode::UNAUTHORIZED, Json(error_json(401, e))).into_response(),
+    }
+}
+
+/// POST 路由处理器，接收 SSO 服务端的登出通知 | POST route handler receiving logout notifications from the SSO server
+pub fn sso_logout_handler<S>(client: Arc<SsoClient>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    post(move |Form(params): Form<BTreeMap<String, String>>| {
+        let client = client.clone();
+        async move { sso_logout_response(&client, &params).await }
+    })
+}
+
+/// SSO 客户端中间件层 | SSO client middleware layer
+///
+/// 未配置路径时保护所有请求；票据回跳地址（服务 URL 的路径）总是由该层处理。
+/// Protects every request when no paths are configured; the ticket callback
+/// (the path of the service URL) is always handled by this layer.
+#[derive(Clone)]
+pub struct SsoClientLayer {
+    client: Arc<SsoClient>,
+    include: Vec<CompiledPattern>,
+    exclude: Vec<CompiledPattern>,
+}
+
+impl SsoClientLayer {
+    pub fn new(client: Arc<SsoClient>) -> Self {
+        Self { client, include: Vec::new(), exclude: Vec::new() }
+    }
+
+    /// 只保护匹配这些模式的路径 | Only protect paths matching these patterns
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+
+    /// 不保护匹配这些模式的路径 | Never protect paths matching these patterns
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.exclude = patterns.into_iter().map(CompiledPattern::new).collect();
+        self
+    }
+}
+
+impl<S> Layer<S> for SsoClientLayer {
+    type Service = SsoClientMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SsoClientMiddleware {
+            inner,
+            client: self.client.clone(),
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+        }
+    }
+}
+
+/// SSO 客户端中间件 | SSO client middleware
+///
+/// - 已登录：放行 | Logged in: passed through
+/// - 回跳地址带 `ticket`：换取本地 Token 写入 Cookie，再 302 回到 `back` 页面，票据无效时返回401
+///   | Callback carrying `ticket`: the ticket is exchanged for a local token stored in a cookie, then
+///   302 back to the `back` page; an invalid ticket gets 401
+/// - 未登录的浏览器 GET 请求：302 到 SSO 登录中心，并带上当前页面 | Unauthenticated browser GET:
+///   302 to the SSO login center, carrying the current page
+/// - 其他未登录请求：返回401 | Any other unauthenticated request: 401
+#[derive(Clone)]
+pub struct SsoClientMiddleware<S> {
+    inner: S,
+    client: Arc<SsoClient>,
+    include: Vec<CompiledPattern>,
+    exclude: Vec<CompiledPattern>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SsoClientMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let client = self.client.clone();
+        let include = self.include.clone();
+        let exclude = self.exclude.clone();
+
+        Box::pin(async move {
+            if request.extensions().get::<String>().is_some() {
+                return inner.call(request).await;
+            }
+
+            let path = request.uri().path();
+            if path == client.callback_path() {
+                let params = SaSignManager::params_from_query(request.uri().query().unwrap_or_default());
+                if let Some(ticket) = params.get("ticket") {
+                    return Ok(match client.exchange_ticket(ticket).await {
+                        Ok((_, token)) => {
+                            let back = SsoClient::safe_back(params.get("back").map(String::as_str));
+                            let mut response = redirect(back);
+                            if let Ok(value) = HeaderValue::from_str(&client.token_cookie(&token)) {
+                                response.headers_mut().insert(header::SET_COOKIE, value);
+                            }
+                            response
+                        }
+                        Err(e) => unauthorized(e),
+                    });
+                }
+            }
+
+            let protected = (include.is_empty() || include.iter().any(|p| p.matches(path)))
+                && !exclude.iter().any(|p| p.matches(path));
+            if !protected {
+                return inner.call(request).await;
+            }
+
+            let accepts_html = request.headers().get(header::ACCEPT)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("text/html"));
+            if request.method() == Method::GET && accepts_html {
+                let back = request.uri().path_and_query().map_or(path, |pq| pq.as_str());
+                return Ok(redirect(&client.get_login_url_with_back(back)));
+            }
+            Ok(unauthorized(SaTokenError::NotLogin))
+        })
+    }
+}
+
+fn redirect<B: Default>(location: &str) -> http::Response<B> {
+    let mut response = http::Response::new(B::default());
+    *response.status_mut() = StatusCode::FOUND;
+    if let Ok(value) = HeaderValue::from_str(location) {
+        response.headers_mut().insert(header::LOCATION, value);
+    }
+    response
+}
+
+fn unauthorized<B: Default>(e: SaTokenError) -> http::Response<B> {
+    let mut response = http::Response::new(B::default());
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    let error_json = serde_json::to_string(&error_json(401, e)).unwrap_or_default();
+    if let Ok(value) = HeaderValue::from_str(&error_json) {
+        response.headers_mut().insert("X-Sa-Token-Error", value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Request;
+    use sa_token_core::{SaTokenConfig, SaTokenManager, SsoServer};
+    use sa_token_storage_memory::MemoryStorage;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_sso_logout_handler() {
+        let manager = Arc::new(SaTokenManager::new(Arc::new(MemoryStorage::new()), SaTokenConfig::default()));
+        let client = Arc::new(
+            SsoClient::new(manager, "http://sso.com/auth".to_string(), "http://app.com/sso/login".to_string())
+                .with_sign(SaSignManager::new("sso-secret", Arc::new(MemoryStorage::new()))),
+        );
+        client.login_by_ticket("alice".to_string()).await.unwrap();
+        let app: Router = Router::new().route("/sso/logout-callback", sso_logout_handler(client.clone()));
+
+        let post = |params: BTreeMap<String, String>| {
+            let body = params.iter()
+                .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+                .collect::<Vec<_>>()
+                .join("&");
+            Request::post("/sso/logout-callback")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let params = |secret: &str| SaSignManager::new(secret, Arc::new(MemoryStorage::new()))
+            .sign(BTreeMap::from([("login_id".to_string(), "alice".to_string())]));
+
+        let response = app.clone().oneshot(post(params("wrong-secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(client.check_local_login("alice").await);
+
+        let response = app.oneshot(post(params("sso-secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!client.check_local_login("alice").await);
+    }
+
+    #[tokio::test]
+    async fn test_sso_client_layer() {
+        let storage: Arc<dyn sa_token_adapter::storage::SaStorage> = Arc::new(MemoryStorage::new());
+        let manager = Arc::new(SaTokenManager::new(storage, SaTokenConfig::default()));
+        let server = SsoServer::new(manager.clone()).with_allow_urls(vec!["http://app.com/sso/login".to_string()]);
+        let client = Arc::new(SsoClient::new(manager, "http://sso.com/auth".to_string(), "http://app.com/sso/login".to_string()));
+        let service = SsoClientLayer::new(client)
+            .include(vec!["/orders/**".to_string()])
+            .layer(tower::service_fn(|_: Request<Body>| async { Ok::<_, std::convert::Infallible>(Response::new(Body::empty())) }));
+        let get = |uri: &str, accept: &str| Request::get(uri).header("Accept", accept).body(Body::empty()).unwrap();
+
+        let response = service.clone().oneshot(get("/public", "text/html")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = service.clone().oneshot(get("/orders/1", "application/json")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // 浏览器请求重定向到登录中心 | A browser request is redirected to the login center
+        let response = service.clone().oneshot(get("/orders/1?tab=items", "text/html")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+        let location = response.headers()[header::LOCATION].to_str().unwrap();
+        let service_url = SaSignManager::params_from_query(location.split_once('?').unwrap().1).remove("service").unwrap();
+        assert_eq!(service_url, "http://app.com/sso/login?back=%2Forders%2F1%3Ftab%3Ditems");
+
+        // 票据回跳：写入 Cookie 并回到原页面 | Ticket callback: cookie set and back to the original page
+        let redirect = server.build_redirect_url("alice", &service_url).await.unwrap();
+        let callback = redirect.strip_prefix("http://app.com").unwrap();
+        let response = service.clone().oneshot(get(callback, "text/html")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "/orders/1?tab=items");
+        assert!(response.headers()[header::SET_COOKIE].to_str().unwrap().starts_with("sa-token="));
+
+        // 票据只能使用一次 | A ticket is single use
+        let response = service.clone().oneshot(get(callback, "text/html")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut logged_in = get("/orders/1", "text/html");
+        logged_in.extensions_mut().insert("alice".to_string());
+        let response = service.oneshot(logged_in).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
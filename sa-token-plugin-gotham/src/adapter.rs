@@ -1,19 +1,28 @@
 use gotham::hyper::{HeaderMap, Uri};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, token::TokenValue};
 
 /// 中文: Gotham 请求适配器，实现 SaRequest 接口
 /// English: Gotham request adapter implementing SaRequest trait
 pub struct GothamRequestAdapter<'a> {
     headers: &'a HeaderMap,
     uri: &'a Uri,
+    client_ip: Option<String>,
 }
 
 impl<'a> GothamRequestAdapter<'a> {
     /// 中文: 通过 HeaderMap 和 Uri 构造适配器
     /// English: Constructs adapter from HeaderMap and Uri
     pub fn new(headers: &'a HeaderMap, uri: &'a Uri) -> Self {
-        Self { headers, uri }
+        Self { headers, uri, client_ip: None }
+    }
+
+    /// 中文: 设置客户端 IP（来自 `gotham::state::client_addr`）
+    /// English: Sets the client IP (from `gotham::state::client_addr`)
+    pub fn with_client_ip(mut self, client_ip: Option<String>) -> Self {
+        self.client_ip = client_ip;
+        self
     }
 }
 
@@ -72,10 +81,10 @@ impl<'a> SaRequest for GothamRequestAdapter<'a> {
         "GET".to_string()
     }
 
-    /// 中文: Gotham 状态中默认无法获取客户端 IP
-    /// English: Client IP not available by default in Gotham state
+    /// 中文: Gotham 请求头中不含对端地址，需通过 `with_client_ip` 设置
+    /// English: Headers carry no peer address in Gotham, set it with `with_client_ip`
     fn get_client_ip(&self) -> Option<String> {
-        None
+        self.client_ip.clone()
     }
 }
 
@@ -130,3 +139,35 @@ impl SaResponse for GothamResponseAdapter {
     }
 }
 
+/// 中文: 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+/// English: Writes a new token into the response: the cookie, plus the `token_name` header when `is_write_header` is on
+pub fn write_token<B>(manager: &SaTokenManager, token: &TokenValue, response: gotham::hyper::Response<B>) -> gotham::hyper::Response<B> {
+    append_headers(response, manager.token_response_headers(token))
+}
+
+/// 中文: 登出后清除响应中的 token cookie
+/// English: Clears the token cookie after logout
+pub fn clear_token<B>(manager: &SaTokenManager, response: gotham::hyper::Response<B>) -> gotham::hyper::Response<B> {
+    append_headers(response, manager.logout_response_headers())
+}
+
+/// 中文: 用给定的 `ErrorRenderer`（通常是 `SaTokenState::error_renderer`）把错误渲染为拒绝响应，中间件共用
+/// English: Render an error as a rejection with the given `ErrorRenderer` (usually `SaTokenState::error_renderer`), shared by the middlewares
+pub fn error_response(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> gotham::hyper::Response<gotham::hyper::Body> {
+    let (status, headers, body) = renderer.render(error);
+    let mut response = gotham::hyper::Response::new(gotham::hyper::Body::from(body));
+    *response.status_mut() = gotham::hyper::StatusCode::from_u16(status)
+        .unwrap_or(gotham::hyper::StatusCode::UNAUTHORIZED);
+    append_headers(response, headers)
+}
+
+fn append_headers<B>(mut response: gotham::hyper::Response<B>, headers: Vec<(String, String)>) -> gotham::hyper::Response<B> {
+    use gotham::hyper::header::{HeaderName, HeaderValue};
+    for (name, value) in headers {
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
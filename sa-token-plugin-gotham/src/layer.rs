@@ -2,7 +2,7 @@ use gotham::state::State;
 use gotham::middleware::Middleware;
 use gotham::handler::HandlerFuture;
 use std::pin::Pin;
-use sa_token_core::{token::TokenValue, SaTokenContext};
+use sa_token_core::{token::TokenValue, SaTokenContext, StpUtil};
 use crate::state::SaTokenState;
 use std::sync::Arc;
 
@@ -22,32 +22,50 @@ impl Middleware for SaTokenLayer {
     where
         Chain: FnOnce(State) -> Pin<Box<HandlerFuture>> + Send + 'static,
     {
-        Box::pin(async move {
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = state.try_borrow::<gotham::hyper::HeaderMap>().and_then(|headers| {
+            self.state.manager.config.resolve_tenant(|name| {
+                headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+            })
+        });
+        
+        Box::pin(sa_token_core::tenant::scope(tenant, async move {
             let mut ctx = SaTokenContext::new();
+            if let (Some(headers), Some(uri)) = (state.try_borrow::<gotham::hyper::HeaderMap>(), state.try_borrow::<gotham::hyper::Uri>()) {
+                let adapter = crate::adapter::GothamRequestAdapter::new(headers, uri)
+                    .with_client_ip(gotham::state::client_addr(&state).map(|addr| addr.ip().to_string()));
+                ctx.client_ip = self.state.manager.config.client_ip(&adapter);
+                ctx.fingerprint = self.state.manager.config.fingerprint(&adapter);
+            }
             
             if let Some(token_str) = extract_token_from_state(&state, &self.state) {
-                tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+                sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
                 let token = TokenValue::new(token_str);
                 
-                if self.state.manager.is_valid(&token).await {
-                    if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if self.state.manager.is_valid(&token).await
+                    && let Ok(token_info) = self.state.manager.get_token_info(&token).await
+                    && self.state.manager.check_binding(&token_info, ctx.client_ip.as_deref(), ctx.fingerprint.as_deref()).is_ok() {
+                    let login_id = token_info.login_id.clone();
+                    self.state.manager.record_activity(&token_info, ctx.client_ip.as_deref()).await;
                         
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id.clone());
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id.clone());
                         
-                        state.put(crate::wrapper::TokenValueWrapper(token));
-                        state.put(crate::wrapper::LoginIdWrapper(login_id));
-                    }
+                    state.put(crate::wrapper::TokenValueWrapper(token));
+                    state.put(crate::wrapper::LoginIdWrapper(login_id));
                 }
             }
+            if let (Some(headers), Some(uri)) = (state.try_borrow::<gotham::hyper::HeaderMap>(), state.try_borrow::<gotham::hyper::Uri>()) {
+                ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::GothamRequestAdapter::new(headers, uri));
+            }
             
             SaTokenContext::set_current(ctx);
             let result = chain(state).await;
             SaTokenContext::clear();
             result
-        })
+        }))
     }
 }
 
@@ -68,37 +86,33 @@ fn extract_token_from_state(state: &State, token_state: &SaTokenState) -> Option
     // 1. 从 Header 中获取
     if let Some(headers) = state.try_borrow::<HeaderMap>() {
         // 1.1 尝试从指定名称的 header 获取
-        if let Some(header_value) = headers.get(token_name) {
-            if let Ok(value_str) = header_value.to_str() {
-                return Some(extract_bearer_token(value_str));
-            }
+        if let Some(header_value) = headers.get(token_name)
+            && let Ok(value_str) = header_value.to_str() {
+            return Some(extract_bearer_token(value_str));
         }
         
         // 1.2 尝试从 Authorization header 获取
-        if let Some(auth_header) = headers.get("authorization") {
-            if let Ok(auth_str) = auth_header.to_str() {
-                return Some(extract_bearer_token(auth_str));
-            }
+        if let Some(auth_header) = headers.get("authorization")
+            && let Ok(auth_str) = auth_header.to_str() {
+            return Some(extract_bearer_token(auth_str));
         }
         
         // 2. 从 Cookie 中获取
-        if let Some(cookie_header) = headers.get("cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                let cookies = parse_cookies(cookie_str);
-                if let Some(token) = cookies.get(token_name) {
-                    return Some(token.clone());
-                }
+        if let Some(cookie_header) = headers.get("cookie")
+            && let Ok(cookie_str) = cookie_header.to_str() {
+            let cookies = parse_cookies(cookie_str);
+            if let Some(token) = cookies.get(token_name) {
+                return Some(token.clone());
             }
         }
     }
     
     // 3. 从 Query 参数中获取
-    if let Some(uri) = state.try_borrow::<Uri>() {
-        if let Some(query) = uri.query() {
-            let params = parse_query_string(query);
-            if let Some(token) = params.get(token_name) {
-                return Some(token.clone());
-            }
+    if let Some(uri) = state.try_borrow::<Uri>()
+        && let Some(query) = uri.query() {
+        let params = parse_query_string(query);
+        if let Some(token) = params.get(token_name) {
+            return Some(token.clone());
         }
     }
     
@@ -113,9 +127,9 @@ fn extract_token_from_state(state: &State, token_state: &SaTokenState) -> Option
 fn extract_bearer_token(header_value: &str) -> String {
     const BEARER_PREFIX: &str = "Bearer ";
     
-    if header_value.starts_with(BEARER_PREFIX) {
+    if let Some(stripped) = header_value.strip_prefix(BEARER_PREFIX) {
         // 去除 "Bearer " 前缀
-        header_value[BEARER_PREFIX.len()..].trim().to_string()
+        stripped.trim().to_string()
     } else {
         // 直接返回 token
         header_value.trim().to_string()
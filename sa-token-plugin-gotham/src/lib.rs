@@ -93,6 +93,8 @@ pub use sa_token_storage_database::*;
 pub use adapter::*;
 pub use extractor::*;
 pub use middleware::*;
+// 显式导出以消除与 prelude 中 LoginId trait 的歧义 | Explicit export to disambiguate from prelude's LoginId trait
+pub use middleware::LoginId;
 pub use layer::SaTokenLayer;
 pub use state::{SaTokenState, SaTokenStateBuilder};
 pub use wrapper::{TokenValueWrapper, LoginIdWrapper};
@@ -12,16 +12,14 @@
 use gotham::state::{State, StateData};
 use gotham::middleware::Middleware;
 use gotham::handler::HandlerFuture;
-use gotham::hyper::{Response, StatusCode};
-use gotham::hyper::body::Body;
 use std::pin::Pin;
 use std::sync::Arc;
-use serde_json::json;
 use sa_token_core::{
-    error::messages, 
+    SaTokenError,
     token::TokenValue, 
     SaTokenContext
 };
+use crate::adapter::error_response;
 use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
 use crate::{SaTokenState, wrapper::{TokenValueWrapper, LoginIdWrapper}};
 
@@ -56,7 +54,7 @@ impl Middleware for SaTokenMiddleware {
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
-                tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+                sa_token_core::logging::token_extracted(&token_state.manager.config, "layer", &token_str);
                 let token = TokenValue::new(token_str);
                 
                 // 验证 token
@@ -99,6 +97,7 @@ impl Middleware for SaTokenMiddleware {
 #[derive(Clone)]
 pub struct AuthMiddleware;
 
+#[allow(deprecated)]
 impl AuthMiddleware {
     /// 中文 | English
     /// 创建新的认证中间件 | Create a new authentication middleware
@@ -107,6 +106,7 @@ impl AuthMiddleware {
     }
 }
 
+#[allow(deprecated)]
 impl Middleware for AuthMiddleware {
     fn call<Chain>(self, state: State, chain: Chain) -> Pin<Box<HandlerFuture>>
     where
@@ -119,6 +119,7 @@ impl Middleware for AuthMiddleware {
     }
 }
 
+#[allow(deprecated)]
 impl Default for AuthMiddleware {
     fn default() -> Self {
         Self::new()
@@ -152,7 +153,7 @@ impl Middleware for SaCheckLoginMiddleware {
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
-                tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
+                sa_token_core::logging::token_extracted(&token_state.manager.config, "login-check", &token_str);
                 let token = TokenValue::new(token_str);
                 
                 // 验证 token
@@ -178,18 +179,7 @@ impl Middleware for SaCheckLoginMiddleware {
             }
             
             // 未登录，返回401错误
-            let error_json = json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            });
-            
-            let response = Response::builder()
-                .status(StatusCode::UNAUTHORIZED)
-                .header("Content-Type", "application/json")
-                .body(Body::from(error_json.to_string()))
-                .expect("Unable to create response");
-            
-            Ok((state, response))
+            Ok((state, error_response(token_state.error_renderer.as_ref(), &SaTokenError::NotLogin)))
         })
     }
 }
@@ -225,47 +215,35 @@ impl Middleware for SaCheckPermissionMiddleware {
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
-                tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
+                sa_token_core::logging::token_extracted(&token_state.manager.config, "permission-check", &token_str);
                 let token = TokenValue::new(token_str);
                 
                 // 验证 token
-                if token_state.manager.is_valid(&token).await {
-                    if let Ok(token_info) = token_state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
+                if token_state.manager.is_valid(&token).await
+                    && let Ok(token_info) = token_state.manager.get_token_info(&token).await {
+                    let login_id = token_info.login_id.clone();
                         
-                        // 检查权限
-                        if sa_token_core::StpUtil::has_permission(&login_id, &permission).await {
-                            // 存储信息到 State
-                            state.put(TokenValueWrapper(token.clone()));
-                            state.put(LoginIdWrapper(login_id.clone()));
+                    // 检查权限
+                    if sa_token_core::StpUtil::has_permission(&login_id, &permission).await {
+                        // 存储信息到 State
+                        state.put(TokenValueWrapper(token.clone()));
+                        state.put(LoginIdWrapper(login_id.clone()));
                             
-                            // 设置上下文
-                            ctx.token = Some(token.clone());
-                            ctx.token_info = Some(Arc::new(token_info));
-                            ctx.login_id = Some(login_id);
+                        // 设置上下文
+                        ctx.token = Some(token.clone());
+                        ctx.token_info = Some(Arc::new(token_info));
+                        ctx.login_id = Some(login_id);
                             
-                            SaTokenContext::set_current(ctx);
-                            let result = chain(state).await;
-                            SaTokenContext::clear();
-                            return result;
-                        }
+                        SaTokenContext::set_current(ctx);
+                        let result = chain(state).await;
+                        SaTokenContext::clear();
+                        return result;
                     }
                 }
             }
             
             // 无权限或未登录，返回403错误
-            let error_json = json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            });
-            
-            let response = Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .header("Content-Type", "application/json")
-                .body(Body::from(error_json.to_string()))
-                .expect("Unable to create response");
-            
-            Ok((state, response))
+            Ok((state, error_response(token_state.error_renderer.as_ref(), &SaTokenError::PermissionDenied)))
         })
     }
 }
@@ -301,47 +279,35 @@ impl Middleware for SaCheckRoleMiddleware {
             
             // 提取 token
             if let Some(token_str) = extract_token_from_state(&state, &token_state) {
-                tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
+                sa_token_core::logging::token_extracted(&token_state.manager.config, "role-check", &token_str);
                 let token = TokenValue::new(token_str);
                 
                 // 验证 token
-                if token_state.manager.is_valid(&token).await {
-                    if let Ok(token_info) = token_state.manager.get_token_info(&token).await {
-                        let login_id = token_info.login_id.clone();
+                if token_state.manager.is_valid(&token).await
+                    && let Ok(token_info) = token_state.manager.get_token_info(&token).await {
+                    let login_id = token_info.login_id.clone();
                         
-                        // 检查角色
-                        if sa_token_core::StpUtil::has_role(&login_id, &role).await {
-                            // 存储信息到 State
-                            state.put(TokenValueWrapper(token.clone()));
-                            state.put(LoginIdWrapper(login_id.clone()));
+                    // 检查角色
+                    if sa_token_core::StpUtil::has_role(&login_id, &role).await {
+                        // 存储信息到 State
+                        state.put(TokenValueWrapper(token.clone()));
+                        state.put(LoginIdWrapper(login_id.clone()));
                             
-                            // 设置上下文
-                            ctx.token = Some(token.clone());
-                            ctx.token_info = Some(Arc::new(token_info));
-                            ctx.login_id = Some(login_id);
+                        // 设置上下文
+                        ctx.token = Some(token.clone());
+                        ctx.token_info = Some(Arc::new(token_info));
+                        ctx.login_id = Some(login_id);
                             
-                            SaTokenContext::set_current(ctx);
-                            let result = chain(state).await;
-                            SaTokenContext::clear();
-                            return result;
-                        }
+                        SaTokenContext::set_current(ctx);
+                        let result = chain(state).await;
+                        SaTokenContext::clear();
+                        return result;
                     }
                 }
             }
             
             // 无角色或未登录，返回403错误
-            let error_json = json!({
-                "code": 403,
-                "message": messages::ROLE_REQUIRED
-            });
-            
-            let response = Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .header("Content-Type", "application/json")
-                .body(Body::from(error_json.to_string()))
-                .expect("Unable to create response");
-            
-            Ok((state, response))
+            Ok((state, error_response(token_state.error_renderer.as_ref(), &SaTokenError::RoleDenied(role))))
         })
     }
 }
@@ -356,41 +322,35 @@ fn extract_token_from_state(state: &State, token_state: &SaTokenState) -> Option
     
     // 1. 优先从 Header 中获取
     if let Some(headers) = state.try_borrow::<HeaderMap>() {
-        if let Some(header_value) = headers.get(token_name) {
-            if let Ok(value_str) = header_value.to_str() {
-                if let Some(token) = extract_bearer_token(value_str) {
-                    return Some(token);
-                }
-            }
+        if let Some(header_value) = headers.get(token_name)
+            && let Ok(value_str) = header_value.to_str()
+            && let Some(token) = extract_bearer_token(value_str) {
+            return Some(token);
         }
         
         // 检查 Authorization header
-        if let Some(auth_header) = headers.get("authorization") {
-            if let Ok(auth_str) = auth_header.to_str() {
-                if let Some(token) = extract_bearer_token(auth_str) {
-                    return Some(token);
-                }
-            }
+        if let Some(auth_header) = headers.get("authorization")
+            && let Ok(auth_str) = auth_header.to_str()
+            && let Some(token) = extract_bearer_token(auth_str) {
+            return Some(token);
         }
         
         // 2. 从 Cookie 中获取
-        if let Some(cookie_header) = headers.get("cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                let cookies = parse_cookies(cookie_str);
-                if let Some(token) = cookies.get(token_name) {
-                    return Some(token.clone());
-                }
+        if let Some(cookie_header) = headers.get("cookie")
+            && let Ok(cookie_str) = cookie_header.to_str() {
+            let cookies = parse_cookies(cookie_str);
+            if let Some(token) = cookies.get(token_name) {
+                return Some(token.clone());
             }
         }
     }
     
     // 3. 从 Query 参数中获取
-    if let Some(uri) = state.try_borrow::<Uri>() {
-        if let Some(query) = uri.query() {
-            let params = parse_query_string(query);
-            if let Some(token) = params.get(token_name) {
-                return Some(token.clone());
-            }
+    if let Some(uri) = state.try_borrow::<Uri>()
+        && let Some(query) = uri.query() {
+        let params = parse_query_string(query);
+        if let Some(token) = params.get(token_name) {
+            return Some(token.clone());
         }
     }
     
@@ -1,6 +1,7 @@
 use std::sync::Arc;
+use std::collections::HashMap;
 use sa_token_adapter::storage::SaStorage;
-use sa_token_core::{SaTokenManager, SaTokenConfig, StpUtil};
+use sa_token_core::{SaTokenManager, SaTokenConfig, StpLogic, StpUtil};
 
 /// 中文 | English
 /// Sa-Token 状态 | Sa-Token State
@@ -9,14 +10,23 @@ use sa_token_core::{SaTokenManager, SaTokenConfig, StpUtil};
 #[derive(Clone)]
 pub struct SaTokenState {
     pub manager: Arc<SaTokenManager>,
+    
+    /// 本状态的中间件使用的拒绝响应渲染器 | Rejection renderer of this state's middlewares
+    pub error_renderer: Arc<dyn sa_token_core::ErrorRenderer>,
+    
+    /// 其他账号体系，按账号类型索引 | Other account systems keyed by account type
+    pub logics: HashMap<String, Arc<StpLogic>>,
 }
 
 impl SaTokenState {
     /// 中文 | English
     /// 从存储和配置创建状态 | Create state from storage and config
     pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
+        let manager = SaTokenManager::new(storage, config);
         Self {
-            manager: Arc::new(SaTokenManager::new(storage, config)),
+            error_renderer: manager.error_renderer(),
+            manager: Arc::new(manager),
+            logics: HashMap::new(),
         }
     }
     
@@ -27,7 +37,9 @@ impl SaTokenState {
         StpUtil::init_manager(manager.clone());
         
         Self {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: HashMap::new(),
         }
     }
     
@@ -36,6 +48,11 @@ impl SaTokenState {
     pub fn builder() -> SaTokenStateBuilder {
         SaTokenStateBuilder::default()
     }
+    
+    /// 获取指定账号类型的账号体系 | Get the account system of an account type
+    pub fn logic(&self, login_type: &str) -> Option<&Arc<StpLogic>> {
+        self.logics.get(login_type)
+    }
 }
 
 /// 中文 | English
@@ -43,6 +60,7 @@ impl SaTokenState {
 #[derive(Default)]
 pub struct SaTokenStateBuilder {
     config_builder: sa_token_core::config::SaTokenConfigBuilder,
+    logics: Vec<StpLogic>,
 }
 
 impl SaTokenStateBuilder {
@@ -122,6 +140,18 @@ impl SaTokenStateBuilder {
         self
     }
     
+    /// 设置本状态的拒绝响应渲染器，本状态的中间件用它生成错误响应 | Set this state's rejection renderer, used by the middlewares built from this state
+    pub fn error_renderer(mut self, renderer: Arc<dyn sa_token_core::ErrorRenderer>) -> Self {
+        self.config_builder = self.config_builder.error_renderer(renderer);
+        self
+    }
+    
+    /// 添加账号体系（如 admin），构建时注册到 StpUtil | Add an account system (e.g. admin), registered with StpUtil on build
+    pub fn logic(mut self, logic: StpLogic) -> Self {
+        self.logics.push(logic);
+        self
+    }
+    
     /// 中文 | English
     /// 构建 SaTokenState | Build SaTokenState
     pub fn build(self) -> SaTokenState {
@@ -131,7 +161,9 @@ impl SaTokenStateBuilder {
         // 直接创建 SaTokenState，不再调用 from_manager 避免重复初始化
         // Create SaTokenState directly, don't call from_manager to avoid duplicate initialization
         SaTokenState {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: StpUtil::register_logics(self.logics),
         }
     }
 }
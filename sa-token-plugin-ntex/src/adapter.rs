@@ -1,6 +1,8 @@
 use ntex::web::HttpRequest;
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
+use std::sync::Arc;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// 中文: 将 Ntex HttpRequest 封装为 SaRequest 适配器
 /// English: Adapter wrapping Ntex HttpRequest to implement SaRequest
@@ -132,3 +134,46 @@ impl SaResponse for NtexResponseAdapter {
     }
 }
 
+/// 中文 | English
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头 | Write a new token into the response: the cookie, plus the `token_name` header when `is_write_header` is on
+pub fn write_token(manager: &SaTokenManager, token: &TokenValue, response: ntex::web::HttpResponse) -> ntex::web::HttpResponse {
+    append_headers(response, manager.token_response_headers(token))
+}
+
+/// 中文 | English
+/// 登出后清除响应中的 token cookie | Clear the token cookie after logout
+pub fn clear_token(manager: &SaTokenManager, response: ntex::web::HttpResponse) -> ntex::web::HttpResponse {
+    append_headers(response, manager.logout_response_headers())
+}
+
+/// 中文 | English
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenLayer` 放入请求扩展），缺省为 `DefaultErrorRenderer`
+/// The `ErrorRenderer` of the request's `SaTokenState` (put into the extensions by `SaTokenLayer`), defaulting to `DefaultErrorRenderer`
+pub fn error_renderer<E>(req: &ntex::web::WebRequest<E>) -> Arc<dyn ErrorRenderer> {
+    req.extensions().get::<Arc<dyn ErrorRenderer>>().cloned().unwrap_or_else(default_error_renderer)
+}
+
+/// 中文 | English
+/// 按给定的 `ErrorRenderer` 渲染错误响应 | Render an error response with the given `ErrorRenderer`
+pub fn error_response(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> ntex::web::HttpResponse {
+    let (status, headers, body) = renderer.render(error);
+    let status = ntex::http::StatusCode::from_u16(status).unwrap_or(ntex::http::StatusCode::UNAUTHORIZED);
+    append_headers(ntex::web::HttpResponse::build(status).body(body), headers)
+}
+
+/// 中文 | English
+/// 把错误包装成中间件可返回的 `ntex::web::Error` | Wrap an error into an `ntex::web::Error` middlewares can return
+pub(crate) fn rejection(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> ntex::web::Error {
+    ntex::web::Error::from(ntex::web::error::InternalError::from_response(error.to_string(), error_response(renderer, error)))
+}
+
+fn append_headers(mut response: ntex::web::HttpResponse, headers: Vec<(String, String)>) -> ntex::web::HttpResponse {
+    use ntex::http::header::{HeaderName, HeaderValue};
+    for (name, value) in headers {
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
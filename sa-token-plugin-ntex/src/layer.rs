@@ -40,30 +40,49 @@ where
     type Error = Error;
 
     async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
-        let mut sa_ctx = SaTokenContext::new();
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = self.state.manager.config.resolve_tenant(
+            |name| req.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string),
+        );
+        // 之后的中间件用本状态的渲染器生成拒绝响应 | Later middlewares render rejections with this state's renderer
+        req.extensions_mut().insert(self.state.error_renderer.clone());
         
-        if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
+        sa_token_core::tenant::scope(tenant, async move {
+            let mut sa_ctx = SaTokenContext::new();
+            let client_ip = self.state.manager.config.resolve_client_ip(
+                |name| req.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string),
+                || req.peer_addr().map(|addr| addr.ip().to_string()),
+            );
+            let fingerprint = self.state.manager.config.resolve_fingerprint(
+                |name| req.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string),
+            );
+        
+            if let Some(token_str) = extract_token_from_request(&req, &self.state) {
+                sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
+                let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                req.extensions_mut().insert(token.clone());
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if self.state.manager.is_valid(&token).await
+                    && let Ok(token_info) = self.state.manager.get_token_info(&token).await
+                    && self.state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
                     let login_id = token_info.login_id.clone();
+                    self.state.manager.record_activity(&token_info, client_ip.as_deref()).await;
+                    req.extensions_mut().insert(token.clone());
                     req.extensions_mut().insert(login_id.clone());
-                    
+                
                     sa_ctx.token = Some(token.clone());
                     sa_ctx.token_info = Some(Arc::new(token_info));
                     sa_ctx.login_id = Some(login_id);
                 }
             }
-        }
+            sa_ctx.client_ip = client_ip;
+            sa_ctx.fingerprint = fingerprint;
         
-        SaTokenContext::set_current(sa_ctx);
-        let result = ctx.call(&self.service, req).await;
-        SaTokenContext::clear();
-        result
+            SaTokenContext::set_current(sa_ctx);
+            let result = ctx.call(&self.service, req).await;
+            SaTokenContext::clear();
+            result
+        }).await
     }
 }
 
@@ -75,41 +94,36 @@ where
     let headers = req.headers();
     
     // 1. 从 token_name 指定的 header 获取
-    if let Some(header_value) = headers.get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            return Some(extract_bearer_token(value_str));
-        }
+    if let Some(header_value) = headers.get(token_name)
+        && let Ok(value_str) = header_value.to_str() {
+        return Some(extract_bearer_token(value_str));
     }
     
     // 2. 从标准 Authorization 头获取
-    if let Some(auth_header) = headers.get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            return Some(extract_bearer_token(auth_str));
-        }
+    if let Some(auth_header) = headers.get("Authorization")
+        && let Ok(auth_str) = auth_header.to_str() {
+        return Some(extract_bearer_token(auth_str));
     }
     
     // 3. 从 Cookie 获取
-    if let Some(cookie_header) = headers.get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            if let Some(token) = parse_cookie(cookie_str, token_name) {
-                return Some(token);
-            }
-        }
+    if let Some(cookie_header) = headers.get("cookie")
+        && let Ok(cookie_str) = cookie_header.to_str()
+        && let Some(token) = parse_cookie(cookie_str, token_name) {
+        return Some(token);
     }
     
     // 4. 从查询参数获取
-    if let Some(query) = req.uri().query() {
-        if let Some(token) = parse_query_param(query, token_name) {
-            return Some(token);
-        }
+    if let Some(query) = req.uri().query()
+        && let Some(token) = parse_query_param(query, token_name) {
+        return Some(token);
     }
     
     None
 }
 
 fn extract_bearer_token(header_value: &str) -> String {
-    if header_value.starts_with("Bearer ") {
-        header_value[7..].trim().to_string()
+    if let Some(stripped) = header_value.strip_prefix("Bearer ") {
+        stripped.trim().to_string()
     } else {
         header_value.trim().to_string()
     }
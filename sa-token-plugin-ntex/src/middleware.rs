@@ -12,17 +12,15 @@
 use ntex::service::{Middleware, Service, ServiceCtx};
 use ntex::web::{Error, ErrorRenderer, WebRequest, WebResponse};
 use std::sync::Arc;
-use serde_json::json;
 use sa_token_core::{
-    error::messages, 
+    SaTokenError,
     token::TokenValue, 
     SaTokenContext,
     StpUtil
 };
 use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
 use crate::SaTokenState;
-use ntex::web::error::InternalError;
-use ntex::web::Error as WebError;
+use crate::adapter::{error_renderer, rejection};
 
 
 /// sa-token 基础中间件 - 提取并验证 token
@@ -64,10 +62,11 @@ where
 
     async fn call(&self, req: WebRequest<Err>, ctx: ServiceCtx<'_, Self>) -> Result<Self::Response, Self::Error> {
         let mut sa_ctx = SaTokenContext::new();
+        req.extensions_mut().insert(self.state.error_renderer.clone());
         
         // 提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
             let token = TokenValue::new(token_str);
             
             // 验证 token
@@ -118,6 +117,7 @@ where
 #[deprecated(note = "Use SaTokenMiddleware + SaCheckLoginMiddleware instead")]
 pub struct AuthMiddleware;
 
+#[allow(deprecated)]
 impl<S> Middleware<S> for AuthMiddleware {
     type Service = AuthMiddlewareService<S>;
 
@@ -165,10 +165,7 @@ where
         
         // 中文 | English
         // Token 无效，返回 401 | Token invalid, return 401
-        Err(WebError::from(InternalError::new(
-            "Unauthorized",
-            ntex::http::StatusCode::UNAUTHORIZED,
-        )))
+        Err(rejection(error_renderer(&req).as_ref(), &SaTokenError::NotLogin))
     }
 }
 
@@ -214,7 +211,7 @@ where
         
         // 提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "login-check", &token_str);
             let token = TokenValue::new(token_str);
             
             // 验证 token
@@ -240,13 +237,7 @@ where
         }
         
         // 未登录，返回401错误
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            }).to_string(),
-            ntex::http::StatusCode::UNAUTHORIZED,
-        )))
+        Err(rejection(self.state.error_renderer.as_ref(), &SaTokenError::NotLogin))
     }
 }
 
@@ -298,42 +289,35 @@ where
         
         // 提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "permission-check", &token_str);
             let token = TokenValue::new(token_str);
             
             // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
                     
-                    // 检查权限
-                    if StpUtil::has_permission(&login_id, &self.permission).await {
-                        // 存储信息到请求扩展
-                        req.extensions_mut().insert(token.clone());
-                        req.extensions_mut().insert(login_id.clone());
+                // 检查权限
+                if StpUtil::has_permission(&login_id, &self.permission).await {
+                    // 存储信息到请求扩展
+                    req.extensions_mut().insert(token.clone());
+                    req.extensions_mut().insert(login_id.clone());
                         
-                        // 设置上下文
-                        sa_ctx.token = Some(token.clone());
-                        sa_ctx.token_info = Some(Arc::new(token_info));
-                        sa_ctx.login_id = Some(login_id);
+                    // 设置上下文
+                    sa_ctx.token = Some(token.clone());
+                    sa_ctx.token_info = Some(Arc::new(token_info));
+                    sa_ctx.login_id = Some(login_id);
                         
-                        SaTokenContext::set_current(sa_ctx);
-                        let result = ctx.call(&self.service, req).await;
-                        SaTokenContext::clear();
-                        return result;
-                    }
+                    SaTokenContext::set_current(sa_ctx);
+                    let result = ctx.call(&self.service, req).await;
+                    SaTokenContext::clear();
+                    return result;
                 }
             }
         }
         
         // 无权限或未登录，返回403错误
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            }).to_string(),
-            ntex::http::StatusCode::FORBIDDEN,
-        )))
+        Err(rejection(self.state.error_renderer.as_ref(), &SaTokenError::PermissionDenied))
     }
 }
 
@@ -385,43 +369,36 @@ where
         
         // 提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "role-check", &token_str);
             let token = TokenValue::new(token_str);
             
             // 验证 token
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
                     
-                    // 检查角色
-                    if StpUtil::has_role(&login_id, &self.role).await {
-                        // 存储信息到请求扩展
-                        req.extensions_mut().insert(token.clone());
-                        req.extensions_mut().insert(login_id.clone());
+                // 检查角色
+                if StpUtil::has_role(&login_id, &self.role).await {
+                    // 存储信息到请求扩展
+                    req.extensions_mut().insert(token.clone());
+                    req.extensions_mut().insert(login_id.clone());
                         
-                        // 设置上下文
-                        sa_ctx.token = Some(token.clone());
-                        sa_ctx.token_info = Some(Arc::new(token_info));
-                        sa_ctx.login_id = Some(login_id);
+                    // 设置上下文
+                    sa_ctx.token = Some(token.clone());
+                    sa_ctx.token_info = Some(Arc::new(token_info));
+                    sa_ctx.login_id = Some(login_id);
                         
-                        SaTokenContext::set_current(sa_ctx);
-                        let result = ctx.call(&self.service, req).await;
-                        SaTokenContext::clear();
-                        return result;
-                    }
+                    SaTokenContext::set_current(sa_ctx);
+                    let result = ctx.call(&self.service, req).await;
+                    SaTokenContext::clear();
+                    return result;
                 }
             }
         }
         
         // 无角色或未登录，返回403错误
 
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 403,
-                "message": messages::ROLE_REQUIRED
-            }).to_string(),
-            ntex::http::StatusCode::FORBIDDEN,
-        )))
+        Err(rejection(self.state.error_renderer.as_ref(), &SaTokenError::RoleDenied(self.role.clone())))
     }
 }
 
@@ -434,6 +411,7 @@ pub struct PermissionMiddleware {
     permission: String,
 }
 
+#[allow(deprecated)]
 impl PermissionMiddleware {
     /// 中文 | English
     /// 创建权限验证中间件 | Create permission middleware
@@ -444,6 +422,7 @@ impl PermissionMiddleware {
     }
 }
 
+#[allow(deprecated)]
 impl<S> Middleware<S> for PermissionMiddleware {
     type Service = PermissionMiddlewareService<S>;
 
@@ -491,28 +470,21 @@ where
                 
                 // 简单验证 token 是否有效
                 // Simple token validation
-                if StpUtil::is_login(&token).await {
-                    if let Ok(login_id) = StpUtil::get_login_id(&token).await {
-                        // 验证权限 | Verify permission
-                        if StpUtil::has_permission(&login_id, &self.permission).await {
-                            // 将 login_id 存储到扩展中供后续使用
-                            // Store login_id in extensions for later use
-                            req.extensions_mut().insert(login_id);
-                            return ctx.call(&self.service, req).await;
-                        }
+                if StpUtil::is_login(&token).await
+                    && let Ok(login_id) = StpUtil::get_login_id(&token).await {
+                    // 验证权限 | Verify permission
+                    if StpUtil::has_permission(&login_id, &self.permission).await {
+                        // 将 login_id 存储到扩展中供后续使用
+                        // Store login_id in extensions for later use
+                        req.extensions_mut().insert(login_id);
+                        return ctx.call(&self.service, req).await;
                     }
                 }
             }
         }
         
         // 无权限或未登录，返回 403 | No permission or not logged in, return 403
-        Err(WebError::from(InternalError::new(
-            json!({
-                "code": 403,
-                "message": messages::PERMISSION_REQUIRED
-            }).to_string(),
-            ntex::http::StatusCode::FORBIDDEN,
-        )))
+        Err(rejection(error_renderer(&req).as_ref(), &SaTokenError::PermissionDenied))
     }
 }
 
@@ -526,30 +498,25 @@ where
     let token_name = &state.manager.config.token_name;
     
     // 1. 优先从 Header 中获取
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if let Some(token) = extract_bearer_token(value_str) {
-                return Some(token);
-            }
-        }
+    if let Some(header_value) = req.headers().get(token_name)
+        && let Ok(value_str) = header_value.to_str()
+        && let Some(token) = extract_bearer_token(value_str) {
+        return Some(token);
     }
     
     // 检查 Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
-        }
+    if let Some(auth_header) = req.headers().get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && let Some(token) = extract_bearer_token(auth_str) {
+        return Some(token);
     }
     
     // 2. 从 Cookie 中获取
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                return Some(token.clone());
-            }
+    if let Some(cookie_header) = req.headers().get("cookie")
+        && let Ok(cookie_str) = cookie_header.to_str() {
+        let cookies = parse_cookies(cookie_str);
+        if let Some(token) = cookies.get(token_name) {
+            return Some(token.clone());
         }
     }
     
@@ -573,12 +540,10 @@ where
     Err: ErrorRenderer,
 {
     // 只从 Authorization header 中获取 Bearer token
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
-        }
+    if let Some(auth_header) = req.headers().get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && let Some(token) = extract_bearer_token(auth_str) {
+        return Some(token);
     }
     
     None
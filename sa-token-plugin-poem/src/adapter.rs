@@ -7,6 +7,8 @@ use poem::{Request, Response, Body};
 use poem::http::{StatusCode, HeaderMap, HeaderName, HeaderValue};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
+use std::sync::Arc;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// Poem 请求适配器
 pub struct PoemRequestAdapter<'a> {
@@ -50,6 +52,10 @@ impl<'a> SaRequest for PoemRequestAdapter<'a> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn get_client_ip(&self) -> Option<String> {
+        self.request.remote_addr().as_socket_addr().map(|addr| addr.ip().to_string())
+    }
 }
 
 /// Poem 响应构建器适配器
@@ -97,10 +103,9 @@ impl Default for PoemResponseAdapter {
 
 impl SaResponse for PoemResponseAdapter {
     fn set_header(&mut self, name: &str, value: &str) {
-        if let Ok(header_name) = HeaderName::from_bytes(name.as_bytes()) {
-            if let Ok(header_value) = HeaderValue::from_str(value) {
-                self.headers.insert(header_name, header_value);
-            }
+        if let Ok(header_name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(header_value) = HeaderValue::from_str(value) {
+            self.headers.insert(header_name, header_value);
         }
     }
     
@@ -167,3 +172,41 @@ fn parse_query_string(query: &str) -> HashMap<String, String> {
     }
     params
 }
+
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+pub fn write_token(manager: &SaTokenManager, token: &TokenValue, response: Response) -> Response {
+    append_headers(response, manager.token_response_headers(token))
+}
+
+/// 登出后清除响应中的 token cookie
+pub fn clear_token(manager: &SaTokenManager, response: Response) -> Response {
+    append_headers(response, manager.logout_response_headers())
+}
+
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenLayer` 放入请求扩展），缺省为 `DefaultErrorRenderer`
+pub fn error_renderer(req: &Request) -> Arc<dyn ErrorRenderer> {
+    req.extensions().get::<Arc<dyn ErrorRenderer>>().cloned().unwrap_or_else(default_error_renderer)
+}
+
+/// 用给定的 `ErrorRenderer` 把错误渲染为拒绝响应，中间件与提取器共用
+pub fn error_response(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> Response {
+    rendered_response(renderer.render(error))
+}
+
+/// 由渲染结果（状态码、响应头、响应体）构建响应
+pub(crate) fn rendered_response((status, headers, body): (u16, Vec<(String, String)>, String)) -> Response {
+    let response = Response::builder()
+        .status(StatusCode::from_u16(status).unwrap_or(StatusCode::UNAUTHORIZED))
+        .body(body);
+    append_headers(response, headers)
+}
+
+fn append_headers(mut response: Response, headers: Vec<(String, String)>) -> Response {
+    for (name, value) in headers {
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
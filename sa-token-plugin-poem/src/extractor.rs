@@ -3,9 +3,8 @@
 //! Poem Extractor 实现
 
 use poem::{Request, Result, FromRequest, RequestBody};
-use poem::http::StatusCode;
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::{TokenValue, DEFAULT_ACCOUNT_TYPE}, PermissionSnapshot, SaTokenError, StpUtil};
+use crate::adapter::{error_renderer, error_response};
 
 /// Token 提取器
 /// 
@@ -46,30 +45,14 @@ impl<'a> FromRequest<'a> for SaTokenExtractor {
             .extensions()
             .get::<TokenValue>()
             .cloned()
-            .ok_or_else(|| {
-                poem::Error::from_string(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string(),
-                    StatusCode::UNAUTHORIZED
-                )
-            })?;
+            .ok_or_else(|| poem::Error::from_response(error_response(error_renderer(req).as_ref(), &SaTokenError::NotLogin)))?;
         
         // 从请求扩展中获取 login_id
         let login_id = req
             .extensions()
             .get::<String>()
             .cloned()
-            .ok_or_else(|| {
-                poem::Error::from_string(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string(),
-                    StatusCode::UNAUTHORIZED
-                )
-            })?;
+            .ok_or_else(|| poem::Error::from_response(error_response(error_renderer(req).as_ref(), &SaTokenError::NotLogin)))?;
         
         Ok(Self { token, login_id })
     }
@@ -131,16 +114,53 @@ impl<'a> FromRequest<'a> for LoginIdExtractor {
             .extensions()
             .get::<String>()
             .cloned()
-            .ok_or_else(|| {
-                poem::Error::from_string(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string(),
-                    StatusCode::UNAUTHORIZED
-                )
-            })?;
+            .ok_or_else(|| poem::Error::from_response(error_response(error_renderer(req).as_ref(), &SaTokenError::NotLogin)))?;
         
         Ok(Self(login_id))
     }
 }
+
+/// 当前用户权限提取器
+/// 
+/// 一次加载权限与角色列表，之后通过 `has()`、`has_any()`、`has_all()`、`has_role()` 在内存中检查
+/// 
+/// # 示例
+/// ```rust,ignore
+/// use poem::{handler, http::StatusCode};
+/// use sa_token_plugin_poem::CurrentPermissions;
+/// 
+/// #[handler]
+/// async fn refund(perms: CurrentPermissions) -> Result<String> {
+///     if !perms.has_any(&["order:refund", "order:*"]) {
+///         return Err(StatusCode::FORBIDDEN.into());
+///     }
+///     Ok(format!("refunded by {}", perms.login_id()))
+/// }
+/// ```
+pub struct CurrentPermissions(pub PermissionSnapshot);
+
+impl std::ops::Deref for CurrentPermissions {
+    type Target = PermissionSnapshot;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> FromRequest<'a> for CurrentPermissions {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        if let Some(snapshot) = req.extensions().get::<PermissionSnapshot>() {
+            return Ok(Self(snapshot.clone()));
+        }
+        let login_id = req
+            .extensions()
+            .get::<String>()
+            .cloned()
+            .ok_or_else(|| poem::Error::from_response(error_response(error_renderer(req).as_ref(), &SaTokenError::NotLogin)))?;
+        let account_type = StpUtil::get_token_info_current()
+            .map(|info| info.account_type().to_string())
+            .unwrap_or_else(|_| DEFAULT_ACCOUNT_TYPE.to_string());
+        
+        Ok(Self(StpUtil::get_permission_snapshot_by_type(&login_id, &account_type).await))
+    }
+}
@@ -3,9 +3,10 @@
 //! Poem middleware layer for Sa-Token
 //! Poem 中间件层，用于 Sa-Token
 
-use poem::{Endpoint, Middleware, Request, Result};
+use poem::{Endpoint, IntoResponse, Middleware, Request, Result};
 use std::sync::Arc;
-use sa_token_core::{token::TokenValue, SaTokenContext};
+use sa_token_core::{token::TokenValue, RememberedSession, SaTokenContext, StpUtil};
+use sa_token_adapter::context::SaRequest;
 use sa_token_core::router::PathAuthConfig;
 use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
 use crate::SaTokenState;
@@ -62,87 +63,117 @@ impl<E> Endpoint for SaTokenMiddleware<E>
 where
     E: Endpoint,
 {
-    type Output = E::Output;
+    type Output = poem::Response;
 
     async fn call(&self, mut req: Request) -> Result<Self::Output> {
-        if let Some(config) = &self.path_config {
-            let path = req.uri().path();
-            let token_str = extract_token_from_request(&req, &self.state.manager.config.token_name);
-            let result = sa_token_core::router::process_auth(path, token_str, config, &self.state.manager).await;
-            
-            if result.should_reject() {
-                return Err(poem::Error::from_status(poem::http::StatusCode::UNAUTHORIZED));
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = self.state.manager.config.tenant(&crate::adapter::PoemRequestAdapter::new(&req));
+        // Later middlewares and extractors render rejections with this state's renderer | 之后的中间件与提取器用本状态的渲染器生成拒绝响应
+        req.extensions_mut().insert(self.state.error_renderer.clone());
+        
+        sa_token_core::tenant::scope(tenant, async move {
+            let mut token_str = extract_token_from_request(&req, &self.state.manager.config.token_name);
+            // Silently re-create an expired session from the remember-me cookie | 会话失效时用 remember-me cookie 静默重建
+            let remember = crate::adapter::PoemRequestAdapter::new(&req).get_cookie(&self.state.manager.config.remember_me_cookie);
+            let remembered = self.state.manager.restore_remembered(token_str.as_deref(), remember.as_deref()).await;
+            if let Some(session) = &remembered {
+                token_str = Some(session.token.as_str().to_string());
             }
+        
+            if let Some(config) = &self.path_config {
+                let path = req.uri().path();
+                let header_version = config.version_header_name().and_then(|name| req.header(name));
+                let client_ip = config.client_ip(
+                    config.client_ip_header_name().and_then(|name| req.header(name)),
+                    self.state.manager.config.client_ip(&crate::adapter::PoemRequestAdapter::new(&req)),
+                );
+                let fingerprint = self.state.manager.config.fingerprint(&crate::adapter::PoemRequestAdapter::new(&req));
+                let result = sa_token_core::router::process_auth_request(path, Some(req.method().as_str()), header_version, client_ip.as_deref(), fingerprint.as_deref(), token_str, config, &self.state.manager).await;
             
-            let ctx = sa_token_core::router::create_context(&result);
-            SaTokenContext::set_current(ctx);
-            let response = self.inner.call(req).await;
-            SaTokenContext::clear();
-            return response;
-        }
+                if result.should_reject() {
+                    return Err(poem::Error::from_response(crate::adapter::rendered_response(config.render_rejection_response(&result, self.state.error_renderer.as_ref()))));
+                }
+            
+                let mut ctx = sa_token_core::router::create_context(&result);
+                ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::PoemRequestAdapter::new(&req));
+                SaTokenContext::set_current(ctx);
+                let response = self.inner.call(req).await;
+                SaTokenContext::clear();
+                return response.map(|response| append_set_cookies(response.into_response(), remembered));
+            }
         
-        let mut ctx = SaTokenContext::new();
-        if let Some(token_str) = extract_token_from_request(&req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
+            let mut ctx = SaTokenContext::new();
+            let client_ip = self.state.manager.config.client_ip(&crate::adapter::PoemRequestAdapter::new(&req));
+            let fingerprint = self.state.manager.config.fingerprint(&crate::adapter::PoemRequestAdapter::new(&req));
+            if let Some(token_str) = token_str {
+                sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
+                let token = TokenValue::new(token_str);
             
-            // Validate token | 验证 token
-            if self.state.manager.is_valid(&token).await {
-                // Store token in request extensions | 将 token 存储到请求扩展中
-                req.extensions_mut().insert(token.clone());
-                
-                // Get and store login_id | 获取并存储 login_id
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                // Validate token, a token bound to another IP counts as not logged in | 验证 token，绑定到其他 IP 或设备的 token 视为未登录
+                if self.state.manager.is_valid(&token).await
+                    && let Ok(token_info) = self.state.manager.get_token_info(&token).await
+                    && self.state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
+                    // Store token and login_id in request extensions | 将 token 和 login_id 存储到请求扩展中
                     let login_id = token_info.login_id.clone();
+                    self.state.manager.record_activity(&token_info, client_ip.as_deref()).await;
+                    req.extensions_mut().insert(token.clone());
                     req.extensions_mut().insert(login_id.clone());
-                    
+                
                     // Set context | 设置上下文
                     ctx.token = Some(token.clone());
                     ctx.token_info = Some(Arc::new(token_info));
                     ctx.login_id = Some(login_id);
                 }
             }
-        }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+            ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::PoemRequestAdapter::new(&req));
         
-        // Set current context | 设置当前上下文
-        SaTokenContext::set_current(ctx);
+            // Set current context | 设置当前上下文
+            SaTokenContext::set_current(ctx);
         
-        // Continue processing | 继续处理请求
-        let result = self.inner.call(req).await;
+            // Continue processing | 继续处理请求
+            let result = self.inner.call(req).await;
         
-        // Clear context | 清除上下文
-        SaTokenContext::clear();
+            // Clear context | 清除上下文
+            SaTokenContext::clear();
         
-        result
+            result.map(|response| append_set_cookies(response.into_response(), remembered))
+        }).await
+    }
+}
+
+/// Write the cookies of a session re-created from remember-me | 把 remember-me 重建会话产生的 cookie 写入响应
+fn append_set_cookies(mut response: poem::Response, remembered: Option<RememberedSession>) -> poem::Response {
+    for cookie in remembered.into_iter().flat_map(|session| session.set_cookies) {
+        if let Ok(value) = poem::http::HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(poem::http::header::SET_COOKIE, value);
+        }
     }
+    response
 }
 
 /// Extract token from Poem request | 从 Poem 请求中提取 token
 pub fn extract_token_from_request(req: &Request, token_name: &str) -> Option<String> {
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if let Some(token) = extract_bearer_token(value_str) {
-                return Some(token);
-            }
-        }
+    if let Some(header_value) = req.headers().get(token_name)
+        && let Ok(value_str) = header_value.to_str()
+        && let Some(token) = extract_bearer_token(value_str) {
+        return Some(token);
     }
     
     // Check Authorization header | 检查 Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
-        }
+    if let Some(auth_header) = req.headers().get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && let Some(token) = extract_bearer_token(auth_str) {
+        return Some(token);
     }
     
     // 2. From cookie | 从 Cookie 中获取
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                return Some(token.clone());
-            }
+    if let Some(cookie_header) = req.headers().get("cookie")
+        && let Ok(cookie_str) = cookie_header.to_str() {
+        let cookies = parse_cookies(cookie_str);
+        if let Some(token) = cookies.get(token_name) {
+            return Some(token.clone());
         }
     }
     
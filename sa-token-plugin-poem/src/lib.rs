@@ -59,15 +59,17 @@ pub mod middleware;
 pub mod extractor;
 pub mod layer;
 pub mod state;
+pub mod realtime;
 
 // ============================================================================
 // Poem 框架集成（本插件特有）
 // ============================================================================
 pub use middleware::{SaTokenMiddleware, SaCheckLoginMiddleware};
-pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor};
-pub use adapter::{PoemRequestAdapter, PoemResponseAdapter};
+pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, CurrentPermissions};
+pub use adapter::{PoemRequestAdapter, PoemResponseAdapter, write_token, clear_token, error_renderer, error_response};
 pub use layer::SaTokenLayer;
 pub use state::{SaTokenState, SaTokenStateBuilder};
+pub use realtime::SaWsAuthMiddleware;
 
 pub use sa_token_core::{self, prelude::*};
 pub use sa_token_adapter::{self, storage::SaStorage, framework::FrameworkAdapter};
@@ -5,12 +5,10 @@
 use std::sync::Arc;
 use poem::{
     Endpoint, IntoResponse, Middleware, Request, Response, Result as PoemResult,
-    http::StatusCode,
 };
-use sa_token_core::{token::TokenValue, SaTokenContext, error::messages};
+use sa_token_core::{token::TokenValue, SaTokenContext, SaTokenError};
 use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token};
-use serde_json::json;
-use crate::SaTokenState;
+use crate::{SaTokenState, adapter::error_response};
 
 /// sa-token 基础中间件 - 提取并验证 token
 pub struct SaTokenMiddleware {
@@ -44,10 +42,11 @@ impl<E: Endpoint> Endpoint for SaTokenMiddlewareImpl<E> {
     
     async fn call(&self, mut req: Request) -> PoemResult<Self::Output> {
         let mut ctx = SaTokenContext::new();
+        req.extensions_mut().insert(self.state.error_renderer.clone());
         
         // Extract token from request | 从请求中提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
             let token = TokenValue::new(token_str);
             
             // Validate token | 验证 token
@@ -120,7 +119,7 @@ impl<E: Endpoint> Endpoint for SaCheckLoginMiddlewareImpl<E> {
         
         // Extract token from request | 从请求中提取 token
         if let Some(token_str) = extract_token_from_request(&req, &self.state) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "login-check", &token_str);
             let token = TokenValue::new(token_str);
             
             // Validate token | 验证 token
@@ -150,13 +149,7 @@ impl<E: Endpoint> Endpoint for SaCheckLoginMiddlewareImpl<E> {
         }
         
         // Not logged in, return 401 | 未登录，返回401错误
-        Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header("Content-Type", "application/json")
-            .body(json!({
-                "code": 401,
-                "message": messages::AUTH_ERROR
-            }).to_string()))
+        Ok(error_response(self.state.error_renderer.as_ref(), &SaTokenError::NotLogin))
     }
 }
 
@@ -165,30 +158,25 @@ fn extract_token_from_request(req: &Request, state: &SaTokenState) -> Option<Str
     let token_name = &state.manager.config.token_name;
     
     // 1. From header | 从 Header 中获取
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if let Some(token) = extract_bearer_token(value_str) {
-                return Some(token);
-            }
-        }
+    if let Some(header_value) = req.headers().get(token_name)
+        && let Ok(value_str) = header_value.to_str()
+        && let Some(token) = extract_bearer_token(value_str) {
+        return Some(token);
     }
     
     // Check Authorization header | 检查 Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = extract_bearer_token(auth_str) {
-                return Some(token);
-            }
-        }
+    if let Some(auth_header) = req.headers().get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && let Some(token) = extract_bearer_token(auth_str) {
+        return Some(token);
     }
     
     // 2. From cookie | 从 Cookie 中获取
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                return Some(token.clone());
-            }
+    if let Some(cookie_header) = req.headers().get("cookie")
+        && let Ok(cookie_str) = cookie_header.to_str() {
+        let cookies = parse_cookies(cookie_str);
+        if let Some(token) = cookies.get(token_name) {
+            return Some(token.clone());
         }
     }
     
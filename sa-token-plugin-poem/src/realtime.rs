@@ -0,0 +1,94 @@
+// Author: 金书记
+//
+//! Poem 实时连接认证 | Poem realtime connection authentication
+//!
+//! 在 WebSocket 升级或 SSE 连接建立前校验 token，并在 token 被吊销时结束连接
+//! Validates the token before a WebSocket upgrade or SSE connection, and ends the connection once the token is revoked
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use poem::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result as PoemResult,
+};
+use sa_token_core::{SaTokenError, ws::WsAuthManager};
+use sa_token_adapter::utils::parse_query_string;
+use crate::{SaTokenState, adapter::{error_renderer, error_response}};
+
+/// WebSocket / SSE 认证中间件 | WebSocket / SSE authentication middleware
+///
+/// 认证成功后 `WsAuthInfo` 和 `Arc<WsAuthManager>` 存入请求扩展
+/// On success `WsAuthInfo` and `Arc<WsAuthManager>` are stored in the request extensions
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// use poem::{get, handler, Route, EndpointExt, web::{Data, sse::{Event, SSE}}};
+/// use sa_token_plugin_poem::*;
+///
+/// #[handler]
+/// fn events(auth_info: Data<&WsAuthInfo>, ws_auth: Data<&Arc<WsAuthManager>>) -> SSE {
+///     let stream = until_revoked(notifications(), ws_auth.clone(), auth_info.token.clone(), DEFAULT_REVOCATION_CHECK_INTERVAL);
+///     SSE::new(stream.map(|msg| Event::message(msg)))
+/// }
+///
+/// let app = Route::new()
+///     .at("/events", get(events))
+///     .with(SaWsAuthMiddleware::new(state.clone()));
+/// ```
+pub struct SaWsAuthMiddleware {
+    ws_auth: Arc<WsAuthManager>,
+}
+
+impl SaWsAuthMiddleware {
+    /// 使用默认 token 提取器创建 | Create with the default token extractor
+    pub fn new(state: SaTokenState) -> Self {
+        Self {
+            ws_auth: Arc::new(WsAuthManager::new(state.manager.clone())),
+        }
+    }
+    
+    /// 使用自定义的 WebSocket 认证管理器创建 | Create with a custom WebSocket auth manager
+    pub fn with_ws_auth(ws_auth: Arc<WsAuthManager>) -> Self {
+        Self { ws_auth }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for SaWsAuthMiddleware {
+    type Output = SaWsAuthMiddlewareImpl<E>;
+    
+    fn transform(&self, ep: E) -> Self::Output {
+        SaWsAuthMiddlewareImpl {
+            ep,
+            ws_auth: self.ws_auth.clone(),
+        }
+    }
+}
+
+pub struct SaWsAuthMiddlewareImpl<E> {
+    ep: E,
+    ws_auth: Arc<WsAuthManager>,
+}
+
+impl<E: Endpoint> Endpoint for SaWsAuthMiddlewareImpl<E> {
+    type Output = Response;
+    
+    async fn call(&self, mut req: Request) -> PoemResult<Self::Output> {
+        let headers: HashMap<String, String> = req.headers()
+            .iter()
+            .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
+            .collect();
+        let query = req.uri().query().map(parse_query_string).unwrap_or_default();
+        
+        match self.ws_auth.authenticate(&headers, &query).await {
+            Ok(auth_info) => {
+                req.extensions_mut().insert(auth_info.login_id.clone());
+                req.extensions_mut().insert(auth_info);
+                req.extensions_mut().insert(self.ws_auth.clone());
+                self.ep.call(req).await.map(IntoResponse::into_response)
+            }
+            Err(e) => {
+                sa_token_core::logging::rejected("realtime", req.uri().path(), 401, &e);
+                Ok(error_response(error_renderer(&req).as_ref(), &SaTokenError::NotLogin))
+            }
+        }
+    }
+}
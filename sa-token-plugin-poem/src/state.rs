@@ -4,7 +4,8 @@
 //! Poem 的 Sa-Token 状态管理
 
 use std::sync::Arc;
-use sa_token_core::SaTokenManager;
+use std::collections::HashMap;
+use sa_token_core::{ErrorRenderer, SaTokenManager, StpLogic};
 use sa_token_adapter::storage::SaStorage;
 
 /// Sa-Token state for Poem framework
@@ -12,18 +13,29 @@ use sa_token_adapter::storage::SaStorage;
 #[derive(Clone)]
 pub struct SaTokenState {
     pub manager: Arc<SaTokenManager>,
+    
+    /// Rejection renderer of this state's middlewares and extractors | 本状态的中间件与提取器使用的拒绝响应渲染器
+    pub error_renderer: Arc<dyn ErrorRenderer>,
+    
+    /// 其他账号体系，按账号类型索引 | Other account systems keyed by account type
+    pub logics: HashMap<String, Arc<StpLogic>>,
 }
 
 impl SaTokenState {
     /// Create new Sa-Token state | 创建新的 Sa-Token 状态
     pub fn new(manager: Arc<SaTokenManager>) -> Self {
-        Self { manager }
+        Self { error_renderer: manager.error_renderer(), manager, logics: HashMap::new() }
     }
     
     /// Create builder for Sa-Token state | 创建 Sa-Token 状态构建器
     pub fn builder() -> SaTokenStateBuilder {
         SaTokenStateBuilder::new()
     }
+    
+    /// 获取指定账号类型的账号体系 | Get the account system of an account type
+    pub fn logic(&self, login_type: &str) -> Option<&Arc<StpLogic>> {
+        self.logics.get(login_type)
+    }
 }
 
 /// Builder for Sa-Token state | Sa-Token 状态构建器
@@ -31,6 +43,8 @@ pub struct SaTokenStateBuilder {
     storage: Option<Arc<dyn SaStorage>>,
     timeout: Option<i64>,
     token_name: Option<String>,
+    error_renderer: Option<Arc<dyn ErrorRenderer>>,
+    logics: Vec<StpLogic>,
 }
 
 impl SaTokenStateBuilder {
@@ -40,6 +54,8 @@ impl SaTokenStateBuilder {
             storage: None,
             timeout: None,
             token_name: None,
+            error_renderer: None,
+            logics: Vec::new(),
         }
     }
     
@@ -61,6 +77,18 @@ impl SaTokenStateBuilder {
         self
     }
     
+    /// Set this state's rejection renderer, handed by `SaTokenLayer` to the middlewares and extractors after it | 设置本状态的拒绝响应渲染器，`SaTokenLayer` 把它交给之后的中间件与提取器
+    pub fn error_renderer(mut self, renderer: Arc<dyn ErrorRenderer>) -> Self {
+        self.error_renderer = Some(renderer);
+        self
+    }
+    
+    /// 添加账号体系（如 admin），构建时注册到 StpUtil | Add an account system (e.g. admin), registered with StpUtil on build
+    pub fn logic(mut self, logic: StpLogic) -> Self {
+        self.logics.push(logic);
+        self
+    }
+    
     /// Build Sa-Token state | 构建 Sa-Token 状态
     pub fn build(self) -> SaTokenState {
         let mut config = sa_token_core::SaTokenConfig::default();
@@ -79,10 +107,18 @@ impl SaTokenStateBuilder {
         
         let manager = SaTokenManager::new(storage, config);
         
+        if let Some(renderer) = self.error_renderer {
+            manager.set_error_renderer(renderer);
+        }
+        
         // 自动初始化全局 StpUtil | Auto-initialize global StpUtil
         sa_token_core::StpUtil::init_manager(manager.clone());
         
-        SaTokenState::new(Arc::new(manager))
+        SaTokenState {
+            error_renderer: manager.error_renderer(),
+            manager: Arc::new(manager),
+            logics: sa_token_core::StpUtil::register_logics(self.logics),
+        }
     }
 }
 
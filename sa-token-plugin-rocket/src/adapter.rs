@@ -7,6 +7,8 @@ use rocket::http::{Header, Cookie, Status, ContentType};
 use sa_token_adapter::context::{SaRequest, SaResponse, CookieOptions};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::Arc;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// Rocket 请求适配器
 pub struct RocketRequestAdapter<'a> {
@@ -118,11 +120,63 @@ impl<'a> SaResponse for RocketResponseAdapter<'a> {
 fn parse_query_string(query: &str) -> HashMap<String, String> {
     let mut params = HashMap::new();
     for pair in query.split('&') {
-        if let Some((key, value)) = pair.split_once('=') {
-            if let Ok(decoded_value) = urlencoding::decode(value) {
-                params.insert(key.to_string(), decoded_value.to_string());
-            }
+        if let Some((key, value)) = pair.split_once('=')
+            && let Ok(decoded_value) = urlencoding::decode(value) {
+            params.insert(key.to_string(), decoded_value.to_string());
         }
     }
     params
 }
+
+/// 带 token 响应头的 Responder | Responder carrying the token headers
+///
+/// 由 [`write_token`] 与 [`clear_token`] 创建 | Created by [`write_token`] and [`clear_token`]
+pub struct WithTokenHeaders<R> {
+    inner: R,
+    headers: Vec<(String, String)>,
+}
+
+impl<'r, 'o: 'r, R: rocket::response::Responder<'r, 'o>> rocket::response::Responder<'r, 'o> for WithTokenHeaders<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'o> {
+        let mut response = self.inner.respond_to(request)?;
+        for (name, value) in self.headers {
+            response.adjoin_raw_header(name, value);
+        }
+        Ok(response)
+    }
+}
+
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+///
+/// # 示例
+/// ```rust,ignore
+/// #[post("/login")]
+/// async fn login(state: &State<SaTokenState>) -> WithTokenHeaders<Json<Value>> {
+///     let token = StpUtil::login(10001).await.unwrap();
+///     write_token(&state.manager, &token, Json(json!({ "token": token.as_str() })))
+/// }
+/// ```
+pub fn write_token<R>(manager: &SaTokenManager, token: &TokenValue, responder: R) -> WithTokenHeaders<R> {
+    WithTokenHeaders { inner: responder, headers: manager.token_response_headers(token) }
+}
+
+/// 登出后清除响应中的 token cookie
+pub fn clear_token<R>(manager: &SaTokenManager, responder: R) -> WithTokenHeaders<R> {
+    WithTokenHeaders { inner: responder, headers: manager.logout_response_headers() }
+}
+
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenLayer` 写入请求缓存），缺省为 `DefaultErrorRenderer`
+/// The `ErrorRenderer` of the request's `SaTokenState` (cached on the request by `SaTokenLayer`), defaulting to `DefaultErrorRenderer`
+pub fn error_renderer(request: &Request<'_>) -> Arc<dyn ErrorRenderer> {
+    request.local_cache(|| None::<Arc<dyn ErrorRenderer>>).clone().unwrap_or_else(default_error_renderer)
+}
+
+/// 按给定的 `ErrorRenderer` 把错误写入响应 | Write an error into the response with the given `ErrorRenderer`
+pub fn write_error(renderer: &dyn ErrorRenderer, error: &SaTokenError, response: &mut Response<'_>) {
+    let (status, headers, body) = renderer.render(error);
+    response.set_status(Status::from_code(status).unwrap_or(Status::Unauthorized));
+    for (name, value) in headers {
+        response.set_raw_header(name, value);
+    }
+    response.set_sized_body(Some(body.len()), std::io::Cursor::new(body));
+}
@@ -4,23 +4,20 @@
 
 use rocket::request::{FromRequest, Request, Outcome};
 use rocket::http::Status;
-use rocket::http::ContentType;
 use rocket::response::{self, Responder};
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use sa_token_core::{token::TokenValue, SaTokenError};
+use crate::adapter::{error_renderer, write_error};
 
 /// 认证错误响应
 #[derive(Debug)]
 pub struct AuthError {
-    json: String,
+    error: SaTokenError,
 }
 
 impl<'r> Responder<'r, 'static> for AuthError {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
         let mut response = rocket::Response::new();
-        response.set_header(ContentType::JSON);
-        response.set_status(Status::Unauthorized);
-        response.set_sized_body(self.json.len(), std::io::Cursor::new(self.json));
+        write_error(error_renderer(request).as_ref(), &self.error, &mut response);
         Ok(response)
     }
 }
@@ -44,12 +41,7 @@ impl<'r> FromRequest<'r> for SaTokenGuard {
             return Outcome::Success(SaTokenGuard(token.clone()));
         }
         
-        let error = json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string();
-        
-        Outcome::Error((Status::Unauthorized, AuthError { json: error }))
+        Outcome::Error((Status::Unauthorized, AuthError { error: SaTokenError::NotLogin }))
     }
 }
 
@@ -85,11 +77,6 @@ impl<'r> FromRequest<'r> for LoginIdGuard {
             return Outcome::Success(LoginIdGuard(login_id.clone()));
         }
         
-        let error = json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string();
-        
-        Outcome::Error((Status::Unauthorized, AuthError { json: error }))
+        Outcome::Error((Status::Unauthorized, AuthError { error: SaTokenError::NotLogin }))
     }
 }
@@ -24,27 +24,45 @@ impl Fairing for SaTokenLayer {
     }
     
     async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
-        let mut ctx = SaTokenContext::new();
+        // 在所属租户中校验 token，处理函数通过 SaTokenContext 中的租户继续使用它
+        // Validate the token within its tenant; handlers keep using it through the tenant in SaTokenContext
+        let tenant = self.state.manager.config.resolve_tenant(|name| req.headers().get_one(name).map(str::to_string));
+        // 请求守卫用本状态的渲染器生成拒绝响应 | Request guards render rejections with this state's renderer
+        req.local_cache(|| Some(self.state.error_renderer.clone()));
         
-        if let Some(token_str) = extract_token_from_request(req, &self.state) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
+        sa_token_core::tenant::scope(tenant, async move {
+            let mut ctx = SaTokenContext::new();
+            let client_ip = self.state.manager.config.resolve_client_ip(
+                |name| req.headers().get_one(name).map(str::to_string),
+                || req.client_ip().map(|ip| ip.to_string()),
+            );
+            let fingerprint = self.state.manager.config.resolve_fingerprint(
+                |name| req.headers().get_one(name).map(str::to_string),
+            );
+        
+            if let Some(token_str) = extract_token_from_request(req, &self.state) {
+                sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
+                let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                req.local_cache(|| Some(token.clone()));
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if self.state.manager.is_valid(&token).await
+                    && let Ok(token_info) = self.state.manager.get_token_info(&token).await
+                    && self.state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
                     let login_id = token_info.login_id.clone();
+                    self.state.manager.record_activity(&token_info, client_ip.as_deref()).await;
+                    req.local_cache(|| Some(token.clone()));
                     req.local_cache(|| Some(login_id.clone()));
-                    
+                
                     ctx.token = Some(token.clone());
                     ctx.token_info = Some(Arc::new(token_info));
                     ctx.login_id = Some(login_id);
                 }
             }
-        }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
         
-        SaTokenContext::set_current(ctx);
+            SaTokenContext::set_current(ctx);
+        }).await
     }
     
     async fn on_response<'r>(&self, _req: &'r Request<'_>, _res: &mut Response<'r>) {
@@ -57,17 +75,15 @@ fn extract_token_from_request(req: &Request, state: &SaTokenState) -> Option<Str
     let token_name = &state.manager.config.token_name;
     
     // 1. 优先从 Header 中获取
-    if let Some(header_value) = req.headers().get_one(token_name) {
-        if let Some(token) = utils_extract_bearer_token(header_value) {
-            return Some(token);
-        }
+    if let Some(header_value) = req.headers().get_one(token_name)
+        && let Some(token) = utils_extract_bearer_token(header_value) {
+        return Some(token);
     }
     
     // 检查 Authorization header
-    if let Some(auth_header) = req.headers().get_one("authorization") {
-        if let Some(token) = utils_extract_bearer_token(auth_header) {
-            return Some(token);
-        }
+    if let Some(auth_header) = req.headers().get_one("authorization")
+        && let Some(token) = utils_extract_bearer_token(auth_header) {
+        return Some(token);
     }
     
     // 2. 从 Cookie 中获取
@@ -90,10 +106,9 @@ fn extract_token_from_request(req: &Request, state: &SaTokenState) -> Option<Str
 fn parse_query_string(query: &str) -> std::collections::HashMap<String, String> {
     let mut params = std::collections::HashMap::new();
     for pair in query.split('&') {
-        if let Some((key, value)) = pair.split_once('=') {
-            if let Ok(decoded_value) = urlencoding::decode(value) {
-                params.insert(key.to_string(), decoded_value.to_string());
-            }
+        if let Some((key, value)) = pair.split_once('=')
+            && let Ok(decoded_value) = urlencoding::decode(value) {
+            params.insert(key.to_string(), decoded_value.to_string());
         }
     }
     params
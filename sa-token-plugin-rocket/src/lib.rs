@@ -70,7 +70,7 @@ pub mod state;
 pub use middleware::{SaTokenFairing, SaCheckLoginFairing, SaCheckPermissionFairing, SaCheckRoleFairing};
 pub use layer::SaTokenLayer;
 pub use extractor::{SaTokenGuard, OptionalSaTokenGuard, LoginIdGuard};
-pub use adapter::{RocketRequestAdapter, RocketResponseAdapter};
+pub use adapter::{RocketRequestAdapter, RocketResponseAdapter, WithTokenHeaders, write_token, clear_token, error_renderer, write_error};
 
 pub use sa_token_core::{self, prelude::*};
 pub use sa_token_adapter::{self, storage::SaStorage, framework::FrameworkAdapter};
@@ -4,10 +4,9 @@
 
 use rocket::{Request, Data, Response};
 use rocket::fairing::{Fairing, Info, Kind};
-use rocket::http::{Status, ContentType};
 use crate::SaTokenState;
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use crate::adapter::write_error;
+use sa_token_core::{token::TokenValue, SaTokenError};
 
 /// sa-token Fairing - 提取并验证 token
 pub struct SaTokenFairing {
@@ -130,16 +129,9 @@ impl Fairing for SaCheckLoginFairing {
     
     async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
         // 检查是否标记为未授权
-        if let Some(_) = request.local_cache(|| None::<&str>) {
-            if *request.local_cache(|| None::<&str>) == Some("unauthorized") {
-                response.set_status(Status::Unauthorized);
-                response.set_sized_body(None, std::io::Cursor::new(
-                    json!({
-                        "code": 401,
-                        "message": messages::AUTH_ERROR
-                    }).to_string()
-                ));
-            }
+        if request.local_cache(|| None::<&str>).is_some()
+            && *request.local_cache(|| None::<&str>) == Some("unauthorized") {
+            write_error(self.state.error_renderer.as_ref(), &SaTokenError::NotLogin, response);
         }
     }
 }
@@ -184,17 +176,9 @@ impl Fairing for SaCheckPermissionFairing {
     
     async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
         // 检查是否标记为禁止访问
-        if let Some(_) = request.local_cache(|| None::<&str>) {
-            if *request.local_cache(|| None::<&str>) == Some("forbidden") {
-                response.set_status(Status::Forbidden);
-                response.set_header(ContentType::JSON);
-                response.set_sized_body(None, std::io::Cursor::new(
-                    json!({
-                        "code": 403,
-                        "message": messages::PERMISSION_REQUIRED
-                    }).to_string()
-                ));
-            }
+        if request.local_cache(|| None::<&str>).is_some()
+            && *request.local_cache(|| None::<&str>) == Some("forbidden") {
+            write_error(self.state.error_renderer.as_ref(), &SaTokenError::PermissionDenied, response);
         }
     }
 }
@@ -239,25 +223,17 @@ impl Fairing for SaCheckRoleFairing {
     
     async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
         // 检查是否标记为禁止访问
-        if let Some(_) = request.local_cache(|| None::<&str>) {
-            if *request.local_cache(|| None::<&str>) == Some("forbidden_role") {
-                response.set_status(Status::Forbidden);
-                response.set_header(ContentType::JSON);
-                response.set_sized_body(None, std::io::Cursor::new(
-                    json!({
-                        "code": 403,
-                        "message": messages::ROLE_REQUIRED
-                    }).to_string()
-                ));
-            }
+        if request.local_cache(|| None::<&str>).is_some()
+            && *request.local_cache(|| None::<&str>) == Some("forbidden_role") {
+            write_error(self.state.error_renderer.as_ref(), &SaTokenError::RoleDenied(self.role.clone()), response);
         }
     }
 }
 
 /// 提取 Bearer token
 fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
+    if let Some(stripped) = token.strip_prefix("Bearer ") {
+        stripped.to_string()
     } else {
         token.to_string()
     }
@@ -266,10 +242,9 @@ fn extract_bearer_token(token: &str) -> String {
 /// 解析查询参数
 fn parse_query_param(query: &str, name: &str) -> Option<String> {
     for pair in query.split('&') {
-        if let Some((key, value)) = pair.split_once('=') {
-            if key == name {
-                return urlencoding::decode(value).ok().map(|s| s.to_string());
-            }
+        if let Some((key, value)) = pair.split_once('=')
+            && key == name {
+            return urlencoding::decode(value).ok().map(|s| s.to_string());
         }
     }
     None
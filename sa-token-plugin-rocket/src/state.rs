@@ -3,38 +3,49 @@
 //! Sa-Token 状态管理
 
 use std::sync::Arc;
-use sa_token_core::{SaTokenManager, SaTokenListener};
+use std::collections::HashMap;
+use sa_token_core::{SaTokenManager, SaTokenListener, StpLogic, StpUtil};
 use sa_token_adapter::storage::SaStorage;
 
 /// Rocket 应用状态
 #[derive(Clone)]
 pub struct SaTokenState {
     pub manager: Arc<SaTokenManager>,
+    
+    /// 本状态的 Fairing 与请求守卫使用的拒绝响应渲染器 | Rejection renderer of this state's fairings and request guards
+    pub error_renderer: Arc<dyn sa_token_core::ErrorRenderer>,
+    
+    /// 其他账号体系，按账号类型索引 | Other account systems keyed by account type
+    pub logics: HashMap<String, Arc<StpLogic>>,
 }
 
 impl SaTokenState {
     /// 创建新的 Sa-Token 状态
     pub fn new(manager: Arc<SaTokenManager>) -> Self {
-        Self { manager }
+        Self { error_renderer: manager.error_renderer(), manager, logics: HashMap::new() }
     }
     
     /// 从存储和配置创建状态
     pub fn from_storage_and_config(storage: Arc<dyn SaStorage>, config: sa_token_core::SaTokenConfig) -> Self {
-        Self {
-            manager: Arc::new(SaTokenManager::new(storage, config)),
-        }
+        Self::new(Arc::new(SaTokenManager::new(storage, config)))
     }
     
     /// 创建状态构建器
     pub fn builder() -> SaTokenStateBuilder {
         SaTokenStateBuilder::new()
     }
+    
+    /// 获取指定账号类型的账号体系 | Get the account system of an account type
+    pub fn logic(&self, login_type: &str) -> Option<&Arc<StpLogic>> {
+        self.logics.get(login_type)
+    }
 }
 
 /// 状态构建器
 #[derive(Default)]
 pub struct SaTokenStateBuilder {
     config_builder: sa_token_core::config::SaTokenConfigBuilder,
+    logics: Vec<StpLogic>,
     listeners: Vec<Arc<dyn SaTokenListener>>,
 }
 
@@ -115,6 +126,18 @@ impl SaTokenStateBuilder {
         self
     }
     
+    /// 设置本状态的拒绝响应渲染器，本状态的 Fairing 使用它，并经 `SaTokenLayer` 交给请求守卫 | Set this state's rejection renderer, used by its fairings and, through `SaTokenLayer`, its request guards
+    pub fn error_renderer(mut self, renderer: Arc<dyn sa_token_core::ErrorRenderer>) -> Self {
+        self.config_builder = self.config_builder.error_renderer(renderer);
+        self
+    }
+    
+    /// 添加账号体系（如 admin），构建时注册到 StpUtil | Add an account system (e.g. admin), registered with StpUtil on build
+    pub fn logic(mut self, logic: StpLogic) -> Self {
+        self.logics.push(logic);
+        self
+    }
+    
     /// 构建 Sa-Token 状态
     pub fn build(self) -> SaTokenState {
         // config_builder.build() 已经自动初始化了 StpUtil
@@ -129,7 +152,9 @@ impl SaTokenStateBuilder {
         // 直接创建 SaTokenState，config_builder.build() 已经初始化了 StpUtil
         // Create SaTokenState directly, config_builder.build() already initialized StpUtil
         SaTokenState {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: StpUtil::register_logics(self.logics),
         }
     }
 }
@@ -6,6 +6,8 @@
 use salvo::prelude::*;
 use sa_token_adapter::{SaRequest, SaResponse, CookieOptions, build_cookie_string};
 use serde::Serialize;
+use std::sync::Arc;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// 中文 | English
 /// Salvo 请求适配器 | Salvo request adapter
@@ -37,12 +39,11 @@ impl<'a> SaRequest for SalvoRequestAdapter<'a> {
         }
         
         // 如果没有找到，手动解析 Cookie 头 | If not found, manually parse Cookie header
-        if let Some(cookie_header) = self.request.headers().get("cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                let cookies = sa_token_adapter::utils::parse_cookies(cookie_str);
-                if let Some(value) = cookies.get(name) {
-                    return Some(value.to_string());
-                }
+        if let Some(cookie_header) = self.request.headers().get("cookie")
+            && let Ok(cookie_str) = cookie_header.to_str() {
+            let cookies = sa_token_adapter::utils::parse_cookies(cookie_str);
+            if let Some(value) = cookies.get(name) {
+                return Some(value.to_string());
             }
         }
         
@@ -73,6 +74,10 @@ impl<'a> SaRequest for SalvoRequestAdapter<'a> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn get_client_ip(&self) -> Option<String> {
+        self.request.remote_addr().clone().into_std().map(|addr| addr.ip().to_string())
+    }
 }
 
 /// 中文 | English
@@ -91,10 +96,9 @@ impl<'a> SalvoResponseAdapter<'a> {
 
 impl<'a> SaResponse for SalvoResponseAdapter<'a> {
     fn set_header(&mut self, name: &str, value: &str) {
-        if let Ok(header_name) = http::header::HeaderName::from_bytes(name.as_bytes()) {
-            if let Ok(header_value) = http::header::HeaderValue::from_str(value) {
-                self.response.headers_mut().insert(header_name, header_value);
-            }
+        if let Ok(header_name) = http::header::HeaderName::from_bytes(name.as_bytes())
+            && let Ok(header_value) = http::header::HeaderValue::from_str(value) {
+            self.response.headers_mut().insert(header_name, header_value);
         }
     }
     
@@ -120,3 +124,47 @@ impl<'a> SaResponse for SalvoResponseAdapter<'a> {
     }
 }
 
+/// 中文 | English
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+/// Write a new token into the response: the cookie, plus the `token_name` header when `is_write_header` is on
+pub fn write_token(manager: &SaTokenManager, token: &TokenValue, res: &mut Response) {
+    append_headers(res, manager.token_response_headers(token));
+}
+
+/// 中文 | English
+/// 登出后清除响应中的 token cookie | Clear the token cookie after logout
+pub fn clear_token(manager: &SaTokenManager, res: &mut Response) {
+    append_headers(res, manager.logout_response_headers());
+}
+
+/// 中文 | English
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenLayer` 注入 Depot），缺省为 `DefaultErrorRenderer`
+/// The `ErrorRenderer` of the request's `SaTokenState` (injected into the depot by `SaTokenLayer`), defaulting to `DefaultErrorRenderer`
+pub fn error_renderer(depot: &Depot) -> Arc<dyn ErrorRenderer> {
+    depot.obtain::<Arc<dyn ErrorRenderer>>().cloned().unwrap_or_else(|_| default_error_renderer())
+}
+
+/// 中文 | English
+/// 用请求所属状态的 `ErrorRenderer` 把错误渲染进响应，中间件共用 | Render an error into the response with the `ErrorRenderer` of the request's state, shared by the middlewares
+pub fn write_error(depot: &Depot, error: &SaTokenError, res: &mut Response) {
+    write_rendered(res, error_renderer(depot).render(error));
+}
+
+/// 写入渲染结果（状态码、响应头、响应体）
+pub(crate) fn write_rendered(res: &mut Response, (status, headers, body): (u16, Vec<(String, String)>, String)) {
+    res.status_code(StatusCode::from_u16(status).unwrap_or(StatusCode::UNAUTHORIZED));
+    for (name, value) in headers {
+        if let Ok(name) = salvo::http::header::HeaderName::from_bytes(name.as_bytes()) {
+            let _ = res.add_header(name, value, true);
+        }
+    }
+    let _ = res.write_body(body);
+}
+
+fn append_headers(res: &mut Response, headers: Vec<(String, String)>) {
+    for (name, value) in headers {
+        if let Ok(name) = salvo::http::header::HeaderName::from_bytes(name.as_bytes()) {
+            let _ = res.add_header(name, value, false);
+        }
+    }
+}
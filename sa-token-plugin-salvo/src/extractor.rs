@@ -1,9 +1,71 @@
+use std::future::Future;
 use salvo::prelude::*;
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use salvo::extract::{Extractible, Metadata};
+use sa_token_core::{token::TokenValue, error::messages, SaSession, SaTokenError, SaTokenResult, StpUtil};
+use sa_token_core::response::error_json;
+use crate::adapter::write_error;
+
+/// 中文: Depot 中 token 的键 | English: Depot key of the token
+pub const DEPOT_TOKEN_KEY: &str = "sa_token";
+
+/// 中文: Depot 中登录 ID 的键 | English: Depot key of the login ID
+pub const DEPOT_LOGIN_ID_KEY: &str = "sa_login_id";
+
+/// 中文: 把已验证的登录写入 Depot 与请求扩展，供 `SaDepotExt` 与提取器读取
+/// English: Store a verified login in the depot and request extensions, read by `SaDepotExt` and the extractors
+pub(crate) fn store_login(req: &mut Request, depot: &mut Depot, token: &TokenValue, login_id: &str) {
+    depot.insert(DEPOT_TOKEN_KEY, token.clone());
+    depot.insert(DEPOT_LOGIN_ID_KEY, login_id.to_string());
+    req.extensions_mut().insert(token.clone());
+    req.extensions_mut().insert(login_id.to_string());
+}
+
+/// 中文: Depot 的类型化访问方法，替代 `depot.get::<TokenValue>("sa_token")` 这类字符串键
+/// English: Typed depot accessors replacing string-keyed lookups like `depot.get::<TokenValue>("sa_token")`
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// #[handler]
+/// async fn profile(depot: &mut Depot) -> Result<String, StatusError> {
+///     let login_id = depot.sa_login_id().ok_or_else(StatusError::unauthorized)?;
+///     let session = depot.sa_session().await.map_err(|_| StatusError::internal_server_error())?;
+///     Ok(format!("{} {:?}", login_id, session.get::<String>("nickname")))
+/// }
+/// ```
+pub trait SaDepotExt {
+    /// 中文: 当前请求的 token | English: Token of the current request
+    fn sa_token(&self) -> Option<&TokenValue>;
+
+    /// 中文: 当前登录 ID | English: Current login ID
+    fn sa_login_id(&self) -> Option<&str>;
+
+    /// 中文: 当前登录用户的会话，未登录时返回 `NotLogin`
+    /// English: Session of the logged-in user, `NotLogin` without a login
+    fn sa_session(&self) -> impl Future<Output = SaTokenResult<SaSession>> + Send;
+}
+
+impl SaDepotExt for Depot {
+    fn sa_token(&self) -> Option<&TokenValue> {
+        self.get::<TokenValue>(DEPOT_TOKEN_KEY).ok()
+    }
+
+    fn sa_login_id(&self) -> Option<&str> {
+        self.get::<String>(DEPOT_LOGIN_ID_KEY).ok().map(String::as_str)
+    }
+
+    fn sa_session(&self) -> impl Future<Output = SaTokenResult<SaSession>> + Send {
+        let login_id = self.sa_login_id().map(str::to_string);
+        async move {
+            match login_id {
+                Some(login_id) => StpUtil::get_session(login_id).await,
+                None => Err(SaTokenError::NotLogin),
+            }
+        }
+    }
+}
 
 /// 中文: 认证错误 | English: Authentication error
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct AuthError;
 
 impl AuthError {
@@ -19,10 +81,14 @@ impl AuthError {
     
     /// 中文: 转换为 JSON 字符串 | English: Convert to JSON string
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 401,
-            "message": self.message()
-        }).to_string()
+        error_json(401, self.message()).to_string()
+    }
+}
+
+#[salvo::async_trait]
+impl Writer for AuthError {
+    async fn write(self, _req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        write_error(depot, &SaTokenError::NotLogin, res);
     }
 }
 
@@ -86,5 +152,47 @@ impl LoginIdExtractor {
     }
 }
 
+// 中文: 作为 `#[handler]` 参数直接使用 | English: Usable directly as `#[handler]` arguments
+//
+// ```rust,ignore
+// #[handler]
+// async fn user_info(login_id: LoginIdExtractor) -> String {
+//     login_id.login_id().to_string()
+// }
+// ```
+
+impl<'ex> Extractible<'ex> for SaTokenExtractor {
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("SaTokenExtractor");
+        &METADATA
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + std::fmt::Debug + 'static> {
+        Self::from_request(req)
+    }
+}
+
+impl<'ex> Extractible<'ex> for OptionalSaTokenExtractor {
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("OptionalSaTokenExtractor");
+        &METADATA
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + std::fmt::Debug + 'static> {
+        Ok::<_, AuthError>(Self::from_request(req))
+    }
+}
+
+impl<'ex> Extractible<'ex> for LoginIdExtractor {
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("LoginIdExtractor");
+        &METADATA
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + std::fmt::Debug + 'static> {
+        Self::from_request(req)
+    }
+}
+
 // 这些处理程序可以在应用中定义，而不是在库中
 // These handlers can be defined in the application, not in the library
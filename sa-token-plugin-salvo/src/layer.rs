@@ -1,7 +1,8 @@
 use salvo::{Depot, Request, Response, Handler, FlowCtrl};
-use salvo::http::StatusCode;
-use sa_token_core::{token::TokenValue, SaTokenContext, router::PathAuthConfig};
+use sa_token_core::{token::TokenValue, SaTokenContext, StpUtil, router::PathAuthConfig};
 use crate::state::SaTokenState;
+use crate::extractor::store_login;
+use sa_token_adapter::context::SaRequest;
 use std::sync::Arc;
 use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token as utils_extract_bearer_token};
 
@@ -32,47 +33,84 @@ impl SaTokenLayer {
 #[salvo::async_trait]
 impl Handler for SaTokenLayer {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        if let Some(config) = &self.path_config {
-            let path = req.uri().path();
-            let token_str = extract_token_from_request(req, &self.state.manager.config.token_name);
-            let result = sa_token_core::router::process_auth(path, token_str, config, &self.state.manager).await;
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = self.state.manager.config.tenant(&crate::adapter::SalvoRequestAdapter::new(req));
+        // 之后的中间件与提取器用本状态的渲染器生成拒绝响应 | Later middlewares and extractors render rejections with this state's renderer
+        depot.inject(self.state.error_renderer.clone());
+        
+        sa_token_core::tenant::scope(tenant, async move {
+            let mut token_str = extract_token_from_request(req, &self.state.manager.config.token_name);
+            // 会话失效时用 remember-me cookie 静默重建 | Silently re-create an expired session from the remember-me cookie
+            let remember = crate::adapter::SalvoRequestAdapter::new(req).get_cookie(&self.state.manager.config.remember_me_cookie);
+            if let Some(session) = self.state.manager.restore_remembered(token_str.as_deref(), remember.as_deref()).await {
+                for cookie in &session.set_cookies {
+                    let _ = res.add_header(salvo::http::header::SET_COOKIE, cookie, false);
+                }
+                token_str = Some(session.token.as_str().to_string());
+            }
+        
+            if let Some(config) = &self.path_config {
+                let path = req.uri().path();
+                let header_version = config.version_header_name()
+                    .and_then(|name| req.headers().get(name))
+                    .and_then(|v| v.to_str().ok());
+                let client_ip = config.client_ip(
+                    config.client_ip_header_name()
+                        .and_then(|name| req.headers().get(name))
+                        .and_then(|v| v.to_str().ok()),
+                    self.state.manager.config.client_ip(&crate::adapter::SalvoRequestAdapter::new(req)),
+                );
+                let fingerprint = self.state.manager.config.fingerprint(&crate::adapter::SalvoRequestAdapter::new(req));
+                let result = sa_token_core::router::process_auth_request(path, Some(req.method().as_str()), header_version, client_ip.as_deref(), fingerprint.as_deref(), token_str, config, &self.state.manager).await;
             
-            if result.should_reject() {
-                res.status_code(StatusCode::UNAUTHORIZED);
+                if result.should_reject() {
+                    crate::adapter::write_rendered(res, config.render_rejection_response(&result, self.state.error_renderer.as_ref()));
+                    return;
+                }
+            
+                if result.is_valid
+                    && let (Some(token), Some(info)) = (&result.token, &result.token_info) {
+                    store_login(req, depot, token, &info.login_id);
+                }
+                let mut ctx = sa_token_core::router::create_context(&result);
+                ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::SalvoRequestAdapter::new(req));
+                SaTokenContext::set_current(ctx);
+                ctrl.call_next(req, depot, res).await;
+                SaTokenContext::clear();
                 return;
             }
-            
-            let ctx = sa_token_core::router::create_context(&result);
-            SaTokenContext::set_current(ctx);
-            ctrl.call_next(req, depot, res).await;
-            SaTokenContext::clear();
-            return;
-        }
         
-        // No path auth config, use default token extraction and validation
-        // 没有路径鉴权配置，使用默认的 token 提取和验证
-        let mut ctx = SaTokenContext::new();
-        if let Some(token_str) = extract_token_from_request(req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
+            // No path auth config, use default token extraction and validation
+            // 没有路径鉴权配置，使用默认的 token 提取和验证
+            let mut ctx = SaTokenContext::new();
+            let client_ip = self.state.manager.config.client_ip(&crate::adapter::SalvoRequestAdapter::new(req));
+            let fingerprint = self.state.manager.config.fingerprint(&crate::adapter::SalvoRequestAdapter::new(req));
+            if let Some(token_str) = token_str {
+                sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
+                let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                depot.insert("sa_token", token.clone());
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                // 中文 | English
+                // 绑定到其他 IP 或设备的 token 视为未登录 | A token bound to another IP counts as not logged in
+                if self.state.manager.is_valid(&token).await
+                    && let Ok(token_info) = self.state.manager.get_token_info(&token).await
+                    && self.state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
                     let login_id = token_info.login_id.clone();
-                    depot.insert("sa_login_id", login_id.clone());
-                    
+                    self.state.manager.record_activity(&token_info, client_ip.as_deref()).await;
+                    store_login(req, depot, &token, &login_id);
+                
                     ctx.token = Some(token.clone());
                     ctx.token_info = Some(Arc::new(token_info));
                     ctx.login_id = Some(login_id);
                 }
             }
-        }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+            ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::SalvoRequestAdapter::new(req));
         
-        SaTokenContext::set_current(ctx);
-        ctrl.call_next(req, depot, res).await;
-        SaTokenContext::clear();
+            SaTokenContext::set_current(ctx);
+            ctrl.call_next(req, depot, res).await;
+            SaTokenContext::clear();
+        }).await
     }
 }
 
@@ -87,46 +125,37 @@ impl Handler for SaTokenLayer {
 pub fn extract_token_from_request(req: &Request, token_name: &str) -> Option<String> {
     
     // 1. 从指定名称的请求头提取 | Extract from specified header name
-    if let Some(header_value) = req.headers().get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if !value_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(value_str) {
-                    return Some(token);
-                }
-            }
-        }
+    if let Some(header_value) = req.headers().get(token_name)
+        && let Ok(value_str) = header_value.to_str()
+        && !value_str.is_empty()
+        && let Some(token) = utils_extract_bearer_token(value_str) {
+        return Some(token);
     }
     
     // 2. 从 Authorization 请求头提取 | Extract from Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if !auth_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(auth_str) {
-                    return Some(token);
-                }
-            }
-        }
+    if let Some(auth_header) = req.headers().get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && !auth_str.is_empty()
+        && let Some(token) = utils_extract_bearer_token(auth_str) {
+        return Some(token);
     }
     
     // 3. 从 Cookie 提取 | Extract from cookie
-    if let Some(cookie_header) = req.headers().get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                if !token.is_empty() {
-                    return Some(token.to_string());
-                }
-            }
+    if let Some(cookie_header) = req.headers().get("cookie")
+        && let Ok(cookie_str) = cookie_header.to_str() {
+        let cookies = parse_cookies(cookie_str);
+        if let Some(token) = cookies.get(token_name)
+            && !token.is_empty() {
+            return Some(token.to_string());
         }
     }
     
     // 4. 从查询参数提取 | Extract from query parameter
     if let Some(query) = req.uri().query() {
         let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            if !token.is_empty() {
-                return Some(token.to_string());
-            }
+        if let Some(token) = params.get(token_name)
+            && !token.is_empty() {
+            return Some(token.to_string());
         }
     }
     
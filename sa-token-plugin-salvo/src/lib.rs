@@ -54,6 +54,18 @@
 //!         .serve(router)
 //!         .await;
 //! }
+//!
+//! // 通过 `SaDepotExt` 或提取器读取登录信息 | Read the login through `SaDepotExt` or an extractor
+//! #[handler]
+//! async fn user_info_handler(depot: &mut Depot) -> Result<String, StatusError> {
+//!     let session = depot.sa_session().await.map_err(|_| StatusError::unauthorized())?;
+//!     Ok(format!("{}: {}", depot.sa_login_id().unwrap_or_default(), session.id))
+//! }
+//!
+//! #[handler]
+//! async fn admin_handler(login_id: LoginIdExtractor) -> String {
+//!     format!("admin {}", login_id.login_id())
+//! }
 //! ```
 
 pub mod adapter;
@@ -61,6 +73,7 @@ pub mod extractor;
 pub mod middleware;
 pub mod layer;
 pub mod state;
+pub mod realtime;
 
 pub use sa_token_core::{self, prelude::*};
 pub use sa_token_adapter::{self, storage::SaStorage, framework::FrameworkAdapter};
@@ -85,4 +98,5 @@ pub use middleware::{
     SaCheckLoginMiddleware, SaCheckPermissionMiddleware, SaCheckRoleMiddleware
 };
 pub use layer::{SaTokenLayer, extract_token_from_request};
-pub use state::{SaTokenState, SaTokenStateBuilder};
\ No newline at end of file
+pub use state::{SaTokenState, SaTokenStateBuilder};
+pub use realtime::SaWsAuthHoop;
\ No newline at end of file
@@ -4,11 +4,12 @@
 // Salvo 认证中间件 | Salvo authentication middleware
 
 use salvo::prelude::*;
-use sa_token_core::{StpUtil, error::messages, SaTokenContext, token::TokenValue};
-use serde_json::json;
+use sa_token_core::{StpUtil, SaTokenError, SaTokenContext, token::TokenValue};
+use crate::adapter::write_error;
 use crate::state::SaTokenState;
 use std::sync::Arc;
 use crate::layer::extract_token_from_request;
+use crate::extractor::store_login;
 
 /// 中文 | English
 /// 认证中间件 - 验证用户登录状态 | Authentication middleware - verify user login status
@@ -55,8 +56,7 @@ async fn auth_middleware_handler(req: &mut Request, res: &mut Response, depot: &
     
     // 中文 | English
     // Token 无效，返回 401 | Token invalid, return 401
-    res.status_code(StatusCode::UNAUTHORIZED);
-    res.render(Text::Json(r#"{"error":"Unauthorized"}"#));
+    write_error(depot, &SaTokenError::NotLogin, res);
     ctrl.skip_rest();
 }
 
@@ -96,8 +96,7 @@ impl PermissionMiddleware {
         
         // 中文 | English
         // 无权限，返回 403 | No permission, return 403
-        res.status_code(StatusCode::FORBIDDEN);
-        res.render(Text::Json(r#"{"error":"Forbidden"}"#));
+        write_error(depot, &SaTokenError::PermissionDenied, res);
         ctrl.skip_rest();
     }
 }
@@ -125,33 +124,27 @@ impl Handler for SaCheckLoginMiddleware {
         let mut ctx = SaTokenContext::new();
         
         if let Some(token_str) = extract_token_from_request(req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "login-check", &token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    depot.insert("sa_token", token.clone());
-                    depot.insert("sa_login_id", login_id.clone());
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
+                store_login(req, depot, &token, &login_id);
                     
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
+                ctx.token = Some(token.clone());
+                ctx.token_info = Some(Arc::new(token_info));
+                ctx.login_id = Some(login_id);
                     
-                    SaTokenContext::set_current(ctx);
-                    ctrl.call_next(req, depot, res).await;
-                    SaTokenContext::clear();
-                    return;
-                }
+                SaTokenContext::set_current(ctx);
+                ctrl.call_next(req, depot, res).await;
+                SaTokenContext::clear();
+                return;
             }
         }
         
         // 未登录，返回401错误
-        res.status_code(StatusCode::UNAUTHORIZED);
-        res.render(Text::Json(json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string()));
+        write_error(depot, &SaTokenError::NotLogin, res);
         ctrl.skip_rest();
     }
 }
@@ -180,37 +173,31 @@ impl Handler for SaCheckPermissionMiddleware {
         let mut ctx = SaTokenContext::new();
         
         if let Some(token_str) = extract_token_from_request(req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "permission-check", &token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
                     
-                    // 检查权限
-                    if StpUtil::has_permission(&login_id, &self.permission).await {
-                        depot.insert("sa_token", token.clone());
-                        depot.insert("sa_login_id", login_id.clone());
+                // 检查权限
+                if StpUtil::has_permission(&login_id, &self.permission).await {
+                    store_login(req, depot, &token, &login_id);
                         
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                         
-                        SaTokenContext::set_current(ctx);
-                        ctrl.call_next(req, depot, res).await;
-                        SaTokenContext::clear();
-                        return;
-                    }
+                    SaTokenContext::set_current(ctx);
+                    ctrl.call_next(req, depot, res).await;
+                    SaTokenContext::clear();
+                    return;
                 }
             }
         }
         
         // 无权限，返回403错误
-        res.status_code(StatusCode::FORBIDDEN);
-        res.render(Text::Json(json!({
-            "code": 403,
-            "message": messages::PERMISSION_REQUIRED
-        }).to_string()));
+        write_error(depot, &SaTokenError::PermissionDenied, res);
         ctrl.skip_rest();
     }
 }
@@ -239,37 +226,31 @@ impl Handler for SaCheckRoleMiddleware {
         let mut ctx = SaTokenContext::new();
         
         if let Some(token_str) = extract_token_from_request(req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "role-check", &token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
                     
-                    // 检查角色
-                    if StpUtil::has_role(&login_id, &self.role).await {
-                        depot.insert("sa_token", token.clone());
-                        depot.insert("sa_login_id", login_id.clone());
+                // 检查角色
+                if StpUtil::has_role(&login_id, &self.role).await {
+                    store_login(req, depot, &token, &login_id);
                         
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                         
-                        SaTokenContext::set_current(ctx);
-                        ctrl.call_next(req, depot, res).await;
-                        SaTokenContext::clear();
-                        return;
-                    }
+                    SaTokenContext::set_current(ctx);
+                    ctrl.call_next(req, depot, res).await;
+                    SaTokenContext::clear();
+                    return;
                 }
             }
         }
         
         // 无角色权限，返回403错误
-        res.status_code(StatusCode::FORBIDDEN);
-        res.render(Text::Json(json!({
-            "code": 403,
-            "message": messages::ROLE_REQUIRED
-        }).to_string()));
+        write_error(depot, &SaTokenError::RoleDenied(self.role.clone()), res);
         ctrl.skip_rest();
     }
 }
\ No newline at end of file
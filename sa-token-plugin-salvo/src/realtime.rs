@@ -0,0 +1,83 @@
+// Author: 金书记
+//
+// 中文 | English
+// Salvo 实时连接认证 | Salvo realtime connection authentication
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use salvo::prelude::*;
+use sa_token_core::{SaTokenError, ws::WsAuthManager};
+use sa_token_adapter::utils::parse_query_string;
+use crate::adapter::write_error;
+use crate::extractor::DEPOT_LOGIN_ID_KEY;
+use crate::state::SaTokenState;
+
+/// 中文 | English
+/// WebSocket / SSE 认证中间件 - 在协议升级前校验 token | WebSocket / SSE auth hoop - validates the token before upgrade
+///
+/// 认证成功后 `WsAuthInfo` 注入 depot，可配合 `until_revoked` 在 token 被吊销时结束连接
+/// On success `WsAuthInfo` is injected into the depot, combine with `until_revoked` to end the connection once the token is revoked
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// use salvo::prelude::*;
+/// use sa_token_plugin_salvo::*;
+///
+/// let hoop = SaWsAuthHoop::new(state.clone());
+/// let router = Router::with_path("ws").hoop(hoop.clone()).goal(ws_handler);
+///
+/// #[handler]
+/// async fn ws_handler(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Result<(), StatusError> {
+///     let auth_info = depot.obtain::<WsAuthInfo>().cloned().map_err(|_| StatusError::unauthorized())?;
+///     let ws_auth = depot.obtain::<Arc<WsAuthManager>>().cloned().map_err(|_| StatusError::unauthorized())?;
+///     WebSocketUpgrade::new().upgrade(req, res, move |ws| async move {
+///         let messages = until_revoked(ws, ws_auth, auth_info.token, DEFAULT_REVOCATION_CHECK_INTERVAL);
+///         // ...
+///     }).await
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SaWsAuthHoop {
+    ws_auth: Arc<WsAuthManager>,
+}
+
+impl SaWsAuthHoop {
+    /// 中文 | English
+    /// 使用默认 token 提取器创建 | Create with the default token extractor
+    pub fn new(state: SaTokenState) -> Self {
+        Self {
+            ws_auth: Arc::new(WsAuthManager::new(state.manager.clone())),
+        }
+    }
+    
+    /// 中文 | English
+    /// 使用自定义的 WebSocket 认证管理器创建 | Create with a custom WebSocket auth manager
+    pub fn with_ws_auth(ws_auth: Arc<WsAuthManager>) -> Self {
+        Self { ws_auth }
+    }
+}
+
+#[salvo::async_trait]
+impl Handler for SaWsAuthHoop {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let headers: HashMap<String, String> = req.headers()
+            .iter()
+            .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
+            .collect();
+        let query = req.uri().query().map(parse_query_string).unwrap_or_default();
+        
+        match self.ws_auth.authenticate(&headers, &query).await {
+            Ok(auth_info) => {
+                depot.insert(DEPOT_LOGIN_ID_KEY, auth_info.login_id.clone());
+                depot.inject(auth_info);
+                depot.inject(self.ws_auth.clone());
+                ctrl.call_next(req, depot, res).await;
+            }
+            Err(e) => {
+                sa_token_core::logging::rejected("realtime", req.uri().path(), 401, &e);
+                write_error(depot, &SaTokenError::NotLogin, res);
+                ctrl.skip_rest();
+            }
+        }
+    }
+}
@@ -6,6 +6,8 @@
 use tide::{Request, Response, StatusCode};
 use sa_token_adapter::{SaRequest, SaResponse, CookieOptions, build_cookie_string};
 use serde::Serialize;
+use std::sync::Arc;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, response::default_error_renderer, token::TokenValue};
 
 /// 中文 | English
 /// Tide 请求适配器 | Tide request adapter
@@ -35,12 +37,11 @@ impl<'a, State> SaRequest for TideRequestAdapter<'a, State> {
         }
         
         // 如果没有找到，手动解析 Cookie 头 | If not found, manually parse Cookie header
-        if let Some(cookie_header) = self.request.header("cookie") {
-            if let Some(cookie_str) = cookie_header.get(0) {
-                let cookies = sa_token_adapter::utils::parse_cookies(cookie_str.as_str());
-                if let Some(value) = cookies.get(name) {
-                    return Some(value.to_string());
-                }
+        if let Some(cookie_header) = self.request.header("cookie")
+            && let Some(cookie_str) = cookie_header.get(0) {
+            let cookies = sa_token_adapter::utils::parse_cookies(cookie_str.as_str());
+            if let Some(value) = cookies.get(name) {
+                return Some(value.to_string());
             }
         }
         
@@ -72,6 +73,12 @@ impl<'a, State> SaRequest for TideRequestAdapter<'a, State> {
     fn get_method(&self) -> String {
         self.request.method().to_string()
     }
+    
+    fn get_client_ip(&self) -> Option<String> {
+        self.request.peer_addr()
+            .and_then(|addr| addr.parse::<std::net::SocketAddr>().ok())
+            .map(|addr| addr.ip().to_string())
+    }
 }
 
 /// 中文 | English
@@ -122,3 +129,45 @@ impl SaResponse for TideResponseAdapter {
     }
 }
 
+/// 中文 | English
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+/// Write a new token into the response: the cookie, plus the `token_name` header when `is_write_header` is on
+pub fn write_token(manager: &SaTokenManager, token: &TokenValue, response: Response) -> Response {
+    append_headers(response, manager.token_response_headers(token))
+}
+
+/// 中文 | English
+/// 登出后清除响应中的 token cookie | Clear the token cookie after logout
+pub fn clear_token(manager: &SaTokenManager, response: Response) -> Response {
+    append_headers(response, manager.logout_response_headers())
+}
+
+/// 中文 | English
+/// 请求所属 `SaTokenState` 的 `ErrorRenderer`（由 `SaTokenLayer` 放入扩展），缺省为 `DefaultErrorRenderer`
+/// The `ErrorRenderer` of the request's `SaTokenState` (set as an extension by `SaTokenLayer`), defaulting to `DefaultErrorRenderer`
+pub fn error_renderer<State>(req: &Request<State>) -> Arc<dyn ErrorRenderer> {
+    req.ext::<Arc<dyn ErrorRenderer>>().cloned().unwrap_or_else(default_error_renderer)
+}
+
+/// 中文 | English
+/// 用给定的 `ErrorRenderer` 把错误渲染为拒绝响应，中间件与提取器共用 | Render an error as a rejection with the given `ErrorRenderer`, shared by the middlewares and extractors
+pub fn error_response(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> Response {
+    rendered_response(renderer.render(error))
+}
+
+/// 由渲染结果（状态码、响应头、响应体）构建响应
+pub(crate) fn rendered_response((status, headers, body): (u16, Vec<(String, String)>, String)) -> Response {
+    let mut response = Response::new(StatusCode::try_from(status).unwrap_or(StatusCode::Unauthorized));
+    response.set_body(body);
+    for (name, value) in headers {
+        response.insert_header(name.as_str(), value);
+    }
+    response
+}
+
+fn append_headers(mut response: Response, headers: Vec<(String, String)>) -> Response {
+    for (name, value) in headers {
+        response.append_header(name.as_str(), value);
+    }
+    response
+}
@@ -1,9 +1,10 @@
-use tide::{Request, Response, StatusCode};
-use sa_token_core::{token::TokenValue, error::messages};
-use serde_json::json;
+use tide::{Request, Response};
+use sa_token_core::{token::TokenValue, error::messages, SaTokenError};
+use sa_token_core::response::error_json;
+use crate::adapter::{error_renderer, error_response};
 
 /// 中文: 认证错误 | English: Authentication error
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct AuthError;
 
 impl AuthError {
@@ -19,18 +20,12 @@ impl AuthError {
     
     /// 中文: 转换为 JSON 字符串 | English: Convert to JSON string
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 401,
-            "message": self.message()
-        }).to_string()
+        error_json(401, self.message()).to_string()
     }
     
-    /// 中文: 转换为 Response | English: Convert to Response
-    pub fn to_response(&self) -> Response {
-        let mut res = Response::new(StatusCode::Unauthorized);
-        res.set_body(self.to_json());
-        res.set_content_type("application/json");
-        res
+    /// 中文: 用请求所属状态的渲染器转换为 Response | English: Convert to a Response with the renderer of the request's state
+    pub fn to_response<State>(&self, req: &Request<State>) -> Response {
+        error_response(error_renderer(req).as_ref(), &SaTokenError::NotLogin)
     }
 }
 
@@ -1,5 +1,6 @@
 use tide::{Middleware, Request, Result, Next};
-use sa_token_core::{token::TokenValue, SaTokenContext};
+use sa_token_core::{token::TokenValue, RememberedSession, SaTokenContext, StpUtil};
+use sa_token_adapter::context::SaRequest;
 use std::sync::Arc;
 use crate::state::SaTokenState;
 use sa_token_adapter::utils::{parse_cookies, parse_query_string, extract_bearer_token as utils_extract_bearer_token};
@@ -33,48 +34,90 @@ impl SaTokenLayer {
 #[tide::utils::async_trait]
 impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaTokenLayer {
     async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> Result {
-        if let Some(config) = &self.path_config {
-            let path = req.url().path();
-            let token_str = extract_token_from_request(&req, &self.state.manager.config.token_name);
-            let result = sa_token_core::router::process_auth(path, token_str, config, &self.state.manager).await;
-            
-            if result.should_reject() {
-                return Ok(tide::Response::builder(tide::StatusCode::Unauthorized).build());
+        // 整个请求在所属租户中处理 | The whole request is handled within its tenant
+        let tenant = self.state.manager.config.tenant(&crate::adapter::TideRequestAdapter::new(&req));
+        // 之后的中间件与提取器用本状态的渲染器生成拒绝响应 | Later middlewares and extractors render rejections with this state's renderer
+        req.set_ext(self.state.error_renderer.clone());
+        
+        sa_token_core::tenant::scope(tenant, async move {
+            let mut token_str = extract_token_from_request(&req, &self.state.manager.config.token_name);
+            // 会话失效时用 remember-me cookie 静默重建 | Silently re-create an expired session from the remember-me cookie
+            let remember = crate::adapter::TideRequestAdapter::new(&req).get_cookie(&self.state.manager.config.remember_me_cookie);
+            let remembered = self.state.manager.restore_remembered(token_str.as_deref(), remember.as_deref()).await;
+            if let Some(session) = &remembered {
+                token_str = Some(session.token.as_str().to_string());
             }
+        
+            if let Some(config) = &self.path_config {
+                let path = req.url().path();
+                let method = req.method().to_string();
+                let header_version = config.version_header_name()
+                    .and_then(|name| req.header(name))
+                    .map(|v| v.as_str());
+                let client_ip = config.client_ip(
+                    config.client_ip_header_name()
+                        .and_then(|name| req.header(name))
+                        .map(|v| v.as_str()),
+                    self.state.manager.config.client_ip(&crate::adapter::TideRequestAdapter::new(&req)),
+                );
+                let fingerprint = self.state.manager.config.fingerprint(&crate::adapter::TideRequestAdapter::new(&req));
+                let result = sa_token_core::router::process_auth_request(path, Some(method.as_str()), header_version, client_ip.as_deref(), fingerprint.as_deref(), token_str, config, &self.state.manager).await;
             
-            let ctx = sa_token_core::router::create_context(&result);
-            SaTokenContext::set_current(ctx);
-            let response = next.run(req).await;
-            SaTokenContext::clear();
-            return Ok(response);
-        }
+                if result.should_reject() {
+                    return Ok(crate::adapter::rendered_response(config.render_rejection_response(&result, self.state.error_renderer.as_ref())));
+                }
+            
+                let mut ctx = sa_token_core::router::create_context(&result);
+                ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::TideRequestAdapter::new(&req));
+                SaTokenContext::set_current(ctx);
+                let response = next.run(req).await;
+                SaTokenContext::clear();
+                return Ok(append_set_cookies(response, remembered));
+            }
         
-        // No path auth config, use default token extraction and validation
-        // 没有路径鉴权配置，使用默认的 token 提取和验证
-        let mut ctx = SaTokenContext::new();
-        if let Some(token_str) = extract_token_from_request(&req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token: extracted token from request: {}", token_str);
-            let token = TokenValue::new(token_str);
+            // No path auth config, use default token extraction and validation
+            // 没有路径鉴权配置，使用默认的 token 提取和验证
+            let mut ctx = SaTokenContext::new();
+            let client_ip = self.state.manager.config.client_ip(&crate::adapter::TideRequestAdapter::new(&req));
+            let fingerprint = self.state.manager.config.fingerprint(&crate::adapter::TideRequestAdapter::new(&req));
+            if let Some(token_str) = token_str {
+                sa_token_core::logging::token_extracted(&self.state.manager.config, "layer", &token_str);
+                let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                req.set_ext(token.clone());
-                
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                // 中文 | English
+                // 绑定到其他 IP 或设备的 token 视为未登录 | A token bound to another IP counts as not logged in
+                if self.state.manager.is_valid(&token).await
+                    && let Ok(token_info) = self.state.manager.get_token_info(&token).await
+                    && self.state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
                     let login_id = token_info.login_id.clone();
+                    self.state.manager.record_activity(&token_info, client_ip.as_deref()).await;
+                    req.set_ext(token.clone());
                     req.set_ext(login_id.clone());
-                    
+                
                     ctx.token = Some(token.clone());
                     ctx.token_info = Some(Arc::new(token_info));
                     ctx.login_id = Some(login_id);
                 }
             }
-        }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+            ctx.typed_tokens = StpUtil::collect_typed_tokens(&crate::adapter::TideRequestAdapter::new(&req));
         
-        SaTokenContext::set_current(ctx);
-        let result = next.run(req).await;
-        SaTokenContext::clear();
-        Ok(result)
+            SaTokenContext::set_current(ctx);
+            let result = next.run(req).await;
+            SaTokenContext::clear();
+            Ok(append_set_cookies(result, remembered))
+        }).await
+    }
+}
+
+/// 中文 | English
+/// 把 remember-me 重建会话产生的 cookie 写入响应 | Write the cookies of a session re-created from remember-me
+fn append_set_cookies(mut response: tide::Response, remembered: Option<RememberedSession>) -> tide::Response {
+    for cookie in remembered.into_iter().flat_map(|session| session.set_cookies) {
+        response.append_header("Set-Cookie", cookie);
     }
+    response
 }
 
 /// 中文 | English
@@ -86,48 +129,41 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaTokenLayer {
 /// 3. 从 Cookie | From cookie
 /// 4. 从查询参数 | From query parameter
 pub fn extract_token_from_request<State>(req: &Request<State>, token_name: &str) -> Option<String> {
-    if let Some(header_value) = req.header(token_name) {
-        if let Some(value_str) = header_value.get(0) {
-            let value_str = value_str.as_str();
-            if !value_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(value_str) {
-                    return Some(token);
-                }
-            }
+    if let Some(header_value) = req.header(token_name)
+        && let Some(value_str) = header_value.get(0) {
+        let value_str = value_str.as_str();
+        if !value_str.is_empty()
+            && let Some(token) = utils_extract_bearer_token(value_str) {
+            return Some(token);
         }
     }
     
     // 2. 从 Authorization 请求头提取 | Extract from Authorization header
-    if let Some(auth_header) = req.header("authorization") {
-        if let Some(auth_str) = auth_header.get(0) {
-            let auth_str = auth_str.as_str();
-            if !auth_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(auth_str) {
-                    return Some(token);
-                }
-            }
+    if let Some(auth_header) = req.header("authorization")
+        && let Some(auth_str) = auth_header.get(0) {
+        let auth_str = auth_str.as_str();
+        if !auth_str.is_empty()
+            && let Some(token) = utils_extract_bearer_token(auth_str) {
+            return Some(token);
         }
     }
     
     // 3. 从 Cookie 提取 | Extract from cookie
-    if let Some(cookie_header) = req.header("cookie") {
-        if let Some(cookie_str) = cookie_header.get(0) {
-            let cookies = parse_cookies(cookie_str.as_str());
-            if let Some(token) = cookies.get(token_name) {
-                if !token.is_empty() {
-                    return Some(token.to_string());
-                }
-            }
+    if let Some(cookie_header) = req.header("cookie")
+        && let Some(cookie_str) = cookie_header.get(0) {
+        let cookies = parse_cookies(cookie_str.as_str());
+        if let Some(token) = cookies.get(token_name)
+            && !token.is_empty() {
+            return Some(token.to_string());
         }
     }
     
     // 4. 从查询参数提取 | Extract from query parameter
     if let Some(query) = req.url().query() {
         let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            if !token.is_empty() {
-                return Some(token.to_string());
-            }
+        if let Some(token) = params.get(token_name)
+            && !token.is_empty() {
+            return Some(token.to_string());
         }
     }
     
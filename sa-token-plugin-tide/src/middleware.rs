@@ -3,13 +3,13 @@
 // 中文 | English
 // Tide 认证中间件 | Tide authentication middleware
 
-use tide::{Middleware, Request, Response, Next, StatusCode};
-use sa_token_core::{StpUtil, error::messages, SaTokenContext, token::TokenValue};
+use tide::{Middleware, Request, Next};
+use sa_token_core::{StpUtil, SaTokenError, SaTokenContext, token::TokenValue};
 use async_trait::async_trait;
 use crate::state::SaTokenState;
 use crate::layer::extract_token_from_request;
+use crate::adapter::{error_renderer, error_response};
 use std::sync::Arc;
-use serde_json::json;
 
 /// 中文 | English
 /// 认证中间件 - 验证用户登录状态 | Authentication middleware - verify user login status
@@ -53,10 +53,7 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for AuthMiddleware
         
         // 中文 | English
         // Token 无效，返回 401 | Token invalid, return 401
-        let mut res = Response::new(StatusCode::Unauthorized);
-        res.set_body(r#"{"error":"Unauthorized"}"#);
-        res.set_content_type("application/json");
-        Ok(res)
+        Ok(error_response(error_renderer(&req).as_ref(), &SaTokenError::NotLogin))
     }
 }
 
@@ -98,10 +95,7 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for PermissionMiddl
         
         // 中文 | English
         // 无权限，返回 403 | No permission, return 403
-        let mut res = Response::new(StatusCode::Forbidden);
-        res.set_body(r#"{"error":"Forbidden"}"#);
-        res.set_content_type("application/json");
-        Ok(res)
+        Ok(error_response(error_renderer(&req).as_ref(), &SaTokenError::PermissionDenied))
     }
 }
 
@@ -128,35 +122,28 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaCheckLoginMid
         let mut ctx = SaTokenContext::new();
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token(login-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "login-check", &token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
-                    req.set_ext(token.clone());
-                    req.set_ext(login_id.clone());
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
+                req.set_ext(token.clone());
+                req.set_ext(login_id.clone());
                     
-                    ctx.token = Some(token.clone());
-                    ctx.token_info = Some(Arc::new(token_info));
-                    ctx.login_id = Some(login_id);
+                ctx.token = Some(token.clone());
+                ctx.token_info = Some(Arc::new(token_info));
+                ctx.login_id = Some(login_id);
                     
-                    SaTokenContext::set_current(ctx);
-                    let result = next.run(req).await;
-                    SaTokenContext::clear();
-                    return Ok(result);
-                }
+                SaTokenContext::set_current(ctx);
+                let result = next.run(req).await;
+                SaTokenContext::clear();
+                return Ok(result);
             }
         }
         
         // 未登录，返回401错误
-        let mut res = Response::new(StatusCode::Unauthorized);
-        res.set_body(json!({
-            "code": 401,
-            "message": messages::AUTH_ERROR
-        }).to_string());
-        res.set_content_type("application/json");
-        Ok(res)
+        Ok(error_response(error_renderer(&req).as_ref(), &SaTokenError::NotLogin))
     }
 }
 
@@ -184,39 +171,32 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaCheckPermissi
         let mut ctx = SaTokenContext::new();
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token(permission-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "permission-check", &token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
                     
-                    // 检查权限
-                    if StpUtil::has_permission(&login_id, &self.permission).await {
-                        req.set_ext(token.clone());
-                        req.set_ext(login_id.clone());
+                // 检查权限
+                if StpUtil::has_permission(&login_id, &self.permission).await {
+                    req.set_ext(token.clone());
+                    req.set_ext(login_id.clone());
                         
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                         
-                        SaTokenContext::set_current(ctx);
-                        let result = next.run(req).await;
-                        SaTokenContext::clear();
-                        return Ok(result);
-                    }
+                    SaTokenContext::set_current(ctx);
+                    let result = next.run(req).await;
+                    SaTokenContext::clear();
+                    return Ok(result);
                 }
             }
         }
         
         // 无权限，返回403错误
-        let mut res = Response::new(StatusCode::Forbidden);
-        res.set_body(json!({
-            "code": 403,
-            "message": messages::PERMISSION_REQUIRED
-        }).to_string());
-        res.set_content_type("application/json");
-        Ok(res)
+        Ok(error_response(error_renderer(&req).as_ref(), &SaTokenError::PermissionDenied))
     }
 }
 
@@ -244,38 +224,31 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for SaCheckRoleMidd
         let mut ctx = SaTokenContext::new();
         
         if let Some(token_str) = extract_token_from_request(&req, &self.state.manager.config.token_name) {
-            tracing::debug!("Sa-Token(role-check): extracted token from request: {}", token_str);
+            sa_token_core::logging::token_extracted(&self.state.manager.config, "role-check", &token_str);
             let token = TokenValue::new(token_str);
             
-            if self.state.manager.is_valid(&token).await {
-                if let Ok(token_info) = self.state.manager.get_token_info(&token).await {
-                    let login_id = token_info.login_id.clone();
+            if self.state.manager.is_valid(&token).await
+                && let Ok(token_info) = self.state.manager.get_token_info(&token).await {
+                let login_id = token_info.login_id.clone();
                     
-                    // 检查角色
-                    if StpUtil::has_role(&login_id, &self.role).await {
-                        req.set_ext(token.clone());
-                        req.set_ext(login_id.clone());
+                // 检查角色
+                if StpUtil::has_role(&login_id, &self.role).await {
+                    req.set_ext(token.clone());
+                    req.set_ext(login_id.clone());
                         
-                        ctx.token = Some(token.clone());
-                        ctx.token_info = Some(Arc::new(token_info));
-                        ctx.login_id = Some(login_id);
+                    ctx.token = Some(token.clone());
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
                         
-                        SaTokenContext::set_current(ctx);
-                        let result = next.run(req).await;
-                        SaTokenContext::clear();
-                        return Ok(result);
-                    }
+                    SaTokenContext::set_current(ctx);
+                    let result = next.run(req).await;
+                    SaTokenContext::clear();
+                    return Ok(result);
                 }
             }
         }
         
         // 无角色权限，返回403错误
-        let mut res = Response::new(StatusCode::Forbidden);
-        res.set_body(json!({
-            "code": 403,
-            "message": messages::ROLE_REQUIRED
-        }).to_string());
-        res.set_content_type("application/json");
-        Ok(res)
+        Ok(error_response(error_renderer(&req).as_ref(), &SaTokenError::RoleDenied(self.role.clone())))
     }
 }
\ No newline at end of file
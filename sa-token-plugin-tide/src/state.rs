@@ -1,6 +1,7 @@
 use std::sync::Arc;
+use std::collections::HashMap;
 use sa_token_adapter::storage::SaStorage;
-use sa_token_core::{SaTokenManager, SaTokenConfig, StpUtil, SaTokenListener};
+use sa_token_core::{SaTokenManager, SaTokenConfig, StpLogic, StpUtil, SaTokenListener};
 
 /// 中文 | English
 /// Sa-Token 状态 | Sa-Token State
@@ -9,14 +10,23 @@ use sa_token_core::{SaTokenManager, SaTokenConfig, StpUtil, SaTokenListener};
 #[derive(Clone)]
 pub struct SaTokenState {
     pub manager: Arc<SaTokenManager>,
+    
+    /// 本状态的中间件与提取器使用的拒绝响应渲染器 | Rejection renderer of this state's middlewares and extractors
+    pub error_renderer: Arc<dyn sa_token_core::ErrorRenderer>,
+    
+    /// 其他账号体系，按账号类型索引 | Other account systems keyed by account type
+    pub logics: HashMap<String, Arc<StpLogic>>,
 }
 
 impl SaTokenState {
     /// 中文 | English
     /// 从存储和配置创建状态 | Create state from storage and config
     pub fn new(storage: Arc<dyn SaStorage>, config: SaTokenConfig) -> Self {
+        let manager = SaTokenManager::new(storage, config);
         Self {
-            manager: Arc::new(SaTokenManager::new(storage, config)),
+            error_renderer: manager.error_renderer(),
+            manager: Arc::new(manager),
+            logics: HashMap::new(),
         }
     }
     
@@ -27,7 +37,9 @@ impl SaTokenState {
         StpUtil::init_manager(manager.clone());
         
         Self {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: HashMap::new(),
         }
     }
     
@@ -36,6 +48,11 @@ impl SaTokenState {
     pub fn builder() -> SaTokenStateBuilder {
         SaTokenStateBuilder::default()
     }
+    
+    /// 获取指定账号类型的账号体系 | Get the account system of an account type
+    pub fn logic(&self, login_type: &str) -> Option<&Arc<StpLogic>> {
+        self.logics.get(login_type)
+    }
 }
 
 /// 中文 | English
@@ -43,6 +60,7 @@ impl SaTokenState {
 #[derive(Default)]
 pub struct SaTokenStateBuilder {
     config_builder: sa_token_core::config::SaTokenConfigBuilder,
+    logics: Vec<StpLogic>,
     listeners: Vec<Arc<dyn SaTokenListener>>,
 }
 
@@ -137,6 +155,18 @@ impl SaTokenStateBuilder {
         self
     }
     
+    /// 设置本状态的拒绝响应渲染器，`SaTokenLayer` 把它交给之后的中间件与提取器 | Set this state's rejection renderer, handed by `SaTokenLayer` to the middlewares and extractors after it
+    pub fn error_renderer(mut self, renderer: Arc<dyn sa_token_core::ErrorRenderer>) -> Self {
+        self.config_builder = self.config_builder.error_renderer(renderer);
+        self
+    }
+    
+    /// 添加账号体系（如 admin），构建时注册到 StpUtil | Add an account system (e.g. admin), registered with StpUtil on build
+    pub fn logic(mut self, logic: StpLogic) -> Self {
+        self.logics.push(logic);
+        self
+    }
+    
     /// 中文 | English
     /// 构建 SaTokenState | Build SaTokenState
     pub fn build(self) -> SaTokenState {
@@ -152,7 +182,9 @@ impl SaTokenStateBuilder {
         // 直接创建 SaTokenState，不再调用 from_manager 避免重复初始化
         // Create SaTokenState directly, don't call from_manager to avoid duplicate initialization
         SaTokenState {
+            error_renderer: manager.error_renderer(),
             manager: Arc::new(manager),
+            logics: StpUtil::register_logics(self.logics),
         }
     }
 }
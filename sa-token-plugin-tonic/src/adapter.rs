@@ -0,0 +1,47 @@
+// Author: 金书记
+//
+//! gRPC 请求适配器 | gRPC request adapter
+//!
+//! gRPC 元数据就是 HTTP/2 请求头，这里直接读取 `http::Request` 的请求头
+//! gRPC metadata are HTTP/2 headers, so this reads the headers of the `http::Request`
+
+use http::Request;
+use sa_token_adapter::context::SaRequest;
+
+/// gRPC 请求适配器 | gRPC request adapter
+pub struct TonicRequestAdapter<'a, T> {
+    request: &'a Request<T>,
+}
+
+impl<'a, T> TonicRequestAdapter<'a, T> {
+    pub fn new(request: &'a Request<T>) -> Self {
+        Self { request }
+    }
+}
+
+impl<'a, T> SaRequest for TonicRequestAdapter<'a, T> {
+    fn get_header(&self, name: &str) -> Option<String> {
+        self.request.headers().get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    }
+
+    /// gRPC 不使用 Cookie | gRPC carries no cookies
+    fn get_cookie(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// gRPC 不使用查询参数 | gRPC carries no query parameters
+    fn get_param(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// gRPC 方法路径，例如 `/helloworld.Greeter/SayHello` | gRPC method path, e.g. `/helloworld.Greeter/SayHello`
+    fn get_path(&self) -> String {
+        self.request.uri().path().to_string()
+    }
+
+    fn get_method(&self) -> String {
+        self.request.method().to_string()
+    }
+}
@@ -0,0 +1,134 @@
+// Author: 金书记
+//
+//! gRPC 认证层 | gRPC authentication layer
+//!
+//! 从元数据中提取并校验 token，把登录信息放入请求扩展，不拦截请求；
+//! 需要拦截时在其后挂上 `SaCheckLoginLayer` 或 `SaMethodAuthLayer`
+//! Extracts and validates the token from the metadata and puts the login into the request
+//! extensions without rejecting anything; add `SaCheckLoginLayer` or `SaMethodAuthLayer` to reject
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use http::{Request, Response};
+use sa_token_adapter::context::SaRequest;
+use sa_token_core::{token::TokenValue, SaTokenContext};
+use crate::{SaTokenState, adapter::TonicRequestAdapter};
+
+/// gRPC Sa-Token 层 | Sa-Token layer for gRPC
+#[derive(Clone)]
+pub struct SaTokenLayer {
+    state: SaTokenState,
+}
+
+impl SaTokenLayer {
+    pub fn new(state: SaTokenState) -> Self {
+        Self { state }
+    }
+}
+
+impl<S> Layer<S> for SaTokenLayer {
+    type Service = SaTokenMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaTokenMiddleware {
+            inner,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// gRPC Sa-Token 中间件 | Sa-Token middleware for gRPC
+///
+/// token 有效时向请求扩展写入 `TokenValue`、登录 ID（`String`）与 `TokenInfo`，
+/// tonic 处理函数通过 `request.extensions()` 读取
+/// For a valid token writes `TokenValue`, the login ID (`String`) and `TokenInfo` into the
+/// request extensions, which tonic handlers read through `request.extensions()`
+#[derive(Clone)]
+pub struct SaTokenMiddleware<S> {
+    inner: S,
+    state: SaTokenState,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaTokenMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let state = self.state.clone();
+        // 整个调用在所属租户中处理 | The whole call is handled within its tenant
+        let tenant = state.manager.config.tenant(&TonicRequestAdapter::new(&request));
+
+        Box::pin(sa_token_core::tenant::scope(tenant, async move {
+            let mut ctx = SaTokenContext::new();
+            let client_ip = state.manager.config.client_ip(&TonicRequestAdapter::new(&request));
+            let fingerprint = state.manager.config.fingerprint(&TonicRequestAdapter::new(&request));
+            if let Some(token_str) = extract_token_from_request(&request, &state) {
+                let token = TokenValue::new(token_str);
+                // 绑定到其他 IP 或设备的 token 视为未登录
+                if state.manager.is_valid(&token).await
+                    && let Ok(token_info) = state.manager.get_token_info(&token).await
+                    && state.manager.check_binding(&token_info, client_ip.as_deref(), fingerprint.as_deref()).is_ok() {
+                    let login_id = token_info.login_id.clone();
+                    state.manager.record_activity(&token_info, client_ip.as_deref()).await;
+                    request.extensions_mut().insert(token.clone());
+                    request.extensions_mut().insert(login_id.clone());
+                    request.extensions_mut().insert(token_info.clone());
+                    ctx.token = Some(token);
+                    ctx.token_info = Some(Arc::new(token_info));
+                    ctx.login_id = Some(login_id);
+                }
+            }
+            ctx.client_ip = client_ip;
+            ctx.fingerprint = fingerprint;
+
+            SaTokenContext::set_current(ctx);
+            let response = inner.call(request).await;
+            SaTokenContext::clear();
+            response
+        }))
+    }
+}
+
+/// 从 gRPC 元数据中提取 Token
+///
+/// 按优先级顺序查找 Token：
+/// 1. 元数据 `<token_name>: <token>` 或 `<token_name>: Bearer <token>`
+/// 2. 元数据 `authorization: <token>` 或 `authorization: Bearer <token>`
+///
+/// 元数据键不区分大小写，客户端按 gRPC 约定发送小写键即可
+pub fn extract_token_from_request<T>(request: &Request<T>, state: &SaTokenState) -> Option<String> {
+    let adapter = TonicRequestAdapter::new(request);
+    let token_name = &state.manager.config.token_name;
+
+    if let Some(token) = adapter.get_header(token_name) {
+        return Some(extract_bearer_token(&token));
+    }
+
+    if !token_name.eq_ignore_ascii_case("authorization")
+        && let Some(token) = adapter.get_header("authorization") {
+        return Some(extract_bearer_token(&token));
+    }
+
+    None
+}
+
+/// 提取 Bearer Token，没有 `Bearer ` 前缀时原样返回
+fn extract_bearer_token(header_value: &str) -> String {
+    match header_value.strip_prefix("Bearer ") {
+        Some(stripped) => stripped.trim().to_string(),
+        None => header_value.trim().to_string(),
+    }
+}
@@ -0,0 +1,80 @@
+// Author: 金书记
+//
+// 中文 | English
+// Tonic (gRPC) 集成 | Tonic (gRPC) Integration
+//
+//! # sa-token-plugin-tonic
+//!
+//! 为 Tonic gRPC 服务提供 sa-token 认证和授权支持
+//! Provides sa-token authentication and authorization support for Tonic gRPC services
+//!
+//! tonic 服务本身就是 tower 服务，这里的层直接挂在 `Server::builder().layer(...)` 上。
+//! token 从 gRPC 元数据（`token_name` 或 `authorization: Bearer <token>`）中读取，
+//! 校验失败时返回 `UNAUTHENTICATED`，权限不足时返回 `PERMISSION_DENIED`。
+//! Tonic services are tower services, so these layers plug straight into `Server::builder().layer(...)`.
+//! The token is read from the gRPC metadata (`token_name` or `authorization: Bearer <token>`);
+//! failed checks answer `UNAUTHENTICATED`, missing permissions answer `PERMISSION_DENIED`.
+//!
+//! ## 快速开始 | Quick Start
+//!
+//! ```toml
+//! [dependencies]
+//! sa-token-plugin-tonic = "0.1.12"
+//! ```
+//!
+//! ```rust,ignore
+//! use sa_token_plugin_tonic::*;
+//! use std::sync::Arc;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let state = SaTokenState::builder()
+//!         .storage(Arc::new(MemoryStorage::new()))
+//!         .timeout(7200)
+//!         .build();
+//!
+//!     let auth = SaMethodAuthLayer::new()
+//!         .require_login("/helloworld.Greeter/*")
+//!         .require_permission("/admin.Admin/*", "admin");
+//!
+//!     tonic::transport::Server::builder()
+//!         .layer(SaTokenLayer::new(state.clone()))
+//!         .layer(auth)
+//!         .add_service(GreeterServer::new(MyGreeter::default()))
+//!         .serve("[::1]:50051".parse()?)
+//!         .await?;
+//!     Ok(())
+//! }
+//!
+//! // 处理函数从请求扩展中取出登录 ID | Handlers read the login ID from the request extensions
+//! async fn say_hello(&self, request: Request<HelloRequest>) -> Result<Response<HelloReply>, Status> {
+//!     let login_id = request.extensions().get::<String>().cloned().unwrap_or_default();
+//!     ...
+//! }
+//! ```
+
+pub mod adapter;
+pub mod layer;
+pub mod middleware;
+pub mod state;
+
+pub use sa_token_core::{self, prelude::*};
+pub use sa_token_adapter::{self, storage::SaStorage, framework::FrameworkAdapter};
+pub use sa_token_macro::*;
+
+// 重新导出存储实现（通过 feature 控制）
+// Re-export storage implementations (controlled by features)
+#[cfg(feature = "memory")]
+pub use sa_token_storage_memory::*;
+
+#[cfg(feature = "redis")]
+pub use sa_token_storage_redis::*;
+
+#[cfg(feature = "database")]
+pub use sa_token_storage_database::*;
+
+// 重新导出本模块的适配器 | Re-export adapters from this module
+pub use adapter::TonicRequestAdapter;
+pub use layer::{SaTokenLayer, SaTokenMiddleware, extract_token_from_request};
+pub use middleware::{grpc_status_response, GrpcCode, SaCheckLoginLayer, SaCheckLoginMiddleware, SaMethodAuthLayer, SaMethodAuthMiddleware};
+pub use state::{SaTokenState, SaTokenStateBuilder};
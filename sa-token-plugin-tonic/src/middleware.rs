@@ -0,0 +1,256 @@
+// Author: 金书记
+//
+//! gRPC 拦截中间件 | gRPC rejecting middlewares
+//!
+//! - `SaCheckLoginMiddleware`：未登录时返回 `UNAUTHENTICATED`
+//! - `SaMethodAuthMiddleware`：按服务方法要求登录或权限，未登录返回 `UNAUTHENTICATED`，权限不足返回 `PERMISSION_DENIED`
+//!
+//! 两者都依赖外层的 `SaTokenLayer` 写入的登录 ID
+//! Both rely on the login ID written by an outer `SaTokenLayer`
+
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use http::{Request, Response, HeaderValue};
+use sa_token_core::error::messages;
+
+/// 本插件返回的 gRPC 状态码 | gRPC status codes returned by this plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrpcCode {
+    /// 权限不足 | Permission denied
+    PermissionDenied = 7,
+    /// 未认证 | Unauthenticated
+    Unauthenticated = 16,
+}
+
+/// 构造 gRPC 错误响应（trailers-only）| Build a trailers-only gRPC error response
+///
+/// HTTP 状态为 200，状态码与消息放在 `grpc-status` 与 `grpc-message` 中，tonic 客户端会还原为 `Status`
+/// The HTTP status is 200; code and message travel in `grpc-status` and `grpc-message`,
+/// which tonic clients turn back into a `Status`
+pub fn grpc_status_response<B: Default>(code: GrpcCode, message: &str) -> Response<B> {
+    let mut response = Response::new(B::default());
+    let headers = response.headers_mut();
+    headers.insert(http::header::CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+    headers.insert("grpc-status", HeaderValue::from(code as u16));
+    if let Ok(value) = HeaderValue::from_str(&urlencoding::encode(message)) {
+        headers.insert("grpc-message", value);
+    }
+    response
+}
+
+/// 检查登录中间件层
+#[derive(Clone, Default)]
+pub struct SaCheckLoginLayer;
+
+impl SaCheckLoginLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for SaCheckLoginLayer {
+    type Service = SaCheckLoginMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaCheckLoginMiddleware { inner }
+    }
+}
+
+/// 检查登录中间件
+///
+/// 所有方法都要求登录，未登录时返回 `UNAUTHENTICATED`
+#[derive(Clone)]
+pub struct SaCheckLoginMiddleware<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaCheckLoginMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            if request.extensions().get::<String>().is_none() {
+                return Ok(grpc_status_response(GrpcCode::Unauthenticated, messages::AUTH_ERROR));
+            }
+            inner.call(request).await
+        })
+    }
+}
+
+/// 单条方法规则 | A single method rule
+#[derive(Debug, Clone)]
+struct MethodRule {
+    /// 方法路径：`/pkg.Service/Method`、`/pkg.Service/*` 或 `*`
+    pattern: String,
+    /// 需要的权限，`None` 表示只要求登录
+    permission: Option<String>,
+}
+
+impl MethodRule {
+    fn matches(&self, path: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.pattern,
+        }
+    }
+}
+
+/// 按服务方法鉴权的中间件层 | Per-method authorization layer
+///
+/// 方法路径形如 `/helloworld.Greeter/SayHello`，可用 `/helloworld.Greeter/*` 匹配整个服务、`*` 匹配全部方法。
+/// 一个方法匹配的所有规则都要满足；没有匹配规则的方法直接放行。
+/// Method paths look like `/helloworld.Greeter/SayHello`; `/helloworld.Greeter/*` matches a whole
+/// service and `*` every method. All rules matching a method must pass; methods without rules pass through.
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let auth = SaMethodAuthLayer::new()
+///     .require_login("/helloworld.Greeter/*")
+///     .require_permission("/admin.Admin/DeleteUser", "user:delete");
+/// ```
+#[derive(Clone, Default)]
+pub struct SaMethodAuthLayer {
+    rules: Vec<MethodRule>,
+}
+
+impl SaMethodAuthLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 要求匹配的方法已登录 | Require a login for matching methods
+    pub fn require_login(mut self, method: impl Into<String>) -> Self {
+        self.rules.push(MethodRule { pattern: method.into(), permission: None });
+        self
+    }
+
+    /// 要求匹配的方法拥有指定权限 | Require a permission for matching methods
+    pub fn require_permission(mut self, method: impl Into<String>, permission: impl Into<String>) -> Self {
+        self.rules.push(MethodRule { pattern: method.into(), permission: Some(permission.into()) });
+        self
+    }
+}
+
+impl<S> Layer<S> for SaMethodAuthLayer {
+    type Service = SaMethodAuthMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SaMethodAuthMiddleware {
+            inner,
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+/// 按服务方法鉴权的中间件 | Per-method authorization middleware
+#[derive(Clone)]
+pub struct SaMethodAuthMiddleware<S> {
+    inner: S,
+    rules: Vec<MethodRule>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for SaMethodAuthMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let path = request.uri().path().to_string();
+        let rules: Vec<MethodRule> = self.rules.iter()
+            .filter(|rule| rule.matches(&path))
+            .cloned()
+            .collect();
+
+        Box::pin(async move {
+            if rules.is_empty() {
+                return inner.call(request).await;
+            }
+            let Some(login_id) = request.extensions().get::<String>().cloned() else {
+                return Ok(grpc_status_response(GrpcCode::Unauthenticated, messages::AUTH_ERROR));
+            };
+            for permission in rules.iter().filter_map(|rule| rule.permission.as_deref()) {
+                if !sa_token_core::StpUtil::has_permission(&login_id, permission).await {
+                    return Ok(grpc_status_response(GrpcCode::PermissionDenied, messages::PERMISSION_REQUIRED));
+                }
+            }
+            inner.call(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use tower::{ServiceBuilder, ServiceExt, service_fn};
+    use crate::{MemoryStorage, SaTokenLayer, SaTokenState, StpUtil};
+
+    fn grpc_status<B>(response: &Response<B>) -> Option<&str> {
+        response.headers().get("grpc-status").and_then(|v| v.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn test_method_auth() {
+        let state = SaTokenState::builder()
+            .storage(Arc::new(MemoryStorage::new()))
+            .build();
+        let service = ServiceBuilder::new()
+            .layer(SaTokenLayer::new(state))
+            .layer(SaMethodAuthLayer::new()
+                .require_login("/helloworld.Greeter/*")
+                .require_permission("/admin.Admin/DeleteUser", "user:delete"))
+            .service(service_fn(|request: Request<()>| async move {
+                let login_id = request.extensions().get::<String>().cloned().unwrap_or_default();
+                Ok::<_, Infallible>(Response::new(login_id))
+            }));
+        let call = |path: &str, token: Option<&str>| {
+            let mut request = Request::post(path);
+            if let Some(token) = token {
+                request = request.header("authorization", format!("Bearer {}", token));
+            }
+            service.clone().oneshot(request.body(()).unwrap())
+        };
+
+        let response = call("/health.Health/Check", None).await.unwrap();
+        assert_eq!(grpc_status(&response), None);
+        let response = call("/helloworld.Greeter/SayHello", None).await.unwrap();
+        assert_eq!(grpc_status(&response), Some("16"));
+
+        let token = StpUtil::login("10001").await.unwrap();
+        let response = call("/helloworld.Greeter/SayHello", Some(token.as_str())).await.unwrap();
+        assert_eq!(grpc_status(&response), None);
+        assert_eq!(response.body(), "10001");
+
+        let response = call("/admin.Admin/DeleteUser", Some(token.as_str())).await.unwrap();
+        assert_eq!(grpc_status(&response), Some("7"));
+        StpUtil::set_permissions("10001", vec!["user:delete".to_string()]).await.unwrap();
+        let response = call("/admin.Admin/DeleteUser", Some(token.as_str())).await.unwrap();
+        assert_eq!(grpc_status(&response), None);
+    }
+}
@@ -7,6 +7,7 @@ use warp::http::{HeaderMap, Response, StatusCode};
 use warp::hyper::body::Bytes;
 use sa_token_adapter::{SaRequest, SaResponse, CookieOptions, build_cookie_string, utils};
 use serde::Serialize;
+use sa_token_core::{ErrorRenderer, SaTokenError, SaTokenManager, token::TokenValue};
 
 /// 中文 | English
 /// Warp 请求适配器 | Warp request adapter
@@ -32,11 +33,10 @@ impl<'a> SaRequest for WarpRequestAdapter<'a> {
     
     fn get_cookie(&self, name: &str) -> Option<String> {
         // Warp 中 Cookie 通常从 Header 中解析 | In Warp, cookies are usually parsed from headers
-        if let Some(cookie_header) = self.headers.get("cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                let cookies = utils::parse_cookies(cookie_str);
-                return cookies.get(name).map(|s| s.to_string());
-            }
+        if let Some(cookie_header) = self.headers.get("cookie")
+            && let Ok(cookie_str) = cookie_header.to_str() {
+            let cookies = utils::parse_cookies(cookie_str);
+            return cookies.get(name).map(|s| s.to_string());
         }
         None
     }
@@ -127,4 +127,42 @@ impl SaResponse for WarpResponseAdapter {
         self.headers.push(("Content-Type".to_string(), "application/json".to_string()));
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// 中文 | English
+/// 把新登录的 token 写入响应：cookie，以及开启 `is_write_header` 时的 `token_name` 响应头
+/// Write a new token into the response: the cookie, plus the `token_name` header when `is_write_header` is on
+pub fn write_token(manager: &SaTokenManager, token: &TokenValue, reply: impl warp::Reply) -> warp::reply::Response {
+    append_headers(reply.into_response(), manager.token_response_headers(token))
+}
+
+/// 中文 | English
+/// 登出后清除响应中的 token cookie | Clear the token cookie after logout
+pub fn clear_token(manager: &SaTokenManager, reply: impl warp::Reply) -> warp::reply::Response {
+    append_headers(reply.into_response(), manager.logout_response_headers())
+}
+
+/// 中文 | English
+/// 用给定的 `ErrorRenderer` 把错误渲染为拒绝响应 | Render an error as a rejection with the given `ErrorRenderer`
+pub fn error_response(renderer: &dyn ErrorRenderer, error: &SaTokenError) -> warp::reply::Response {
+    rendered_response(renderer.render(error))
+}
+
+/// 中文 | English
+/// 把已渲染的 `(状态码, 响应头, 响应体)` 转为 Warp 响应 | Turn a rendered `(status, headers, body)` into a Warp response
+pub(crate) fn rendered_response((status, headers, body): (u16, Vec<(String, String)>, String)) -> warp::reply::Response {
+    let mut response = warp::reply::Response::new(body.into());
+    *response.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::UNAUTHORIZED);
+    append_headers(response, headers)
+}
+
+fn append_headers(mut response: warp::reply::Response, headers: Vec<(String, String)>) -> warp::reply::Response {
+    use warp::http::header::{HeaderName, HeaderValue};
+    for (name, value) in headers {
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
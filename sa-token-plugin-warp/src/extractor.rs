@@ -3,13 +3,16 @@
 // 中文 | English
 // Warp 提取器 | Warp extractors
 
-use sa_token_core::{token::TokenValue, error::messages};
-use warp::reject::Reject;
-use serde_json::json;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use sa_token_core::{token::TokenValue, error::messages, ErrorRenderer, SaTokenError};
+use warp::{Reply, reject::Reject};
+use sa_token_core::response::{default_error_renderer, error_json};
+use crate::{SaTokenState, adapter::{error_response, rendered_response}, filter::{PathAuthRejection, UnauthorizedError}};
 
 /// 中文 | English
 /// 认证错误 | Authentication error
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct AuthError;
 
 impl AuthError {
@@ -28,10 +31,7 @@ impl AuthError {
     /// 中文 | English
     /// 转换为 JSON 字符串 | Convert to JSON string
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 401,
-            "message": self.message()
-        }).to_string()
+        error_json(401, self.message()).to_string()
     }
 }
 
@@ -39,7 +39,7 @@ impl Reject for AuthError {}
 
 /// 中文 | English
 /// 权限错误 | Permission error
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct PermissionError;
 
 impl PermissionError {
@@ -58,10 +58,7 @@ impl PermissionError {
     /// 中文 | English
     /// 转换为 JSON 字符串 | Convert to JSON string
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 403,
-            "message": self.message()
-        }).to_string()
+        error_json(403, self.message()).to_string()
     }
 }
 
@@ -69,7 +66,7 @@ impl Reject for PermissionError {}
 
 /// 中文 | English
 /// 角色错误 | Role error
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct RoleError;
 
 impl RoleError {
@@ -88,15 +85,26 @@ impl RoleError {
     /// 中文 | English
     /// 转换为 JSON 字符串 | Convert to JSON string
     pub fn to_json(&self) -> String {
-        json!({
-            "code": 403,
-            "message": self.message()
-        }).to_string()
+        error_json(403, self.message()).to_string()
     }
 }
 
 impl Reject for RoleError {}
 
+/// 中文 | English
+/// 携带 `SaTokenError` 的拒绝，由 `rejection_handler` 交给状态的 `ErrorRenderer` 渲染
+/// A rejection carrying a `SaTokenError`, rendered by the state's `ErrorRenderer` in `rejection_handler`
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// StpUtil::check_permission(&login_id, "user:delete").await
+///     .map_err(|e| warp::reject::custom(SaTokenRejection(e)))?;
+/// ```
+#[derive(Debug)]
+pub struct SaTokenRejection(pub SaTokenError);
+
+impl Reject for SaTokenRejection {}
+
 /// 中文 | English
 /// Token 提取器 - 从请求中提取 Token | Token extractor - extract token from request
 pub struct SaTokenExtractor(pub TokenValue);
@@ -136,22 +144,40 @@ impl LoginIdExtractor {
 /// 中文 | English
 /// 处理 Warp 拒绝 | Handle Warp rejection
 ///
-/// 将 Sa-Token 错误转换为 HTTP 响应 | Convert Sa-Token errors to HTTP responses
-pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, std::convert::Infallible> {
-    let (code, message) = if err.is_not_found() {
-        (404, json!({"code": 404, "message": "Not Found"}).to_string())
-    } else if let Some(auth_error) = err.find::<AuthError>() {
-        (401, auth_error.to_json())
-    } else if let Some(perm_error) = err.find::<PermissionError>() {
-        (403, perm_error.to_json())
-    } else if let Some(role_error) = err.find::<RoleError>() {
-        (403, role_error.to_json())
+/// 用 `DefaultErrorRenderer` 将 Sa-Token 错误转换为 HTTP 响应；设置了自定义渲染器时改用 `rejection_handler`
+/// Convert Sa-Token errors to HTTP responses with the `DefaultErrorRenderer`; use `rejection_handler` when a custom renderer is set
+pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
+    Ok(render_rejection(&err, default_error_renderer().as_ref()))
+}
+
+/// 中文 | English
+/// 用 `state` 的 `ErrorRenderer` 处理 Warp 拒绝，交给 `recover` 使用
+/// Handle Warp rejections with the `ErrorRenderer` of `state`, for use with `recover`
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let routes = sa_token_filter_with_path_auth(state.clone(), config)
+///     .and(api_routes)
+///     .recover(rejection_handler(&state));
+/// ```
+pub fn rejection_handler(state: &SaTokenState) -> impl Fn(warp::Rejection) -> Ready<Result<warp::reply::Response, Infallible>> + Clone + Send + Sync + 'static {
+    let renderer = state.error_renderer.clone();
+    move |err| ready(Ok(render_rejection(&err, renderer.as_ref())))
+}
+
+fn render_rejection(err: &warp::Rejection, renderer: &dyn ErrorRenderer) -> warp::reply::Response {
+    if err.is_not_found() {
+        warp::reply::with_status(warp::reply::json(&error_json(404, "Not Found")), warp::http::StatusCode::NOT_FOUND).into_response()
+    } else if let Some(PathAuthRejection(rendered)) = err.find::<PathAuthRejection>() {
+        rendered_response(rendered.clone())
+    } else if let Some(SaTokenRejection(e)) = err.find::<SaTokenRejection>() {
+        error_response(renderer, e)
+    } else if err.find::<AuthError>().is_some() || err.find::<UnauthorizedError>().is_some() {
+        error_response(renderer, &SaTokenError::NotLogin)
+    } else if err.find::<PermissionError>().is_some() || err.find::<RoleError>().is_some() {
+        // 角色不足同样按权限不足渲染 | A missing role renders as a missing permission
+        error_response(renderer, &SaTokenError::PermissionDenied)
     } else {
-        (500, json!({"code": 500, "message": "Internal Server Error"}).to_string())
-    };
-    
-    Ok(warp::reply::with_status(
-        warp::reply::json(&serde_json::from_str::<serde_json::Value>(&message).unwrap_or_default()),
-        warp::http::StatusCode::from_u16(code).unwrap_or(warp::http::StatusCode::INTERNAL_SERVER_ERROR)
-    ))
-}
\ No newline at end of file
+        warp::reply::with_status(warp::reply::json(&error_json(500, "Internal Server Error")), warp::http::StatusCode::INTERNAL_SERVER_ERROR).into_response()
+    }
+}
@@ -2,15 +2,19 @@
 //
 //! Warp Filter (中间件)
 
-use warp::{Filter, Rejection, http::HeaderMap};
+use std::sync::Arc;
+use warp::{Filter, Rejection, http::HeaderMap, path::FullPath};
 use crate::SaTokenState;
 use sa_token_core::token::TokenValue;
+use sa_token_core::router::PathAuthConfig;
 
 /// Token 数据，存储在请求中
 #[derive(Clone)]
 pub struct TokenData {
     pub token: Option<TokenValue>,
     pub login_id: Option<String>,
+    /// 请求所属的租户，处理函数中用 `sa_token_core::tenant::scope` 进入该租户
+    pub tenant: Option<String>,
 }
 
 /// sa-token 基础过滤器 - 提取并验证 token
@@ -41,18 +45,93 @@ pub fn sa_check_login_filter(
         })
 }
 
+/// sa-token 路径鉴权过滤器 - 按 `PathAuthConfig` 放行或拒绝
+///
+/// include/exclude 规则、登录 ID 校验器、路由守卫与限流的行为与其他插件的 `SaTokenLayer::with_path_auth` 一致；
+/// 拒绝时返回用 `state` 的 `ErrorRenderer` 渲染好的 `PathAuthRejection`，由 `rejection_handler` 输出。
+/// Include/exclude patterns, login ID validators, route guards and rate limits behave as in the
+/// `SaTokenLayer::with_path_auth` of the other plugins; rejections surface as a `PathAuthRejection`
+/// already rendered with the `ErrorRenderer` of `state`, written out by `rejection_handler`.
+///
+/// # 示例 | Example
+/// ```rust,ignore
+/// let config = PathAuthConfig::new()
+///     .include(vec!["/api/**".to_string()])
+///     .exclude(vec!["/api/login".to_string()]);
+///
+/// let routes = sa_token_filter_with_path_auth(state.clone(), config)
+///     .and(api_routes)
+///     .recover(rejection_handler(&state));
+/// ```
+pub fn sa_token_filter_with_path_auth(
+    state: SaTokenState,
+    config: PathAuthConfig,
+) -> impl Filter<Extract = (TokenData,), Error = Rejection> + Clone {
+    let config = Arc::new(config);
+    warp::path::full()
+        .and(warp::method())
+        .and(warp::header::headers_cloned())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::addr::remote())
+        .and(warp::any().map(move || (state.clone(), config.clone())))
+        .and_then(|path: FullPath, method: warp::http::Method, headers: HeaderMap, query: String, remote: Option<std::net::SocketAddr>, (state, config): (SaTokenState, Arc<PathAuthConfig>)| async move {
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+            // 在所属租户中鉴权 | Authenticate within the request's tenant
+            let tenant = state.manager.config.resolve_tenant(header);
+            sa_token_core::tenant::scope(tenant.clone(), async move {
+                let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+                let token_str = crate::layer::extract_token_from_request(&headers, &query, &state);
+                let header_version = config.version_header_name().and_then(header);
+                let client_ip = config.client_ip(
+                    config.client_ip_header_name().and_then(header).as_deref(),
+                    state.manager.config.resolve_client_ip(header, || remote.map(|addr| addr.ip().to_string())),
+                );
+                let fingerprint = state.manager.config.resolve_fingerprint(header);
+                let result = sa_token_core::router::process_auth_request(
+                    path.as_str(), Some(method.as_str()), header_version.as_deref(), client_ip.as_deref(), fingerprint.as_deref(),
+                    token_str, &config, &state.manager,
+                ).await;
+
+                if result.should_reject() {
+                    return Err(warp::reject::custom(PathAuthRejection(config.render_rejection_response(&result, state.error_renderer.as_ref()))));
+                }
+                let login_id = result.token_info.as_ref().filter(|_| result.is_valid).map(|info| info.login_id.clone());
+                Ok(TokenData {
+                    token: if login_id.is_some() { result.token } else { None },
+                    login_id,
+                    tenant,
+                })
+            }).await
+        })
+}
+
 /// 提取并验证 token
 async fn extract_and_validate_token(
     headers: HeaderMap,
     cookie_token: Option<String>,
     query: std::collections::HashMap<String, String>,
     state: SaTokenState,
+) -> Result<TokenData, Rejection> {
+    // 在所属租户中校验 token | Validate the token within its tenant
+    let tenant = state.manager.config.resolve_tenant(
+        |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string),
+    );
+    let token_data = sa_token_core::tenant::scope(tenant.clone(), validate_token(headers, cookie_token, query, state)).await?;
+    Ok(TokenData { tenant, ..token_data })
+}
+
+/// 按 Header、Cookie、Query 的顺序提取并验证 token
+async fn validate_token(
+    headers: HeaderMap,
+    cookie_token: Option<String>,
+    query: std::collections::HashMap<String, String>,
+    state: SaTokenState,
 ) -> Result<TokenData, Rejection> {
     let token_name = &state.manager.config.token_name;
     
     // 1. 从 Header 获取
     let token_str = if let Some(header_val) = headers.get(token_name) {
-        header_val.to_str().ok().map(|s| extract_bearer_token(s))
+        header_val.to_str().ok().map(extract_bearer_token)
     }
     // 2. 从 Cookie 获取
     else if let Some(token) = cookie_token {
@@ -70,9 +149,11 @@ async fn extract_and_validate_token(
         if state.manager.is_valid(&token).await {
             // 获取 login_id
             if let Ok(token_info) = state.manager.get_token_info(&token).await {
+                state.manager.record_activity(&token_info, None).await;
                 return Ok(TokenData {
                     token: Some(token),
                     login_id: Some(token_info.login_id),
+                    tenant: None,
                 });
             }
         }
@@ -81,13 +162,14 @@ async fn extract_and_validate_token(
     Ok(TokenData {
         token: None,
         login_id: None,
+        tenant: None,
     })
 }
 
 /// 提取 Bearer token
 fn extract_bearer_token(token: &str) -> String {
-    if token.starts_with("Bearer ") {
-        token[7..].to_string()
+    if let Some(stripped) = token.strip_prefix("Bearer ") {
+        stripped.to_string()
     } else {
         token.to_string()
     }
@@ -98,3 +180,10 @@ fn extract_bearer_token(token: &str) -> String {
 pub struct UnauthorizedError;
 
 impl warp::reject::Reject for UnauthorizedError {}
+
+/// 路径鉴权拒绝，携带已渲染的状态码、响应头与响应体
+/// Path-auth rejection carrying the rendered status, headers and body
+#[derive(Debug)]
+pub struct PathAuthRejection(pub (u16, Vec<(String, String)>, String));
+
+impl warp::reject::Reject for PathAuthRejection {}
@@ -41,46 +41,37 @@ pub fn extract_token_from_request(
     let token_name = &state.manager.config.token_name;
     
     // 1. 从指定名称的请求头提取 | Extract from specified header name
-    if let Some(header_value) = headers.get(token_name) {
-        if let Ok(value_str) = header_value.to_str() {
-            if !value_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(value_str) {
-                    return Some(token);
-                }
-            }
-        }
+    if let Some(header_value) = headers.get(token_name)
+        && let Ok(value_str) = header_value.to_str()
+        && !value_str.is_empty()
+        && let Some(token) = utils_extract_bearer_token(value_str) {
+        return Some(token);
     }
     
     // 2. 从 Authorization 请求头提取 | Extract from Authorization header
-    if let Some(auth_header) = headers.get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if !auth_str.is_empty() {
-                if let Some(token) = utils_extract_bearer_token(auth_str) {
-                    return Some(token);
-                }
-            }
-        }
+    if let Some(auth_header) = headers.get("authorization")
+        && let Ok(auth_str) = auth_header.to_str()
+        && !auth_str.is_empty()
+        && let Some(token) = utils_extract_bearer_token(auth_str) {
+        return Some(token);
     }
     
     // 3. 从 Cookie 提取 | Extract from cookie
-    if let Some(cookie_header) = headers.get("cookie") {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let cookies = parse_cookies(cookie_str);
-            if let Some(token) = cookies.get(token_name) {
-                if !token.is_empty() {
-                    return Some(token.to_string());
-                }
-            }
+    if let Some(cookie_header) = headers.get("cookie")
+        && let Ok(cookie_str) = cookie_header.to_str() {
+        let cookies = parse_cookies(cookie_str);
+        if let Some(token) = cookies.get(token_name)
+            && !token.is_empty() {
+            return Some(token.to_string());
         }
     }
     
     // 4. 从查询参数提取 | Extract from query parameter
     if !query.is_empty() {
         let params = parse_query_string(query);
-        if let Some(token) = params.get(token_name) {
-            if !token.is_empty() {
-                return Some(token.to_string());
-            }
+        if let Some(token) = params.get(token_name)
+            && !token.is_empty() {
+            return Some(token.to_string());
         }
     }
     
@@ -60,7 +60,7 @@
 //!     let routes = login_route
 //!         .or(user_route)
 //!         .or(admin_route)
-//!         .recover(handle_rejection);
+//!         .recover(rejection_handler(&state));
 //!     
 //!     warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
 //! }
@@ -76,11 +76,11 @@ pub mod filter;
 // ============================================================================
 // Warp 框架集成（本插件特有） | Warp framework integration (plugin specific)
 // ============================================================================
-pub use filter::{sa_token_filter, sa_check_login_filter};
+pub use filter::{sa_token_filter, sa_check_login_filter, sa_token_filter_with_path_auth, PathAuthRejection, TokenData};
 pub use layer::{sa_token_layer, sa_token_cleanup, sa_check_login, sa_check_permission, sa_check_role, extract_token_from_request};
 pub use middleware::{with_auth, with_permission, with_role, require_auth, require_permission, require_role};
-pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, AuthError, PermissionError, RoleError, handle_rejection};
-pub use adapter::{WarpRequestAdapter, WarpResponseAdapter};
+pub use extractor::{SaTokenExtractor, OptionalSaTokenExtractor, LoginIdExtractor, AuthError, PermissionError, RoleError, SaTokenRejection, handle_rejection, rejection_handler};
+pub use adapter::{WarpRequestAdapter, WarpResponseAdapter, write_token, clear_token, error_response};
 pub use state::{SaTokenState, SaTokenStateBuilder};
 
 pub use sa_token_core::{self, prelude::*};
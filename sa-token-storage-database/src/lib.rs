@@ -23,6 +23,13 @@
 //! );
 //! 
 //! CREATE INDEX idx_expire_at ON sa_token_storage(expire_at);
+//! 
+//! -- 集合（会话属性索引等）| Sets (session attribute indexes, etc.)
+//! CREATE TABLE sa_token_set (
+//!     set_key VARCHAR(255) NOT NULL,
+//!     member VARCHAR(255) NOT NULL,
+//!     PRIMARY KEY (set_key, member)
+//! );
 //! ```
 
 use std::time::Duration;
@@ -71,6 +78,21 @@ impl SaStorage for DatabaseStorage {
         Err(StorageError::InternalError("Not implemented".to_string()))
     }
     
+    async fn sadd(&self, _key: &str, _member: &str) -> StorageResult<()> {
+        // INSERT INTO sa_token_set (set_key, member) VALUES (?, ?) ON CONFLICT DO NOTHING
+        Err(StorageError::InternalError("Not implemented".to_string()))
+    }
+    
+    async fn srem(&self, _key: &str, _member: &str) -> StorageResult<()> {
+        // DELETE FROM sa_token_set WHERE set_key = ? AND member = ?
+        Err(StorageError::InternalError("Not implemented".to_string()))
+    }
+    
+    async fn smembers(&self, _key: &str) -> StorageResult<Vec<String>> {
+        // SELECT member FROM sa_token_set WHERE set_key = ?
+        Err(StorageError::InternalError("Not implemented".to_string()))
+    }
+    
     async fn clear(&self) -> StorageResult<()> {
         Err(StorageError::InternalError("Not implemented".to_string()))
     }
@@ -58,6 +58,26 @@ impl MemoryStorage {
         let mut data = self.data.write().await;
         data.retain(|_, item| !item.is_expired());
     }
+    
+    /// 在写锁内增减计数，保留原有的过期时间
+    async fn add(&self, key: &str, delta: i64) -> StorageResult<i64> {
+        let mut data = self.data.write().await;
+        let item = data.get(key).filter(|item| !item.is_expired());
+        let current = item.and_then(|item| item.value.parse::<i64>().ok()).unwrap_or(0);
+        let expire_at = item.and_then(|item| item.expire_at);
+        let value = current + delta;
+        data.insert(key.to_string(), StorageItem { value: value.to_string(), expire_at });
+        Ok(value)
+    }
+    
+    /// 解析集合成员（集合以 JSON 数组存储）
+    fn read_members(item: Option<&StorageItem>) -> StorageResult<Vec<String>> {
+        match item.filter(|item| !item.is_expired()) {
+            Some(item) => serde_json::from_str(&item.value)
+                .map_err(|e| StorageError::SerializationError(e.to_string())),
+            None => Ok(Vec::new()),
+        }
+    }
 }
 
 impl Default for MemoryStorage {
@@ -92,6 +112,16 @@ impl SaStorage for MemoryStorage {
         Ok(())
     }
     
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        // 在写锁内完成检查和写入
+        let mut data = self.data.write().await;
+        if data.get(key).is_some_and(|item| !item.is_expired()) {
+            return Ok(false);
+        }
+        data.insert(key.to_string(), StorageItem::new(value.to_string(), ttl));
+        Ok(true)
+    }
+    
     async fn delete(&self, key: &str) -> StorageResult<()> {
         let mut data = self.data.write().await;
         data.remove(key);
@@ -135,6 +165,46 @@ impl SaStorage for MemoryStorage {
         }
     }
     
+    async fn incr(&self, key: &str) -> StorageResult<i64> {
+        self.add(key, 1).await
+    }
+    
+    async fn decr(&self, key: &str) -> StorageResult<i64> {
+        self.add(key, -1).await
+    }
+    
+    async fn sadd(&self, key: &str, member: &str) -> StorageResult<()> {
+        // 在写锁内完成读-改-写，保证并发安全
+        let mut data = self.data.write().await;
+        let mut members = Self::read_members(data.get(key))?;
+        if !members.iter().any(|m| m == member) {
+            members.push(member.to_string());
+            let value = serde_json::to_string(&members)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            data.insert(key.to_string(), StorageItem::new(value, None));
+        }
+        Ok(())
+    }
+    
+    async fn srem(&self, key: &str, member: &str) -> StorageResult<()> {
+        let mut data = self.data.write().await;
+        let mut members = Self::read_members(data.get(key))?;
+        members.retain(|m| m != member);
+        if members.is_empty() {
+            data.remove(key);
+        } else {
+            let value = serde_json::to_string(&members)
+                .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+            data.insert(key.to_string(), StorageItem::new(value, None));
+        }
+        Ok(())
+    }
+    
+    async fn smembers(&self, key: &str) -> StorageResult<Vec<String>> {
+        let data = self.data.read().await;
+        Self::read_members(data.get(key))
+    }
+    
     async fn clear(&self) -> StorageResult<()> {
         let mut data = self.data.write().await;
         data.clear();
@@ -205,4 +275,19 @@ mod tests {
         let value = storage.get("key1").await.unwrap();
         assert_eq!(value, None);
     }
+    
+    #[tokio::test]
+    async fn test_set_operations() {
+        let storage = MemoryStorage::new();
+        
+        storage.sadd("set", "a").await.unwrap();
+        storage.sadd("set", "b").await.unwrap();
+        storage.sadd("set", "a").await.unwrap();
+        assert_eq!(storage.smembers("set").await.unwrap(), vec!["a", "b"]);
+        
+        storage.srem("set", "a").await.unwrap();
+        storage.srem("set", "b").await.unwrap();
+        assert!(storage.smembers("set").await.unwrap().is_empty());
+        assert!(!storage.exists("set").await.unwrap());
+    }
 }
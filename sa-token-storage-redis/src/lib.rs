@@ -36,12 +36,36 @@
 //! 
 //! let storage = RedisStorage::from_config(config, "sa-token:").await?;
 //! ```
+//! 
+//! ## 集群失效通知
+//! ```rust,ignore
+//! use sa_token_storage_redis::RedisClusterChannel;
+//! 
+//! let channel = RedisClusterChannel::new("redis://localhost:6379/0", "sa-token:invalidation").await?;
+//! let manager = manager.with_cluster_channel(Arc::new(channel));
+//! manager.start_cluster_sync();
+//! ```
+//! 
+//! ## 过期事件
+//! ```rust,ignore
+//! use sa_token_storage_redis::RedisExpiryListener;
+//! 
+//! let listener = RedisExpiryListener::new("redis://localhost:6379/0", "sa-token:", manager.event_bus().clone())?;
+//! listener.enable_notifications().await?;
+//! listener.start();
+//! ```
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use redis::{Client, AsyncCommands, aio::ConnectionManager};
 use serde::{Deserialize, Serialize};
 use sa_token_adapter::storage::{SaStorage, StorageResult, StorageError};
+use sa_token_adapter::cluster::{ClusterChannel, ClusterMessageHandler};
+use sa_token_core::{tenant, SaTokenEvent, SaTokenEventBus, SaTokenListener};
+use tokio::task::JoinHandle;
 
 /// Redis 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -280,6 +304,20 @@ impl SaStorage for RedisStorage {
         }
     }
     
+    async fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> StorageResult<bool> {
+        let mut conn = self.client.clone();
+        let full_key = self.full_key(key);
+        
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(&full_key).arg(value).arg("NX");
+        if let Some(ttl) = ttl {
+            cmd.arg("EX").arg(ttl.as_secs().max(1));
+        }
+        let result: Option<String> = cmd.query_async(&mut conn).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))?;
+        Ok(result.is_some())
+    }
+    
     async fn delete(&self, key: &str) -> StorageResult<()> {
         let mut conn = self.client.clone();
         let full_key = self.full_key(key);
@@ -347,6 +385,13 @@ impl SaStorage for RedisStorage {
             .map_err(|e| StorageError::OperationFailed(e.to_string()))
     }
     
+    async fn warm_up(&self) -> StorageResult<()> {
+        let mut conn = self.client.clone();
+        let _: String = redis::cmd("PING").query_async(&mut conn).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        Ok(())
+    }
+    
     async fn mdel(&self, keys: &[&str]) -> StorageResult<()> {
         let mut conn = self.client.clone();
         let full_keys: Vec<String> = keys.iter().map(|k| self.full_key(k)).collect();
@@ -371,6 +416,30 @@ impl SaStorage for RedisStorage {
             .map_err(|e| StorageError::OperationFailed(e.to_string()))
     }
     
+    async fn sadd(&self, key: &str, member: &str) -> StorageResult<()> {
+        let mut conn = self.client.clone();
+        let full_key = self.full_key(key);
+        
+        conn.sadd::<_, _, ()>(&full_key, member).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))
+    }
+    
+    async fn srem(&self, key: &str, member: &str) -> StorageResult<()> {
+        let mut conn = self.client.clone();
+        let full_key = self.full_key(key);
+        
+        conn.srem::<_, _, ()>(&full_key, member).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))
+    }
+    
+    async fn smembers(&self, key: &str) -> StorageResult<Vec<String>> {
+        let mut conn = self.client.clone();
+        let full_key = self.full_key(key);
+        
+        conn.smembers(&full_key).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))
+    }
+    
     async fn clear(&self) -> StorageResult<()> {
         let mut conn = self.client.clone();
         let pattern = format!("{}*", self.key_prefix);
@@ -387,3 +456,243 @@ impl SaStorage for RedisStorage {
         Ok(())
     }
 }
+
+/// 基于 Redis pub/sub 的集群消息通道
+/// 
+/// 所有实例订阅同一个频道，一个实例登出或踢人后，其他实例据此丢弃本地缓存
+#[derive(Clone)]
+pub struct RedisClusterChannel {
+    client: Client,
+    publisher: ConnectionManager,
+    channel: String,
+}
+
+impl RedisClusterChannel {
+    /// 使用 Redis URL 创建集群消息通道
+    /// 
+    /// # 参数
+    /// * `redis_url` - Redis 连接 URL
+    /// * `channel` - pub/sub 频道名（例如：`sa-token:invalidation`）
+    pub async fn new(redis_url: &str, channel: impl Into<String>) -> StorageResult<Self> {
+        let client = Client::open(redis_url)
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        
+        let publisher = ConnectionManager::new(client.clone()).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        
+        Ok(Self {
+            client,
+            publisher,
+            channel: channel.into(),
+        })
+    }
+    
+    /// 频道名
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+}
+
+#[async_trait]
+impl ClusterChannel for RedisClusterChannel {
+    async fn publish(&self, payload: &str) -> StorageResult<()> {
+        let mut conn = self.publisher.clone();
+        conn.publish::<_, _, ()>(&self.channel, payload).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))
+    }
+    
+    async fn subscribe(&self, on_message: ClusterMessageHandler) -> StorageResult<()> {
+        let mut pubsub = self.client.get_async_pubsub().await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        pubsub.subscribe(&self.channel).await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        
+        let mut messages = pubsub.on_message();
+        while let Some(message) = messages.next().await {
+            if let Ok(payload) = message.get_payload::<String>() {
+                on_message(payload);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 过期订阅断开后重新订阅前的等待时间
+const EXPIRY_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// 已签发 token 的归属 | Owner of an issued token
+struct ExpirySession {
+    login_id: String,
+    login_type: String,
+    token: String,
+    tenant: Option<String>,
+}
+
+/// Redis 过期通知监听器
+/// 
+/// token 因 TTL 到期被 Redis 删除时，向事件总线发布 `SaTokenEventType::Expired`。
+/// 订阅 `__keyevent@*__:expired`，需要 Redis 开启过期通知（见 [`enable_notifications`](Self::enable_notifications)）。
+/// 
+/// 过期通知只带键名，所以监听器同时注册为事件监听器，记住本实例登录签发的 token 属于谁。
+/// 每个实例只为自己签发的 token 发布事件，多实例部署时不会重复；
+/// 实例重启前签发的 token 到期时不会发布事件。
+pub struct RedisExpiryListener {
+    client: Client,
+    key_prefix: String,
+    event_bus: SaTokenEventBus,
+    sessions: Mutex<HashMap<String, ExpirySession>>,
+}
+
+impl RedisExpiryListener {
+    /// 创建过期通知监听器
+    /// 
+    /// # 参数
+    /// * `redis_url` - Redis 连接 URL
+    /// * `key_prefix` - 与 `RedisStorage` 相同的键前缀（例如：`sa-token:`）
+    /// * `event_bus` - 发布过期事件的事件总线，通常为 `manager.event_bus().clone()`
+    pub fn new(redis_url: &str, key_prefix: impl Into<String>, event_bus: SaTokenEventBus) -> StorageResult<Arc<Self>> {
+        let client = Client::open(redis_url)
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        
+        Ok(Arc::new(Self {
+            client,
+            key_prefix: key_prefix.into(),
+            event_bus,
+            sessions: Mutex::new(HashMap::new()),
+        }))
+    }
+    
+    /// 开启 Redis 的过期键事件通知（`notify-keyspace-events` 加上 `Ex`）
+    /// 
+    /// 保留已有的通知配置；托管 Redis 通常禁用 `CONFIG`，此时需在控制台中开启
+    pub async fn enable_notifications(&self) -> StorageResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        
+        let current: Vec<String> = redis::cmd("CONFIG").arg("GET").arg("notify-keyspace-events")
+            .query_async(&mut conn).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))?;
+        let mut flags = current.get(1).cloned().unwrap_or_default();
+        if !flags.contains('E') {
+            flags.push('E');
+        }
+        if !flags.contains('x') && !flags.contains('A') {
+            flags.push('x');
+        }
+        
+        redis::cmd("CONFIG").arg("SET").arg("notify-keyspace-events").arg(flags)
+            .query_async::<()>(&mut conn).await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))
+    }
+    
+    /// 注册到事件总线并在后台订阅过期通知，连接断开后自动重新订阅
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        self.event_bus.register(self.clone());
+        let listener = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let _ = listener.subscribe().await;
+                tokio::time::sleep(EXPIRY_RESUBSCRIBE_DELAY).await;
+            }
+        })
+    }
+    
+    async fn subscribe(&self) -> StorageResult<()> {
+        let mut pubsub = self.client.get_async_pubsub().await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        pubsub.psubscribe("__keyevent@*__:expired").await
+            .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
+        
+        let mut messages = pubsub.on_message();
+        while let Some(message) = messages.next().await {
+            if let Ok(key) = message.get_payload::<String>() {
+                self.handle_expired(&key).await;
+            }
+        }
+        Ok(())
+    }
+    
+    /// 处理一个过期的 Redis 键，键属于本实例签发的 token 时发布过期事件
+    async fn handle_expired(&self, key: &str) {
+        let Some(key) = key.strip_prefix(self.key_prefix.as_str()) else {
+            return;
+        };
+        let session = self.sessions.lock().unwrap().remove(key);
+        if let Some(session) = session {
+            let event = SaTokenEvent::expired(session.login_id, session.token)
+                .with_login_type(session.login_type);
+            tenant::scope(session.tenant, self.event_bus.publish(event)).await;
+        }
+    }
+    
+    fn forget(&self, token: &str) {
+        self.sessions.lock().unwrap().remove(&tenant::scoped(&format!("sa:token:{}", token)));
+    }
+}
+
+#[async_trait]
+impl SaTokenListener for RedisExpiryListener {
+    async fn on_login(&self, login_id: &str, token: &str, login_type: &str) {
+        let session = ExpirySession {
+            login_id: login_id.to_string(),
+            login_type: login_type.to_string(),
+            token: token.to_string(),
+            tenant: tenant::current(),
+        };
+        self.sessions.lock().unwrap().insert(tenant::scoped(&format!("sa:token:{}", token)), session);
+    }
+    
+    async fn on_logout(&self, _login_id: &str, token: &str, _login_type: &str) {
+        self.forget(token);
+    }
+    
+    async fn on_kick_out(&self, _login_id: &str, token: &str, _login_type: &str) {
+        self.forget(token);
+    }
+    
+    async fn on_replaced(&self, _login_id: &str, token: &str, _login_type: &str) {
+        self.forget(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[derive(Default)]
+    struct ExpiredRecorder(Mutex<Vec<(String, String, Option<String>)>>);
+    
+    #[async_trait]
+    impl SaTokenListener for ExpiredRecorder {
+        async fn on_expired(&self, login_id: &str, token: &str, login_type: &str) {
+            let _ = login_type;
+            self.0.lock().unwrap().push((login_id.to_string(), token.to_string(), tenant::current()));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_expiry_listener() {
+        let bus = SaTokenEventBus::new();
+        let listener = RedisExpiryListener::new("redis://127.0.0.1:6379/0", "sa-token:", bus.clone()).unwrap();
+        let recorder = Arc::new(ExpiredRecorder::default());
+        bus.register(listener.clone());
+        bus.register(recorder.clone());
+        
+        bus.publish(SaTokenEvent::login("1001", "token-a")).await;
+        bus.publish(SaTokenEvent::login("1002", "token-b")).await;
+        tenant::scope(Some("acme".to_string()), bus.publish(SaTokenEvent::login("1003", "token-c"))).await;
+        bus.publish(SaTokenEvent::logout("1002", "token-b")).await;
+        
+        listener.handle_expired("sa-token:sa:token:token-a").await;
+        listener.handle_expired("sa-token:sa:token:token-b").await;
+        listener.handle_expired("sa-token:sa:token:unknown").await;
+        listener.handle_expired("other:sa:token:token-c").await;
+        listener.handle_expired("sa-token:tenant:acme:sa:token:token-c").await;
+        
+        let expired = recorder.0.lock().unwrap().clone();
+        assert_eq!(expired, vec![
+            ("1001".to_string(), "token-a".to_string(), None),
+            ("1003".to_string(), "token-c".to_string(), Some("acme".to_string())),
+        ]);
+    }
+}
@@ -0,0 +1,357 @@
+// Author: 金书记
+//
+//! 测试夹具 | Test fixtures
+//!
+//! ## 加载流程 | Loading Flow
+//!
+//! ```text
+//! Fixture (JSON / TOML)
+//!   ├─→ roles:  role → permissions
+//!   ├─→ users:  id, account_type, roles, permissions
+//!   └─→ routes: method + path pattern → required permissions / roles
+//!        ↓ load(manager)
+//! LoadedFixture
+//!   ├─→ 每个用户登录一次，token 写入存储 | Every user logs in once, tokens go to storage
+//!   ├─→ 用户权限 = 自身权限 + 角色权限 | User permissions = own + role permissions
+//!   └─→ routes 转换为 PathAuthConfig，可用 with_path_auth 换成应用自己的配置
+//!       routes become a PathAuthConfig, with_path_auth swaps in the application's own config
+//!        ↓ check(user, "GET /api/x")
+//! process_auth_request(path, method, ...) → 路由规则 / 守卫 | route rules / guards
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use sa_token_adapter::storage::SaStorage;
+use sa_token_core::{
+    Guard, SaTokenConfig, SaTokenError, SaTokenManager, SaTokenResult, TokenValue,
+    router::{process_auth_request, PathAuthConfig},
+    token::DEFAULT_ACCOUNT_TYPE,
+};
+use sa_token_storage_memory::MemoryStorage;
+
+/// 测试夹具 | Test fixture
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    /// 角色 → 权限 | Role → permissions
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<String>>,
+
+    /// 用户 | Users
+    #[serde(default)]
+    pub users: Vec<FixtureUser>,
+
+    /// 路由规则，见 `path_auth_config` | Route rules, see `path_auth_config`
+    #[serde(default)]
+    pub routes: Vec<FixtureRoute>,
+}
+
+/// 夹具用户 | Fixture user
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureUser {
+    /// 登录 ID，也是断言中使用的用户名 | Login ID, also the user name used in assertions
+    pub id: String,
+
+    /// 账号类型，默认为 `default` | Account type, `default` if omitted
+    #[serde(default)]
+    pub account_type: Option<String>,
+
+    /// 角色 | Roles
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    /// 直接授予的权限 | Directly granted permissions
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// 夹具路由规则 | Fixture route rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureRoute {
+    /// HTTP 方法，`*` 匹配任意方法 | HTTP method, `*` matches any method
+    #[serde(default = "any_method")]
+    pub method: String,
+
+    /// 路径模式（与 `match_path` 相同）| Path pattern (same as `match_path`)
+    pub path: String,
+
+    /// 需要的全部权限 | All required permissions
+    #[serde(default)]
+    pub permissions: Vec<String>,
+
+    /// 需要的全部角色 | All required roles
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    /// 无需登录 | No login required
+    #[serde(default)]
+    pub public: bool,
+}
+
+fn any_method() -> String {
+    "*".to_string()
+}
+
+impl Fixture {
+    /// 从 JSON 解析 | Parse from JSON
+    pub fn from_json(json: &str) -> SaTokenResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// 从 TOML 解析 | Parse from TOML
+    pub fn from_toml(toml: &str) -> SaTokenResult<Self> {
+        toml::from_str(toml).map_err(|e| SaTokenError::ConfigError(e.to_string()))
+    }
+
+    /// 将路由规则转换为 `PathAuthConfig` | Turn the route rules into a `PathAuthConfig`
+    ///
+    /// 公开路由成为排除规则，其余路由成为要求登录及全部权限、角色的守卫（按声明顺序匹配第一条），
+    /// 未声明规则的路由只要求登录。
+    /// Public routes become exclude rules, the other routes become guards requiring login plus all
+    /// their permissions and roles (first match in declaration order); undeclared routes only require login.
+    pub fn path_auth_config(&self) -> PathAuthConfig {
+        let mut public = Vec::new();
+        let mut config = PathAuthConfig::new();
+        for route in &self.routes {
+            let any_method = route.method == "*";
+            if route.public {
+                if any_method {
+                    public.push(route.path.clone());
+                } else {
+                    config = config.exclude_methods(&[route.method.as_str()], vec![route.path.clone()]);
+                }
+                continue;
+            }
+            let guard = route.permissions.iter().map(Guard::permission)
+                .chain(route.roles.iter().map(Guard::role))
+                .fold(Guard::login(), Guard::and);
+            config = if any_method {
+                config.route(route.path.clone(), guard)
+            } else {
+                config.route_methods(&[route.method.as_str()], route.path.clone(), guard)
+            };
+        }
+        config.exclude(public).include(vec!["/**".to_string()])
+    }
+
+    /// 加载到指定的管理器 | Load into the given manager
+    ///
+    /// # 参数 | Parameters
+    /// * `manager` - 目标管理器，可使用任意存储后端 | Target manager, any storage backend
+    pub async fn load(self, manager: Arc<SaTokenManager>) -> SaTokenResult<LoadedFixture> {
+        let mut tokens = HashMap::new();
+
+        for user in &self.users {
+            let mut permissions = user.permissions.clone();
+            for role in &user.roles {
+                for permission in self.roles.get(role).into_iter().flatten() {
+                    if !permissions.contains(permission) {
+                        permissions.push(permission.clone());
+                    }
+                }
+            }
+            let account_type = user.account_type.as_deref().unwrap_or(DEFAULT_ACCOUNT_TYPE);
+            manager.set_permissions_by_type(&user.id, account_type, permissions).await;
+            manager.set_roles_by_type(&user.id, account_type, user.roles.clone()).await;
+
+            let token = manager
+                .login_with_options(&user.id, user.account_type.clone(), None, None, None, None)
+                .await?;
+            tokens.insert(user.id.clone(), token);
+        }
+
+        Ok(LoadedFixture {
+            config: self.path_auth_config(),
+            manager,
+            tokens,
+        })
+    }
+
+    /// 使用默认配置加载到指定存储 | Load into the given storage with the default config
+    pub async fn load_into(self, storage: Arc<dyn SaStorage>) -> SaTokenResult<LoadedFixture> {
+        self.load(Arc::new(SaTokenManager::new(storage, SaTokenConfig::default()))).await
+    }
+
+    /// 使用内存存储加载 | Load into a fresh memory storage
+    pub async fn load_memory(self) -> SaTokenResult<LoadedFixture> {
+        self.load_into(Arc::new(MemoryStorage::new())).await
+    }
+}
+
+/// 已加载的夹具 | Loaded fixture
+pub struct LoadedFixture {
+    manager: Arc<SaTokenManager>,
+    tokens: HashMap<String, TokenValue>,
+    config: PathAuthConfig,
+}
+
+impl LoadedFixture {
+    /// 夹具使用的管理器 | Manager used by the fixture
+    pub fn manager(&self) -> &Arc<SaTokenManager> {
+        &self.manager
+    }
+
+    /// 用户的 token | Token of a user
+    pub fn token(&self, user: &str) -> Option<&TokenValue> {
+        self.tokens.get(user)
+    }
+
+    /// 使用应用自己的路径鉴权配置代替夹具的路由规则 | Use the application's own path auth config instead of the fixture routes
+    ///
+    /// # 示例 | Example
+    /// ```rust,ignore
+    /// let fixture = Fixture::from_toml(USERS)?.load_memory().await?
+    ///     .with_path_auth(app::path_auth_config());
+    /// fixture.assert_denies("bob", "DELETE /api/users/42").await;
+    /// ```
+    pub fn with_path_auth(mut self, config: PathAuthConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 断言所用的路径鉴权配置 | Path auth config used by the assertions
+    pub fn path_auth(&self) -> &PathAuthConfig {
+        &self.config
+    }
+
+    /// 以指定用户（`None` 为匿名）执行一次鉴权 | Run the auth pipeline as a user (`None` for anonymous)
+    ///
+    /// # 参数 | Parameters
+    /// * `user` - 夹具用户 ID | Fixture user ID
+    /// * `request` - 形如 `"GET /api/x"` 的请求 | Request in the form `"GET /api/x"`
+    pub async fn check(&self, user: Option<&str>, request: &str) -> SaTokenResult<()> {
+        let (method, path) = request.trim().split_once(' ')
+            .map(|(method, path)| (method, path.trim()))
+            .ok_or_else(|| SaTokenError::ConfigError(format!("invalid request '{}', expected 'METHOD /path'", request)))?;
+
+        let token = match user {
+            Some(user) => Some(self.tokens.get(user)
+                .ok_or_else(|| SaTokenError::ConfigError(format!("unknown fixture user '{}'", user)))?
+                .as_str()
+                .to_string()),
+            None => None,
+        };
+
+        let result = process_auth_request(path, Some(method), None, None, None, token, &self.config, &self.manager).await;
+        if result.should_reject() {
+            return Err(result.guard_error.unwrap_or(SaTokenError::NotLogin));
+        }
+        Ok(())
+    }
+
+    /// 断言用户可以访问 | Assert that the user is allowed
+    pub async fn assert_allows(&self, user: &str, request: &str) {
+        if let Err(e) = self.check(Some(user), request).await {
+            panic!("expected user '{}' to be allowed '{}', but got: {}", user, request, e);
+        }
+    }
+
+    /// 断言用户被拒绝 | Assert that the user is denied
+    pub async fn assert_denies(&self, user: &str, request: &str) {
+        if self.check(Some(user), request).await.is_ok() {
+            panic!("expected user '{}' to be denied '{}', but it was allowed", user, request);
+        }
+    }
+
+    /// 断言匿名请求可以访问 | Assert that an anonymous request is allowed
+    pub async fn assert_anonymous_allows(&self, request: &str) {
+        if let Err(e) = self.check(None, request).await {
+            panic!("expected anonymous request to be allowed '{}', but got: {}", request, e);
+        }
+    }
+
+    /// 断言匿名请求被拒绝 | Assert that an anonymous request is denied
+    pub async fn assert_anonymous_denies(&self, request: &str) {
+        if self.check(None, request).await.is_ok() {
+            panic!("expected anonymous request to be denied '{}', but it was allowed", request);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"
+        [roles]
+        admin = ["user:*"]
+
+        [[users]]
+        id = "alice"
+        roles = ["admin"]
+
+        [[users]]
+        id = "bob"
+        permissions = ["user:read"]
+
+        [[routes]]
+        path = "/public/**"
+        public = true
+
+        [[routes]]
+        method = "GET"
+        path = "/api/users/*"
+        permissions = ["user:read"]
+
+        [[routes]]
+        method = "DELETE"
+        path = "/api/users/*"
+        permissions = ["user:delete"]
+
+        [[routes]]
+        path = "/admin/**"
+        roles = ["admin"]
+    "#;
+
+    #[tokio::test]
+    async fn test_toml_fixture() {
+        let fixture = Fixture::from_toml(FIXTURE).unwrap().load_memory().await.unwrap();
+
+        fixture.assert_allows("alice", "DELETE /api/users/42").await;
+        fixture.assert_allows("bob", "GET /api/users/42").await;
+        fixture.assert_denies("bob", "DELETE /api/users/42").await;
+        fixture.assert_denies("bob", "GET /admin/settings").await;
+        fixture.assert_allows("alice", "GET /admin/settings").await;
+
+        fixture.assert_anonymous_allows("GET /public/index.html").await;
+        fixture.assert_anonymous_denies("GET /api/users/42").await;
+    }
+
+    #[tokio::test]
+    async fn test_json_fixture_and_revoked_token() {
+        let fixture = Fixture::from_json(r#"{
+            "users": [{ "id": "carol", "account_type": "admin", "permissions": ["order:read"] }],
+            "routes": [{ "method": "GET", "path": "/orders", "permissions": ["order:read"] }]
+        }"#).unwrap().load_memory().await.unwrap();
+
+        fixture.assert_allows("carol", "GET /orders").await;
+
+        let token = fixture.token("carol").unwrap().clone();
+        fixture.manager().logout(&token).await.unwrap();
+        fixture.assert_denies("carol", "GET /orders").await;
+
+        assert!(matches!(
+            fixture.check(Some("nobody"), "GET /orders").await,
+            Err(SaTokenError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_application_path_auth() {
+        use sa_token_core::router::required_permission;
+
+        let fixture = Fixture::from_toml(FIXTURE).unwrap().load_memory().await.unwrap()
+            .with_path_auth(PathAuthConfig::new()
+                .include(vec!["/api/**".to_string()])
+                .exclude_methods(&["GET"], vec!["/api/articles/**".to_string()])
+                .route_methods(&["DELETE"], "/api/articles/*", required_permission("user:delete")));
+
+        fixture.assert_anonymous_allows("GET /api/articles/1").await;
+        fixture.assert_anonymous_denies("PUT /api/articles/1").await;
+        fixture.assert_allows("bob", "PUT /api/articles/1").await;
+        fixture.assert_denies("bob", "DELETE /api/articles/1").await;
+        fixture.assert_allows("alice", "DELETE /api/articles/1").await;
+        // 夹具自身的路由规则不再生效 | The fixture's own routes no longer apply
+        fixture.assert_allows("bob", "GET /admin/settings").await;
+    }
+}
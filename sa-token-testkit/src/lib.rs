@@ -0,0 +1,45 @@
+// Author: 金书记
+//
+//! # sa-token-testkit
+//!
+//! 权限场景的声明式测试夹具 | Declarative test fixtures for permission scenarios
+//!
+//! 在 JSON / TOML 夹具中声明用户、角色、权限和路由规则，加载到任意存储后端，
+//! 然后用 `assert_allows` / `assert_denies` 走完整的鉴权流程进行断言。
+//! Declare users, roles, permissions and route rules in a JSON / TOML fixture, load it into
+//! any storage backend, then assert with `assert_allows` / `assert_denies` through the full
+//! authentication pipeline.
+//!
+//! ## 示例 | Example
+//!
+//! ```rust,ignore
+//! use sa_token_testkit::Fixture;
+//!
+//! #[tokio::test]
+//! async fn admin_can_delete_users() {
+//!     let fixture = Fixture::from_toml(r#"
+//!         [roles]
+//!         admin = ["user:*"]
+//!
+//!         [[users]]
+//!         id = "alice"
+//!         roles = ["admin"]
+//!
+//!         [[users]]
+//!         id = "bob"
+//!         permissions = ["user:read"]
+//!
+//!         [[routes]]
+//!         method = "DELETE"
+//!         path = "/api/users/*"
+//!         permissions = ["user:delete"]
+//!     "#).unwrap().load_memory().await.unwrap();
+//!
+//!     fixture.assert_allows("alice", "DELETE /api/users/42").await;
+//!     fixture.assert_denies("bob", "DELETE /api/users/42").await;
+//! }
+//! ```
+
+pub mod fixture;
+
+pub use fixture::{Fixture, FixtureUser, FixtureRoute, LoadedFixture};